@@ -0,0 +1,104 @@
+// Copyright (c) Roc Streaming authors
+// Licensed under MPL-2.0
+use crate::audio::error::*;
+use crate::dto::EncodingSpec;
+
+use std::fmt;
+use std::time::Duration;
+
+/// Format of audio frames passed between sources and sinks.
+///
+/// Samples are always 32-bit floats in range [-1; 1], interleaved
+/// if there are multiple channels.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct AudioFormat {
+    pub sample_rate: u32,
+    pub channels: u16,
+}
+
+impl AudioFormat {
+    /// Get frame format corresponding to stream encoding.
+    pub fn from_encoding(encoding: &EncodingSpec) -> Self {
+        AudioFormat { sample_rate: encoding.sample_rate, channels: encoding.channels }
+    }
+
+    /// Number of interleaved samples (for all channels) per given duration.
+    pub fn samples_per_duration(&self, duration: Duration) -> usize {
+        (self.sample_rate as u128 * duration.as_nanos() / 1_000_000_000) as usize
+            * self.channels as usize
+    }
+
+    /// Duration of given number of interleaved samples (for all channels).
+    pub fn duration_of_samples(&self, n_samples: usize) -> Duration {
+        Duration::from_nanos(
+            (n_samples / self.channels as usize) as u64 * 1_000_000_000
+                / self.sample_rate as u64,
+        )
+    }
+}
+
+impl fmt::Display for AudioFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}Hz/{}ch", self.sample_rate, self.channels)
+    }
+}
+
+/// Metrics of network link, reported by sources and sinks
+/// backed by network transport.
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct LinkMetrics {
+    /// Number of remote peers connected to the link.
+    pub connection_count: u32,
+    /// Estimated end-to-end latency.
+    pub e2e_latency: Duration,
+    /// Latency which receiver tries to maintain, zero if unknown.
+    pub target_latency: Duration,
+    /// Estimated mean packet jitter.
+    pub mean_jitter: Duration,
+    /// Number of packets expected to be received.
+    pub expected_packets: u64,
+    /// Number of packets which were not received in time.
+    pub lost_packets: u64,
+}
+
+/// Something that produces audio frames: device, network receiver, etc.
+///
+/// Methods are invoked on real-time pipeline thread and should not
+/// block for longer than a frame duration.
+pub trait AudioSource: Send {
+    /// Format of produced frames.
+    fn format(&self) -> AudioFormat;
+
+    /// Whether read() blocks until frame is available in real time.
+    /// If neither source nor sink is clocked, pipeline paces itself.
+    fn is_clocked(&self) -> bool;
+
+    /// Fill buffer with interleaved samples.
+    fn read(&mut self, samples: &mut [f32]) -> Result<(), AudioError>;
+
+    /// Link metrics, if source is backed by network transport.
+    fn metrics(&self) -> Option<LinkMetrics> {
+        None
+    }
+}
+
+/// Something that consumes audio frames: device, network sender, etc.
+///
+/// Methods are invoked on real-time pipeline thread and should not
+/// block for longer than a frame duration.
+pub trait AudioSink: Send {
+    /// Format of consumed frames.
+    fn format(&self) -> AudioFormat;
+
+    /// Whether write() blocks until frame is consumed in real time.
+    /// If neither source nor sink is clocked, pipeline paces itself.
+    fn is_clocked(&self) -> bool;
+
+    /// Consume interleaved samples.
+    fn write(&mut self, samples: &[f32]) -> Result<(), AudioError>;
+
+    /// Link metrics, if sink is backed by network transport.
+    fn metrics(&self) -> Option<LinkMetrics> {
+        None
+    }
+}
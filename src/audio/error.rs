@@ -0,0 +1,17 @@
+// Copyright (c) Roc Streaming authors
+// Licensed under MPL-2.0
+use crate::audio::audio_io::AudioFormat;
+
+use std::io;
+
+#[derive(thiserror::Error, Debug)]
+pub enum AudioError {
+    #[error("format mismatch: source is {0}, sink is {1}")]
+    FormatMismatchError(AudioFormat, AudioFormat),
+
+    #[error("i/o error: {0}")]
+    IoError(#[from] io::Error),
+
+    #[error("backend error: {0}")]
+    BackendError(String),
+}
@@ -0,0 +1,402 @@
+// Copyright (c) Roc Streaming authors
+// Licensed under MPL-2.0
+use crate::audio::audio_io::*;
+use crate::audio::error::*;
+use crate::dto::{FailoverSpec, SwitchReason};
+
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Sources of failover, shared between runner and pipeline.
+///
+/// Pipeline reports which source is active and which sources failed;
+/// runner reopens failed sources and hands them back to pipeline.
+pub struct FailoverControl {
+    active: AtomicUsize,
+    // sources that failed and weren't replaced yet
+    failed: Vec<AtomicBool>,
+    // reopened sources, not yet taken by pipeline
+    replacements: Mutex<Vec<Option<Box<dyn AudioSource>>>>,
+}
+
+impl FailoverControl {
+    pub fn new(n_sources: usize) -> Self {
+        FailoverControl {
+            active: AtomicUsize::new(0),
+            failed: (0..n_sources).map(|_| AtomicBool::new(false)).collect(),
+            replacements: Mutex::new((0..n_sources).map(|_| None).collect()),
+        }
+    }
+
+    /// Index of source which is currently played.
+    pub fn active_source(&self) -> usize {
+        self.active.load(Ordering::Relaxed)
+    }
+
+    /// Indices of sources that should be reopened.
+    pub fn failed_sources(&self) -> Vec<usize> {
+        let replacements = self.replacements.lock().unwrap();
+
+        (0..self.failed.len())
+            .filter(|&index| {
+                self.failed[index].load(Ordering::Relaxed) && replacements[index].is_none()
+            })
+            .collect()
+    }
+
+    /// Pass reopened source to pipeline.
+    pub fn replace_source(&self, index: usize, source: Box<dyn AudioSource>) {
+        self.replacements.lock().unwrap()[index] = Some(source);
+    }
+}
+
+struct SourceSlot {
+    // none if source failed
+    source: Option<Box<dyn AudioSource>>,
+    // how long source is continuously silent
+    silent_time: Duration,
+    // how long source is continuously usable
+    healthy_time: Duration,
+}
+
+/// Source that plays first usable source from list, in order of preference.
+///
+/// All sources are read every frame, so that receivers keep draining
+/// their buffers and recovery of preferred source is noticed. Source is
+/// usable if it doesn't fail and isn't silent for longer than silence
+/// timeout. Stream switches back to preferred source after it stays
+/// usable for recovery time. Switches are reported via callback,
+/// invoked on pipeline thread.
+///
+/// Source never reports itself as clocked, since any of inner sources
+/// may fail. Inner sources should not pace themselves.
+pub struct FailoverSource {
+    format: AudioFormat,
+    slots: Vec<SourceSlot>,
+    active: usize,
+    // linear peak level
+    silence_threshold: f32,
+    silence_timeout: Duration,
+    recovery_time: Duration,
+    control: Arc<FailoverControl>,
+    on_switch: Box<dyn FnMut(usize, usize, SwitchReason) + Send>,
+    scratch: Vec<f32>,
+}
+
+impl FailoverSource {
+    /// Create source from list of sources, where none means source that
+    /// couldn't be opened. At least one source should be present.
+    pub fn new(
+        sources: Vec<Option<Box<dyn AudioSource>>>, spec: &FailoverSpec,
+        control: &Arc<FailoverControl>,
+        on_switch: Box<dyn FnMut(usize, usize, SwitchReason) + Send>,
+    ) -> Result<Self, AudioError> {
+        let format = sources
+            .iter()
+            .flatten()
+            .next()
+            .map(|source| source.format())
+            .ok_or_else(|| AudioError::BackendError("no failover sources".into()))?;
+
+        for source in sources.iter().flatten() {
+            if source.format() != format {
+                return Err(AudioError::FormatMismatchError(source.format(), format));
+            }
+        }
+
+        for (index, source) in sources.iter().enumerate() {
+            control.failed[index].store(source.is_none(), Ordering::Relaxed);
+        }
+
+        let mut failover = FailoverSource {
+            format,
+            slots: sources
+                .into_iter()
+                .map(|source| SourceSlot {
+                    source,
+                    silent_time: Duration::ZERO,
+                    healthy_time: Duration::ZERO,
+                })
+                .collect(),
+            active: 0,
+            silence_threshold: 10f32.powf(spec.silence_threshold() / 20.0),
+            silence_timeout: Duration::from_secs_f32(spec.silence_timeout()),
+            recovery_time: Duration::from_secs_f32(spec.recovery_time()),
+            control: Arc::clone(control),
+            on_switch,
+            scratch: Vec::new(),
+        };
+
+        // if preferred source can't be opened, start with next one
+        failover.active =
+            failover.slots.iter().position(|slot| slot.source.is_some()).unwrap_or(0);
+        failover.control.active.store(failover.active, Ordering::Relaxed);
+
+        Ok(failover)
+    }
+
+    /// Take sources reopened by runner.
+    fn take_replacements(&mut self) {
+        // don't block real-time thread if runner holds lock
+        let Ok(mut replacements) = self.control.replacements.try_lock() else {
+            return;
+        };
+
+        for (index, replacement) in replacements.iter_mut().enumerate() {
+            if let Some(source) = replacement.take() {
+                if source.format() != self.format {
+                    continue;
+                }
+                self.slots[index] = SourceSlot {
+                    source: Some(source),
+                    silent_time: Duration::ZERO,
+                    healthy_time: Duration::ZERO,
+                };
+                self.control.failed[index].store(false, Ordering::Relaxed);
+            }
+        }
+    }
+
+    fn is_usable(&self, index: usize) -> bool {
+        let slot = &self.slots[index];
+        slot.source.is_some() && slot.silent_time < self.silence_timeout
+    }
+
+    /// Choose source to play after current frame.
+    fn select(&mut self) {
+        let candidate = (0..self.slots.len())
+            .find(|&index| {
+                self.is_usable(index)
+                    && (index >= self.active
+                        || self.slots[index].healthy_time >= self.recovery_time)
+            })
+            .or_else(|| {
+                // if nothing is usable, prefer silent sources to failed one
+                if self.slots[self.active].source.is_some() {
+                    return None;
+                }
+                self.slots.iter().position(|slot| slot.source.is_some())
+            });
+
+        let Some(candidate) = candidate.filter(|&index| index != self.active) else {
+            return;
+        };
+
+        let reason = if self.slots[self.active].source.is_none() {
+            SwitchReason::Failed
+        } else if !self.is_usable(self.active) {
+            SwitchReason::Silent
+        } else {
+            SwitchReason::Recovered
+        };
+
+        let previous = self.active;
+        self.active = candidate;
+        self.control.active.store(candidate, Ordering::Relaxed);
+
+        (self.on_switch)(previous, candidate, reason);
+    }
+}
+
+impl AudioSource for FailoverSource {
+    fn format(&self) -> AudioFormat {
+        self.format
+    }
+
+    fn is_clocked(&self) -> bool {
+        false
+    }
+
+    fn read(&mut self, samples: &mut [f32]) -> Result<(), AudioError> {
+        self.take_replacements();
+
+        let frame_duration = self.format.duration_of_samples(samples.len());
+        self.scratch.resize(samples.len(), 0.0);
+
+        for index in 0..self.slots.len() {
+            let buffer =
+                if index == self.active { &mut samples[..] } else { &mut self.scratch[..] };
+            let slot = &mut self.slots[index];
+
+            let Some(source) = &mut slot.source else {
+                if index == self.active {
+                    buffer.fill(0.0);
+                }
+                continue;
+            };
+
+            if let Err(err) = source.read(buffer) {
+                tracing::warn!("failover source {index} failed: {err}");
+
+                slot.source = None;
+                self.control.failed[index].store(true, Ordering::Relaxed);
+                if index == self.active {
+                    buffer.fill(0.0);
+                }
+                continue;
+            }
+
+            let peak = buffer.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+            if peak >= self.silence_threshold {
+                slot.silent_time = Duration::ZERO;
+            } else {
+                slot.silent_time += frame_duration;
+            }
+        }
+
+        for index in 0..self.slots.len() {
+            if self.is_usable(index) {
+                self.slots[index].healthy_time += frame_duration;
+            } else {
+                self.slots[index].healthy_time = Duration::ZERO;
+            }
+        }
+
+        self.select();
+
+        Ok(())
+    }
+
+    fn metrics(&self) -> Option<LinkMetrics> {
+        self.slots[self.active].source.as_ref()?.metrics()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FORMAT: AudioFormat = AudioFormat { sample_rate: 48000, channels: 2 };
+
+    /// Source producing constant value, or error if value is NaN.
+    struct ConstSource {
+        value: Arc<Mutex<f32>>,
+    }
+
+    impl AudioSource for ConstSource {
+        fn format(&self) -> AudioFormat {
+            FORMAT
+        }
+
+        fn is_clocked(&self) -> bool {
+            false
+        }
+
+        fn read(&mut self, samples: &mut [f32]) -> Result<(), AudioError> {
+            let value = *self.value.lock().unwrap();
+            if value.is_nan() {
+                return Err(AudioError::BackendError("broken".into()));
+            }
+            samples.fill(value);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_switch() {
+        let values: Vec<Arc<Mutex<f32>>> =
+            vec![Arc::new(Mutex::new(0.5)), Arc::new(Mutex::new(0.25))];
+        let switches = Arc::new(Mutex::new(Vec::new()));
+
+        let spec = FailoverSpec {
+            sources: vec![],
+            silence_threshold: Some(-40.0),
+            silence_timeout: Some(0.045),
+            recovery_time: Some(0.095),
+        };
+        let control = Arc::new(FailoverControl::new(2));
+        let mut source = {
+            let switches = Arc::clone(&switches);
+            FailoverSource::new(
+                values
+                    .iter()
+                    .map(|value| {
+                        Some(Box::new(ConstSource { value: Arc::clone(value) })
+                            as Box<dyn AudioSource>)
+                    })
+                    .collect(),
+                &spec,
+                &control,
+                Box::new(move |previous, active, reason| {
+                    switches.lock().unwrap().push((previous, active, reason))
+                }),
+            )
+            .unwrap()
+        };
+
+        // 10ms frames, timeout is 4.5 frames, recovery time is 9.5 frames
+        let mut frame = vec![0.0; 960];
+        let mut read_frames = |n: usize| {
+            for _ in 0..n {
+                source.read(&mut frame).unwrap();
+            }
+            frame[0]
+        };
+
+        // primary is played
+        assert_eq!(read_frames(10), 0.5);
+        assert_eq!(control.active_source(), 0);
+
+        // primary becomes silent, fallback is played after timeout
+        *values[0].lock().unwrap() = 0.0;
+        assert_eq!(read_frames(4), 0.0);
+        assert_eq!(control.active_source(), 0);
+        assert_eq!(read_frames(2), 0.25);
+        assert_eq!(control.active_source(), 1);
+
+        // primary returns, but is played only after recovery time
+        *values[0].lock().unwrap() = 0.5;
+        assert_eq!(read_frames(9), 0.25);
+        assert_eq!(read_frames(2), 0.5);
+        assert_eq!(control.active_source(), 0);
+
+        // primary fails, fallback is played right away
+        *values[0].lock().unwrap() = f32::NAN;
+        read_frames(1);
+        assert_eq!(read_frames(1), 0.25);
+        assert_eq!(control.active_source(), 1);
+        assert_eq!(control.failed_sources(), vec![0]);
+
+        // primary is reopened
+        *values[0].lock().unwrap() = 0.5;
+        control.replace_source(0, Box::new(ConstSource { value: Arc::clone(&values[0]) }));
+        assert_eq!(read_frames(5), 0.25);
+        assert_eq!(control.failed_sources(), Vec::<usize>::new());
+        assert_eq!(read_frames(10), 0.5);
+
+        assert_eq!(
+            *switches.lock().unwrap(),
+            vec![
+                (0, 1, SwitchReason::Silent),
+                (1, 0, SwitchReason::Recovered),
+                (0, 1, SwitchReason::Failed),
+                (1, 0, SwitchReason::Recovered),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_missing_source() {
+        let control = Arc::new(FailoverControl::new(2));
+        let mut source = FailoverSource::new(
+            vec![None, Some(Box::new(ConstSource { value: Arc::new(Mutex::new(0.25)) }))],
+            &FailoverSpec {
+                sources: vec![],
+                silence_threshold: None,
+                silence_timeout: None,
+                recovery_time: None,
+            },
+            &control,
+            Box::new(|_, _, _| {}),
+        )
+        .unwrap();
+
+        let mut frame = vec![0.0; 960];
+        source.read(&mut frame).unwrap();
+
+        assert_eq!(frame[0], 0.25);
+        assert_eq!(control.active_source(), 1);
+        assert_eq!(control.failed_sources(), vec![0]);
+    }
+}
@@ -0,0 +1,455 @@
+// Copyright (c) Roc Streaming authors
+// Licensed under MPL-2.0
+use crate::audio::audio_io::*;
+use crate::audio::error::*;
+
+use std::f64::consts::PI;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Marker signal: linear sweep from CHIRP_START_FREQ to CHIRP_END_FREQ.
+const CHIRP_DURATION: Duration = Duration::from_millis(100);
+const CHIRP_START_FREQ: f64 = 500.0;
+const CHIRP_END_FREQ: f64 = 3000.0;
+const CHIRP_AMPLITUDE: f64 = 0.5;
+
+/// Captured signal is downmixed and decimated to at least this rate,
+/// which is enough for the chirp and keeps correlation cheap.
+const CAPTURE_RATE: u32 = 12000;
+
+/// Minimum normalized correlation at which chirp is considered detected.
+const DETECTION_THRESHOLD: f32 = 0.5;
+
+/// Latency measurement state, shared between stream runner and pipelines.
+///
+/// Sending pipeline replaces its output with a chirp when injection is
+/// requested, and remembers when chirp was sent. Receiving pipeline
+/// captures its input for requested duration; runner then searches the
+/// capture for the chirp using find_chirp().
+pub struct LatencyProbe {
+    inject_requested: AtomicBool,
+    capture_requested: AtomicBool,
+    state: Mutex<ProbeState>,
+}
+
+#[derive(Default)]
+struct ProbeState {
+    // when chirp was written to sink
+    injected_at: Option<Instant>,
+    // buffer handed to pipeline by request_capture()
+    capture_buffer: Option<Vec<f32>>,
+    // when pipeline started filling buffer
+    capture_started_at: Option<Instant>,
+    // filled buffer returned by pipeline
+    capture: Option<Capture>,
+}
+
+/// Mono signal captured by pipeline.
+pub struct Capture {
+    /// When first sample was read.
+    pub start: Instant,
+    pub sample_rate: f64,
+    pub samples: Vec<f32>,
+}
+
+impl LatencyProbe {
+    pub fn new() -> Self {
+        LatencyProbe {
+            inject_requested: AtomicBool::new(false),
+            capture_requested: AtomicBool::new(false),
+            state: Mutex::new(ProbeState::default()),
+        }
+    }
+
+    /// Ask pipeline to capture given duration of its input.
+    /// Discards previous capture.
+    pub fn request_capture(&self, format: AudioFormat, duration: Duration) {
+        let len = capture_samples(format, duration);
+
+        let mut state = self.state.lock().unwrap();
+        state.capture_buffer = Some(Vec::with_capacity(len));
+        state.capture_started_at = None;
+        state.capture = None;
+
+        self.capture_requested.store(true, Ordering::SeqCst);
+    }
+
+    /// Get time when pipeline started capturing, if it did.
+    pub fn capture_started_at(&self) -> Option<Instant> {
+        self.state.lock().unwrap().capture_started_at
+    }
+
+    /// Get capture, if pipeline finished it.
+    pub fn take_capture(&self) -> Option<Capture> {
+        self.state.lock().unwrap().capture.take()
+    }
+
+    /// Ask pipeline to send chirp.
+    /// Discards previous injection time.
+    pub fn request_injection(&self) {
+        self.state.lock().unwrap().injected_at = None;
+        self.inject_requested.store(true, Ordering::SeqCst);
+    }
+
+    /// Get time when pipeline sent chirp, if it did.
+    pub fn injected_at(&self) -> Option<Instant> {
+        self.state.lock().unwrap().injected_at
+    }
+
+    /// Cancel pending requests.
+    pub fn cancel(&self) {
+        self.inject_requested.store(false, Ordering::SeqCst);
+        self.capture_requested.store(false, Ordering::SeqCst);
+
+        let mut state = self.state.lock().unwrap();
+        state.capture_buffer = None;
+        state.capture = None;
+    }
+}
+
+impl Default for LatencyProbe {
+    fn default() -> Self {
+        LatencyProbe::new()
+    }
+}
+
+/// Search capture for chirp.
+/// Returns offset of chirp from beginning of capture, if it was found.
+///
+/// Uses normalized cross-correlation, so it doesn't depend on level of
+/// received signal. Takes a while on long captures, better not to call
+/// it from async code directly.
+pub fn find_chirp(capture: &Capture) -> Option<Duration> {
+    let reference = chirp_signal(capture.sample_rate);
+    let samples = &capture.samples;
+
+    if samples.len() < reference.len() {
+        return None;
+    }
+
+    let ref_energy: f64 = reference.iter().map(|s| s * s).sum();
+
+    // energy of current window, updated incrementally
+    let mut energy: f64 =
+        samples[..reference.len()].iter().map(|s| (*s as f64) * (*s as f64)).sum();
+
+    let mut best_pos = 0;
+    let mut best_score = 0.0;
+
+    for pos in 0..=samples.len() - reference.len() {
+        if pos > 0 {
+            let removed = samples[pos - 1] as f64;
+            let added = samples[pos + reference.len() - 1] as f64;
+            energy = (energy - removed * removed + added * added).max(0.0);
+        }
+
+        // skip silence, where score is undefined
+        if energy < 1e-9 {
+            continue;
+        }
+
+        let dot: f64 = samples[pos..].iter().zip(&reference).map(|(s, r)| *s as f64 * r).sum();
+        let score = dot / (energy * ref_energy).sqrt();
+
+        if score > best_score {
+            best_score = score;
+            best_pos = pos;
+        }
+    }
+
+    (best_score as f32 >= DETECTION_THRESHOLD)
+        .then(|| Duration::from_secs_f64(best_pos as f64 / capture.sample_rate))
+}
+
+/// Chirp sampled at given rate, Hann-windowed to avoid clicks.
+fn chirp_signal(sample_rate: f64) -> Vec<f64> {
+    let duration = CHIRP_DURATION.as_secs_f64();
+    let len = (duration * sample_rate) as usize;
+    let sweep_rate = (CHIRP_END_FREQ - CHIRP_START_FREQ) / duration;
+
+    (0..len)
+        .map(|n| {
+            let t = n as f64 / sample_rate;
+            let window = 0.5 * (1.0 - (2.0 * PI * t / duration).cos());
+            let phase = 2.0 * PI * (CHIRP_START_FREQ * t + sweep_rate * t * t / 2.0);
+            CHIRP_AMPLITUDE * window * phase.sin()
+        })
+        .collect()
+}
+
+/// How many input frames are averaged into one captured sample.
+fn decimation(format: AudioFormat) -> usize {
+    (format.sample_rate / CAPTURE_RATE).max(1) as usize
+}
+
+/// Number of captured samples for given duration.
+fn capture_samples(format: AudioFormat, duration: Duration) -> usize {
+    (duration.as_secs_f64() * format.sample_rate as f64) as usize / decimation(format)
+}
+
+/// Sink that replaces written frames with chirp when requested.
+pub struct ProbeSink {
+    inner: Box<dyn AudioSink>,
+    probe: Arc<LatencyProbe>,
+    chirp: Vec<f32>,
+    // position in chirp, if it's being sent
+    position: Option<usize>,
+    // frame with chirp, reused between writes
+    frame: Vec<f32>,
+}
+
+impl ProbeSink {
+    pub fn new(inner: Box<dyn AudioSink>, probe: &Arc<LatencyProbe>) -> Self {
+        let format = inner.format();
+        let chirp = chirp_signal(format.sample_rate as f64)
+            .into_iter()
+            .flat_map(|s| std::iter::repeat_n(s as f32, format.channels as usize))
+            .collect();
+
+        ProbeSink { inner, probe: Arc::clone(probe), chirp, position: None, frame: Vec::new() }
+    }
+
+    fn start_injection(&mut self) {
+        // don't block real-time thread, try again on next write
+        if let Ok(mut state) = self.probe.state.try_lock() {
+            self.probe.inject_requested.store(false, Ordering::SeqCst);
+            state.injected_at = Some(Instant::now());
+            self.position = Some(0);
+        }
+    }
+}
+
+impl AudioSink for ProbeSink {
+    fn format(&self) -> AudioFormat {
+        self.inner.format()
+    }
+
+    fn is_clocked(&self) -> bool {
+        self.inner.is_clocked()
+    }
+
+    fn write(&mut self, samples: &[f32]) -> Result<(), AudioError> {
+        if self.position.is_none() && self.probe.inject_requested.load(Ordering::Relaxed) {
+            self.start_injection();
+        }
+
+        let Some(position) = self.position else {
+            return self.inner.write(samples);
+        };
+
+        let count = samples.len().min(self.chirp.len() - position);
+        self.frame.clear();
+        self.frame.extend_from_slice(&self.chirp[position..position + count]);
+        self.frame.resize(samples.len(), 0.0);
+
+        self.position = Some(position + count).filter(|pos| *pos < self.chirp.len());
+
+        self.inner.write(&self.frame)
+    }
+
+    fn metrics(&self) -> Option<LinkMetrics> {
+        self.inner.metrics()
+    }
+}
+
+/// Source that captures frames read from inner source when requested.
+///
+/// Capture is downmixed to mono and decimated by averaging,
+/// which is enough for find_chirp().
+pub struct ProbeSource {
+    inner: Box<dyn AudioSource>,
+    probe: Arc<LatencyProbe>,
+    decimation: usize,
+    // buffer being filled and when filling started
+    capture: Option<(Instant, Vec<f32>)>,
+    // sum of frames for next decimated sample
+    sum: f32,
+    n_frames: usize,
+}
+
+impl ProbeSource {
+    pub fn new(inner: Box<dyn AudioSource>, probe: &Arc<LatencyProbe>) -> Self {
+        let decimation = decimation(inner.format());
+
+        ProbeSource {
+            inner,
+            probe: Arc::clone(probe),
+            decimation,
+            capture: None,
+            sum: 0.0,
+            n_frames: 0,
+        }
+    }
+
+    fn start_capture(&mut self) {
+        // don't block real-time thread, try again on next read
+        if let Ok(mut state) = self.probe.state.try_lock() {
+            self.probe.capture_requested.store(false, Ordering::SeqCst);
+
+            if let Some(buffer) = state.capture_buffer.take() {
+                let now = Instant::now();
+                state.capture_started_at = Some(now);
+                self.capture = Some((now, buffer));
+                self.sum = 0.0;
+                self.n_frames = 0;
+            }
+        }
+    }
+
+    fn finish_capture(&mut self) {
+        if let Ok(mut state) = self.probe.state.try_lock()
+            && let Some((start, samples)) = self.capture.take()
+        {
+            let sample_rate = self.inner.format().sample_rate as f64 / self.decimation as f64;
+            state.capture = Some(Capture { start, sample_rate, samples });
+        }
+    }
+}
+
+impl AudioSource for ProbeSource {
+    fn format(&self) -> AudioFormat {
+        self.inner.format()
+    }
+
+    fn is_clocked(&self) -> bool {
+        self.inner.is_clocked()
+    }
+
+    fn read(&mut self, samples: &mut [f32]) -> Result<(), AudioError> {
+        self.inner.read(samples)?;
+
+        if self.capture.is_none() && self.probe.capture_requested.load(Ordering::Relaxed) {
+            self.start_capture();
+        }
+
+        let channels = self.inner.format().channels as usize;
+
+        if let Some((_, buffer)) = &mut self.capture {
+            for frame in samples.chunks_exact(channels) {
+                if buffer.len() == buffer.capacity() {
+                    break;
+                }
+                self.sum += frame.iter().sum::<f32>() / channels as f32;
+                self.n_frames += 1;
+                if self.n_frames == self.decimation {
+                    buffer.push(self.sum / self.n_frames as f32);
+                    self.sum = 0.0;
+                    self.n_frames = 0;
+                }
+            }
+
+            if buffer.len() == buffer.capacity() {
+                self.finish_capture();
+            }
+        }
+
+        Ok(())
+    }
+
+    fn metrics(&self) -> Option<LinkMetrics> {
+        self.inner.metrics()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assertables::*;
+
+    const FORMAT: AudioFormat = AudioFormat { sample_rate: 48000, channels: 2 };
+
+    /// Sink and source connected via buffer.
+    struct Loopback {
+        buffer: Arc<Mutex<Vec<f32>>>,
+    }
+
+    impl AudioSink for Loopback {
+        fn format(&self) -> AudioFormat {
+            FORMAT
+        }
+
+        fn is_clocked(&self) -> bool {
+            false
+        }
+
+        fn write(&mut self, samples: &[f32]) -> Result<(), AudioError> {
+            // attenuate, so that detection doesn't depend on level
+            self.buffer.lock().unwrap().extend(samples.iter().map(|s| s * 0.1));
+            Ok(())
+        }
+    }
+
+    impl AudioSource for Loopback {
+        fn format(&self) -> AudioFormat {
+            FORMAT
+        }
+
+        fn is_clocked(&self) -> bool {
+            false
+        }
+
+        fn read(&mut self, samples: &mut [f32]) -> Result<(), AudioError> {
+            let mut buffer = self.buffer.lock().unwrap();
+            let count = samples.len().min(buffer.len());
+            samples.fill(0.0);
+            samples[..count].copy_from_slice(&buffer[..count]);
+            buffer.drain(..count);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_find_chirp() {
+        let buffer = Arc::new(Mutex::new(vec![0.0; 960 * 5]));
+        let probe = Arc::new(LatencyProbe::new());
+
+        let mut sink = ProbeSink::new(Box::new(Loopback { buffer: buffer.clone() }), &probe);
+        let mut source = ProbeSource::new(Box::new(Loopback { buffer }), &probe);
+
+        probe.request_capture(FORMAT, Duration::from_millis(500));
+
+        let mut frame = vec![0.0; 960];
+        sink.write(&frame).unwrap();
+        source.read(&mut frame).unwrap();
+
+        assert_some!(probe.capture_started_at());
+        assert_none!(probe.injected_at());
+
+        probe.request_injection();
+
+        // loopback has 5 frames of delay, i.e. 50ms
+        for _ in 0..50 {
+            sink.write(&[0.0; 960]).unwrap();
+            source.read(&mut frame).unwrap();
+        }
+
+        assert_some!(probe.injected_at());
+
+        let capture = probe.take_capture().unwrap();
+
+        assert_eq!(capture.sample_rate, 12000.0);
+        assert_eq!(capture.samples.len(), 6000);
+
+        // chirp was injected on second write
+        let offset = find_chirp(&capture).unwrap();
+
+        assert_in_delta!(offset.as_secs_f64(), 0.06, 0.001);
+    }
+
+    #[test]
+    fn test_find_chirp_missing() {
+        let capture = Capture {
+            start: Instant::now(),
+            sample_rate: 12000.0,
+            // tone instead of chirp
+            samples: (0..6000).map(|n| (n as f32 * 0.5).sin() * 0.5).collect(),
+        };
+
+        assert_none!(find_chirp(&capture));
+
+        let capture = Capture { samples: vec![0.0; 6000], ..capture };
+
+        assert_none!(find_chirp(&capture));
+    }
+}
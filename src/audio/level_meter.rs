@@ -0,0 +1,283 @@
+// Copyright (c) Roc Streaming authors
+// Licensed under MPL-2.0
+use crate::audio::audio_io::*;
+use crate::audio::error::*;
+use crate::dto::ChannelLevelSpec;
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use time::OffsetDateTime;
+
+/// Levels measured by pipeline, shared between pipeline and stream runner.
+///
+/// Metering is disabled by default, so that pipeline doesn't spend time on
+/// it when nobody is interested. When enabled, pipeline stores new snapshot
+/// of levels every interval.
+pub struct LevelMeter {
+    interval: Duration,
+    enabled: AtomicBool,
+    // incremented on every new snapshot
+    revision: AtomicU64,
+    // revision returned by last take_new()
+    taken_revision: AtomicU64,
+    latest: Mutex<Option<LevelSnapshot>>,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct LevelSnapshot {
+    pub time: OffsetDateTime,
+    pub channels: Vec<ChannelLevelSpec>,
+}
+
+impl LevelMeter {
+    pub fn new(interval: Duration) -> Self {
+        LevelMeter {
+            interval,
+            enabled: AtomicBool::new(false),
+            revision: AtomicU64::new(0),
+            taken_revision: AtomicU64::new(0),
+            latest: Mutex::new(None),
+        }
+    }
+
+    /// Enable or disable metering.
+    /// When metering is disabled, latest snapshot is discarded.
+    pub fn set_enabled(&self, enabled: bool) {
+        if self.enabled.swap(enabled, Ordering::SeqCst) && !enabled {
+            *self.latest.lock().unwrap() = None;
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    /// Get latest snapshot, if any.
+    pub fn latest(&self) -> Option<LevelSnapshot> {
+        self.latest.lock().unwrap().clone()
+    }
+
+    /// Get latest snapshot, if it wasn't returned by previous call.
+    pub fn take_new(&self) -> Option<LevelSnapshot> {
+        let latest = self.latest.lock().unwrap();
+        let revision = self.revision.load(Ordering::SeqCst);
+
+        if self.taken_revision.swap(revision, Ordering::SeqCst) == revision {
+            return None;
+        }
+
+        latest.clone()
+    }
+
+    fn store(&self, snapshot: LevelSnapshot) {
+        // don't block real-time thread if snapshot is being read,
+        // next snapshot will be stored after interval
+        if let Ok(mut latest) = self.latest.try_lock() {
+            *latest = Some(snapshot);
+            self.revision.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+}
+
+/// Accumulates per-channel levels on pipeline thread.
+struct LevelAccumulator {
+    meter: Arc<LevelMeter>,
+    format: AudioFormat,
+    peak: Vec<f32>,
+    sum_squares: Vec<f64>,
+    n_frames: usize,
+    interval_frames: usize,
+}
+
+impl LevelAccumulator {
+    fn new(meter: &Arc<LevelMeter>, format: AudioFormat) -> Self {
+        let channels = format.channels as usize;
+
+        LevelAccumulator {
+            meter: Arc::clone(meter),
+            format,
+            peak: vec![0.0; channels],
+            sum_squares: vec![0.0; channels],
+            n_frames: 0,
+            interval_frames: format.samples_per_duration(meter.interval).max(channels)
+                / channels,
+        }
+    }
+
+    fn process(&mut self, samples: &[f32]) {
+        if !self.meter.is_enabled() {
+            if self.n_frames != 0 {
+                self.reset();
+            }
+            return;
+        }
+
+        for frame in samples.chunks_exact(self.format.channels as usize) {
+            for (ch, sample) in frame.iter().enumerate() {
+                self.peak[ch] = self.peak[ch].max(sample.abs());
+                self.sum_squares[ch] += (*sample as f64) * (*sample as f64);
+            }
+        }
+        self.n_frames += samples.len() / self.format.channels as usize;
+
+        if self.n_frames >= self.interval_frames {
+            let channels = self
+                .peak
+                .iter()
+                .zip(&self.sum_squares)
+                .map(|(peak, sum_squares)| {
+                    let rms = (sum_squares / self.n_frames as f64).sqrt() as f32;
+                    ChannelLevelSpec::from_amplitudes(*peak, rms)
+                })
+                .collect();
+
+            self.meter.store(LevelSnapshot { time: OffsetDateTime::now_utc(), channels });
+            self.reset();
+        }
+    }
+
+    fn reset(&mut self) {
+        self.peak.fill(0.0);
+        self.sum_squares.fill(0.0);
+        self.n_frames = 0;
+    }
+}
+
+/// Source that measures levels of frames produced by inner source.
+pub struct MeteringSource {
+    inner: Box<dyn AudioSource>,
+    accumulator: LevelAccumulator,
+}
+
+impl MeteringSource {
+    pub fn new(inner: Box<dyn AudioSource>, meter: &Arc<LevelMeter>) -> Self {
+        let accumulator = LevelAccumulator::new(meter, inner.format());
+
+        MeteringSource { inner, accumulator }
+    }
+}
+
+impl AudioSource for MeteringSource {
+    fn format(&self) -> AudioFormat {
+        self.inner.format()
+    }
+
+    fn is_clocked(&self) -> bool {
+        self.inner.is_clocked()
+    }
+
+    fn read(&mut self, samples: &mut [f32]) -> Result<(), AudioError> {
+        self.inner.read(samples)?;
+        self.accumulator.process(samples);
+        Ok(())
+    }
+
+    fn metrics(&self) -> Option<LinkMetrics> {
+        self.inner.metrics()
+    }
+}
+
+/// Sink that measures levels of frames written to inner sink.
+pub struct MeteringSink {
+    inner: Box<dyn AudioSink>,
+    accumulator: LevelAccumulator,
+}
+
+impl MeteringSink {
+    pub fn new(inner: Box<dyn AudioSink>, meter: &Arc<LevelMeter>) -> Self {
+        let accumulator = LevelAccumulator::new(meter, inner.format());
+
+        MeteringSink { inner, accumulator }
+    }
+}
+
+impl AudioSink for MeteringSink {
+    fn format(&self) -> AudioFormat {
+        self.inner.format()
+    }
+
+    fn is_clocked(&self) -> bool {
+        self.inner.is_clocked()
+    }
+
+    fn write(&mut self, samples: &[f32]) -> Result<(), AudioError> {
+        self.accumulator.process(samples);
+        self.inner.write(samples)
+    }
+
+    fn metrics(&self) -> Option<LinkMetrics> {
+        self.inner.metrics()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assertables::*;
+
+    const FORMAT: AudioFormat = AudioFormat { sample_rate: 48000, channels: 2 };
+
+    struct NullSink {}
+
+    impl AudioSink for NullSink {
+        fn format(&self) -> AudioFormat {
+            FORMAT
+        }
+
+        fn is_clocked(&self) -> bool {
+            false
+        }
+
+        fn write(&mut self, _samples: &[f32]) -> Result<(), AudioError> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_levels() {
+        let meter = Arc::new(LevelMeter::new(Duration::from_millis(20)));
+        let mut sink = MeteringSink::new(Box::new(NullSink {}), &meter);
+
+        // left channel is square wave with amplitude 0.5, right is silent
+        let frame: Vec<f32> = (0..960)
+            .map(|n| {
+                if n % 2 == 1 {
+                    0.0
+                } else if n % 4 == 0 {
+                    0.5
+                } else {
+                    -0.5
+                }
+            })
+            .collect();
+
+        // disabled by default
+        sink.write(&frame).unwrap();
+        sink.write(&frame).unwrap();
+        assert_none!(meter.latest());
+        assert_none!(meter.take_new());
+
+        meter.set_enabled(true);
+
+        // snapshot is stored after interval
+        sink.write(&frame).unwrap();
+        assert_none!(meter.latest());
+        sink.write(&frame).unwrap();
+
+        let snapshot = meter.take_new().unwrap();
+        assert_eq!(snapshot.channels.len(), 2);
+        assert_in_delta!(snapshot.channels[0].peak, -6.02, 0.01);
+        assert_in_delta!(snapshot.channels[0].rms, -6.02, 0.01);
+        assert_eq!(snapshot.channels[1].peak, ChannelLevelSpec::MIN_LEVEL);
+        assert_eq!(snapshot.channels[1].rms, ChannelLevelSpec::MIN_LEVEL);
+
+        // snapshot is returned once by take_new(), but kept for latest()
+        assert_none!(meter.take_new());
+        assert_some!(meter.latest());
+
+        // disabling discards snapshot
+        meter.set_enabled(false);
+        assert_none!(meter.latest());
+    }
+}
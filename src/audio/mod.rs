@@ -0,0 +1,25 @@
+// Copyright (c) Roc Streaming authors
+// Licensed under MPL-2.0
+mod audio_io;
+mod error;
+mod failover_source;
+mod latency_probe;
+mod level_meter;
+mod pipeline;
+mod playout_aligner;
+mod processor;
+mod resample;
+mod silence_gate;
+mod tee_sink;
+
+pub use self::audio_io::*;
+pub use self::error::*;
+pub use self::failover_source::*;
+pub use self::latency_probe::*;
+pub use self::level_meter::*;
+pub use self::pipeline::*;
+pub use self::playout_aligner::*;
+pub use self::processor::*;
+pub use self::resample::*;
+pub use self::silence_gate::*;
+pub use self::tee_sink::*;
@@ -0,0 +1,319 @@
+// Copyright (c) Roc Streaming authors
+// Licensed under MPL-2.0
+use crate::audio::audio_io::*;
+use crate::audio::error::*;
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// Duration of frame passed from source to sink in one iteration.
+pub const FRAME_DURATION: Duration = Duration::from_millis(10);
+
+/// Snapshot of pipeline state, updated by pipeline thread.
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct PipelineStatus {
+    /// Number of frames passed from source to sink.
+    pub frame_count: u64,
+    /// Metrics of source, if it's backed by transport.
+    pub source_metrics: Option<LinkMetrics>,
+    /// Metrics of sink, if it's backed by transport.
+    pub sink_metrics: Option<LinkMetrics>,
+    /// Error which terminated pipeline.
+    pub error: Option<String>,
+    /// Whether sending is paused because signal is silent.
+    pub paused: bool,
+    /// Index of played source, if stream has failover sources.
+    pub active_source: Option<usize>,
+}
+
+/// Real-time thread that moves audio frames from source to sink.
+///
+/// If neither source nor sink is clocked, pipeline paces itself
+/// using system clock. Thread is stopped when pipeline is dropped.
+pub struct AudioPipeline {
+    name: String,
+    stop_flag: Arc<AtomicBool>,
+    status: Arc<Mutex<PipelineStatus>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl AudioPipeline {
+    /// Start pipeline thread.
+    pub fn start(
+        name: &str, source: Box<dyn AudioSource>, sink: Box<dyn AudioSink>,
+    ) -> Result<Self, AudioError> {
+        if source.format() != sink.format() {
+            return Err(AudioError::FormatMismatchError(source.format(), sink.format()));
+        }
+
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        let status = Arc::new(Mutex::new(PipelineStatus::default()));
+
+        let thread = {
+            let worker = PipelineWorker {
+                name: name.into(),
+                source,
+                sink,
+                stop_flag: Arc::clone(&stop_flag),
+                status: Arc::clone(&status),
+            };
+
+            thread::Builder::new().name(format!("rt-{name}")).spawn(move || worker.run())?
+        };
+
+        tracing::debug!("started pipeline {name}");
+
+        Ok(AudioPipeline { name: name.into(), stop_flag, status, thread: Some(thread) })
+    }
+
+    /// Check if pipeline thread is still running.
+    /// Thread exits on error, in this case status contains error.
+    pub fn is_running(&self) -> bool {
+        self.thread.as_ref().is_some_and(|thread| !thread.is_finished())
+    }
+
+    /// Get latest status reported by pipeline thread.
+    pub fn status(&self) -> PipelineStatus {
+        self.status.lock().unwrap().clone()
+    }
+
+    /// Stop pipeline thread and wait until it exits.
+    pub fn stop(&mut self) {
+        self.stop_flag.store(true, Ordering::SeqCst);
+
+        if let Some(thread) = self.thread.take() {
+            if thread.join().is_err() {
+                tracing::error!("pipeline {} panicked", self.name);
+            }
+            tracing::debug!("stopped pipeline {}", self.name);
+        }
+    }
+}
+
+impl Drop for AudioPipeline {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+struct PipelineWorker {
+    name: String,
+    source: Box<dyn AudioSource>,
+    sink: Box<dyn AudioSink>,
+    stop_flag: Arc<AtomicBool>,
+    status: Arc<Mutex<PipelineStatus>>,
+}
+
+impl PipelineWorker {
+    /// How often metrics are queried from source and sink.
+    const METRICS_INTERVAL: Duration = Duration::from_millis(500);
+
+    fn run(mut self) {
+        promote_to_realtime(&self.name);
+
+        if let Err(err) = self.run_loop() {
+            tracing::warn!("pipeline {} failed: {err}", self.name);
+            self.status.lock().unwrap().error = Some(err.to_string());
+        }
+    }
+
+    fn run_loop(&mut self) -> Result<(), AudioError> {
+        let format = self.source.format();
+        let mut frame = vec![0.0f32; format.samples_per_duration(FRAME_DURATION)];
+
+        let self_clocked = !self.source.is_clocked() && !self.sink.is_clocked();
+        let mut deadline = Instant::now();
+
+        let mut frame_count = 0u64;
+        let mut metrics_time = Instant::now();
+
+        while !self.stop_flag.load(Ordering::Relaxed) {
+            self.source.read(&mut frame)?;
+            self.sink.write(&frame)?;
+            frame_count += 1;
+
+            if metrics_time.elapsed() >= Self::METRICS_INTERVAL {
+                self.report(frame_count);
+                metrics_time = Instant::now();
+            }
+
+            if self_clocked {
+                deadline += FRAME_DURATION;
+                let now = Instant::now();
+                if deadline > now {
+                    thread::sleep(deadline - now);
+                } else if now - deadline > FRAME_DURATION * 10 {
+                    // we're too late, don't try to catch up
+                    deadline = now;
+                }
+            }
+        }
+
+        self.report(frame_count);
+
+        Ok(())
+    }
+
+    fn report(&self, frame_count: u64) {
+        // don't block real-time thread if status is being read
+        if let Ok(mut status) = self.status.try_lock() {
+            status.frame_count = frame_count;
+            status.source_metrics = self.source.metrics();
+            status.sink_metrics = self.sink.metrics();
+        }
+    }
+}
+
+/// Try to switch current thread to real-time scheduling.
+/// Usually requires privileges, so failure is not an error.
+#[cfg(target_os = "linux")]
+fn promote_to_realtime(name: &str) {
+    const RT_PRIORITY: libc::c_int = 10;
+
+    let param = libc::sched_param { sched_priority: RT_PRIORITY };
+
+    let err =
+        unsafe { libc::pthread_setschedparam(libc::pthread_self(), libc::SCHED_FIFO, &param) };
+
+    if err != 0 {
+        tracing::debug!(
+            "can't enable real-time scheduling for pipeline {name}: {}",
+            std::io::Error::from_raw_os_error(err)
+        );
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn promote_to_realtime(_name: &str) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assertables::*;
+
+    const FORMAT: AudioFormat = AudioFormat { sample_rate: 48000, channels: 2 };
+
+    struct CountingSource {
+        value: f32,
+    }
+
+    impl AudioSource for CountingSource {
+        fn format(&self) -> AudioFormat {
+            FORMAT
+        }
+
+        fn is_clocked(&self) -> bool {
+            false
+        }
+
+        fn read(&mut self, samples: &mut [f32]) -> Result<(), AudioError> {
+            self.value += 1.0;
+            samples.fill(self.value);
+            Ok(())
+        }
+    }
+
+    struct CollectingSink {
+        frames: Arc<Mutex<Vec<f32>>>,
+        fail_after: usize,
+    }
+
+    impl AudioSink for CollectingSink {
+        fn format(&self) -> AudioFormat {
+            FORMAT
+        }
+
+        fn is_clocked(&self) -> bool {
+            false
+        }
+
+        fn write(&mut self, samples: &[f32]) -> Result<(), AudioError> {
+            let mut frames = self.frames.lock().unwrap();
+            if frames.len() == self.fail_after {
+                return Err(AudioError::BackendError("sink closed".into()));
+            }
+            frames.push(samples[0]);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_frames() {
+        let frames = Arc::new(Mutex::new(Vec::new()));
+
+        let mut pipeline = AudioPipeline::start(
+            "test",
+            Box::new(CountingSource { value: 0.0 }),
+            Box::new(CollectingSink { frames: Arc::clone(&frames), fail_after: usize::MAX }),
+        )
+        .unwrap();
+
+        thread::sleep(FRAME_DURATION * 10);
+        assert!(pipeline.is_running());
+
+        pipeline.stop();
+        assert!(!pipeline.is_running());
+
+        let frames = frames.lock().unwrap().clone();
+        assert_gt!(frames.len(), 1);
+        // self-clocked pipeline doesn't run faster than real time
+        assert_lt!(frames.len(), 20);
+        for (n, value) in frames.iter().enumerate() {
+            assert_eq!(*value, (n + 1) as f32);
+        }
+
+        let status = pipeline.status();
+        assert_eq!(status.frame_count, frames.len() as u64);
+        assert_none!(status.error);
+    }
+
+    #[test]
+    fn test_error() {
+        let mut pipeline = AudioPipeline::start(
+            "test",
+            Box::new(CountingSource { value: 0.0 }),
+            Box::new(CollectingSink {
+                frames: Arc::new(Mutex::new(Vec::new())),
+                fail_after: 3,
+            }),
+        )
+        .unwrap();
+
+        while pipeline.is_running() {
+            thread::sleep(FRAME_DURATION);
+        }
+        pipeline.stop();
+
+        assert_eq!(pipeline.status().error.as_deref(), Some("backend error: sink closed"));
+    }
+
+    #[test]
+    fn test_format_mismatch() {
+        struct MonoSink {}
+
+        impl AudioSink for MonoSink {
+            fn format(&self) -> AudioFormat {
+                AudioFormat { sample_rate: 48000, channels: 1 }
+            }
+
+            fn is_clocked(&self) -> bool {
+                false
+            }
+
+            fn write(&mut self, _samples: &[f32]) -> Result<(), AudioError> {
+                Ok(())
+            }
+        }
+
+        assert_matches!(
+            AudioPipeline::start(
+                "test",
+                Box::new(CountingSource { value: 0.0 }),
+                Box::new(MonoSink {})
+            ),
+            Err(AudioError::FormatMismatchError(_, _))
+        );
+    }
+}
@@ -0,0 +1,215 @@
+// Copyright (c) Roc Streaming authors
+// Licensed under MPL-2.0
+use crate::audio::audio_io::*;
+use crate::audio::error::*;
+use crate::audio::resample::*;
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// Delay of aligned source, shared between runner and pipeline.
+///
+/// Runner sets target delay, pipeline moves actual delay towards it
+/// and reports actual delay back.
+pub struct AlignmentControl {
+    // in nanoseconds
+    target_delay: AtomicU64,
+    current_delay: AtomicU64,
+}
+
+impl AlignmentControl {
+    pub fn new() -> Self {
+        AlignmentControl { target_delay: AtomicU64::new(0), current_delay: AtomicU64::new(0) }
+    }
+
+    pub fn set_target_delay(&self, delay: Duration) {
+        self.target_delay.store(delay.as_nanos() as u64, Ordering::Relaxed);
+    }
+
+    pub fn target_delay(&self) -> Duration {
+        Duration::from_nanos(self.target_delay.load(Ordering::Relaxed))
+    }
+
+    /// Delay currently applied by pipeline.
+    pub fn current_delay(&self) -> Duration {
+        Duration::from_nanos(self.current_delay.load(Ordering::Relaxed))
+    }
+}
+
+impl Default for AlignmentControl {
+    fn default() -> Self {
+        AlignmentControl::new()
+    }
+}
+
+/// Source that delays inner source by adjustable amount, so that
+/// playout of several pipelines can be aligned.
+///
+/// Small changes of delay are applied gradually, by slightly stretching
+/// or squeezing frames. Large changes are applied at once, by inserting
+/// silence or dropping samples.
+///
+/// Inner source is always read at normal rate, so that receivers
+/// don't see delay changes as clock drift.
+pub struct AlignedSource {
+    inner: Box<dyn AudioSource>,
+    control: Arc<AlignmentControl>,
+    // delayed interleaved samples
+    fifo: VecDeque<f32>,
+    scratch: Vec<f32>,
+}
+
+impl AlignedSource {
+    /// Difference between target and actual delay that is ignored.
+    const TOLERANCE: Duration = Duration::from_millis(1);
+    /// Difference after which delay is changed at once.
+    const JUMP_THRESHOLD: Duration = Duration::from_millis(20);
+    /// Maximum relative change of playback speed during gradual change.
+    const MAX_STRETCH: f64 = 0.005;
+
+    pub fn new(inner: Box<dyn AudioSource>, control: &Arc<AlignmentControl>) -> Self {
+        AlignedSource {
+            inner,
+            control: Arc::clone(control),
+            fifo: VecDeque::new(),
+            scratch: Vec::new(),
+        }
+    }
+}
+
+impl AudioSource for AlignedSource {
+    fn format(&self) -> AudioFormat {
+        self.inner.format()
+    }
+
+    fn is_clocked(&self) -> bool {
+        self.inner.is_clocked()
+    }
+
+    fn read(&mut self, samples: &mut [f32]) -> Result<(), AudioError> {
+        let format = self.inner.format();
+        let channels = format.channels as usize;
+        let frames_of = |duration: Duration| format.samples_per_duration(duration) / channels;
+
+        self.inner.read(samples)?;
+        self.fifo.extend(samples.iter());
+
+        let n_frames = samples.len() / channels;
+        // delay if we consume exactly one frame
+        let delay = (self.fifo.len() / channels - n_frames) as i64;
+        let diff = frames_of(self.control.target_delay()) as i64 - delay;
+
+        let consume = if diff.unsigned_abs() as usize > frames_of(Self::JUMP_THRESHOLD) {
+            if diff > 0 {
+                for _ in 0..diff as usize * channels {
+                    self.fifo.push_front(0.0);
+                }
+            } else {
+                self.fifo.drain(..(-diff) as usize * channels);
+            }
+            n_frames
+        } else if diff.unsigned_abs() as usize > frames_of(Self::TOLERANCE) {
+            let step = ((n_frames as f64 * Self::MAX_STRETCH) as usize)
+                .clamp(1, diff.unsigned_abs() as usize)
+                .min(n_frames - 1);
+            if diff > 0 { n_frames - step } else { n_frames + step }
+        } else {
+            n_frames
+        };
+
+        if consume == n_frames {
+            for (sample, delayed) in
+                samples.iter_mut().zip(self.fifo.drain(..consume * channels))
+            {
+                *sample = delayed;
+            }
+        } else {
+            self.scratch.clear();
+            self.scratch.extend(self.fifo.drain(..consume * channels));
+            resample_linear(&self.scratch, samples, channels);
+        }
+
+        let current_delay = format.duration_of_samples(self.fifo.len());
+        self.control.current_delay.store(current_delay.as_nanos() as u64, Ordering::Relaxed);
+
+        Ok(())
+    }
+
+    fn metrics(&self) -> Option<LinkMetrics> {
+        self.inner.metrics()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assertables::*;
+
+    const FORMAT: AudioFormat = AudioFormat { sample_rate: 48000, channels: 2 };
+
+    /// Source producing increasing numbers.
+    struct CounterSource {
+        counter: u32,
+    }
+
+    impl AudioSource for CounterSource {
+        fn format(&self) -> AudioFormat {
+            FORMAT
+        }
+
+        fn is_clocked(&self) -> bool {
+            false
+        }
+
+        fn read(&mut self, samples: &mut [f32]) -> Result<(), AudioError> {
+            for frame in samples.chunks_exact_mut(2) {
+                self.counter += 1;
+                frame.fill(self.counter as f32);
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_delay() {
+        let control = Arc::new(AlignmentControl::new());
+        let mut source = AlignedSource::new(Box::new(CounterSource { counter: 0 }), &control);
+        let mut frame = vec![0.0; 960];
+
+        // no delay
+        source.read(&mut frame).unwrap();
+        assert_eq!(frame[0], 1.0);
+        assert_eq!(control.current_delay(), Duration::ZERO);
+
+        // large change is applied at once
+        control.set_target_delay(Duration::from_millis(100));
+        source.read(&mut frame).unwrap();
+        assert_eq!(frame[0], 0.0);
+        assert_eq!(control.current_delay(), Duration::from_millis(100));
+
+        // after delay, signal continues where it stopped
+        for _ in 0..10 {
+            source.read(&mut frame).unwrap();
+        }
+        assert_eq!(frame[0], 481.0);
+
+        // small change is applied gradually
+        control.set_target_delay(Duration::from_millis(105));
+        source.read(&mut frame).unwrap();
+        assert_gt!(control.current_delay(), Duration::from_millis(100));
+        assert_lt!(control.current_delay(), Duration::from_millis(105));
+
+        for _ in 0..200 {
+            source.read(&mut frame).unwrap();
+        }
+        assert_in_delta!(control.current_delay().as_secs_f64(), 0.105, 0.002);
+
+        // large decrease drops samples
+        control.set_target_delay(Duration::ZERO);
+        source.read(&mut frame).unwrap();
+        assert_eq!(control.current_delay(), Duration::ZERO);
+        assert_eq!(frame[0], frame[958] - 479.0);
+    }
+}
@@ -0,0 +1,332 @@
+// Copyright (c) Roc Streaming authors
+// Licensed under MPL-2.0
+use crate::audio::audio_io::*;
+use crate::audio::error::*;
+use crate::audio::pipeline::FRAME_DURATION;
+use crate::dto::ProcessingSpec;
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+/// Processing parameters shared between stream runner and pipeline.
+///
+/// Parameters are stored in atomics, so that they can be updated while
+/// pipeline is running, without blocking real-time thread.
+pub struct ProcessingControl {
+    // linear gain, as f32 bits
+    gain: AtomicU32,
+    mute: AtomicBool,
+    // linear threshold, as f32 bits; NaN if disabled
+    limiter_threshold: AtomicU32,
+    // target mean square, as f32 bits; NaN if disabled
+    loudness_target: AtomicU32,
+}
+
+impl ProcessingControl {
+    pub fn new(spec: Option<&ProcessingSpec>) -> Self {
+        let control = ProcessingControl {
+            gain: AtomicU32::new(0),
+            mute: AtomicBool::new(false),
+            limiter_threshold: AtomicU32::new(0),
+            loudness_target: AtomicU32::new(0),
+        };
+        control.update(spec);
+        control
+    }
+
+    /// Apply new parameters; None means no processing.
+    pub fn update(&self, spec: Option<&ProcessingSpec>) {
+        let default_spec = ProcessingSpec::default();
+        let spec = spec.unwrap_or(&default_spec);
+
+        let gain = db_to_amplitude(spec.gain.unwrap_or(0.0));
+        let limiter_threshold = spec.limiter_threshold.map(db_to_amplitude);
+        let loudness_target = spec.loudness_target.map(|db| db_to_amplitude(db).powi(2));
+
+        self.gain.store(gain.to_bits(), Ordering::Relaxed);
+        self.mute.store(spec.mute, Ordering::Relaxed);
+        self.limiter_threshold
+            .store(limiter_threshold.unwrap_or(f32::NAN).to_bits(), Ordering::Relaxed);
+        self.loudness_target
+            .store(loudness_target.unwrap_or(f32::NAN).to_bits(), Ordering::Relaxed);
+    }
+
+    fn load(&self) -> ProcessingParams {
+        let load_f32 = |value: &AtomicU32| f32::from_bits(value.load(Ordering::Relaxed));
+        let load_opt = |value: &AtomicU32| Some(load_f32(value)).filter(|v| !v.is_nan());
+
+        ProcessingParams {
+            gain: load_f32(&self.gain),
+            mute: self.mute.load(Ordering::Relaxed),
+            limiter_threshold: load_opt(&self.limiter_threshold),
+            loudness_target: load_opt(&self.loudness_target),
+        }
+    }
+}
+
+struct ProcessingParams {
+    gain: f32,
+    mute: bool,
+    limiter_threshold: Option<f32>,
+    loudness_target: Option<f32>,
+}
+
+/// Source that applies loudness normalization, gain, mute, and limiter
+/// to frames produced by inner source.
+///
+/// Gain changes are ramped over one frame to avoid clicks.
+pub struct ProcessingSource {
+    inner: Box<dyn AudioSource>,
+    control: Arc<ProcessingControl>,
+    // gain applied at the end of previous frame
+    current_gain: f32,
+    // smoothed mean square of non-silent frames, 0 if unknown
+    mean_square: f32,
+    // gain reduction of limiter, in range (0; 1]
+    limiter_gain: f32,
+    limiter_release: f32,
+    smoothing: f32,
+}
+
+impl ProcessingSource {
+    /// Time during which loudness is averaged.
+    const LOUDNESS_WINDOW: f32 = 3.0;
+    /// Frames quieter than this don't affect loudness measurement.
+    const LOUDNESS_GATE: f32 = 1e-7; // -70 dBFS
+    /// Normalization doesn't change gain by more than this, in dB.
+    const MAX_NORMALIZATION: f32 = 20.0;
+    /// Time during which limiter returns to unity gain.
+    const LIMITER_RELEASE: f32 = 0.05;
+
+    pub fn new(inner: Box<dyn AudioSource>, control: &Arc<ProcessingControl>) -> Self {
+        let sample_rate = inner.format().sample_rate as f32;
+        let frame_duration = FRAME_DURATION.as_secs_f32();
+
+        ProcessingSource {
+            inner,
+            control: Arc::clone(control),
+            current_gain: control.load().gain,
+            mean_square: 0.0,
+            limiter_gain: 1.0,
+            limiter_release: 1.0 - (-1.0 / (Self::LIMITER_RELEASE * sample_rate)).exp(),
+            smoothing: 1.0 - (-frame_duration / Self::LOUDNESS_WINDOW).exp(),
+        }
+    }
+
+    fn normalization_gain(&mut self, samples: &[f32], target: f32) -> f32 {
+        let mean_square =
+            samples.iter().map(|s| s * s).sum::<f32>() / samples.len().max(1) as f32;
+
+        if mean_square > Self::LOUDNESS_GATE {
+            self.mean_square = if self.mean_square == 0.0 {
+                mean_square
+            } else {
+                self.mean_square + (mean_square - self.mean_square) * self.smoothing
+            };
+        }
+
+        if self.mean_square == 0.0 {
+            return 1.0;
+        }
+
+        let max_gain = db_to_amplitude(Self::MAX_NORMALIZATION);
+        (target / self.mean_square).sqrt().clamp(1.0 / max_gain, max_gain)
+    }
+
+    fn process(&mut self, samples: &mut [f32]) {
+        let params = self.control.load();
+
+        let mut target_gain = if params.mute { 0.0 } else { params.gain };
+        match params.loudness_target {
+            Some(target) => target_gain *= self.normalization_gain(samples, target),
+            None => self.mean_square = 0.0,
+        }
+
+        // fast path: nothing to do
+        if target_gain == 1.0 && self.current_gain == 1.0 && params.limiter_threshold.is_none()
+        {
+            self.limiter_gain = 1.0;
+            return;
+        }
+
+        let channels = self.inner.format().channels as usize;
+        let n_frames = (samples.len() / channels).max(1);
+        let gain_step = (target_gain - self.current_gain) / n_frames as f32;
+
+        for frame in samples.chunks_exact_mut(channels) {
+            self.current_gain += gain_step;
+
+            for sample in frame.iter_mut() {
+                *sample *= self.current_gain;
+            }
+
+            if let Some(threshold) = params.limiter_threshold {
+                // instant attack, exponential release
+                let peak = frame.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+                let needed_gain = if peak > threshold { threshold / peak } else { 1.0 };
+
+                if needed_gain < self.limiter_gain {
+                    self.limiter_gain = needed_gain;
+                } else {
+                    self.limiter_gain += (1.0 - self.limiter_gain) * self.limiter_release;
+                }
+
+                for sample in frame.iter_mut() {
+                    *sample = (*sample * self.limiter_gain).clamp(-threshold, threshold);
+                }
+            }
+        }
+
+        self.current_gain = target_gain;
+    }
+}
+
+impl AudioSource for ProcessingSource {
+    fn format(&self) -> AudioFormat {
+        self.inner.format()
+    }
+
+    fn is_clocked(&self) -> bool {
+        self.inner.is_clocked()
+    }
+
+    fn read(&mut self, samples: &mut [f32]) -> Result<(), AudioError> {
+        self.inner.read(samples)?;
+        self.process(samples);
+        Ok(())
+    }
+
+    fn metrics(&self) -> Option<LinkMetrics> {
+        self.inner.metrics()
+    }
+}
+
+fn db_to_amplitude(db: f32) -> f32 {
+    10f32.powf(db / 20.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assertables::*;
+
+    const FORMAT: AudioFormat = AudioFormat { sample_rate: 48000, channels: 2 };
+
+    /// Produces square wave with given amplitude.
+    struct SquareSource {
+        amplitude: f32,
+    }
+
+    impl AudioSource for SquareSource {
+        fn format(&self) -> AudioFormat {
+            FORMAT
+        }
+
+        fn is_clocked(&self) -> bool {
+            false
+        }
+
+        fn read(&mut self, samples: &mut [f32]) -> Result<(), AudioError> {
+            for (n, sample) in samples.iter_mut().enumerate() {
+                *sample = if (n / 2) % 2 == 0 { self.amplitude } else { -self.amplitude };
+            }
+            Ok(())
+        }
+    }
+
+    fn make_source(
+        amplitude: f32, spec: &ProcessingSpec,
+    ) -> (ProcessingSource, Arc<ProcessingControl>) {
+        let control = Arc::new(ProcessingControl::new(Some(spec)));
+        let source = ProcessingSource::new(Box::new(SquareSource { amplitude }), &control);
+        (source, control)
+    }
+
+    fn read_frame(source: &mut ProcessingSource) -> Vec<f32> {
+        let mut samples = vec![0.0; FORMAT.samples_per_duration(FRAME_DURATION)];
+        source.read(&mut samples).unwrap();
+        samples
+    }
+
+    fn peak(samples: &[f32]) -> f32 {
+        samples.iter().fold(0.0f32, |peak, s| peak.max(s.abs()))
+    }
+
+    #[test]
+    fn test_passthrough() {
+        let (mut source, _) = make_source(0.5, &ProcessingSpec::default());
+
+        let samples = read_frame(&mut source);
+        assert!(samples.iter().all(|s| s.abs() == 0.5));
+    }
+
+    #[test]
+    fn test_gain_and_mute() {
+        let (mut source, control) =
+            make_source(0.5, &ProcessingSpec { gain: Some(-6.0), ..Default::default() });
+
+        let samples = read_frame(&mut source);
+        assert_in_delta!(peak(&samples), 0.25, 0.01);
+
+        // mute is ramped during one frame, then output is silent
+        control.update(Some(&ProcessingSpec { mute: true, ..Default::default() }));
+
+        let samples = read_frame(&mut source);
+        assert_in_delta!(samples[0].abs(), 0.25, 0.01);
+        assert_in_delta!(samples[samples.len() - 1].abs(), 0.0, 0.01);
+
+        let samples = read_frame(&mut source);
+        assert!(samples.iter().all(|s| *s == 0.0));
+
+        // unmute
+        control.update(None);
+
+        read_frame(&mut source);
+        let samples = read_frame(&mut source);
+        assert!(samples.iter().all(|s| s.abs() == 0.5));
+    }
+
+    #[test]
+    fn test_limiter() {
+        let (mut source, _) = make_source(
+            0.5,
+            &ProcessingSpec {
+                gain: Some(12.0),
+                limiter_threshold: Some(-6.0),
+                ..Default::default()
+            },
+        );
+
+        for _ in 0..10 {
+            let samples = read_frame(&mut source);
+            assert_le!(peak(&samples), db_to_amplitude(-6.0) + 0.0001);
+            assert_in_delta!(peak(&samples), db_to_amplitude(-6.0), 0.01);
+        }
+    }
+
+    #[test]
+    fn test_loudness() {
+        // square wave has RMS equal to amplitude
+        let (mut source, _) = make_source(
+            0.01,
+            &ProcessingSpec { loudness_target: Some(-20.0), ..Default::default() },
+        );
+
+        let mut samples = Vec::new();
+        for _ in 0..100 {
+            samples = read_frame(&mut source);
+        }
+        assert_in_delta!(peak(&samples), 0.1, 0.001);
+
+        // gain is bounded
+        let (mut source, _) = make_source(
+            0.001,
+            &ProcessingSpec { loudness_target: Some(-10.0), ..Default::default() },
+        );
+
+        for _ in 0..100 {
+            samples = read_frame(&mut source);
+        }
+        assert_in_delta!(peak(&samples), 0.01, 0.001);
+    }
+}
@@ -0,0 +1,43 @@
+// Copyright (c) Roc Streaming authors
+// Licensed under MPL-2.0
+
+/// Stretch or shrink interleaved frames using linear interpolation.
+pub fn resample_linear(input: &[f32], output: &mut [f32], channels: usize) {
+    let in_frames = input.len() / channels;
+    let out_frames = output.len() / channels;
+
+    if in_frames == 0 || out_frames == 0 {
+        return;
+    }
+
+    let ratio =
+        if out_frames > 1 { (in_frames - 1) as f64 / (out_frames - 1) as f64 } else { 0.0 };
+
+    for out_frame in 0..out_frames {
+        let pos = out_frame as f64 * ratio;
+        let index = (pos as usize).min(in_frames - 1);
+        let next = (index + 1).min(in_frames - 1);
+        let frac = (pos - index as f64) as f32;
+
+        for ch in 0..channels {
+            let a = input[index * channels + ch];
+            let b = input[next * channels + ch];
+            output[out_frame * channels + ch] = a + (b - a) * frac;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resample() {
+        let input = [0.0, 0.0, 1.0, 2.0, 2.0, 4.0];
+        let mut output = [0.0; 10];
+
+        // 3 frames -> 5 frames
+        resample_linear(&input, &mut output, 2);
+        assert_eq!(output, [0.0, 0.0, 0.5, 1.0, 1.0, 2.0, 1.5, 3.0, 2.0, 4.0]);
+    }
+}
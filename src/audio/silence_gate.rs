@@ -0,0 +1,183 @@
+// Copyright (c) Roc Streaming authors
+// Licensed under MPL-2.0
+use crate::audio::audio_io::*;
+use crate::audio::error::*;
+use crate::dto::SilenceDetectionSpec;
+
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Sink that stops writing to inner sink while signal is silent.
+///
+/// When peak level stays below threshold for hold time, frames are dropped
+/// instead of being written, until a frame above threshold arrives.
+/// Pause and resume are reported via callback, invoked on pipeline thread.
+///
+/// If inner sink is clocked, dropped frames are paced using system clock,
+/// so that pipeline keeps running in real time while paused.
+pub struct SilenceGate {
+    inner: Box<dyn AudioSink>,
+    // linear peak level
+    threshold: f32,
+    hold_time: Duration,
+    // how long signal is silent
+    silent_time: Duration,
+    paused: bool,
+    on_change: Box<dyn FnMut(bool) + Send>,
+    // when next dropped frame is due, if inner sink is clocked
+    deadline: Option<Instant>,
+}
+
+impl SilenceGate {
+    pub fn new(
+        inner: Box<dyn AudioSink>, spec: &SilenceDetectionSpec,
+        on_change: Box<dyn FnMut(bool) + Send>,
+    ) -> Self {
+        SilenceGate {
+            inner,
+            threshold: 10f32.powf(spec.threshold() / 20.0),
+            hold_time: Duration::from_secs_f32(spec.hold_time()),
+            silent_time: Duration::ZERO,
+            paused: false,
+            on_change,
+            deadline: None,
+        }
+    }
+
+    fn update(&mut self, samples: &[f32]) {
+        let peak = samples.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+
+        if peak >= self.threshold {
+            self.silent_time = Duration::ZERO;
+            if self.paused {
+                self.paused = false;
+                (self.on_change)(false);
+            }
+        } else {
+            self.silent_time += self.inner.format().duration_of_samples(samples.len());
+            if !self.paused && self.silent_time >= self.hold_time {
+                self.paused = true;
+                self.deadline = None;
+                (self.on_change)(true);
+            }
+        }
+    }
+
+    /// Sleep for duration of dropped frame.
+    fn pace(&mut self, duration: Duration) {
+        let now = Instant::now();
+        let next = self.deadline.get_or_insert(now);
+
+        if *next > now {
+            thread::sleep(*next - now);
+        } else if now - *next > duration * 10 {
+            // we're too late, don't try to catch up
+            *next = now;
+        }
+
+        *next += duration;
+    }
+}
+
+impl AudioSink for SilenceGate {
+    fn format(&self) -> AudioFormat {
+        self.inner.format()
+    }
+
+    fn is_clocked(&self) -> bool {
+        self.inner.is_clocked()
+    }
+
+    fn write(&mut self, samples: &[f32]) -> Result<(), AudioError> {
+        self.update(samples);
+
+        if !self.paused {
+            return self.inner.write(samples);
+        }
+
+        if self.inner.is_clocked() {
+            self.pace(self.inner.format().duration_of_samples(samples.len()));
+        }
+
+        Ok(())
+    }
+
+    fn metrics(&self) -> Option<LinkMetrics> {
+        self.inner.metrics()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    const FORMAT: AudioFormat = AudioFormat { sample_rate: 48000, channels: 2 };
+
+    struct CountingSink {
+        count: Arc<Mutex<usize>>,
+    }
+
+    impl AudioSink for CountingSink {
+        fn format(&self) -> AudioFormat {
+            FORMAT
+        }
+
+        fn is_clocked(&self) -> bool {
+            false
+        }
+
+        fn write(&mut self, _samples: &[f32]) -> Result<(), AudioError> {
+            *self.count.lock().unwrap() += 1;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_pause_resume() {
+        let count = Arc::new(Mutex::new(0));
+        let changes = Arc::new(Mutex::new(Vec::new()));
+
+        let mut gate = {
+            let changes = Arc::clone(&changes);
+            SilenceGate::new(
+                Box::new(CountingSink { count: Arc::clone(&count) }),
+                &SilenceDetectionSpec { threshold: Some(-40.0), hold_time: Some(0.095) },
+                Box::new(move |paused| changes.lock().unwrap().push(paused)),
+            )
+        };
+
+        // 10ms frames, hold time is 9.5 frames
+        let loud = vec![0.1; 960];
+        let quiet = vec![0.001; 960];
+
+        gate.write(&loud).unwrap();
+        assert_eq!(*count.lock().unwrap(), 1);
+
+        // quiet frames are written during hold time
+        for _ in 0..9 {
+            gate.write(&quiet).unwrap();
+        }
+        assert_eq!(*count.lock().unwrap(), 10);
+        assert!(changes.lock().unwrap().is_empty());
+
+        // then dropped
+        for _ in 0..5 {
+            gate.write(&quiet).unwrap();
+        }
+        assert_eq!(*count.lock().unwrap(), 10);
+        assert_eq!(*changes.lock().unwrap(), vec![true]);
+
+        // first loud frame resumes
+        gate.write(&loud).unwrap();
+        assert_eq!(*count.lock().unwrap(), 11);
+        assert_eq!(*changes.lock().unwrap(), vec![true, false]);
+
+        // hold time starts over
+        for _ in 0..9 {
+            gate.write(&quiet).unwrap();
+        }
+        assert_eq!(*count.lock().unwrap(), 20);
+        assert_eq!(*changes.lock().unwrap(), vec![true, false]);
+    }
+}
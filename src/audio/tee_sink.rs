@@ -0,0 +1,44 @@
+// Copyright (c) Roc Streaming authors
+// Licensed under MPL-2.0
+use crate::audio::audio_io::*;
+use crate::audio::error::*;
+
+/// Sink that writes frames to primary sink and a copy to secondary sink.
+///
+/// Clocking and metrics are taken from primary sink; secondary sink
+/// should not block (e.g. it may be recorder that queues frames).
+pub struct TeeSink {
+    primary: Box<dyn AudioSink>,
+    secondary: Box<dyn AudioSink>,
+}
+
+impl TeeSink {
+    pub fn new(
+        primary: Box<dyn AudioSink>, secondary: Box<dyn AudioSink>,
+    ) -> Result<Self, AudioError> {
+        if primary.format() != secondary.format() {
+            return Err(AudioError::FormatMismatchError(primary.format(), secondary.format()));
+        }
+
+        Ok(TeeSink { primary, secondary })
+    }
+}
+
+impl AudioSink for TeeSink {
+    fn format(&self) -> AudioFormat {
+        self.primary.format()
+    }
+
+    fn is_clocked(&self) -> bool {
+        self.primary.is_clocked()
+    }
+
+    fn write(&mut self, samples: &[f32]) -> Result<(), AudioError> {
+        self.primary.write(samples)?;
+        self.secondary.write(samples)
+    }
+
+    fn metrics(&self) -> Option<LinkMetrics> {
+        self.primary.metrics()
+    }
+}
@@ -0,0 +1,25 @@
+// Copyright (c) Roc Streaming authors
+// Licensed under MPL-2.0
+use crate::dto::{TokenScope, Uid, ValidationError};
+use crate::vault::VaultError;
+
+#[derive(thiserror::Error, Debug)]
+pub enum AuthError {
+    #[error("missing bearer token")]
+    MissingTokenError,
+
+    #[error("invalid bearer token")]
+    InvalidTokenError,
+
+    #[error("token scope '{0}' is not enough, '{1}' required")]
+    ScopeError(TokenScope, TokenScope),
+
+    #[error("token not found: {0}")]
+    NotFoundError(Uid),
+
+    #[error("{0}")]
+    ValidationError(#[from] ValidationError),
+
+    #[error("{0}")]
+    VaultError(#[from] VaultError),
+}
@@ -0,0 +1,7 @@
+// Copyright (c) Roc Streaming authors
+// Licensed under MPL-2.0
+mod error;
+mod token_store;
+
+pub use self::error::*;
+pub use self::token_store::*;
@@ -0,0 +1,208 @@
+// Copyright (c) Roc Streaming authors
+// Licensed under MPL-2.0
+use crate::auth::error::*;
+use crate::dto::*;
+use crate::vault::{Vault, VaultError};
+
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use std::fmt::Write as _;
+use std::result;
+use std::sync::Arc;
+use time::OffsetDateTime;
+
+pub type Result<T> = result::Result<T, AuthError>;
+
+// number of random bytes in token secret
+const SECRET_LEN: usize = 32;
+
+/// Manages API tokens in vault and checks tokens of requests.
+///
+/// Bearer token given to user has form "<token_uid>.<secret>", where
+/// secret is hex-encoded random bytes. Vault stores only secret hash, so
+/// lost tokens can't be recovered, only removed and created again.
+#[derive(Debug)]
+pub struct TokenStore {
+    vault: Arc<Vault>,
+}
+
+impl TokenStore {
+    pub fn new(vault: &Arc<Vault>) -> Self {
+        TokenStore { vault: Arc::clone(vault) }
+    }
+
+    /// Create new token.
+    /// Returns stored token and bearer token string for user.
+    pub async fn create_token(
+        &self, name: &str, scope: TokenScope,
+    ) -> Result<(TokenSpec, String)> {
+        let mut secret_bytes = [0u8; SECRET_LEN];
+        rand::rng().fill_bytes(&mut secret_bytes);
+        let secret = hex_encode(&secret_bytes);
+
+        let token = TokenSpec {
+            token_uid: Uid::generate_random(),
+            scope,
+            name: name.into(),
+            secret_hash: hash_secret(&secret),
+            created_at: OffsetDateTime::now_utc(),
+        };
+        token.validate()?;
+
+        self.vault.write_token(&Arc::new(token.clone())).await?;
+
+        tracing::debug!("created token {} with scope '{}'", token.token_uid, token.scope);
+
+        let bearer = format!("{}.{}", token.token_uid, secret);
+
+        Ok((token, bearer))
+    }
+
+    /// Get all tokens, oldest first.
+    pub async fn list_tokens(&self) -> Result<Vec<TokenSpec>> {
+        let mut tokens = Vec::new();
+        for token_uid in self.vault.list_tokens().await?.iter() {
+            tokens.push((*self.vault.read_token(token_uid).await?).clone());
+        }
+        tokens.sort_by_key(|token| (token.created_at, token.token_uid));
+
+        Ok(tokens)
+    }
+
+    /// Check if there are any tokens.
+    pub async fn has_tokens(&self) -> Result<bool> {
+        Ok(!self.vault.list_tokens().await?.is_empty())
+    }
+
+    /// Remove token; requests with it will be rejected.
+    pub async fn remove_token(&self, token_uid: &Uid) -> Result<()> {
+        match self.vault.read_token(token_uid).await {
+            Ok(_) => (),
+            Err(VaultError::UidNotFound(_)) => {
+                return Err(AuthError::NotFoundError(*token_uid));
+            },
+            Err(err) => return Err(err.into()),
+        }
+
+        self.vault.remove_token(token_uid).await?;
+
+        tracing::debug!("removed token {token_uid}");
+
+        Ok(())
+    }
+
+    /// Find token by bearer token string and check its secret.
+    pub async fn authenticate(&self, bearer: &str) -> Result<TokenSpec> {
+        let (token_uid, secret) =
+            bearer.split_once('.').ok_or(AuthError::InvalidTokenError)?;
+        let token_uid = Uid::parse(token_uid).map_err(|_| AuthError::InvalidTokenError)?;
+
+        let token = match self.vault.read_token(&token_uid).await {
+            Ok(token) => token,
+            Err(VaultError::UidNotFound(_)) => return Err(AuthError::InvalidTokenError),
+            Err(err) => return Err(err.into()),
+        };
+
+        if !constant_time_eq(hash_secret(secret).as_bytes(), token.secret_hash.as_bytes()) {
+            return Err(AuthError::InvalidTokenError);
+        }
+
+        Ok((*token).clone())
+    }
+}
+
+fn hash_secret(secret: &str) -> String {
+    hex_encode(&Sha256::digest(secret.as_bytes()))
+}
+
+fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::with_capacity(bytes.len() * 2), |mut text, byte| {
+        _ = write!(text, "{byte:02x}");
+        text
+    })
+}
+
+/// Compare without early exit, to not reveal position of first mismatch.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vault::VaultConfigBuilder;
+    use assertables::*;
+    use tempfile::TempDir;
+
+    async fn open_store(dir: &TempDir) -> TokenStore {
+        let vault = Vault::open(
+            &VaultConfigBuilder::default()
+                .db_path(dir.path().join("rocd.db").to_str().unwrap())
+                .build()
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+        TokenStore::new(&Arc::new(vault))
+    }
+
+    #[tokio::test]
+    async fn test_authenticate() {
+        let dir = TempDir::new().unwrap();
+        let store = open_store(&dir).await;
+
+        assert!(!store.has_tokens().await.unwrap());
+
+        let (token, bearer) = store.create_token("test", TokenScope::Control).await.unwrap();
+        assert_starts_with!(bearer, token.token_uid.as_str());
+        assert_not_contains!(bearer, &token.secret_hash);
+        assert!(store.has_tokens().await.unwrap());
+
+        assert_eq!(store.authenticate(&bearer).await.unwrap(), token);
+
+        let (token_uid, secret) = bearer.split_once('.').unwrap();
+        let other_uid = Uid::generate_random();
+
+        for bad_bearer in [
+            String::new(),
+            token_uid.to_string(),
+            format!("{token_uid}."),
+            format!("{token_uid}.{secret}0"),
+            format!("{token_uid}.{}", &secret[1..]),
+            format!("{other_uid}.{secret}"),
+            format!("bad-uid.{secret}"),
+        ] {
+            assert_matches!(
+                store.authenticate(&bad_bearer).await,
+                Err(AuthError::InvalidTokenError)
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_list_remove() {
+        let dir = TempDir::new().unwrap();
+        let store = open_store(&dir).await;
+
+        let (token1, bearer1) = store.create_token("one", TokenScope::ReadOnly).await.unwrap();
+        let (token2, bearer2) = store.create_token("two", TokenScope::Admin).await.unwrap();
+        assert_ne!(bearer1, bearer2);
+
+        let tokens = store.list_tokens().await.unwrap();
+        assert_eq!(tokens.len(), 2);
+        assert_contains!(tokens, &token1);
+        assert_contains!(tokens, &token2);
+
+        store.remove_token(&token1.token_uid).await.unwrap();
+
+        assert_eq!(store.list_tokens().await.unwrap(), vec![token2.clone()]);
+        assert_matches!(store.authenticate(&bearer1).await, Err(AuthError::InvalidTokenError));
+        assert_ok!(store.authenticate(&bearer2).await);
+
+        assert_matches!(
+            store.remove_token(&token1.token_uid).await,
+            Err(AuthError::NotFoundError(_))
+        );
+    }
+}
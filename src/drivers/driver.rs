@@ -1,13 +1,18 @@
 // Copyright (c) Roc Streaming authors
 // Licensed under MPL-2.0
+use crate::audio::{AudioFormat, AudioSink, AudioSource};
 use crate::drivers::error::*;
-use crate::dto::DriverId;
+use crate::dto::{DriverId, Uri};
 
 use async_trait::async_trait;
 use std::sync::Arc;
 
 pub type DriverResult<T> = std::result::Result<T, DriverError>;
 
+/// UID of device endpoint which represents default device of driver.
+/// Devices are not enumerated yet, so it's the only device endpoint.
+pub const DEFAULT_DEVICE_UID: &str = "444444-555555-666666";
+
 #[async_trait]
 pub trait Driver: Send + Sync {
     /// Open driver.
@@ -20,4 +25,26 @@ pub trait Driver: Send + Sync {
 
     /// Get driver ID.
     fn id(&self) -> DriverId;
+
+    /// Check if driver is still operational, e.g. connected to sound server.
+    /// Default implementation reports that it is.
+    fn is_up(&self) -> bool {
+        true
+    }
+
+    /// Open endpoint for capturing audio from it.
+    /// Default implementation reports that audio i/o is not supported.
+    async fn open_source(
+        &self, endpoint_uri: &Uri, _format: AudioFormat,
+    ) -> DriverResult<Box<dyn AudioSource>> {
+        Err(DriverError::EndpointError(endpoint_uri.clone(), "audio i/o not supported".into()))
+    }
+
+    /// Open endpoint for playing audio to it.
+    /// Default implementation reports that audio i/o is not supported.
+    async fn open_sink(
+        &self, endpoint_uri: &Uri, _format: AudioFormat,
+    ) -> DriverResult<Box<dyn AudioSink>> {
+        Err(DriverError::EndpointError(endpoint_uri.clone(), "audio i/o not supported".into()))
+    }
 }
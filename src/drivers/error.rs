@@ -1,6 +1,6 @@
 // Copyright (c) Roc Streaming authors
 // Licensed under MPL-2.0
-use crate::dto::DriverId;
+use crate::dto::{DriverId, Uri};
 
 #[derive(thiserror::Error, Debug)]
 pub enum DriverError {
@@ -15,4 +15,7 @@ pub enum DriverError {
 
     #[error("lost connection to driver")]
     ConnectionError,
+
+    #[error("can't open endpoint {0}: {1}")]
+    EndpointError(Uri, String),
 }
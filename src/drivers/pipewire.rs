@@ -2,35 +2,61 @@
 // Licensed under MPL-2.0
 #![allow(non_upper_case_globals)]
 
+use crate::audio::{AudioError, AudioFormat, AudioSink, AudioSource};
 use crate::drivers::driver::*;
 use crate::drivers::error::*;
-use crate::dto::DriverId;
+use crate::dto::{DriverId, Uri};
 
 use async_trait::async_trait;
 use libspa::param::ParamType;
+use libspa::param::audio::{AudioFormat as SpaAudioFormat, AudioInfoRaw, MAX_CHANNELS};
 use libspa::pod::deserialize::PodDeserializer;
+use libspa::pod::serialize::PodSerializer;
 use libspa::pod::{Object, Pod, Value};
 use libspa::utils::dict::DictRef;
 use libspa::utils::result::AsyncSeq;
-use libspa_sys::SPA_PROP_device;
+use libspa::utils::{Direction, SpaTypes};
+use libspa_sys::{
+    SPA_AUDIO_CHANNEL_FL, SPA_AUDIO_CHANNEL_FR, SPA_AUDIO_CHANNEL_MONO, SPA_PROP_device,
+};
 use pipewire::context::ContextRc;
-use pipewire::core::{CoreRc, Info};
+use pipewire::core::{CoreRc, Info, PW_ID_CORE};
+use pipewire::keys;
 use pipewire::main_loop::MainLoopRc;
 use pipewire::node::{Node, NodeListener};
+use pipewire::properties::properties;
 use pipewire::registry::{GlobalObject, RegistryRc};
+use pipewire::stream::{
+    Stream, StreamFlags, StreamListener, StreamRc, StreamState as PwStreamState,
+};
 use pipewire::types::ObjectType;
-use std::cell::RefCell;
-use std::collections::HashMap;
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::io::Cursor;
+use std::mem;
 use std::rc::Rc;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex as SyncMutex};
 use std::thread::{self};
+use std::time::Duration;
 use tokio::sync::Mutex;
 use tokio::task;
 
+/// How much audio is buffered between pipewire thread and pipeline.
+const RING_DURATION: Duration = Duration::from_millis(200);
+
+/// How long pipeline waits for pipewire before reporting error.
+const IO_TIMEOUT: Duration = Duration::from_secs(1);
+
+const SAMPLE_SIZE: usize = mem::size_of::<f32>();
+
 /// Driver implementation for pipewire.
 pub struct PipewireDriver {
     task_tx: pipewire::channel::Sender<PwTask>,
     thread_handle: Mutex<Option<thread::JoinHandle<()>>>,
+    // cleared by pipewire thread on fatal error or exit
+    is_up: Arc<AtomicBool>,
 }
 
 #[async_trait]
@@ -40,9 +66,11 @@ impl Driver for PipewireDriver {
 
         let (open_tx, open_rx) = tokio::sync::oneshot::channel();
         let (task_tx, task_rx) = pipewire::channel::channel();
+        let is_up = Arc::new(AtomicBool::new(true));
 
+        let thread_is_up = Arc::clone(&is_up);
         let thread_handle = thread::spawn(move || {
-            let pw_loop = match PwLoop::open_and_connect() {
+            let pw_loop = match PwLoop::open_and_connect(&thread_is_up) {
                 Ok(pw_loop) => {
                     tracing::trace!("sending open ok");
                     open_tx.send(Ok(())).unwrap();
@@ -56,6 +84,7 @@ impl Driver for PipewireDriver {
             };
 
             pw_loop.run(task_rx);
+            thread_is_up.store(false, Ordering::SeqCst);
         });
 
         tracing::trace!("waiting open result");
@@ -65,6 +94,7 @@ impl Driver for PipewireDriver {
         Ok(Arc::new(PipewireDriver {
             task_tx,
             thread_handle: Mutex::new(Some(thread_handle)),
+            is_up,
         }))
     }
 
@@ -88,12 +118,75 @@ impl Driver for PipewireDriver {
     fn id(&self) -> DriverId {
         DriverId::Pipewire
     }
+
+    /// Driver is up until pipewire thread gets fatal core error or exits.
+    fn is_up(&self) -> bool {
+        self.is_up.load(Ordering::SeqCst)
+    }
+
+    /// Open capture stream connected to endpoint.
+    async fn open_source(
+        &self, endpoint_uri: &Uri, format: AudioFormat,
+    ) -> DriverResult<Box<dyn AudioSource>> {
+        let handle = self.open_stream(PwDirection::Capture, endpoint_uri, format).await?;
+
+        Ok(Box::new(PwSource { handle }))
+    }
+
+    /// Open playback stream connected to endpoint.
+    async fn open_sink(
+        &self, endpoint_uri: &Uri, format: AudioFormat,
+    ) -> DriverResult<Box<dyn AudioSink>> {
+        let handle = self.open_stream(PwDirection::Playback, endpoint_uri, format).await?;
+
+        Ok(Box::new(PwSink { handle }))
+    }
 }
 
 impl PipewireDriver {
+    /// Ask pipewire thread to create stream and return handle which
+    /// exchanges samples with it. Stream is closed when handle is dropped.
+    /// Device endpoints are not mapped to pipewire nodes yet, so only
+    /// default device endpoint is supported.
+    async fn open_stream(
+        &self, direction: PwDirection, endpoint_uri: &Uri, format: AudioFormat,
+    ) -> DriverResult<PwStreamHandle> {
+        if endpoint_uri.endpoint_uid().map(|uid| uid.to_string()).as_deref()
+            != Some(DEFAULT_DEVICE_UID)
+        {
+            return Err(DriverError::EndpointError(
+                endpoint_uri.clone(),
+                "only default device endpoint is supported".into(),
+            ));
+        }
+
+        tracing::debug!("opening pipewire {direction:?} stream for {endpoint_uri} ({format})");
+
+        let ring = Arc::new(PwRing::new(format.samples_per_duration(RING_DURATION)));
+
+        let resp = self
+            .round_trip(PwReq::OpenStream(PwStreamParams {
+                direction,
+                endpoint_uri: endpoint_uri.clone(),
+                format,
+                ring: Arc::clone(&ring),
+            }))
+            .await?;
+
+        match resp {
+            PwResp::Stream(result) => Ok(PwStreamHandle {
+                stream_id: result?,
+                format,
+                ring,
+                task_tx: self.task_tx.clone(),
+            }),
+            _ => Err(DriverError::ConnectionError),
+        }
+    }
+
     /// Send PwReq to pipewire thread and wait PwResp.
     /// PwReq + PwResp are packed into PwTask.
-    async fn round_trip(self: &Arc<Self>, req: PwReq) -> DriverResult<PwResp> {
+    async fn round_trip(&self, req: PwReq) -> DriverResult<PwResp> {
         let (resp_tx, resp_rx) = tokio::sync::oneshot::channel();
 
         tracing::trace!("scheduling task");
@@ -116,11 +209,204 @@ struct PwTask {
 #[derive(Debug)]
 enum PwReq {
     Close,
+    OpenStream(PwStreamParams),
+    CloseStream(u32),
 }
 
 #[derive(Debug)]
 enum PwResp {
     None,
+    Stream(DriverResult<u32>),
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+enum PwDirection {
+    Capture,
+    Playback,
+}
+
+#[derive(Debug)]
+struct PwStreamParams {
+    direction: PwDirection,
+    endpoint_uri: Uri,
+    format: AudioFormat,
+    ring: Arc<PwRing>,
+}
+
+/// Samples passed between pipewire thread and pipeline thread.
+/// Capture stream pushes samples and PwSource pops them;
+/// PwSink pushes samples and playback stream pops them.
+struct PwRing {
+    state: SyncMutex<PwRingState>,
+    cond: Condvar,
+    capacity: usize,
+}
+
+#[derive(Default)]
+struct PwRingState {
+    samples: VecDeque<f32>,
+    // set when pipewire stream fails
+    error: Option<String>,
+}
+
+impl PwRing {
+    fn new(capacity: usize) -> Self {
+        PwRing {
+            state: SyncMutex::new(PwRingState::default()),
+            cond: Condvar::new(),
+            capacity,
+        }
+    }
+
+    /// Called on pipewire thread by capture stream.
+    /// If pipeline doesn't keep up, oldest samples are dropped.
+    fn push_bytes(&self, bytes: &[u8]) {
+        let mut state = self.state.lock().unwrap();
+
+        for chunk in bytes.chunks_exact(SAMPLE_SIZE) {
+            if state.samples.len() == self.capacity {
+                state.samples.pop_front();
+            }
+            state.samples.push_back(f32::from_le_bytes(chunk.try_into().unwrap()));
+        }
+
+        self.cond.notify_all();
+    }
+
+    /// Called on pipewire thread by playback stream.
+    /// If pipeline doesn't keep up, missing samples are zeroed.
+    fn pop_bytes(&self, bytes: &mut [u8]) {
+        let mut state = self.state.lock().unwrap();
+
+        for chunk in bytes.chunks_exact_mut(SAMPLE_SIZE) {
+            let sample = state.samples.pop_front().unwrap_or(0.0);
+            chunk.copy_from_slice(&sample.to_le_bytes());
+        }
+
+        self.cond.notify_all();
+    }
+
+    /// Called on pipewire thread when stream fails.
+    fn fail(&self, error: String) {
+        self.state.lock().unwrap().error = Some(error);
+        self.cond.notify_all();
+    }
+
+    /// Called on pipeline thread by PwSource.
+    /// Blocks until capture stream produces enough samples.
+    fn read(&self, samples: &mut [f32]) -> Result<(), AudioError> {
+        let (mut state, wait_result) = self
+            .cond
+            .wait_timeout_while(self.state.lock().unwrap(), IO_TIMEOUT, |state| {
+                state.error.is_none() && state.samples.len() < samples.len()
+            })
+            .unwrap();
+
+        if let Some(error) = &state.error {
+            return Err(AudioError::BackendError(error.clone()));
+        }
+        if wait_result.timed_out() {
+            return Err(AudioError::BackendError("pipewire capture timed out".into()));
+        }
+
+        for (sample, value) in samples.iter_mut().zip(state.samples.drain(..samples.len())) {
+            *sample = value;
+        }
+
+        Ok(())
+    }
+
+    /// Called on pipeline thread by PwSink.
+    /// Blocks until playback stream consumes enough samples to fit new ones.
+    fn write(&self, samples: &[f32]) -> Result<(), AudioError> {
+        let (mut state, wait_result) = self
+            .cond
+            .wait_timeout_while(self.state.lock().unwrap(), IO_TIMEOUT, |state| {
+                state.error.is_none()
+                    && !state.samples.is_empty()
+                    && state.samples.len() + samples.len() > self.capacity
+            })
+            .unwrap();
+
+        if let Some(error) = &state.error {
+            return Err(AudioError::BackendError(error.clone()));
+        }
+        if wait_result.timed_out() {
+            return Err(AudioError::BackendError("pipewire playback timed out".into()));
+        }
+
+        state.samples.extend(samples);
+
+        Ok(())
+    }
+}
+
+impl fmt::Debug for PwRing {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PwRing").field("capacity", &self.capacity).finish()
+    }
+}
+
+/// Pipeline side of pipewire stream.
+/// Asks pipewire thread to close stream when dropped.
+struct PwStreamHandle {
+    stream_id: u32,
+    format: AudioFormat,
+    ring: Arc<PwRing>,
+    task_tx: pipewire::channel::Sender<PwTask>,
+}
+
+impl Drop for PwStreamHandle {
+    fn drop(&mut self) {
+        // response is not needed, pipewire thread drops it
+        let (resp_tx, _resp_rx) = tokio::sync::oneshot::channel();
+        _ = self.task_tx.send(PwTask { req: PwReq::CloseStream(self.stream_id), resp_tx });
+    }
+}
+
+/// Source capturing audio from pipewire stream.
+struct PwSource {
+    handle: PwStreamHandle,
+}
+
+impl AudioSource for PwSource {
+    fn format(&self) -> AudioFormat {
+        self.handle.format
+    }
+
+    fn is_clocked(&self) -> bool {
+        true
+    }
+
+    fn read(&mut self, samples: &mut [f32]) -> Result<(), AudioError> {
+        self.handle.ring.read(samples)
+    }
+}
+
+/// Sink playing audio to pipewire stream.
+struct PwSink {
+    handle: PwStreamHandle,
+}
+
+impl AudioSink for PwSink {
+    fn format(&self) -> AudioFormat {
+        self.handle.format
+    }
+
+    fn is_clocked(&self) -> bool {
+        true
+    }
+
+    fn write(&mut self, samples: &[f32]) -> Result<(), AudioError> {
+        self.handle.ring.write(samples)
+    }
+}
+
+/// Pipewire thread side of stream.
+/// Fields are dropped in order: listener, then stream.
+struct PwStream {
+    _listener: StreamListener<()>,
+    _stream: StreamRc,
 }
 
 struct PwDev {
@@ -140,6 +426,8 @@ struct PwLoop {
     mainloop: MainLoopRc,
     context: ContextRc,
     conn: RefCell<Option<PwConn>>,
+    next_stream_id: Cell<u32>,
+    is_up: Arc<AtomicBool>,
 }
 
 struct PwConn {
@@ -148,10 +436,11 @@ struct PwConn {
     registry: RegistryRc,
     registry_listener: pipewire::registry::Listener,
     devices: HashMap<u32, PwDev>,
+    streams: HashMap<u32, PwStream>,
 }
 
 impl PwLoop {
-    fn open_and_connect() -> DriverResult<Rc<PwLoop>> {
+    fn open_and_connect(is_up: &Arc<AtomicBool>) -> DriverResult<Rc<PwLoop>> {
         tracing::debug!("creating mainloop");
 
         let mainloop = MainLoopRc::new(None).map_err(|err| {
@@ -162,7 +451,13 @@ impl PwLoop {
             DriverError::OpenError(format!("can't open pipewire context: {err}"))
         })?;
 
-        let pw_loop = Rc::new(PwLoop { mainloop, context, conn: RefCell::new(None) });
+        let pw_loop = Rc::new(PwLoop {
+            mainloop,
+            context,
+            conn: RefCell::new(None),
+            next_stream_id: Cell::new(1),
+            is_up: Arc::clone(is_up),
+        });
         pw_loop.reconnect()?;
 
         Ok(pw_loop)
@@ -216,6 +511,7 @@ impl PwLoop {
             registry,
             registry_listener,
             devices: HashMap::new(),
+            streams: HashMap::new(),
         });
         Ok(())
     }
@@ -257,6 +553,11 @@ impl PwLoop {
 
         tracing::warn!("got error from pipewire: {}", msg);
 
+        // error on core object means that connection is broken
+        if id == PW_ID_CORE {
+            self.is_up.store(false, Ordering::SeqCst);
+        }
+
         // TODO: reconnect, emit event (if id == 0)
     }
 
@@ -364,7 +665,8 @@ impl PwLoop {
         let resp = self.request(&task.req);
 
         tracing::trace!("task response: {:?}", resp);
-        task.resp_tx.send(resp).unwrap();
+        // receiver is dropped if nobody waits for response
+        _ = task.resp_tx.send(resp);
     }
 
     fn request(self: &Rc<Self>, req: &PwReq) -> PwResp {
@@ -373,8 +675,162 @@ impl PwLoop {
                 self.mainloop.quit();
                 PwResp::None
             },
+            PwReq::OpenStream(params) => PwResp::Stream(self.open_stream(params)),
+            PwReq::CloseStream(stream_id) => {
+                self.close_stream(*stream_id);
+                PwResp::None
+            },
         }
     }
+
+    /// Create stream and connect it to default device endpoint.
+    /// Stream is auto-connected by session manager to default node.
+    fn open_stream(self: &Rc<Self>, params: &PwStreamParams) -> DriverResult<u32> {
+        let endpoint_error = |err: pipewire::Error| {
+            DriverError::EndpointError(params.endpoint_uri.clone(), err.to_string())
+        };
+
+        let mut conn_ref = self.conn.borrow_mut();
+        let Some(conn) = conn_ref.as_mut() else {
+            return Err(DriverError::ConnectionError);
+        };
+
+        let stream = StreamRc::new(
+            conn.core.clone(),
+            "rocd",
+            properties! {
+                *keys::MEDIA_TYPE => "Audio",
+                *keys::MEDIA_CATEGORY => match params.direction {
+                    PwDirection::Capture => "Capture",
+                    PwDirection::Playback => "Playback",
+                },
+                *keys::MEDIA_ROLE => "Music",
+                *keys::NODE_DESCRIPTION => params.endpoint_uri.to_string(),
+            },
+        )
+        .map_err(endpoint_error)?;
+
+        let listener = stream
+            .add_local_listener::<()>()
+            .state_changed({
+                let ring = Arc::clone(&params.ring);
+                let endpoint_uri = params.endpoint_uri.clone();
+                move |_stream, _, _old, new| {
+                    if let PwStreamState::Error(error) = new {
+                        tracing::warn!("pipewire stream for {endpoint_uri} failed: {error}");
+                        ring.fail(error);
+                    }
+                }
+            })
+            .process({
+                let ring = Arc::clone(&params.ring);
+                let direction = params.direction;
+                let stride = SAMPLE_SIZE * params.format.channels as usize;
+                move |stream, _| on_stream_process(stream, &ring, direction, stride)
+            })
+            .register()
+            .map_err(endpoint_error)?;
+
+        let format_param = make_format_param(params.format);
+        let mut stream_params = [Pod::from_bytes(&format_param).unwrap()];
+
+        stream
+            .connect(
+                match params.direction {
+                    PwDirection::Capture => Direction::Input,
+                    PwDirection::Playback => Direction::Output,
+                },
+                None,
+                StreamFlags::AUTOCONNECT | StreamFlags::MAP_BUFFERS,
+                &mut stream_params,
+            )
+            .map_err(endpoint_error)?;
+
+        let stream_id = self.next_stream_id.get();
+        self.next_stream_id.set(stream_id.wrapping_add(1));
+
+        conn.streams.insert(stream_id, PwStream { _listener: listener, _stream: stream });
+
+        tracing::debug!("opened pipewire stream {stream_id} for {}", params.endpoint_uri);
+
+        Ok(stream_id)
+    }
+
+    /// Destroy stream, if it's still present.
+    fn close_stream(self: &Rc<Self>, stream_id: u32) {
+        if let Some(conn) = self.conn.borrow_mut().as_mut()
+            && conn.streams.remove(&stream_id).is_some()
+        {
+            tracing::debug!("closed pipewire stream {stream_id}");
+        }
+    }
+}
+
+/// Called by stream listener when stream needs or provides a buffer.
+/// Audio is always F32LE interleaved, as requested in format param;
+/// pipewire converts rate and channels if device differs.
+fn on_stream_process(stream: &Stream, ring: &PwRing, direction: PwDirection, stride: usize) {
+    let Some(mut buffer) = stream.dequeue_buffer() else {
+        return;
+    };
+    let datas = buffer.datas_mut();
+    if datas.is_empty() {
+        return;
+    }
+    let data = &mut datas[0];
+
+    match direction {
+        PwDirection::Capture => {
+            let size = data.chunk().size() as usize;
+            if let Some(bytes) = data.data() {
+                ring.push_bytes(&bytes[..size.min(bytes.len())]);
+            }
+        },
+        PwDirection::Playback => {
+            let n_frames = match data.data() {
+                Some(bytes) => {
+                    let n_frames = bytes.len() / stride;
+                    ring.pop_bytes(&mut bytes[..n_frames * stride]);
+                    n_frames
+                },
+                None => 0,
+            };
+
+            let chunk = data.chunk_mut();
+            *chunk.offset_mut() = 0;
+            *chunk.stride_mut() = stride as _;
+            *chunk.size_mut() = (n_frames * stride) as _;
+        },
+    }
+}
+
+/// Build EnumFormat param requesting F32LE with given rate and channels.
+fn make_format_param(format: AudioFormat) -> Vec<u8> {
+    let mut audio_info = AudioInfoRaw::new();
+    audio_info.set_format(SpaAudioFormat::F32LE);
+    audio_info.set_rate(format.sample_rate);
+    audio_info.set_channels(format.channels as u32);
+
+    // more than two channels are left unpositioned
+    let mut position = [0; MAX_CHANNELS];
+    match format.channels {
+        1 => position[0] = SPA_AUDIO_CHANNEL_MONO,
+        2 => position[..2].copy_from_slice(&[SPA_AUDIO_CHANNEL_FL, SPA_AUDIO_CHANNEL_FR]),
+        _ => (),
+    }
+    audio_info.set_position(position);
+
+    PodSerializer::serialize(
+        Cursor::new(Vec::new()),
+        &Value::Object(Object {
+            type_: SpaTypes::ObjectParamFormat.as_raw(),
+            id: ParamType::EnumFormat.as_raw(),
+            properties: audio_info.into(),
+        }),
+    )
+    .unwrap()
+    .0
+    .into_inner()
 }
 
 fn from_pw_string(mut s: String) -> String {
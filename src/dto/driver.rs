@@ -25,6 +25,10 @@ pub enum DriverId {
     #[value(skip)]
     Unspecified,
 
+    // Built-in virtual endpoints, not a real driver.
+    #[value(skip)]
+    Virtual,
+
     // Drivers are probed in the order defined here. When multiple drivers are supported,
     // and the user didn't select a driver explicitly, the first working one is used.
     Pipewire,
@@ -4,6 +4,7 @@ use crate::dto::driver::*;
 use crate::dto::uid::*;
 use crate::dto::uri::*;
 use crate::dto::validate::*;
+use crate::dto::virtual_source_spec::*;
 
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
@@ -22,6 +23,16 @@ pub struct EndpointSpec {
 
     pub display_name: String,
     pub system_name: String,
+
+    // signal parameters, present only for virtual_source endpoints
+    #[serde(default)]
+    #[schema(nullable = false)]
+    pub virtual_source: Option<VirtualSourceSpec>,
+
+    // incremented on every change, maintained by server
+    #[serde(default)]
+    #[schema(read_only)]
+    pub revision: u64,
 }
 
 impl Validate for EndpointSpec {
@@ -36,6 +47,34 @@ impl Validate for EndpointSpec {
             ));
         }
 
+        match &self.virtual_source {
+            Some(virtual_source) => {
+                if self.endpoint_type != EndpointType::VirtualSource {
+                    return Err(ValidationError::LayoutError(
+                        "virtual_source is allowed only for virtual_source endpoints".into(),
+                    ));
+                }
+                if self.stream_direction != EndpointDir::Input {
+                    return Err(ValidationError::LayoutError(
+                        "virtual_source endpoint must have input direction".into(),
+                    ));
+                }
+                if self.driver != DriverId::Virtual {
+                    return Err(ValidationError::LayoutError(
+                        "virtual_source endpoint must have virtual driver".into(),
+                    ));
+                }
+                virtual_source.validate()?;
+            },
+            None => {
+                if self.endpoint_type == EndpointType::VirtualSource {
+                    return Err(ValidationError::LayoutError(
+                        "virtual_source is required for virtual_source endpoints".into(),
+                    ));
+                }
+            },
+        }
+
         Ok(())
     }
 }
@@ -46,6 +85,8 @@ impl Validate for EndpointSpec {
 pub enum EndpointType {
     SystemDevice,
     StreamingDevice,
+    // built-in signal generator, see VirtualSourceSpec
+    VirtualSource,
 }
 
 #[derive(Copy, Clone, PartialEq, Debug, strum::Display, Serialize, Deserialize, ToSchema)]
@@ -75,10 +116,30 @@ mod tests {
             driver: DriverId::Pipewire,
             display_name: "test".into(),
             system_name: "test".into(),
+            virtual_source: None,
+            revision: 0,
         };
 
         assert_ok!(good_spec.validate());
 
+        let good_virtual_spec = EndpointSpec {
+            endpoint_type: EndpointType::VirtualSource,
+            stream_direction: EndpointDir::Input,
+            driver: DriverId::Virtual,
+            virtual_source: Some(VirtualSourceSpec {
+                signal: SignalType::Sine,
+                frequency: Some(440.0),
+                end_frequency: None,
+                sweep_duration: None,
+                level: None,
+                channel: None,
+                file_path: None,
+            }),
+            ..good_spec.clone()
+        };
+
+        assert_ok!(good_virtual_spec.validate());
+
         let bad_specs = vec![
             // invalid endpoint_uri type
             {
@@ -92,6 +153,30 @@ mod tests {
                 spec.endpoint_uid = Uid::generate_random();
                 spec
             },
+            // virtual_source for device endpoint
+            {
+                let mut spec = good_spec.clone();
+                spec.virtual_source = good_virtual_spec.virtual_source.clone();
+                spec
+            },
+            // virtual_source endpoint without virtual_source
+            {
+                let mut spec = good_virtual_spec.clone();
+                spec.virtual_source = None;
+                spec
+            },
+            // virtual_source endpoint with output direction
+            {
+                let mut spec = good_virtual_spec.clone();
+                spec.stream_direction = EndpointDir::Output;
+                spec
+            },
+            // invalid virtual_source
+            {
+                let mut spec = good_virtual_spec.clone();
+                spec.virtual_source.as_mut().unwrap().level = Some(10.0);
+                spec
+            },
         ];
 
         for spec in &bad_specs {
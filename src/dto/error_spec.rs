@@ -13,4 +13,11 @@ pub struct ErrorSpec {
 #[serde(rename_all = "snake_case")]
 pub enum ErrorCode {
     InvalidArgs,
+    NotFound,
+    AlreadyExists,
+    Conflict,
+    PreconditionFailed,
+    Unauthorized,
+    Forbidden,
+    InternalError,
 }
@@ -0,0 +1,102 @@
+// Copyright (c) Roc Streaming authors
+// Licensed under MPL-2.0
+use crate::dto::levels_spec::*;
+use crate::dto::uri::*;
+
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use utoipa::ToSchema;
+
+/// Notification about change on server, delivered via GET /events.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize, ToSchema)]
+pub struct EventSpec {
+    pub event_type: EventType,
+
+    // object which event is about, e.g. stream
+    #[schema(value_type = String)]
+    pub object_uri: Uri,
+
+    // RFC 3339 timestamp
+    #[serde(with = "time::serde::rfc3339")]
+    #[schema(value_type = String)]
+    pub time: OffsetDateTime,
+
+    // measured levels, present in levels_updated events
+    #[serde(default)]
+    #[schema(nullable = false)]
+    pub levels: Option<LevelsSpec>,
+
+    // switch details, present in stream_source_switched events
+    #[serde(default)]
+    #[schema(nullable = false)]
+    pub source_switch: Option<SourceSwitchSpec>,
+}
+
+impl EventSpec {
+    /// Create event that happened now.
+    pub fn new(event_type: EventType, object_uri: &Uri) -> Self {
+        EventSpec {
+            event_type,
+            object_uri: object_uri.clone(),
+            time: OffsetDateTime::now_utc(),
+            levels: None,
+            source_switch: None,
+        }
+    }
+
+    /// Create levels_updated event.
+    pub fn from_levels(levels: LevelsSpec) -> Self {
+        EventSpec {
+            event_type: EventType::LevelsUpdated,
+            object_uri: levels.object_uri.clone(),
+            time: levels.time,
+            levels: Some(levels),
+            source_switch: None,
+        }
+    }
+
+    /// Create stream_source_switched event that happened now.
+    pub fn from_source_switch(stream_uri: &Uri, source_switch: SourceSwitchSpec) -> Self {
+        EventSpec {
+            source_switch: Some(source_switch),
+            ..EventSpec::new(EventType::StreamSourceSwitched, stream_uri)
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Debug, strum::Display, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum EventType {
+    // sending stream paused because signal became silent
+    StreamPaused,
+    // paused stream resumed because signal returned
+    StreamResumed,
+    // new levels of stream or endpoint were measured;
+    // delivered only to subscribers that requested levels
+    LevelsUpdated,
+    // stream with failover switched to another source
+    StreamSourceSwitched,
+}
+
+/// Change of active source of stream with failover.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize, ToSchema)]
+pub struct SourceSwitchSpec {
+    // sources are numbered in order of preference:
+    // 0 is stream source, N is N-th failover source
+    pub previous_source: u32,
+    pub active_source: u32,
+    pub reason: SwitchReason,
+}
+
+#[derive(Copy, Clone, PartialEq, Debug, strum::Display, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum SwitchReason {
+    // previous source returned error
+    Failed,
+    // previous source was silent for silence timeout
+    Silent,
+    // preferred source was healthy for recovery time
+    Recovered,
+}
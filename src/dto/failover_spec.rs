@@ -0,0 +1,178 @@
+// Copyright (c) Roc Streaming authors
+// Licensed under MPL-2.0
+use crate::dto::stream_spec::*;
+use crate::dto::validate::*;
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Fallback sources of stream.
+///
+/// Primary source and all fallback sources are opened together. Stream
+/// plays first source that doesn't fail and isn't silent, and switches
+/// back to preferred source when it stays healthy for recovery time.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize, ToSchema)]
+pub struct FailoverSpec {
+    // fallback sources, in order of preference;
+    // each can be endpoint (e.g. virtual tone or file source) or external
+    pub sources: Vec<ConnectionSpec>,
+
+    // peak level below which source is considered silent, in dBFS;
+    // DEFAULT_SILENCE_THRESHOLD if omitted
+    #[serde(default)]
+    #[schema(nullable = false)]
+    pub silence_threshold: Option<f32>,
+
+    // how long active source should be silent before switching, in seconds;
+    // DEFAULT_SILENCE_TIMEOUT if omitted
+    #[serde(default)]
+    #[schema(nullable = false)]
+    pub silence_timeout: Option<f32>,
+
+    // how long preferred source should be healthy before switching back
+    // to it, in seconds; DEFAULT_RECOVERY_TIME if omitted
+    #[serde(default)]
+    #[schema(nullable = false)]
+    pub recovery_time: Option<f32>,
+}
+
+impl FailoverSpec {
+    pub const DEFAULT_SILENCE_THRESHOLD: f32 = -60.0;
+    pub const DEFAULT_SILENCE_TIMEOUT: f32 = 3.0;
+    pub const DEFAULT_RECOVERY_TIME: f32 = 5.0;
+
+    pub const MAX_SOURCES: usize = 8;
+    pub const SILENCE_THRESHOLD_RANGE: (f32, f32) = (-120.0, 0.0);
+    pub const SILENCE_TIMEOUT_RANGE: (f32, f32) = (0.01, 3600.0);
+    pub const RECOVERY_TIME_RANGE: (f32, f32) = (0.0, 3600.0);
+
+    pub fn silence_threshold(&self) -> f32 {
+        self.silence_threshold.unwrap_or(Self::DEFAULT_SILENCE_THRESHOLD)
+    }
+
+    pub fn silence_timeout(&self) -> f32 {
+        self.silence_timeout.unwrap_or(Self::DEFAULT_SILENCE_TIMEOUT)
+    }
+
+    pub fn recovery_time(&self) -> f32 {
+        self.recovery_time.unwrap_or(Self::DEFAULT_RECOVERY_TIME)
+    }
+}
+
+impl Validate for FailoverSpec {
+    fn validate(&self) -> ValidationResult {
+        if !(1..=Self::MAX_SOURCES).contains(&self.sources.len()) {
+            return Err(ValidationError::LayoutError(format!(
+                "failover sources must contain from 1 to {} entries",
+                Self::MAX_SOURCES
+            )));
+        }
+
+        for source in &self.sources {
+            source.validate()?;
+
+            if source.external_uris().iter().any(|uri| uri.port() == Some(0)) {
+                return Err(ValidationError::LayoutError(
+                    "automatic port allocation (port 0) is not allowed in failover sources"
+                        .into(),
+                ));
+            }
+        }
+
+        for (name, value, (min, max)) in [
+            ("silence_threshold", self.silence_threshold, Self::SILENCE_THRESHOLD_RANGE),
+            ("silence_timeout", self.silence_timeout, Self::SILENCE_TIMEOUT_RANGE),
+            ("recovery_time", self.recovery_time, Self::RECOVERY_TIME_RANGE),
+        ] {
+            if let Some(value) = value
+                && !(min..=max).contains(&value)
+            {
+                return Err(ValidationError::LayoutError(format!(
+                    "{name} must be in range [{min}; {max}]"
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dto::uid::*;
+    use crate::dto::uri::*;
+    use assertables::*;
+
+    #[test]
+    fn test_validate() {
+        let endpoint_source = ConnectionSpec::Endpoint {
+            connection_type: ConnectionType::Endpoint,
+            endpoint_uri: Uri::from_endpoint(&Uid::generate_random(), &Uid::generate_random()),
+        };
+        let external_source = ConnectionSpec::External {
+            connection_type: ConnectionType::External,
+            media_uri: Uri::parse("rtp://0.0.0.0:10000").unwrap(),
+            repair_uri: None,
+            control_uri: Uri::parse("rtcp://0.0.0.0:10001").unwrap(),
+            multicast_interface: None,
+            multicast_ttl: None,
+        };
+
+        let good_spec = FailoverSpec {
+            sources: vec![endpoint_source.clone(), external_source.clone()],
+            silence_threshold: None,
+            silence_timeout: None,
+            recovery_time: None,
+        };
+
+        let good_specs = vec![
+            good_spec.clone(),
+            FailoverSpec {
+                silence_threshold: Some(-40.0),
+                silence_timeout: Some(0.5),
+                recovery_time: Some(0.0),
+                ..good_spec.clone()
+            },
+        ];
+
+        for spec in &good_specs {
+            assert_ok!(spec.validate(), "{spec:?}");
+        }
+
+        let bad_specs = vec![
+            // no sources
+            FailoverSpec { sources: vec![], ..good_spec.clone() },
+            // too many sources
+            FailoverSpec { sources: vec![endpoint_source.clone(); 9], ..good_spec.clone() },
+            // invalid source
+            FailoverSpec {
+                sources: vec![ConnectionSpec::Endpoint {
+                    connection_type: ConnectionType::External,
+                    endpoint_uri: Uri::from_peer(&Uid::generate_random()),
+                }],
+                ..good_spec.clone()
+            },
+            // wildcard port
+            FailoverSpec {
+                sources: vec![ConnectionSpec::External {
+                    connection_type: ConnectionType::External,
+                    media_uri: Uri::parse("rtp://0.0.0.0:0").unwrap(),
+                    repair_uri: None,
+                    control_uri: Uri::parse("rtcp://0.0.0.0:0").unwrap(),
+                    multicast_interface: None,
+                    multicast_ttl: None,
+                }],
+                ..good_spec.clone()
+            },
+            // invalid settings
+            FailoverSpec { silence_threshold: Some(3.0), ..good_spec.clone() },
+            FailoverSpec { silence_timeout: Some(0.0), ..good_spec.clone() },
+            FailoverSpec { recovery_time: Some(-1.0), ..good_spec.clone() },
+        ];
+
+        for spec in &bad_specs {
+            assert_matches!(spec.validate(), Err(ValidationError::LayoutError(_)), "{spec:?}");
+        }
+    }
+}
@@ -0,0 +1,167 @@
+// Copyright (c) Roc Streaming authors
+// Licensed under MPL-2.0
+use crate::dto::uid::*;
+use crate::dto::uri::*;
+use crate::dto::validate::*;
+
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use utoipa::ToSchema;
+
+/// Group of receiving streams which latencies are equalised,
+/// e.g. speakers in different rooms playing same source.
+///
+/// Each member reports end-to-end latency estimated by its transport.
+/// Members that have lower latency are delayed, so that all members
+/// play with same latency: target_latency, or latency of slowest member
+/// if it's higher.
+///
+/// Members don't share a clock reference: clock drift between receivers
+/// is not corrected, only compensated indirectly when it shows up in
+/// estimated latency. Alignment is therefore as good as latency
+/// estimates of transports, and can't be better than their jitter.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize, ToSchema)]
+pub struct GroupSpec {
+    #[schema(value_type = String)]
+    pub group_uri: Uri,
+
+    #[schema(value_type = String)]
+    pub group_uid: Uid,
+
+    // streams in group; must be receiving streams (with external source),
+    // and stream can be member of only one group
+    #[schema(value_type = Vec<String>)]
+    pub members: Vec<Uri>,
+
+    // latency which all members should have, in seconds;
+    // if omitted, members are aligned to slowest one
+    #[serde(default)]
+    #[schema(nullable = false)]
+    pub target_latency: Option<f32>,
+}
+
+impl GroupSpec {
+    pub const TARGET_LATENCY_RANGE: (f32, f32) = (0.0, 10.0);
+}
+
+impl Validate for GroupSpec {
+    fn validate(&self) -> ValidationResult {
+        if self.group_uri.kind() != UriKind::Group {
+            return Err(ValidationError::LayoutError("unexpected group_uri format".into()));
+        }
+
+        if self.group_uri.group_uid().unwrap() != self.group_uid {
+            return Err(ValidationError::LayoutError(
+                "UID mismatch in group_uri and group_uid fields".into(),
+            ));
+        }
+
+        for (n, member) in self.members.iter().enumerate() {
+            if member.kind() != UriKind::Stream {
+                return Err(ValidationError::LayoutError(
+                    "unexpected members format, must be stream URIs".into(),
+                ));
+            }
+            if self.members[..n].contains(member) {
+                return Err(ValidationError::LayoutError(format!(
+                    "duplicate group member '{member}'"
+                )));
+            }
+        }
+
+        let (min, max) = Self::TARGET_LATENCY_RANGE;
+        if let Some(target_latency) = self.target_latency
+            && !(min..=max).contains(&target_latency)
+        {
+            return Err(ValidationError::LayoutError(format!(
+                "target_latency must be in range [{min}; {max}]"
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+/// Current alignment of group members (GET /groups/{uid}/status).
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize, ToSchema)]
+pub struct GroupStatusSpec {
+    #[schema(value_type = String)]
+    pub group_uri: Uri,
+
+    // RFC 3339 timestamp
+    #[serde(with = "time::serde::rfc3339")]
+    #[schema(value_type = String)]
+    pub time: OffsetDateTime,
+
+    // status of each member, in same order as in group
+    pub members: Vec<GroupMemberStatusSpec>,
+}
+
+/// Alignment of one group member.
+/// Latencies are absent if member isn't running or its latency is unknown yet.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize, ToSchema)]
+pub struct GroupMemberStatusSpec {
+    #[schema(value_type = String)]
+    pub stream_uri: Uri,
+
+    // measured playout latency, including alignment delay, in seconds
+    #[serde(default)]
+    #[schema(nullable = false)]
+    pub latency: Option<f32>,
+
+    // delay added by rocd to align member with others, in seconds
+    #[serde(default)]
+    #[schema(nullable = false)]
+    pub delay: Option<f32>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assertables::*;
+
+    fn make_group(members: &[&str], target_latency: Option<f32>) -> GroupSpec {
+        GroupSpec {
+            group_uri: Uri::parse("/groups/111111-222222-333333").unwrap(),
+            group_uid: Uid::parse("111111-222222-333333").unwrap(),
+            members: members.iter().map(|member| Uri::parse(member).unwrap()).collect(),
+            target_latency,
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        let good_specs = vec![
+            make_group(&[], None),
+            make_group(
+                &["/streams/aaaaaa-bbbbbb-cccccc", "/streams/dddddd-eeeeee-ffffff"],
+                Some(0.2),
+            ),
+        ];
+
+        for spec in &good_specs {
+            assert_ok!(spec.validate(), "{spec:?}");
+        }
+
+        let bad_specs = vec![
+            // not a stream
+            make_group(&["/peers/aaaaaa-bbbbbb-cccccc"], None),
+            // duplicate member
+            make_group(
+                &["/streams/aaaaaa-bbbbbb-cccccc", "/streams/aaaaaa-bbbbbb-cccccc"],
+                None,
+            ),
+            // bad latency
+            make_group(&[], Some(-1.0)),
+            // uid mismatch
+            GroupSpec {
+                group_uid: Uid::parse("444444-555555-666666").unwrap(),
+                ..make_group(&[], None)
+            },
+        ];
+
+        for spec in &bad_specs {
+            assert_matches!(spec.validate(), Err(ValidationError::LayoutError(_)), "{spec:?}");
+        }
+    }
+}
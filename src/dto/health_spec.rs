@@ -0,0 +1,34 @@
+// Copyright (c) Roc Streaming authors
+// Licensed under MPL-2.0
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Liveness of server.
+///
+/// Server is alive while its background tasks are running.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize, ToSchema)]
+pub struct HealthSpec {
+    pub alive: bool,
+}
+
+/// Readiness of server to handle requests.
+///
+/// Audio driver is opened before server starts accepting requests.
+/// After that, server becomes ready when streams are loaded from
+/// persistent storage, and their audio pipelines are started.
+/// Server stops being ready if audio driver goes down.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize, ToSchema)]
+pub struct ReadinessSpec {
+    // true if all of the below are true
+    pub ready: bool,
+
+    // audio driver is operational
+    pub driver_up: bool,
+
+    // streams were loaded from persistent storage
+    pub vault_loaded: bool,
+
+    // audio pipelines were brought in line with loaded streams
+    // while audio driver was up
+    pub streams_reconciled: bool,
+}
@@ -0,0 +1,43 @@
+// Copyright (c) Roc Streaming authors
+// Licensed under MPL-2.0
+use crate::dto::uri::*;
+
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use utoipa::ToSchema;
+
+/// Result of latency measurement (POST /streams/{uid}/measure-latency).
+///
+/// Measured latency is the time between sending a marker signal from
+/// stream destination and detecting it at detector stream source.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize, ToSchema)]
+pub struct LatencyReportSpec {
+    // stream which sent marker
+    #[schema(value_type = String)]
+    pub stream_uri: Uri,
+
+    // stream which received marker; same as stream_uri
+    // if marker was played and recorded by same stream
+    #[schema(value_type = String)]
+    pub detector_stream_uri: Uri,
+
+    // measured latency, in seconds
+    pub measured_latency: f32,
+
+    // latency which receiver is configured to maintain, in seconds;
+    // absent if unknown
+    #[serde(default)]
+    #[schema(nullable = false)]
+    pub target_latency: Option<f32>,
+
+    // latency estimated by transport, in seconds;
+    // absent if path doesn't involve transport
+    #[serde(default)]
+    #[schema(nullable = false)]
+    pub estimated_latency: Option<f32>,
+
+    // when measurement finished, RFC 3339 timestamp
+    #[serde(with = "time::serde::rfc3339")]
+    #[schema(value_type = String)]
+    pub time: OffsetDateTime,
+}
@@ -0,0 +1,48 @@
+// Copyright (c) Roc Streaming authors
+// Licensed under MPL-2.0
+use crate::dto::uri::*;
+
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use utoipa::ToSchema;
+
+/// Audio levels of stream or endpoint, measured during one metering interval.
+///
+/// Stream levels are measured at destination, after processing.
+/// Source endpoint levels are measured before processing.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize, ToSchema)]
+pub struct LevelsSpec {
+    // stream or endpoint which levels are measured
+    #[schema(value_type = String)]
+    pub object_uri: Uri,
+
+    // end of metering interval, RFC 3339 timestamp
+    #[serde(with = "time::serde::rfc3339")]
+    #[schema(value_type = String)]
+    pub time: OffsetDateTime,
+
+    // levels of each channel, in channel order
+    pub channels: Vec<ChannelLevelSpec>,
+}
+
+/// Levels of one channel.
+#[derive(Copy, Clone, PartialEq, Debug, Serialize, Deserialize, ToSchema)]
+pub struct ChannelLevelSpec {
+    // maximum absolute sample value, in dBFS
+    pub peak: f32,
+    // root mean square of samples, in dBFS
+    pub rms: f32,
+}
+
+impl ChannelLevelSpec {
+    /// Levels below this are reported as this value, in dBFS.
+    /// Digital silence would be -inf, which can't be represented in JSON.
+    pub const MIN_LEVEL: f32 = -120.0;
+
+    /// Convert linear peak and RMS to dBFS.
+    pub fn from_amplitudes(peak: f32, rms: f32) -> Self {
+        let to_db = |amplitude: f32| (20.0 * amplitude.log10()).max(Self::MIN_LEVEL);
+
+        ChannelLevelSpec { peak: to_db(peak), rms: to_db(rms) }
+    }
+}
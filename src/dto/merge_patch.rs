@@ -0,0 +1,112 @@
+// Copyright (c) Roc Streaming authors
+// Licensed under MPL-2.0
+use crate::dto::validate::*;
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+/// Apply JSON Merge Patch (RFC 7396) to entity.
+///
+/// Entity is converted to JSON, patched, and converted back. Result
+/// isn't validated: caller should validate it before saving.
+pub fn apply_merge_patch<T>(entity: &T, patch: &Value) -> Result<T, ValidationError>
+where
+    T: Serialize + DeserializeOwned,
+{
+    let mut value = serde_json::to_value(entity).map_err(|err| {
+        ValidationError::LayoutError(format!("can't serialize entity: {err}"))
+    })?;
+
+    merge(&mut value, patch);
+
+    serde_json::from_value(value)
+        .map_err(|err| ValidationError::LayoutError(format!("invalid merge patch: {err}")))
+}
+
+/// Merge patch into target, as defined by RFC 7396: objects are merged
+/// recursively, nulls remove members, other values replace target.
+fn merge(target: &mut Value, patch: &Value) {
+    let Value::Object(patch_members) = patch else {
+        *target = patch.clone();
+        return;
+    };
+
+    if !target.is_object() {
+        *target = Value::Object(Default::default());
+    }
+    let target_members = target.as_object_mut().unwrap();
+
+    for (key, patch_value) in patch_members {
+        if patch_value.is_null() {
+            target_members.remove(key);
+        } else {
+            merge(target_members.entry(key).or_insert(Value::Null), patch_value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assertables::*;
+    use serde::Deserialize;
+    use serde_json::json;
+
+    #[test]
+    fn test_merge() {
+        // examples from RFC 7396, appendix A
+        let cases = [
+            (json!({"a": "b"}), json!({"a": "c"}), json!({"a": "c"})),
+            (json!({"a": "b"}), json!({"b": "c"}), json!({"a": "b", "b": "c"})),
+            (json!({"a": "b"}), json!({"a": null}), json!({})),
+            (json!({"a": "b", "b": "c"}), json!({"a": null}), json!({"b": "c"})),
+            (json!({"a": ["b"]}), json!({"a": "c"}), json!({"a": "c"})),
+            (json!({"a": "c"}), json!({"a": ["b"]}), json!({"a": ["b"]})),
+            (
+                json!({"a": {"b": "c"}}),
+                json!({"a": {"b": "d", "c": null}}),
+                json!({"a": {"b": "d"}}),
+            ),
+            (json!({"a": [{"b": "c"}]}), json!({"a": [1]}), json!({"a": [1]})),
+            (json!(["a", "b"]), json!(["c", "d"]), json!(["c", "d"])),
+            (json!({"a": "b"}), json!(["c"]), json!(["c"])),
+            (json!({"a": "foo"}), json!(null), json!(null)),
+            (json!({"a": "foo"}), json!("bar"), json!("bar")),
+            (json!({"e": null}), json!({"a": 1}), json!({"e": null, "a": 1})),
+            (json!([1, 2]), json!({"a": "b", "c": null}), json!({"a": "b"})),
+            (json!({}), json!({"a": {"bb": {"ccc": null}}}), json!({"a": {"bb": {}}})),
+        ];
+
+        for (target, patch, result) in cases {
+            let mut value = target.clone();
+            merge(&mut value, &patch);
+            assert_eq!(value, result, "target: {target}, patch: {patch}");
+        }
+    }
+
+    #[derive(PartialEq, Debug, Serialize, Deserialize)]
+    struct Entity {
+        name: String,
+        #[serde(default)]
+        owner: Option<String>,
+    }
+
+    #[test]
+    fn test_apply() {
+        let entity = Entity { name: "foo".into(), owner: Some("bar".into()) };
+
+        assert_eq!(
+            apply_merge_patch(&entity, &json!({"name": "baz"})).unwrap(),
+            Entity { name: "baz".into(), owner: Some("bar".into()) }
+        );
+        assert_eq!(
+            apply_merge_patch(&entity, &json!({"owner": null})).unwrap(),
+            Entity { name: "foo".into(), owner: None }
+        );
+
+        // required field removed, or has wrong type
+        assert_err!(apply_merge_patch(&entity, &json!({"name": null})));
+        assert_err!(apply_merge_patch(&entity, &json!({"name": 1})));
+    }
+}
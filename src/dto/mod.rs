@@ -3,17 +3,49 @@
 mod driver;
 mod endpoint_spec;
 mod error_spec;
+mod event_spec;
+mod failover_spec;
+mod group_spec;
+mod health_spec;
+mod latency_report_spec;
+mod levels_spec;
+mod merge_patch;
 mod peer_spec;
+mod processing_spec;
+mod recording_spec;
+mod revision;
+mod schedule_spec;
+mod sdp;
+mod silence_detection_spec;
 mod stream_spec;
+mod token_spec;
 mod uid;
 mod uri;
 mod validate;
+mod version_spec;
+mod virtual_source_spec;
 
 pub use self::driver::*;
 pub use self::endpoint_spec::*;
 pub use self::error_spec::*;
+pub use self::event_spec::*;
+pub use self::failover_spec::*;
+pub use self::group_spec::*;
+pub use self::health_spec::*;
+pub use self::latency_report_spec::*;
+pub use self::levels_spec::*;
+pub use self::merge_patch::*;
 pub use self::peer_spec::*;
+pub use self::processing_spec::*;
+pub use self::recording_spec::*;
+pub use self::revision::*;
+pub use self::schedule_spec::*;
+pub use self::sdp::*;
+pub use self::silence_detection_spec::*;
 pub use self::stream_spec::*;
+pub use self::token_spec::*;
 pub use self::uid::*;
 pub use self::uri::*;
 pub use self::validate::*;
+pub use self::version_spec::*;
+pub use self::virtual_source_spec::*;
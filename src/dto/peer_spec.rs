@@ -14,6 +14,11 @@ pub struct PeerSpec {
 
     #[schema(value_type = String)]
     pub peer_uid: Uid,
+
+    // incremented on every change, maintained by server
+    #[serde(default)]
+    #[schema(read_only)]
+    pub revision: u64,
 }
 
 impl Validate for PeerSpec {
@@ -41,7 +46,8 @@ mod tests {
     fn test_validate() {
         let peer_uid = Uid::generate_random();
 
-        let good_spec = PeerSpec { peer_uri: Uri::from_peer(&peer_uid), peer_uid: peer_uid };
+        let good_spec =
+            PeerSpec { peer_uri: Uri::from_peer(&peer_uid), peer_uid: peer_uid, revision: 0 };
 
         assert_ok!(good_spec.validate());
 
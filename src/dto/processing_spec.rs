@@ -0,0 +1,94 @@
+// Copyright (c) Roc Streaming authors
+// Licensed under MPL-2.0
+use crate::dto::validate::*;
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Audio processing applied to stream between source and destination.
+///
+/// Processing is applied in order: loudness normalization, gain, mute,
+/// limiter. Changes are applied to running stream without restart.
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize, ToSchema)]
+pub struct ProcessingSpec {
+    // gain in dB, 0 if omitted
+    #[serde(default)]
+    #[schema(nullable = false)]
+    pub gain: Option<f32>,
+
+    // if true, stream keeps running but plays silence
+    #[serde(default)]
+    pub mute: bool,
+
+    // if set, peaks are limited to this level, in dBFS
+    #[serde(default)]
+    #[schema(nullable = false)]
+    pub limiter_threshold: Option<f32>,
+
+    // if set, gain is slowly adjusted so that average (RMS) level of
+    // signal approaches this level, in dBFS
+    #[serde(default)]
+    #[schema(nullable = false)]
+    pub loudness_target: Option<f32>,
+}
+
+impl ProcessingSpec {
+    pub const GAIN_RANGE: (f32, f32) = (-60.0, 24.0);
+    pub const LIMITER_RANGE: (f32, f32) = (-30.0, 0.0);
+    pub const LOUDNESS_RANGE: (f32, f32) = (-50.0, 0.0);
+}
+
+impl Validate for ProcessingSpec {
+    fn validate(&self) -> ValidationResult {
+        for (name, value, (min, max)) in [
+            ("gain", self.gain, Self::GAIN_RANGE),
+            ("limiter_threshold", self.limiter_threshold, Self::LIMITER_RANGE),
+            ("loudness_target", self.loudness_target, Self::LOUDNESS_RANGE),
+        ] {
+            if let Some(value) = value
+                && !(min..=max).contains(&value)
+            {
+                return Err(ValidationError::LayoutError(format!(
+                    "{name} must be in range [{min}; {max}]"
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assertables::*;
+
+    #[test]
+    fn test_validate() {
+        let good_specs = vec![
+            ProcessingSpec::default(),
+            ProcessingSpec {
+                gain: Some(-6.0),
+                mute: true,
+                limiter_threshold: Some(-1.0),
+                loudness_target: Some(-23.0),
+            },
+            ProcessingSpec { gain: Some(24.0), ..Default::default() },
+        ];
+
+        for spec in &good_specs {
+            assert_ok!(spec.validate(), "{spec:?}");
+        }
+
+        let bad_specs = vec![
+            ProcessingSpec { gain: Some(30.0), ..Default::default() },
+            ProcessingSpec { gain: Some(f32::NAN), ..Default::default() },
+            ProcessingSpec { limiter_threshold: Some(1.0), ..Default::default() },
+            ProcessingSpec { loudness_target: Some(-70.0), ..Default::default() },
+        ];
+
+        for spec in &bad_specs {
+            assert_matches!(spec.validate(), Err(ValidationError::LayoutError(_)), "{spec:?}");
+        }
+    }
+}
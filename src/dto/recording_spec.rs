@@ -0,0 +1,160 @@
+// Copyright (c) Roc Streaming authors
+// Licensed under MPL-2.0
+use crate::dto::uid::*;
+use crate::dto::validate::*;
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use time::OffsetDateTime;
+use utoipa::ToSchema;
+
+/// Recording of stream audio to files on server.
+///
+/// Audio written to destination is also written to files in server state
+/// directory. A new file is started every rotation_interval seconds of audio.
+/// After a new file is started, oldest files are deleted if there are more
+/// than max_files recordings, or if recordings are older than max_age.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize, ToSchema)]
+pub struct RecordingSpec {
+    pub format: RecordingFormat,
+
+    // number of seconds after which new file is started,
+    // if omitted, whole session is written to single file
+    #[serde(default)]
+    #[schema(nullable = false)]
+    pub rotation_interval: Option<u32>,
+
+    // maximum number of recordings of stream to keep
+    #[serde(default)]
+    #[schema(nullable = false)]
+    pub max_files: Option<u32>,
+
+    // maximum age of recordings, in seconds
+    #[serde(default)]
+    #[schema(nullable = false)]
+    pub max_age: Option<u32>,
+
+    // tags written to files, e.g. "title" or "artist"
+    #[serde(default)]
+    pub metadata: BTreeMap<String, String>,
+}
+
+impl Validate for RecordingSpec {
+    fn validate(&self) -> ValidationResult {
+        if self.rotation_interval == Some(0) {
+            return Err(ValidationError::LayoutError(
+                "rotation_interval must be positive".into(),
+            ));
+        }
+        if self.max_files == Some(0) {
+            return Err(ValidationError::LayoutError("max_files must be positive".into()));
+        }
+        if self.max_age == Some(0) {
+            return Err(ValidationError::LayoutError("max_age must be positive".into()));
+        }
+
+        for key in self.metadata.keys() {
+            if key.is_empty() || !key.chars().all(|ch| ch.is_ascii_graphic() && ch != '=') {
+                return Err(ValidationError::LayoutError(format!(
+                    "invalid metadata key '{key}'"
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Debug, strum::Display, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum RecordingFormat {
+    Wav,
+    Flac,
+}
+
+impl RecordingFormat {
+    pub const ALL: [RecordingFormat; 2] = [RecordingFormat::Wav, RecordingFormat::Flac];
+
+    /// Find format by file extension.
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        RecordingFormat::ALL.into_iter().find(|format| format.extension() == extension)
+    }
+
+    /// File extension, without dot.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            RecordingFormat::Wav => "wav",
+            RecordingFormat::Flac => "flac",
+        }
+    }
+
+    /// MIME type of file.
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            RecordingFormat::Wav => "audio/wav",
+            RecordingFormat::Flac => "audio/flac",
+        }
+    }
+}
+
+/// Recorded file.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize, ToSchema)]
+pub struct RecordingInfo {
+    // file name, unique within stream
+    pub recording_id: String,
+
+    #[schema(value_type = String)]
+    pub stream_uid: Uid,
+
+    pub format: RecordingFormat,
+
+    // RFC 3339 timestamp
+    #[serde(with = "time::serde::rfc3339")]
+    #[schema(value_type = String)]
+    pub start_time: OffsetDateTime,
+
+    // size in bytes
+    pub size: u64,
+
+    // file is still being written
+    pub in_progress: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assertables::*;
+
+    fn make_spec() -> RecordingSpec {
+        RecordingSpec {
+            format: RecordingFormat::Flac,
+            rotation_interval: Some(3600),
+            max_files: Some(24),
+            max_age: None,
+            metadata: BTreeMap::from([("title".into(), "Studio A".into())]),
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        assert_ok!(make_spec().validate());
+
+        let spec = RecordingSpec { rotation_interval: Some(0), ..make_spec() };
+        assert_err!(spec.validate());
+
+        let spec = RecordingSpec { max_files: Some(0), ..make_spec() };
+        assert_err!(spec.validate());
+
+        let spec = RecordingSpec { max_age: Some(0), ..make_spec() };
+        assert_err!(spec.validate());
+
+        for key in ["", "a=b", "a b"] {
+            let spec = RecordingSpec {
+                metadata: BTreeMap::from([(key.into(), "x".into())]),
+                ..make_spec()
+            };
+            assert_err!(spec.validate(), "{key}");
+        }
+    }
+}
@@ -0,0 +1,40 @@
+// Copyright (c) Roc Streaming authors
+// Licensed under MPL-2.0
+
+/// Condition on revision of stored entity, checked before modifying
+/// or deleting it (e.g. from If-Match header).
+#[derive(Clone, PartialEq, Debug)]
+pub enum RevisionMatch {
+    /// Entity should exist, any revision.
+    Any,
+    /// Entity should exist and have one of these revisions.
+    OneOf(Vec<u64>),
+}
+
+impl RevisionMatch {
+    /// Check condition against revision of stored entity,
+    /// or none if entity doesn't exist.
+    pub fn matches(&self, revision: Option<u64>) -> bool {
+        match (self, revision) {
+            (_, None) => false,
+            (RevisionMatch::Any, Some(_)) => true,
+            (RevisionMatch::OneOf(revisions), Some(revision)) => revisions.contains(&revision),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches() {
+        assert!(RevisionMatch::Any.matches(Some(1)));
+        assert!(!RevisionMatch::Any.matches(None));
+
+        assert!(RevisionMatch::OneOf(vec![1, 3]).matches(Some(3)));
+        assert!(!RevisionMatch::OneOf(vec![1, 3]).matches(Some(2)));
+        assert!(!RevisionMatch::OneOf(vec![1, 3]).matches(None));
+        assert!(!RevisionMatch::OneOf(vec![]).matches(Some(1)));
+    }
+}
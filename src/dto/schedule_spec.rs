@@ -0,0 +1,437 @@
+// Copyright (c) Roc Streaming authors
+// Licensed under MPL-2.0
+use crate::dto::validate::*;
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use time::{Date, Month, OffsetDateTime, Time, UtcOffset};
+use utoipa::ToSchema;
+
+/// Schedule of stream start and stop.
+///
+/// One-shot schedule uses start_time and either stop_time or duration.
+/// Both start_time and stop_time may be omitted, in which case the stream
+/// is started immediately or is never stopped.
+///
+/// Recurring schedule uses cron and duration: stream is started at every
+/// time matching cron expression and stopped after duration.
+///
+/// All times are in UTC.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize, ToSchema)]
+pub struct ScheduleSpec {
+    // RFC 3339 timestamp
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    #[schema(value_type = String, nullable = false)]
+    pub start_time: Option<OffsetDateTime>,
+
+    // RFC 3339 timestamp
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    #[schema(value_type = String, nullable = false)]
+    pub stop_time: Option<OffsetDateTime>,
+
+    // number of seconds from start to stop
+    #[serde(default)]
+    #[schema(nullable = false)]
+    pub duration: Option<u32>,
+
+    // cron expression: "minute hour day-of-month month day-of-week"
+    #[serde(default)]
+    #[schema(nullable = false)]
+    pub cron: Option<String>,
+}
+
+impl ScheduleSpec {
+    /// Get duration as std Duration.
+    pub fn duration(&self) -> Option<Duration> {
+        self.duration.map(|secs| Duration::from_secs(secs as u64))
+    }
+}
+
+impl Validate for ScheduleSpec {
+    fn validate(&self) -> ValidationResult {
+        if self.duration == Some(0) {
+            return Err(ValidationError::LayoutError("duration must be positive".into()));
+        }
+
+        match &self.cron {
+            Some(cron) => {
+                CronExpr::parse(cron)?;
+
+                if self.start_time.is_some() || self.stop_time.is_some() {
+                    return Err(ValidationError::LayoutError(
+                        "start_time and stop_time are not allowed with cron".into(),
+                    ));
+                }
+                if self.duration.is_none() {
+                    return Err(ValidationError::LayoutError(
+                        "duration is required with cron".into(),
+                    ));
+                }
+            },
+            None => {
+                if self.start_time.is_none() && self.stop_time.is_none() {
+                    return Err(ValidationError::LayoutError(
+                        "schedule must have start_time, stop_time, or cron".into(),
+                    ));
+                }
+                if self.duration.is_some() && self.start_time.is_none() {
+                    return Err(ValidationError::LayoutError(
+                        "duration requires start_time".into(),
+                    ));
+                }
+                if self.duration.is_some() && self.stop_time.is_some() {
+                    return Err(ValidationError::LayoutError(
+                        "duration and stop_time are mutually exclusive".into(),
+                    ));
+                }
+                if let (Some(start_time), Some(stop_time)) = (self.start_time, self.stop_time)
+                    && stop_time <= start_time
+                {
+                    return Err(ValidationError::LayoutError(
+                        "stop_time must be after start_time".into(),
+                    ));
+                }
+            },
+        }
+
+        Ok(())
+    }
+}
+
+/// Parsed cron expression.
+///
+/// Supports standard 5-field format: minute, hour, day of month, month,
+/// day of week (0-7, both 0 and 7 are Sunday). Each field is "*", number,
+/// range "a-b", step "*/n" or "a-b/n", or comma-separated list of them.
+///
+/// As in classic cron, when both day of month and day of week are
+/// restricted, time matches if either of them matches.
+#[derive(Clone, PartialEq, Debug)]
+pub struct CronExpr {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    any_day: bool,
+    any_weekday: bool,
+}
+
+impl CronExpr {
+    /// Parse cron expression.
+    pub fn parse(text: &str) -> Result<Self, ValidationError> {
+        let fields: Vec<&str> = text.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(ValidationError::CronFormatError(
+                text.into(),
+                "expected 5 fields".into(),
+            ));
+        }
+
+        let parse = |index: usize, min: u32, max: u32| {
+            parse_cron_field(fields[index], min, max)
+                .map_err(|err| ValidationError::CronFormatError(text.into(), err))
+        };
+
+        let mut weekdays = parse(4, 0, 7)?;
+        // 7 is alias for Sunday
+        if weekdays & (1 << 7) != 0 {
+            weekdays |= 1;
+        }
+
+        Ok(CronExpr {
+            minutes: parse(0, 0, 59)?,
+            hours: parse(1, 0, 23)?,
+            days: parse(2, 1, 31)?,
+            months: parse(3, 1, 12)?,
+            weekdays,
+            any_day: fields[2] == "*",
+            any_weekday: fields[4] == "*",
+        })
+    }
+
+    /// Check if given time (truncated to minute) matches expression.
+    pub fn matches(&self, time: OffsetDateTime) -> bool {
+        self.matches_date(time.date())
+            && self.hours & (1 << time.hour()) != 0
+            && self.minutes & (1 << time.minute()) != 0
+    }
+
+    /// Find first matching time strictly after given time.
+    /// Returns None if there is no match in the next few years
+    /// (e.g. "0 0 31 2 *").
+    pub fn next_after(&self, after: OffsetDateTime) -> Option<OffsetDateTime> {
+        // start from next minute
+        let mut candidate = after
+            .to_offset(UtcOffset::UTC)
+            .replace_second(0)
+            .ok()?
+            .replace_nanosecond(0)
+            .ok()?
+            + time::Duration::minutes(1);
+
+        let deadline = candidate + time::Duration::days(366 * 5);
+
+        while candidate < deadline {
+            if self.months & (1 << candidate.month() as u8) == 0 {
+                // jump to the first day of next month
+                let (year, month) = match candidate.month() {
+                    Month::December => (candidate.year() + 1, Month::January),
+                    month => (candidate.year(), month.next()),
+                };
+                candidate = Date::from_calendar_date(year, month, 1)
+                    .ok()?
+                    .with_time(Time::MIDNIGHT)
+                    .assume_utc();
+                continue;
+            }
+            if !self.matches_date(candidate.date()) {
+                // jump to next day
+                candidate =
+                    candidate.date().next_day()?.with_time(Time::MIDNIGHT).assume_utc();
+                continue;
+            }
+            if self.hours & (1 << candidate.hour()) == 0 {
+                // jump to next hour
+                candidate = candidate.replace_minute(0).ok()? + time::Duration::hours(1);
+                continue;
+            }
+            if self.minutes & (1 << candidate.minute()) == 0 {
+                candidate += time::Duration::minutes(1);
+                continue;
+            }
+            return Some(candidate);
+        }
+
+        None
+    }
+
+    fn matches_date(&self, date: Date) -> bool {
+        if self.months & (1 << date.month() as u8) == 0 {
+            return false;
+        }
+
+        let day_matches = self.days & (1 << date.day()) != 0;
+        let weekday_matches =
+            self.weekdays & (1 << date.weekday().number_days_from_sunday()) != 0;
+
+        match (self.any_day, self.any_weekday) {
+            (false, false) => day_matches || weekday_matches,
+            _ => day_matches && weekday_matches,
+        }
+    }
+}
+
+/// Parse one cron field into bitmask of allowed values.
+fn parse_cron_field(field: &str, min: u32, max: u32) -> Result<u64, String> {
+    let mut mask = 0;
+
+    for item in field.split(',') {
+        let (range, step) = match item.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 =
+                    step.parse().map_err(|_| format!("invalid step in '{item}'"))?;
+                if step == 0 {
+                    return Err(format!("zero step in '{item}'"));
+                }
+                (range, step)
+            },
+            None => (item, 1),
+        };
+
+        let parse_value = |value: &str| -> Result<u32, String> {
+            let value: u32 =
+                value.parse().map_err(|_| format!("invalid value in '{item}'"))?;
+            if value < min || value > max {
+                return Err(format!("value out of range [{min}; {max}] in '{item}'"));
+            }
+            Ok(value)
+        };
+
+        let (first, last) = match range {
+            "*" => (min, max),
+            _ => match range.split_once('-') {
+                Some((first, last)) => (parse_value(first)?, parse_value(last)?),
+                None if step == 1 => {
+                    let value = parse_value(range)?;
+                    (value, value)
+                },
+                // "a/n" means "a-max/n"
+                None => (parse_value(range)?, max),
+            },
+        };
+
+        if first > last {
+            return Err(format!("invalid range in '{item}'"));
+        }
+
+        for value in (first..=last).step_by(step as usize) {
+            mask |= 1 << value;
+        }
+    }
+
+    Ok(mask)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assertables::*;
+    use time::macros::datetime;
+
+    #[test]
+    fn test_validate() {
+        let good_schedules = vec![
+            ScheduleSpec {
+                start_time: Some(datetime!(2026-01-01 10:00 UTC)),
+                stop_time: Some(datetime!(2026-01-01 11:00 UTC)),
+                duration: None,
+                cron: None,
+            },
+            ScheduleSpec {
+                start_time: Some(datetime!(2026-01-01 10:00 UTC)),
+                stop_time: None,
+                duration: Some(3600),
+                cron: None,
+            },
+            ScheduleSpec {
+                start_time: None,
+                stop_time: Some(datetime!(2026-01-01 11:00 UTC)),
+                duration: None,
+                cron: None,
+            },
+            ScheduleSpec {
+                start_time: None,
+                stop_time: None,
+                duration: Some(600),
+                cron: Some("0 9 * * 1-5".into()),
+            },
+        ];
+
+        for schedule in &good_schedules {
+            assert_ok!(schedule.validate(), "{schedule:?}");
+        }
+
+        let bad_schedules = vec![
+            // empty
+            ScheduleSpec { start_time: None, stop_time: None, duration: None, cron: None },
+            // stop before start
+            ScheduleSpec {
+                start_time: Some(datetime!(2026-01-01 11:00 UTC)),
+                stop_time: Some(datetime!(2026-01-01 10:00 UTC)),
+                duration: None,
+                cron: None,
+            },
+            // duration and stop_time
+            ScheduleSpec {
+                start_time: Some(datetime!(2026-01-01 10:00 UTC)),
+                stop_time: Some(datetime!(2026-01-01 11:00 UTC)),
+                duration: Some(3600),
+                cron: None,
+            },
+            // duration without start_time
+            ScheduleSpec {
+                start_time: None,
+                stop_time: None,
+                duration: Some(3600),
+                cron: None,
+            },
+            // zero duration
+            ScheduleSpec {
+                start_time: Some(datetime!(2026-01-01 10:00 UTC)),
+                stop_time: None,
+                duration: Some(0),
+                cron: None,
+            },
+            // cron without duration
+            ScheduleSpec {
+                start_time: None,
+                stop_time: None,
+                duration: None,
+                cron: Some("0 9 * * *".into()),
+            },
+            // cron with start_time
+            ScheduleSpec {
+                start_time: Some(datetime!(2026-01-01 10:00 UTC)),
+                stop_time: None,
+                duration: Some(600),
+                cron: Some("0 9 * * *".into()),
+            },
+            // invalid cron
+            ScheduleSpec {
+                start_time: None,
+                stop_time: None,
+                duration: Some(600),
+                cron: Some("0 25 * * *".into()),
+            },
+        ];
+
+        for schedule in &bad_schedules {
+            assert_err!(schedule.validate(), "{schedule:?}");
+        }
+    }
+
+    #[test]
+    fn test_cron_parse() {
+        let good_exprs = ["* * * * *", "0 9 * * 1-5", "*/15 0-6/2 1,15 * 0", "30 4 1 1 7"];
+
+        for text in good_exprs {
+            assert_ok!(CronExpr::parse(text), "{text}");
+        }
+
+        let bad_exprs = [
+            "",
+            "* * * *",
+            "* * * * * *",
+            "60 * * * *",
+            "* 24 * * *",
+            "* * 0 * *",
+            "* * * 13 *",
+            "* * * * 8",
+            "*/0 * * * *",
+            "5-1 * * * *",
+            "a * * * *",
+        ];
+
+        for text in bad_exprs {
+            assert_err!(CronExpr::parse(text), "{text}");
+        }
+    }
+
+    #[test]
+    fn test_cron_next() {
+        let cases = vec![
+            // every minute
+            ("* * * * *", datetime!(2026-03-10 10:20:30 UTC), datetime!(2026-03-10 10:21 UTC)),
+            // exact match is skipped
+            ("20 10 * * *", datetime!(2026-03-10 10:20 UTC), datetime!(2026-03-11 10:20 UTC)),
+            // later today
+            ("0 12 * * *", datetime!(2026-03-10 10:20 UTC), datetime!(2026-03-10 12:00 UTC)),
+            // every 15 minutes
+            ("*/15 * * * *", datetime!(2026-03-10 10:20 UTC), datetime!(2026-03-10 10:30 UTC)),
+            // weekdays (2026-03-14 is Saturday)
+            ("0 9 * * 1-5", datetime!(2026-03-13 10:00 UTC), datetime!(2026-03-16 09:00 UTC)),
+            // Sunday as 7
+            ("0 9 * * 7", datetime!(2026-03-13 10:00 UTC), datetime!(2026-03-15 09:00 UTC)),
+            // next month
+            ("0 0 1 * *", datetime!(2026-03-10 10:20 UTC), datetime!(2026-04-01 00:00 UTC)),
+            // next year
+            ("0 0 1 1 *", datetime!(2026-03-10 10:20 UTC), datetime!(2027-01-01 00:00 UTC)),
+            // leap day
+            ("0 0 29 2 *", datetime!(2026-03-10 10:20 UTC), datetime!(2028-02-29 00:00 UTC)),
+            // day of month OR day of week
+            ("0 0 20 * 1", datetime!(2026-03-10 10:20 UTC), datetime!(2026-03-16 00:00 UTC)),
+        ];
+
+        for (text, time, expected) in cases {
+            let expr = CronExpr::parse(text).unwrap();
+
+            assert_eq!(expr.next_after(time), Some(expected), "{text} {time}");
+            assert!(expr.matches(expected), "{text} {expected}");
+        }
+
+        // never matches
+        let expr = CronExpr::parse("0 0 31 2 *").unwrap();
+        assert_none!(expr.next_after(datetime!(2026-03-10 10:20 UTC)));
+    }
+}
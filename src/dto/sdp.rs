@@ -0,0 +1,692 @@
+// Copyright (c) Roc Streaming authors
+// Licensed under MPL-2.0
+use crate::dto::stream_spec::*;
+use crate::dto::uri::*;
+use crate::dto::validate::*;
+
+use std::fmt;
+use std::net::{IpAddr, SocketAddr};
+
+/// SDP session description (RFC 8866) of external connection.
+///
+/// Describes media, repair and control addresses and audio encoding in a form
+/// understood by ordinary RTP tools (ffmpeg, gstreamer, VLC). Repair flow is
+/// described using FECFRAME attributes (RFC 6363, RFC 6364).
+///
+/// Example:
+///
+///   v=0
+///   o=rocd 0 0 IN IP4 192.168.0.101
+///   s=rx4sse-w0zas1-gf2s1o
+///   c=IN IP4 192.168.0.101
+///   t=0 0
+///   a=group:FEC-FR S1 R1
+///   m=audio 10000 RTP/AVP 10
+///   a=rtpmap:10 L16/44100/2
+///   a=rtcp:10002
+///   a=fec-source-flow:id=0
+///   a=mid:S1
+///   m=application 10001 UDP/FEC
+///   a=fec-repair-flow:encoding-id=8
+///   a=mid:R1
+#[derive(Clone, PartialEq, Debug)]
+pub struct SdpSession {
+    pub session_name: String,
+    pub connection: ConnectionSpec,
+    pub encoding: EncodingSpec,
+    // addresses of connection, checked when session is constructed,
+    // so that formatting can't fail
+    addrs: SdpAddrs,
+}
+
+/// Addresses of external connection, extracted from URIs.
+#[derive(Clone, PartialEq, Debug)]
+struct SdpAddrs {
+    media: SocketAddr,
+    repair: Option<SocketAddr>,
+    control: SocketAddr,
+    ttl: Option<u8>,
+    fec: FecEncoding,
+}
+
+/// Media section being parsed.
+#[derive(Copy, Clone, PartialEq)]
+enum SdpSection {
+    Session,
+    Audio,
+    Repair,
+    Other,
+}
+
+impl SdpSession {
+    /// Describe stream.
+    ///
+    /// Exactly one side of the stream (source or destination) should be
+    /// external connection, it's the one described by SDP. Its URIs
+    /// should have IP addresses, because SDP can't describe hostnames.
+    pub fn from_stream(stream: &StreamSpec) -> Result<Self, ValidationError> {
+        let connection = match (&stream.source, &stream.destination) {
+            (ConnectionSpec::External { .. }, ConnectionSpec::Endpoint { .. }) => {
+                &stream.source
+            },
+            (ConnectionSpec::Endpoint { .. }, ConnectionSpec::External { .. }) => {
+                &stream.destination
+            },
+            _ => {
+                return Err(ValidationError::SdpFormatError(
+                    "stream should have exactly one external connection".into(),
+                ));
+            },
+        };
+
+        Ok(SdpSession {
+            session_name: stream.stream_uid.to_string(),
+            connection: connection.clone(),
+            encoding: stream.encoding.clone(),
+            addrs: SdpAddrs::from_connection(connection)?,
+        })
+    }
+
+    /// Parse SDP text.
+    pub fn parse(text: &str) -> Result<Self, ValidationError> {
+        let mut session_name = String::new();
+        let mut section = SdpSection::Session;
+
+        let mut session_addr: Option<(IpAddr, Option<u8>)> = None;
+        let mut audio_addr: Option<(IpAddr, Option<u8>)> = None;
+        let mut repair_addr: Option<(IpAddr, Option<u8>)> = None;
+
+        let mut audio_port: Option<u16> = None;
+        let mut audio_payload: Option<u8> = None;
+        let mut audio_rtpmap: Option<String> = None;
+        let mut rtcp_port: Option<u16> = None;
+        let mut rtcp_addr: Option<IpAddr> = None;
+        let mut repair_port: Option<u16> = None;
+        let mut repair_encoding_id: Option<u32> = None;
+
+        for line in text.lines().map(|line| line.trim_end_matches('\r')) {
+            if line.is_empty() {
+                continue;
+            }
+
+            let (kind, value) = line.split_once('=').ok_or_else(|| sdp_error(line))?;
+
+            match (kind, section) {
+                ("s", _) => {
+                    session_name = value.to_string();
+                },
+                ("c", SdpSection::Session) => {
+                    session_addr = Some(parse_connection_line(value)?);
+                },
+                ("c", SdpSection::Audio) => {
+                    audio_addr = Some(parse_connection_line(value)?);
+                },
+                ("c", SdpSection::Repair) => {
+                    repair_addr = Some(parse_connection_line(value)?);
+                },
+                ("m", _) => {
+                    // format list is omitted for UDP/FEC (RFC 6364)
+                    let fields: Vec<&str> = value.split_whitespace().collect();
+                    if fields.len() < 3 {
+                        return Err(sdp_error(line));
+                    }
+
+                    let port = parse_port(fields[1].split('/').next().unwrap(), line)?;
+
+                    section = match (fields[0], fields[2]) {
+                        ("audio", proto) if proto.starts_with("RTP/") && fields.len() > 3 => {
+                            if audio_port.is_some() {
+                                return Err(ValidationError::SdpFormatError(
+                                    "multiple audio media sections are not supported".into(),
+                                ));
+                            }
+                            audio_port = Some(port);
+                            audio_payload =
+                                Some(fields[3].parse().map_err(|_| sdp_error(line))?);
+                            SdpSection::Audio
+                        },
+                        ("application", "UDP/FEC") => {
+                            repair_port = Some(port);
+                            SdpSection::Repair
+                        },
+                        _ => SdpSection::Other,
+                    };
+                },
+                ("a", SdpSection::Audio) => {
+                    let (attr, attr_value) = value.split_once(':').unwrap_or((value, ""));
+
+                    match attr {
+                        "rtpmap" => {
+                            let (payload, rtpmap) =
+                                attr_value.split_once(' ').ok_or_else(|| sdp_error(line))?;
+                            if payload.parse::<u8>().ok() == audio_payload {
+                                audio_rtpmap = Some(rtpmap.trim().to_string());
+                            }
+                        },
+                        "rtcp" => {
+                            let fields: Vec<&str> = attr_value.split_whitespace().collect();
+                            rtcp_port = Some(parse_port(
+                                fields.first().ok_or_else(|| sdp_error(line))?,
+                                line,
+                            )?);
+                            if fields.len() == 4 {
+                                rtcp_addr =
+                                    Some(parse_connection_line(&fields[1..].join(" "))?.0);
+                            }
+                        },
+                        _ => (),
+                    }
+                },
+                ("a", SdpSection::Repair) => {
+                    let (attr, attr_value) = value.split_once(':').unwrap_or((value, ""));
+
+                    if attr == "fec-repair-flow" {
+                        for param in attr_value.split(';').map(|param| param.trim()) {
+                            if let Some(id) = param.strip_prefix("encoding-id=") {
+                                repair_encoding_id =
+                                    Some(id.parse().map_err(|_| sdp_error(line))?);
+                            }
+                        }
+                    }
+                },
+                _ => (),
+            }
+        }
+
+        let audio_port = audio_port.ok_or_else(|| {
+            ValidationError::SdpFormatError("missing audio media section".into())
+        })?;
+        let (audio_ip, audio_ttl) = audio_addr.or(session_addr).ok_or_else(|| {
+            ValidationError::SdpFormatError("missing connection address for audio".into())
+        })?;
+
+        let fec = match (repair_port, repair_encoding_id) {
+            (None, _) => FecEncoding::Disable,
+            (Some(_), Some(8)) => FecEncoding::Rs8m,
+            (Some(_), Some(7)) => FecEncoding::Ldpc,
+            (Some(_), Some(id)) => {
+                return Err(ValidationError::SdpFormatError(format!(
+                    "unsupported FEC encoding-id {id}"
+                )));
+            },
+            (Some(_), None) => {
+                return Err(ValidationError::SdpFormatError(
+                    "missing fec-repair-flow encoding-id".into(),
+                ));
+            },
+        };
+
+        let repair = match repair_port {
+            Some(port) => {
+                let (repair_ip, _) = repair_addr.or(session_addr).ok_or_else(|| {
+                    ValidationError::SdpFormatError(
+                        "missing connection address for repair".into(),
+                    )
+                })?;
+                Some(SocketAddr::new(repair_ip, port))
+            },
+            None => None,
+        };
+
+        let addrs = SdpAddrs {
+            media: SocketAddr::new(audio_ip, audio_port),
+            repair,
+            control: SocketAddr::new(
+                rtcp_addr.unwrap_or(audio_ip),
+                rtcp_port.unwrap_or(audio_port.wrapping_add(1)),
+            ),
+            ttl: audio_ttl,
+            fec,
+        };
+
+        let encoding = parse_encoding(audio_payload.unwrap(), audio_rtpmap.as_deref())?;
+        encoding.validate()?;
+
+        let connection = addrs.to_connection()?;
+        connection.validate()?;
+
+        Ok(SdpSession { session_name, connection, encoding, addrs })
+    }
+}
+
+impl fmt::Display for SdpSession {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let addrs = &self.addrs;
+
+        let encoding_name = match self.encoding.sample_format {
+            SampleFormat::S16 => "L16",
+            SampleFormat::S24 => "L24",
+        };
+
+        write!(f, "v=0\r\n")?;
+        write!(f, "o=rocd 0 0 {}\r\n", format_addr(addrs.media.ip()))?;
+        write!(f, "s={}\r\n", self.session_name)?;
+        write!(f, "c={}\r\n", format_connection_line(addrs.media.ip(), addrs.ttl))?;
+        write!(f, "t=0 0\r\n")?;
+        if addrs.repair.is_some() {
+            write!(f, "a=group:FEC-FR S1 R1\r\n")?;
+        }
+
+        write!(
+            f,
+            "m=audio {} RTP/AVP {}\r\n",
+            addrs.media.port(),
+            self.encoding.payload_type
+        )?;
+        write!(
+            f,
+            "a=rtpmap:{} {}/{}/{}\r\n",
+            self.encoding.payload_type,
+            encoding_name,
+            self.encoding.sample_rate,
+            self.encoding.channels
+        )?;
+        if addrs.control.ip() == addrs.media.ip() {
+            write!(f, "a=rtcp:{}\r\n", addrs.control.port())?;
+        } else {
+            write!(
+                f,
+                "a=rtcp:{} {}\r\n",
+                addrs.control.port(),
+                format_addr(addrs.control.ip())
+            )?;
+        }
+
+        if let Some(repair) = addrs.repair {
+            let encoding_id = match addrs.fec {
+                FecEncoding::Rs8m => 8,
+                FecEncoding::Ldpc => 7,
+                FecEncoding::Disable => unreachable!(),
+            };
+
+            write!(f, "a=fec-source-flow:id=0\r\n")?;
+            write!(f, "a=mid:S1\r\n")?;
+            write!(f, "m=application {} UDP/FEC\r\n", repair.port())?;
+            if repair.ip() != addrs.media.ip() {
+                write!(f, "c={}\r\n", format_connection_line(repair.ip(), addrs.ttl))?;
+            }
+            write!(f, "a=fec-repair-flow:encoding-id={encoding_id}\r\n")?;
+            write!(f, "a=mid:R1\r\n")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl SdpAddrs {
+    fn from_connection(connection: &ConnectionSpec) -> Result<Self, ValidationError> {
+        let ConnectionSpec::External {
+            media_uri, repair_uri, control_uri, multicast_ttl, ..
+        } = connection
+        else {
+            return Err(ValidationError::SdpFormatError(
+                "SDP can describe only external connection".into(),
+            ));
+        };
+
+        let fec = connection.fec_encoding().ok_or_else(|| {
+            ValidationError::SdpFormatError("unsupported media_uri scheme".into())
+        })?;
+
+        Ok(SdpAddrs {
+            media: uri_to_addr(media_uri)?,
+            repair: repair_uri.as_ref().map(uri_to_addr).transpose()?,
+            control: uri_to_addr(control_uri)?,
+            ttl: *multicast_ttl,
+            fec,
+        })
+    }
+
+    fn to_connection(&self) -> Result<ConnectionSpec, ValidationError> {
+        let media_uri = Uri::parse(&format!("{}://{}", self.fec.media_scheme(), self.media))?;
+        let repair_uri = match (self.fec.repair_scheme(), self.repair) {
+            (Some(scheme), Some(repair)) => Some(Uri::parse(&format!("{scheme}://{repair}"))?),
+            _ => None,
+        };
+        let control_uri = Uri::parse(&format!("rtcp://{}", self.control))?;
+
+        Ok(ConnectionSpec::External {
+            connection_type: ConnectionType::External,
+            media_uri,
+            repair_uri,
+            control_uri,
+            multicast_interface: None,
+            multicast_ttl: if self.media.ip().is_multicast() { self.ttl } else { None },
+        })
+    }
+}
+
+fn sdp_error(line: &str) -> ValidationError {
+    ValidationError::SdpFormatError(format!("malformed line '{line}'"))
+}
+
+fn uri_to_addr(uri: &Uri) -> Result<SocketAddr, ValidationError> {
+    match (uri.ip_addr(), uri.port()) {
+        (Some(ip), Some(port)) => Ok(SocketAddr::new(ip, port)),
+        _ => Err(ValidationError::SdpFormatError(format!(
+            "URI '{uri}' should have IP address and port"
+        ))),
+    }
+}
+
+fn parse_port(text: &str, line: &str) -> Result<u16, ValidationError> {
+    text.parse().map_err(|_| sdp_error(line))
+}
+
+/// Parse "IN IP4 <addr>[/<ttl>[/<count>]]".
+fn parse_connection_line(value: &str) -> Result<(IpAddr, Option<u8>), ValidationError> {
+    let fields: Vec<&str> = value.split_whitespace().collect();
+
+    let (addr, ttl) = match fields.as_slice() {
+        ["IN", "IP4" | "IP6", addr] => {
+            let mut parts = addr.split('/');
+            let addr = parts.next().unwrap();
+            (addr, parts.next())
+        },
+        _ => return Err(sdp_error(value)),
+    };
+
+    let addr: IpAddr = addr.parse().map_err(|_| sdp_error(value))?;
+    let ttl = match (addr, ttl) {
+        // for IPv6, the only suffix is address count
+        (IpAddr::V4(_), Some(ttl)) => Some(ttl.parse().map_err(|_| sdp_error(value))?),
+        _ => None,
+    };
+
+    Ok((addr, ttl))
+}
+
+fn format_addr(addr: IpAddr) -> String {
+    match addr {
+        IpAddr::V4(addr) => format!("IN IP4 {addr}"),
+        IpAddr::V6(addr) => format!("IN IP6 {addr}"),
+    }
+}
+
+fn format_connection_line(addr: IpAddr, ttl: Option<u8>) -> String {
+    match addr {
+        // TTL is mandatory for IPv4 multicast
+        IpAddr::V4(_) if addr.is_multicast() => {
+            format!("{}/{}", format_addr(addr), ttl.unwrap_or(1))
+        },
+        _ => format_addr(addr),
+    }
+}
+
+/// Parse "<encoding>/<rate>[/<channels>]".
+fn parse_encoding(
+    payload_type: u8, rtpmap: Option<&str>,
+) -> Result<EncodingSpec, ValidationError> {
+    let Some(rtpmap) = rtpmap else {
+        return EncodingSpec::from_static_payload(payload_type).ok_or_else(|| {
+            ValidationError::SdpFormatError(format!(
+                "missing rtpmap for payload type {payload_type}"
+            ))
+        });
+    };
+
+    let fields: Vec<&str> = rtpmap.split('/').collect();
+    let rtpmap_error =
+        || ValidationError::SdpFormatError(format!("unsupported rtpmap '{rtpmap}'"));
+
+    let sample_format = match fields.first().map(|name| name.to_ascii_uppercase()).as_deref() {
+        Some("L16") => SampleFormat::S16,
+        Some("L24") => SampleFormat::S24,
+        _ => return Err(rtpmap_error()),
+    };
+    let sample_rate =
+        fields.get(1).and_then(|rate| rate.parse().ok()).ok_or_else(rtpmap_error)?;
+    let channels = match fields.get(2) {
+        Some(channels) => channels.parse().map_err(|_| rtpmap_error())?,
+        None => 1,
+    };
+
+    Ok(EncodingSpec { payload_type, sample_format, sample_rate, channels })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dto::uid::*;
+    use assertables::*;
+
+    fn make_stream(destination: ConnectionSpec, encoding: EncodingSpec) -> StreamSpec {
+        let stream_uid = Uid::parse("rx4sse-w0zas1-gf2s1o").unwrap();
+
+        StreamSpec {
+            stream_uri: Uri::from_stream(&stream_uid),
+            stream_uid,
+            source: ConnectionSpec::Endpoint {
+                connection_type: ConnectionType::Endpoint,
+                endpoint_uri: Uri::from_endpoint(
+                    &Uid::generate_random(),
+                    &Uid::generate_random(),
+                ),
+            },
+            destination,
+            encoding,
+            owner: None,
+            lease_ttl: None,
+            lease_expiration: None,
+            schedule: None,
+            state: StreamState::Running,
+            next_transition: None,
+            error: None,
+            recording: None,
+            processing: None,
+            silence_detection: None,
+            failover: None,
+            active_source: None,
+            revision: 0,
+        }
+    }
+
+    fn make_connection(
+        media_uri: &str, repair_uri: Option<&str>, control_uri: &str,
+        multicast_ttl: Option<u8>,
+    ) -> ConnectionSpec {
+        ConnectionSpec::External {
+            connection_type: ConnectionType::External,
+            media_uri: Uri::parse(media_uri).unwrap(),
+            repair_uri: repair_uri.map(|uri| Uri::parse(uri).unwrap()),
+            control_uri: Uri::parse(control_uri).unwrap(),
+            multicast_interface: None,
+            multicast_ttl,
+        }
+    }
+
+    #[test]
+    fn test_format_fec() {
+        let stream = make_stream(
+            make_connection(
+                "rtp+rs8m://192.168.0.101:10000",
+                Some("rs8m://192.168.0.101:10001"),
+                "rtcp://192.168.0.101:10002",
+                None,
+            ),
+            EncodingSpec::default(),
+        );
+
+        let sdp = SdpSession::from_stream(&stream).unwrap().to_string();
+
+        assert_eq!(
+            sdp,
+            "v=0\r\n\
+             o=rocd 0 0 IN IP4 192.168.0.101\r\n\
+             s=rx4sse-w0zas1-gf2s1o\r\n\
+             c=IN IP4 192.168.0.101\r\n\
+             t=0 0\r\n\
+             a=group:FEC-FR S1 R1\r\n\
+             m=audio 10000 RTP/AVP 10\r\n\
+             a=rtpmap:10 L16/44100/2\r\n\
+             a=rtcp:10002\r\n\
+             a=fec-source-flow:id=0\r\n\
+             a=mid:S1\r\n\
+             m=application 10001 UDP/FEC\r\n\
+             a=fec-repair-flow:encoding-id=8\r\n\
+             a=mid:R1\r\n"
+        );
+    }
+
+    #[test]
+    fn test_format_multicast() {
+        let stream = make_stream(
+            make_connection("rtp://239.1.2.3:10000", None, "rtcp://239.1.2.3:10001", Some(8)),
+            EncodingSpec {
+                payload_type: 96,
+                sample_format: SampleFormat::S24,
+                sample_rate: 48000,
+                channels: 2,
+            },
+        );
+
+        let sdp = SdpSession::from_stream(&stream).unwrap().to_string();
+
+        assert_eq!(
+            sdp,
+            "v=0\r\n\
+             o=rocd 0 0 IN IP4 239.1.2.3\r\n\
+             s=rx4sse-w0zas1-gf2s1o\r\n\
+             c=IN IP4 239.1.2.3/8\r\n\
+             t=0 0\r\n\
+             m=audio 10000 RTP/AVP 96\r\n\
+             a=rtpmap:96 L24/48000/2\r\n\
+             a=rtcp:10001\r\n"
+        );
+    }
+
+    #[test]
+    fn test_format_no_external() {
+        let mut stream = make_stream(
+            make_connection("rtp://239.1.2.3:10000", None, "rtcp://239.1.2.3:10001", None),
+            EncodingSpec::default(),
+        );
+        stream.destination = stream.source.clone();
+
+        assert_matches!(
+            SdpSession::from_stream(&stream),
+            Err(ValidationError::SdpFormatError(_))
+        );
+    }
+
+    #[test]
+    fn test_format_hostname() {
+        let stream = make_stream(
+            make_connection(
+                "rtp://myhost.local:10000",
+                None,
+                "rtcp://myhost.local:10001",
+                None,
+            ),
+            EncodingSpec::default(),
+        );
+
+        assert_matches!(
+            SdpSession::from_stream(&stream),
+            Err(ValidationError::SdpFormatError(_))
+        );
+    }
+
+    #[test]
+    fn test_parse_roundtrip() {
+        let connections = vec![
+            make_connection(
+                "rtp+rs8m://192.168.0.101:10000",
+                Some("rs8m://192.168.0.101:10001"),
+                "rtcp://192.168.0.101:10002",
+                None,
+            ),
+            make_connection(
+                "rtp+ldpc://[ff02::1234]:10000",
+                Some("ldpc://[ff02::1234]:10001"),
+                "rtcp://[ff02::1234]:10002",
+                None,
+            ),
+            make_connection("rtp://239.1.2.3:10000", None, "rtcp://239.1.2.3:10001", Some(16)),
+            make_connection("rtp://10.0.0.1:10000", None, "rtcp://10.0.0.2:20000", None),
+        ];
+
+        for connection in connections {
+            let stream = make_stream(connection, EncodingSpec::default());
+
+            let session = SdpSession::from_stream(&stream).unwrap();
+            let parsed_session = SdpSession::parse(&session.to_string()).unwrap();
+
+            assert_eq!(session, parsed_session);
+        }
+    }
+
+    #[test]
+    fn test_parse_ffmpeg() {
+        // generated by:
+        //   ffmpeg -re -i test.wav -c:a pcm_s16be -f rtp rtp://127.0.0.1:5004
+        let sdp = "v=0\n\
+                   o=- 0 0 IN IP4 127.0.0.1\n\
+                   s=No Name\n\
+                   c=IN IP4 127.0.0.1\n\
+                   t=0 0\n\
+                   a=tool:libavformat 61.7.100\n\
+                   m=audio 5004 RTP/AVP 97\n\
+                   b=AS:1536\n\
+                   a=rtpmap:97 L16/48000/2\n";
+
+        let session = SdpSession::parse(sdp).unwrap();
+
+        assert_eq!(session.session_name, "No Name");
+        assert_eq!(
+            session.connection,
+            make_connection("rtp://127.0.0.1:5004", None, "rtcp://127.0.0.1:5005", None)
+        );
+        assert_eq!(
+            session.encoding,
+            EncodingSpec {
+                payload_type: 97,
+                sample_format: SampleFormat::S16,
+                sample_rate: 48000,
+                channels: 2,
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_static_payload() {
+        let sdp = "v=0\n\
+                   s=-\n\
+                   c=IN IP4 10.0.0.1\n\
+                   t=0 0\n\
+                   m=audio 5004 RTP/AVP 11\n";
+
+        let session = SdpSession::parse(sdp).unwrap();
+
+        assert_eq!(session.encoding, EncodingSpec::from_static_payload(11).unwrap());
+    }
+
+    #[test]
+    fn test_parse_err() {
+        let cases = vec![
+            // no media
+            "v=0\ns=-\nc=IN IP4 10.0.0.1\nt=0 0\n",
+            // no connection address
+            "v=0\ns=-\nt=0 0\nm=audio 5004 RTP/AVP 10\n",
+            // malformed connection address
+            "v=0\ns=-\nc=IN IP4 foo\nt=0 0\nm=audio 5004 RTP/AVP 10\n",
+            // unsupported payload
+            "v=0\ns=-\nc=IN IP4 10.0.0.1\nt=0 0\nm=audio 5004 RTP/AVP 0\n",
+            // unsupported encoding
+            "v=0\ns=-\nc=IN IP4 10.0.0.1\nt=0 0\nm=audio 5004 RTP/AVP 96\n\
+             a=rtpmap:96 opus/48000/2\n",
+            // unsupported FEC
+            "v=0\ns=-\nc=IN IP4 10.0.0.1\nt=0 0\nm=audio 5004 RTP/AVP 10\n\
+             m=application 5006 UDP/FEC\na=fec-repair-flow:encoding-id=5\n",
+            // malformed line
+            "v=0\ns=-\nfoo\n",
+        ];
+
+        for sdp in &cases {
+            assert_matches!(
+                SdpSession::parse(sdp),
+                Err(ValidationError::SdpFormatError(_) | ValidationError::LayoutError(_))
+            );
+        }
+    }
+}
@@ -0,0 +1,91 @@
+// Copyright (c) Roc Streaming authors
+// Licensed under MPL-2.0
+use crate::dto::validate::*;
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Silence detection of sending stream.
+///
+/// When signal stays below threshold for hold time, stream stops sending
+/// packets and enters paused state, and resumes as soon as signal returns.
+/// Receivers keep playing silence while sender is paused.
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize, ToSchema)]
+pub struct SilenceDetectionSpec {
+    // peak level below which signal is considered silent, in dBFS;
+    // DEFAULT_THRESHOLD if omitted
+    #[serde(default)]
+    #[schema(nullable = false)]
+    pub threshold: Option<f32>,
+
+    // how long signal should be silent before stream is paused, in seconds;
+    // DEFAULT_HOLD_TIME if omitted
+    #[serde(default)]
+    #[schema(nullable = false)]
+    pub hold_time: Option<f32>,
+}
+
+impl SilenceDetectionSpec {
+    pub const DEFAULT_THRESHOLD: f32 = -60.0;
+    pub const DEFAULT_HOLD_TIME: f32 = 10.0;
+
+    pub const THRESHOLD_RANGE: (f32, f32) = (-120.0, 0.0);
+    pub const HOLD_TIME_RANGE: (f32, f32) = (0.01, 86400.0);
+
+    pub fn threshold(&self) -> f32 {
+        self.threshold.unwrap_or(Self::DEFAULT_THRESHOLD)
+    }
+
+    pub fn hold_time(&self) -> f32 {
+        self.hold_time.unwrap_or(Self::DEFAULT_HOLD_TIME)
+    }
+}
+
+impl Validate for SilenceDetectionSpec {
+    fn validate(&self) -> ValidationResult {
+        for (name, value, (min, max)) in [
+            ("threshold", self.threshold, Self::THRESHOLD_RANGE),
+            ("hold_time", self.hold_time, Self::HOLD_TIME_RANGE),
+        ] {
+            if let Some(value) = value
+                && !(min..=max).contains(&value)
+            {
+                return Err(ValidationError::LayoutError(format!(
+                    "{name} must be in range [{min}; {max}]"
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assertables::*;
+
+    #[test]
+    fn test_validate() {
+        let good_specs = vec![
+            SilenceDetectionSpec::default(),
+            SilenceDetectionSpec { threshold: Some(-50.0), hold_time: Some(30.0) },
+            SilenceDetectionSpec { threshold: Some(0.0), hold_time: Some(0.5) },
+        ];
+
+        for spec in &good_specs {
+            assert_ok!(spec.validate(), "{spec:?}");
+        }
+
+        let bad_specs = vec![
+            SilenceDetectionSpec { threshold: Some(6.0), hold_time: None },
+            SilenceDetectionSpec { threshold: Some(f32::NAN), hold_time: None },
+            SilenceDetectionSpec { threshold: None, hold_time: Some(0.0) },
+            SilenceDetectionSpec { threshold: None, hold_time: Some(-1.0) },
+        ];
+
+        for spec in &bad_specs {
+            assert_matches!(spec.validate(), Err(ValidationError::LayoutError(_)), "{spec:?}");
+        }
+    }
+}
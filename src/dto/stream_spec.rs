@@ -1,10 +1,16 @@
 // Copyright (c) Roc Streaming authors
 // Licensed under MPL-2.0
+use crate::dto::failover_spec::*;
+use crate::dto::processing_spec::*;
+use crate::dto::recording_spec::*;
+use crate::dto::schedule_spec::*;
+use crate::dto::silence_detection_spec::*;
 use crate::dto::uid::*;
 use crate::dto::uri::*;
 use crate::dto::validate::*;
 
 use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
 use utoipa::ToSchema;
 
 #[derive(Clone, PartialEq, Debug, Serialize, Deserialize, ToSchema)]
@@ -17,6 +23,90 @@ pub struct StreamSpec {
 
     pub source: ConnectionSpec,
     pub destination: ConnectionSpec,
+
+    #[serde(default)]
+    pub encoding: EncodingSpec,
+
+    // optional free-form identifier of client that owns the stream
+    #[serde(default)]
+    #[schema(nullable = false)]
+    pub owner: Option<String>,
+
+    // if set, stream is deleted when its lease is not renewed
+    // during this number of seconds (POST /streams/{uid}/renew)
+    #[serde(default)]
+    #[schema(nullable = false)]
+    pub lease_ttl: Option<u32>,
+
+    // when lease expires, maintained by server
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    #[schema(value_type = String, nullable = false, read_only)]
+    pub lease_expiration: Option<OffsetDateTime>,
+
+    // if set, stream is started and stopped according to schedule,
+    // otherwise stream is always running
+    #[serde(default)]
+    #[schema(nullable = false)]
+    pub schedule: Option<ScheduleSpec>,
+
+    // current state, maintained by server
+    #[serde(default)]
+    #[schema(read_only)]
+    pub state: StreamState,
+
+    // next planned state change, maintained by server
+    #[serde(default)]
+    #[schema(nullable = false, read_only)]
+    pub next_transition: Option<TransitionSpec>,
+
+    // why stream is in failed state, maintained by server
+    #[serde(default)]
+    #[schema(nullable = false, read_only)]
+    pub error: Option<String>,
+
+    // if set, audio written to destination is also recorded to files
+    #[serde(default)]
+    #[schema(nullable = false)]
+    pub recording: Option<RecordingSpec>,
+
+    // gain, mute, limiter, and loudness normalization;
+    // can be changed without restarting stream
+    #[serde(default)]
+    #[schema(nullable = false)]
+    pub processing: Option<ProcessingSpec>,
+
+    // if set, sending stream pauses while its signal is silent;
+    // allowed only when destination is external
+    #[serde(default)]
+    #[schema(nullable = false)]
+    pub silence_detection: Option<SilenceDetectionSpec>,
+
+    // if set, stream switches to fallback source when source fails
+    // or becomes silent, and back when source recovers
+    #[serde(default)]
+    #[schema(nullable = false)]
+    pub failover: Option<FailoverSpec>,
+
+    // which source is currently played: 0 is source, N is N-th failover
+    // source; present only when stream with failover is running,
+    // maintained by server
+    #[serde(default)]
+    #[schema(nullable = false, read_only)]
+    pub active_source: Option<u32>,
+
+    // incremented on every change, maintained by server
+    #[serde(default)]
+    #[schema(read_only)]
+    pub revision: u64,
+}
+
+impl StreamSpec {
+    /// Get source followed by failover sources, in order of preference.
+    pub fn all_sources(&self) -> Vec<&ConnectionSpec> {
+        std::iter::once(&self.source)
+            .chain(self.failover.iter().flat_map(|failover| &failover.sources))
+            .collect()
+    }
 }
 
 impl Validate for StreamSpec {
@@ -33,11 +123,110 @@ impl Validate for StreamSpec {
 
         self.source.validate()?;
         self.destination.validate()?;
+        self.encoding.validate()?;
+
+        if self.owner.as_ref().is_some_and(|owner| owner.is_empty()) {
+            return Err(ValidationError::LayoutError("owner must not be empty".into()));
+        }
+        if self.lease_ttl == Some(0) {
+            return Err(ValidationError::LayoutError("lease_ttl must be positive".into()));
+        }
+
+        if let Some(schedule) = &self.schedule {
+            schedule.validate()?;
+        }
+        if let Some(recording) = &self.recording {
+            recording.validate()?;
+        }
+        if let Some(processing) = &self.processing {
+            processing.validate()?;
+        }
+        if let Some(silence_detection) = &self.silence_detection {
+            if !matches!(self.destination, ConnectionSpec::External { .. }) {
+                return Err(ValidationError::LayoutError(
+                    "silence_detection is allowed only when destination is external".into(),
+                ));
+            }
+            silence_detection.validate()?;
+        }
+        if let Some(failover) = &self.failover {
+            failover.validate()?;
+
+            let sources = self.all_sources();
+            for (n, source) in sources.iter().enumerate() {
+                if sources[..n].contains(source) {
+                    return Err(ValidationError::LayoutError(
+                        "failover sources must differ from source and each other".into(),
+                    ));
+                }
+            }
+
+            let mut ports: Vec<u16> = sources
+                .iter()
+                .flat_map(|source| source.external_uris())
+                .filter_map(|uri| uri.port())
+                .filter(|port| *port != 0)
+                .collect();
+            let n_ports = ports.len();
+            ports.sort();
+            ports.dedup();
+            if ports.len() != n_ports {
+                return Err(ValidationError::LayoutError(
+                    "failover sources must use different ports than source and each other"
+                        .into(),
+                ));
+            }
+        }
+
+        // port 0 means that rocd should allocate local port, which makes
+        // sense only when we're receiving from external connection
+        if self.destination.external_uris().iter().any(|uri| uri.port() == Some(0)) {
+            return Err(ValidationError::LayoutError(
+                "automatic port allocation (port 0) is allowed only in source".into(),
+            ));
+        }
 
         Ok(())
     }
 }
 
+#[derive(
+    Copy,
+    Clone,
+    PartialEq,
+    Debug,
+    Default,
+    strum::Display,
+    strum::EnumIter,
+    Serialize,
+    Deserialize,
+    ToSchema,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum StreamState {
+    #[default]
+    Running,
+    Stopped,
+    // should be running, but can't be started or terminated
+    // with error; server periodically retries to start it
+    Failed,
+    // running, but doesn't send packets because signal is silent;
+    // resumes automatically when signal returns
+    Paused,
+}
+
+/// Planned change of stream state.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize, ToSchema)]
+pub struct TransitionSpec {
+    pub state: StreamState,
+
+    // RFC 3339 timestamp
+    #[serde(with = "time::serde::rfc3339")]
+    #[schema(value_type = String)]
+    pub time: OffsetDateTime,
+}
+
 #[derive(Copy, Clone, PartialEq, Debug, strum::Display, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "snake_case")]
 #[strum(serialize_all = "snake_case")]
@@ -64,13 +253,63 @@ pub enum ConnectionSpec {
 
         #[schema(value_type = String)]
         media_uri: Uri,
-        #[schema(value_type = String)]
-        repair_uri: Uri,
+        /// Required when media_uri has FEC scheme (e.g. rtp+rs8m),
+        /// not allowed otherwise (e.g. rtp).
+        #[serde(default)]
+        #[schema(value_type = String, nullable = false)]
+        repair_uri: Option<Uri>,
         #[schema(value_type = String)]
         control_uri: Uri,
+
+        // network interface used to send to or join multicast group,
+        // allowed only when addresses are multicast
+        #[serde(default)]
+        #[schema(nullable = false)]
+        multicast_interface: Option<String>,
+        // time-to-live of sent multicast packets,
+        // allowed only when addresses are multicast;
+        // not supported with FEC schemes, which are handled by libroc
+        #[serde(default)]
+        #[schema(nullable = false)]
+        multicast_ttl: Option<u8>,
     },
 }
 
+impl ConnectionSpec {
+    /// Get type of connection, determined by its fields.
+    pub fn layout_type(&self) -> ConnectionType {
+        match self {
+            ConnectionSpec::Endpoint { .. } => ConnectionType::Endpoint,
+            ConnectionSpec::External { .. } => ConnectionType::External,
+        }
+    }
+
+    /// Get FEC encoding of external connection.
+    /// Returns None for endpoint connection or unknown media_uri scheme.
+    pub fn fec_encoding(&self) -> Option<FecEncoding> {
+        match self {
+            ConnectionSpec::External { media_uri, .. } => {
+                FecEncoding::from_media_scheme(media_uri.scheme()?)
+            },
+            _ => None,
+        }
+    }
+
+    /// Get media, repair (if present), and control URIs of external connection.
+    /// Returns empty list for endpoint connection.
+    pub fn external_uris(&self) -> Vec<&Uri> {
+        match self {
+            ConnectionSpec::External { media_uri, repair_uri, control_uri, .. } => {
+                [Some(media_uri), repair_uri.as_ref(), Some(control_uri)]
+                    .into_iter()
+                    .flatten()
+                    .collect()
+            },
+            _ => Vec::new(),
+        }
+    }
+}
+
 impl Validate for ConnectionSpec {
     fn validate(&self) -> ValidationResult {
         let (specified_type, layout_type) = match self {
@@ -101,13 +340,20 @@ impl Validate for ConnectionSpec {
                     ));
                 }
             },
-            ConnectionSpec::External { media_uri, repair_uri, control_uri, .. } => {
+            ConnectionSpec::External {
+                media_uri,
+                repair_uri,
+                control_uri,
+                multicast_interface,
+                multicast_ttl,
+                ..
+            } => {
                 if media_uri.kind() != UriKind::External {
                     return Err(ValidationError::LayoutError(
                         "unexpected media_uri format, must be external URI".into(),
                     ));
                 }
-                if repair_uri.kind() != UriKind::External {
+                if repair_uri.as_ref().is_some_and(|uri| uri.kind() != UriKind::External) {
                     return Err(ValidationError::LayoutError(
                         "unexpected repair_uri format, must be external URI".into(),
                     ));
@@ -117,13 +363,233 @@ impl Validate for ConnectionSpec {
                         "unexpected control_uri format, must be external URI".into(),
                     ));
                 }
+
+                let fec_encoding = self.fec_encoding().ok_or_else(|| {
+                    ValidationError::LayoutError(format!(
+                        "unsupported media_uri scheme, must be one of: {}",
+                        FecEncoding::ALL.map(|fec| fec.media_scheme()).join(", ")
+                    ))
+                })?;
+                match (fec_encoding.repair_scheme(), repair_uri) {
+                    (Some(_), None) => {
+                        return Err(ValidationError::LayoutError(format!(
+                            "repair_uri is required when media_uri scheme is '{}'",
+                            fec_encoding.media_scheme()
+                        )));
+                    },
+                    (None, Some(_)) => {
+                        return Err(ValidationError::LayoutError(format!(
+                            "repair_uri is not allowed when media_uri scheme is '{}'",
+                            fec_encoding.media_scheme()
+                        )));
+                    },
+                    (Some(repair_scheme), Some(repair_uri)) => {
+                        if repair_uri.scheme() != Some(repair_scheme) {
+                            return Err(ValidationError::LayoutError(format!(
+                                "repair_uri scheme must be '{repair_scheme}' when media_uri \
+                                 scheme is '{}'",
+                                fec_encoding.media_scheme()
+                            )));
+                        }
+                    },
+                    (None, None) => (),
+                }
+                if control_uri.scheme() != Some("rtcp") {
+                    return Err(ValidationError::LayoutError(
+                        "control_uri scheme must be 'rtcp'".into(),
+                    ));
+                }
+
+                if repair_uri
+                    .as_ref()
+                    .is_some_and(|uri| uri.is_multicast() != media_uri.is_multicast())
+                {
+                    return Err(ValidationError::LayoutError(
+                        "media_uri and repair_uri must be either both multicast or both unicast"
+                            .into(),
+                    ));
+                }
+                if !media_uri.is_multicast()
+                    && (multicast_interface.is_some() || multicast_ttl.is_some())
+                {
+                    return Err(ValidationError::LayoutError(
+                        "multicast_interface and multicast_ttl are allowed only for multicast \
+                         media_uri"
+                            .into(),
+                    ));
+                }
+                if multicast_interface.as_ref().is_some_and(|iface| iface.is_empty()) {
+                    return Err(ValidationError::LayoutError(
+                        "multicast_interface must not be empty".into(),
+                    ));
+                }
+                if *multicast_ttl == Some(0) {
+                    return Err(ValidationError::LayoutError(
+                        "multicast_ttl must be positive".into(),
+                    ));
+                }
+
+                let mut ports: Vec<u16> = self
+                    .external_uris()
+                    .iter()
+                    .filter_map(|uri| uri.port())
+                    .filter(|port| *port != 0)
+                    .collect();
+                let n_ports = ports.len();
+                ports.sort();
+                ports.dedup();
+                if ports.len() != n_ports {
+                    return Err(ValidationError::LayoutError(
+                        "media_uri, repair_uri, and control_uri must have different ports"
+                            .into(),
+                    ));
+                }
+            },
+        }
+
+        Ok(())
+    }
+}
+
+/// FEC encoding of external connection.
+/// Determined by URI schemes of media_uri and repair_uri.
+#[derive(Copy, Clone, PartialEq, Debug, strum::Display)]
+#[strum(serialize_all = "snake_case")]
+pub enum FecEncoding {
+    /// No FEC, plain RTP.
+    Disable,
+    /// Reed-Solomon over GF(2^8) (RFC 6865).
+    Rs8m,
+    /// LDPC-Staircase (RFC 6816).
+    Ldpc,
+}
+
+impl FecEncoding {
+    pub const ALL: [FecEncoding; 3] =
+        [FecEncoding::Disable, FecEncoding::Rs8m, FecEncoding::Ldpc];
+
+    /// Find encoding by media_uri scheme.
+    pub fn from_media_scheme(scheme: &str) -> Option<Self> {
+        FecEncoding::ALL.into_iter().find(|fec| fec.media_scheme() == scheme)
+    }
+
+    /// Scheme of media_uri.
+    pub fn media_scheme(&self) -> &'static str {
+        match self {
+            FecEncoding::Disable => "rtp",
+            FecEncoding::Rs8m => "rtp+rs8m",
+            FecEncoding::Ldpc => "rtp+ldpc",
+        }
+    }
+
+    /// Scheme of repair_uri, if repair packets are used.
+    pub fn repair_scheme(&self) -> Option<&'static str> {
+        match self {
+            FecEncoding::Disable => None,
+            FecEncoding::Rs8m => Some("rs8m"),
+            FecEncoding::Ldpc => Some("ldpc"),
+        }
+    }
+}
+
+/// Audio encoding of media packets.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize, ToSchema)]
+pub struct EncodingSpec {
+    /// RTP payload type.
+    /// Static payload types 10 and 11 are defined by RFC 3551,
+    /// dynamic payload types (96-127) may be used for other encodings.
+    pub payload_type: u8,
+
+    pub sample_format: SampleFormat,
+    pub sample_rate: u32,
+    pub channels: u16,
+}
+
+impl EncodingSpec {
+    /// Static payload types from RFC 3551 that we support.
+    const STATIC_PAYLOADS: [(u8, SampleFormat, u32, u16); 2] =
+        [(10, SampleFormat::S16, 44100, 2), (11, SampleFormat::S16, 44100, 1)];
+
+    const MAX_CHANNELS: u16 = 8;
+
+    /// Find static payload type from RFC 3551.
+    pub fn from_static_payload(payload_type: u8) -> Option<Self> {
+        EncodingSpec::STATIC_PAYLOADS.into_iter().find(|entry| entry.0 == payload_type).map(
+            |(payload_type, sample_format, sample_rate, channels)| EncodingSpec {
+                payload_type,
+                sample_format,
+                sample_rate,
+                channels,
             },
+        )
+    }
+}
+
+/// L16 stereo 44100 Hz, the default encoding of Roc Toolkit.
+impl Default for EncodingSpec {
+    fn default() -> Self {
+        EncodingSpec::from_static_payload(10).unwrap()
+    }
+}
+
+impl Validate for EncodingSpec {
+    fn validate(&self) -> ValidationResult {
+        if self.payload_type > 127 {
+            return Err(ValidationError::LayoutError(
+                "payload_type must be in range [0; 127]".into(),
+            ));
+        }
+
+        if self.payload_type < 96 {
+            match EncodingSpec::from_static_payload(self.payload_type) {
+                Some(static_encoding) => {
+                    if *self != static_encoding {
+                        return Err(ValidationError::LayoutError(format!(
+                            "payload_type {} requires {} {} Hz {} channel(s)",
+                            static_encoding.payload_type,
+                            static_encoding.sample_format,
+                            static_encoding.sample_rate,
+                            static_encoding.channels,
+                        )));
+                    }
+                },
+                None => {
+                    return Err(ValidationError::LayoutError(format!(
+                        "unsupported static payload_type {}, use dynamic payload type \
+                         in range [96; 127]",
+                        self.payload_type
+                    )));
+                },
+            }
+        }
+
+        if !(8000..=192000).contains(&self.sample_rate) {
+            return Err(ValidationError::LayoutError(
+                "sample_rate must be in range [8000; 192000]".into(),
+            ));
+        }
+
+        if !(1..=EncodingSpec::MAX_CHANNELS).contains(&self.channels) {
+            return Err(ValidationError::LayoutError(format!(
+                "channels must be in range [1; {}]",
+                EncodingSpec::MAX_CHANNELS
+            )));
         }
 
         Ok(())
     }
 }
 
+#[derive(Copy, Clone, PartialEq, Debug, strum::Display, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum SampleFormat {
+    /// 16-bit signed big-endian PCM (L16).
+    S16,
+    /// 24-bit signed big-endian PCM (L24).
+    S24,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -141,7 +607,7 @@ mod tests {
 
         let good_spec = StreamSpec {
             stream_uri: Uri::from_stream(&stream_uid),
-            stream_uid: stream_uid,
+            stream_uid,
             source: ConnectionSpec::Endpoint {
                 connection_type: ConnectionType::Endpoint,
                 endpoint_uri: Uri::from_endpoint(&peer_uid, &endpoint_uid),
@@ -149,13 +615,142 @@ mod tests {
             destination: ConnectionSpec::External {
                 connection_type: ConnectionType::External,
                 media_uri: media_uri.clone(),
-                repair_uri: repair_uri.clone(),
+                repair_uri: Some(repair_uri.clone()),
                 control_uri: control_uri.clone(),
+                multicast_interface: None,
+                multicast_ttl: None,
             },
+            encoding: EncodingSpec::default(),
+            owner: None,
+            lease_ttl: None,
+            lease_expiration: None,
+            schedule: None,
+            state: StreamState::Running,
+            next_transition: None,
+            error: None,
+            recording: None,
+            processing: None,
+            silence_detection: None,
+            failover: None,
+            active_source: None,
+            revision: 0,
         };
 
         assert_ok!(good_spec.validate());
 
+        let good_lease_spec = {
+            let mut spec = good_spec.clone();
+            spec.owner = Some("controller-1".into());
+            spec.lease_ttl = Some(30);
+            spec
+        };
+
+        assert_ok!(good_lease_spec.validate());
+
+        let good_schedule_spec = {
+            let mut spec = good_spec.clone();
+            spec.schedule = Some(ScheduleSpec {
+                start_time: None,
+                stop_time: None,
+                duration: Some(600),
+                cron: Some("0 9 * * *".into()),
+            });
+            spec
+        };
+
+        assert_ok!(good_schedule_spec.validate());
+
+        let good_silence_spec = {
+            let mut spec = good_spec.clone();
+            spec.silence_detection =
+                Some(SilenceDetectionSpec { threshold: Some(-50.0), hold_time: Some(60.0) });
+            spec
+        };
+
+        assert_ok!(good_silence_spec.validate());
+
+        let good_multicast_spec = {
+            let mut spec = good_spec.clone();
+            spec.destination = ConnectionSpec::External {
+                connection_type: ConnectionType::External,
+                media_uri: Uri::parse("rtp+rs8m://239.1.2.3:10000").unwrap(),
+                repair_uri: Some(Uri::parse("rs8m://239.1.2.3:10001").unwrap()),
+                control_uri: Uri::parse("rtcp://239.1.2.3:10002").unwrap(),
+                multicast_interface: Some("eth0".into()),
+                multicast_ttl: Some(8),
+            };
+            spec
+        };
+
+        assert_ok!(good_multicast_spec.validate());
+
+        let good_failover_spec = {
+            let mut spec = good_spec.clone();
+            spec.failover = Some(FailoverSpec {
+                sources: vec![ConnectionSpec::External {
+                    connection_type: ConnectionType::External,
+                    media_uri: Uri::parse("rtp://0.0.0.0:10010").unwrap(),
+                    repair_uri: None,
+                    control_uri: Uri::parse("rtcp://0.0.0.0:10011").unwrap(),
+                    multicast_interface: None,
+                    multicast_ttl: None,
+                }],
+                silence_threshold: None,
+                silence_timeout: None,
+                recovery_time: None,
+            });
+            spec
+        };
+
+        assert_ok!(good_failover_spec.validate());
+
+        let good_nofec_spec = {
+            let mut spec = good_spec.clone();
+            spec.destination = ConnectionSpec::External {
+                connection_type: ConnectionType::External,
+                media_uri: Uri::parse("rtp://192.168.0.101:10000").unwrap(),
+                repair_uri: None,
+                control_uri: Uri::parse("rtcp://192.168.0.101:10001").unwrap(),
+                multicast_interface: None,
+                multicast_ttl: None,
+            };
+            spec
+        };
+
+        assert_ok!(good_nofec_spec.validate());
+
+        let good_l24_spec = {
+            let mut spec = good_spec.clone();
+            spec.encoding = EncodingSpec {
+                payload_type: 96,
+                sample_format: SampleFormat::S24,
+                sample_rate: 48000,
+                channels: 2,
+            };
+            spec
+        };
+
+        assert_ok!(good_l24_spec.validate());
+
+        let good_wildcard_spec = {
+            let mut spec = good_spec.clone();
+            spec.source = ConnectionSpec::External {
+                connection_type: ConnectionType::External,
+                media_uri: Uri::parse("rtp+rs8m://0.0.0.0:0").unwrap(),
+                repair_uri: Some(Uri::parse("rs8m://0.0.0.0:0").unwrap()),
+                control_uri: Uri::parse("rtcp://0.0.0.0:0").unwrap(),
+                multicast_interface: None,
+                multicast_ttl: None,
+            };
+            spec.destination = ConnectionSpec::Endpoint {
+                connection_type: ConnectionType::Endpoint,
+                endpoint_uri: Uri::from_endpoint(&peer_uid, &endpoint_uid),
+            };
+            spec
+        };
+
+        assert_ok!(good_wildcard_spec.validate());
+
         let bad_specs = vec![
             // invalid stream_uri type
             {
@@ -184,8 +779,10 @@ mod tests {
                 spec.destination = ConnectionSpec::External {
                     connection_type: ConnectionType::Endpoint,
                     media_uri: media_uri.clone(),
-                    repair_uri: repair_uri.clone(),
+                    repair_uri: Some(repair_uri.clone()),
                     control_uri: control_uri.clone(),
+                    multicast_interface: None,
+                    multicast_ttl: None,
                 };
                 spec
             },
@@ -202,10 +799,12 @@ mod tests {
             {
                 let mut spec = good_spec.clone();
                 spec.destination = ConnectionSpec::External {
-                    connection_type: ConnectionType::Endpoint,
+                    connection_type: ConnectionType::External,
                     media_uri: Uri::from_peer(&peer_uid),
-                    repair_uri: repair_uri.clone(),
+                    repair_uri: Some(repair_uri.clone()),
                     control_uri: control_uri.clone(),
+                    multicast_interface: None,
+                    multicast_ttl: None,
                 };
                 spec
             },
@@ -213,10 +812,12 @@ mod tests {
             {
                 let mut spec = good_spec.clone();
                 spec.destination = ConnectionSpec::External {
-                    connection_type: ConnectionType::Endpoint,
+                    connection_type: ConnectionType::External,
                     media_uri: media_uri.clone(),
-                    repair_uri: Uri::from_peer(&peer_uid),
+                    repair_uri: Some(Uri::from_peer(&peer_uid)),
                     control_uri: control_uri.clone(),
+                    multicast_interface: None,
+                    multicast_ttl: None,
                 };
                 spec
             },
@@ -224,13 +825,232 @@ mod tests {
             {
                 let mut spec = good_spec.clone();
                 spec.destination = ConnectionSpec::External {
-                    connection_type: ConnectionType::Endpoint,
+                    connection_type: ConnectionType::External,
                     media_uri: media_uri.clone(),
-                    repair_uri: repair_uri.clone(),
+                    repair_uri: Some(repair_uri.clone()),
                     control_uri: Uri::from_peer(&peer_uid),
+                    multicast_interface: None,
+                    multicast_ttl: None,
+                };
+                spec
+            },
+            // unsupported media_uri scheme
+            {
+                let mut spec = good_spec.clone();
+                spec.destination = ConnectionSpec::External {
+                    connection_type: ConnectionType::External,
+                    media_uri: Uri::parse("rtsp://192.168.0.101:10000").unwrap(),
+                    repair_uri: Some(repair_uri.clone()),
+                    control_uri: control_uri.clone(),
+                    multicast_interface: None,
+                    multicast_ttl: None,
+                };
+                spec
+            },
+            // missing repair_uri for FEC scheme
+            {
+                let mut spec = good_spec.clone();
+                spec.destination = ConnectionSpec::External {
+                    connection_type: ConnectionType::External,
+                    media_uri: media_uri.clone(),
+                    repair_uri: None,
+                    control_uri: control_uri.clone(),
+                    multicast_interface: None,
+                    multicast_ttl: None,
+                };
+                spec
+            },
+            // repair_uri for non-FEC scheme
+            {
+                let mut spec = good_spec.clone();
+                spec.destination = ConnectionSpec::External {
+                    connection_type: ConnectionType::External,
+                    media_uri: Uri::parse("rtp://192.168.0.101:10000").unwrap(),
+                    repair_uri: Some(repair_uri.clone()),
+                    control_uri: control_uri.clone(),
+                    multicast_interface: None,
+                    multicast_ttl: None,
+                };
+                spec
+            },
+            // repair_uri scheme doesn't match media_uri scheme
+            {
+                let mut spec = good_spec.clone();
+                spec.destination = ConnectionSpec::External {
+                    connection_type: ConnectionType::External,
+                    media_uri: media_uri.clone(),
+                    repair_uri: Some(Uri::parse("ldpc://192.168.0.101:10001").unwrap()),
+                    control_uri: control_uri.clone(),
+                    multicast_interface: None,
+                    multicast_ttl: None,
+                };
+                spec
+            },
+            // invalid control_uri scheme
+            {
+                let mut spec = good_spec.clone();
+                spec.destination = ConnectionSpec::External {
+                    connection_type: ConnectionType::External,
+                    media_uri: media_uri.clone(),
+                    repair_uri: Some(repair_uri.clone()),
+                    control_uri: Uri::parse("rtp://192.168.0.101:10002").unwrap(),
+                    multicast_interface: None,
+                    multicast_ttl: None,
+                };
+                spec
+            },
+            // multicast_interface for unicast address
+            {
+                let mut spec = good_spec.clone();
+                spec.destination = ConnectionSpec::External {
+                    connection_type: ConnectionType::External,
+                    media_uri: media_uri.clone(),
+                    repair_uri: Some(repair_uri.clone()),
+                    control_uri: control_uri.clone(),
+                    multicast_interface: Some("eth0".into()),
+                    multicast_ttl: None,
+                };
+                spec
+            },
+            // multicast_ttl for unicast address
+            {
+                let mut spec = good_spec.clone();
+                spec.destination = ConnectionSpec::External {
+                    connection_type: ConnectionType::External,
+                    media_uri: media_uri.clone(),
+                    repair_uri: Some(repair_uri.clone()),
+                    control_uri: control_uri.clone(),
+                    multicast_interface: None,
+                    multicast_ttl: Some(8),
                 };
                 spec
             },
+            // multicast media_uri and unicast repair_uri
+            {
+                let mut spec = good_multicast_spec.clone();
+                if let ConnectionSpec::External { repair_uri: uri, .. } = &mut spec.destination
+                {
+                    *uri = Some(repair_uri.clone());
+                }
+                spec
+            },
+            // empty multicast_interface
+            {
+                let mut spec = good_multicast_spec.clone();
+                if let ConnectionSpec::External { multicast_interface, .. } =
+                    &mut spec.destination
+                {
+                    *multicast_interface = Some("".into());
+                }
+                spec
+            },
+            // zero multicast_ttl
+            {
+                let mut spec = good_multicast_spec.clone();
+                if let ConnectionSpec::External { multicast_ttl, .. } = &mut spec.destination {
+                    *multicast_ttl = Some(0);
+                }
+                spec
+            },
+            // wildcard port in destination
+            {
+                let mut spec = good_spec.clone();
+                if let ConnectionSpec::External { media_uri, .. } = &mut spec.destination {
+                    *media_uri = Uri::parse("rtp+rs8m://192.168.0.101:0").unwrap();
+                }
+                spec
+            },
+            // same port in media_uri and control_uri
+            {
+                let mut spec = good_spec.clone();
+                if let ConnectionSpec::External { control_uri, .. } = &mut spec.destination {
+                    *control_uri = Uri::parse("rtcp://192.168.0.101:10000").unwrap();
+                }
+                spec
+            },
+            // empty owner
+            {
+                let mut spec = good_lease_spec.clone();
+                spec.owner = Some("".into());
+                spec
+            },
+            // zero lease_ttl
+            {
+                let mut spec = good_lease_spec.clone();
+                spec.lease_ttl = Some(0);
+                spec
+            },
+            // invalid schedule
+            {
+                let mut spec = good_schedule_spec.clone();
+                spec.schedule.as_mut().unwrap().duration = None;
+                spec
+            },
+            // static payload_type with mismatching encoding
+            {
+                let mut spec = good_spec.clone();
+                spec.encoding.sample_rate = 48000;
+                spec
+            },
+            // unsupported static payload_type
+            {
+                let mut spec = good_spec.clone();
+                spec.encoding.payload_type = 0;
+                spec
+            },
+            // invalid payload_type
+            {
+                let mut spec = good_l24_spec.clone();
+                spec.encoding.payload_type = 128;
+                spec
+            },
+            // invalid sample_rate
+            {
+                let mut spec = good_l24_spec.clone();
+                spec.encoding.sample_rate = 0;
+                spec
+            },
+            // invalid channels
+            {
+                let mut spec = good_l24_spec.clone();
+                spec.encoding.channels = 0;
+                spec
+            },
+            // silence detection in receiving stream
+            {
+                let mut spec = good_silence_spec.clone();
+                std::mem::swap(&mut spec.source, &mut spec.destination);
+                spec
+            },
+            // failover source same as source
+            {
+                let mut spec = good_failover_spec.clone();
+                spec.failover.as_mut().unwrap().sources = vec![spec.source.clone()];
+                spec
+            },
+            // failover sources with same ports
+            {
+                let mut spec = good_failover_spec.clone();
+                let failover = spec.failover.as_mut().unwrap();
+                let mut source = failover.sources[0].clone();
+                if let ConnectionSpec::External { media_uri, .. } = &mut source {
+                    *media_uri = Uri::parse("rtp://127.0.0.1:10010").unwrap();
+                }
+                failover.sources.push(source);
+                spec
+            },
+            // invalid failover
+            {
+                let mut spec = good_failover_spec.clone();
+                spec.failover.as_mut().unwrap().sources.clear();
+                spec
+            },
+            // invalid silence detection threshold
+            {
+                let mut spec = good_silence_spec.clone();
+                spec.silence_detection.as_mut().unwrap().threshold = Some(10.0);
+                spec
+            },
         ];
 
         for spec in &bad_specs {
@@ -0,0 +1,114 @@
+// Copyright (c) Roc Streaming authors
+// Licensed under MPL-2.0
+use crate::dto::uid::*;
+use crate::dto::validate::*;
+
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+/// API access token, as stored on server.
+///
+/// The secret part of token is shown to user only once, when token is
+/// created. Server keeps only its hash.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct TokenSpec {
+    pub token_uid: Uid,
+
+    pub scope: TokenScope,
+
+    // human-readable name, e.g. name of client using token
+    pub name: String,
+
+    // hex-encoded SHA-256 hash of secret
+    pub secret_hash: String,
+
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+}
+
+/// What requests token allows.
+/// Each scope includes everything allowed by previous ones.
+#[derive(
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Debug,
+    strum::Display,
+    clap::ValueEnum,
+    Serialize,
+    Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+#[value(rename_all = "snake_case")]
+pub enum TokenScope {
+    // reading anything
+    ReadOnly,
+    // managing streams, groups and recordings
+    Control,
+    // managing peers and endpoints
+    Admin,
+}
+
+impl Validate for TokenSpec {
+    fn validate(&self) -> ValidationResult {
+        if self.name.is_empty() {
+            return Err(ValidationError::LayoutError("token name can't be empty".into()));
+        }
+
+        if self.secret_hash.len() != 64
+            || !self.secret_hash.chars().all(|ch| ch.is_ascii_hexdigit())
+        {
+            return Err(ValidationError::LayoutError("invalid token secret_hash".into()));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assertables::*;
+
+    #[test]
+    fn test_validate() {
+        let good_spec = TokenSpec {
+            token_uid: Uid::generate_random(),
+            scope: TokenScope::Admin,
+            name: "test".into(),
+            secret_hash: "0123456789abcdef".repeat(4),
+            created_at: OffsetDateTime::now_utc(),
+        };
+
+        assert_ok!(good_spec.validate());
+
+        let bad_specs = vec![
+            // empty name
+            {
+                let mut spec = good_spec.clone();
+                spec.name = String::new();
+                spec
+            },
+            // short hash
+            {
+                let mut spec = good_spec.clone();
+                spec.secret_hash = "0123456789abcdef".into();
+                spec
+            },
+            // non-hex hash
+            {
+                let mut spec = good_spec.clone();
+                spec.secret_hash = "x".repeat(64);
+                spec
+            },
+        ];
+
+        for spec in &bad_specs {
+            assert_matches!(spec.validate(), Err(ValidationError::LayoutError(_)));
+        }
+    }
+}
@@ -16,6 +16,12 @@ pub enum ValidationError {
     #[error("invalid URL '{0}': {1}")]
     UrlFormatError(String, #[source] url::ParseError),
 
+    #[error("invalid cron expression '{0}': {1}")]
+    CronFormatError(String, String),
+
+    #[error("invalid SDP: {0}")]
+    SdpFormatError(String),
+
     #[error("illformed UTF-8 string: {0}")]
     Utf8Error(#[from] Utf8Error),
 }
@@ -0,0 +1,22 @@
+// Copyright (c) Roc Streaming authors
+// Licensed under MPL-2.0
+use crate::dto::driver::*;
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Version and build configuration of server.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize, ToSchema)]
+pub struct VersionSpec {
+    // crate version, e.g. "0.1.0"
+    pub version: String,
+
+    // cargo features enabled at build time
+    pub features: Vec<String>,
+
+    // drivers enabled at build time, in order of probing
+    pub supported_drivers: Vec<DriverId>,
+
+    // driver in use
+    pub active_driver: DriverId,
+}
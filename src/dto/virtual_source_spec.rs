@@ -0,0 +1,230 @@
+// Copyright (c) Roc Streaming authors
+// Licensed under MPL-2.0
+use crate::dto::validate::*;
+
+use serde::{Deserialize, Serialize};
+use std::path::{Component, Path};
+use utoipa::ToSchema;
+
+/// Built-in signal generator used as endpoint.
+///
+/// Virtual source endpoint can be used as source of any stream, e.g. to
+/// check a speaker chain without external player. Parameters can be changed
+/// while streams are running, and are applied immediately.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize, ToSchema)]
+pub struct VirtualSourceSpec {
+    pub signal: SignalType,
+
+    // frequency of sine, or start frequency of sweep, in Hz
+    #[serde(default)]
+    #[schema(nullable = false)]
+    pub frequency: Option<f32>,
+
+    // end frequency of sweep, in Hz
+    #[serde(default)]
+    #[schema(nullable = false)]
+    pub end_frequency: Option<f32>,
+
+    // duration of sweep, in seconds; sweep is repeated after it ends
+    #[serde(default)]
+    #[schema(nullable = false)]
+    pub sweep_duration: Option<f32>,
+
+    // peak level of sine and sweep, RMS level of noise (in dBFS),
+    // or gain applied to file (in dB)
+    #[serde(default)]
+    #[schema(nullable = false)]
+    pub level: Option<f32>,
+
+    // zero-based index of channel that plays signal, other channels are
+    // silent; if omitted, signal is played to all channels
+    #[serde(default)]
+    #[schema(nullable = false)]
+    pub channel: Option<u16>,
+
+    // path to WAV file, relative to media directory of server
+    #[serde(default)]
+    #[schema(nullable = false)]
+    pub file_path: Option<String>,
+}
+
+impl VirtualSourceSpec {
+    pub const DEFAULT_FREQUENCY: f32 = 1000.0;
+    pub const DEFAULT_SWEEP_START: f32 = 20.0;
+    pub const DEFAULT_SWEEP_END: f32 = 20000.0;
+    pub const DEFAULT_SWEEP_DURATION: f32 = 10.0;
+    pub const DEFAULT_LEVEL: f32 = -20.0;
+    pub const DEFAULT_FILE_GAIN: f32 = 0.0;
+
+    /// Get frequency or default for signal type.
+    pub fn frequency(&self) -> f32 {
+        match self.signal {
+            SignalType::Sweep => self.frequency.unwrap_or(Self::DEFAULT_SWEEP_START),
+            _ => self.frequency.unwrap_or(Self::DEFAULT_FREQUENCY),
+        }
+    }
+
+    /// Get sweep end frequency or default.
+    pub fn end_frequency(&self) -> f32 {
+        self.end_frequency.unwrap_or(Self::DEFAULT_SWEEP_END)
+    }
+
+    /// Get sweep duration or default.
+    pub fn sweep_duration(&self) -> f32 {
+        self.sweep_duration.unwrap_or(Self::DEFAULT_SWEEP_DURATION)
+    }
+
+    /// Get level or default for signal type.
+    pub fn level(&self) -> f32 {
+        match self.signal {
+            SignalType::WavFile => self.level.unwrap_or(Self::DEFAULT_FILE_GAIN),
+            _ => self.level.unwrap_or(Self::DEFAULT_LEVEL),
+        }
+    }
+}
+
+impl Validate for VirtualSourceSpec {
+    fn validate(&self) -> ValidationResult {
+        let uses_frequency = matches!(self.signal, SignalType::Sine | SignalType::Sweep);
+        let is_sweep = self.signal == SignalType::Sweep;
+        let is_file = self.signal == SignalType::WavFile;
+
+        for (name, value, allowed) in [
+            ("frequency", self.frequency, uses_frequency),
+            ("end_frequency", self.end_frequency, is_sweep),
+            ("sweep_duration", self.sweep_duration, is_sweep),
+        ] {
+            match value {
+                Some(_) if !allowed => {
+                    return Err(ValidationError::LayoutError(format!(
+                        "{name} is not allowed when signal is '{}'",
+                        self.signal
+                    )));
+                },
+                Some(value) if !value.is_finite() || value <= 0.0 => {
+                    return Err(ValidationError::LayoutError(format!(
+                        "{name} must be positive"
+                    )));
+                },
+                _ => (),
+            }
+        }
+
+        if let Some(level) = self.level
+            && (!level.is_finite() || (!is_file && level > 0.0))
+        {
+            return Err(ValidationError::LayoutError(
+                "level must be finite and not above 0 dBFS".into(),
+            ));
+        }
+
+        match &self.file_path {
+            None if is_file => {
+                return Err(ValidationError::LayoutError(
+                    "file_path is required when signal is 'wav_file'".into(),
+                ));
+            },
+            Some(_) if !is_file => {
+                return Err(ValidationError::LayoutError(format!(
+                    "file_path is not allowed when signal is '{}'",
+                    self.signal
+                )));
+            },
+            Some(file_path) => {
+                // must not point outside of media directory
+                let path = Path::new(file_path);
+                if file_path.is_empty()
+                    || !path.components().all(|comp| matches!(comp, Component::Normal(_)))
+                {
+                    return Err(ValidationError::LayoutError(
+                        "file_path must be a relative path without '..'".into(),
+                    ));
+                }
+            },
+            None => (),
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Debug, strum::Display, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum SignalType {
+    Sine,
+    PinkNoise,
+    // logarithmic sine sweep
+    Sweep,
+    // WAV file played in a loop
+    WavFile,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assertables::*;
+
+    fn make_spec(signal: SignalType) -> VirtualSourceSpec {
+        VirtualSourceSpec {
+            signal,
+            frequency: None,
+            end_frequency: None,
+            sweep_duration: None,
+            level: None,
+            channel: None,
+            file_path: None,
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        let good_specs = vec![
+            make_spec(SignalType::Sine),
+            make_spec(SignalType::PinkNoise),
+            VirtualSourceSpec {
+                frequency: Some(100.0),
+                end_frequency: Some(10000.0),
+                sweep_duration: Some(5.0),
+                level: Some(-6.0),
+                channel: Some(1),
+                ..make_spec(SignalType::Sweep)
+            },
+            VirtualSourceSpec {
+                file_path: Some("tones/pink.wav".into()),
+                level: Some(6.0),
+                ..make_spec(SignalType::WavFile)
+            },
+        ];
+
+        for spec in &good_specs {
+            assert_ok!(spec.validate(), "{spec:?}");
+        }
+
+        let bad_specs = vec![
+            VirtualSourceSpec { frequency: Some(0.0), ..make_spec(SignalType::Sine) },
+            VirtualSourceSpec { frequency: Some(100.0), ..make_spec(SignalType::PinkNoise) },
+            VirtualSourceSpec { end_frequency: Some(100.0), ..make_spec(SignalType::Sine) },
+            VirtualSourceSpec { sweep_duration: Some(-1.0), ..make_spec(SignalType::Sweep) },
+            VirtualSourceSpec { level: Some(3.0), ..make_spec(SignalType::Sine) },
+            VirtualSourceSpec { level: Some(f32::NAN), ..make_spec(SignalType::Sine) },
+            VirtualSourceSpec {
+                file_path: Some("a.wav".into()),
+                ..make_spec(SignalType::Sine)
+            },
+            make_spec(SignalType::WavFile),
+            VirtualSourceSpec {
+                file_path: Some("../rocd.db".into()),
+                ..make_spec(SignalType::WavFile)
+            },
+            VirtualSourceSpec {
+                file_path: Some("/etc/passwd".into()),
+                ..make_spec(SignalType::WavFile)
+            },
+        ];
+
+        for spec in &bad_specs {
+            assert_matches!(spec.validate(), Err(ValidationError::LayoutError(_)), "{spec:?}");
+        }
+    }
+}
@@ -0,0 +1,118 @@
+// Copyright (c) Roc Streaming authors
+// Licensed under MPL-2.0
+use crate::dto::EventSpec;
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tokio::sync::broadcast;
+
+/// Delivers events from server components to subscribers.
+///
+/// Publishing never blocks. If subscriber doesn't keep up, it misses
+/// oldest events, and its receiver reports how many were lost.
+///
+/// Level events are frequent and costly to produce, so they're published
+/// only while someone watches levels (see watch_levels()).
+pub struct EventBus {
+    sender: broadcast::Sender<EventSpec>,
+    level_watchers: Arc<AtomicUsize>,
+}
+
+/// Keeps level metering enabled until dropped.
+pub struct LevelWatcher {
+    level_watchers: Arc<AtomicUsize>,
+}
+
+impl Drop for LevelWatcher {
+    fn drop(&mut self) {
+        self.level_watchers.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl EventBus {
+    /// How many events are buffered for each subscriber.
+    const CAPACITY: usize = 256;
+
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(Self::CAPACITY);
+
+        EventBus { sender, level_watchers: Arc::new(AtomicUsize::new(0)) }
+    }
+
+    /// Send event to all current subscribers.
+    /// Event is dropped if there are no subscribers.
+    pub fn publish(&self, event: EventSpec) {
+        tracing::debug!("publishing event {} for {}", event.event_type, event.object_uri);
+
+        let _ = self.sender.send(event);
+    }
+
+    /// Receive events published from now on.
+    pub fn subscribe(&self) -> broadcast::Receiver<EventSpec> {
+        self.sender.subscribe()
+    }
+
+    /// Number of active subscribers.
+    pub fn subscriber_count(&self) -> usize {
+        self.sender.receiver_count()
+    }
+
+    /// Register interest in level events.
+    /// Levels are measured and published while returned watcher is alive.
+    pub fn watch_levels(&self) -> LevelWatcher {
+        self.level_watchers.fetch_add(1, Ordering::SeqCst);
+
+        LevelWatcher { level_watchers: Arc::clone(&self.level_watchers) }
+    }
+
+    /// Check if there are alive level watchers.
+    pub fn has_level_watchers(&self) -> bool {
+        self.level_watchers.load(Ordering::SeqCst) != 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dto::{EventType, Uid, Uri};
+    use assertables::*;
+
+    #[tokio::test]
+    async fn test_publish() {
+        let bus = EventBus::new();
+        let uri = Uri::from_stream(&Uid::generate_random());
+
+        // no subscribers, event is dropped
+        bus.publish(EventSpec::new(EventType::StreamPaused, &uri));
+        assert_eq!(bus.subscriber_count(), 0);
+
+        let mut receiver = bus.subscribe();
+        assert_eq!(bus.subscriber_count(), 1);
+
+        bus.publish(EventSpec::new(EventType::StreamResumed, &uri));
+
+        let event = receiver.recv().await.unwrap();
+        assert_eq!(event.event_type, EventType::StreamResumed);
+        assert_eq!(event.object_uri, uri);
+        assert_err!(receiver.try_recv());
+
+        drop(receiver);
+        assert_eq!(bus.subscriber_count(), 0);
+    }
+
+    #[test]
+    fn test_watch_levels() {
+        let bus = EventBus::new();
+        assert!(!bus.has_level_watchers());
+
+        let watcher1 = bus.watch_levels();
+        let watcher2 = bus.watch_levels();
+        assert!(bus.has_level_watchers());
+
+        drop(watcher1);
+        assert!(bus.has_level_watchers());
+
+        drop(watcher2);
+        assert!(!bus.has_level_watchers());
+    }
+}
@@ -0,0 +1,5 @@
+// Copyright (c) Roc Streaming authors
+// Licensed under MPL-2.0
+mod event_bus;
+
+pub use self::event_bus::*;
@@ -1,30 +1,217 @@
 // Copyright (c) Roc Streaming authors
 // Licensed under MPL-2.0
+use crate::audio::*;
 use crate::drivers::*;
 use crate::dto::*;
+use crate::io_endpoints::error::*;
+use crate::io_endpoints::virtual_source::*;
+use crate::io_endpoints::wav_reader::*;
+use crate::vault::{Vault, VaultError};
 
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::result;
+use std::sync::{Arc, Mutex as SyncMutex};
+use tokio::sync::Mutex;
+
+pub type Result<T> = result::Result<T, EndpointError>;
 
 pub struct EndpointDispatcher {
     driver: Arc<dyn Driver>,
+    vault: Arc<Vault>,
+    // directory with media files for virtual sources
+    media_dir: PathBuf,
+    // parameters of virtual sources which were opened at least once,
+    // shared with running pipelines
+    controls: SyncMutex<HashMap<Uid, Arc<SignalControl>>>,
+    // serializes revision checks and endpoint writes
+    write_mutex: Mutex<()>,
 }
 
 impl EndpointDispatcher {
-    pub fn new(driver: &Arc<dyn Driver>) -> Self {
-        EndpointDispatcher { driver: Arc::clone(driver) }
+    pub fn new(driver: &Arc<dyn Driver>, vault: &Arc<Vault>, media_dir: &Path) -> Self {
+        EndpointDispatcher {
+            driver: Arc::clone(driver),
+            vault: Arc::clone(vault),
+            media_dir: media_dir.into(),
+            controls: SyncMutex::new(HashMap::new()),
+            write_mutex: Mutex::new(()),
+        }
+    }
+
+    /// Get ID of driver used for device endpoints.
+    pub fn driver_id(&self) -> DriverId {
+        self.driver.id()
+    }
+
+    /// Check if driver used for device endpoints is operational.
+    pub fn is_driver_up(&self) -> bool {
+        self.driver.is_up()
+    }
+
+    pub async fn get_all(&self, peer_uid: &Uid) -> Result<Vec<EndpointSpec>> {
+        let mut endpoints = vec![self.device_endpoint(
+            &Uid::parse("111111-222222-333333").unwrap(),
+            &Uid::parse(DEFAULT_DEVICE_UID).unwrap(),
+        )];
+
+        let mut virtual_endpoints = Vec::new();
+        for endpoint_uid in self.vault.list_endpoints().await?.iter() {
+            if let Some(endpoint) = self.load_virtual(endpoint_uid).await?
+                && endpoint.endpoint_uri.peer_uid() == Some(*peer_uid)
+            {
+                virtual_endpoints.push(endpoint);
+            }
+        }
+        virtual_endpoints.sort_by_key(|endpoint| endpoint.endpoint_uid.to_string());
+        endpoints.extend(virtual_endpoints);
+
+        Ok(endpoints)
+    }
+
+    pub async fn get_endpoint(
+        &self, peer_uid: &Uid, endpoint_uid: &Uid,
+    ) -> Result<EndpointSpec> {
+        if let Some(endpoint) = self.load_virtual(endpoint_uid).await?
+            && endpoint.endpoint_uri.peer_uid() == Some(*peer_uid)
+        {
+            return Ok(endpoint);
+        }
+
+        Ok(self.device_endpoint(peer_uid, endpoint_uid))
+    }
+
+    /// Create or update virtual endpoint.
+    /// If condition is given, fails unless stored endpoint matches it.
+    /// Device endpoints are managed by driver and can't be modified.
+    /// Running streams that use the endpoint pick up new parameters
+    /// without restart.
+    pub async fn update_endpoint(
+        &self, endpoint: EndpointSpec, if_match: Option<&RevisionMatch>,
+    ) -> Result<EndpointSpec> {
+        let _guard = self.write_mutex.lock().await;
+
+        let revision =
+            self.load_virtual(&endpoint.endpoint_uid).await?.map(|stored| stored.revision);
+        if if_match.is_some_and(|if_match| !if_match.matches(revision)) {
+            return Err(EndpointError::RevisionError(endpoint.endpoint_uid));
+        }
+
+        self.write_endpoint(endpoint, revision).await
+    }
+
+    /// Apply JSON Merge Patch to endpoint and save result.
+    /// If condition is given, fails unless endpoint matches it.
+    /// Same restrictions as in update_endpoint() apply.
+    pub async fn patch_endpoint(
+        &self, peer_uid: &Uid, endpoint_uid: &Uid, patch: &serde_json::Value,
+        if_match: Option<&RevisionMatch>,
+    ) -> Result<EndpointSpec> {
+        let _guard = self.write_mutex.lock().await;
+
+        let endpoint = self.get_endpoint(peer_uid, endpoint_uid).await?;
+        if if_match.is_some_and(|if_match| !if_match.matches(Some(endpoint.revision))) {
+            return Err(EndpointError::RevisionError(*endpoint_uid));
+        }
+
+        let patched_endpoint = apply_merge_patch(&endpoint, patch)?;
+        if patched_endpoint.endpoint_uri != endpoint.endpoint_uri {
+            return Err(
+                ValidationError::LayoutError("endpoint_uri can't be changed".into()).into()
+            );
+        }
+
+        let revision = endpoint.virtual_source.is_some().then_some(endpoint.revision);
+
+        self.write_endpoint(patched_endpoint, revision).await
     }
 
-    pub async fn get_all(&self, _peer_uid: &Uid) -> Vec<EndpointSpec> {
-        vec![
-            self.get_endpoint(
-                &Uid::parse("111111-222222-333333").unwrap(),
-                &Uid::parse("444444-555555-666666").unwrap(),
-            )
-            .await,
-        ]
+    /// Delete virtual endpoint.
+    /// If condition is given, fails unless stored endpoint matches it.
+    /// Running streams that use the endpoint keep playing last signal
+    /// until they're restarted.
+    pub async fn delete_endpoint(
+        &self, peer_uid: &Uid, endpoint_uid: &Uid, if_match: Option<&RevisionMatch>,
+    ) -> Result<()> {
+        let _guard = self.write_mutex.lock().await;
+
+        let revision = match self.load_virtual(endpoint_uid).await? {
+            Some(endpoint) if endpoint.endpoint_uri.peer_uid() == Some(*peer_uid) => {
+                endpoint.revision
+            },
+            _ => return Err(EndpointError::NotFoundError(*endpoint_uid)),
+        };
+        if if_match.is_some_and(|if_match| !if_match.matches(Some(revision))) {
+            return Err(EndpointError::RevisionError(*endpoint_uid));
+        }
+
+        self.vault.remove_endpoint(endpoint_uid).await?;
+        self.controls.lock().unwrap().remove(endpoint_uid);
+
+        tracing::debug!("deleted virtual endpoint {endpoint_uid}");
+
+        Ok(())
     }
 
-    pub async fn get_endpoint(&self, peer_uid: &Uid, endpoint_uid: &Uid) -> EndpointSpec {
+    /// Open endpoint for capturing audio from it.
+    /// Virtual endpoints are handled here, others are opened via driver.
+    pub async fn open_source(
+        &self, endpoint_uri: &Uri, format: AudioFormat,
+    ) -> Result<Box<dyn AudioSource>> {
+        let Some(endpoint) = self.find_virtual(endpoint_uri).await? else {
+            return Ok(self.driver.open_source(endpoint_uri, format).await?);
+        };
+
+        let control = self.get_control(&endpoint).await?;
+        let source = VirtualSource::open(&control, format)
+            .map_err(|err| EndpointError::SignalError(endpoint.endpoint_uid, err))?;
+
+        Ok(Box::new(source))
+    }
+
+    /// Open endpoint for playing audio to it.
+    pub async fn open_sink(
+        &self, endpoint_uri: &Uri, format: AudioFormat,
+    ) -> Result<Box<dyn AudioSink>> {
+        if let Some(endpoint) = self.find_virtual(endpoint_uri).await? {
+            return Err(EndpointError::DriverError(DriverError::EndpointError(
+                endpoint.endpoint_uri,
+                "virtual source can't be used as destination".into(),
+            )));
+        }
+
+        Ok(self.driver.open_sink(endpoint_uri, format).await?)
+    }
+
+    /// Save virtual endpoint, which stored revision is given (none if
+    /// it's new), and pass new parameters to its sources.
+    /// Must be called with write_mutex locked.
+    async fn write_endpoint(
+        &self, mut endpoint: EndpointSpec, revision: Option<u64>,
+    ) -> Result<EndpointSpec> {
+        endpoint.validate()?;
+
+        let Some(virtual_source) = &endpoint.virtual_source else {
+            return Err(EndpointError::ReadOnlyError(endpoint.endpoint_uid));
+        };
+
+        // load file before saving, to report missing or broken file to user
+        let params = self.load_params(virtual_source).await?;
+
+        endpoint.revision = revision.map_or(1, |revision| revision + 1);
+
+        self.vault.write_endpoint(&Arc::new(endpoint.clone())).await?;
+
+        if let Some(control) = self.controls.lock().unwrap().get(&endpoint.endpoint_uid) {
+            control.update(params);
+        }
+
+        tracing::debug!("updated virtual endpoint {}", endpoint.endpoint_uid);
+
+        Ok(endpoint)
+    }
+
+    fn device_endpoint(&self, peer_uid: &Uid, endpoint_uid: &Uid) -> EndpointSpec {
         EndpointSpec {
             endpoint_uri: Uri::from_endpoint(peer_uid, endpoint_uid),
             endpoint_uid: *endpoint_uid,
@@ -33,6 +220,78 @@ impl EndpointDispatcher {
             driver: DriverId::Pipewire,
             display_name: "Display Name".into(),
             system_name: "system_name".into(),
+            virtual_source: None,
+            revision: 0,
+        }
+    }
+
+    /// Read virtual endpoint from vault, if it exists.
+    async fn load_virtual(&self, endpoint_uid: &Uid) -> Result<Option<EndpointSpec>> {
+        match self.vault.read_endpoint(endpoint_uid).await {
+            Ok(endpoint) if endpoint.virtual_source.is_some() => Ok(Some((*endpoint).clone())),
+            Ok(_) => Ok(None),
+            Err(VaultError::UidNotFound(_)) => Ok(None),
+            Err(err) => Err(err.into()),
         }
     }
+
+    async fn find_virtual(&self, endpoint_uri: &Uri) -> Result<Option<EndpointSpec>> {
+        let Some(endpoint_uid) = endpoint_uri.endpoint_uid() else {
+            return Ok(None);
+        };
+
+        Ok(self
+            .load_virtual(&endpoint_uid)
+            .await?
+            .filter(|endpoint| endpoint.endpoint_uri.peer_uid() == endpoint_uri.peer_uid()))
+    }
+
+    /// Get control shared by sources of virtual endpoint, creating it
+    /// on first use (e.g. after restart).
+    async fn get_control(&self, endpoint: &EndpointSpec) -> Result<Arc<SignalControl>> {
+        if let Some(control) = self.controls.lock().unwrap().get(&endpoint.endpoint_uid) {
+            return Ok(Arc::clone(control));
+        }
+
+        let params = self.load_params(endpoint.virtual_source.as_ref().unwrap()).await?;
+
+        Ok(Arc::clone(
+            self.controls
+                .lock()
+                .unwrap()
+                .entry(endpoint.endpoint_uid)
+                .or_insert_with(|| Arc::new(SignalControl::new(params))),
+        ))
+    }
+
+    async fn load_params(&self, spec: &VirtualSourceSpec) -> Result<SignalParams> {
+        let clip = match &spec.file_path {
+            Some(file_path) => Some(Arc::new(self.load_clip(file_path).await?)),
+            None => None,
+        };
+
+        Ok(SignalParams { spec: spec.clone(), clip })
+    }
+
+    async fn load_clip(&self, file_path: &str) -> Result<WavClip> {
+        let path = self.media_dir.join(file_path);
+        let media_error = |err: std::io::Error| {
+            EndpointError::MediaError(format!("{}: {err}", path.display()))
+        };
+
+        let size = tokio::fs::metadata(&path).await.map_err(media_error)?.len();
+        if size > WavClip::MAX_FILE_SIZE {
+            return Err(EndpointError::MediaError(format!(
+                "{}: file is larger than {} bytes",
+                path.display(),
+                WavClip::MAX_FILE_SIZE
+            )));
+        }
+
+        let data = tokio::fs::read(&path).await.map_err(media_error)?;
+
+        tokio::task::spawn_blocking(move || WavClip::parse(&data))
+            .await
+            .map_err(|err| EndpointError::MediaError(err.to_string()))?
+    }
 }
@@ -0,0 +1,42 @@
+// Copyright (c) Roc Streaming authors
+// Licensed under MPL-2.0
+use crate::drivers::DriverError;
+use crate::dto::{Uid, ValidationError};
+use crate::vault::VaultError;
+
+#[derive(thiserror::Error, Debug)]
+pub enum EndpointError {
+    #[error("endpoint not found: {0}")]
+    NotFoundError(Uid),
+
+    #[error("endpoint can't be modified: {0}")]
+    ReadOnlyError(Uid),
+
+    #[error("endpoint revision mismatch: {0}")]
+    RevisionError(Uid),
+
+    #[error("{0}")]
+    ValidationError(#[from] ValidationError),
+
+    #[error("can't load media file: {0}")]
+    MediaError(String),
+
+    #[error("can't open virtual source {0}: {1}")]
+    SignalError(Uid, String),
+
+    #[error("{0}")]
+    DriverError(#[from] DriverError),
+
+    #[error("storage failure: {0}")]
+    VaultError(#[source] VaultError),
+}
+
+impl From<VaultError> for EndpointError {
+    fn from(err: VaultError) -> Self {
+        match err {
+            VaultError::UidNotFound(uid) => EndpointError::NotFoundError(*uid),
+            VaultError::ValidationError(err) => EndpointError::ValidationError(err),
+            err => EndpointError::VaultError(err),
+        }
+    }
+}
@@ -1,5 +1,13 @@
 // Copyright (c) Roc Streaming authors
 // Licensed under MPL-2.0
 mod endpoint_dispatcher;
+mod error;
+mod signal_generator;
+mod virtual_source;
+mod wav_reader;
 
 pub use self::endpoint_dispatcher::*;
+pub use self::error::*;
+pub use self::signal_generator::*;
+pub use self::virtual_source::*;
+pub use self::wav_reader::*;
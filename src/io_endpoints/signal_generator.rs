@@ -0,0 +1,372 @@
+// Copyright (c) Roc Streaming authors
+// Licensed under MPL-2.0
+use crate::audio::AudioFormat;
+use crate::dto::{SignalType, VirtualSourceSpec};
+use crate::io_endpoints::wav_reader::*;
+
+use std::f64::consts::TAU;
+use std::sync::Arc;
+
+/// Produces test signal described by VirtualSourceSpec.
+///
+/// Generation doesn't allocate and can be done on real-time thread.
+pub struct SignalGenerator {
+    kind: SignalKind,
+    gain: f32,
+    channels: usize,
+    // if set, only this channel is non-silent
+    channel: Option<usize>,
+}
+
+enum SignalKind {
+    Sine {
+        // phase in cycles, in range [0; 1)
+        phase: f64,
+        step: f64,
+    },
+    PinkNoise {
+        seed: u32,
+        filter: [f32; 3],
+    },
+    Sweep {
+        phase: f64,
+        // current and total sweep length, in frames
+        position: u64,
+        length: u64,
+        start_step: f64,
+        // ln(end_frequency / start_frequency)
+        log_ratio: f64,
+    },
+    Clip {
+        clip: Arc<WavClip>,
+        // position in clip frames
+        position: f64,
+        step: f64,
+    },
+}
+
+impl SignalGenerator {
+    /// RMS of pink noise filter output for uniform white noise in [-1; 1].
+    /// Used to normalize noise to requested RMS level.
+    const PINK_RMS: f32 = 1.707;
+
+    /// Create generator for given output format.
+    /// Clip is required for wav_file signal and ignored otherwise.
+    pub fn new(
+        spec: &VirtualSourceSpec, clip: Option<&Arc<WavClip>>, format: AudioFormat,
+    ) -> Result<Self, String> {
+        let sample_rate = format.sample_rate as f64;
+        let nyquist = sample_rate / 2.0;
+
+        if let Some(channel) = spec.channel
+            && channel >= format.channels
+        {
+            return Err(format!(
+                "channel {channel} is out of range for {} channels",
+                format.channels
+            ));
+        }
+
+        let kind = match spec.signal {
+            SignalType::Sine => {
+                let frequency = spec.frequency() as f64;
+                if frequency >= nyquist {
+                    return Err(format!(
+                        "frequency {frequency}Hz is above Nyquist frequency {nyquist}Hz"
+                    ));
+                }
+                SignalKind::Sine { phase: 0.0, step: frequency / sample_rate }
+            },
+            SignalType::PinkNoise => {
+                SignalKind::PinkNoise { seed: 0x2545_f491, filter: [0.0; 3] }
+            },
+            SignalType::Sweep => {
+                let start = spec.frequency() as f64;
+                if start >= nyquist {
+                    return Err(format!(
+                        "frequency {start}Hz is above Nyquist frequency {nyquist}Hz"
+                    ));
+                }
+                // default end frequency may be above Nyquist for low sample rates
+                let end = (spec.end_frequency() as f64).min(nyquist);
+                SignalKind::Sweep {
+                    phase: 0.0,
+                    position: 0,
+                    length: ((spec.sweep_duration() as f64 * sample_rate) as u64).max(1),
+                    start_step: start / sample_rate,
+                    log_ratio: (end / start).ln(),
+                }
+            },
+            SignalType::WavFile => {
+                let Some(clip) = clip else {
+                    return Err("media file is not loaded".into());
+                };
+                SignalKind::Clip {
+                    clip: Arc::clone(clip),
+                    position: 0.0,
+                    step: clip.format.sample_rate as f64 / sample_rate,
+                }
+            },
+        };
+
+        let mut gain = 10f32.powf(spec.level() / 20.0);
+        if spec.signal == SignalType::PinkNoise {
+            gain /= Self::PINK_RMS;
+        }
+
+        Ok(SignalGenerator {
+            kind,
+            gain,
+            channels: format.channels as usize,
+            channel: spec.channel.map(|channel| channel as usize),
+        })
+    }
+
+    /// Fill buffer with interleaved samples.
+    pub fn generate(&mut self, samples: &mut [f32]) {
+        for frame in samples.chunks_exact_mut(self.channels) {
+            self.kind.next_frame(frame, self.channel);
+
+            for sample in frame {
+                *sample = (*sample * self.gain).clamp(-1.0, 1.0);
+            }
+        }
+    }
+}
+
+impl SignalKind {
+    fn next_frame(&mut self, frame: &mut [f32], channel: Option<usize>) {
+        if let SignalKind::Clip { clip, position, step } = self {
+            let clip_channels = clip.format.channels as usize;
+            let frames = clip.frames();
+
+            // linear interpolation between adjacent frames, wrapping
+            // around at the end of clip
+            let index = *position as usize;
+            let next_index = (index + 1) % frames;
+            let frac = (*position - index as f64) as f32;
+            let sample_at = |ch: usize| {
+                let a = clip.samples[index * clip_channels + ch];
+                let b = clip.samples[next_index * clip_channels + ch];
+                a + (b - a) * frac
+            };
+
+            match channel {
+                Some(channel) => {
+                    // downmix clip to selected channel
+                    let mix =
+                        (0..clip_channels).map(sample_at).sum::<f32>() / clip_channels as f32;
+                    frame.fill(0.0);
+                    frame[channel] = mix;
+                },
+                None => {
+                    for (ch, sample) in frame.iter_mut().enumerate() {
+                        *sample = match clip_channels {
+                            1 => sample_at(0),
+                            _ if ch < clip_channels => sample_at(ch),
+                            _ => 0.0,
+                        };
+                    }
+                },
+            }
+
+            *position += *step;
+            if *position >= frames as f64 {
+                *position -= frames as f64;
+            }
+            return;
+        }
+
+        let value = self.next_sample();
+
+        match channel {
+            Some(channel) => {
+                frame.fill(0.0);
+                frame[channel] = value;
+            },
+            None => frame.fill(value),
+        }
+    }
+
+    fn next_sample(&mut self) -> f32 {
+        match self {
+            SignalKind::Sine { phase, step } => {
+                let value = (*phase * TAU).sin();
+                *phase = (*phase + *step).fract();
+                value as f32
+            },
+            SignalKind::PinkNoise { seed, filter } => {
+                // xorshift32
+                *seed ^= *seed << 13;
+                *seed ^= *seed >> 17;
+                *seed ^= *seed << 5;
+                let white = (*seed as f64 / u32::MAX as f64 * 2.0 - 1.0) as f32;
+
+                // Paul Kellett's economy pink noise filter
+                filter[0] = 0.99765 * filter[0] + white * 0.0990460;
+                filter[1] = 0.96300 * filter[1] + white * 0.2965164;
+                filter[2] = 0.57000 * filter[2] + white * 1.0526913;
+                filter[0] + filter[1] + filter[2] + white * 0.1848
+            },
+            SignalKind::Sweep { phase, position, length, start_step, log_ratio } => {
+                let value = (*phase * TAU).sin();
+
+                // frequency grows exponentially from start to end
+                let progress = *position as f64 / *length as f64;
+                *phase = (*phase + *start_step * (progress * *log_ratio).exp()).fract();
+
+                *position += 1;
+                if *position == *length {
+                    *position = 0;
+                }
+                value as f32
+            },
+            SignalKind::Clip { .. } => unreachable!(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assertables::*;
+
+    const FORMAT: AudioFormat = AudioFormat { sample_rate: 48000, channels: 2 };
+
+    fn make_spec(signal: SignalType) -> VirtualSourceSpec {
+        VirtualSourceSpec {
+            signal,
+            frequency: None,
+            end_frequency: None,
+            sweep_duration: None,
+            level: None,
+            channel: None,
+            file_path: None,
+        }
+    }
+
+    fn generate(generator: &mut SignalGenerator, seconds: f32) -> Vec<f32> {
+        let mut samples = vec![0.0; (FORMAT.sample_rate as f32 * seconds) as usize * 2];
+        generator.generate(&mut samples);
+        samples
+    }
+
+    fn channel(samples: &[f32], channel: usize) -> Vec<f32> {
+        samples.iter().skip(channel).step_by(FORMAT.channels as usize).copied().collect()
+    }
+
+    fn peak_db(samples: &[f32]) -> f32 {
+        20.0 * samples.iter().fold(0.0f32, |peak, s| peak.max(s.abs())).log10()
+    }
+
+    fn rms_db(samples: &[f32]) -> f32 {
+        let power = samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32;
+        10.0 * power.log10()
+    }
+
+    fn zero_crossings(samples: &[f32]) -> usize {
+        samples.windows(2).filter(|w| w[0] < 0.0 && w[1] >= 0.0).count()
+    }
+
+    #[test]
+    fn test_sine() {
+        let spec = VirtualSourceSpec {
+            frequency: Some(440.0),
+            level: Some(-6.0),
+            ..make_spec(SignalType::Sine)
+        };
+        let mut generator = SignalGenerator::new(&spec, None, FORMAT).unwrap();
+        let samples = generate(&mut generator, 1.0);
+
+        for ch in 0..2 {
+            let samples = channel(&samples, ch);
+            assert_in_delta!(peak_db(&samples), -6.0, 0.01);
+            assert_in_delta!(zero_crossings(&samples), 440, 1);
+        }
+    }
+
+    #[test]
+    fn test_pink_noise() {
+        let spec = make_spec(SignalType::PinkNoise);
+        let mut generator = SignalGenerator::new(&spec, None, FORMAT).unwrap();
+        let samples = generate(&mut generator, 10.0);
+
+        assert_in_delta!(rms_db(&channel(&samples, 0)), VirtualSourceSpec::DEFAULT_LEVEL, 1.0);
+        assert_le!(peak_db(&samples), 0.0);
+    }
+
+    #[test]
+    fn test_sweep() {
+        let spec = VirtualSourceSpec {
+            frequency: Some(100.0),
+            end_frequency: Some(1000.0),
+            sweep_duration: Some(1.0),
+            ..make_spec(SignalType::Sweep)
+        };
+        let mut generator = SignalGenerator::new(&spec, None, FORMAT).unwrap();
+        let samples = channel(&generate(&mut generator, 2.0), 0);
+
+        assert_in_delta!(peak_db(&samples), VirtualSourceSpec::DEFAULT_LEVEL, 0.01);
+
+        // frequency rises from 100Hz to 1000Hz, then starts over
+        let rate = FORMAT.sample_rate as usize;
+        for second in 0..2 {
+            let first = &samples[second * rate..second * rate + rate / 10];
+            let last = &samples[second * rate + rate * 9 / 10..(second + 1) * rate];
+            assert_in_delta!(zero_crossings(first), 11, 2);
+            assert_in_delta!(zero_crossings(last), 90, 5);
+        }
+    }
+
+    #[test]
+    fn test_clip() {
+        // 2 frames at half of output rate, mono
+        let clip = Arc::new(WavClip {
+            format: AudioFormat { sample_rate: 24000, channels: 1 },
+            samples: vec![0.0, 0.5],
+        });
+        let spec = VirtualSourceSpec {
+            file_path: Some("clip.wav".into()),
+            level: Some(0.0),
+            ..make_spec(SignalType::WavFile)
+        };
+        let mut generator = SignalGenerator::new(&spec, Some(&clip), FORMAT).unwrap();
+
+        let mut samples = vec![0.0; 10];
+        generator.generate(&mut samples);
+
+        // interpolated, looped, and copied to both channels
+        assert_eq!(samples, vec![0.0, 0.0, 0.25, 0.25, 0.5, 0.5, 0.25, 0.25, 0.0, 0.0]);
+
+        assert!(SignalGenerator::new(&spec, None, FORMAT).is_err());
+    }
+
+    #[test]
+    fn test_channel() {
+        let spec = VirtualSourceSpec { channel: Some(1), ..make_spec(SignalType::Sine) };
+        let mut generator = SignalGenerator::new(&spec, None, FORMAT).unwrap();
+        let samples = generate(&mut generator, 0.1);
+
+        assert!(channel(&samples, 0).iter().all(|s| *s == 0.0));
+        assert_in_delta!(
+            peak_db(&channel(&samples, 1)),
+            VirtualSourceSpec::DEFAULT_LEVEL,
+            0.01
+        );
+
+        let spec = VirtualSourceSpec { channel: Some(2), ..make_spec(SignalType::Sine) };
+        assert!(SignalGenerator::new(&spec, None, FORMAT).is_err());
+    }
+
+    #[test]
+    fn test_nyquist() {
+        let spec =
+            VirtualSourceSpec { frequency: Some(30000.0), ..make_spec(SignalType::Sine) };
+        assert!(SignalGenerator::new(&spec, None, FORMAT).is_err());
+
+        // default end of sweep is clamped
+        let spec = make_spec(SignalType::Sweep);
+        let format = AudioFormat { sample_rate: 8000, channels: 1 };
+        assert!(SignalGenerator::new(&spec, None, format).is_ok());
+    }
+}
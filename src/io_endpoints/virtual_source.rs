@@ -0,0 +1,140 @@
+// Copyright (c) Roc Streaming authors
+// Licensed under MPL-2.0
+use crate::audio::*;
+use crate::dto::VirtualSourceSpec;
+use crate::io_endpoints::signal_generator::*;
+use crate::io_endpoints::wav_reader::*;
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Parameters of virtual source endpoint, shared between dispatcher
+/// and all opened sources of the endpoint.
+///
+/// When parameters are updated, revision is incremented, and sources
+/// pick up new parameters on next read().
+pub struct SignalControl {
+    revision: AtomicU64,
+    params: Mutex<SignalParams>,
+}
+
+#[derive(Clone)]
+pub struct SignalParams {
+    pub spec: VirtualSourceSpec,
+    // loaded media file, if signal is wav_file
+    pub clip: Option<Arc<WavClip>>,
+}
+
+impl SignalControl {
+    pub fn new(params: SignalParams) -> Self {
+        SignalControl { revision: AtomicU64::new(0), params: Mutex::new(params) }
+    }
+
+    /// Replace parameters of all sources of the endpoint.
+    pub fn update(&self, params: SignalParams) {
+        *self.params.lock().unwrap() = params;
+        self.revision.fetch_add(1, Ordering::SeqCst);
+    }
+
+    pub fn params(&self) -> SignalParams {
+        self.params.lock().unwrap().clone()
+    }
+}
+
+/// Audio source that plays test signal of virtual source endpoint.
+pub struct VirtualSource {
+    format: AudioFormat,
+    control: Arc<SignalControl>,
+    revision: u64,
+    generator: SignalGenerator,
+}
+
+impl VirtualSource {
+    pub fn open(control: &Arc<SignalControl>, format: AudioFormat) -> Result<Self, String> {
+        let revision = control.revision.load(Ordering::SeqCst);
+        let params = control.params();
+        let generator = SignalGenerator::new(&params.spec, params.clip.as_ref(), format)?;
+
+        Ok(VirtualSource { format, control: Arc::clone(control), revision, generator })
+    }
+
+    /// Switch to new parameters if they were updated.
+    fn refresh(&mut self) {
+        let revision = self.control.revision.load(Ordering::SeqCst);
+        if revision == self.revision {
+            return;
+        }
+
+        // don't block real-time thread if parameters are being updated,
+        // retry on next read
+        let Ok(params) = self.control.params.try_lock() else {
+            return;
+        };
+
+        match SignalGenerator::new(&params.spec, params.clip.as_ref(), self.format) {
+            Ok(generator) => self.generator = generator,
+            // e.g. channel is out of range for this stream,
+            // keep playing previous signal
+            Err(err) => tracing::warn!("can't apply virtual source parameters: {err}"),
+        }
+        self.revision = revision;
+    }
+}
+
+impl AudioSource for VirtualSource {
+    fn format(&self) -> AudioFormat {
+        self.format
+    }
+
+    fn is_clocked(&self) -> bool {
+        false
+    }
+
+    fn read(&mut self, samples: &mut [f32]) -> Result<(), AudioError> {
+        self.refresh();
+        self.generator.generate(samples);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dto::SignalType;
+
+    #[test]
+    fn test_update() {
+        let format = AudioFormat { sample_rate: 48000, channels: 2 };
+        let mut spec = VirtualSourceSpec {
+            signal: SignalType::Sine,
+            frequency: None,
+            end_frequency: None,
+            sweep_duration: None,
+            level: Some(0.0),
+            channel: Some(0),
+            file_path: None,
+        };
+
+        let control =
+            Arc::new(SignalControl::new(SignalParams { spec: spec.clone(), clip: None }));
+        let mut source = VirtualSource::open(&control, format).unwrap();
+
+        let mut samples = vec![0.0; 960];
+        source.read(&mut samples).unwrap();
+        assert!(samples.iter().step_by(2).any(|s| *s != 0.0));
+        assert!(samples.iter().skip(1).step_by(2).all(|s| *s == 0.0));
+
+        // switch to other channel
+        spec.channel = Some(1);
+        control.update(SignalParams { spec: spec.clone(), clip: None });
+        source.read(&mut samples).unwrap();
+        assert!(samples.iter().step_by(2).all(|s| *s == 0.0));
+        assert!(samples.iter().skip(1).step_by(2).any(|s| *s != 0.0));
+
+        // invalid channel is ignored
+        spec.channel = Some(5);
+        control.update(SignalParams { spec, clip: None });
+        source.read(&mut samples).unwrap();
+        assert!(samples.iter().skip(1).step_by(2).any(|s| *s != 0.0));
+    }
+}
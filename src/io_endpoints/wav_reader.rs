@@ -0,0 +1,197 @@
+// Copyright (c) Roc Streaming authors
+// Licensed under MPL-2.0
+use crate::audio::AudioFormat;
+use crate::io_endpoints::error::*;
+
+/// Audio clip decoded from RIFF/WAVE file into memory.
+///
+/// Supports integer PCM (8, 16, 24, 32 bits) and 32-bit float,
+/// including WAVE_FORMAT_EXTENSIBLE variants of them.
+#[derive(Clone, PartialEq, Debug)]
+pub struct WavClip {
+    pub format: AudioFormat,
+    // interleaved samples in range [-1; 1]
+    pub samples: Vec<f32>,
+}
+
+impl WavClip {
+    /// Files larger than this are rejected, to avoid exhausting memory.
+    pub const MAX_FILE_SIZE: u64 = 256 * 1024 * 1024;
+
+    const FORMAT_PCM: u16 = 1;
+    const FORMAT_FLOAT: u16 = 3;
+    const FORMAT_EXTENSIBLE: u16 = 0xFFFE;
+
+    /// Decode contents of WAV file.
+    pub fn parse(data: &[u8]) -> Result<Self, EndpointError> {
+        if data.len() < 12 || &data[0..4] != b"RIFF" || &data[8..12] != b"WAVE" {
+            return Err(EndpointError::MediaError("not a RIFF/WAVE file".into()));
+        }
+
+        let mut fmt = None;
+        let mut payload = None;
+
+        let mut pos = 12;
+        while pos + 8 <= data.len() {
+            let id = &data[pos..pos + 4];
+            let size = u32::from_le_bytes(data[pos + 4..pos + 8].try_into().unwrap()) as usize;
+            // size of data chunk may be not updated if file wasn't finalized
+            let end = (pos + 8).saturating_add(size).min(data.len());
+
+            match id {
+                b"fmt " => fmt = Some(&data[pos + 8..end]),
+                b"data" => payload = Some(&data[pos + 8..end]),
+                _ => (),
+            }
+
+            // chunks are padded to even size
+            pos = end + (size & 1);
+        }
+
+        let (Some(fmt), Some(payload)) = (fmt, payload) else {
+            return Err(EndpointError::MediaError("missing fmt or data chunk".into()));
+        };
+        if fmt.len() < 16 {
+            return Err(EndpointError::MediaError("truncated fmt chunk".into()));
+        }
+
+        let read_u16 = |off: usize| u16::from_le_bytes([fmt[off], fmt[off + 1]]);
+
+        let mut format_tag = read_u16(0);
+        let channels = read_u16(2);
+        let sample_rate = u32::from_le_bytes(fmt[4..8].try_into().unwrap());
+        let bits = read_u16(14);
+
+        if format_tag == Self::FORMAT_EXTENSIBLE {
+            if fmt.len() < 26 {
+                return Err(EndpointError::MediaError("truncated fmt chunk".into()));
+            }
+            // first two bytes of sub-format GUID hold format tag
+            format_tag = read_u16(24);
+        }
+
+        if channels == 0 || sample_rate == 0 {
+            return Err(EndpointError::MediaError(
+                "invalid channel count or sample rate".into(),
+            ));
+        }
+
+        let decode: fn(&[u8]) -> f32 = match (format_tag, bits) {
+            (Self::FORMAT_PCM, 8) => |b| (b[0] as f32 - 128.0) / 128.0,
+            (Self::FORMAT_PCM, 16) => |b| i16::from_le_bytes([b[0], b[1]]) as f32 / 32768.0,
+            (Self::FORMAT_PCM, 24) => {
+                // shift right to sign-extend
+                |b| (i32::from_le_bytes([0, b[0], b[1], b[2]]) >> 8) as f32 / 8388608.0
+            },
+            (Self::FORMAT_PCM, 32) => {
+                |b| i32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f32 / 2147483648.0
+            },
+            (Self::FORMAT_FLOAT, 32) => {
+                |b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]).clamp(-1.0, 1.0)
+            },
+            _ => {
+                return Err(EndpointError::MediaError(format!(
+                    "unsupported sample format: tag {format_tag:#x}, {bits} bits"
+                )));
+            },
+        };
+
+        let frame_size = bits as usize / 8 * channels as usize;
+        let samples: Vec<f32> = payload
+            .chunks_exact(frame_size)
+            .flat_map(|frame| frame.chunks_exact(bits as usize / 8).map(decode))
+            .collect();
+
+        if samples.is_empty() {
+            return Err(EndpointError::MediaError("file has no samples".into()));
+        }
+
+        Ok(WavClip { format: AudioFormat { sample_rate, channels }, samples })
+    }
+
+    /// Number of frames (samples per channel).
+    pub fn frames(&self) -> usize {
+        self.samples.len() / self.format.channels as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dto::SampleFormat;
+    use crate::recordings::{FileWriter, WavWriter};
+    use assertables::*;
+    use std::io::{Read, Seek, SeekFrom};
+    use tempfile::tempfile;
+
+    #[test]
+    fn test_pcm() {
+        let format = AudioFormat { sample_rate: 44100, channels: 2 };
+        let samples = [0.0, 0.5, -0.5, 0.25, -1.0, 0.75];
+
+        for sample_format in [SampleFormat::S16, SampleFormat::S24] {
+            let mut file = tempfile().unwrap();
+            let mut writer = WavWriter::create(
+                file.try_clone().unwrap(),
+                format,
+                sample_format,
+                &[("title".into(), "test".into())],
+            )
+            .unwrap();
+            writer.write(&samples).unwrap();
+            writer.finish().unwrap();
+
+            let mut data = Vec::new();
+            file.seek(SeekFrom::Start(0)).unwrap();
+            file.read_to_end(&mut data).unwrap();
+
+            let clip = WavClip::parse(&data).unwrap();
+            assert_eq!(clip.format, format);
+            assert_eq!(clip.frames(), 3);
+            for (orig, decoded) in samples.iter().zip(&clip.samples) {
+                assert_in_delta!(*orig, *decoded, 0.0001);
+            }
+        }
+    }
+
+    #[test]
+    fn test_float() {
+        let mut data = Vec::new();
+        data.extend_from_slice(b"RIFF");
+        data.extend_from_slice(&0u32.to_le_bytes());
+        data.extend_from_slice(b"WAVE");
+        data.extend_from_slice(b"fmt ");
+        data.extend_from_slice(&16u32.to_le_bytes());
+        data.extend_from_slice(&3u16.to_le_bytes());
+        data.extend_from_slice(&1u16.to_le_bytes());
+        data.extend_from_slice(&8000u32.to_le_bytes());
+        data.extend_from_slice(&32000u32.to_le_bytes());
+        data.extend_from_slice(&4u16.to_le_bytes());
+        data.extend_from_slice(&32u16.to_le_bytes());
+        // odd-sized chunk is padded
+        data.extend_from_slice(b"junk");
+        data.extend_from_slice(&3u32.to_le_bytes());
+        data.extend_from_slice(&[1, 2, 3, 0]);
+        data.extend_from_slice(b"data");
+        data.extend_from_slice(&8u32.to_le_bytes());
+        data.extend_from_slice(&0.5f32.to_le_bytes());
+        data.extend_from_slice(&(-2.0f32).to_le_bytes());
+
+        let clip = WavClip::parse(&data).unwrap();
+        assert_eq!(clip.format, AudioFormat { sample_rate: 8000, channels: 1 });
+        assert_eq!(clip.samples, vec![0.5, -1.0]);
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert_matches!(WavClip::parse(b""), Err(EndpointError::MediaError(_)));
+        assert_matches!(
+            WavClip::parse(b"RIFF\0\0\0\0WAVEdata\0\0\0\0"),
+            Err(EndpointError::MediaError(_))
+        );
+        assert_matches!(
+            WavClip::parse(b"fLaC\0\0\0\0\0\0\0\0"),
+            Err(EndpointError::MediaError(_))
+        );
+    }
+}
@@ -0,0 +1,97 @@
+// Copyright (c) Roc Streaming authors
+// Licensed under MPL-2.0
+use crate::audio::AudioError;
+use crate::dto::{Uid, ValidationError};
+use crate::io_endpoints::EndpointError;
+use crate::recordings::RecordingError;
+use crate::vault::VaultError;
+
+use std::io;
+
+#[derive(thiserror::Error, Debug)]
+pub enum StreamError {
+    #[error("stream not found: {0}")]
+    NotFoundError(Uid),
+
+    #[error("stream already exists: {0}")]
+    ExistsError(Uid),
+
+    #[error("stream revision mismatch: {0}")]
+    RevisionError(Uid),
+
+    #[error("stream is not running: {0}")]
+    NotRunningError(Uid),
+
+    #[error("latency measurement failed: {0}")]
+    MeasurementError(String),
+
+    #[error("group not found: {0}")]
+    GroupNotFoundError(Uid),
+
+    #[error("invalid group member: {0}")]
+    GroupMemberError(String),
+
+    #[error("stream {0} is already member of group {1}")]
+    GroupConflictError(Uid, Uid),
+
+    #[error("stream has no lease: {0}")]
+    NoLeaseError(Uid),
+
+    #[error("port {0} is already used by stream {1}")]
+    PortConflictError(u16, Uid),
+
+    #[error("no free ports in range {0}")]
+    PortsExhaustedError(String),
+
+    #[error("{0}")]
+    ValidationError(#[from] ValidationError),
+
+    #[error("{0}")]
+    RecordingError(#[from] RecordingError),
+
+    #[error("storage failure: {0}")]
+    VaultError(#[source] VaultError),
+}
+
+impl From<VaultError> for StreamError {
+    fn from(err: VaultError) -> Self {
+        match err {
+            VaultError::UidNotFound(uid) => StreamError::NotFoundError(*uid),
+            VaultError::ValidationError(err) => StreamError::ValidationError(err),
+            err => StreamError::VaultError(err),
+        }
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum TransportError {
+    #[error("no transport supports {0}")]
+    UnsupportedError(String),
+
+    #[error("unsupported encoding: {0}")]
+    EncodingError(String),
+
+    #[error("invalid connection: {0}")]
+    ConnectionError(String),
+
+    #[error("{0} failed with code {1}")]
+    BackendError(&'static str, i32),
+
+    #[error("i/o error: {0}")]
+    IoError(#[from] io::Error),
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum PipelineError {
+    #[error("{0}")]
+    EndpointError(#[from] EndpointError),
+
+    #[error("{0}")]
+    TransportError(#[from] TransportError),
+
+    #[error("{0}")]
+    AudioError(#[from] AudioError),
+
+    #[error("{0}")]
+    RecordingError(#[from] RecordingError),
+}
@@ -0,0 +1,323 @@
+// Copyright (c) Roc Streaming authors
+// Licensed under MPL-2.0
+use crate::audio::resample_linear;
+
+use std::collections::BTreeMap;
+
+/// Reorders received packets and delivers continuous audio with
+/// constant latency, compensating clock drift between sender and receiver.
+///
+/// Positions and lengths are measured in frames (one sample per channel),
+/// same as RTP timestamps of audio payloads.
+///
+/// Playback starts when buffer accumulates target latency. Missing packets
+/// are replaced with silence, late packets are dropped. If sender clock is
+/// faster or slower than receiver clock, buffer level slowly grows or shrinks;
+/// this is compensated by resampling frames by one frame up or down until
+/// level returns to target.
+pub struct JitterBuffer {
+    channels: usize,
+    target_latency: u64,
+    // packets by extended timestamp
+    packets: BTreeMap<u64, Vec<f32>>,
+    // last extended timestamp, for unwrapping
+    last_timestamp: Option<u64>,
+    // end of most recent packet
+    head_pos: u64,
+    // next position to read, none while prefilling
+    read_pos: Option<u64>,
+    // smoothed buffer level
+    avg_level: f64,
+    // scratch buffer for resampling
+    scratch: Vec<f32>,
+}
+
+impl JitterBuffer {
+    /// Level deviation (relative to target) that triggers drift compensation.
+    const DRIFT_TOLERANCE: f64 = 0.1;
+    /// Level deviation (relative to target) after which buffer is restarted.
+    const MAX_LEVEL: u64 = 4;
+    /// Weight of new level in exponential moving average.
+    const LEVEL_ALPHA: f64 = 0.01;
+
+    pub fn new(channels: usize, target_latency: u64) -> Self {
+        JitterBuffer {
+            channels,
+            target_latency: target_latency.max(1),
+            packets: BTreeMap::new(),
+            last_timestamp: None,
+            head_pos: 0,
+            read_pos: None,
+            avg_level: 0.0,
+            scratch: Vec::new(),
+        }
+    }
+
+    /// Add packet with given RTP timestamp and decoded interleaved samples.
+    /// Returns false if packet was dropped because it's too late.
+    pub fn push(&mut self, timestamp: u32, samples: Vec<f32>) -> bool {
+        let position = self.unwrap_timestamp(timestamp);
+        let n_frames = (samples.len() / self.channels) as u64;
+
+        if let Some(read_pos) = self.read_pos
+            && position + n_frames <= read_pos
+        {
+            return false;
+        }
+
+        self.head_pos = self.head_pos.max(position + n_frames);
+        self.packets.insert(position, samples);
+
+        true
+    }
+
+    /// Fill buffer with next interleaved samples.
+    /// Produces silence while buffer is prefilling.
+    pub fn pop(&mut self, samples: &mut [f32]) {
+        samples.fill(0.0);
+
+        let n_frames = samples.len() / self.channels;
+
+        let read_pos = match self.read_pos {
+            Some(read_pos) => read_pos,
+            None => {
+                let Some((&first_pos, _)) = self.packets.first_key_value() else {
+                    return;
+                };
+                if self.head_pos - first_pos < self.target_latency {
+                    return;
+                }
+                let start_pos = self.head_pos - self.target_latency;
+                tracing::debug!("jitter buffer: starting playback at {start_pos}");
+                self.avg_level = self.target_latency as f64;
+                start_pos
+            },
+        };
+
+        let level = self.head_pos.saturating_sub(read_pos);
+
+        // sender was gone or jumped too far, start over
+        if read_pos > self.head_pos + self.target_latency
+            || level > self.target_latency * Self::MAX_LEVEL
+        {
+            tracing::debug!("jitter buffer: restarting, level={level}");
+            self.packets.clear();
+            self.head_pos = 0;
+            self.read_pos = None;
+            return;
+        }
+
+        self.avg_level += (level as f64 - self.avg_level) * Self::LEVEL_ALPHA;
+
+        // read one frame more or less than requested and stretch
+        // it to requested size
+        let deviation =
+            (self.avg_level - self.target_latency as f64) / self.target_latency as f64;
+        let read_frames = if n_frames < 2 {
+            n_frames
+        } else if deviation > Self::DRIFT_TOLERANCE {
+            n_frames + 1
+        } else if deviation < -Self::DRIFT_TOLERANCE {
+            n_frames - 1
+        } else {
+            n_frames
+        };
+
+        if read_frames == n_frames {
+            self.copy_frames(read_pos, samples);
+        } else {
+            let mut scratch = std::mem::take(&mut self.scratch);
+            scratch.clear();
+            scratch.resize(read_frames * self.channels, 0.0);
+            self.copy_frames(read_pos, &mut scratch);
+            resample_linear(&scratch, samples, self.channels);
+            self.scratch = scratch;
+        }
+
+        let next_pos = read_pos + read_frames as u64;
+        self.read_pos = Some(next_pos);
+
+        // drop packets that were fully read
+        while let Some(entry) = self.packets.first_entry() {
+            let end_pos = *entry.key() + (entry.get().len() / self.channels) as u64;
+            if end_pos > next_pos {
+                break;
+            }
+            entry.remove();
+        }
+    }
+
+    /// Number of buffered frames after read position.
+    pub fn level(&self) -> u64 {
+        match self.read_pos {
+            Some(read_pos) => self.head_pos.saturating_sub(read_pos),
+            None => 0,
+        }
+    }
+
+    /// Whether playback was started after prefilling.
+    pub fn is_playing(&self) -> bool {
+        self.read_pos.is_some()
+    }
+
+    /// Convert 32-bit RTP timestamp to 64-bit position, handling wrap-around.
+    fn unwrap_timestamp(&mut self, timestamp: u32) -> u64 {
+        let position = match self.last_timestamp {
+            // offset relative to previous timestamp, may be negative
+            Some(last) => {
+                let delta = timestamp.wrapping_sub(last as u32) as i32 as i64;
+                // keep positions positive, start far enough from zero
+                (last as i64 + delta).max(0) as u64
+            },
+            None => (1u64 << 32) + timestamp as u64,
+        };
+
+        self.last_timestamp = Some(position);
+        position
+    }
+
+    /// Copy frames starting from given position, leaving gaps zeroed.
+    fn copy_frames(&self, start_pos: u64, samples: &mut [f32]) {
+        let end_pos = start_pos + (samples.len() / self.channels) as u64;
+
+        for (&packet_pos, packet) in self.packets.range(..end_pos) {
+            let packet_end = packet_pos + (packet.len() / self.channels) as u64;
+            if packet_end <= start_pos {
+                continue;
+            }
+
+            let from = packet_pos.max(start_pos);
+            let to = packet_end.min(end_pos);
+
+            let src = ((from - packet_pos) as usize * self.channels)
+                ..((to - packet_pos) as usize * self.channels);
+            let dst = ((from - start_pos) as usize * self.channels)
+                ..((to - start_pos) as usize * self.channels);
+
+            samples[dst].copy_from_slice(&packet[src]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assertables::*;
+
+    fn make_packet(first_value: u32, n_frames: usize) -> Vec<f32> {
+        (0..n_frames).map(|n| (first_value + n as u32) as f32).collect()
+    }
+
+    #[test]
+    fn test_prefill() {
+        let mut buffer = JitterBuffer::new(1, 30);
+        let mut frame = vec![-1.0; 10];
+
+        buffer.push(1000, make_packet(1, 10));
+        buffer.push(1010, make_packet(11, 10));
+
+        // not enough data yet
+        buffer.pop(&mut frame);
+        assert_eq!(frame, vec![0.0; 10]);
+        assert!(!buffer.is_playing());
+
+        buffer.push(1020, make_packet(21, 10));
+
+        buffer.pop(&mut frame);
+        assert_eq!(frame, make_packet(1, 10));
+        assert!(buffer.is_playing());
+        assert_eq!(buffer.level(), 20);
+    }
+
+    #[test]
+    fn test_reorder_and_loss() {
+        let mut buffer = JitterBuffer::new(2, 10);
+        let mut frame = vec![0.0; 20];
+
+        // packets arrive out of order
+        buffer.push(1010, make_packet(100, 20));
+        buffer.push(1000, make_packet(0, 20));
+
+        // start from head - target
+        buffer.pop(&mut frame);
+        assert_eq!(frame, make_packet(100, 20));
+
+        // packet 1020 is lost
+        buffer.push(1030, make_packet(300, 20));
+
+        // lost packet is replaced with silence
+        buffer.pop(&mut frame);
+        assert_eq!(frame, vec![0.0; 20]);
+
+        buffer.pop(&mut frame);
+        assert_eq!(frame, make_packet(300, 20));
+
+        // late packet is dropped
+        assert!(!buffer.push(1020, make_packet(200, 20)));
+    }
+
+    #[test]
+    fn test_wraparound() {
+        let mut buffer = JitterBuffer::new(1, 20);
+        let mut frame = vec![0.0; 10];
+
+        buffer.push(u32::MAX - 9, make_packet(0, 10));
+        buffer.push(0, make_packet(10, 10));
+
+        buffer.pop(&mut frame);
+        assert_eq!(frame, make_packet(0, 10));
+
+        buffer.push(10, make_packet(20, 10));
+
+        buffer.pop(&mut frame);
+        assert_eq!(frame, make_packet(10, 10));
+    }
+
+    #[test]
+    fn test_drift() {
+        const TARGET: u64 = 480;
+
+        for extra_frames in [1, -1] {
+            let mut buffer = JitterBuffer::new(1, TARGET);
+            let mut frame = vec![0.0; 100];
+            let mut timestamp = 0u32;
+
+            // sender clock is 0.1% faster or slower than receiver clock
+            for n in 0..10000 {
+                let packet_frames = if n % 10 == 0 { 100 + extra_frames } else { 100 };
+                buffer.push(timestamp, vec![0.5; packet_frames as usize]);
+                timestamp += packet_frames as u32;
+                buffer.pop(&mut frame);
+            }
+
+            // level is measured before reading
+            buffer.push(timestamp, vec![0.5; 100]);
+
+            assert!(buffer.is_playing());
+            assert_in_delta!(
+                buffer.level() as f64,
+                TARGET as f64,
+                TARGET as f64 * JitterBuffer::DRIFT_TOLERANCE * 1.5
+            );
+            assert_eq!(frame, vec![0.5; 100]);
+        }
+    }
+
+    #[test]
+    fn test_restart() {
+        let mut buffer = JitterBuffer::new(1, 10);
+        let mut frame = vec![0.0; 10];
+
+        buffer.push(0, make_packet(0, 10));
+        buffer.pop(&mut frame);
+        assert!(buffer.is_playing());
+
+        // sender is gone, reader overruns head
+        for _ in 0..5 {
+            buffer.pop(&mut frame);
+        }
+        assert!(!buffer.is_playing());
+
+        assert_eq!(buffer.level(), 0);
+    }
+}
@@ -1,5 +1,33 @@
 // Copyright (c) Roc Streaming authors
 // Licensed under MPL-2.0
+mod error;
+mod jitter_buffer;
+mod multicast;
+mod port_allocator;
+mod rtp_packet;
+mod rtp_transport;
+mod scheduler;
 mod stream_dispatcher;
+mod stream_runner;
+mod transport;
+mod transport_registry;
 
+#[cfg(feature = "roc")]
+mod roc_ffi;
+#[cfg(feature = "roc")]
+mod roc_transport;
+
+pub use self::error::*;
+pub use self::jitter_buffer::*;
+pub use self::multicast::*;
+pub use self::port_allocator::*;
+pub use self::rtp_packet::*;
+pub use self::rtp_transport::*;
+pub use self::scheduler::*;
 pub use self::stream_dispatcher::*;
+pub use self::stream_runner::*;
+pub use self::transport::*;
+pub use self::transport_registry::*;
+
+#[cfg(feature = "roc")]
+pub use self::roc_transport::*;
@@ -0,0 +1,302 @@
+// Copyright (c) Roc Streaming authors
+// Licensed under MPL-2.0
+use crate::dto::*;
+
+use socket2::{Domain, Protocol, Socket, Type};
+use std::collections::BTreeMap;
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use tokio::net::UdpSocket;
+
+/// Network interface of the host.
+#[derive(Clone, PartialEq, Debug)]
+pub struct NetInterface {
+    /// Interface name, e.g. "eth0".
+    pub name: String,
+    /// Interface index, used for IPv6 multicast.
+    pub index: u32,
+    /// First IPv4 address of interface, used for IPv4 multicast.
+    pub ipv4_addr: Option<Ipv4Addr>,
+    /// Whether this is a loopback interface.
+    pub is_loopback: bool,
+}
+
+/// List network interfaces of the host.
+///
+/// Interfaces with multiple addresses are reported once.
+/// Interfaces without addresses are not reported.
+pub fn list_interfaces() -> io::Result<Vec<NetInterface>> {
+    let mut iface_map: BTreeMap<String, NetInterface> = BTreeMap::new();
+
+    for if_addr in if_addrs::get_if_addrs()? {
+        let iface = iface_map.entry(if_addr.name.clone()).or_insert_with(|| NetInterface {
+            name: if_addr.name.clone(),
+            index: if_addr.index.unwrap_or(0),
+            ipv4_addr: None,
+            is_loopback: if_addr.is_loopback(),
+        });
+
+        if let (None, IpAddr::V4(addr)) = (iface.ipv4_addr, if_addr.ip()) {
+            iface.ipv4_addr = Some(addr);
+        }
+    }
+
+    Ok(iface_map.into_values().collect())
+}
+
+/// Check multicast settings of connection against host network interfaces.
+///
+/// Validate impl of ConnectionSpec checks only the spec itself, this function
+/// additionally checks that the spec is usable on this host:
+///  - multicast_interface, if present, should exist and have suitable address
+///  - if host is multi-homed, multicast_interface is mandatory for multicast
+///    addresses, because we can't guess which interface to use
+pub fn validate_multicast(
+    connection: &ConnectionSpec, interfaces: &[NetInterface],
+) -> ValidationResult {
+    let ConnectionSpec::External { media_uri, multicast_interface, .. } = connection else {
+        return Ok(());
+    };
+
+    let Some(group_addr) = media_uri.ip_addr().filter(|addr| addr.is_multicast()) else {
+        return Ok(());
+    };
+
+    match multicast_interface {
+        Some(iface_name) => {
+            let iface = find_interface(interfaces, iface_name)?;
+
+            if group_addr.is_ipv4() && iface.ipv4_addr.is_none() {
+                return Err(ValidationError::LayoutError(format!(
+                    "multicast_interface '{iface_name}' has no IPv4 address"
+                )));
+            }
+        },
+        None => {
+            let n_ifaces = interfaces.iter().filter(|iface| !iface.is_loopback).count();
+
+            if n_ifaces > 1 {
+                return Err(ValidationError::LayoutError(
+                    "host has multiple network interfaces, multicast_interface must be \
+                     specified for multicast media_uri"
+                        .into(),
+                ));
+            }
+        },
+    }
+
+    Ok(())
+}
+
+/// Find interface by name.
+pub fn find_interface<'a>(
+    interfaces: &'a [NetInterface], iface_name: &str,
+) -> Result<&'a NetInterface, ValidationError> {
+    interfaces.iter().find(|iface| iface.name == iface_name).ok_or_else(|| {
+        ValidationError::LayoutError(format!("unknown multicast_interface '{iface_name}'"))
+    })
+}
+
+/// Open UDP socket for sending to multicast group.
+///
+/// If interface is omitted, OS routing table decides where to send.
+/// If ttl is omitted, OS default is used (usually 1, i.e. local network).
+pub fn open_multicast_sender(
+    group_addr: &SocketAddr, iface: Option<&NetInterface>, ttl: Option<u8>,
+) -> io::Result<UdpSocket> {
+    let socket = open_std_multicast_sender(group_addr, iface, ttl)?;
+    socket.set_nonblocking(true)?;
+
+    UdpSocket::from_std(socket)
+}
+
+/// Open UDP socket for receiving from multicast group.
+///
+/// Socket is bound to group address with SO_REUSEADDR, so that multiple
+/// receivers on the same host can join the same group.
+///
+/// If interface is omitted, OS chooses default interface for joining.
+pub fn open_multicast_receiver(
+    group_addr: &SocketAddr, iface: Option<&NetInterface>,
+) -> io::Result<UdpSocket> {
+    let socket = open_std_multicast_receiver(group_addr, iface)?;
+    socket.set_nonblocking(true)?;
+
+    UdpSocket::from_std(socket)
+}
+
+/// Same as open_multicast_sender(), but returns blocking std socket,
+/// for use outside of async runtime (e.g. on real-time threads).
+pub fn open_std_multicast_sender(
+    group_addr: &SocketAddr, iface: Option<&NetInterface>, ttl: Option<u8>,
+) -> io::Result<std::net::UdpSocket> {
+    let socket =
+        Socket::new(Domain::for_address(*group_addr), Type::DGRAM, Some(Protocol::UDP))?;
+
+    match group_addr.ip() {
+        IpAddr::V4(_) => {
+            socket.bind(&SocketAddr::from((Ipv4Addr::UNSPECIFIED, 0)).into())?;
+
+            if let Some(iface_addr) = iface.and_then(|iface| iface.ipv4_addr) {
+                socket.set_multicast_if_v4(&iface_addr)?;
+            }
+            if let Some(ttl) = ttl {
+                socket.set_multicast_ttl_v4(ttl as u32)?;
+            }
+        },
+        IpAddr::V6(_) => {
+            socket.bind(&SocketAddr::from((Ipv6Addr::UNSPECIFIED, 0)).into())?;
+
+            if let Some(iface) = iface {
+                socket.set_multicast_if_v6(iface.index)?;
+            }
+            if let Some(ttl) = ttl {
+                socket.set_multicast_hops_v6(ttl as u32)?;
+            }
+        },
+    }
+
+    tracing::debug!(
+        "opened multicast sender: group={} iface={:?} ttl={:?}",
+        group_addr,
+        iface.map(|iface| &iface.name),
+        ttl
+    );
+
+    Ok(socket.into())
+}
+
+/// Same as open_multicast_receiver(), but returns blocking std socket,
+/// for use outside of async runtime (e.g. on real-time threads).
+pub fn open_std_multicast_receiver(
+    group_addr: &SocketAddr, iface: Option<&NetInterface>,
+) -> io::Result<std::net::UdpSocket> {
+    let socket =
+        Socket::new(Domain::for_address(*group_addr), Type::DGRAM, Some(Protocol::UDP))?;
+
+    socket.set_reuse_address(true)?;
+    socket.bind(&(*group_addr).into())?;
+
+    match group_addr.ip() {
+        IpAddr::V4(group_ip) => {
+            let iface_addr =
+                iface.and_then(|iface| iface.ipv4_addr).unwrap_or(Ipv4Addr::UNSPECIFIED);
+            socket.join_multicast_v4(&group_ip, &iface_addr)?;
+        },
+        IpAddr::V6(group_ip) => {
+            let iface_index = iface.map(|iface| iface.index).unwrap_or(0);
+            socket.join_multicast_v6(&group_ip, iface_index)?;
+        },
+    }
+
+    tracing::debug!(
+        "opened multicast receiver: group={} iface={:?}",
+        group_addr,
+        iface.map(|iface| &iface.name)
+    );
+
+    Ok(socket.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assertables::*;
+
+    fn make_iface(name: &str, index: u32, ipv4_addr: Option<&str>) -> NetInterface {
+        NetInterface {
+            name: name.into(),
+            index,
+            ipv4_addr: ipv4_addr.map(|addr| addr.parse().unwrap()),
+            is_loopback: name == "lo",
+        }
+    }
+
+    fn make_connection(media_uri: &str, multicast_interface: Option<&str>) -> ConnectionSpec {
+        let host = Uri::parse(media_uri).unwrap().ip_addr().unwrap();
+        let repair_addr = SocketAddr::new(host, 10001);
+        let control_addr = SocketAddr::new(host, 10002);
+
+        ConnectionSpec::External {
+            connection_type: ConnectionType::External,
+            media_uri: Uri::parse(media_uri).unwrap(),
+            repair_uri: Some(Uri::parse(&format!("rs8m://{repair_addr}")).unwrap()),
+            control_uri: Uri::parse(&format!("rtcp://{control_addr}")).unwrap(),
+            multicast_interface: multicast_interface.map(|iface| iface.into()),
+            multicast_ttl: None,
+        }
+    }
+
+    #[test]
+    fn test_validate_single_homed() {
+        let interfaces = vec![
+            make_iface("lo", 1, Some("127.0.0.1")),
+            make_iface("eth0", 2, Some("10.0.0.1")),
+        ];
+
+        // unicast
+        assert_ok!(validate_multicast(
+            &make_connection("rtp+rs8m://10.0.0.2:10000", None),
+            &interfaces
+        ));
+        // multicast with default interface
+        assert_ok!(validate_multicast(
+            &make_connection("rtp+rs8m://239.1.2.3:10000", None),
+            &interfaces
+        ));
+        // multicast with explicit interface
+        assert_ok!(validate_multicast(
+            &make_connection("rtp+rs8m://239.1.2.3:10000", Some("lo")),
+            &interfaces
+        ));
+        // multicast with unknown interface
+        assert_matches!(
+            validate_multicast(
+                &make_connection("rtp+rs8m://239.1.2.3:10000", Some("eth1")),
+                &interfaces
+            ),
+            Err(ValidationError::LayoutError(_))
+        );
+    }
+
+    #[test]
+    fn test_validate_multi_homed() {
+        let interfaces = vec![
+            make_iface("lo", 1, Some("127.0.0.1")),
+            make_iface("eth0", 2, Some("10.0.0.1")),
+            make_iface("eth1", 3, None),
+        ];
+
+        // unicast
+        assert_ok!(validate_multicast(
+            &make_connection("rtp+rs8m://10.0.0.2:10000", None),
+            &interfaces
+        ));
+        // multicast without interface
+        assert_matches!(
+            validate_multicast(
+                &make_connection("rtp+rs8m://239.1.2.3:10000", None),
+                &interfaces
+            ),
+            Err(ValidationError::LayoutError(_))
+        );
+        // multicast with explicit interface
+        assert_ok!(validate_multicast(
+            &make_connection("rtp+rs8m://239.1.2.3:10000", Some("eth0")),
+            &interfaces
+        ));
+        // IPv4 multicast on interface without IPv4 address
+        assert_matches!(
+            validate_multicast(
+                &make_connection("rtp+rs8m://239.1.2.3:10000", Some("eth1")),
+                &interfaces
+            ),
+            Err(ValidationError::LayoutError(_))
+        );
+        // IPv6 multicast on interface without IPv4 address
+        assert_ok!(validate_multicast(
+            &make_connection("rtp+rs8m://[ff02::1234]:10000", Some("eth1")),
+            &interfaces
+        ));
+    }
+}
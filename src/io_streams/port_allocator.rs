@@ -0,0 +1,324 @@
+// Copyright (c) Roc Streaming authors
+// Licensed under MPL-2.0
+use crate::dto::*;
+use crate::io_streams::error::*;
+
+use std::fmt;
+use std::net::{IpAddr, UdpSocket};
+use std::str::FromStr;
+
+/// Range of local UDP ports used for automatic allocation.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct PortRange {
+    pub first: u16,
+    pub last: u16,
+}
+
+impl PortRange {
+    pub fn contains(&self, port: u16) -> bool {
+        (self.first..=self.last).contains(&port)
+    }
+}
+
+impl Default for PortRange {
+    fn default() -> Self {
+        PortRange { first: 10000, last: 10999 }
+    }
+}
+
+/// PortRange from "FIRST-LAST" string.
+impl FromStr for PortRange {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let (first, last) = text
+            .split_once('-')
+            .ok_or_else(|| format!("expected FIRST-LAST, got '{text}'"))?;
+
+        let parse_port = |port: &str| {
+            port.trim().parse::<u16>().map_err(|_| format!("invalid port '{port}'"))
+        };
+
+        let range = PortRange { first: parse_port(first)?, last: parse_port(last)? };
+
+        if range.first == 0 || range.first > range.last {
+            return Err(format!("invalid port range '{text}'"));
+        }
+
+        Ok(range)
+    }
+}
+
+impl fmt::Display for PortRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.first, self.last)
+    }
+}
+
+/// Allocates local ports for receiving streams.
+///
+/// Local ports are ports of external connection used as stream source,
+/// i.e. addresses on which rocd receives packets. Port 0 in media_uri,
+/// repair_uri, or control_uri means that port should be allocated
+/// automatically from configured range.
+///
+/// Explicit ports of failover sources are reserved and checked for
+/// conflicts too, but never allocated.
+///
+/// Allocator is stateless: ports in use are determined from existing
+/// streams, which are persisted in vault together with allocated ports.
+/// Caller is responsible for serializing allocate() and stream writes.
+pub struct PortAllocator {
+    range: PortRange,
+}
+
+impl PortAllocator {
+    pub fn new(range: PortRange) -> Self {
+        PortAllocator { range }
+    }
+
+    /// Replace wildcard ports in stream source with allocated ports,
+    /// and ensure that stream doesn't use ports of other streams.
+    pub fn allocate(
+        &self, stream: &mut StreamSpec, other_streams: &[StreamSpec],
+    ) -> Result<(), StreamError> {
+        let mut used_ports: Vec<(IpAddr, u16, Uid)> = Vec::new();
+
+        for other_stream in other_streams {
+            if other_stream.stream_uid == stream.stream_uid {
+                continue;
+            }
+            for uri in other_stream.all_sources().iter().flat_map(|s| s.external_uris()) {
+                if let (Some(addr), Some(port)) = (uri.ip_addr(), uri.port()) {
+                    used_ports.push((addr, port, other_stream.stream_uid));
+                }
+            }
+        }
+
+        // explicit ports
+        let mut own_ports = Vec::new();
+        for uri in stream.all_sources().iter().flat_map(|s| s.external_uris()) {
+            let (Some(addr), Some(port)) = (uri.ip_addr(), uri.port()) else {
+                continue;
+            };
+            if port == 0 {
+                continue;
+            }
+            if let Some((_, _, other_uid)) = find_conflict(&used_ports, addr, port) {
+                return Err(StreamError::PortConflictError(port, *other_uid));
+            }
+            own_ports.push((addr, port, stream.stream_uid));
+        }
+
+        // wildcard ports shouldn't collide with explicit ports
+        // of failover sources
+        used_ports.extend(own_ports);
+
+        let ConnectionSpec::External { media_uri, repair_uri, control_uri, .. } =
+            &mut stream.source
+        else {
+            return Ok(());
+        };
+
+        // wildcard ports
+        for uri in
+            [Some(media_uri), repair_uri.as_mut(), Some(control_uri)].into_iter().flatten()
+        {
+            let Some(addr) = uri.ip_addr() else {
+                continue;
+            };
+            if uri.port() != Some(0) {
+                continue;
+            }
+
+            let port = self.find_free_port(&used_ports, addr)?;
+            used_ports.push((addr, port, stream.stream_uid));
+
+            *uri = uri.with_port(port).unwrap();
+        }
+
+        Ok(())
+    }
+
+    fn find_free_port(
+        &self, used_ports: &[(IpAddr, u16, Uid)], addr: IpAddr,
+    ) -> Result<u16, StreamError> {
+        for port in self.range.first..=self.range.last {
+            if find_conflict(used_ports, addr, port).is_some() {
+                continue;
+            }
+            // skip ports occupied by other processes
+            if UdpSocket::bind((addr, port)).is_err() {
+                tracing::debug!("skipping port {port}: can't bind to {addr}");
+                continue;
+            }
+            return Ok(port);
+        }
+
+        Err(StreamError::PortsExhaustedError(self.range.to_string()))
+    }
+}
+
+/// Find used port that overlaps with given address and port.
+/// Unspecified address (e.g. 0.0.0.0) overlaps with any address.
+fn find_conflict(
+    used_ports: &[(IpAddr, u16, Uid)], addr: IpAddr, port: u16,
+) -> Option<&(IpAddr, u16, Uid)> {
+    used_ports.iter().find(|(used_addr, used_port, _)| {
+        *used_port == port
+            && (*used_addr == addr || used_addr.is_unspecified() || addr.is_unspecified())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assertables::*;
+
+    fn make_stream(stream_uid: &Uid, host: &str, ports: [u16; 3]) -> StreamSpec {
+        let peer_uid = Uid::generate_random();
+        let endpoint_uid = Uid::generate_random();
+
+        StreamSpec {
+            stream_uri: Uri::from_stream(stream_uid),
+            stream_uid: *stream_uid,
+            source: ConnectionSpec::External {
+                connection_type: ConnectionType::External,
+                media_uri: Uri::parse(&format!("rtp+rs8m://{host}:{}", ports[0])).unwrap(),
+                repair_uri: Some(Uri::parse(&format!("rs8m://{host}:{}", ports[1])).unwrap()),
+                control_uri: Uri::parse(&format!("rtcp://{host}:{}", ports[2])).unwrap(),
+                multicast_interface: None,
+                multicast_ttl: None,
+            },
+            destination: ConnectionSpec::Endpoint {
+                connection_type: ConnectionType::Endpoint,
+                endpoint_uri: Uri::from_endpoint(&peer_uid, &endpoint_uid),
+            },
+            encoding: EncodingSpec::default(),
+            owner: None,
+            lease_ttl: None,
+            lease_expiration: None,
+            schedule: None,
+            state: StreamState::Running,
+            next_transition: None,
+            error: None,
+            recording: None,
+            processing: None,
+            silence_detection: None,
+            failover: None,
+            active_source: None,
+            revision: 0,
+        }
+    }
+
+    fn source_ports(stream: &StreamSpec) -> Vec<u16> {
+        stream.source.external_uris().iter().map(|uri| uri.port().unwrap()).collect()
+    }
+
+    #[test]
+    fn test_parse_range() {
+        assert_eq!(
+            "20000-20099".parse::<PortRange>().unwrap(),
+            PortRange { first: 20000, last: 20099 }
+        );
+        assert_eq!(
+            "20000-20000".parse::<PortRange>().unwrap(),
+            PortRange { first: 20000, last: 20000 }
+        );
+
+        for text in ["", "20000", "20000-", "-20000", "20099-20000", "0-100", "1-70000"] {
+            assert_err!(text.parse::<PortRange>(), "{text}");
+        }
+    }
+
+    #[test]
+    fn test_allocate_wildcard() {
+        let allocator = PortAllocator::new(PortRange { first: 42000, last: 42099 });
+
+        let mut stream_a = make_stream(&Uid::generate_random(), "127.0.0.1", [0, 0, 0]);
+        assert_ok!(allocator.allocate(&mut stream_a, &[]));
+
+        let ports_a = source_ports(&stream_a);
+        for port in &ports_a {
+            assert!(allocator.range.contains(*port));
+        }
+        assert_ne!(ports_a[0], ports_a[1]);
+        assert_ne!(ports_a[1], ports_a[2]);
+        assert_ne!(ports_a[0], ports_a[2]);
+
+        // second stream gets different ports
+        let mut stream_b = make_stream(&Uid::generate_random(), "0.0.0.0", [0, 0, 0]);
+        assert_ok!(allocator.allocate(&mut stream_b, &[stream_a.clone()]));
+
+        for port in source_ports(&stream_b) {
+            assert!(allocator.range.contains(port));
+            assert_not_contains!(ports_a, &port);
+        }
+
+        // re-allocation of same stream doesn't conflict with itself
+        let mut stream_a2 = stream_a.clone();
+        assert_ok!(allocator.allocate(&mut stream_a2, &[stream_a.clone(), stream_b.clone()]));
+        assert_eq!(stream_a2, stream_a);
+    }
+
+    #[test]
+    fn test_allocate_conflict() {
+        let allocator = PortAllocator::new(PortRange::default());
+
+        let other_streams =
+            vec![make_stream(&Uid::generate_random(), "0.0.0.0", [30000, 30001, 30002])];
+
+        // same port on unspecified address
+        let mut stream_b = make_stream(&Uid::generate_random(), "0.0.0.0", [30002, 0, 0]);
+        assert_matches!(
+            allocator.allocate(&mut stream_b, &other_streams),
+            Err(StreamError::PortConflictError(30002, uid)) if uid == other_streams[0].stream_uid
+        );
+
+        // same port on specific address
+        let mut stream_b = make_stream(&Uid::generate_random(), "127.0.0.1", [0, 30001, 0]);
+        assert_matches!(
+            allocator.allocate(&mut stream_b, &other_streams),
+            Err(StreamError::PortConflictError(30001, _))
+        );
+
+        // different ports
+        let mut stream_b = make_stream(&Uid::generate_random(), "127.0.0.1", [30003, 0, 0]);
+        assert_ok!(allocator.allocate(&mut stream_b, &other_streams));
+
+        // same port in failover source
+        let mut stream_b = make_stream(&Uid::generate_random(), "127.0.0.1", [30003, 0, 0]);
+        stream_b.failover = Some(FailoverSpec {
+            sources: vec![
+                make_stream(&stream_b.stream_uid, "0.0.0.0", [30010, 30011, 30000]).source,
+            ],
+            silence_threshold: None,
+            silence_timeout: None,
+            recovery_time: None,
+        });
+        assert_matches!(
+            allocator.allocate(&mut stream_b, &other_streams),
+            Err(StreamError::PortConflictError(30000, _))
+        );
+
+        // same port in failover source of other stream
+        let mut stream_c = make_stream(&Uid::generate_random(), "127.0.0.1", [30011, 0, 0]);
+        stream_b.failover.as_mut().unwrap().sources =
+            vec![make_stream(&stream_b.stream_uid, "0.0.0.0", [30010, 30011, 30012]).source];
+        assert_matches!(
+            allocator.allocate(&mut stream_c, &[stream_b.clone()]),
+            Err(StreamError::PortConflictError(30011, uid)) if uid == stream_b.stream_uid
+        );
+    }
+
+    #[test]
+    fn test_allocate_exhausted() {
+        let allocator = PortAllocator::new(PortRange { first: 42100, last: 42101 });
+
+        let mut stream = make_stream(&Uid::generate_random(), "127.0.0.1", [0, 0, 0]);
+        assert_matches!(
+            allocator.allocate(&mut stream, &[]),
+            Err(StreamError::PortsExhaustedError(_))
+        );
+    }
+}
@@ -0,0 +1,207 @@
+// Copyright (c) Roc Streaming authors
+// Licensed under MPL-2.0
+
+//! Minimal bindings to libroc (Roc Toolkit C API, version 0.4).
+//!
+//! Declarations mirror roc/context.h, roc/sender.h, roc/receiver.h,
+//! roc/endpoint.h, roc/config.h and roc/metrics.h; only parts used
+//! by RocTransport are declared. Enums are passed as C int.
+
+#![allow(non_camel_case_types)]
+
+use std::ffi::{c_char, c_int, c_longlong, c_uint, c_ulonglong, c_void};
+
+#[repr(C)]
+pub struct roc_context {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+pub struct roc_sender {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+pub struct roc_receiver {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+pub struct roc_endpoint {
+    _private: [u8; 0],
+}
+
+pub type roc_slot = c_ulonglong;
+
+pub const ROC_SLOT_DEFAULT: roc_slot = 0;
+
+// roc_interface
+pub const ROC_INTERFACE_AUDIO_SOURCE: c_int = 11;
+pub const ROC_INTERFACE_AUDIO_REPAIR: c_int = 12;
+pub const ROC_INTERFACE_AUDIO_CONTROL: c_int = 13;
+
+// roc_packet_encoding
+pub const ROC_PACKET_ENCODING_AVP_L16_STEREO: c_int = 10;
+pub const ROC_PACKET_ENCODING_AVP_L16_MONO: c_int = 11;
+
+// roc_fec_encoding
+pub const ROC_FEC_ENCODING_DISABLE: c_int = -1;
+pub const ROC_FEC_ENCODING_RS8M: c_int = 1;
+pub const ROC_FEC_ENCODING_LDPC_STAIRCASE: c_int = 2;
+
+// roc_format
+pub const ROC_FORMAT_PCM_FLOAT32: c_int = 1;
+
+// roc_channel_layout
+pub const ROC_CHANNEL_LAYOUT_MULTITRACK: c_int = 1;
+pub const ROC_CHANNEL_LAYOUT_MONO: c_int = 2;
+pub const ROC_CHANNEL_LAYOUT_STEREO: c_int = 3;
+
+// roc_clock_source
+pub const ROC_CLOCK_SOURCE_EXTERNAL: c_int = 0;
+pub const ROC_CLOCK_SOURCE_INTERNAL: c_int = 1;
+
+#[repr(C)]
+#[derive(Copy, Clone, Default)]
+pub struct roc_context_config {
+    pub max_packet_size: c_uint,
+    pub max_frame_size: c_uint,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Default)]
+pub struct roc_media_encoding {
+    pub rate: c_uint,
+    pub format: c_int,
+    pub channels: c_int,
+    pub tracks: c_uint,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Default)]
+pub struct roc_sender_config {
+    pub frame_encoding: roc_media_encoding,
+    pub packet_encoding: c_int,
+    pub packet_length: c_ulonglong,
+    pub fec_encoding: c_int,
+    pub fec_block_source_packets: c_uint,
+    pub fec_block_repair_packets: c_uint,
+    pub clock_source: c_int,
+    pub latency_tuner_backend: c_int,
+    pub latency_tuner_profile: c_int,
+    pub resampler_backend: c_int,
+    pub resampler_profile: c_int,
+    pub target_latency: c_ulonglong,
+    pub latency_tolerance: c_ulonglong,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Default)]
+pub struct roc_receiver_config {
+    pub frame_encoding: roc_media_encoding,
+    pub clock_source: c_int,
+    pub latency_tuner_backend: c_int,
+    pub latency_tuner_profile: c_int,
+    pub resampler_backend: c_int,
+    pub resampler_profile: c_int,
+    pub target_latency: c_ulonglong,
+    pub latency_tolerance: c_ulonglong,
+    pub no_playback_timeout: c_longlong,
+    pub choppy_playback_timeout: c_longlong,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct roc_interface_config {
+    pub outgoing_address: [c_char; 48],
+    pub multicast_group: [c_char; 48],
+    pub reuse_address: c_int,
+}
+
+impl Default for roc_interface_config {
+    fn default() -> Self {
+        roc_interface_config {
+            outgoing_address: [0; 48],
+            multicast_group: [0; 48],
+            reuse_address: 0,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Default)]
+pub struct roc_sender_metrics {
+    pub connection_count: c_uint,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Default)]
+pub struct roc_receiver_metrics {
+    pub connection_count: c_uint,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Default)]
+pub struct roc_connection_metrics {
+    pub e2e_latency: c_ulonglong,
+    pub mean_jitter: c_ulonglong,
+    pub expected_packets: c_ulonglong,
+    pub lost_packets: c_ulonglong,
+}
+
+#[repr(C)]
+pub struct roc_frame {
+    pub samples: *mut c_void,
+    pub samples_size: usize,
+}
+
+#[link(name = "roc")]
+unsafe extern "C" {
+    pub fn roc_context_open(
+        config: *const roc_context_config, result: *mut *mut roc_context,
+    ) -> c_int;
+    pub fn roc_context_register_encoding(
+        context: *mut roc_context, encoding_id: c_int, encoding: *const roc_media_encoding,
+    ) -> c_int;
+    pub fn roc_context_close(context: *mut roc_context) -> c_int;
+
+    pub fn roc_endpoint_allocate(result: *mut *mut roc_endpoint) -> c_int;
+    pub fn roc_endpoint_set_uri(endpoint: *mut roc_endpoint, uri: *const c_char) -> c_int;
+    pub fn roc_endpoint_deallocate(endpoint: *mut roc_endpoint) -> c_int;
+
+    pub fn roc_sender_open(
+        context: *mut roc_context, config: *const roc_sender_config,
+        result: *mut *mut roc_sender,
+    ) -> c_int;
+    pub fn roc_sender_configure(
+        sender: *mut roc_sender, slot: roc_slot, iface: c_int,
+        config: *const roc_interface_config,
+    ) -> c_int;
+    pub fn roc_sender_connect(
+        sender: *mut roc_sender, slot: roc_slot, iface: c_int, endpoint: *const roc_endpoint,
+    ) -> c_int;
+    pub fn roc_sender_query(
+        sender: *mut roc_sender, slot: roc_slot, slot_metrics: *mut roc_sender_metrics,
+        conn_metrics: *mut roc_connection_metrics, conn_metrics_count: *mut usize,
+    ) -> c_int;
+    pub fn roc_sender_write(sender: *mut roc_sender, frame: *const roc_frame) -> c_int;
+    pub fn roc_sender_close(sender: *mut roc_sender) -> c_int;
+
+    pub fn roc_receiver_open(
+        context: *mut roc_context, config: *const roc_receiver_config,
+        result: *mut *mut roc_receiver,
+    ) -> c_int;
+    pub fn roc_receiver_configure(
+        receiver: *mut roc_receiver, slot: roc_slot, iface: c_int,
+        config: *const roc_interface_config,
+    ) -> c_int;
+    pub fn roc_receiver_bind(
+        receiver: *mut roc_receiver, slot: roc_slot, iface: c_int, endpoint: *mut roc_endpoint,
+    ) -> c_int;
+    pub fn roc_receiver_query(
+        receiver: *mut roc_receiver, slot: roc_slot, slot_metrics: *mut roc_receiver_metrics,
+        conn_metrics: *mut roc_connection_metrics, conn_metrics_count: *mut usize,
+    ) -> c_int;
+    pub fn roc_receiver_read(receiver: *mut roc_receiver, frame: *mut roc_frame) -> c_int;
+    pub fn roc_receiver_close(receiver: *mut roc_receiver) -> c_int;
+}
@@ -0,0 +1,463 @@
+// Copyright (c) Roc Streaming authors
+// Licensed under MPL-2.0
+use crate::audio::*;
+use crate::dto::*;
+use crate::io_streams::error::*;
+use crate::io_streams::multicast::*;
+use crate::io_streams::roc_ffi::*;
+use crate::io_streams::transport::*;
+
+use std::collections::HashMap;
+use std::ffi::{CString, c_char, c_int, c_void};
+use std::net::IpAddr;
+use std::ptr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Transport implemented by libroc, running in-process.
+///
+/// Each stream gets its own roc_sender or roc_receiver; all of them
+/// share one roc_context (and its network thread).
+pub struct RocTransport {
+    context: Arc<RocContext>,
+    // custom encodings registered in context, by payload type
+    encodings: Mutex<HashMap<u8, EncodingSpec>>,
+}
+
+impl RocTransport {
+    /// Open roc context.
+    pub fn open() -> TransportResult<Self> {
+        let config = roc_context_config::default();
+        let mut context = ptr::null_mut();
+
+        check_code("roc_context_open", unsafe { roc_context_open(&config, &mut context) })?;
+
+        tracing::debug!("opened roc context");
+
+        Ok(RocTransport {
+            context: Arc::new(RocContext(context)),
+            encodings: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Get roc packet encoding ID for stream encoding.
+    /// Static payload types are built into libroc, others are registered
+    /// in context with payload type as ID.
+    fn packet_encoding(&self, encoding: &EncodingSpec) -> TransportResult<c_int> {
+        if EncodingSpec::from_static_payload(encoding.payload_type).as_ref() == Some(encoding)
+        {
+            return Ok(match encoding.channels {
+                1 => ROC_PACKET_ENCODING_AVP_L16_MONO,
+                _ => ROC_PACKET_ENCODING_AVP_L16_STEREO,
+            });
+        }
+
+        let mut encodings = self.encodings.lock().unwrap();
+
+        match encodings.get(&encoding.payload_type) {
+            Some(registered) if registered == encoding => {},
+            Some(registered) => {
+                return Err(TransportError::EncodingError(format!(
+                    "payload type {} is already registered as {:?}",
+                    encoding.payload_type, registered
+                )));
+            },
+            None => {
+                let media_encoding = media_encoding(encoding);
+
+                check_code("roc_context_register_encoding", unsafe {
+                    roc_context_register_encoding(
+                        self.context.0,
+                        encoding.payload_type as c_int,
+                        &media_encoding,
+                    )
+                })?;
+
+                encodings.insert(encoding.payload_type, encoding.clone());
+            },
+        }
+
+        Ok(encoding.payload_type as c_int)
+    }
+}
+
+impl Transport for RocTransport {
+    fn name(&self) -> &'static str {
+        "roc"
+    }
+
+    fn supports(&self, connection: &ConnectionSpec, encoding: &EncodingSpec) -> bool {
+        // libroc 0.4 encodes packets only as L16 and can't set multicast TTL
+        connection.fec_encoding().is_some()
+            && encoding.sample_format == SampleFormat::S16
+            && !matches!(connection, ConnectionSpec::External { multicast_ttl: Some(_), .. })
+    }
+
+    fn open_receiver(
+        &self, connection: &ConnectionSpec, encoding: &EncodingSpec, external_clock: bool,
+    ) -> TransportResult<Box<dyn AudioSource>> {
+        // receiver detects packet encoding by payload type,
+        // so custom encoding should be just registered
+        self.packet_encoding(encoding)?;
+
+        let config = roc_receiver_config {
+            frame_encoding: media_encoding(encoding),
+            clock_source: clock_source(external_clock),
+            ..Default::default()
+        };
+
+        let mut receiver = ptr::null_mut();
+        check_code("roc_receiver_open", unsafe {
+            roc_receiver_open(self.context.0, &config, &mut receiver)
+        })?;
+
+        // wrap immediately, so that receiver is closed on error
+        let receiver = RocReceiver {
+            _context: Arc::clone(&self.context),
+            receiver,
+            format: AudioFormat::from_encoding(encoding),
+            external_clock,
+        };
+
+        for (iface, uri) in interfaces(connection)? {
+            if let Some(iface_config) = interface_config(connection, uri, false)? {
+                check_code("roc_receiver_configure", unsafe {
+                    roc_receiver_configure(
+                        receiver.receiver,
+                        ROC_SLOT_DEFAULT,
+                        iface,
+                        &iface_config,
+                    )
+                })?;
+            }
+
+            let endpoint = RocEndpoint::new(uri)?;
+            check_code("roc_receiver_bind", unsafe {
+                roc_receiver_bind(receiver.receiver, ROC_SLOT_DEFAULT, iface, endpoint.0)
+            })?;
+        }
+
+        tracing::debug!("opened roc receiver: {:?}", connection.external_uris());
+
+        Ok(Box::new(receiver))
+    }
+
+    fn open_sender(
+        &self, connection: &ConnectionSpec, encoding: &EncodingSpec, external_clock: bool,
+    ) -> TransportResult<Box<dyn AudioSink>> {
+        let fec_encoding = match connection.fec_encoding() {
+            Some(FecEncoding::Disable) => ROC_FEC_ENCODING_DISABLE,
+            Some(FecEncoding::Rs8m) => ROC_FEC_ENCODING_RS8M,
+            Some(FecEncoding::Ldpc) => ROC_FEC_ENCODING_LDPC_STAIRCASE,
+            None => {
+                return Err(TransportError::ConnectionError(
+                    "expected external connection".into(),
+                ));
+            },
+        };
+
+        let config = roc_sender_config {
+            frame_encoding: media_encoding(encoding),
+            packet_encoding: self.packet_encoding(encoding)?,
+            fec_encoding,
+            clock_source: clock_source(external_clock),
+            ..Default::default()
+        };
+
+        let mut sender = ptr::null_mut();
+        check_code("roc_sender_open", unsafe {
+            roc_sender_open(self.context.0, &config, &mut sender)
+        })?;
+
+        // wrap immediately, so that sender is closed on error
+        let sender = RocSender {
+            _context: Arc::clone(&self.context),
+            sender,
+            format: AudioFormat::from_encoding(encoding),
+            external_clock,
+        };
+
+        for (iface, uri) in interfaces(connection)? {
+            if let Some(iface_config) = interface_config(connection, uri, true)? {
+                check_code("roc_sender_configure", unsafe {
+                    roc_sender_configure(sender.sender, ROC_SLOT_DEFAULT, iface, &iface_config)
+                })?;
+            }
+
+            let endpoint = RocEndpoint::new(uri)?;
+            check_code("roc_sender_connect", unsafe {
+                roc_sender_connect(sender.sender, ROC_SLOT_DEFAULT, iface, endpoint.0)
+            })?;
+        }
+
+        tracing::debug!("opened roc sender: {:?}", connection.external_uris());
+
+        Ok(Box::new(sender))
+    }
+}
+
+struct RocContext(*mut roc_context);
+
+// libroc objects are thread-safe
+unsafe impl Send for RocContext {}
+unsafe impl Sync for RocContext {}
+
+impl Drop for RocContext {
+    fn drop(&mut self) {
+        // context is referenced by all senders and receivers,
+        // so they're already closed at this point
+        if let Err(err) = check_code("roc_context_close", unsafe { roc_context_close(self.0) })
+        {
+            tracing::warn!("can't close roc context: {err}");
+        }
+    }
+}
+
+struct RocEndpoint(*mut roc_endpoint);
+
+impl RocEndpoint {
+    fn new(uri: &Uri) -> TransportResult<Self> {
+        let uri_str = CString::new(uri.to_string())
+            .map_err(|_| TransportError::ConnectionError(format!("invalid uri: {uri}")))?;
+
+        let mut endpoint = ptr::null_mut();
+        check_code("roc_endpoint_allocate", unsafe { roc_endpoint_allocate(&mut endpoint) })?;
+
+        let endpoint = RocEndpoint(endpoint);
+        check_code("roc_endpoint_set_uri", unsafe {
+            roc_endpoint_set_uri(endpoint.0, uri_str.as_ptr())
+        })?;
+
+        Ok(endpoint)
+    }
+}
+
+impl Drop for RocEndpoint {
+    fn drop(&mut self) {
+        unsafe { roc_endpoint_deallocate(self.0) };
+    }
+}
+
+struct RocReceiver {
+    _context: Arc<RocContext>,
+    receiver: *mut roc_receiver,
+    format: AudioFormat,
+    external_clock: bool,
+}
+
+unsafe impl Send for RocReceiver {}
+
+impl AudioSource for RocReceiver {
+    fn format(&self) -> AudioFormat {
+        self.format
+    }
+
+    fn is_clocked(&self) -> bool {
+        !self.external_clock
+    }
+
+    fn read(&mut self, samples: &mut [f32]) -> Result<(), AudioError> {
+        let mut frame = roc_frame {
+            samples: samples.as_mut_ptr() as *mut c_void,
+            samples_size: size_of_val(samples),
+        };
+
+        check_code("roc_receiver_read", unsafe {
+            roc_receiver_read(self.receiver, &mut frame)
+        })
+        .map_err(|err| AudioError::BackendError(err.to_string()))
+    }
+
+    fn metrics(&self) -> Option<LinkMetrics> {
+        let mut slot_metrics = roc_receiver_metrics::default();
+        let mut conn_metrics = roc_connection_metrics::default();
+        let mut conn_count = 1;
+
+        let code = unsafe {
+            roc_receiver_query(
+                self.receiver,
+                ROC_SLOT_DEFAULT,
+                &mut slot_metrics,
+                &mut conn_metrics,
+                &mut conn_count,
+            )
+        };
+        if code != 0 {
+            return None;
+        }
+
+        Some(link_metrics(slot_metrics.connection_count, &conn_metrics, conn_count))
+    }
+}
+
+impl Drop for RocReceiver {
+    fn drop(&mut self) {
+        unsafe { roc_receiver_close(self.receiver) };
+    }
+}
+
+struct RocSender {
+    _context: Arc<RocContext>,
+    sender: *mut roc_sender,
+    format: AudioFormat,
+    external_clock: bool,
+}
+
+unsafe impl Send for RocSender {}
+
+impl AudioSink for RocSender {
+    fn format(&self) -> AudioFormat {
+        self.format
+    }
+
+    fn is_clocked(&self) -> bool {
+        !self.external_clock
+    }
+
+    fn write(&mut self, samples: &[f32]) -> Result<(), AudioError> {
+        let frame = roc_frame {
+            samples: samples.as_ptr() as *mut c_void,
+            samples_size: size_of_val(samples),
+        };
+
+        check_code("roc_sender_write", unsafe { roc_sender_write(self.sender, &frame) })
+            .map_err(|err| AudioError::BackendError(err.to_string()))
+    }
+
+    fn metrics(&self) -> Option<LinkMetrics> {
+        let mut slot_metrics = roc_sender_metrics::default();
+        let mut conn_metrics = roc_connection_metrics::default();
+        let mut conn_count = 1;
+
+        let code = unsafe {
+            roc_sender_query(
+                self.sender,
+                ROC_SLOT_DEFAULT,
+                &mut slot_metrics,
+                &mut conn_metrics,
+                &mut conn_count,
+            )
+        };
+        if code != 0 {
+            return None;
+        }
+
+        Some(link_metrics(slot_metrics.connection_count, &conn_metrics, conn_count))
+    }
+}
+
+impl Drop for RocSender {
+    fn drop(&mut self) {
+        unsafe { roc_sender_close(self.sender) };
+    }
+}
+
+/// Convert libroc return code to result.
+fn check_code(func: &'static str, code: c_int) -> TransportResult<()> {
+    match code {
+        0 => Ok(()),
+        code => Err(TransportError::BackendError(func, code)),
+    }
+}
+
+fn media_encoding(encoding: &EncodingSpec) -> roc_media_encoding {
+    let (channels, tracks) = match encoding.channels {
+        1 => (ROC_CHANNEL_LAYOUT_MONO, 0),
+        2 => (ROC_CHANNEL_LAYOUT_STEREO, 0),
+        n => (ROC_CHANNEL_LAYOUT_MULTITRACK, n as u32),
+    };
+
+    roc_media_encoding {
+        rate: encoding.sample_rate,
+        format: ROC_FORMAT_PCM_FLOAT32,
+        channels,
+        tracks,
+    }
+}
+
+fn clock_source(external_clock: bool) -> c_int {
+    if external_clock { ROC_CLOCK_SOURCE_EXTERNAL } else { ROC_CLOCK_SOURCE_INTERNAL }
+}
+
+/// Get libroc interfaces and URIs of external connection.
+fn interfaces(connection: &ConnectionSpec) -> TransportResult<Vec<(c_int, &Uri)>> {
+    let ConnectionSpec::External { media_uri, repair_uri, control_uri, .. } = connection
+    else {
+        return Err(TransportError::ConnectionError("expected external connection".into()));
+    };
+
+    let mut result = vec![(ROC_INTERFACE_AUDIO_SOURCE, media_uri)];
+    if let Some(repair_uri) = repair_uri {
+        result.push((ROC_INTERFACE_AUDIO_REPAIR, repair_uri));
+    }
+    result.push((ROC_INTERFACE_AUDIO_CONTROL, control_uri));
+
+    Ok(result)
+}
+
+/// Build libroc interface config for multicast URI.
+/// Returns None for unicast URI or when default interface should be used.
+fn interface_config(
+    connection: &ConnectionSpec, uri: &Uri, is_sender: bool,
+) -> TransportResult<Option<roc_interface_config>> {
+    let ConnectionSpec::External { multicast_interface, .. } = connection else {
+        return Ok(None);
+    };
+    let (Some(iface_name), true) = (multicast_interface, uri.is_multicast()) else {
+        return Ok(None);
+    };
+
+    let interfaces = list_interfaces()?;
+    let iface = find_interface(&interfaces, iface_name)
+        .map_err(|err| TransportError::ConnectionError(err.to_string()))?;
+
+    // libroc selects interface by its address
+    let iface_addr = match uri.ip_addr() {
+        Some(IpAddr::V4(_)) => iface.ipv4_addr.map(|addr| addr.to_string()),
+        _ => None,
+    };
+    let Some(iface_addr) = iface_addr else {
+        tracing::warn!(
+            "can't select interface {iface_name} for {uri} in libroc, using default"
+        );
+        return Ok(None);
+    };
+
+    let mut config = roc_interface_config::default();
+    if is_sender {
+        copy_cstr(&mut config.outgoing_address, &iface_addr);
+    } else {
+        copy_cstr(&mut config.multicast_group, &iface_addr);
+        config.reuse_address = 1;
+    }
+
+    Ok(Some(config))
+}
+
+/// Copy string to fixed-size C buffer, keeping terminating zero.
+fn copy_cstr(dst: &mut [c_char], src: &str) {
+    let max_len = dst.len() - 1;
+    for (dst_char, src_byte) in dst.iter_mut().zip(src.bytes().take(max_len)) {
+        *dst_char = src_byte as c_char;
+    }
+}
+
+fn link_metrics(
+    connection_count: u32, conn_metrics: &roc_connection_metrics, conn_count: usize,
+) -> LinkMetrics {
+    if conn_count == 0 {
+        return LinkMetrics { connection_count, ..Default::default() };
+    }
+
+    LinkMetrics {
+        connection_count,
+        e2e_latency: Duration::from_nanos(conn_metrics.e2e_latency),
+        // library default is used, it's not reported in metrics;
+        // group target latency isn't passed to libroc, groups are
+        // aligned by delaying playout instead
+        target_latency: Duration::ZERO,
+        mean_jitter: Duration::from_nanos(conn_metrics.mean_jitter),
+        expected_packets: conn_metrics.expected_packets,
+        lost_packets: conn_metrics.lost_packets,
+    }
+}
@@ -0,0 +1,340 @@
+// Copyright (c) Roc Streaming authors
+// Licensed under MPL-2.0
+use crate::dto::SampleFormat;
+
+/// Fixed RTP header (RFC 3550), without CSRC list and extensions.
+#[derive(Clone, PartialEq, Debug)]
+pub struct RtpHeader {
+    pub payload_type: u8,
+    pub marker: bool,
+    pub seqnum: u16,
+    pub timestamp: u32,
+    pub ssrc: u32,
+}
+
+impl RtpHeader {
+    pub const SIZE: usize = 12;
+
+    const VERSION: u8 = 2;
+
+    /// Parse RTP packet into header and payload.
+    /// CSRC list, extension, and padding are skipped.
+    pub fn parse(packet: &[u8]) -> Option<(RtpHeader, &[u8])> {
+        if packet.len() < Self::SIZE || packet[0] >> 6 != Self::VERSION {
+            return None;
+        }
+
+        let has_padding = packet[0] & 0x20 != 0;
+        let has_extension = packet[0] & 0x10 != 0;
+        let csrc_count = (packet[0] & 0x0f) as usize;
+
+        let header = RtpHeader {
+            payload_type: packet[1] & 0x7f,
+            marker: packet[1] & 0x80 != 0,
+            seqnum: u16::from_be_bytes([packet[2], packet[3]]),
+            timestamp: u32::from_be_bytes(packet[4..8].try_into().unwrap()),
+            ssrc: u32::from_be_bytes(packet[8..12].try_into().unwrap()),
+        };
+
+        let mut begin = Self::SIZE + csrc_count * 4;
+        if has_extension {
+            let ext = packet.get(begin..begin + 4)?;
+            begin += 4 + u16::from_be_bytes([ext[2], ext[3]]) as usize * 4;
+        }
+
+        let mut end = packet.len();
+        if has_padding {
+            end = end.checked_sub(*packet.last()? as usize)?;
+        }
+
+        Some((header, packet.get(begin..end)?))
+    }
+
+    /// Append serialized header to buffer.
+    pub fn write(&self, buf: &mut Vec<u8>) {
+        buf.push(Self::VERSION << 6);
+        buf.push((self.marker as u8) << 7 | (self.payload_type & 0x7f));
+        buf.extend_from_slice(&self.seqnum.to_be_bytes());
+        buf.extend_from_slice(&self.timestamp.to_be_bytes());
+        buf.extend_from_slice(&self.ssrc.to_be_bytes());
+    }
+}
+
+/// Size of one encoded sample, in bytes.
+pub fn sample_size(format: SampleFormat) -> usize {
+    match format {
+        SampleFormat::S16 => 2,
+        SampleFormat::S24 => 3,
+    }
+}
+
+/// Append samples encoded as big-endian PCM (L16 or L24) to buffer.
+pub fn encode_samples(format: SampleFormat, samples: &[f32], buf: &mut Vec<u8>) {
+    for sample in samples {
+        let sample = sample.clamp(-1.0, 1.0);
+        match format {
+            SampleFormat::S16 => {
+                let value = (sample * i16::MAX as f32).round() as i16;
+                buf.extend_from_slice(&value.to_be_bytes());
+            },
+            SampleFormat::S24 => {
+                let value = (sample * 0x7fffff as f32).round() as i32;
+                buf.extend_from_slice(&value.to_be_bytes()[1..]);
+            },
+        }
+    }
+}
+
+/// Append samples decoded from big-endian PCM (L16 or L24) to buffer.
+/// Incomplete trailing sample is ignored.
+pub fn decode_samples(format: SampleFormat, payload: &[u8], buf: &mut Vec<f32>) {
+    match format {
+        SampleFormat::S16 => {
+            for chunk in payload.chunks_exact(2) {
+                let value = i16::from_be_bytes([chunk[0], chunk[1]]);
+                buf.push(value as f32 / i16::MAX as f32);
+            }
+        },
+        SampleFormat::S24 => {
+            for chunk in payload.chunks_exact(3) {
+                // shift left and back to sign-extend
+                let value = i32::from_be_bytes([chunk[0], chunk[1], chunk[2], 0]) >> 8;
+                buf.push(value as f32 / 0x7fffff as f32);
+            }
+        },
+    }
+}
+
+/// Reception report block of RTCP SR or RR (RFC 3550).
+#[derive(Clone, PartialEq, Debug)]
+pub struct ReportBlock {
+    pub ssrc: u32,
+    pub fraction_lost: u8,
+    pub cumulative_lost: u32,
+    pub highest_seqnum: u32,
+    pub jitter: u32,
+    pub last_sr: u32,
+    pub delay_since_last_sr: u32,
+}
+
+/// Subset of RTCP packets (RFC 3550) used by RTP transport.
+#[derive(Clone, PartialEq, Debug)]
+pub enum RtcpPacket {
+    SenderReport {
+        ssrc: u32,
+        ntp_timestamp: u64,
+        rtp_timestamp: u32,
+        packet_count: u32,
+        octet_count: u32,
+    },
+    ReceiverReport {
+        ssrc: u32,
+        blocks: Vec<ReportBlock>,
+    },
+}
+
+impl RtcpPacket {
+    const VERSION: u8 = 2;
+    const PT_SR: u8 = 200;
+    const PT_RR: u8 = 201;
+    const BLOCK_SIZE: usize = 24;
+
+    /// Parse first packet of compound RTCP packet.
+    /// Returns None for malformed or unsupported packets.
+    pub fn parse(packet: &[u8]) -> Option<RtcpPacket> {
+        if packet.len() < 8 || packet[0] >> 6 != Self::VERSION {
+            return None;
+        }
+
+        let count = (packet[0] & 0x1f) as usize;
+        let length = (u16::from_be_bytes([packet[2], packet[3]]) as usize + 1) * 4;
+        let packet = packet.get(..length)?;
+
+        let read_u32 = |offset: usize| -> Option<u32> {
+            Some(u32::from_be_bytes(packet.get(offset..offset + 4)?.try_into().unwrap()))
+        };
+
+        match packet[1] {
+            Self::PT_SR => Some(RtcpPacket::SenderReport {
+                ssrc: read_u32(4)?,
+                ntp_timestamp: (read_u32(8)? as u64) << 32 | read_u32(12)? as u64,
+                rtp_timestamp: read_u32(16)?,
+                packet_count: read_u32(20)?,
+                octet_count: read_u32(24)?,
+            }),
+            Self::PT_RR => {
+                let mut blocks = Vec::with_capacity(count);
+                for n in 0..count {
+                    let offset = 8 + n * Self::BLOCK_SIZE;
+                    let lost = read_u32(offset + 4)?;
+                    blocks.push(ReportBlock {
+                        ssrc: read_u32(offset)?,
+                        fraction_lost: (lost >> 24) as u8,
+                        cumulative_lost: lost & 0xffffff,
+                        highest_seqnum: read_u32(offset + 8)?,
+                        jitter: read_u32(offset + 12)?,
+                        last_sr: read_u32(offset + 16)?,
+                        delay_since_last_sr: read_u32(offset + 20)?,
+                    });
+                }
+                Some(RtcpPacket::ReceiverReport { ssrc: read_u32(4)?, blocks })
+            },
+            _ => None,
+        }
+    }
+
+    /// Append serialized packet to buffer.
+    pub fn write(&self, buf: &mut Vec<u8>) {
+        match self {
+            RtcpPacket::SenderReport {
+                ssrc,
+                ntp_timestamp,
+                rtp_timestamp,
+                packet_count,
+                octet_count,
+            } => {
+                Self::write_header(buf, 0, Self::PT_SR, 28);
+                for word in [
+                    *ssrc,
+                    (ntp_timestamp >> 32) as u32,
+                    *ntp_timestamp as u32,
+                    *rtp_timestamp,
+                    *packet_count,
+                    *octet_count,
+                ] {
+                    buf.extend_from_slice(&word.to_be_bytes());
+                }
+            },
+            RtcpPacket::ReceiverReport { ssrc, blocks } => {
+                let blocks = &blocks[..blocks.len().min(31)];
+                Self::write_header(
+                    buf,
+                    blocks.len() as u8,
+                    Self::PT_RR,
+                    8 + blocks.len() * Self::BLOCK_SIZE,
+                );
+                buf.extend_from_slice(&ssrc.to_be_bytes());
+                for block in blocks {
+                    for word in [
+                        block.ssrc,
+                        (block.fraction_lost as u32) << 24
+                            | (block.cumulative_lost & 0xffffff),
+                        block.highest_seqnum,
+                        block.jitter,
+                        block.last_sr,
+                        block.delay_since_last_sr,
+                    ] {
+                        buf.extend_from_slice(&word.to_be_bytes());
+                    }
+                }
+            },
+        }
+    }
+
+    fn write_header(buf: &mut Vec<u8>, count: u8, packet_type: u8, size: usize) {
+        buf.push(Self::VERSION << 6 | count);
+        buf.push(packet_type);
+        buf.extend_from_slice(&((size / 4 - 1) as u16).to_be_bytes());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assertables::*;
+
+    #[test]
+    fn test_rtp_header() {
+        let header = RtpHeader {
+            payload_type: 96,
+            marker: true,
+            seqnum: 65535,
+            timestamp: 0xdeadbeef,
+            ssrc: 0x12345678,
+        };
+
+        let mut packet = Vec::new();
+        header.write(&mut packet);
+        packet.extend_from_slice(&[1, 2, 3, 4]);
+
+        assert_eq!(packet.len(), RtpHeader::SIZE + 4);
+        assert_eq!(RtpHeader::parse(&packet), Some((header, &[1u8, 2, 3, 4][..])));
+
+        // truncated
+        assert_none!(RtpHeader::parse(&packet[..RtpHeader::SIZE - 1]));
+        // wrong version
+        packet[0] = 0x40;
+        assert_none!(RtpHeader::parse(&packet));
+    }
+
+    #[test]
+    fn test_rtp_padding() {
+        let header =
+            RtpHeader { payload_type: 10, marker: false, seqnum: 1, timestamp: 2, ssrc: 3 };
+
+        let mut packet = Vec::new();
+        header.write(&mut packet);
+        packet[0] |= 0x20;
+        packet.extend_from_slice(&[1, 2, 0, 2]);
+
+        assert_eq!(RtpHeader::parse(&packet), Some((header, &[1u8, 2][..])));
+    }
+
+    #[test]
+    fn test_samples() {
+        let samples = [0.0, 0.5, -0.5, 1.0, -1.0];
+
+        for format in [SampleFormat::S16, SampleFormat::S24] {
+            let mut payload = Vec::new();
+            encode_samples(format, &samples, &mut payload);
+            assert_eq!(payload.len(), samples.len() * sample_size(format));
+
+            let mut decoded = Vec::new();
+            decode_samples(format, &payload, &mut decoded);
+            assert_eq!(decoded.len(), samples.len());
+
+            for (orig, decoded) in samples.iter().zip(&decoded) {
+                assert_in_delta!(*orig, *decoded, 0.0001, "{format}");
+            }
+        }
+
+        // L24 is big-endian and sign-extended
+        let mut decoded = Vec::new();
+        decode_samples(SampleFormat::S24, &[0xff, 0xff, 0xff], &mut decoded);
+        assert_in_delta!(decoded[0], 0.0, 0.0001);
+        assert_lt!(decoded[0], 0.0);
+    }
+
+    #[test]
+    fn test_rtcp() {
+        let packets = [
+            RtcpPacket::SenderReport {
+                ssrc: 1,
+                ntp_timestamp: 0x0102030405060708,
+                rtp_timestamp: 3,
+                packet_count: 4,
+                octet_count: 5,
+            },
+            RtcpPacket::ReceiverReport { ssrc: 1, blocks: vec![] },
+            RtcpPacket::ReceiverReport {
+                ssrc: 1,
+                blocks: vec![ReportBlock {
+                    ssrc: 2,
+                    fraction_lost: 3,
+                    cumulative_lost: 4,
+                    highest_seqnum: 5,
+                    jitter: 6,
+                    last_sr: 7,
+                    delay_since_last_sr: 8,
+                }],
+            },
+        ];
+
+        for packet in packets {
+            let mut buf = Vec::new();
+            packet.write(&mut buf);
+            assert_eq!(buf.len() % 4, 0);
+            assert_eq!(RtcpPacket::parse(&buf), Some(packet));
+        }
+    }
+}
@@ -0,0 +1,591 @@
+// Copyright (c) Roc Streaming authors
+// Licensed under MPL-2.0
+use crate::audio::*;
+use crate::dto::*;
+use crate::io_streams::error::*;
+use crate::io_streams::jitter_buffer::*;
+use crate::io_streams::multicast::*;
+use crate::io_streams::rtp_packet::*;
+use crate::io_streams::transport::*;
+
+use std::io;
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr, UdpSocket};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Latency maintained by receiver jitter buffer.
+const TARGET_LATENCY: Duration = Duration::from_millis(200);
+
+/// Duration of audio in one packet.
+const PACKET_DURATION: Duration = Duration::from_millis(5);
+
+/// Maximum payload size, to fit packets into typical MTU.
+const MAX_PAYLOAD_SIZE: usize = 1200;
+
+/// How often RTCP reports are sent.
+const REPORT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Peer is considered disconnected if nothing was received during this time.
+const PEER_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Receive timeout of network thread, defines how fast it notices stop.
+const RECV_TIMEOUT: Duration = Duration::from_millis(50);
+
+/// Built-in transport implementing plain RTP/RTCP without FEC.
+///
+/// Used for rtp:// URIs when libroc is not available or doesn't support
+/// encoding (e.g. L24). Compatible with other RTP implementations that
+/// use L16 or L24 payloads.
+pub struct RtpTransport {}
+
+impl RtpTransport {
+    pub fn new() -> Self {
+        RtpTransport {}
+    }
+}
+
+impl Transport for RtpTransport {
+    fn name(&self) -> &'static str {
+        "rtp"
+    }
+
+    fn supports(&self, connection: &ConnectionSpec, _encoding: &EncodingSpec) -> bool {
+        connection.fec_encoding() == Some(FecEncoding::Disable)
+    }
+
+    fn open_receiver(
+        &self, connection: &ConnectionSpec, encoding: &EncodingSpec, external_clock: bool,
+    ) -> TransportResult<Box<dyn AudioSource>> {
+        let (media_uri, control_uri) = connection_uris(connection)?;
+        let iface = connection_iface(connection)?;
+
+        let media_socket = bind_socket(&socket_addr(media_uri)?, iface.as_ref())?;
+        let control_socket = bind_socket(&socket_addr(control_uri)?, iface.as_ref())?;
+
+        media_socket.set_read_timeout(Some(RECV_TIMEOUT))?;
+        control_socket.set_nonblocking(true)?;
+
+        let format = AudioFormat::from_encoding(encoding);
+        let state = Arc::new(Mutex::new(ReceiverState::new(format)));
+        let stop_flag = Arc::new(AtomicBool::new(false));
+
+        let thread = {
+            let worker = ReceiverWorker {
+                media_socket,
+                control_socket,
+                encoding: encoding.clone(),
+                format,
+                ssrc: rand::random(),
+                state: Arc::clone(&state),
+                stop_flag: Arc::clone(&stop_flag),
+            };
+            thread::Builder::new().name("rtp-recv".into()).spawn(move || worker.run())?
+        };
+
+        tracing::debug!("opened rtp receiver: media={media_uri} control={control_uri}");
+
+        Ok(Box::new(RtpReceiver {
+            format,
+            external_clock,
+            deadline: None,
+            state,
+            stop_flag,
+            thread: Some(thread),
+        }))
+    }
+
+    fn open_sender(
+        &self, connection: &ConnectionSpec, encoding: &EncodingSpec, external_clock: bool,
+    ) -> TransportResult<Box<dyn AudioSink>> {
+        let (media_uri, control_uri) = connection_uris(connection)?;
+        let iface = connection_iface(connection)?;
+
+        let media_addr = socket_addr(media_uri)?;
+        let control_addr = socket_addr(control_uri)?;
+
+        let socket = if media_addr.ip().is_multicast() {
+            let ttl = match connection {
+                ConnectionSpec::External { multicast_ttl, .. } => *multicast_ttl,
+                _ => None,
+            };
+            open_std_multicast_sender(&media_addr, iface.as_ref(), ttl)?
+        } else if media_addr.is_ipv4() {
+            UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0))?
+        } else {
+            UdpSocket::bind((Ipv6Addr::UNSPECIFIED, 0))?
+        };
+
+        // writes are invoked on real-time thread
+        socket.set_nonblocking(true)?;
+
+        let format = AudioFormat::from_encoding(encoding);
+        let frame_size = format.channels as usize * sample_size(encoding.sample_format);
+        let packet_frames = (format.samples_per_duration(PACKET_DURATION)
+            / format.channels as usize)
+            .clamp(1, MAX_PAYLOAD_SIZE / frame_size);
+
+        tracing::debug!("opened rtp sender: media={media_uri} control={control_uri}");
+
+        Ok(Box::new(RtpSender {
+            socket,
+            media_addr,
+            control_addr,
+            encoding: encoding.clone(),
+            format,
+            external_clock,
+            deadline: None,
+            ssrc: rand::random(),
+            seqnum: rand::random(),
+            timestamp: rand::random(),
+            packet_frames,
+            pending: Vec::new(),
+            buf: Vec::with_capacity(RtpHeader::SIZE + MAX_PAYLOAD_SIZE),
+            packet_count: 0,
+            octet_count: 0,
+            report_time: None,
+            receiver_report: None,
+        }))
+    }
+}
+
+struct RtpReceiver {
+    format: AudioFormat,
+    external_clock: bool,
+    deadline: Option<Instant>,
+    state: Arc<Mutex<ReceiverState>>,
+    stop_flag: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl AudioSource for RtpReceiver {
+    fn format(&self) -> AudioFormat {
+        self.format
+    }
+
+    fn is_clocked(&self) -> bool {
+        !self.external_clock
+    }
+
+    fn read(&mut self, samples: &mut [f32]) -> Result<(), AudioError> {
+        if !self.external_clock {
+            pace(&mut self.deadline, self.format.duration_of_samples(samples.len()));
+        }
+
+        self.state.lock().unwrap().jitter_buffer.pop(samples);
+
+        Ok(())
+    }
+
+    fn metrics(&self) -> Option<LinkMetrics> {
+        let state = self.state.lock().unwrap();
+
+        let is_connected =
+            state.last_packet_time.is_some_and(|time| time.elapsed() < PEER_TIMEOUT);
+        let expected_packets = match (state.first_seqnum, state.highest_seqnum) {
+            (Some(first), Some(highest)) => highest - first + 1,
+            _ => 0,
+        };
+        let channels = self.format.channels as usize;
+
+        Some(LinkMetrics {
+            connection_count: is_connected as u32,
+            e2e_latency: self
+                .format
+                .duration_of_samples(state.jitter_buffer.level() as usize * channels),
+            target_latency: TARGET_LATENCY,
+            mean_jitter: Duration::from_secs_f64(
+                state.jitter / self.format.sample_rate as f64,
+            ),
+            expected_packets,
+            lost_packets: expected_packets.saturating_sub(state.received_packets),
+        })
+    }
+}
+
+impl Drop for RtpReceiver {
+    fn drop(&mut self) {
+        self.stop_flag.store(true, Ordering::SeqCst);
+
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Receiver state shared between network thread and pipeline thread.
+struct ReceiverState {
+    jitter_buffer: JitterBuffer,
+    sender_ssrc: Option<u32>,
+    // extended sequence numbers
+    first_seqnum: Option<u64>,
+    highest_seqnum: Option<u64>,
+    received_packets: u64,
+    // interarrival jitter (RFC 3550), in timestamp units
+    jitter: f64,
+    last_transit: Option<(Instant, u32)>,
+    last_packet_time: Option<Instant>,
+}
+
+impl ReceiverState {
+    fn new(format: AudioFormat) -> Self {
+        ReceiverState {
+            jitter_buffer: JitterBuffer::new(
+                format.channels as usize,
+                format.samples_per_duration(TARGET_LATENCY) as u64 / format.channels as u64,
+            ),
+            sender_ssrc: None,
+            first_seqnum: None,
+            highest_seqnum: None,
+            received_packets: 0,
+            jitter: 0.0,
+            last_transit: None,
+            last_packet_time: None,
+        }
+    }
+}
+
+struct ReceiverWorker {
+    media_socket: UdpSocket,
+    control_socket: UdpSocket,
+    encoding: EncodingSpec,
+    format: AudioFormat,
+    ssrc: u32,
+    state: Arc<Mutex<ReceiverState>>,
+    stop_flag: Arc<AtomicBool>,
+}
+
+impl ReceiverWorker {
+    fn run(self) {
+        let mut packet = vec![0u8; 65536];
+        let mut samples = Vec::new();
+
+        // address of sender control socket and middle bits
+        // of NTP timestamp of its last report
+        let mut sender_report: Option<(SocketAddr, u32, Instant)> = None;
+        let mut report_time = Instant::now();
+
+        while !self.stop_flag.load(Ordering::Relaxed) {
+            match self.media_socket.recv(&mut packet) {
+                Ok(size) => {
+                    if let Some((header, payload)) = RtpHeader::parse(&packet[..size])
+                        && header.payload_type == self.encoding.payload_type
+                    {
+                        samples.clear();
+                        decode_samples(self.encoding.sample_format, payload, &mut samples);
+                        self.handle_packet(&header, std::mem::take(&mut samples));
+                    }
+                },
+                Err(err)
+                    if matches!(
+                        err.kind(),
+                        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                    ) => {},
+                Err(err) => {
+                    tracing::warn!("rtp receiver: can't receive packet: {err}");
+                    thread::sleep(RECV_TIMEOUT);
+                },
+            }
+
+            while let Ok((size, addr)) = self.control_socket.recv_from(&mut packet) {
+                if let Some(RtcpPacket::SenderReport { ntp_timestamp, .. }) =
+                    RtcpPacket::parse(&packet[..size])
+                {
+                    sender_report = Some((addr, (ntp_timestamp >> 16) as u32, Instant::now()));
+                }
+            }
+
+            if let Some((addr, last_sr, sr_time)) = sender_report
+                && report_time.elapsed() >= REPORT_INTERVAL
+            {
+                report_time = Instant::now();
+                self.send_report(addr, last_sr, sr_time);
+            }
+        }
+    }
+
+    fn handle_packet(&self, header: &RtpHeader, samples: Vec<f32>) {
+        let mut state = self.state.lock().unwrap();
+
+        if state.sender_ssrc != Some(header.ssrc) {
+            tracing::debug!("rtp receiver: new sender ssrc={}", header.ssrc);
+            *state = ReceiverState::new(self.format);
+            state.sender_ssrc = Some(header.ssrc);
+        }
+
+        let now = Instant::now();
+
+        // extend sequence number relative to highest one
+        let seqnum = match state.highest_seqnum {
+            Some(highest) => {
+                let delta = header.seqnum.wrapping_sub(highest as u16) as i16 as i64;
+                (highest as i64 + delta).max(0) as u64
+            },
+            None => (1 << 16) + header.seqnum as u64,
+        };
+        state.first_seqnum =
+            Some(state.first_seqnum.map_or(seqnum, |first| first.min(seqnum)));
+        state.highest_seqnum =
+            Some(state.highest_seqnum.map_or(seqnum, |high| high.max(seqnum)));
+        state.received_packets += 1;
+
+        // interarrival jitter, RFC 3550 section 6.4.1
+        if let Some((last_time, last_timestamp)) = state.last_transit {
+            let arrival_delta =
+                (now - last_time).as_secs_f64() * self.format.sample_rate as f64;
+            let timestamp_delta = header.timestamp.wrapping_sub(last_timestamp) as i32 as f64;
+            let d = (arrival_delta - timestamp_delta).abs();
+            state.jitter += (d - state.jitter) / 16.0;
+        }
+        state.last_transit = Some((now, header.timestamp));
+        state.last_packet_time = Some(now);
+
+        state.jitter_buffer.push(header.timestamp, samples);
+    }
+
+    fn send_report(&self, addr: SocketAddr, last_sr: u32, sr_time: Instant) {
+        let block = {
+            let state = self.state.lock().unwrap();
+            let (Some(sender_ssrc), Some(first), Some(highest)) =
+                (state.sender_ssrc, state.first_seqnum, state.highest_seqnum)
+            else {
+                return;
+            };
+            let expected = highest - first + 1;
+            let lost = expected.saturating_sub(state.received_packets);
+
+            ReportBlock {
+                ssrc: sender_ssrc,
+                fraction_lost: (lost * 256 / expected).min(255) as u8,
+                cumulative_lost: lost.min(0xffffff) as u32,
+                highest_seqnum: highest as u32,
+                jitter: state.jitter as u32,
+                last_sr,
+                delay_since_last_sr: (sr_time.elapsed().as_secs_f64() * 65536.0) as u32,
+            }
+        };
+
+        let mut buf = Vec::new();
+        RtcpPacket::ReceiverReport { ssrc: self.ssrc, blocks: vec![block] }.write(&mut buf);
+
+        if let Err(err) = self.control_socket.send_to(&buf, addr) {
+            tracing::debug!("rtp receiver: can't send report to {addr}: {err}");
+        }
+    }
+}
+
+struct RtpSender {
+    socket: UdpSocket,
+    media_addr: SocketAddr,
+    control_addr: SocketAddr,
+    encoding: EncodingSpec,
+    format: AudioFormat,
+    external_clock: bool,
+    deadline: Option<Instant>,
+    ssrc: u32,
+    seqnum: u16,
+    timestamp: u32,
+    packet_frames: usize,
+    // samples not yet sent
+    pending: Vec<f32>,
+    buf: Vec<u8>,
+    packet_count: u32,
+    octet_count: u32,
+    report_time: Option<Instant>,
+    receiver_report: Option<(ReportBlock, Instant)>,
+}
+
+impl RtpSender {
+    fn send_packet(&mut self, n_samples: usize) -> Result<(), AudioError> {
+        self.buf.clear();
+
+        RtpHeader {
+            payload_type: self.encoding.payload_type,
+            marker: self.packet_count == 0,
+            seqnum: self.seqnum,
+            timestamp: self.timestamp,
+            ssrc: self.ssrc,
+        }
+        .write(&mut self.buf);
+
+        encode_samples(self.encoding.sample_format, &self.pending[..n_samples], &mut self.buf);
+        self.pending.drain(..n_samples);
+
+        self.seqnum = self.seqnum.wrapping_add(1);
+        self.timestamp =
+            self.timestamp.wrapping_add((n_samples / self.format.channels as usize) as u32);
+        self.packet_count = self.packet_count.wrapping_add(1);
+        self.octet_count =
+            self.octet_count.wrapping_add((self.buf.len() - RtpHeader::SIZE) as u32);
+
+        send_nonblocking(&self.socket, &self.buf, self.media_addr)
+    }
+
+    fn send_report(&mut self) -> Result<(), AudioError> {
+        self.buf.clear();
+
+        RtcpPacket::SenderReport {
+            ssrc: self.ssrc,
+            ntp_timestamp: ntp_now(),
+            rtp_timestamp: self.timestamp,
+            packet_count: self.packet_count,
+            octet_count: self.octet_count,
+        }
+        .write(&mut self.buf);
+
+        send_nonblocking(&self.socket, &self.buf, self.control_addr)
+    }
+
+    fn poll_reports(&mut self) {
+        let mut packet = [0u8; 1500];
+
+        while let Ok((size, _)) = self.socket.recv_from(&mut packet) {
+            if let Some(RtcpPacket::ReceiverReport { blocks, .. }) =
+                RtcpPacket::parse(&packet[..size])
+                && let Some(block) = blocks.into_iter().find(|block| block.ssrc == self.ssrc)
+            {
+                self.receiver_report = Some((block, Instant::now()));
+            }
+        }
+    }
+}
+
+impl AudioSink for RtpSender {
+    fn format(&self) -> AudioFormat {
+        self.format
+    }
+
+    fn is_clocked(&self) -> bool {
+        !self.external_clock
+    }
+
+    fn write(&mut self, samples: &[f32]) -> Result<(), AudioError> {
+        if !self.external_clock {
+            pace(&mut self.deadline, self.format.duration_of_samples(samples.len()));
+        }
+
+        self.pending.extend_from_slice(samples);
+
+        let packet_samples = self.packet_frames * self.format.channels as usize;
+        while self.pending.len() >= packet_samples {
+            self.send_packet(packet_samples)?;
+        }
+
+        if self.report_time.is_none_or(|time| time.elapsed() >= REPORT_INTERVAL) {
+            self.report_time = Some(Instant::now());
+            self.send_report()?;
+        }
+
+        self.poll_reports();
+
+        Ok(())
+    }
+
+    fn metrics(&self) -> Option<LinkMetrics> {
+        let Some((block, _)) =
+            self.receiver_report.as_ref().filter(|(_, time)| time.elapsed() < PEER_TIMEOUT)
+        else {
+            return Some(LinkMetrics::default());
+        };
+
+        // round-trip time, RFC 3550 section 6.4.1
+        let ntp_middle = (ntp_now() >> 16) as u32;
+        let rtt =
+            ntp_middle.wrapping_sub(block.last_sr).wrapping_sub(block.delay_since_last_sr);
+        let rtt = if block.last_sr != 0 && rtt < 0x10000 * 10 {
+            Duration::from_secs_f64(rtt as f64 / 65536.0)
+        } else {
+            Duration::ZERO
+        };
+
+        Some(LinkMetrics {
+            connection_count: 1,
+            // one-way network delay plus receiver latency
+            e2e_latency: rtt / 2 + TARGET_LATENCY,
+            // receiver is assumed to use same target as ours
+            target_latency: TARGET_LATENCY,
+            mean_jitter: Duration::from_secs_f64(
+                block.jitter as f64 / self.format.sample_rate as f64,
+            ),
+            expected_packets: block.highest_seqnum as u64,
+            lost_packets: block.cumulative_lost as u64,
+        })
+    }
+}
+
+/// Sleep until deadline, then move deadline forward by given duration.
+fn pace(deadline: &mut Option<Instant>, duration: Duration) {
+    let now = Instant::now();
+    let next = deadline.get_or_insert(now);
+
+    if *next > now {
+        thread::sleep(*next - now);
+    } else if now - *next > duration * 10 {
+        // we're too late, don't try to catch up
+        *next = now;
+    }
+
+    *next += duration;
+}
+
+/// Send datagram, dropping it if socket buffer is full.
+fn send_nonblocking(
+    socket: &UdpSocket, buf: &[u8], addr: SocketAddr,
+) -> Result<(), AudioError> {
+    match socket.send_to(buf, addr) {
+        Ok(_) => Ok(()),
+        Err(err) if err.kind() == io::ErrorKind::WouldBlock => Ok(()),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Current time in NTP format (seconds since 1900 in 32.32 fixed point).
+fn ntp_now() -> u64 {
+    const NTP_EPOCH_OFFSET: u64 = 2_208_988_800;
+
+    let since_epoch = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+    let seconds = since_epoch.as_secs() + NTP_EPOCH_OFFSET;
+    let fraction = ((since_epoch.subsec_nanos() as u64) << 32) / 1_000_000_000;
+
+    seconds << 32 | fraction
+}
+
+fn connection_uris(connection: &ConnectionSpec) -> TransportResult<(&Uri, &Uri)> {
+    match connection {
+        ConnectionSpec::External { media_uri, control_uri, .. } => {
+            Ok((media_uri, control_uri))
+        },
+        _ => Err(TransportError::ConnectionError("expected external connection".into())),
+    }
+}
+
+fn connection_iface(connection: &ConnectionSpec) -> TransportResult<Option<NetInterface>> {
+    let ConnectionSpec::External { multicast_interface: Some(iface_name), .. } = connection
+    else {
+        return Ok(None);
+    };
+
+    let interfaces = list_interfaces()?;
+    let iface = find_interface(&interfaces, iface_name)
+        .map_err(|err| TransportError::ConnectionError(err.to_string()))?;
+
+    Ok(Some(iface.clone()))
+}
+
+fn socket_addr(uri: &Uri) -> TransportResult<SocketAddr> {
+    match (uri.ip_addr(), uri.port()) {
+        (Some(addr), Some(port)) => Ok(SocketAddr::new(addr, port)),
+        _ => Err(TransportError::ConnectionError(format!(
+            "expected IP address and port: {uri}"
+        ))),
+    }
+}
+
+fn bind_socket(addr: &SocketAddr, iface: Option<&NetInterface>) -> TransportResult<UdpSocket> {
+    if addr.ip().is_multicast() {
+        Ok(open_std_multicast_receiver(addr, iface)?)
+    } else {
+        Ok(UdpSocket::bind(addr)?)
+    }
+}
@@ -0,0 +1,197 @@
+// Copyright (c) Roc Streaming authors
+// Licensed under MPL-2.0
+use crate::dto::*;
+
+use time::OffsetDateTime;
+
+/// Compute stream state at given time according to its schedule,
+/// and the next planned transition, if any.
+///
+/// Stream without schedule is always running.
+pub fn evaluate_schedule(
+    schedule: Option<&ScheduleSpec>, now: OffsetDateTime,
+) -> (StreamState, Option<TransitionSpec>) {
+    let Some(schedule) = schedule else {
+        return (StreamState::Running, None);
+    };
+
+    match &schedule.cron {
+        Some(cron) => evaluate_recurring(schedule, cron, now),
+        None => evaluate_oneshot(schedule, now),
+    }
+}
+
+fn evaluate_oneshot(
+    schedule: &ScheduleSpec, now: OffsetDateTime,
+) -> (StreamState, Option<TransitionSpec>) {
+    let stop_time = match (schedule.start_time, schedule.duration()) {
+        (Some(start_time), Some(duration)) => Some(start_time + duration),
+        _ => schedule.stop_time,
+    };
+
+    if let Some(start_time) = schedule.start_time
+        && now < start_time
+    {
+        return (
+            StreamState::Stopped,
+            Some(TransitionSpec { state: StreamState::Running, time: start_time }),
+        );
+    }
+
+    match stop_time {
+        Some(stop_time) if now < stop_time => (
+            StreamState::Running,
+            Some(TransitionSpec { state: StreamState::Stopped, time: stop_time }),
+        ),
+        Some(_) => (StreamState::Stopped, None),
+        None => (StreamState::Running, None),
+    }
+}
+
+fn evaluate_recurring(
+    schedule: &ScheduleSpec, cron: &str, now: OffsetDateTime,
+) -> (StreamState, Option<TransitionSpec>) {
+    let (Ok(expr), Some(duration)) = (CronExpr::parse(cron), schedule.duration()) else {
+        // not possible for validated spec
+        tracing::warn!("ignoring invalid schedule: {schedule:?}");
+        return (StreamState::Stopped, None);
+    };
+
+    // first occurrence which is not yet finished
+    match expr.next_after(now - duration) {
+        Some(start_time) if start_time <= now => (
+            StreamState::Running,
+            Some(TransitionSpec { state: StreamState::Stopped, time: start_time + duration }),
+        ),
+        Some(start_time) => (
+            StreamState::Stopped,
+            Some(TransitionSpec { state: StreamState::Running, time: start_time }),
+        ),
+        None => (StreamState::Stopped, None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::datetime;
+
+    fn transition(state: StreamState, time: OffsetDateTime) -> Option<TransitionSpec> {
+        Some(TransitionSpec { state, time })
+    }
+
+    #[test]
+    fn test_no_schedule() {
+        assert_eq!(
+            evaluate_schedule(None, datetime!(2026-03-10 10:00 UTC)),
+            (StreamState::Running, None)
+        );
+    }
+
+    #[test]
+    fn test_oneshot() {
+        let schedule = ScheduleSpec {
+            start_time: Some(datetime!(2026-03-10 10:00 UTC)),
+            stop_time: None,
+            duration: Some(1800),
+            cron: None,
+        };
+
+        let cases = vec![
+            (
+                datetime!(2026-03-10 09:00 UTC),
+                StreamState::Stopped,
+                transition(StreamState::Running, datetime!(2026-03-10 10:00 UTC)),
+            ),
+            (
+                datetime!(2026-03-10 10:00 UTC),
+                StreamState::Running,
+                transition(StreamState::Stopped, datetime!(2026-03-10 10:30 UTC)),
+            ),
+            (
+                datetime!(2026-03-10 10:29:59 UTC),
+                StreamState::Running,
+                transition(StreamState::Stopped, datetime!(2026-03-10 10:30 UTC)),
+            ),
+            (datetime!(2026-03-10 10:30 UTC), StreamState::Stopped, None),
+        ];
+
+        for (now, state, next) in cases {
+            assert_eq!(evaluate_schedule(Some(&schedule), now), (state, next), "{now}");
+        }
+    }
+
+    #[test]
+    fn test_oneshot_open_ended() {
+        // start only
+        let schedule = ScheduleSpec {
+            start_time: Some(datetime!(2026-03-10 10:00 UTC)),
+            stop_time: None,
+            duration: None,
+            cron: None,
+        };
+
+        assert_eq!(
+            evaluate_schedule(Some(&schedule), datetime!(2026-03-10 11:00 UTC)),
+            (StreamState::Running, None)
+        );
+
+        // stop only
+        let schedule = ScheduleSpec {
+            start_time: None,
+            stop_time: Some(datetime!(2026-03-10 10:00 UTC)),
+            duration: None,
+            cron: None,
+        };
+
+        assert_eq!(
+            evaluate_schedule(Some(&schedule), datetime!(2026-03-10 09:00 UTC)),
+            (
+                StreamState::Running,
+                transition(StreamState::Stopped, datetime!(2026-03-10 10:00 UTC))
+            )
+        );
+        assert_eq!(
+            evaluate_schedule(Some(&schedule), datetime!(2026-03-10 11:00 UTC)),
+            (StreamState::Stopped, None)
+        );
+    }
+
+    #[test]
+    fn test_recurring() {
+        // every day at 09:00 for 1 hour
+        let schedule = ScheduleSpec {
+            start_time: None,
+            stop_time: None,
+            duration: Some(3600),
+            cron: Some("0 9 * * *".into()),
+        };
+
+        let cases = vec![
+            (
+                datetime!(2026-03-10 08:00 UTC),
+                StreamState::Stopped,
+                transition(StreamState::Running, datetime!(2026-03-10 09:00 UTC)),
+            ),
+            (
+                datetime!(2026-03-10 09:00 UTC),
+                StreamState::Running,
+                transition(StreamState::Stopped, datetime!(2026-03-10 10:00 UTC)),
+            ),
+            (
+                datetime!(2026-03-10 09:59:30 UTC),
+                StreamState::Running,
+                transition(StreamState::Stopped, datetime!(2026-03-10 10:00 UTC)),
+            ),
+            (
+                datetime!(2026-03-10 10:00 UTC),
+                StreamState::Stopped,
+                transition(StreamState::Running, datetime!(2026-03-11 09:00 UTC)),
+            ),
+        ];
+
+        for (now, state, next) in cases {
+            assert_eq!(evaluate_schedule(Some(&schedule), now), (state, next), "{now}");
+        }
+    }
+}
@@ -1,40 +1,690 @@
 // Copyright (c) Roc Streaming authors
 // Licensed under MPL-2.0
-use crate::drivers::*;
+use crate::audio::PipelineStatus;
 use crate::dto::*;
+use crate::events::EventBus;
+use crate::io_endpoints::EndpointDispatcher;
+use crate::io_streams::error::*;
+use crate::io_streams::multicast::*;
+use crate::io_streams::port_allocator::*;
+use crate::io_streams::scheduler::*;
+use crate::io_streams::stream_runner::*;
+use crate::io_streams::transport_registry::*;
+use crate::recordings::{RecordingError, RecordingStore};
+use crate::vault::{Vault, VaultError};
 
-use std::sync::Arc;
+use std::result;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex as SyncMutex};
+use std::time::Duration;
+use time::OffsetDateTime;
+use tokio::fs::File;
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+
+pub type Result<T> = result::Result<T, StreamError>;
+
+/// How often background task checks leases, schedules, and pipelines.
+const TICK_INTERVAL: Duration = Duration::from_millis(500);
 
 pub struct StreamDispatcher {
-    driver: Arc<dyn Driver>,
+    endpoints: Arc<EndpointDispatcher>,
+    vault: Arc<Vault>,
+    recordings: Arc<RecordingStore>,
+    transports: Arc<TransportRegistry>,
+    port_allocator: PortAllocator,
+    runner: StreamRunner,
+    // serializes port allocation and stream writes
+    write_mutex: Mutex<()>,
+    meter_interval: Duration,
+    background_tasks: SyncMutex<Vec<JoinHandle<()>>>,
+    // set when streams were loaded from vault on startup, and when
+    // pipelines were reconciled with them for the first time
+    vault_loaded: AtomicBool,
+    streams_reconciled: AtomicBool,
 }
 
 impl StreamDispatcher {
-    pub fn new(driver: &Arc<dyn Driver>) -> Self {
-        StreamDispatcher { driver: Arc::clone(driver) }
+    pub fn new(
+        endpoints: &Arc<EndpointDispatcher>, vault: &Arc<Vault>,
+        transports: &Arc<TransportRegistry>, recordings: &Arc<RecordingStore>,
+        events: &Arc<EventBus>, port_range: PortRange, meter_interval: Duration,
+    ) -> Self {
+        StreamDispatcher {
+            endpoints: Arc::clone(endpoints),
+            vault: Arc::clone(vault),
+            recordings: Arc::clone(recordings),
+            transports: Arc::clone(transports),
+            port_allocator: PortAllocator::new(port_range),
+            runner: StreamRunner::new(
+                endpoints,
+                transports,
+                recordings,
+                events,
+                meter_interval,
+            ),
+            write_mutex: Mutex::new(()),
+            meter_interval,
+            background_tasks: SyncMutex::new(Vec::new()),
+            vault_loaded: AtomicBool::new(false),
+            streams_reconciled: AtomicBool::new(false),
+        }
+    }
+
+    /// Start background task that deletes streams with expired leases,
+    /// applies scheduled state transitions, starts, stops, or restarts
+    /// audio pipelines of streams, and aligns playout of stream groups.
+    /// Since streams are kept in vault, they're restored after restart.
+    /// Another task publishes levels of streams every meter interval.
+    /// Tasks hold weak reference and exit when dispatcher is dropped.
+    pub fn start(self: &Arc<Self>) {
+        let weak_self = Arc::downgrade(self);
+
+        let main_task = tokio::spawn(async move {
+            let mut interval = tokio::time::interval(TICK_INTERVAL);
+
+            loop {
+                interval.tick().await;
+
+                let Some(dispatcher) = weak_self.upgrade() else {
+                    break;
+                };
+                if !dispatcher.is_vault_loaded()
+                    && let Err(err) = dispatcher.load_vault().await
+                {
+                    tracing::warn!("can't load streams from vault: {err}");
+                    continue;
+                }
+                if let Err(err) = dispatcher.reap_expired().await {
+                    tracing::warn!("can't reap expired streams: {err}");
+                }
+                if let Err(err) = dispatcher.apply_schedules().await {
+                    tracing::warn!("can't apply stream schedules: {err}");
+                }
+                if let Err(err) = dispatcher.reconcile_pipelines().await {
+                    tracing::warn!("can't reconcile stream pipelines: {err}");
+                }
+                if let Err(err) = dispatcher.align_groups().await {
+                    tracing::warn!("can't align stream groups: {err}");
+                }
+            }
+        });
+
+        let weak_self = Arc::downgrade(self);
+        let meter_interval = self.meter_interval;
+
+        let levels_task = tokio::spawn(async move {
+            let mut interval = tokio::time::interval(meter_interval);
+
+            loop {
+                interval.tick().await;
+
+                let Some(dispatcher) = weak_self.upgrade() else {
+                    break;
+                };
+                dispatcher.runner.publish_levels().await;
+            }
+        });
+
+        let prev_tasks = std::mem::replace(
+            &mut *self.background_tasks.lock().unwrap(),
+            vec![main_task, levels_task],
+        );
+        for task in prev_tasks {
+            task.abort();
+        }
+    }
+
+    /// Check that background tasks were started and are still running.
+    pub fn is_running(&self) -> bool {
+        let tasks = self.background_tasks.lock().unwrap();
+
+        !tasks.is_empty() && tasks.iter().all(|task| !task.is_finished())
+    }
+
+    /// Check if streams were loaded from vault.
+    pub fn is_vault_loaded(&self) -> bool {
+        self.vault_loaded.load(Ordering::SeqCst)
+    }
+
+    /// Check if pipelines were started for loaded streams
+    /// while driver was up.
+    pub fn is_reconciled(&self) -> bool {
+        self.streams_reconciled.load(Ordering::SeqCst)
+    }
+
+    pub async fn get_all(&self) -> Result<Vec<StreamSpec>> {
+        let mut streams = self.load_all().await?;
+
+        for stream in &mut streams {
+            self.apply_status(stream).await;
+        }
+
+        Ok(streams)
+    }
+
+    pub async fn get_stream(&self, stream_uid: &Uid) -> Result<StreamSpec> {
+        let mut stream = self.load_stream(stream_uid).await?;
+
+        self.apply_status(&mut stream).await;
+
+        Ok(stream)
+    }
+
+    /// Get status of stream audio pipeline, including transport metrics.
+    /// Returns None if stream is not running.
+    pub async fn get_status(&self, stream_uid: &Uid) -> Result<Option<PipelineStatus>> {
+        // ensure stream exists
+        self.load_stream(stream_uid).await?;
+
+        Ok(self.runner.status(stream_uid).await)
+    }
+
+    /// Get latest levels of stream and its endpoints.
+    /// Returns empty list if stream is not running.
+    pub async fn get_levels(&self, stream_uid: &Uid) -> Result<Vec<LevelsSpec>> {
+        // ensure stream exists
+        self.load_stream(stream_uid).await?;
+
+        Ok(self.runner.levels(stream_uid).await)
+    }
+
+    /// Measure latency from stream destination to detector stream source.
+    /// See StreamRunner::measure_latency().
+    pub async fn measure_latency(
+        &self, stream_uid: &Uid, detector_uid: &Uid, duration: Duration,
+    ) -> Result<LatencyReportSpec> {
+        // ensure streams exist
+        self.load_stream(stream_uid).await?;
+        self.load_stream(detector_uid).await?;
+
+        self.runner.measure_latency(stream_uid, detector_uid, duration).await
+    }
+
+    /// Create new stream.
+    /// Fails if stream with this UID already exists.
+    /// Wildcard (zero) source ports are replaced with allocated ports.
+    pub async fn create_stream(&self, stream: StreamSpec) -> Result<StreamSpec> {
+        let _guard = self.write_mutex.lock().await;
+
+        if self.vault.list_streams().await?.contains(&stream.stream_uid) {
+            return Err(StreamError::ExistsError(stream.stream_uid));
+        }
+
+        self.write_stream(stream, None).await
+    }
+
+    /// Create new or replace existing stream.
+    /// If condition is given, fails unless stored stream matches it.
+    pub async fn update_stream(
+        &self, stream: StreamSpec, if_match: Option<&RevisionMatch>,
+    ) -> Result<StreamSpec> {
+        let _guard = self.write_mutex.lock().await;
+
+        let revision = self.check_revision(&stream.stream_uid, if_match).await?;
+
+        self.write_stream(stream, revision).await
+    }
+
+    /// Apply JSON Merge Patch to stored stream and save result.
+    /// If condition is given, fails unless stored stream matches it.
+    pub async fn patch_stream(
+        &self, stream_uid: &Uid, patch: &serde_json::Value, if_match: Option<&RevisionMatch>,
+    ) -> Result<StreamSpec> {
+        let _guard = self.write_mutex.lock().await;
+
+        let stream = self.load_stream(stream_uid).await?;
+        if if_match.is_some_and(|if_match| !if_match.matches(Some(stream.revision))) {
+            return Err(StreamError::RevisionError(*stream_uid));
+        }
+
+        let patched_stream = apply_merge_patch(&stream, patch)?;
+        if patched_stream.stream_uid != *stream_uid {
+            return Err(
+                ValidationError::LayoutError("stream_uid can't be changed".into()).into()
+            );
+        }
+
+        self.write_stream(patched_stream, Some(stream.revision)).await
     }
 
-    pub async fn get_all(&self) -> Vec<StreamSpec> {
-        vec![self.get_stream(&Uid::parse("777777-888888-999999").unwrap()).await]
+    /// Delete stream.
+    /// If condition is given, fails unless stored stream matches it.
+    /// Stream is also removed from its group.
+    pub async fn delete_stream(
+        &self, stream_uid: &Uid, if_match: Option<&RevisionMatch>,
+    ) -> Result<()> {
+        let _guard = self.write_mutex.lock().await;
+
+        self.check_revision(stream_uid, if_match).await?;
+        self.vault.remove_stream(stream_uid).await?;
+        self.leave_groups(stream_uid).await?;
+        self.sync_runner().await?;
+
+        Ok(())
     }
 
-    pub async fn get_stream(&self, stream_uid: &Uid) -> StreamSpec {
-        StreamSpec {
-            stream_uri: Uri::from_stream(stream_uid),
-            stream_uid: *stream_uid,
-            source: ConnectionSpec::Endpoint {
+    /// Extend stream lease by its lease_ttl, starting from now.
+    /// Fails if stream doesn't have lease.
+    pub async fn renew_stream(&self, stream_uid: &Uid) -> Result<StreamSpec> {
+        let _guard = self.write_mutex.lock().await;
+
+        let mut stream = self.load_stream(stream_uid).await?;
+        if stream.lease_ttl.is_none() {
+            return Err(StreamError::NoLeaseError(*stream_uid));
+        }
+
+        update_lease(&mut stream);
+        stream.revision += 1;
+
+        self.vault.write_stream(&Arc::new(stream)).await?;
+
+        self.get_stream(stream_uid).await
+    }
+
+    /// Create receiving stream from SDP session description.
+    /// Stream UID is generated, audio is written to given endpoint.
+    pub async fn import_sdp(
+        &self, sdp: &str, endpoint_uri: &Uri, owner: Option<String>, lease_ttl: Option<u32>,
+    ) -> Result<StreamSpec> {
+        let session = SdpSession::parse(sdp)?;
+        let stream_uid = Uid::generate_random();
+
+        tracing::debug!("importing stream {stream_uid} from sdp: {session:?}");
+
+        self.create_stream(StreamSpec {
+            stream_uri: Uri::from_stream(&stream_uid),
+            stream_uid,
+            source: session.connection,
+            destination: ConnectionSpec::Endpoint {
                 connection_type: ConnectionType::Endpoint,
-                endpoint_uri: Uri::from_endpoint(
-                    &Uid::parse("111111-222222-333333").unwrap(),
-                    &Uid::parse("444444-555555-666666").unwrap(),
-                ),
-            },
-            destination: ConnectionSpec::External {
-                connection_type: ConnectionType::External,
-                media_uri: Uri::parse("rtp+rs8m://192.168.0.101:10000").unwrap(),
-                repair_uri: Uri::parse("rs8m://192.168.0.101:10001").unwrap(),
-                control_uri: Uri::parse("rtcp://192.168.0.101:10002").unwrap(),
+                endpoint_uri: endpoint_uri.clone(),
             },
+            encoding: session.encoding,
+            owner,
+            lease_ttl,
+            lease_expiration: None,
+            schedule: None,
+            state: StreamState::Running,
+            next_transition: None,
+            error: None,
+            recording: None,
+            processing: None,
+            silence_detection: None,
+            failover: None,
+            active_source: None,
+            revision: 0,
+        })
+        .await
+    }
+
+    /// Render SDP session description of stream.
+    pub async fn export_sdp(&self, stream_uid: &Uid) -> Result<String> {
+        let stream = self.get_stream(stream_uid).await?;
+
+        Ok(SdpSession::from_stream(&stream)?.to_string())
+    }
+
+    /// List recordings of stream, oldest first.
+    /// Recordings are kept after stream is deleted, so stream doesn't need to exist.
+    pub async fn list_recordings(&self, stream_uid: &Uid) -> Result<Vec<RecordingInfo>> {
+        Ok(self.recordings.list(stream_uid)?)
+    }
+
+    /// Get recording and open its file for reading.
+    pub async fn open_recording(
+        &self, stream_uid: &Uid, recording_id: &str,
+    ) -> Result<(RecordingInfo, File)> {
+        let (recording, path) = self.recordings.get(stream_uid, recording_id)?;
+        let file = File::open(&path).await.map_err(RecordingError::from)?;
+
+        Ok((recording, file))
+    }
+
+    /// Delete recording.
+    /// Fails if recording is still being written.
+    pub async fn delete_recording(&self, stream_uid: &Uid, recording_id: &str) -> Result<()> {
+        Ok(self.recordings.delete(stream_uid, recording_id)?)
+    }
+
+    pub async fn get_all_groups(&self) -> Result<Vec<GroupSpec>> {
+        self.load_all_groups().await
+    }
+
+    pub async fn get_group(&self, group_uid: &Uid) -> Result<GroupSpec> {
+        self.load_group(group_uid).await
+    }
+
+    /// Create new or replace existing group.
+    /// Members should be existing receiving streams, which aren't
+    /// members of other groups.
+    pub async fn update_group(&self, group: GroupSpec) -> Result<GroupSpec> {
+        let _guard = self.write_mutex.lock().await;
+
+        group.validate()?;
+
+        let other_groups: Vec<GroupSpec> = self
+            .load_all_groups()
+            .await?
+            .into_iter()
+            .filter(|other| other.group_uid != group.group_uid)
+            .collect();
+
+        for member in &group.members {
+            let stream_uid = member.stream_uid().unwrap();
+
+            let stream = self.load_stream(&stream_uid).await.map_err(|err| match err {
+                StreamError::NotFoundError(_) => {
+                    StreamError::GroupMemberError(format!("stream not found: {stream_uid}"))
+                },
+                err => err,
+            })?;
+            if !matches!(stream.source, ConnectionSpec::External { .. }) {
+                return Err(StreamError::GroupMemberError(format!(
+                    "stream {stream_uid} is not receiving from external source"
+                )));
+            }
+
+            if let Some(other) =
+                other_groups.iter().find(|other| other.members.contains(member))
+            {
+                return Err(StreamError::GroupConflictError(stream_uid, other.group_uid));
+            }
+        }
+
+        let group_uid = group.group_uid;
+        self.vault.write_group(&Arc::new(group)).await?;
+
+        // apply new target latency right away
+        self.align_groups().await?;
+
+        self.get_group(&group_uid).await
+    }
+
+    /// Delete group.
+    /// Streams are kept, but not aligned anymore.
+    pub async fn delete_group(&self, group_uid: &Uid) -> Result<()> {
+        let _guard = self.write_mutex.lock().await;
+
+        self.load_group(group_uid).await?;
+        self.vault.remove_group(group_uid).await?;
+        self.align_groups().await?;
+
+        Ok(())
+    }
+
+    /// Get latency and alignment delay of group members.
+    pub async fn get_group_status(&self, group_uid: &Uid) -> Result<GroupStatusSpec> {
+        let group = self.load_group(group_uid).await?;
+
+        Ok(self.runner.group_status(&group).await)
+    }
+
+    /// Save stream, which stored revision is given (none if it's new).
+    /// Must be called with write_mutex locked.
+    async fn write_stream(
+        &self, mut stream: StreamSpec, revision: Option<u64>,
+    ) -> Result<StreamSpec> {
+        self.validate_stream(&stream)?;
+
+        let other_streams = self.load_all().await?;
+        self.port_allocator.allocate(&mut stream, &other_streams)?;
+
+        update_lease(&mut stream);
+        update_state(&mut stream);
+        stream.error = None;
+        stream.active_source = None;
+        stream.revision = revision.map_or(1, |revision| revision + 1);
+
+        let stream_uid = stream.stream_uid;
+        self.vault.write_stream(&Arc::new(stream)).await?;
+
+        // start, restart, or stop pipeline right away,
+        // so that response reflects result
+        self.sync_runner().await?;
+
+        self.get_stream(&stream_uid).await
+    }
+
+    /// Load streams and groups from vault on startup.
+    async fn load_vault(&self) -> Result<()> {
+        self.load_all().await?;
+        self.load_all_groups().await?;
+
+        self.vault_loaded.store(true, Ordering::SeqCst);
+
+        Ok(())
+    }
+
+    /// Restart failed pipelines and stop pipelines of expired
+    /// or stopped streams.
+    async fn reconcile_pipelines(&self) -> Result<()> {
+        let _guard = self.write_mutex.lock().await;
+
+        self.sync_runner().await
+    }
+
+    /// Reconcile pipelines with streams in vault.
+    /// Must be called with write_mutex locked.
+    async fn sync_runner(&self) -> Result<()> {
+        let streams = self.load_all().await?;
+
+        self.runner.reconcile(&streams).await;
+
+        // pipelines of device endpoints can't be started while
+        // driver is down, so such reconcile doesn't count
+        if self.is_vault_loaded() && self.endpoints.is_driver_up() {
+            self.streams_reconciled.store(true, Ordering::SeqCst);
+        }
+
+        Ok(())
+    }
+
+    /// Update delays of group members.
+    async fn align_groups(&self) -> Result<()> {
+        let groups = self.load_all_groups().await?;
+
+        self.runner.align_groups(&groups).await;
+
+        Ok(())
+    }
+
+    /// Remove stream from group, if it's a member.
+    /// Must be called with write_mutex locked.
+    async fn leave_groups(&self, stream_uid: &Uid) -> Result<()> {
+        let stream_uri = Uri::from_stream(stream_uid);
+
+        for mut group in self.load_all_groups().await? {
+            if group.members.contains(&stream_uri) {
+                group.members.retain(|member| *member != stream_uri);
+                self.vault.write_group(&Arc::new(group)).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Get revision of stored stream, or none if it doesn't exist.
+    /// Fails if condition is given and stream doesn't match it.
+    /// Must be called with write_mutex locked.
+    async fn check_revision(
+        &self, stream_uid: &Uid, if_match: Option<&RevisionMatch>,
+    ) -> Result<Option<u64>> {
+        let revision = match self.vault.read_stream(stream_uid).await {
+            Ok(stream) => Some(stream.revision),
+            Err(VaultError::UidNotFound(_)) => None,
+            Err(err) => return Err(err.into()),
+        };
+
+        if if_match.is_some_and(|if_match| !if_match.matches(revision)) {
+            return Err(StreamError::RevisionError(*stream_uid));
+        }
+
+        Ok(revision)
+    }
+
+    /// Read all groups from vault.
+    async fn load_all_groups(&self) -> Result<Vec<GroupSpec>> {
+        let mut group_uids: Vec<Uid> =
+            self.vault.list_groups().await?.iter().copied().collect();
+        group_uids.sort();
+
+        let mut groups = Vec::with_capacity(group_uids.len());
+        for group_uid in &group_uids {
+            groups.push(self.load_group(group_uid).await?);
+        }
+
+        Ok(groups)
+    }
+
+    /// Read group from vault.
+    async fn load_group(&self, group_uid: &Uid) -> Result<GroupSpec> {
+        let group = self.vault.read_group(group_uid).await.map_err(|err| match err {
+            VaultError::UidNotFound(uid) => StreamError::GroupNotFoundError(*uid),
+            err => err.into(),
+        })?;
+
+        Ok((*group).clone())
+    }
+
+    /// Read all streams from vault, as they're stored.
+    async fn load_all(&self) -> Result<Vec<StreamSpec>> {
+        let mut stream_uids: Vec<Uid> =
+            self.vault.list_streams().await?.iter().copied().collect();
+        stream_uids.sort();
+
+        let mut streams = Vec::with_capacity(stream_uids.len());
+        for stream_uid in &stream_uids {
+            streams.push(self.load_stream(stream_uid).await?);
+        }
+
+        Ok(streams)
+    }
+
+    /// Read stream from vault, as it's stored.
+    async fn load_stream(&self, stream_uid: &Uid) -> Result<StreamSpec> {
+        let stream = self.vault.read_stream(stream_uid).await?;
+
+        Ok((*stream).clone())
+    }
+
+    /// Report stream as failed if it should be running,
+    /// but its pipeline can't be started or terminated.
+    /// Report stream as paused if it doesn't send because of silence.
+    /// Report which source is played if stream has failover.
+    async fn apply_status(&self, stream: &mut StreamSpec) {
+        if stream.state != StreamState::Running {
+            return;
+        }
+
+        let Some(status) = self.runner.status(&stream.stream_uid).await else {
+            return;
+        };
+
+        stream.active_source = status.active_source.map(|index| index as u32);
+
+        if let Some(error) = status.error {
+            stream.state = StreamState::Failed;
+            stream.error = Some(error);
+        } else if status.paused {
+            stream.state = StreamState::Paused;
+        }
+    }
+
+    /// Delete streams which leases are expired.
+    async fn reap_expired(&self) -> Result<()> {
+        let _guard = self.write_mutex.lock().await;
+
+        let now = OffsetDateTime::now_utc();
+
+        for stream in self.load_all().await? {
+            if stream.lease_expiration.is_some_and(|expiration| expiration <= now) {
+                tracing::info!(
+                    "deleting stream {} with expired lease (owner {:?})",
+                    stream.stream_uid,
+                    stream.owner
+                );
+                self.vault.remove_stream(&stream.stream_uid).await?;
+                self.leave_groups(&stream.stream_uid).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Update state and next_transition of scheduled streams.
+    async fn apply_schedules(&self) -> Result<()> {
+        let _guard = self.write_mutex.lock().await;
+
+        for mut stream in self.load_all().await? {
+            let Some(schedule) = &stream.schedule else {
+                continue;
+            };
+
+            let (state, next_transition) =
+                evaluate_schedule(Some(schedule), OffsetDateTime::now_utc());
+
+            if state == stream.state && next_transition == stream.next_transition {
+                continue;
+            }
+            if state != stream.state {
+                tracing::info!(
+                    "stream {}: scheduled transition {} -> {}",
+                    stream.stream_uid,
+                    stream.state,
+                    state
+                );
+            }
+
+            stream.state = state;
+            stream.next_transition = next_transition;
+            stream.revision += 1;
+
+            self.vault.write_stream(&Arc::new(stream)).await?;
         }
+
+        Ok(())
     }
+
+    /// In addition to Validate impl, check that stream is usable on this host.
+    fn validate_stream(&self, stream: &StreamSpec) -> Result<()> {
+        stream.validate()?;
+
+        let interfaces = list_interfaces().unwrap_or_else(|err| {
+            tracing::warn!("can't list network interfaces: {err}");
+            Vec::new()
+        });
+
+        for source in stream.all_sources() {
+            validate_multicast(source, &interfaces)?;
+            self.transports.validate(source, &stream.encoding)?;
+        }
+        validate_multicast(&stream.destination, &interfaces)?;
+        self.transports.validate(&stream.destination, &stream.encoding)?;
+
+        Ok(())
+    }
+}
+
+impl Drop for StreamDispatcher {
+    fn drop(&mut self) {
+        for task in self.background_tasks.lock().unwrap().drain(..) {
+            task.abort();
+        }
+    }
+}
+
+/// Recompute state and next transition from schedule.
+/// Client-provided state and next_transition are ignored.
+fn update_state(stream: &mut StreamSpec) {
+    (stream.state, stream.next_transition) =
+        evaluate_schedule(stream.schedule.as_ref(), OffsetDateTime::now_utc());
+}
+
+/// Recompute lease expiration from lease_ttl.
+/// Client-provided lease_expiration is ignored.
+fn update_lease(stream: &mut StreamSpec) {
+    stream.lease_expiration = stream
+        .lease_ttl
+        .map(|ttl| OffsetDateTime::now_utc() + Duration::from_secs(ttl as u64));
 }
@@ -0,0 +1,769 @@
+// Copyright (c) Roc Streaming authors
+// Licensed under MPL-2.0
+use crate::audio::*;
+use crate::dto::*;
+use crate::events::EventBus;
+use crate::io_endpoints::EndpointDispatcher;
+use crate::io_streams::error::*;
+use crate::io_streams::transport_registry::*;
+use crate::recordings::*;
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use time::OffsetDateTime;
+use tokio::sync::Mutex;
+
+/// How often failed streams are restarted.
+const RETRY_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How long metering stays enabled after levels were requested.
+const LEVELS_REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Weight of new value in moving average of transport latency.
+const LATENCY_ALPHA: f64 = 0.3;
+
+/// How long latency measurement waits for pipelines to react,
+/// in addition to capture duration.
+const MEASUREMENT_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// How often latency measurement checks progress.
+const MEASUREMENT_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Runs audio pipelines of streams.
+///
+/// Runner doesn't store anything; it's told which streams should exist
+/// via reconcile(), and starts, restarts, or stops pipelines accordingly.
+pub struct StreamRunner {
+    endpoints: Arc<EndpointDispatcher>,
+    transports: Arc<TransportRegistry>,
+    recordings: Arc<RecordingStore>,
+    events: Arc<EventBus>,
+    meter_interval: Duration,
+    entries: Mutex<HashMap<Uid, RunnerEntry>>,
+    // only one latency measurement at a time
+    measure_mutex: Mutex<()>,
+}
+
+struct RunnerEntry {
+    spec: StreamSpec,
+    pipeline: Option<AudioPipeline>,
+    controls: PipelineControls,
+    // when levels were last requested via levels()
+    levels_requested_at: Option<Instant>,
+    // smoothed latency reported by receiving transport
+    transport_latency: Option<Duration>,
+    // when failed failover sources were last reopened
+    failover_retried_at: Instant,
+    // when pipeline is none
+    error: String,
+    failed_at: Instant,
+}
+
+/// State shared between runner and pipeline.
+struct PipelineControls {
+    // allows changing processing without restart
+    processing: Arc<ProcessingControl>,
+    // set by pipeline when sending is paused because of silence
+    paused: Arc<AtomicBool>,
+    // levels written to destination, after processing
+    stream_meter: Arc<LevelMeter>,
+    // levels read from source endpoint, before processing
+    source_meter: Arc<LevelMeter>,
+    // injects and captures marker for latency measurement
+    probe: Arc<LatencyProbe>,
+    // delays received audio to align group members
+    alignment: Arc<AlignmentControl>,
+    // tracks active source and reopens failed ones, if stream has failover
+    failover: Option<Arc<FailoverControl>>,
+}
+
+impl StreamRunner {
+    pub fn new(
+        endpoints: &Arc<EndpointDispatcher>, transports: &Arc<TransportRegistry>,
+        recordings: &Arc<RecordingStore>, events: &Arc<EventBus>, meter_interval: Duration,
+    ) -> Self {
+        StreamRunner {
+            endpoints: Arc::clone(endpoints),
+            transports: Arc::clone(transports),
+            recordings: Arc::clone(recordings),
+            events: Arc::clone(events),
+            meter_interval,
+            entries: Mutex::new(HashMap::new()),
+            measure_mutex: Mutex::new(()),
+        }
+    }
+
+    /// Bring running pipelines in accordance with given list of streams.
+    ///
+    /// Pipelines of deleted and stopped streams are stopped, pipelines
+    /// of changed streams are restarted, pipelines of new streams are
+    /// started. Pipelines that failed are restarted after RETRY_INTERVAL.
+    /// Failed failover sources are reopened after RETRY_INTERVAL too.
+    pub async fn reconcile(&self, streams: &[StreamSpec]) {
+        let wanted: HashMap<Uid, &StreamSpec> = streams
+            .iter()
+            .filter(|stream| stream.state != StreamState::Stopped)
+            .map(|stream| (stream.stream_uid, stream))
+            .collect();
+
+        let mut stopped = Vec::new();
+        self.entries.lock().await.retain(|stream_uid, entry| match wanted.get(stream_uid) {
+            Some(stream) if same_media(&entry.spec, stream) => true,
+            _ => {
+                tracing::info!("stopping stream {stream_uid}");
+                stopped.extend(entry.pipeline.take());
+                false
+            },
+        });
+
+        // old pipelines should release their ports before
+        // pipelines of changed streams are started below
+        stop_pipelines(stopped).await;
+
+        let mut entries = self.entries.lock().await;
+
+        for (stream_uid, entry) in entries.iter_mut() {
+            if let Some(stream) = wanted.get(stream_uid)
+                && entry.spec.processing != stream.processing
+            {
+                tracing::debug!("updating processing of stream {stream_uid}");
+                entry.controls.processing.update(stream.processing.as_ref());
+                entry.spec.processing = stream.processing.clone();
+            }
+
+            if let Some(pipeline) = &entry.pipeline
+                && !pipeline.is_running()
+            {
+                let error = pipeline.status().error.unwrap_or("pipeline terminated".into());
+                tracing::warn!("stream {stream_uid} failed: {error}");
+
+                entry.pipeline = None;
+                entry.error = error;
+                entry.failed_at = Instant::now();
+            }
+
+            if entry.pipeline.is_some()
+                && entry.failover_retried_at.elapsed() >= RETRY_INTERVAL
+            {
+                self.reopen_failover_sources(entry).await;
+            }
+        }
+
+        for (stream_uid, stream) in wanted {
+            if let Some(entry) = entries.get(&stream_uid)
+                && (entry.pipeline.is_some() || entry.failed_at.elapsed() < RETRY_INTERVAL)
+            {
+                continue;
+            }
+
+            tracing::info!("starting stream {stream_uid}");
+
+            let controls = PipelineControls {
+                processing: Arc::new(ProcessingControl::new(stream.processing.as_ref())),
+                paused: Arc::new(AtomicBool::new(false)),
+                stream_meter: Arc::new(LevelMeter::new(self.meter_interval)),
+                source_meter: Arc::new(LevelMeter::new(self.meter_interval)),
+                probe: Arc::new(LatencyProbe::new()),
+                alignment: Arc::new(AlignmentControl::new()),
+                failover: stream.failover.as_ref().map(|failover| {
+                    Arc::new(FailoverControl::new(failover.sources.len() + 1))
+                }),
+            };
+            // keep metering if it was enabled before restart
+            let levels_requested_at =
+                entries.get(&stream_uid).and_then(|entry| entry.levels_requested_at);
+
+            let (pipeline, error) = match self.open_pipeline(stream, &controls).await {
+                Ok(pipeline) => (Some(pipeline), String::new()),
+                Err(err) => {
+                    tracing::warn!("can't start stream {stream_uid}: {err}");
+                    (None, err.to_string())
+                },
+            };
+
+            let entry = RunnerEntry {
+                spec: stream.clone(),
+                pipeline,
+                controls,
+                levels_requested_at,
+                transport_latency: None,
+                failover_retried_at: Instant::now(),
+                error,
+                failed_at: Instant::now(),
+            };
+
+            entries.insert(stream_uid, entry);
+        }
+    }
+
+    /// Get status of stream pipeline.
+    /// Returns None if stream is not running (e.g. it's stopped).
+    /// If stream failed, returned status contains error.
+    pub async fn status(&self, stream_uid: &Uid) -> Option<PipelineStatus> {
+        let entries = self.entries.lock().await;
+        let entry = entries.get(stream_uid)?;
+
+        Some(match &entry.pipeline {
+            Some(pipeline) => PipelineStatus {
+                paused: entry.controls.paused.load(Ordering::SeqCst),
+                active_source: entry
+                    .controls
+                    .failover
+                    .as_ref()
+                    .map(|failover| failover.active_source()),
+                ..pipeline.status()
+            },
+            None => PipelineStatus { error: Some(entry.error.clone()), ..Default::default() },
+        })
+    }
+
+    /// Get latest levels of stream and its endpoints.
+    ///
+    /// Metering of stream is enabled for LEVELS_REQUEST_TIMEOUT; if it
+    /// wasn't enabled yet, waits until first levels are measured.
+    /// Returns empty list if stream is not running.
+    pub async fn levels(&self, stream_uid: &Uid) -> Vec<LevelsSpec> {
+        let deadline = Instant::now() + self.meter_interval * 3;
+
+        loop {
+            {
+                let mut entries = self.entries.lock().await;
+                let Some(entry) = entries.get_mut(stream_uid).filter(|e| e.pipeline.is_some())
+                else {
+                    return Vec::new();
+                };
+
+                entry.levels_requested_at = Some(Instant::now());
+                entry.controls.set_metering(true);
+
+                let levels = entry.collect_levels(LevelMeter::latest);
+                if !levels.is_empty() || Instant::now() >= deadline {
+                    return levels;
+                }
+            }
+
+            tokio::time::sleep(self.meter_interval / 4).await;
+        }
+    }
+
+    /// Enable metering of streams which levels are watched or were
+    /// recently requested, disable it for others, and publish levels
+    /// measured since previous call to level watchers.
+    /// Should be called every meter interval.
+    pub async fn publish_levels(&self) {
+        let watched = self.events.has_level_watchers();
+        let entries = self.entries.lock().await;
+
+        for entry in entries.values() {
+            let requested = entry
+                .levels_requested_at
+                .is_some_and(|time| time.elapsed() < LEVELS_REQUEST_TIMEOUT);
+
+            entry.controls.set_metering(watched || requested);
+
+            if watched {
+                for levels in entry.collect_levels(LevelMeter::take_new) {
+                    self.events.publish(EventSpec::from_levels(levels));
+                }
+            }
+        }
+    }
+
+    /// Equalise latency of receiving streams in groups.
+    ///
+    /// Members with lower latency are delayed, so that all members have
+    /// target latency of group, or latency of slowest member if it's higher.
+    /// Streams that aren't members of any group aren't delayed.
+    /// Should be called periodically.
+    ///
+    /// Only latency estimates of transports are used; there is no shared
+    /// clock reference, so drift between receiver clocks isn't corrected.
+    pub async fn align_groups(&self, groups: &[GroupSpec]) {
+        let mut entries = self.entries.lock().await;
+
+        for entry in entries.values_mut() {
+            entry.update_latency();
+        }
+
+        let mut aligned = HashSet::new();
+
+        for group in groups {
+            let members: Vec<Uid> = group.members.iter().filter_map(Uri::stream_uid).collect();
+
+            let Some(reference) = members
+                .iter()
+                .filter_map(|stream_uid| entries.get(stream_uid)?.transport_latency)
+                .chain(group.target_latency.map(Duration::from_secs_f32))
+                .max()
+            else {
+                continue;
+            };
+
+            for stream_uid in members {
+                if let Some(entry) = entries.get(&stream_uid)
+                    && let Some(latency) = entry.transport_latency
+                {
+                    entry.controls.alignment.set_target_delay(reference - latency);
+                    aligned.insert(stream_uid);
+                }
+            }
+        }
+
+        for (stream_uid, entry) in entries.iter() {
+            if !aligned.contains(stream_uid) {
+                entry.controls.alignment.set_target_delay(Duration::ZERO);
+            }
+        }
+    }
+
+    /// Get latency and alignment delay of group members.
+    pub async fn group_status(&self, group: &GroupSpec) -> GroupStatusSpec {
+        let entries = self.entries.lock().await;
+
+        let members: Vec<GroupMemberStatusSpec> = group
+            .members
+            .iter()
+            .map(|stream_uri| {
+                let entry = stream_uri
+                    .stream_uid()
+                    .and_then(|stream_uid| entries.get(&stream_uid))
+                    .filter(|entry| entry.pipeline.is_some());
+                let delay = entry.map(|entry| entry.controls.alignment.current_delay());
+                let latency = entry.and_then(|entry| entry.transport_latency);
+
+                GroupMemberStatusSpec {
+                    stream_uri: stream_uri.clone(),
+                    latency: latency
+                        .zip(delay)
+                        .map(|(latency, delay)| (latency + delay).as_secs_f32()),
+                    delay: delay.map(|delay| delay.as_secs_f32()),
+                }
+            })
+            .collect();
+
+        GroupStatusSpec {
+            group_uri: group.group_uri.clone(),
+            time: OffsetDateTime::now_utc(),
+            members,
+        }
+    }
+
+    /// Measure latency between destination of one stream and source of
+    /// another (or same) stream, by sending marker signal via the former
+    /// and detecting it in the latter during given capture duration.
+    ///
+    /// Both streams should be running, and destination of first stream
+    /// should somehow reach source of detector stream, e.g. via network
+    /// or acoustically from speaker to microphone.
+    pub async fn measure_latency(
+        &self, stream_uid: &Uid, detector_uid: &Uid, duration: Duration,
+    ) -> Result<LatencyReportSpec, StreamError> {
+        let _guard = self.measure_mutex.lock().await;
+
+        let (stream_probe, detector_probe, stream_uri, detector_uri) = {
+            let entries = self.entries.lock().await;
+            let running = |uid: &Uid| {
+                entries
+                    .get(uid)
+                    .filter(|entry| entry.pipeline.is_some())
+                    .ok_or(StreamError::NotRunningError(*uid))
+            };
+            let stream = running(stream_uid)?;
+            let detector = running(detector_uid)?;
+
+            detector.controls.probe.request_capture(
+                AudioFormat::from_encoding(&detector.spec.encoding),
+                duration,
+            );
+
+            (
+                Arc::clone(&stream.controls.probe),
+                Arc::clone(&detector.controls.probe),
+                stream.spec.stream_uri.clone(),
+                detector.spec.stream_uri.clone(),
+            )
+        };
+
+        let result = self.run_measurement(&stream_probe, &detector_probe, duration).await;
+
+        stream_probe.cancel();
+        detector_probe.cancel();
+
+        let measured_latency = result?;
+
+        // receiver of marker is either detector stream or peer of stream
+        let (target_latency, estimated_latency) = {
+            let entries = self.entries.lock().await;
+            let metrics = |uid: &Uid, source: bool| {
+                let status = entries.get(uid)?.pipeline.as_ref()?.status();
+                if source { status.source_metrics } else { status.sink_metrics }
+            };
+
+            match metrics(detector_uid, true).or_else(|| metrics(stream_uid, false)) {
+                Some(metrics) => (
+                    Some(metrics.target_latency).filter(|latency| !latency.is_zero()),
+                    Some(metrics.e2e_latency),
+                ),
+                None => (None, None),
+            }
+        };
+
+        Ok(LatencyReportSpec {
+            stream_uri,
+            detector_stream_uri: detector_uri,
+            measured_latency: measured_latency.as_secs_f32(),
+            target_latency: target_latency.map(|latency| latency.as_secs_f32()),
+            estimated_latency: estimated_latency.map(|latency| latency.as_secs_f32()),
+            time: OffsetDateTime::now_utc(),
+        })
+    }
+
+    async fn run_measurement(
+        &self, stream_probe: &LatencyProbe, detector_probe: &LatencyProbe, duration: Duration,
+    ) -> Result<Duration, StreamError> {
+        let failure = |text: &str| StreamError::MeasurementError(text.into());
+
+        // capture should start before marker is sent
+        let deadline = Instant::now() + MEASUREMENT_TIMEOUT;
+        while detector_probe.capture_started_at().is_none() {
+            if Instant::now() >= deadline {
+                return Err(failure("detector stream doesn't read audio"));
+            }
+            tokio::time::sleep(MEASUREMENT_POLL_INTERVAL).await;
+        }
+
+        stream_probe.request_injection();
+
+        let deadline = Instant::now() + duration + MEASUREMENT_TIMEOUT;
+        let capture = loop {
+            if let Some(capture) = detector_probe.take_capture() {
+                break capture;
+            }
+            if Instant::now() >= deadline {
+                return Err(failure("detector stream stopped reading audio"));
+            }
+            tokio::time::sleep(MEASUREMENT_POLL_INTERVAL).await;
+        };
+
+        let injected_at =
+            stream_probe.injected_at().ok_or_else(|| failure("stream didn't send marker"))?;
+
+        let start = capture.start;
+        let offset = tokio::task::spawn_blocking(move || find_chirp(&capture))
+            .await
+            .map_err(|err| StreamError::MeasurementError(err.to_string()))?
+            .ok_or_else(|| failure("marker not detected"))?;
+
+        // detection may be slightly earlier than injection because
+        // of frame granularity of timestamps
+        Ok((start + offset).saturating_duration_since(injected_at))
+    }
+
+    /// Stop all pipelines.
+    pub async fn stop_all(&self) {
+        let entries = std::mem::take(&mut *self.entries.lock().await);
+
+        stop_pipelines(entries.into_values().filter_map(|entry| entry.pipeline).collect())
+            .await;
+    }
+
+    async fn open_pipeline(
+        &self, stream: &StreamSpec, controls: &PipelineControls,
+    ) -> Result<AudioPipeline, PipelineError> {
+        let format = AudioFormat::from_encoding(&stream.encoding);
+
+        // open endpoints first, so that we know whether transport
+        // should be paced by them
+        let source_endpoint = match (&stream.source, &stream.failover) {
+            (ConnectionSpec::Endpoint { endpoint_uri, .. }, None) => {
+                Some(self.endpoints.open_source(endpoint_uri, format).await?)
+            },
+            _ => None,
+        };
+        let sink_endpoint = match &stream.destination {
+            ConnectionSpec::Endpoint { endpoint_uri, .. } => {
+                Some(self.endpoints.open_sink(endpoint_uri, format).await?)
+            },
+            ConnectionSpec::External { .. } => None,
+        };
+
+        let source = match (source_endpoint, &stream.failover) {
+            (Some(source), _) => source,
+            (None, Some(failover)) => {
+                Box::new(self.open_failover(stream, failover, controls).await?)
+            },
+            (None, None) => {
+                let external_clock =
+                    sink_endpoint.as_ref().is_some_and(|sink| sink.is_clocked());
+                self.open_source(&stream.source, &stream.encoding, external_clock).await?
+            },
+        };
+
+        // alignment is always inserted for receivers, so that stream
+        // can join group without restarting pipeline
+        let source: Box<dyn AudioSource> = match &stream.source {
+            ConnectionSpec::External { .. } => {
+                Box::new(AlignedSource::new(source, &controls.alignment))
+            },
+            ConnectionSpec::Endpoint { .. } => source,
+        };
+        let mut sink =
+            match sink_endpoint {
+                Some(sink) => sink,
+                None => {
+                    let external_clock = source.is_clocked();
+                    self.transports
+                        .select(&stream.destination, &stream.encoding)?
+                        .open_sender(&stream.destination, &stream.encoding, external_clock)?
+                },
+            };
+
+        // only sending to network is paused, recording continues
+        if let Some(silence_detection) = &stream.silence_detection {
+            let on_change = {
+                let paused = Arc::clone(&controls.paused);
+                let events = Arc::clone(&self.events);
+                let stream_uid = stream.stream_uid;
+                let stream_uri = stream.stream_uri.clone();
+
+                move |is_paused: bool| {
+                    let event_type = if is_paused {
+                        tracing::info!("stream {stream_uid}: signal is silent, pausing");
+                        EventType::StreamPaused
+                    } else {
+                        tracing::info!("stream {stream_uid}: signal returned, resuming");
+                        EventType::StreamResumed
+                    };
+
+                    paused.store(is_paused, Ordering::SeqCst);
+                    events.publish(EventSpec::new(event_type, &stream_uri));
+                }
+            };
+
+            sink = Box::new(SilenceGate::new(sink, silence_detection, Box::new(on_change)));
+        }
+
+        // marker is injected before silence gate, so that gate
+        // resumes sending if it's paused
+        let mut sink: Box<dyn AudioSink> = Box::new(ProbeSink::new(sink, &controls.probe));
+
+        // marker is detected in raw source signal
+        let source = Box::new(ProbeSource::new(source, &controls.probe));
+
+        // source endpoint levels are measured before processing;
+        // with failover, it's not known which source is played
+        let source: Box<dyn AudioSource> = match &stream.source {
+            ConnectionSpec::Endpoint { .. } if stream.failover.is_none() => {
+                Box::new(MeteringSource::new(source, &controls.source_meter))
+            },
+            _ => source,
+        };
+
+        // processing is always inserted, so that it can be enabled later
+        // without restarting pipeline
+        let source = Box::new(ProcessingSource::new(source, &controls.processing));
+
+        if let Some(recording) = &stream.recording {
+            let recorder = RecordingSink::start(
+                &self.recordings,
+                &stream.stream_uid,
+                recording,
+                &stream.encoding,
+            )?;
+            sink = Box::new(TeeSink::new(sink, Box::new(recorder))?);
+        }
+
+        // metering is always inserted, it's cheap while disabled
+        let sink = Box::new(MeteringSink::new(sink, &controls.stream_meter));
+
+        Ok(AudioPipeline::start(&stream.stream_uid.to_string(), source, sink)?)
+    }
+
+    /// Open source and failover sources of stream.
+    /// Sources that can't be opened are reopened later by reconcile().
+    async fn open_failover(
+        &self, stream: &StreamSpec, failover: &FailoverSpec, controls: &PipelineControls,
+    ) -> Result<FailoverSource, PipelineError> {
+        let mut sources = Vec::new();
+        let mut first_error = None;
+
+        for (index, connection) in stream.all_sources().into_iter().enumerate() {
+            // receivers should not pace themselves, see FailoverSource
+            match self.open_source(connection, &stream.encoding, true).await {
+                Ok(source) => sources.push(Some(source)),
+                Err(err) => {
+                    tracing::warn!(
+                        "stream {}: can't open failover source {index}: {err}",
+                        stream.stream_uid
+                    );
+                    sources.push(None);
+                    first_error.get_or_insert(err);
+                },
+            }
+        }
+
+        if let Some(err) = first_error
+            && sources.iter().all(Option::is_none)
+        {
+            return Err(err);
+        }
+
+        let on_switch = {
+            let events = Arc::clone(&self.events);
+            let stream_uid = stream.stream_uid;
+            let stream_uri = stream.stream_uri.clone();
+
+            move |previous: usize, active: usize, reason: SwitchReason| {
+                tracing::info!(
+                    "stream {stream_uid}: switching from source {previous} to source \
+                     {active} ({reason})"
+                );
+
+                events.publish(EventSpec::from_source_switch(
+                    &stream_uri,
+                    SourceSwitchSpec {
+                        previous_source: previous as u32,
+                        active_source: active as u32,
+                        reason,
+                    },
+                ));
+            }
+        };
+
+        Ok(FailoverSource::new(
+            sources,
+            failover,
+            controls.failover.as_ref().unwrap(),
+            Box::new(on_switch),
+        )?)
+    }
+
+    /// Open endpoint or receiver of external connection.
+    async fn open_source(
+        &self, connection: &ConnectionSpec, encoding: &EncodingSpec, external_clock: bool,
+    ) -> Result<Box<dyn AudioSource>, PipelineError> {
+        Ok(match connection {
+            ConnectionSpec::Endpoint { endpoint_uri, .. } => {
+                self.endpoints
+                    .open_source(endpoint_uri, AudioFormat::from_encoding(encoding))
+                    .await?
+            },
+            ConnectionSpec::External { .. } => {
+                self.transports.select(connection, encoding)?.open_receiver(
+                    connection,
+                    encoding,
+                    external_clock,
+                )?
+            },
+        })
+    }
+
+    /// Reopen failover sources that failed while pipeline was running.
+    async fn reopen_failover_sources(&self, entry: &mut RunnerEntry) {
+        let Some(failover) = &entry.controls.failover else {
+            return;
+        };
+
+        entry.failover_retried_at = Instant::now();
+
+        let sources = entry.spec.all_sources();
+
+        for index in failover.failed_sources() {
+            let stream_uid = entry.spec.stream_uid;
+
+            match self.open_source(sources[index], &entry.spec.encoding, true).await {
+                Ok(source) => {
+                    tracing::info!("stream {stream_uid}: reopened failover source {index}");
+                    failover.replace_source(index, source);
+                },
+                Err(err) => {
+                    tracing::debug!(
+                        "stream {stream_uid}: can't reopen failover source {index}: {err}"
+                    );
+                },
+            }
+        }
+    }
+}
+
+impl RunnerEntry {
+    /// Build levels of stream and its endpoints from snapshots returned
+    /// by given function. Destination endpoint has same levels as stream.
+    fn collect_levels(
+        &self, snapshot_fn: impl Fn(&LevelMeter) -> Option<LevelSnapshot>,
+    ) -> Vec<LevelsSpec> {
+        let mut levels = Vec::new();
+        let mut add = |object_uri: &Uri, snapshot: &LevelSnapshot| {
+            levels.push(LevelsSpec {
+                object_uri: object_uri.clone(),
+                time: snapshot.time,
+                channels: snapshot.channels.clone(),
+            })
+        };
+
+        if let Some(snapshot) = snapshot_fn(&self.controls.stream_meter) {
+            add(&self.spec.stream_uri, &snapshot);
+            if let ConnectionSpec::Endpoint { endpoint_uri, .. } = &self.spec.destination {
+                add(endpoint_uri, &snapshot);
+            }
+        }
+        if let ConnectionSpec::Endpoint { endpoint_uri, .. } = &self.spec.source
+            && let Some(snapshot) = snapshot_fn(&self.controls.source_meter)
+        {
+            add(endpoint_uri, &snapshot);
+        }
+
+        levels
+    }
+
+    /// Update smoothed transport latency from pipeline metrics.
+    fn update_latency(&mut self) {
+        let latency = self
+            .pipeline
+            .as_ref()
+            .and_then(|pipeline| pipeline.status().source_metrics)
+            .map(|metrics| metrics.e2e_latency);
+
+        self.transport_latency = match (self.transport_latency, latency) {
+            (Some(prev), Some(latency)) => Some(Duration::from_secs_f64(
+                prev.as_secs_f64()
+                    + (latency.as_secs_f64() - prev.as_secs_f64()) * LATENCY_ALPHA,
+            )),
+            (_, latency) => latency,
+        };
+    }
+}
+
+impl PipelineControls {
+    fn set_metering(&self, enabled: bool) {
+        self.stream_meter.set_enabled(enabled);
+        self.source_meter.set_enabled(enabled);
+    }
+}
+
+/// Stop pipelines and wait until their threads exit.
+/// Joining pipeline thread blocks, so it's done on blocking thread
+/// instead of async worker, and without holding locks.
+async fn stop_pipelines(pipelines: Vec<AudioPipeline>) {
+    if pipelines.is_empty() {
+        return;
+    }
+
+    if let Err(err) = tokio::task::spawn_blocking(move || drop(pipelines)).await {
+        tracing::error!("can't stop pipelines: {err}");
+    }
+}
+
+/// Check if two versions of stream have same media path,
+/// i.e. running pipeline can be kept when one is replaced with another.
+/// Processing is not compared, since it's updated in running pipeline.
+fn same_media(a: &StreamSpec, b: &StreamSpec) -> bool {
+    a.source == b.source
+        && a.destination == b.destination
+        && a.encoding == b.encoding
+        && a.recording == b.recording
+        && a.silence_detection == b.silence_detection
+        && a.failover == b.failover
+}
@@ -0,0 +1,40 @@
+// Copyright (c) Roc Streaming authors
+// Licensed under MPL-2.0
+use crate::audio::{AudioSink, AudioSource};
+use crate::dto::*;
+use crate::io_streams::error::*;
+
+pub type TransportResult<T> = std::result::Result<T, TransportError>;
+
+/// Network transport that delivers audio between rocd and external
+/// connection (another rocd, roc-send, roc-recv, etc).
+///
+/// Transport converts external connection into audio source (receiver)
+/// or audio sink (sender), which are then connected to endpoints or
+/// to each other by audio pipeline.
+pub trait Transport: Send + Sync {
+    /// Transport name, used in logs.
+    fn name(&self) -> &'static str;
+
+    /// Check if transport can handle given external connection
+    /// (protocols, FEC scheme) and encoding.
+    fn supports(&self, connection: &ConnectionSpec, encoding: &EncodingSpec) -> bool;
+
+    /// Open receiver bound to local addresses of connection.
+    ///
+    /// If external_clock is true, another side of pipeline (e.g. sound card)
+    /// paces reads, and receiver should not block. Otherwise receiver
+    /// should block each read to pace pipeline in real time.
+    fn open_receiver(
+        &self, connection: &ConnectionSpec, encoding: &EncodingSpec, external_clock: bool,
+    ) -> TransportResult<Box<dyn AudioSource>>;
+
+    /// Open sender connected to remote addresses of connection.
+    ///
+    /// If external_clock is true, another side of pipeline paces writes,
+    /// and sender should not block. Otherwise sender should block each write
+    /// to pace pipeline in real time.
+    fn open_sender(
+        &self, connection: &ConnectionSpec, encoding: &EncodingSpec, external_clock: bool,
+    ) -> TransportResult<Box<dyn AudioSink>>;
+}
@@ -0,0 +1,102 @@
+// Copyright (c) Roc Streaming authors
+// Licensed under MPL-2.0
+use crate::dto::*;
+use crate::io_streams::error::*;
+use crate::io_streams::rtp_transport::RtpTransport;
+use crate::io_streams::transport::*;
+
+#[cfg(feature = "roc")]
+use crate::io_streams::roc_transport::RocTransport;
+
+use std::sync::Arc;
+
+/// Registry of available transports.
+/// Whether a transport is available is defined at compile-time.
+pub struct TransportRegistry {
+    transports: Vec<Arc<dyn Transport>>,
+}
+
+impl TransportRegistry {
+    /// Construct registry with all transports enabled at compile time.
+    /// Transports that fail to initialize are skipped.
+    ///
+    /// libroc, if enabled, has priority; built-in RTP transport is
+    /// a fallback for rtp:// connections.
+    pub fn new() -> Self {
+        let mut registry = TransportRegistry::empty();
+
+        #[cfg(feature = "roc")]
+        match RocTransport::open() {
+            Ok(transport) => registry.add(Arc::new(transport)),
+            Err(err) => tracing::warn!("can't initialize roc transport: {err}"),
+        }
+
+        registry.add(Arc::new(RtpTransport::new()));
+
+        registry
+    }
+
+    /// Construct registry without transports.
+    pub fn empty() -> Self {
+        TransportRegistry { transports: Vec::new() }
+    }
+
+    /// Register transport.
+    /// Transports added earlier have higher priority.
+    pub fn add(&mut self, transport: Arc<dyn Transport>) {
+        tracing::debug!("registering transport: {}", transport.name());
+
+        self.transports.push(transport);
+    }
+
+    /// Get names of registered transports, in priority order.
+    pub fn transport_names(&self) -> Vec<&'static str> {
+        self.transports.iter().map(|transport| transport.name()).collect()
+    }
+
+    /// Check that connection options are applied by some transport.
+    /// Connection that no transport supports regardless of options
+    /// isn't rejected here; stream using it fails when started instead.
+    pub fn validate(
+        &self, connection: &ConnectionSpec, encoding: &EncodingSpec,
+    ) -> Result<(), ValidationError> {
+        let ConnectionSpec::External { media_uri, multicast_ttl: Some(_), .. } = connection
+        else {
+            return Ok(());
+        };
+        if self.select(connection, encoding).is_ok() {
+            return Ok(());
+        }
+
+        let mut connection_without_ttl = connection.clone();
+        if let ConnectionSpec::External { multicast_ttl, .. } = &mut connection_without_ttl {
+            *multicast_ttl = None;
+        }
+        if let Ok(transport) = self.select(&connection_without_ttl, encoding) {
+            return Err(ValidationError::LayoutError(format!(
+                "multicast_ttl is not supported by {} transport used for {media_uri}",
+                transport.name()
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Select first transport that supports given connection.
+    pub fn select(
+        &self, connection: &ConnectionSpec, encoding: &EncodingSpec,
+    ) -> TransportResult<Arc<dyn Transport>> {
+        self.transports
+            .iter()
+            .find(|transport| transport.supports(connection, encoding))
+            .cloned()
+            .ok_or_else(|| match connection {
+                ConnectionSpec::External { media_uri, .. } => {
+                    TransportError::UnsupportedError(media_uri.to_string())
+                },
+                ConnectionSpec::Endpoint { endpoint_uri, .. } => {
+                    TransportError::UnsupportedError(endpoint_uri.to_string())
+                },
+            })
+    }
+}
@@ -1,9 +1,13 @@
 // Copyright (c) Roc Streaming authors
 // Licensed under MPL-2.0
+pub mod audio;
+pub mod auth;
 pub mod drivers;
 pub mod dto;
+pub mod events;
 pub mod io_endpoints;
 pub mod io_streams;
 pub mod p2p;
+pub mod recordings;
 pub mod rest_api;
 pub mod vault;
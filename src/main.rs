@@ -1,18 +1,24 @@
 // Copyright (c) Roc Streaming authors
 // Licensed under MPL-2.0
+use rocd::auth::TokenStore;
 use rocd::drivers::DriverRegistry;
-use rocd::dto::DriverId;
+use rocd::dto::{DriverId, TokenScope, Uid};
+use rocd::events::EventBus;
 use rocd::io_endpoints::EndpointDispatcher;
-use rocd::io_streams::StreamDispatcher;
+use rocd::io_streams::{PortRange, StreamDispatcher, TransportRegistry};
 use rocd::p2p::PeerDispatcher;
-use rocd::rest_api::RestServer;
+use rocd::recordings::RecordingStore;
+use rocd::rest_api::{AuthConfig, ListenAddr, RestServer, ServerConfigBuilder, TlsConfig};
+use rocd::vault::{Vault, VaultConfigBuilder};
 
 use clap::builder::styling::{AnsiColor, Styles};
-use clap::{ArgAction, Parser};
-use std::net::SocketAddr;
+use clap::{ArgAction, Parser, Subcommand};
+use std::env;
+use std::fs;
+use std::path::PathBuf;
 use std::process;
-use std::str::FromStr;
 use std::sync::Arc;
+use std::time::Duration;
 use time::macros::format_description;
 use tracing_subscriber::fmt::time::LocalTime;
 
@@ -25,17 +31,116 @@ const CLI_STYLES: Styles = Styles::styled()
 #[derive(Parser, Debug)]
 #[command(about = "rocd server", styles = CLI_STYLES)]
 struct CliArgs {
-    /// Address for HTTP server.
-    #[arg(short, long, value_name = "HOST:PORT", default_value = "127.0.0.1:4040")]
-    addr: String,
+    /// Address for HTTP server, HOST:PORT or unix:PATH.
+    /// Can be specified multiple times.
+    #[arg(short, long, value_name = "ADDR", default_value = "127.0.0.1:4040")]
+    #[arg(alias = "addr", short_alias = 'a')]
+    listen: Vec<ListenAddr>,
+
+    /// Permissions of Unix socket files, in octal.
+    #[arg(long, value_name = "MODE", default_value = "600", value_parser = parse_mode)]
+    socket_mode: u32,
 
     /// Driver for audio devices.
     #[arg(short, long, value_enum, value_name = "DRIVER")]
     driver: Option<DriverId>,
 
+    /// Range of local UDP ports for automatic allocation.
+    #[arg(long, value_name = "FIRST-LAST", default_value_t = PortRange::default())]
+    rtp_ports: PortRange,
+
+    /// How often audio levels are measured and published, in milliseconds.
+    #[arg(long, value_name = "MS", default_value_t = 100)]
+    #[arg(value_parser = clap::value_parser!(u64).range(10..=10000))]
+    meter_interval: u64,
+
+    /// Certificate chain for HTTPS, in PEM format.
+    /// Reloaded automatically when file changes.
+    #[arg(long, value_name = "PATH", requires = "tls_key")]
+    tls_cert: Option<PathBuf>,
+
+    /// Private key for HTTPS, in PEM format.
+    #[arg(long, value_name = "PATH", requires = "tls_cert")]
+    tls_key: Option<PathBuf>,
+
+    /// Use self-signed certificate for HTTPS.
+    /// Generated in state directory on first run.
+    #[arg(long, conflicts_with = "tls_cert")]
+    tls_self_signed: bool,
+
+    /// Require bearer token for API requests.
+    /// Tokens are managed using "token" command.
+    #[arg(long)]
+    auth: bool,
+
+    /// Allow reading OpenAPI docs without token.
+    #[arg(long, requires = "auth")]
+    public_docs: bool,
+
+    /// Allow health and readiness checks without token.
+    #[arg(long, requires = "auth")]
+    public_health: bool,
+
+    /// Don't serve Prometheus metrics at /metrics.
+    #[arg(long)]
+    no_metrics: bool,
+
+    /// Don't serve web admin UI at /ui.
+    #[arg(long)]
+    no_ui: bool,
+
+    /// Directory for persistent state.
+    /// Defaults to $XDG_STATE_HOME/rocd or ~/.local/state/rocd.
+    #[arg(short, long, value_name = "DIR", global = true)]
+    state_dir: Option<PathBuf>,
+
+    /// Directory for stream recordings.
+    /// Defaults to "recordings" in state directory.
+    #[arg(long, value_name = "DIR")]
+    recordings_dir: Option<PathBuf>,
+
+    /// Directory with media files for virtual source endpoints.
+    /// Defaults to "media" in state directory.
+    #[arg(long, value_name = "DIR")]
+    media_dir: Option<PathBuf>,
+
     /// Increase verbosity (can be specified more than once).
-    #[arg(short, long, action = ArgAction::Count)]
+    #[arg(short, long, action = ArgAction::Count, global = true)]
     verbose: u8,
+
+    /// Run command instead of server.
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Manage API tokens.
+    /// Server should be stopped, because it locks state directory.
+    #[command(subcommand)]
+    Token(TokenCommand),
+}
+
+#[derive(Subcommand, Debug)]
+enum TokenCommand {
+    /// Create token and print it.
+    Add {
+        /// Token name, e.g. name of client using it.
+        name: String,
+
+        /// What requests token allows.
+        #[arg(long, value_enum, default_value_t = TokenScope::ReadOnly)]
+        scope: TokenScope,
+    },
+
+    /// List tokens.
+    List,
+
+    /// Remove token.
+    Remove {
+        /// UID of token (part before dot).
+        token_uid: String,
+    },
 }
 
 /// Report error and exit.
@@ -54,9 +159,79 @@ async fn main() {
 
     tracing::info!("running with {args:?}");
 
-    let addr = match SocketAddr::from_str(&args.addr) {
-        Ok(addr) => addr,
-        Err(err) => oops!("invalid --addr: {err}"),
+    let state_dir = match args.state_dir.or_else(default_state_dir) {
+        Some(state_dir) => state_dir,
+        None => oops!("can't determine state directory, use --state-dir"),
+    };
+
+    if let Err(err) = fs::create_dir_all(&state_dir) {
+        oops!("can't create state directory {}: {err}", state_dir.display());
+    }
+
+    let vault = match VaultConfigBuilder::default()
+        .db_path(state_dir.join("rocd.db").to_string_lossy())
+        .build()
+    {
+        Ok(config) => match Vault::open(&config).await {
+            Ok(vault) => Arc::new(vault),
+            Err(err) => oops!("can't open vault: {err}"),
+        },
+        Err(err) => oops!("invalid vault config: {err}"),
+    };
+
+    let tokens = Arc::new(TokenStore::new(&vault));
+
+    if let Some(Command::Token(command)) = args.command {
+        run_token_command(&tokens, command).await;
+        return;
+    }
+
+    let mut server_config = ServerConfigBuilder::default();
+    server_config.socket_mode(args.socket_mode);
+    server_config.metrics(!args.no_metrics);
+    server_config.ui(!args.no_ui);
+
+    if let (Some(cert_path), Some(key_path)) = (&args.tls_cert, &args.tls_key) {
+        server_config.tls(TlsConfig::new(cert_path, key_path));
+    } else if args.tls_self_signed {
+        // certificate is also valid for explicitly specified listen addresses
+        let host_names: Vec<String> = args
+            .listen
+            .iter()
+            .filter_map(|addr| match addr {
+                ListenAddr::Tcp(addr) if !addr.ip().is_unspecified() => {
+                    Some(addr.ip().to_string())
+                },
+                _ => None,
+            })
+            .collect();
+
+        let tls_config = match TlsConfig::self_signed(&state_dir.join("tls"), &host_names) {
+            Ok(tls_config) => tls_config,
+            Err(err) => oops!("can't prepare self-signed certificate: {err}"),
+        };
+        match tls_config.fingerprint() {
+            Ok(fingerprint) => println!("certificate fingerprint (SHA-256): {fingerprint}"),
+            Err(err) => oops!("can't read self-signed certificate: {err}"),
+        }
+
+        server_config.tls(tls_config);
+    }
+    if args.auth {
+        match tokens.has_tokens().await {
+            Ok(true) => (),
+            Ok(false) => tracing::warn!("auth is enabled, but there are no tokens"),
+            Err(err) => oops!("can't read tokens: {err}"),
+        }
+        server_config.auth(AuthConfig {
+            tokens: Arc::clone(&tokens),
+            public_docs: args.public_docs,
+            public_health: args.public_health,
+        });
+    }
+    let server_config = match server_config.build() {
+        Ok(config) => config,
+        Err(err) => oops!("invalid server config: {err}"),
     };
 
     let driver_registry = DriverRegistry::new();
@@ -74,14 +249,39 @@ async fn main() {
             .unwrap(),
     };
 
-    let peer_dispatcher = Arc::new(PeerDispatcher::new());
-    let endpoint_dispatcher = Arc::new(EndpointDispatcher::new(&driver));
-    let stream_dispatcher = Arc::new(StreamDispatcher::new(&driver));
+    let recordings_dir = args.recordings_dir.unwrap_or_else(|| state_dir.join("recordings"));
+    let recordings = Arc::new(RecordingStore::new(&recordings_dir));
+
+    let media_dir = args.media_dir.unwrap_or_else(|| state_dir.join("media"));
 
-    let server =
-        Arc::new(RestServer::new(&peer_dispatcher, &endpoint_dispatcher, &stream_dispatcher));
+    let transports = Arc::new(TransportRegistry::new());
+    tracing::debug!("enabled transports: {:?}", transports.transport_names());
 
-    if let Err(err) = server.start(addr).await {
+    let events = Arc::new(EventBus::new());
+
+    let peer_dispatcher = Arc::new(PeerDispatcher::new(&vault));
+    let endpoint_dispatcher = Arc::new(EndpointDispatcher::new(&driver, &vault, &media_dir));
+    let stream_dispatcher = Arc::new(StreamDispatcher::new(
+        &endpoint_dispatcher,
+        &vault,
+        &transports,
+        &recordings,
+        &events,
+        args.rtp_ports,
+        Duration::from_millis(args.meter_interval),
+    ));
+    stream_dispatcher.start();
+
+    let server = Arc::new(RestServer::new(
+        &peer_dispatcher,
+        &endpoint_dispatcher,
+        &stream_dispatcher,
+        &events,
+        &vault,
+        &server_config,
+    ));
+
+    if let Err(err) = server.start(&args.listen).await {
         oops!("can't start http server: {err}");
     }
 
@@ -92,6 +292,55 @@ async fn main() {
     driver.close().await;
 }
 
+async fn run_token_command(tokens: &TokenStore, command: TokenCommand) {
+    match command {
+        TokenCommand::Add { name, scope } => match tokens.create_token(&name, scope).await {
+            Ok((_, bearer)) => println!("{bearer}"),
+            Err(err) => oops!("can't create token: {err}"),
+        },
+        TokenCommand::List => match tokens.list_tokens().await {
+            Ok(token_list) => {
+                for token in token_list {
+                    println!(
+                        "{}  {:<9}  {}  {}",
+                        token.token_uid,
+                        token.scope.to_string(),
+                        token.created_at.date(),
+                        token.name
+                    );
+                }
+            },
+            Err(err) => oops!("can't list tokens: {err}"),
+        },
+        TokenCommand::Remove { token_uid } => {
+            let token_uid = match Uid::parse(&token_uid) {
+                Ok(token_uid) => token_uid,
+                Err(err) => oops!("invalid token uid: {err}"),
+            };
+            if let Err(err) = tokens.remove_token(&token_uid).await {
+                oops!("can't remove token: {err}");
+            }
+        },
+    }
+}
+
+fn parse_mode(text: &str) -> Result<u32, String> {
+    u32::from_str_radix(text, 8)
+        .ok()
+        .filter(|mode| *mode <= 0o777)
+        .ok_or_else(|| format!("expected octal mode, got '{text}'"))
+}
+
+fn default_state_dir() -> Option<PathBuf> {
+    if let Some(state_home) = env::var_os("XDG_STATE_HOME").filter(|dir| !dir.is_empty()) {
+        return Some(PathBuf::from(state_home).join("rocd"));
+    }
+
+    env::var_os("HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(|home| PathBuf::from(home).join(".local/state/rocd"))
+}
+
 fn init_tracing(verbosity: u8) {
     let level = match verbosity {
         0 => tracing::Level::WARN,
@@ -0,0 +1,29 @@
+// Copyright (c) Roc Streaming authors
+// Licensed under MPL-2.0
+use crate::dto::{Uid, ValidationError};
+use crate::vault::VaultError;
+
+#[derive(thiserror::Error, Debug)]
+pub enum PeerError {
+    #[error("peer not found: {0}")]
+    NotFoundError(Uid),
+
+    #[error("peer revision mismatch: {0}")]
+    RevisionError(Uid),
+
+    #[error("{0}")]
+    ValidationError(#[from] ValidationError),
+
+    #[error("storage failure: {0}")]
+    VaultError(#[source] VaultError),
+}
+
+impl From<VaultError> for PeerError {
+    fn from(err: VaultError) -> Self {
+        match err {
+            VaultError::UidNotFound(uid) => PeerError::NotFoundError(*uid),
+            VaultError::ValidationError(err) => PeerError::ValidationError(err),
+            err => PeerError::VaultError(err),
+        }
+    }
+}
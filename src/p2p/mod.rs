@@ -1,5 +1,7 @@
 // Copyright (c) Roc Streaming authors
 // Licensed under MPL-2.0
+mod error;
 mod peer_dispatcher;
 
+pub use self::error::*;
 pub use self::peer_dispatcher::*;
@@ -1,25 +1,123 @@
 // Copyright (c) Roc Streaming authors
 // Licensed under MPL-2.0
 use crate::dto::*;
+use crate::p2p::error::*;
+use crate::vault::{Vault, VaultError};
 
+use std::result;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+pub type Result<T> = result::Result<T, PeerError>;
+
+/// Keeps peers in vault.
+///
+/// Self peer always exists; until it's written for the first time,
+/// it's not stored and has revision 0.
 pub struct PeerDispatcher {
-    // TODO
+    vault: Arc<Vault>,
+    // serializes revision checks and peer writes
+    write_mutex: Mutex<()>,
 }
 
 impl PeerDispatcher {
-    pub fn new() -> Self {
-        PeerDispatcher {}
+    pub fn new(vault: &Arc<Vault>) -> Self {
+        PeerDispatcher { vault: Arc::clone(vault), write_mutex: Mutex::new(()) }
     }
 
     pub async fn self_uid(&self) -> Uid {
         Uid::parse("777777-888888-999999").unwrap()
     }
 
-    pub async fn get_all(&self) -> Vec<PeerSpec> {
-        vec![self.get_peer(&Uid::parse("777777-888888-999999").unwrap()).await]
+    pub async fn get_all(&self) -> Result<Vec<PeerSpec>> {
+        let self_uid = self.self_uid().await;
+
+        let mut peer_uids: Vec<Uid> = self.vault.list_peers().await?.iter().copied().collect();
+        if !peer_uids.contains(&self_uid) {
+            peer_uids.push(self_uid);
+        }
+        peer_uids.sort();
+
+        let mut peers = Vec::with_capacity(peer_uids.len());
+        for peer_uid in &peer_uids {
+            peers.push(self.get_peer(peer_uid).await?);
+        }
+
+        Ok(peers)
+    }
+
+    pub async fn get_peer(&self, peer_uid: &Uid) -> Result<PeerSpec> {
+        match self.load_peer(peer_uid).await? {
+            Some(peer) => Ok(peer),
+            None if *peer_uid == self.self_uid().await => Ok(PeerSpec {
+                peer_uri: Uri::from_peer(peer_uid),
+                peer_uid: *peer_uid,
+                revision: 0,
+            }),
+            None => Err(PeerError::NotFoundError(*peer_uid)),
+        }
+    }
+
+    /// Create or replace peer.
+    /// If condition is given, fails unless current peer matches it.
+    pub async fn update_peer(
+        &self, peer: PeerSpec, if_match: Option<&RevisionMatch>,
+    ) -> Result<PeerSpec> {
+        let _guard = self.write_mutex.lock().await;
+
+        let revision = match self.get_peer(&peer.peer_uid).await {
+            Ok(current) => Some(current.revision),
+            Err(PeerError::NotFoundError(_)) => None,
+            Err(err) => return Err(err),
+        };
+        if if_match.is_some_and(|if_match| !if_match.matches(revision)) {
+            return Err(PeerError::RevisionError(peer.peer_uid));
+        }
+
+        self.write_peer(peer, revision).await
+    }
+
+    /// Apply JSON Merge Patch to peer and save result.
+    /// If condition is given, fails unless current peer matches it.
+    pub async fn patch_peer(
+        &self, peer_uid: &Uid, patch: &serde_json::Value, if_match: Option<&RevisionMatch>,
+    ) -> Result<PeerSpec> {
+        let _guard = self.write_mutex.lock().await;
+
+        let peer = self.get_peer(peer_uid).await?;
+        if if_match.is_some_and(|if_match| !if_match.matches(Some(peer.revision))) {
+            return Err(PeerError::RevisionError(*peer_uid));
+        }
+
+        let patched_peer = apply_merge_patch(&peer, patch)?;
+        if patched_peer.peer_uid != *peer_uid {
+            return Err(
+                ValidationError::LayoutError("peer_uid can't be changed".into()).into()
+            );
+        }
+
+        self.write_peer(patched_peer, Some(peer.revision)).await
+    }
+
+    /// Save peer, which current revision is given (none if it's new).
+    /// Must be called with write_mutex locked.
+    async fn write_peer(&self, mut peer: PeerSpec, revision: Option<u64>) -> Result<PeerSpec> {
+        peer.validate()?;
+        peer.revision = revision.map_or(1, |revision| revision + 1);
+
+        self.vault.write_peer(&Arc::new(peer.clone())).await?;
+
+        tracing::debug!("updated peer {}", peer.peer_uid);
+
+        Ok(peer)
     }
 
-    pub async fn get_peer(&self, peer_uid: &Uid) -> PeerSpec {
-        PeerSpec { peer_uri: Uri::from_peer(peer_uid), peer_uid: *peer_uid }
+    /// Read peer from vault, or none if it's not stored.
+    async fn load_peer(&self, peer_uid: &Uid) -> Result<Option<PeerSpec>> {
+        match self.vault.read_peer(peer_uid).await {
+            Ok(peer) => Ok(Some((*peer).clone())),
+            Err(VaultError::UidNotFound(_)) => Ok(None),
+            Err(err) => Err(err.into()),
+        }
     }
 }
@@ -0,0 +1,21 @@
+// Copyright (c) Roc Streaming authors
+// Licensed under MPL-2.0
+use std::io;
+
+#[derive(thiserror::Error, Debug)]
+pub enum RecordingError {
+    #[error("recording not found: {0}")]
+    NotFoundError(String),
+
+    #[error("recording is in progress: {0}")]
+    InProgressError(String),
+
+    #[error("invalid recording id '{0}'")]
+    IdFormatError(String),
+
+    #[error("unsupported format: {0}")]
+    FormatError(String),
+
+    #[error("i/o error: {0}")]
+    IoError(#[from] io::Error),
+}
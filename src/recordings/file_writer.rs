@@ -0,0 +1,48 @@
+// Copyright (c) Roc Streaming authors
+// Licensed under MPL-2.0
+use crate::dto::SampleFormat;
+use crate::recordings::error::*;
+
+/// Encoder of audio file.
+pub trait FileWriter: Send {
+    /// Append interleaved samples.
+    fn write(&mut self, samples: &[f32]) -> Result<(), RecordingError>;
+
+    /// Flush buffered data and update headers, so that file written
+    /// so far can be read.
+    fn sync(&mut self) -> Result<(), RecordingError>;
+
+    /// Flush remaining data and finalize headers.
+    /// Writer should not be used after this call.
+    fn finish(&mut self) -> Result<(), RecordingError>;
+}
+
+/// Size of one encoded sample, in bytes.
+pub fn sample_size(format: SampleFormat) -> usize {
+    match format {
+        SampleFormat::S16 => 2,
+        SampleFormat::S24 => 3,
+    }
+}
+
+/// Number of bits in encoded sample.
+pub fn sample_bits(format: SampleFormat) -> u32 {
+    sample_size(format) as u32 * 8
+}
+
+/// Convert float sample to integer with given number of bits.
+pub fn quantize_sample(format: SampleFormat, sample: f32) -> i32 {
+    let max_value = (1i32 << (sample_bits(format) - 1)) - 1;
+
+    (sample.clamp(-1.0, 1.0) * max_value as f32).round() as i32
+}
+
+/// Append samples encoded as little-endian integer PCM to buffer.
+pub fn encode_samples_le(format: SampleFormat, samples: &[f32], buf: &mut Vec<u8>) {
+    let size = sample_size(format);
+
+    for &sample in samples {
+        let value = quantize_sample(format, sample);
+        buf.extend_from_slice(&value.to_le_bytes()[..size]);
+    }
+}
@@ -0,0 +1,727 @@
+// Copyright (c) Roc Streaming authors
+// Licensed under MPL-2.0
+use crate::audio::AudioFormat;
+use crate::dto::SampleFormat;
+use crate::recordings::error::*;
+use crate::recordings::file_writer::*;
+
+use std::fs::File;
+use std::io::{BufWriter, Seek, SeekFrom, Write};
+
+/// Writes FLAC file.
+///
+/// This is a simple encoder: it uses fixed block size, independent
+/// channels, and fixed linear predictors (order 0-4) with single-partition
+/// Rice coding of residual. Compression is usually within 10-20% of what
+/// reference encoder achieves with default settings.
+///
+/// Metadata is written to VORBIS_COMMENT block. STREAMINFO is updated on
+/// sync() and finish() with number of encoded samples.
+pub struct FlacWriter {
+    file: BufWriter<File>,
+    format: AudioFormat,
+    sample_format: SampleFormat,
+    bits: u32,
+    // pending samples of current block, per channel
+    block: Vec<Vec<i32>>,
+    frame_number: u64,
+    // number of encoded samples per channel
+    total_samples: u64,
+    min_frame_size: u32,
+    max_frame_size: u32,
+    residual: Vec<i32>,
+    output: BitWriter,
+}
+
+impl FlacWriter {
+    const BLOCK_SIZE: usize = 4096;
+    const MAX_CHANNELS: u16 = 8;
+    const MAX_ORDER: usize = 4;
+    const MAX_RICE_PARAM: u32 = 14;
+
+    const BLOCK_TYPE_STREAMINFO: u8 = 0;
+    const BLOCK_TYPE_VORBIS_COMMENT: u8 = 4;
+    const STREAMINFO_SIZE: usize = 34;
+    // "fLaC" and metadata block header
+    const STREAMINFO_OFFSET: u64 = 8;
+
+    const VENDOR: &'static str = "rocd";
+
+    pub fn create(
+        file: File, format: AudioFormat, sample_format: SampleFormat,
+        metadata: &[(String, String)],
+    ) -> Result<Self, RecordingError> {
+        if format.channels == 0 || format.channels > Self::MAX_CHANNELS {
+            return Err(RecordingError::FormatError(format!(
+                "FLAC supports up to {} channels, got {}",
+                Self::MAX_CHANNELS,
+                format.channels
+            )));
+        }
+        if format.sample_rate == 0 || format.sample_rate >= 1 << 20 {
+            return Err(RecordingError::FormatError(format!(
+                "FLAC doesn't support sample rate {}",
+                format.sample_rate
+            )));
+        }
+
+        let mut writer = FlacWriter {
+            file: BufWriter::new(file),
+            format,
+            sample_format,
+            bits: sample_bits(sample_format),
+            block: vec![Vec::with_capacity(Self::BLOCK_SIZE); format.channels as usize],
+            frame_number: 0,
+            total_samples: 0,
+            min_frame_size: 0,
+            max_frame_size: 0,
+            residual: Vec::new(),
+            output: BitWriter::default(),
+        };
+
+        let comment = Self::build_comment(metadata);
+
+        let mut header = Vec::new();
+        header.extend_from_slice(b"fLaC");
+        Self::append_block_header(
+            &mut header,
+            false,
+            Self::BLOCK_TYPE_STREAMINFO,
+            Self::STREAMINFO_SIZE,
+        );
+        header.extend_from_slice(&writer.build_streaminfo());
+        Self::append_block_header(
+            &mut header,
+            true,
+            Self::BLOCK_TYPE_VORBIS_COMMENT,
+            comment.len(),
+        );
+        header.extend_from_slice(&comment);
+
+        writer.file.write_all(&header)?;
+
+        Ok(writer)
+    }
+
+    fn append_block_header(buf: &mut Vec<u8>, is_last: bool, block_type: u8, size: usize) {
+        buf.push((is_last as u8) << 7 | block_type);
+        buf.extend_from_slice(&(size as u32).to_be_bytes()[1..]);
+    }
+
+    fn build_streaminfo(&self) -> [u8; Self::STREAMINFO_SIZE] {
+        let mut info = [0u8; Self::STREAMINFO_SIZE];
+
+        let block_size = Self::BLOCK_SIZE as u16;
+        info[0..2].copy_from_slice(&block_size.to_be_bytes());
+        info[2..4].copy_from_slice(&block_size.to_be_bytes());
+        info[4..7].copy_from_slice(&self.min_frame_size.to_be_bytes()[1..]);
+        info[7..10].copy_from_slice(&self.max_frame_size.to_be_bytes()[1..]);
+
+        // 20 bits rate, 3 bits channels, 5 bits depth, 36 bits total samples
+        let packed = (self.format.sample_rate as u64) << 44
+            | ((self.format.channels - 1) as u64) << 41
+            | ((self.bits - 1) as u64) << 36
+            | self.total_samples.min((1 << 36) - 1);
+        info[10..18].copy_from_slice(&packed.to_be_bytes());
+
+        // MD5 signature is left zero, which means "unknown"
+        info
+    }
+
+    fn build_comment(metadata: &[(String, String)]) -> Vec<u8> {
+        let mut comment = Vec::new();
+
+        comment.extend_from_slice(&(Self::VENDOR.len() as u32).to_le_bytes());
+        comment.extend_from_slice(Self::VENDOR.as_bytes());
+        comment.extend_from_slice(&(metadata.len() as u32).to_le_bytes());
+
+        for (key, value) in metadata {
+            let entry = format!("{}={value}", key.to_ascii_uppercase());
+            comment.extend_from_slice(&(entry.len() as u32).to_le_bytes());
+            comment.extend_from_slice(entry.as_bytes());
+        }
+
+        comment
+    }
+
+    fn encode_frame(&mut self) -> Result<(), RecordingError> {
+        let n_samples = self.block[0].len();
+        if n_samples == 0 {
+            return Ok(());
+        }
+
+        let output = &mut self.output;
+        output.clear();
+
+        // sync code, fixed block size
+        output.write(0xfff8, 16);
+        // block size is stored at end of header as 16-bit value,
+        // sample rate is taken from STREAMINFO
+        output.write(0x70, 8);
+        // independent channels, explicit sample size
+        let size_code = match self.sample_format {
+            SampleFormat::S16 => 0b100,
+            SampleFormat::S24 => 0b110,
+        };
+        output.write(((self.format.channels - 1) as u64) << 4 | size_code << 1, 8);
+        output.write_utf8(self.frame_number);
+        output.write((n_samples - 1) as u64, 16);
+        let crc = crc8(output.bytes());
+        output.write(crc as u64, 8);
+
+        for channel in 0..self.block.len() {
+            encode_subframe(output, &self.block[channel], self.bits, &mut self.residual);
+        }
+
+        output.align();
+        let crc = crc16(output.bytes());
+        output.write(crc as u64, 16);
+
+        self.file.write_all(output.bytes())?;
+
+        let frame_size = output.bytes().len() as u32;
+        self.min_frame_size = match self.min_frame_size {
+            0 => frame_size,
+            size => size.min(frame_size),
+        };
+        self.max_frame_size = self.max_frame_size.max(frame_size);
+
+        self.frame_number += 1;
+        self.total_samples += n_samples as u64;
+
+        for channel in &mut self.block {
+            channel.clear();
+        }
+
+        Ok(())
+    }
+
+    fn write_streaminfo(&mut self) -> Result<(), RecordingError> {
+        let info = self.build_streaminfo();
+
+        self.file.seek(SeekFrom::Start(Self::STREAMINFO_OFFSET))?;
+        self.file.write_all(&info)?;
+        self.file.seek(SeekFrom::End(0))?;
+
+        Ok(())
+    }
+}
+
+impl FileWriter for FlacWriter {
+    fn write(&mut self, samples: &[f32]) -> Result<(), RecordingError> {
+        for frame in samples.chunks_exact(self.format.channels as usize) {
+            for (channel, &sample) in frame.iter().enumerate() {
+                self.block[channel].push(quantize_sample(self.sample_format, sample));
+            }
+            if self.block[0].len() == Self::BLOCK_SIZE {
+                self.encode_frame()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn sync(&mut self) -> Result<(), RecordingError> {
+        // pending samples are kept until block is full, because
+        // only last block may be shorter than others
+        self.write_streaminfo()?;
+        self.file.flush()?;
+
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<(), RecordingError> {
+        self.encode_frame()?;
+
+        self.sync()?;
+        self.file.get_ref().sync_all()?;
+
+        Ok(())
+    }
+}
+
+/// Encode channel using encoding with smallest size:
+/// constant, fixed predictor, or verbatim.
+fn encode_subframe(
+    output: &mut BitWriter, samples: &[i32], bits: u32, residual: &mut Vec<i32>,
+) {
+    const TYPE_CONSTANT: u64 = 0b000000;
+    const TYPE_VERBATIM: u64 = 0b000001;
+    const TYPE_FIXED: u64 = 0b001000;
+
+    if samples.iter().all(|&sample| sample == samples[0]) {
+        output.write(TYPE_CONSTANT << 1, 8);
+        output.write_signed(samples[0], bits);
+        return;
+    }
+
+    let mut best: Option<(usize, u32, u64)> = None;
+
+    for order in 0..=FlacWriter::MAX_ORDER.min(samples.len() - 1) {
+        compute_residual(samples, order, residual);
+
+        let (rice_param, rice_bits) = choose_rice_param(residual);
+        let size = order as u64 * bits as u64 + 10 + rice_bits;
+
+        if best.is_none_or(|(_, _, best_size)| size < best_size) {
+            best = Some((order, rice_param, size));
+        }
+    }
+
+    match best {
+        Some((order, rice_param, size)) if size < samples.len() as u64 * bits as u64 => {
+            output.write((TYPE_FIXED | order as u64) << 1, 8);
+            for &sample in &samples[..order] {
+                output.write_signed(sample, bits);
+            }
+
+            compute_residual(samples, order, residual);
+
+            // Rice coding with 4-bit parameter, single partition
+            output.write(0b00, 2);
+            output.write(0, 4);
+            output.write(rice_param as u64, 4);
+            for &value in residual.iter() {
+                output.write_rice(value, rice_param);
+            }
+        },
+        _ => {
+            output.write(TYPE_VERBATIM << 1, 8);
+            for &sample in samples {
+                output.write_signed(sample, bits);
+            }
+        },
+    }
+}
+
+/// Compute residual of fixed polynomial predictor of given order.
+fn compute_residual(samples: &[i32], order: usize, residual: &mut Vec<i32>) {
+    residual.clear();
+
+    for n in order..samples.len() {
+        let x = |i: usize| samples[n - i] as i64;
+        let value = match order {
+            0 => x(0),
+            1 => x(0) - x(1),
+            2 => x(0) - 2 * x(1) + x(2),
+            3 => x(0) - 3 * x(1) + 3 * x(2) - x(3),
+            _ => x(0) - 4 * x(1) + 6 * x(2) - 4 * x(3) + x(4),
+        };
+        residual.push(value as i32);
+    }
+}
+
+/// Find Rice parameter giving smallest encoded size of residual.
+/// Returns parameter and size in bits.
+fn choose_rice_param(residual: &[i32]) -> (u32, u64) {
+    if residual.is_empty() {
+        return (0, 0);
+    }
+
+    let sum: u64 = residual.iter().map(|&value| zigzag(value) as u64).sum();
+    let mean = sum / residual.len() as u64;
+    // optimal parameter is close to log2(mean)
+    let estimate = (u64::BITS - mean.leading_zeros()).min(FlacWriter::MAX_RICE_PARAM);
+
+    (estimate.saturating_sub(1)..=(estimate + 1).min(FlacWriter::MAX_RICE_PARAM))
+        .map(|param| {
+            let size = residual
+                .iter()
+                .map(|&value| (zigzag(value) >> param) as u64 + 1 + param as u64)
+                .sum();
+            (param, size)
+        })
+        .min_by_key(|&(_, size)| size)
+        .unwrap()
+}
+
+/// Map signed value to unsigned: 0, -1, 1, -2, 2... to 0, 1, 2, 3, 4...
+fn zigzag(value: i32) -> u32 {
+    ((value << 1) ^ (value >> 31)) as u32
+}
+
+/// CRC-8 with polynomial x^8 + x^2 + x^1 + x^0, used in frame header.
+fn crc8(data: &[u8]) -> u8 {
+    let mut crc = 0u8;
+    for &byte in data {
+        crc ^= byte;
+        for _ in 0..8 {
+            crc = if crc & 0x80 != 0 { crc << 1 ^ 0x07 } else { crc << 1 };
+        }
+    }
+    crc
+}
+
+/// CRC-16 with polynomial x^16 + x^15 + x^2 + x^0, used in frame footer.
+fn crc16(data: &[u8]) -> u16 {
+    let mut crc = 0u16;
+    for &byte in data {
+        crc ^= (byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 { crc << 1 ^ 0x8005 } else { crc << 1 };
+        }
+    }
+    crc
+}
+
+/// Writes bits MSB-first.
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    // pending bits, less than 8
+    acc: u64,
+    n_bits: u32,
+}
+
+impl BitWriter {
+    fn clear(&mut self) {
+        self.bytes.clear();
+        self.acc = 0;
+        self.n_bits = 0;
+    }
+
+    fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Write lower bits of value, up to 32 bits.
+    fn write(&mut self, value: u64, bits: u32) {
+        self.acc = self.acc << bits | (value & ((1 << bits) - 1));
+        self.n_bits += bits;
+
+        while self.n_bits >= 8 {
+            self.n_bits -= 8;
+            self.bytes.push((self.acc >> self.n_bits) as u8);
+        }
+        self.acc &= (1 << self.n_bits) - 1;
+    }
+
+    /// Write two's complement value.
+    fn write_signed(&mut self, value: i32, bits: u32) {
+        self.write(value as u32 as u64, bits);
+    }
+
+    /// Write value as Rice code: unary quotient and binary remainder.
+    fn write_rice(&mut self, value: i32, param: u32) {
+        let value = zigzag(value);
+        let mut quotient = value >> param;
+
+        while quotient >= 32 {
+            self.write(0, 32);
+            quotient -= 32;
+        }
+        self.write(1, quotient + 1);
+        self.write(value as u64, param);
+    }
+
+    /// Write value using UTF-8-like variable length coding.
+    fn write_utf8(&mut self, value: u64) {
+        if value < 0x80 {
+            self.write(value, 8);
+            return;
+        }
+
+        // number of bytes needed for value
+        let n_bytes = match value {
+            0x80..0x800 => 2,
+            0x800..0x10000 => 3,
+            0x10000..0x200000 => 4,
+            0x200000..0x4000000 => 5,
+            0x4000000..0x80000000 => 6,
+            _ => 7,
+        };
+
+        let prefix = (0xff00u64 >> n_bytes) & 0xff;
+        self.write(prefix | value >> (6 * (n_bytes - 1)), 8);
+        for n in (0..n_bytes - 1).rev() {
+            self.write(0x80 | (value >> (6 * n)) & 0x3f, 8);
+        }
+    }
+
+    /// Pad with zeros to byte boundary.
+    fn align(&mut self) {
+        if self.n_bits > 0 {
+            self.write(0, 8 - self.n_bits);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assertables::*;
+    use std::io::Read;
+    use tempfile::tempfile;
+
+    /// Reads bits MSB-first.
+    struct BitReader<'a> {
+        data: &'a [u8],
+        pos: usize,
+    }
+
+    impl BitReader<'_> {
+        fn read(&mut self, bits: u32) -> u64 {
+            let mut value = 0;
+            for _ in 0..bits {
+                let bit = self.data[self.pos / 8] >> (7 - self.pos % 8) & 1;
+                value = value << 1 | bit as u64;
+                self.pos += 1;
+            }
+            value
+        }
+
+        fn read_signed(&mut self, bits: u32) -> i32 {
+            ((self.read(bits) as u32) << (32 - bits)) as i32 >> (32 - bits)
+        }
+
+        fn read_utf8(&mut self) -> u64 {
+            let first = self.read(8);
+            if first < 0x80 {
+                return first;
+            }
+            let n_bytes = (first as u8).leading_ones();
+            let mut value = first & (0x7f >> n_bytes);
+            for _ in 1..n_bytes {
+                value = value << 6 | self.read(8) & 0x3f;
+            }
+            value
+        }
+
+        fn align(&mut self) {
+            self.pos = self.pos.div_ceil(8) * 8;
+        }
+    }
+
+    struct Decoded {
+        sample_rate: u32,
+        channels: usize,
+        bits: u32,
+        total_samples: u64,
+        comments: Vec<String>,
+        // per channel
+        samples: Vec<Vec<i32>>,
+    }
+
+    /// Decoder of subset of FLAC produced by FlacWriter.
+    fn decode(data: &[u8]) -> Decoded {
+        assert_eq!(&data[0..4], b"fLaC");
+
+        let info = &data[8..8 + FlacWriter::STREAMINFO_SIZE];
+        let packed = u64::from_be_bytes(info[10..18].try_into().unwrap());
+        let mut decoded = Decoded {
+            sample_rate: (packed >> 44) as u32,
+            channels: ((packed >> 41) & 0x7) as usize + 1,
+            bits: ((packed >> 36) & 0x1f) as u32 + 1,
+            total_samples: packed & ((1 << 36) - 1),
+            comments: Vec::new(),
+            samples: Vec::new(),
+        };
+        decoded.samples.resize(decoded.channels, Vec::new());
+
+        // metadata blocks
+        let mut pos = 4;
+        loop {
+            let is_last = data[pos] & 0x80 != 0;
+            let block_type = data[pos] & 0x7f;
+            let size =
+                u32::from_be_bytes([0, data[pos + 1], data[pos + 2], data[pos + 3]]) as usize;
+            let block = &data[pos + 4..pos + 4 + size];
+
+            if block_type == FlacWriter::BLOCK_TYPE_VORBIS_COMMENT {
+                let read_u32 = |at: usize| {
+                    u32::from_le_bytes(block[at..at + 4].try_into().unwrap()) as usize
+                };
+                let mut at = 4 + read_u32(0);
+                let count = read_u32(at);
+                at += 4;
+                for _ in 0..count {
+                    let len = read_u32(at);
+                    decoded.comments.push(
+                        String::from_utf8(block[at + 4..at + 4 + len].to_vec()).unwrap(),
+                    );
+                    at += 4 + len;
+                }
+            }
+
+            pos += 4 + size;
+            if is_last {
+                break;
+            }
+        }
+
+        // frames
+        let mut frame_number = 0;
+        while pos < data.len() {
+            let mut reader = BitReader { data: &data[pos..], pos: 0 };
+
+            assert_eq!(reader.read(16), 0xfff8);
+            assert_eq!(reader.read(8), 0x70);
+            assert_eq!(reader.read(4) as usize, decoded.channels - 1);
+            reader.read(4);
+            assert_eq!(reader.read_utf8(), frame_number);
+            let n_samples = reader.read(16) as usize + 1;
+            let header_size = reader.pos / 8;
+            assert_eq!(reader.read(8) as u8, crc8(&data[pos..pos + header_size]));
+
+            for channel in 0..decoded.channels {
+                let out = &mut decoded.samples[channel];
+                assert_eq!(reader.read(1), 0);
+                let subframe_type = reader.read(6);
+                assert_eq!(reader.read(1), 0);
+
+                match subframe_type {
+                    0b000000 => {
+                        let value = reader.read_signed(decoded.bits);
+                        out.extend(std::iter::repeat_n(value, n_samples));
+                    },
+                    0b000001 => {
+                        for _ in 0..n_samples {
+                            out.push(reader.read_signed(decoded.bits));
+                        }
+                    },
+                    0b001000..=0b001100 => {
+                        let order = (subframe_type & 0x7) as usize;
+                        let start = out.len();
+                        for _ in 0..order {
+                            out.push(reader.read_signed(decoded.bits));
+                        }
+                        assert_eq!(reader.read(2), 0);
+                        assert_eq!(reader.read(4), 0);
+                        let param = reader.read(4) as u32;
+                        for n in order..n_samples {
+                            let mut quotient = 0;
+                            while reader.read(1) == 0 {
+                                quotient += 1;
+                            }
+                            let value = (quotient << param | reader.read(param)) as u32;
+                            let residual = (value >> 1) as i64 ^ -((value & 1) as i64);
+                            let x = |i: usize| out[start + n - i] as i64;
+                            let prediction = match order {
+                                0 => 0,
+                                1 => x(1),
+                                2 => 2 * x(1) - x(2),
+                                3 => 3 * x(1) - 3 * x(2) + x(3),
+                                _ => 4 * x(1) - 6 * x(2) + 4 * x(3) - x(4),
+                            };
+                            out.push((prediction + residual) as i32);
+                        }
+                    },
+                    _ => panic!("unexpected subframe type {subframe_type}"),
+                }
+            }
+
+            reader.align();
+            let frame_size = reader.pos / 8;
+            assert_eq!(reader.read(16) as u16, crc16(&data[pos..pos + frame_size]));
+
+            pos += frame_size + 2;
+            frame_number += 1;
+        }
+
+        decoded
+    }
+
+    fn write_flac(
+        format: AudioFormat, sample_format: SampleFormat, samples: &[f32],
+    ) -> Vec<u8> {
+        let mut file = tempfile().unwrap();
+        let mut writer = FlacWriter::create(
+            file.try_clone().unwrap(),
+            format,
+            sample_format,
+            &[("title".into(), "Studio A".into())],
+        )
+        .unwrap();
+
+        // write in chunks not aligned to block size
+        for chunk in samples.chunks(format.channels as usize * 1000) {
+            writer.write(chunk).unwrap();
+        }
+        writer.finish().unwrap();
+
+        let mut data = Vec::new();
+        file.seek(SeekFrom::Start(0)).unwrap();
+        file.read_to_end(&mut data).unwrap();
+        data
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let format = AudioFormat { sample_rate: 44100, channels: 2 };
+        let n_frames = 10000;
+
+        let mut samples = Vec::new();
+        let mut noise = 1u32;
+        for n in 0..n_frames {
+            // sine in left channel
+            samples.push((n as f32 * 0.05).sin() * 0.8);
+            // silence followed by noise in right channel
+            noise = noise.wrapping_mul(1664525).wrapping_add(1013904223);
+            samples.push(if n < 5000 { 0.0 } else { noise as f32 / u32::MAX as f32 - 0.5 });
+        }
+
+        for sample_format in [SampleFormat::S16, SampleFormat::S24] {
+            let data = write_flac(format, sample_format, &samples);
+            let decoded = decode(&data);
+
+            assert_eq!(decoded.sample_rate, 44100);
+            assert_eq!(decoded.channels, 2);
+            assert_eq!(decoded.bits, sample_bits(sample_format));
+            assert_eq!(decoded.total_samples, n_frames as u64);
+            assert_eq!(decoded.comments, vec!["TITLE=Studio A"]);
+
+            for (n, frame) in samples.chunks(2).enumerate() {
+                for (channel, &sample) in frame.iter().enumerate() {
+                    assert_eq!(
+                        decoded.samples[channel][n],
+                        quantize_sample(sample_format, sample),
+                        "{sample_format} frame={n} channel={channel}"
+                    );
+                }
+            }
+
+            // sine is compressed
+            let pcm_size = n_frames * 2 * sample_size(sample_format);
+            assert_lt!(data.len(), pcm_size * 3 / 4);
+        }
+    }
+
+    #[test]
+    fn test_empty() {
+        let format = AudioFormat { sample_rate: 48000, channels: 1 };
+
+        let data = write_flac(format, SampleFormat::S16, &[]);
+        let decoded = decode(&data);
+
+        assert_eq!(decoded.total_samples, 0);
+        assert_eq!(decoded.samples, vec![Vec::<i32>::new()]);
+    }
+
+    #[test]
+    fn test_utf8() {
+        for value in [0, 0x7f, 0x80, 0x7ff, 0x800, 0xffff, 0x10000, 0x7fffffff, 0xfffffffff] {
+            let mut writer = BitWriter::default();
+            writer.write_utf8(value);
+
+            let mut reader = BitReader { data: writer.bytes(), pos: 0 };
+            assert_eq!(reader.read_utf8(), value, "{value:x}");
+            assert_eq!(reader.pos, writer.bytes().len() * 8);
+        }
+    }
+
+    #[test]
+    fn test_crc() {
+        // check values of CRC-8/SMBUS and CRC-16/UMTS
+        assert_eq!(crc8(b"123456789"), 0xf4);
+        assert_eq!(crc16(b"123456789"), 0xfee8);
+    }
+
+    #[test]
+    fn test_unsupported() {
+        let format = AudioFormat { sample_rate: 48000, channels: 9 };
+        assert_matches!(
+            FlacWriter::create(tempfile().unwrap(), format, SampleFormat::S16, &[]),
+            Err(RecordingError::FormatError(_))
+        );
+    }
+}
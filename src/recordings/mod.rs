@@ -0,0 +1,15 @@
+// Copyright (c) Roc Streaming authors
+// Licensed under MPL-2.0
+mod error;
+mod file_writer;
+mod flac_writer;
+mod recording_sink;
+mod recording_store;
+mod wav_writer;
+
+pub use self::error::*;
+pub use self::file_writer::*;
+pub use self::flac_writer::*;
+pub use self::recording_sink::*;
+pub use self::recording_store::*;
+pub use self::wav_writer::*;
@@ -0,0 +1,326 @@
+// Copyright (c) Roc Streaming authors
+// Licensed under MPL-2.0
+use crate::audio::*;
+use crate::dto::*;
+use crate::recordings::error::*;
+use crate::recordings::file_writer::*;
+use crate::recordings::flac_writer::*;
+use crate::recordings::recording_store::*;
+use crate::recordings::wav_writer::*;
+
+use std::fs::File;
+use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+use time::OffsetDateTime;
+use time::format_description::well_known::Rfc3339;
+
+/// Audio sink that records audio to files of RecordingStore.
+///
+/// Encoding and disk i/o are done on a separate thread; write() passes
+/// samples via bounded queue and never blocks. If writer thread can't keep
+/// up and queue is full, samples are dropped. If writer thread fails (e.g.
+/// disk is full), write() returns error.
+pub struct RecordingSink {
+    format: AudioFormat,
+    sender: Option<SyncSender<Vec<f32>>>,
+    error: Arc<Mutex<Option<String>>>,
+    thread: Option<JoinHandle<()>>,
+    dropped_chunks: u64,
+}
+
+impl RecordingSink {
+    /// Maximum duration of audio queued for writer thread.
+    const QUEUE_DURATION: Duration = Duration::from_secs(5);
+
+    /// Create first file and start writer thread.
+    pub fn start(
+        store: &Arc<RecordingStore>, stream_uid: &Uid, spec: &RecordingSpec,
+        encoding: &EncodingSpec,
+    ) -> Result<Self, RecordingError> {
+        let format = AudioFormat::from_encoding(encoding);
+
+        let mut worker = RecordingWorker {
+            store: Arc::clone(store),
+            stream_uid: *stream_uid,
+            spec: spec.clone(),
+            format,
+            sample_format: encoding.sample_format,
+            file: None,
+            file_start: OffsetDateTime::now_utc(),
+            file_frames: 0,
+            unsynced_frames: 0,
+        };
+        worker.open_file(OffsetDateTime::now_utc())?;
+
+        // assume that pipeline writes frames of FRAME_DURATION
+        let (sender, receiver) = mpsc::sync_channel(
+            (Self::QUEUE_DURATION.as_millis() / FRAME_DURATION.as_millis()) as usize,
+        );
+        let error = Arc::new(Mutex::new(None));
+
+        let thread = {
+            let error = Arc::clone(&error);
+            thread::Builder::new().name(format!("rec-{stream_uid}")).spawn(move || {
+                if let Err(err) = worker.run(receiver) {
+                    tracing::warn!("recording of stream {} failed: {err}", worker.stream_uid);
+                    *error.lock().unwrap() = Some(err.to_string());
+                }
+            })?
+        };
+
+        Ok(RecordingSink {
+            format,
+            sender: Some(sender),
+            error,
+            thread: Some(thread),
+            dropped_chunks: 0,
+        })
+    }
+}
+
+impl AudioSink for RecordingSink {
+    fn format(&self) -> AudioFormat {
+        self.format
+    }
+
+    fn is_clocked(&self) -> bool {
+        false
+    }
+
+    fn write(&mut self, samples: &[f32]) -> Result<(), AudioError> {
+        let Some(sender) = &self.sender else {
+            return Ok(());
+        };
+
+        match sender.try_send(samples.to_vec()) {
+            Ok(()) => Ok(()),
+            Err(TrySendError::Full(_)) => {
+                if self.dropped_chunks == 0 {
+                    tracing::warn!("recording can't keep up, dropping samples");
+                }
+                self.dropped_chunks += 1;
+                Ok(())
+            },
+            Err(TrySendError::Disconnected(_)) => {
+                let error = self.error.lock().unwrap().clone().unwrap_or_default();
+                Err(AudioError::BackendError(format!("recording failed: {error}")))
+            },
+        }
+    }
+}
+
+impl Drop for RecordingSink {
+    fn drop(&mut self) {
+        // writer thread finishes current file and exits
+        // when channel is closed
+        self.sender.take();
+
+        if let Some(thread) = self.thread.take()
+            && thread.join().is_err()
+        {
+            tracing::error!("recording thread panicked");
+        }
+    }
+}
+
+struct RecordingWorker {
+    store: Arc<RecordingStore>,
+    stream_uid: Uid,
+    spec: RecordingSpec,
+    format: AudioFormat,
+    sample_format: SampleFormat,
+    // current file and its id
+    file: Option<(String, Box<dyn FileWriter>)>,
+    file_start: OffsetDateTime,
+    // frames written to current file
+    file_frames: u64,
+    // frames written since last sync
+    unsynced_frames: u64,
+}
+
+impl RecordingWorker {
+    fn run(&mut self, receiver: Receiver<Vec<f32>>) -> Result<(), RecordingError> {
+        while let Ok(samples) = receiver.recv() {
+            self.write(&samples)?;
+        }
+
+        Ok(())
+    }
+
+    fn write(&mut self, samples: &[f32]) -> Result<(), RecordingError> {
+        let channels = self.format.channels as usize;
+        let rotation_frames = self
+            .spec
+            .rotation_interval
+            .map(|interval| interval as u64 * self.format.sample_rate as u64);
+
+        let mut samples = samples;
+
+        while !samples.is_empty() {
+            let mut n_frames = (samples.len() / channels) as u64;
+
+            if let Some(rotation_frames) = rotation_frames {
+                if self.file_frames >= rotation_frames {
+                    // new file starts where previous one ended
+                    let next_start = self.file_start
+                        + Duration::from_secs(self.spec.rotation_interval.unwrap() as u64);
+                    self.close_file();
+                    self.open_file(next_start)?;
+                }
+                n_frames = n_frames.min(rotation_frames - self.file_frames);
+            }
+
+            let (chunk, rest) = samples.split_at(n_frames as usize * channels);
+            if let Some((_, writer)) = &mut self.file {
+                writer.write(chunk)?;
+            }
+
+            self.file_frames += n_frames;
+            self.unsynced_frames += n_frames;
+            samples = rest;
+        }
+
+        // make file readable at least once a second
+        if self.unsynced_frames >= self.format.sample_rate as u64
+            && let Some((_, writer)) = &mut self.file
+        {
+            writer.sync()?;
+            self.unsynced_frames = 0;
+        }
+
+        Ok(())
+    }
+
+    fn open_file(&mut self, start_time: OffsetDateTime) -> Result<(), RecordingError> {
+        let (recording_id, start_time, file) =
+            self.store.create_file(&self.stream_uid, self.spec.format, start_time)?;
+
+        tracing::info!("starting recording {recording_id} of stream {}", self.stream_uid);
+
+        let writer = match self.create_writer(file, start_time) {
+            Ok(writer) => writer,
+            Err(err) => {
+                self.store.discard_file(&self.stream_uid, &recording_id);
+                return Err(err);
+            },
+        };
+
+        self.file = Some((recording_id, writer));
+        self.file_start = start_time;
+        self.file_frames = 0;
+        self.unsynced_frames = 0;
+
+        if let Err(err) =
+            self.store.apply_retention(&self.stream_uid, &self.spec, OffsetDateTime::now_utc())
+        {
+            tracing::warn!("can't apply retention to stream {}: {err}", self.stream_uid);
+        }
+
+        Ok(())
+    }
+
+    fn create_writer(
+        &self, file: File, start_time: OffsetDateTime,
+    ) -> Result<Box<dyn FileWriter>, RecordingError> {
+        let metadata = self.build_metadata(start_time);
+
+        Ok(match self.spec.format {
+            RecordingFormat::Wav => {
+                Box::new(WavWriter::create(file, self.format, self.sample_format, &metadata)?)
+            },
+            RecordingFormat::Flac => {
+                Box::new(FlacWriter::create(file, self.format, self.sample_format, &metadata)?)
+            },
+        })
+    }
+
+    /// User metadata, plus date, encoder, and stream UID if not overridden.
+    fn build_metadata(&self, start_time: OffsetDateTime) -> Vec<(String, String)> {
+        let mut metadata: Vec<(String, String)> =
+            self.spec.metadata.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+
+        let defaults = [
+            ("date", start_time.format(&Rfc3339).unwrap()),
+            ("encoder", "rocd".into()),
+            ("stream_uid", self.stream_uid.to_string()),
+        ];
+        for (key, value) in defaults {
+            if !metadata.iter().any(|(k, _)| k.eq_ignore_ascii_case(key)) {
+                metadata.push((key.into(), value));
+            }
+        }
+
+        metadata
+    }
+
+    fn close_file(&mut self) {
+        let Some((recording_id, mut writer)) = self.file.take() else {
+            return;
+        };
+
+        if let Err(err) = writer.finish() {
+            tracing::warn!("can't finish recording {recording_id}: {err}");
+        }
+
+        self.store.release_file(&self.stream_uid, &recording_id);
+    }
+}
+
+impl Drop for RecordingWorker {
+    fn drop(&mut self) {
+        self.close_file();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_rotation() {
+        let dir = TempDir::new().unwrap();
+        let store = Arc::new(RecordingStore::new(dir.path()));
+        let stream_uid = Uid::parse("111111-222222-333333").unwrap();
+
+        let encoding = EncodingSpec {
+            payload_type: 11,
+            sample_format: SampleFormat::S16,
+            sample_rate: 1000,
+            channels: 1,
+        };
+        let spec = RecordingSpec {
+            format: RecordingFormat::Wav,
+            rotation_interval: Some(1),
+            max_files: Some(3),
+            max_age: None,
+            metadata: BTreeMap::new(),
+        };
+
+        let mut sink = RecordingSink::start(&store, &stream_uid, &spec, &encoding).unwrap();
+
+        // 4.5 seconds in 10ms frames, fits into queue
+        for _ in 0..450 {
+            sink.write(&[0.5; 10]).unwrap();
+        }
+        drop(sink);
+
+        let recordings = store.list(&stream_uid).unwrap();
+
+        // 5 files were created, 2 oldest were deleted
+        assert_eq!(recordings.len(), 3);
+        assert!(recordings.iter().all(|r| !r.in_progress));
+
+        // files are adjacent
+        for pair in recordings.windows(2) {
+            assert_eq!(pair[1].start_time - pair[0].start_time, time::Duration::seconds(1));
+        }
+
+        // last file is half-second, others are full
+        let header_size = recordings[2].size - 1000;
+        assert_eq!(recordings[1].size, header_size + 2000);
+    }
+}
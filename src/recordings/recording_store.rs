@@ -0,0 +1,361 @@
+// Copyright (c) Roc Streaming authors
+// Licensed under MPL-2.0
+use crate::dto::*;
+use crate::recordings::error::*;
+
+use std::collections::HashSet;
+use std::fs::{self, File, OpenOptions};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use time::format_description::BorrowedFormatItem;
+use time::macros::format_description;
+use time::{OffsetDateTime, PrimitiveDateTime};
+
+/// Start time part of recording id.
+const ID_TIME_FORMAT: &[BorrowedFormatItem<'_>] =
+    format_description!("[year][month][day]T[hour][minute][second].[subsecond digits:3]Z");
+
+/// Manages recorded files on disk.
+///
+/// Files of each stream are kept in "<root_dir>/<stream_uid>/". File name
+/// is the recording id and consists of start time and format extension,
+/// e.g. "20260310T100000.000Z.flac", so that sorting ids sorts recordings
+/// by time.
+pub struct RecordingStore {
+    root_dir: PathBuf,
+    // ids of files being written, per stream
+    active: Mutex<HashSet<(Uid, String)>>,
+}
+
+impl RecordingStore {
+    pub fn new(root_dir: &Path) -> Self {
+        RecordingStore { root_dir: root_dir.to_path_buf(), active: Mutex::new(HashSet::new()) }
+    }
+
+    /// List recordings of stream, oldest first.
+    /// Recordings are kept after stream is deleted, so stream doesn't need to exist.
+    pub fn list(&self, stream_uid: &Uid) -> Result<Vec<RecordingInfo>, RecordingError> {
+        let entries = match fs::read_dir(self.stream_dir(stream_uid)) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(err.into()),
+        };
+
+        let mut recordings = Vec::new();
+
+        for entry in entries {
+            let entry = entry?;
+            let Some(recording_id) = entry.file_name().to_str().map(String::from) else {
+                continue;
+            };
+            // skip foreign files
+            if parse_id(&recording_id).is_none() {
+                continue;
+            }
+            recordings.push(self.make_info(stream_uid, &recording_id, &entry.metadata()?)?);
+        }
+
+        recordings.sort_by(|a, b| a.recording_id.cmp(&b.recording_id));
+
+        Ok(recordings)
+    }
+
+    /// Get recording and path to its file.
+    pub fn get(
+        &self, stream_uid: &Uid, recording_id: &str,
+    ) -> Result<(RecordingInfo, PathBuf), RecordingError> {
+        let path = self.file_path(stream_uid, recording_id)?;
+
+        let metadata = match fs::metadata(&path) {
+            Ok(metadata) => metadata,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                return Err(RecordingError::NotFoundError(recording_id.into()));
+            },
+            Err(err) => return Err(err.into()),
+        };
+
+        Ok((self.make_info(stream_uid, recording_id, &metadata)?, path))
+    }
+
+    /// Delete recording.
+    /// Fails if recording is still being written.
+    pub fn delete(&self, stream_uid: &Uid, recording_id: &str) -> Result<(), RecordingError> {
+        let path = self.file_path(stream_uid, recording_id)?;
+
+        if self.is_active(stream_uid, recording_id) {
+            return Err(RecordingError::InProgressError(recording_id.into()));
+        }
+
+        match fs::remove_file(&path) {
+            Ok(()) => Ok(()),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                Err(RecordingError::NotFoundError(recording_id.into()))
+            },
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Create new file for recording and mark it active.
+    /// If file with same start time exists, start time is slightly shifted.
+    /// Returns recording id, actual start time, and opened file.
+    pub(crate) fn create_file(
+        &self, stream_uid: &Uid, format: RecordingFormat, start_time: OffsetDateTime,
+    ) -> Result<(String, OffsetDateTime, File), RecordingError> {
+        fs::create_dir_all(self.stream_dir(stream_uid))?;
+
+        let mut start_time = start_time;
+
+        loop {
+            let recording_id = format_id(start_time, format);
+
+            match OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(self.stream_dir(stream_uid).join(&recording_id))
+            {
+                Ok(file) => {
+                    self.active.lock().unwrap().insert((*stream_uid, recording_id.clone()));
+                    return Ok((recording_id, start_time, file));
+                },
+                Err(err) if err.kind() == io::ErrorKind::AlreadyExists => {
+                    start_time += Duration::from_millis(1);
+                },
+                Err(err) => return Err(err.into()),
+            }
+        }
+    }
+
+    /// Mark file as no longer active.
+    pub(crate) fn release_file(&self, stream_uid: &Uid, recording_id: &str) {
+        self.active.lock().unwrap().remove(&(*stream_uid, recording_id.into()));
+    }
+
+    /// Delete file that was created but couldn't be written.
+    pub(crate) fn discard_file(&self, stream_uid: &Uid, recording_id: &str) {
+        if let Err(err) = fs::remove_file(self.stream_dir(stream_uid).join(recording_id)) {
+            tracing::warn!("can't remove recording {recording_id}: {err}");
+        }
+
+        self.release_file(stream_uid, recording_id);
+    }
+
+    /// Delete recordings exceeding limits of spec, oldest first.
+    /// Active recordings are never deleted, but are counted in max_files.
+    pub(crate) fn apply_retention(
+        &self, stream_uid: &Uid, spec: &RecordingSpec, now: OffsetDateTime,
+    ) -> Result<(), RecordingError> {
+        if spec.max_files.is_none() && spec.max_age.is_none() {
+            return Ok(());
+        }
+
+        let recordings = self.list(stream_uid)?;
+
+        let excess_count =
+            recordings.len().saturating_sub(spec.max_files.unwrap_or(u32::MAX) as usize);
+        let min_start_time =
+            spec.max_age.map(|max_age| now - Duration::from_secs(max_age as u64));
+
+        for (index, recording) in recordings.iter().enumerate() {
+            if recording.in_progress {
+                continue;
+            }
+
+            let is_excess = index < excess_count;
+            let is_expired = min_start_time.is_some_and(|time| recording.start_time < time);
+
+            if is_excess || is_expired {
+                tracing::info!(
+                    "deleting recording {} of stream {stream_uid} according to retention policy",
+                    recording.recording_id
+                );
+                fs::remove_file(self.stream_dir(stream_uid).join(&recording.recording_id))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn is_active(&self, stream_uid: &Uid, recording_id: &str) -> bool {
+        self.active.lock().unwrap().contains(&(*stream_uid, recording_id.into()))
+    }
+
+    fn stream_dir(&self, stream_uid: &Uid) -> PathBuf {
+        self.root_dir.join(stream_uid.to_string())
+    }
+
+    fn file_path(
+        &self, stream_uid: &Uid, recording_id: &str,
+    ) -> Result<PathBuf, RecordingError> {
+        // ensures that id can't point outside of stream directory
+        if parse_id(recording_id).is_none() {
+            return Err(RecordingError::IdFormatError(recording_id.into()));
+        }
+
+        Ok(self.stream_dir(stream_uid).join(recording_id))
+    }
+
+    fn make_info(
+        &self, stream_uid: &Uid, recording_id: &str, metadata: &fs::Metadata,
+    ) -> Result<RecordingInfo, RecordingError> {
+        let (start_time, format) = parse_id(recording_id)
+            .ok_or_else(|| RecordingError::IdFormatError(recording_id.into()))?;
+
+        Ok(RecordingInfo {
+            recording_id: recording_id.into(),
+            stream_uid: *stream_uid,
+            format,
+            start_time,
+            size: metadata.len(),
+            in_progress: self.is_active(stream_uid, recording_id),
+        })
+    }
+}
+
+/// Build recording id from start time and format.
+fn format_id(start_time: OffsetDateTime, format: RecordingFormat) -> String {
+    let start_time = start_time.to_offset(time::UtcOffset::UTC);
+
+    format!("{}.{}", start_time.format(ID_TIME_FORMAT).unwrap(), format.extension())
+}
+
+/// Parse recording id into start time and format.
+fn parse_id(recording_id: &str) -> Option<(OffsetDateTime, RecordingFormat)> {
+    let (time_part, extension) = recording_id.rsplit_once('.')?;
+
+    let format = RecordingFormat::from_extension(extension)?;
+    let start_time = PrimitiveDateTime::parse(time_part, ID_TIME_FORMAT).ok()?.assume_utc();
+
+    // reject alternative spellings of same time
+    if format_id(start_time, format) != recording_id {
+        return None;
+    }
+
+    Some((start_time, format))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assertables::*;
+    use std::collections::BTreeMap;
+    use tempfile::TempDir;
+    use time::macros::datetime;
+
+    fn make_spec(max_files: Option<u32>, max_age: Option<u32>) -> RecordingSpec {
+        RecordingSpec {
+            format: RecordingFormat::Wav,
+            rotation_interval: None,
+            max_files,
+            max_age,
+            metadata: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_id() {
+        let time = datetime!(2026-03-10 10:00:01.5 UTC);
+
+        let id = format_id(time, RecordingFormat::Flac);
+        assert_eq!(id, "20260310T100001.500Z.flac");
+        assert_eq!(parse_id(&id), Some((time, RecordingFormat::Flac)));
+
+        for id in [
+            "20260310T100001.500Z.mp3",
+            "20260310T100001.500Z",
+            "20260310T100001Z.wav",
+            "../20260310T100001.500Z.wav",
+            "20260310T100001.500Z.wav/..",
+            "",
+        ] {
+            assert_none!(parse_id(id), "{id}");
+        }
+    }
+
+    #[test]
+    fn test_files() {
+        let dir = TempDir::new().unwrap();
+        let store = RecordingStore::new(dir.path());
+        let stream_uid = Uid::parse("111111-222222-333333").unwrap();
+
+        assert_eq!(store.list(&stream_uid).unwrap(), vec![]);
+
+        let time = datetime!(2026-03-10 10:00 UTC);
+        let (id1, time1, _) =
+            store.create_file(&stream_uid, RecordingFormat::Wav, time).unwrap();
+        // same start time is shifted
+        let (id2, time2, _) =
+            store.create_file(&stream_uid, RecordingFormat::Wav, time).unwrap();
+        assert_eq!(time1, time);
+        assert_eq!(time2, time + Duration::from_millis(1));
+
+        // foreign files are ignored
+        fs::write(dir.path().join("111111-222222-333333/notes.txt"), "x").unwrap();
+
+        let list = store.list(&stream_uid).unwrap();
+        assert_eq!(list.iter().map(|r| &r.recording_id).collect::<Vec<_>>(), vec![&id1, &id2]);
+        assert!(list.iter().all(|r| r.in_progress));
+
+        assert_matches!(
+            store.delete(&stream_uid, &id1),
+            Err(RecordingError::InProgressError(_))
+        );
+
+        store.release_file(&stream_uid, &id1);
+        assert!(!store.get(&stream_uid, &id1).unwrap().0.in_progress);
+
+        store.delete(&stream_uid, &id1).unwrap();
+        assert_matches!(store.get(&stream_uid, &id1), Err(RecordingError::NotFoundError(_)));
+        assert_matches!(
+            store.delete(&stream_uid, &id1),
+            Err(RecordingError::NotFoundError(_))
+        );
+        assert_matches!(
+            store.get(&stream_uid, "../rocd.db"),
+            Err(RecordingError::IdFormatError(_))
+        );
+
+        assert_eq!(store.list(&stream_uid).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_retention() {
+        let dir = TempDir::new().unwrap();
+        let store = RecordingStore::new(dir.path());
+        let stream_uid = Uid::parse("111111-222222-333333").unwrap();
+
+        let now = datetime!(2026-03-10 10:00 UTC);
+        let mut ids = Vec::new();
+        for hours in [5, 4, 3, 2, 1, 0] {
+            let time = now - Duration::from_secs(hours * 3600);
+            let (id, _, _) =
+                store.create_file(&stream_uid, RecordingFormat::Wav, time).unwrap();
+            ids.push(id);
+        }
+        // all except most recent are finished
+        for id in &ids[..5] {
+            store.release_file(&stream_uid, id);
+        }
+
+        let remaining = || -> Vec<String> {
+            store.list(&stream_uid).unwrap().into_iter().map(|r| r.recording_id).collect()
+        };
+
+        // no limits
+        store.apply_retention(&stream_uid, &make_spec(None, None), now).unwrap();
+        assert_eq!(remaining(), ids);
+
+        // older than 3.5 hours
+        store.apply_retention(&stream_uid, &make_spec(None, Some(12600)), now).unwrap();
+        assert_eq!(remaining(), ids[2..]);
+
+        // more than 2 files
+        store.apply_retention(&stream_uid, &make_spec(Some(2), None), now).unwrap();
+        assert_eq!(remaining(), ids[4..]);
+
+        // active file is never deleted
+        store.apply_retention(&stream_uid, &make_spec(Some(1), Some(1)), now).unwrap();
+        assert_eq!(remaining(), ids[5..]);
+    }
+}
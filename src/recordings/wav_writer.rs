@@ -0,0 +1,238 @@
+// Copyright (c) Roc Streaming authors
+// Licensed under MPL-2.0
+use crate::audio::AudioFormat;
+use crate::dto::SampleFormat;
+use crate::recordings::error::*;
+use crate::recordings::file_writer::*;
+
+use std::fs::File;
+use std::io::{BufWriter, Seek, SeekFrom, Write};
+
+/// Writes integer PCM to RIFF/WAVE file.
+///
+/// Metadata is written to LIST/INFO chunk placed before data chunk.
+/// Chunk sizes are updated on sync() and finish(), so that the file
+/// is playable while it's being written.
+pub struct WavWriter {
+    file: BufWriter<File>,
+    sample_format: SampleFormat,
+    // offset of data chunk payload
+    data_offset: u64,
+    data_size: u64,
+    buffer: Vec<u8>,
+}
+
+impl WavWriter {
+    const FORMAT_PCM: u16 = 1;
+
+    /// Known metadata keys and corresponding INFO chunk ids.
+    /// Other keys are appended to comment.
+    const INFO_IDS: [(&'static str, &'static [u8; 4]); 9] = [
+        ("title", b"INAM"),
+        ("artist", b"IART"),
+        ("album", b"IPRD"),
+        ("genre", b"IGNR"),
+        ("copyright", b"ICOP"),
+        ("date", b"ICRD"),
+        ("encoder", b"ISFT"),
+        ("engineer", b"IENG"),
+        ("comment", b"ICMT"),
+    ];
+
+    pub fn create(
+        file: File, format: AudioFormat, sample_format: SampleFormat,
+        metadata: &[(String, String)],
+    ) -> Result<Self, RecordingError> {
+        let mut header = Vec::new();
+
+        let bytes_per_sample = sample_size(sample_format) as u16;
+        let block_align = bytes_per_sample * format.channels;
+
+        header.extend_from_slice(b"RIFF");
+        header.extend_from_slice(&0u32.to_le_bytes());
+        header.extend_from_slice(b"WAVE");
+
+        header.extend_from_slice(b"fmt ");
+        header.extend_from_slice(&16u32.to_le_bytes());
+        header.extend_from_slice(&Self::FORMAT_PCM.to_le_bytes());
+        header.extend_from_slice(&format.channels.to_le_bytes());
+        header.extend_from_slice(&format.sample_rate.to_le_bytes());
+        header.extend_from_slice(&(format.sample_rate * block_align as u32).to_le_bytes());
+        header.extend_from_slice(&block_align.to_le_bytes());
+        header.extend_from_slice(&(bytes_per_sample * 8).to_le_bytes());
+
+        let info = Self::build_info(metadata);
+        if !info.is_empty() {
+            header.extend_from_slice(b"LIST");
+            header.extend_from_slice(&(4 + info.len() as u32).to_le_bytes());
+            header.extend_from_slice(b"INFO");
+            header.extend_from_slice(&info);
+        }
+
+        header.extend_from_slice(b"data");
+        header.extend_from_slice(&0u32.to_le_bytes());
+
+        let mut file = BufWriter::new(file);
+        file.write_all(&header)?;
+
+        Ok(WavWriter {
+            file,
+            sample_format,
+            data_offset: header.len() as u64,
+            data_size: 0,
+            buffer: Vec::new(),
+        })
+    }
+
+    fn build_info(metadata: &[(String, String)]) -> Vec<u8> {
+        let mut info = Vec::new();
+        let mut comment = Vec::new();
+
+        for (key, value) in metadata {
+            match Self::INFO_IDS.iter().find(|(name, _)| key.eq_ignore_ascii_case(name)) {
+                Some((_, b"ICMT")) => comment.insert(0, value.clone()),
+                Some((_, id)) => Self::append_subchunk(&mut info, id, value),
+                None => comment.push(format!("{key}={value}")),
+            }
+        }
+
+        if !comment.is_empty() {
+            Self::append_subchunk(&mut info, b"ICMT", &comment.join("\n"));
+        }
+
+        info
+    }
+
+    fn append_subchunk(info: &mut Vec<u8>, id: &[u8; 4], value: &str) {
+        // zero-terminated and padded to even size
+        let size = value.len() + 1;
+        info.extend_from_slice(id);
+        info.extend_from_slice(&(size as u32).to_le_bytes());
+        info.extend_from_slice(value.as_bytes());
+        info.push(0);
+        if !size.is_multiple_of(2) {
+            info.push(0);
+        }
+    }
+
+    fn write_sizes(&mut self) -> Result<(), RecordingError> {
+        // includes padding of data chunk, if any
+        let riff_size = self.file.stream_position()? - 8;
+
+        self.file.seek(SeekFrom::Start(4))?;
+        self.file.write_all(&(riff_size.min(u32::MAX as u64) as u32).to_le_bytes())?;
+        self.file.seek(SeekFrom::Start(self.data_offset - 4))?;
+        self.file.write_all(&(self.data_size.min(u32::MAX as u64) as u32).to_le_bytes())?;
+        self.file.seek(SeekFrom::End(0))?;
+
+        Ok(())
+    }
+}
+
+impl FileWriter for WavWriter {
+    fn write(&mut self, samples: &[f32]) -> Result<(), RecordingError> {
+        self.buffer.clear();
+        encode_samples_le(self.sample_format, samples, &mut self.buffer);
+
+        self.file.write_all(&self.buffer)?;
+        self.data_size += self.buffer.len() as u64;
+
+        Ok(())
+    }
+
+    fn sync(&mut self) -> Result<(), RecordingError> {
+        self.write_sizes()?;
+        self.file.flush()?;
+
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<(), RecordingError> {
+        // pad data chunk to even size
+        if !self.data_size.is_multiple_of(2) {
+            self.file.write_all(&[0])?;
+        }
+
+        self.sync()?;
+        self.file.get_ref().sync_all()?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assertables::*;
+    use std::io::Read;
+    use tempfile::tempfile;
+
+    fn read_u32(data: &[u8], offset: usize) -> u32 {
+        u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap())
+    }
+
+    fn find_chunk<'a>(data: &'a [u8], id: &[u8; 4]) -> Option<&'a [u8]> {
+        let mut offset = 12;
+        while offset + 8 <= data.len() {
+            let size = read_u32(data, offset + 4) as usize;
+            if &data[offset..offset + 4] == id {
+                return Some(&data[offset + 8..offset + 8 + size]);
+            }
+            offset += 8 + size + size % 2;
+        }
+        None
+    }
+
+    #[test]
+    fn test_wav() {
+        let format = AudioFormat { sample_rate: 48000, channels: 2 };
+        let metadata = vec![
+            ("title".into(), "Studio A".into()),
+            ("stream_uid".into(), "111111-222222-333333".into()),
+        ];
+
+        for (sample_format, bits) in [(SampleFormat::S16, 16), (SampleFormat::S24, 24)] {
+            let mut file = tempfile().unwrap();
+            let mut writer =
+                WavWriter::create(file.try_clone().unwrap(), format, sample_format, &metadata)
+                    .unwrap();
+
+            writer.write(&[0.0, 0.5, -0.5, 1.0]).unwrap();
+            writer.write(&[-1.0, 0.25]).unwrap();
+            writer.finish().unwrap();
+
+            let mut data = Vec::new();
+            file.seek(SeekFrom::Start(0)).unwrap();
+            file.read_to_end(&mut data).unwrap();
+
+            assert_eq!(&data[0..4], b"RIFF");
+            assert_eq!(&data[8..12], b"WAVE");
+            assert_eq!(read_u32(&data, 4) as usize, data.len() - 8);
+
+            let fmt = find_chunk(&data, b"fmt ").unwrap();
+            assert_eq!(u16::from_le_bytes([fmt[2], fmt[3]]), 2);
+            assert_eq!(read_u32(fmt, 4), 48000);
+            assert_eq!(u16::from_le_bytes([fmt[14], fmt[15]]), bits);
+
+            let list = find_chunk(&data, b"LIST").unwrap();
+            assert_eq!(&list[0..4], b"INFO");
+            assert_eq!(&list[4..8], b"INAM");
+            assert!(list.windows(31).any(|w| w == b"stream_uid=111111-222222-333333"));
+
+            let samples = find_chunk(&data, b"data").unwrap();
+            assert_eq!(samples.len(), 6 * bits as usize / 8);
+
+            let mut decoded = Vec::new();
+            for chunk in samples.chunks(bits as usize / 8) {
+                let mut bytes = [0u8; 4];
+                bytes[4 - chunk.len()..].copy_from_slice(chunk);
+                // shift right to sign-extend
+                let value = i32::from_le_bytes(bytes) >> (32 - bits);
+                decoded.push(value as f32 / ((1 << (bits - 1)) - 1) as f32);
+            }
+            for (orig, decoded) in [0.0, 0.5, -0.5, 1.0, -1.0, 0.25].iter().zip(&decoded) {
+                assert_in_delta!(*orig, *decoded, 0.0001);
+            }
+        }
+    }
+}
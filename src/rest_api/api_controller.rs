@@ -1,17 +1,34 @@
 // Copyright (c) Roc Streaming authors
 // Licensed under MPL-2.0
+use crate::drivers::DriverRegistry;
 use crate::dto::*;
+use crate::events::EventBus;
 use crate::io_endpoints::EndpointDispatcher;
 use crate::io_streams::StreamDispatcher;
 use crate::p2p::PeerDispatcher;
+use crate::rest_api::api_version::*;
 use crate::rest_api::error::*;
+use crate::rest_api::pagination::*;
 
 use axum::Router;
-use axum::extract::{Extension, Json, Path};
+use axum::body::Body;
+use axum::extract::{Extension, Json, Path, Query};
+use axum::http::{HeaderMap, StatusCode, header};
+use axum::response::Response;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use serde::Deserialize;
+use std::convert::Infallible;
 use std::result;
 use std::sync::Arc;
-use utoipa::OpenApi as _;
+use std::time::Duration;
+use strum::IntoEnumIterator;
+use tokio_stream::wrappers::BroadcastStream;
+use tokio_stream::wrappers::errors::BroadcastStreamRecvError;
+use tokio_stream::{Stream, StreamExt};
+use tokio_util::io::ReaderStream;
 use utoipa::openapi::OpenApi;
+use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::{Modify, OpenApi as _};
 use utoipa_axum::router::OpenApiRouter;
 use utoipa_axum::routes;
 
@@ -19,138 +36,353 @@ pub struct ApiController {
     peer_dispatcher: Arc<PeerDispatcher>,
     endpoint_dispatcher: Arc<EndpointDispatcher>,
     stream_dispatcher: Arc<StreamDispatcher>,
+    events: Arc<EventBus>,
 }
 
 impl ApiController {
     pub fn new(
         peer_dispatcher: &Arc<PeerDispatcher>, endpoint_dispatcher: &Arc<EndpointDispatcher>,
-        stream_dispatcher: &Arc<StreamDispatcher>,
+        stream_dispatcher: &Arc<StreamDispatcher>, events: &Arc<EventBus>,
     ) -> Self {
         ApiController {
             peer_dispatcher: Arc::clone(peer_dispatcher),
             endpoint_dispatcher: Arc::clone(endpoint_dispatcher),
             stream_dispatcher: Arc::clone(stream_dispatcher),
+            events: Arc::clone(events),
         }
     }
 
-    pub fn spec() -> OpenApi {
-        ApiController::build().into_openapi()
+    /// Get OpenAPI spec of given API version.
+    pub fn spec(version: ApiVersion) -> OpenApi {
+        ApiController::build(version).into_openapi()
     }
 
-    pub fn router_with_spec(self: &Arc<Self>) -> (Router, OpenApi) {
-        let ext = Extension(Arc::clone(self));
+    /// Get router with all API versions, and spec of each version.
+    /// Besides prefixed paths, routes of ApiVersion::UNPREFIXED are
+    /// also mounted at root as deprecated aliases.
+    pub fn router_with_specs(self: &Arc<Self>) -> (Router, Vec<(ApiVersion, OpenApi)>) {
+        let mut router = Router::new();
+        let mut specs = Vec::new();
 
-        ApiController::build().layer(ext).split_for_parts()
+        for version in ApiVersion::iter() {
+            let (mut version_router, spec) = ApiController::build(version)
+                .layer(Extension(Arc::clone(self)))
+                .split_for_parts();
+
+            if version.is_deprecated() {
+                version_router = with_deprecation_layer(version_router, None);
+            }
+
+            router = router.merge(version_router);
+            specs.push((version, spec));
+        }
+
+        {
+            let (alias_router, _) = ApiController::build_routes(ApiVersion::UNPREFIXED)
+                .layer(Extension(Arc::clone(self)))
+                .split_for_parts();
+
+            router = router
+                .merge(with_deprecation_layer(alias_router, Some(ApiVersion::UNPREFIXED)));
+        }
+
+        (router, specs)
     }
 
-    fn build() -> OpenApiRouter {
+    /// Build routes of version, mounted under its prefix.
+    fn build(version: ApiVersion) -> OpenApiRouter {
         OpenApiRouter::with_openapi(ApiDoc::openapi())
+            .nest(&version.prefix(), ApiController::build_routes(version))
+    }
+
+    /// Build unprefixed routes of version.
+    fn build_routes(version: ApiVersion) -> OpenApiRouter {
+        match version {
+            ApiVersion::V1 => ApiController::build_v1(),
+        }
+    }
+
+    fn build_v1() -> OpenApiRouter {
+        OpenApiRouter::new()
             // peers
             .routes(routes!(list_peers))
             .routes(routes!(read_peer))
             .routes(routes!(update_peer))
+            .routes(routes!(patch_peer))
             // endpoints
             .routes(routes!(list_endpoints))
             .routes(routes!(read_endpoint))
             .routes(routes!(update_endpoint))
+            .routes(routes!(patch_endpoint))
+            .routes(routes!(delete_endpoint))
             // streams
             .routes(routes!(list_streams))
+            .routes(routes!(import_stream))
             .routes(routes!(read_stream))
             .routes(routes!(update_stream))
+            .routes(routes!(patch_stream))
+            .routes(routes!(delete_stream))
+            .routes(routes!(renew_stream))
+            .routes(routes!(export_stream_sdp))
+            .routes(routes!(read_stream_levels))
+            .routes(routes!(measure_stream_latency))
+            // recordings
+            .routes(routes!(list_recordings))
+            .routes(routes!(download_recording))
+            .routes(routes!(delete_recording))
+            // groups
+            .routes(routes!(list_groups))
+            .routes(routes!(read_group))
+            .routes(routes!(update_group))
+            .routes(routes!(delete_group))
+            .routes(routes!(read_group_status))
+            // events
+            .routes(routes!(subscribe_events))
+            // system
+            .routes(routes!(read_health))
+            .routes(routes!(read_readiness))
+            .routes(routes!(read_version))
     }
 }
 
 #[derive(utoipa::OpenApi)]
-#[openapi(info(title = "rocd REST API",))]
+#[openapi(
+    info(title = "rocd REST API",),
+    modifiers(&BearerAuth),
+    security((), ("bearer_token" = [])),
+)]
 struct ApiDoc;
 
+/// Documents bearer token, which is required if server is started with auth.
+struct BearerAuth;
+
+impl Modify for BearerAuth {
+    fn modify(&self, openapi: &mut OpenApi) {
+        openapi.components.get_or_insert_default().add_security_scheme(
+            "bearer_token",
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .description(Some("Token created with \"rocd token add\" command."))
+                    .build(),
+            ),
+        );
+    }
+}
+
 type Result<T> = result::Result<T, HandlerError>;
 
 // peers
 
+#[derive(Deserialize, utoipa::IntoParams)]
+struct PeerListParams {
+    /// Field by which peers are sorted (default peer_uid).
+    #[param(nullable = false, inline)]
+    sort: Option<PeerSortField>,
+    /// Sort direction (default asc).
+    #[param(nullable = false, inline)]
+    order: Option<SortOrder>,
+    /// Maximum number of returned peers; all by default.
+    #[param(nullable = false)]
+    limit: Option<u32>,
+    /// Value of x-next-cursor header of previous page.
+    #[param(nullable = false)]
+    cursor: Option<String>,
+}
+
 #[utoipa::path(
     get,
     path = "/peers",
+    params(PeerListParams),
     responses(
-        (status = 200, description = "Success", body = [PeerSpec]),
+        (status = 200, description = "Success", body = [PeerSpec],
+            headers(("x-next-cursor" = String, description = "Cursor of next page, if any"))),
     )
 )]
 async fn list_peers(
-    Extension(controller): Extension<Arc<ApiController>>,
-) -> Result<Json<Vec<PeerSpec>>> {
-    Ok(Json(controller.peer_dispatcher.get_all().await))
+    Extension(controller): Extension<Arc<ApiController>>, Query(params): Query<PeerListParams>,
+) -> Result<(HeaderMap, Json<Vec<PeerSpec>>)> {
+    let peers = controller.peer_dispatcher.get_all().await?;
+
+    let sort = params.sort.unwrap_or_default();
+    let page = paginate(
+        peers,
+        |peer| peer_sort_key(peer, sort),
+        &sort.to_string(),
+        params.order.unwrap_or_default(),
+        params.cursor.as_deref(),
+        params.limit,
+    )?;
+
+    Ok(page_response(page))
 }
 
 #[utoipa::path(
     get,
     path = "/peers/{peer_uid}",
     responses(
-        (status = 200, description = "Success", body = PeerSpec),
+        (status = 200, description = "Success", body = PeerSpec,
+            headers(("etag" = String, description = "Revision of peer"))),
     )
 )]
 async fn read_peer(
     Extension(controller): Extension<Arc<ApiController>>, Path(peer_uid): Path<String>,
-) -> Result<Json<PeerSpec>> {
+) -> Result<(HeaderMap, Json<PeerSpec>)> {
     let peer_uid = if peer_uid == "self" {
         controller.peer_dispatcher.self_uid().await
     } else {
         Uid::parse(&peer_uid)?
     };
 
-    Ok(Json(controller.peer_dispatcher.get_peer(&peer_uid).await))
+    let peer = controller.peer_dispatcher.get_peer(&peer_uid).await?;
+
+    Ok(etag_response(peer.revision, peer))
 }
 
 #[utoipa::path(
     put,
     path = "/peers/{peer_uid}",
+    params(IfMatchHeader),
+    request_body = PeerSpec,
     responses(
-        (status = 200, description = "Success", body = PeerSpec),
+        (status = 200, description = "Success", body = PeerSpec,
+            headers(("etag" = String, description = "Revision of peer"))),
     )
 )]
 async fn update_peer(
     Extension(controller): Extension<Arc<ApiController>>, Path(peer_uid): Path<String>,
-) -> Result<Json<PeerSpec>> {
+    headers: HeaderMap, Json(peer): Json<PeerSpec>,
+) -> Result<(HeaderMap, Json<PeerSpec>)> {
     let peer_uid = if peer_uid == "self" {
         controller.peer_dispatcher.self_uid().await
     } else {
         Uid::parse(&peer_uid)?
     };
 
-    Ok(Json(controller.peer_dispatcher.get_peer(&peer_uid).await))
+    if peer.peer_uid != peer_uid {
+        return Err(ValidationError::LayoutError(
+            "UID mismatch in request path and peer_uid field".into(),
+        )
+        .into());
+    }
+
+    let if_match = parse_if_match(&headers)?;
+    let peer = controller.peer_dispatcher.update_peer(peer, if_match.as_ref()).await?;
+
+    Ok(etag_response(peer.revision, peer))
+}
+
+#[utoipa::path(
+    patch,
+    path = "/peers/{peer_uid}",
+    params(IfMatchHeader),
+    request_body(
+        content = Object,
+        content_type = "application/merge-patch+json",
+        description = "JSON Merge Patch (RFC 7396) of PeerSpec",
+    ),
+    responses(
+        (status = 200, description = "Success", body = PeerSpec,
+            headers(("etag" = String, description = "Revision of peer"))),
+    )
+)]
+async fn patch_peer(
+    Extension(controller): Extension<Arc<ApiController>>, Path(peer_uid): Path<String>,
+    headers: HeaderMap, body: String,
+) -> Result<(HeaderMap, Json<PeerSpec>)> {
+    let peer_uid = if peer_uid == "self" {
+        controller.peer_dispatcher.self_uid().await
+    } else {
+        Uid::parse(&peer_uid)?
+    };
+
+    let patch = parse_merge_patch(&headers, &body)?;
+    let if_match = parse_if_match(&headers)?;
+    let peer =
+        controller.peer_dispatcher.patch_peer(&peer_uid, &patch, if_match.as_ref()).await?;
+
+    Ok(etag_response(peer.revision, peer))
 }
 
 // endpoints
 
+#[derive(Deserialize, utoipa::IntoParams)]
+struct EndpointListParams {
+    /// Return only endpoints with this direction.
+    #[param(nullable = false)]
+    stream_direction: Option<EndpointDir>,
+    /// Return only endpoints of this type.
+    #[param(nullable = false)]
+    endpoint_type: Option<EndpointType>,
+    /// Return only endpoints of this driver.
+    #[param(nullable = false)]
+    driver: Option<DriverId>,
+    /// Field by which endpoints are sorted (default endpoint_uid).
+    #[param(nullable = false, inline)]
+    sort: Option<EndpointSortField>,
+    /// Sort direction (default asc).
+    #[param(nullable = false, inline)]
+    order: Option<SortOrder>,
+    /// Maximum number of returned endpoints; all by default.
+    #[param(nullable = false)]
+    limit: Option<u32>,
+    /// Value of x-next-cursor header of previous page.
+    #[param(nullable = false)]
+    cursor: Option<String>,
+}
+
 #[utoipa::path(
     get,
     path = "/peers/{peer_uid}/endpoints",
+    params(EndpointListParams),
     responses(
-        (status = 200, description = "Success", body = [EndpointSpec]),
+        (status = 200, description = "Success", body = [EndpointSpec],
+            headers(("x-next-cursor" = String, description = "Cursor of next page, if any"))),
     )
 )]
 async fn list_endpoints(
     Extension(controller): Extension<Arc<ApiController>>, Path(peer_uid): Path<String>,
-) -> Result<Json<Vec<EndpointSpec>>> {
+    Query(params): Query<EndpointListParams>,
+) -> Result<(HeaderMap, Json<Vec<EndpointSpec>>)> {
     let peer_uid = if peer_uid == "self" {
         controller.peer_dispatcher.self_uid().await
     } else {
         Uid::parse(&peer_uid)?
     };
 
-    Ok(Json(controller.endpoint_dispatcher.get_all(&peer_uid).await))
+    let mut endpoints = controller.endpoint_dispatcher.get_all(&peer_uid).await?;
+
+    endpoints.retain(|endpoint| {
+        params.stream_direction.is_none_or(|dir| endpoint.stream_direction == dir)
+            && params.endpoint_type.is_none_or(|ty| endpoint.endpoint_type == ty)
+            && params.driver.is_none_or(|driver| endpoint.driver == driver)
+    });
+
+    let sort = params.sort.unwrap_or_default();
+    let page = paginate(
+        endpoints,
+        |endpoint| endpoint_sort_key(endpoint, sort),
+        &sort.to_string(),
+        params.order.unwrap_or_default(),
+        params.cursor.as_deref(),
+        params.limit,
+    )?;
+
+    Ok(page_response(page))
 }
 
 #[utoipa::path(
     get,
     path = "/peers/{peer_uid}/endpoints/{endpoint_uid}",
     responses(
-        (status = 200, description = "Success", body = EndpointSpec),
+        (status = 200, description = "Success", body = EndpointSpec,
+            headers(("etag" = String, description = "Revision of endpoint"))),
     )
 )]
 async fn read_endpoint(
     Extension(controller): Extension<Arc<ApiController>>,
     Path((peer_uid, endpoint_uid)): Path<(String, String)>,
-) -> Result<Json<EndpointSpec>> {
+) -> Result<(HeaderMap, Json<EndpointSpec>)> {
     let peer_uid = if peer_uid == "self" {
         controller.peer_dispatcher.self_uid().await
     } else {
@@ -159,20 +391,69 @@ async fn read_endpoint(
 
     let endpoint_uid = Uid::parse(&endpoint_uid)?;
 
-    Ok(Json(controller.endpoint_dispatcher.get_endpoint(&peer_uid, &endpoint_uid).await))
+    let endpoint =
+        controller.endpoint_dispatcher.get_endpoint(&peer_uid, &endpoint_uid).await?;
+
+    Ok(etag_response(endpoint.revision, endpoint))
 }
 
 #[utoipa::path(
     put,
     path = "/peers/{peer_uid}/endpoints/{endpoint_uid}",
+    params(IfMatchHeader),
+    request_body = EndpointSpec,
     responses(
-        (status = 200, description = "Success", body = EndpointSpec),
+        (status = 200, description = "Success", body = EndpointSpec,
+            headers(("etag" = String, description = "Revision of endpoint"))),
     )
 )]
 async fn update_endpoint(
     Extension(controller): Extension<Arc<ApiController>>,
-    Path((peer_uid, endpoint_uid)): Path<(String, String)>,
-) -> Result<Json<EndpointSpec>> {
+    Path((peer_uid, endpoint_uid)): Path<(String, String)>, headers: HeaderMap,
+    Json(endpoint): Json<EndpointSpec>,
+) -> Result<(HeaderMap, Json<EndpointSpec>)> {
+    let peer_uid = if peer_uid == "self" {
+        controller.peer_dispatcher.self_uid().await
+    } else {
+        Uid::parse(&peer_uid)?
+    };
+
+    let endpoint_uid = Uid::parse(&endpoint_uid)?;
+
+    if endpoint.endpoint_uid != endpoint_uid
+        || endpoint.endpoint_uri.peer_uid() != Some(peer_uid)
+    {
+        return Err(ValidationError::LayoutError(
+            "UID mismatch in request path and endpoint_uri field".into(),
+        )
+        .into());
+    }
+
+    let if_match = parse_if_match(&headers)?;
+    let endpoint =
+        controller.endpoint_dispatcher.update_endpoint(endpoint, if_match.as_ref()).await?;
+
+    Ok(etag_response(endpoint.revision, endpoint))
+}
+
+#[utoipa::path(
+    patch,
+    path = "/peers/{peer_uid}/endpoints/{endpoint_uid}",
+    params(IfMatchHeader),
+    request_body(
+        content = Object,
+        content_type = "application/merge-patch+json",
+        description = "JSON Merge Patch (RFC 7396) of EndpointSpec",
+    ),
+    responses(
+        (status = 200, description = "Success", body = EndpointSpec,
+            headers(("etag" = String, description = "Revision of endpoint"))),
+    )
+)]
+async fn patch_endpoint(
+    Extension(controller): Extension<Arc<ApiController>>,
+    Path((peer_uid, endpoint_uid)): Path<(String, String)>, headers: HeaderMap, body: String,
+) -> Result<(HeaderMap, Json<EndpointSpec>)> {
     let peer_uid = if peer_uid == "self" {
         controller.peer_dispatcher.self_uid().await
     } else {
@@ -181,50 +462,736 @@ async fn update_endpoint(
 
     let endpoint_uid = Uid::parse(&endpoint_uid)?;
 
-    Ok(Json(controller.endpoint_dispatcher.get_endpoint(&peer_uid, &endpoint_uid).await))
+    let patch = parse_merge_patch(&headers, &body)?;
+    let if_match = parse_if_match(&headers)?;
+    let endpoint = controller
+        .endpoint_dispatcher
+        .patch_endpoint(&peer_uid, &endpoint_uid, &patch, if_match.as_ref())
+        .await?;
+
+    Ok(etag_response(endpoint.revision, endpoint))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/peers/{peer_uid}/endpoints/{endpoint_uid}",
+    params(IfMatchHeader),
+    responses(
+        (status = 204, description = "Deleted"),
+    )
+)]
+async fn delete_endpoint(
+    Extension(controller): Extension<Arc<ApiController>>,
+    Path((peer_uid, endpoint_uid)): Path<(String, String)>, headers: HeaderMap,
+) -> Result<StatusCode> {
+    let peer_uid = if peer_uid == "self" {
+        controller.peer_dispatcher.self_uid().await
+    } else {
+        Uid::parse(&peer_uid)?
+    };
+
+    let endpoint_uid = Uid::parse(&endpoint_uid)?;
+
+    let if_match = parse_if_match(&headers)?;
+    controller
+        .endpoint_dispatcher
+        .delete_endpoint(&peer_uid, &endpoint_uid, if_match.as_ref())
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
 }
 
 // streams
 
+#[derive(Deserialize, utoipa::IntoParams)]
+struct StreamListParams {
+    /// Return only streams in this state.
+    #[param(nullable = false)]
+    state: Option<StreamState>,
+    /// Return only streams which source has this connection type,
+    /// e.g. external for streams receiving from network.
+    #[param(nullable = false)]
+    source_type: Option<ConnectionType>,
+    /// Return only streams which destination has this connection type,
+    /// e.g. external for streams sending to network.
+    #[param(nullable = false)]
+    destination_type: Option<ConnectionType>,
+    /// Return only streams which source has this endpoint URI,
+    /// or this media, repair, or control URI.
+    #[param(nullable = false)]
+    source_uri: Option<String>,
+    /// Return only streams which destination has this endpoint URI,
+    /// or this media, repair, or control URI.
+    #[param(nullable = false)]
+    destination_uri: Option<String>,
+    /// Field by which streams are sorted (default stream_uid).
+    #[param(nullable = false, inline)]
+    sort: Option<StreamSortField>,
+    /// Sort direction (default asc).
+    #[param(nullable = false, inline)]
+    order: Option<SortOrder>,
+    /// Maximum number of returned streams; all by default.
+    #[param(nullable = false)]
+    limit: Option<u32>,
+    /// Value of x-next-cursor header of previous page.
+    #[param(nullable = false)]
+    cursor: Option<String>,
+}
+
 #[utoipa::path(
     get,
     path = "/streams",
+    params(StreamListParams),
     responses(
-        (status = 200, description = "Success", body = [StreamSpec]),
+        (status = 200, description = "Success", body = [StreamSpec],
+            headers(("x-next-cursor" = String, description = "Cursor of next page, if any"))),
     )
 )]
 async fn list_streams(
     Extension(controller): Extension<Arc<ApiController>>,
-) -> Result<Json<Vec<StreamSpec>>> {
-    Ok(Json(controller.stream_dispatcher.get_all().await))
+    Query(params): Query<StreamListParams>,
+) -> Result<(HeaderMap, Json<Vec<StreamSpec>>)> {
+    let source_uri = params.source_uri.as_deref().map(Uri::parse).transpose()?;
+    let destination_uri = params.destination_uri.as_deref().map(Uri::parse).transpose()?;
+
+    let mut streams = controller.stream_dispatcher.get_all().await?;
+
+    streams.retain(|stream| {
+        params.state.is_none_or(|state| stream.state == state)
+            && params.source_type.is_none_or(|ty| stream.source.layout_type() == ty)
+            && params.destination_type.is_none_or(|ty| stream.destination.layout_type() == ty)
+            && source_uri.as_ref().is_none_or(|uri| connection_has_uri(&stream.source, uri))
+            && destination_uri
+                .as_ref()
+                .is_none_or(|uri| connection_has_uri(&stream.destination, uri))
+    });
+
+    let sort = params.sort.unwrap_or_default();
+    let page = paginate(
+        streams,
+        |stream| stream_sort_key(stream, sort),
+        &sort.to_string(),
+        params.order.unwrap_or_default(),
+        params.cursor.as_deref(),
+        params.limit,
+    )?;
+
+    Ok(page_response(page))
+}
+
+/// Check if connection has given endpoint URI or external URI.
+fn connection_has_uri(connection: &ConnectionSpec, uri: &Uri) -> bool {
+    match connection {
+        ConnectionSpec::Endpoint { endpoint_uri, .. } => endpoint_uri == uri,
+        ConnectionSpec::External { .. } => connection.external_uris().contains(&uri),
+    }
+}
+
+/// Build list response with cursor of next page in header.
+fn page_response<T>(page: Page<T>) -> (HeaderMap, Json<Vec<T>>) {
+    let mut headers = HeaderMap::new();
+
+    if let Some(cursor) = page.next_cursor {
+        headers.insert(NEXT_CURSOR_HEADER, cursor.parse().unwrap());
+    }
+
+    (headers, Json(page.items))
+}
+
+#[derive(Deserialize, utoipa::IntoParams)]
+struct ImportParams {
+    /// Endpoint to which received audio is written.
+    endpoint_uri: String,
+    /// Owner of created stream.
+    owner: Option<String>,
+    /// Lease TTL of created stream, in seconds.
+    lease_ttl: Option<u32>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/streams",
+    params(ImportParams),
+    request_body(
+        content = String,
+        content_type = "application/sdp",
+        description = "SDP session description of sender",
+    ),
+    responses(
+        (status = 201, description = "Created", body = StreamSpec),
+    )
+)]
+async fn import_stream(
+    Extension(controller): Extension<Arc<ApiController>>, Query(params): Query<ImportParams>,
+    headers: HeaderMap, body: String,
+) -> Result<(StatusCode, Json<StreamSpec>)> {
+    check_content_type(&headers, SDP_CONTENT_TYPE)?;
+
+    let endpoint_uri = Uri::parse(&params.endpoint_uri)?;
+    if endpoint_uri.kind() != UriKind::Endpoint {
+        return Err(
+            ValidationError::LayoutError("unexpected endpoint_uri format".into()).into()
+        );
+    }
+
+    let stream = controller
+        .stream_dispatcher
+        .import_sdp(&body, &endpoint_uri, params.owner, params.lease_ttl)
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(stream)))
 }
 
 #[utoipa::path(
     get,
     path = "/streams/{stream_uid}",
     responses(
-        (status = 200, description = "Success", body = StreamSpec),
+        (status = 200, description = "Success", body = StreamSpec,
+            headers(("etag" = String, description = "Revision of stream"))),
     )
 )]
 async fn read_stream(
     Extension(controller): Extension<Arc<ApiController>>, Path(stream_uid): Path<String>,
-) -> Result<Json<StreamSpec>> {
+) -> Result<(HeaderMap, Json<StreamSpec>)> {
     let stream_uid = Uid::parse(&stream_uid)?;
 
-    Ok(Json(controller.stream_dispatcher.get_stream(&stream_uid).await))
+    let stream = controller.stream_dispatcher.get_stream(&stream_uid).await?;
+
+    Ok(etag_response(stream.revision, stream))
 }
 
 #[utoipa::path(
     put,
     path = "/streams/{stream_uid}",
+    description = "Create stream or replace existing one.\n\n\
+        If stream with given UID doesn't exist, it's created (upsert).",
+    params(IfMatchHeader),
+    request_body = StreamSpec,
     responses(
-        (status = 200, description = "Success", body = StreamSpec),
+        (status = 200, description = "Success", body = StreamSpec,
+            headers(("etag" = String, description = "Revision of stream"))),
     )
 )]
 async fn update_stream(
     Extension(controller): Extension<Arc<ApiController>>, Path(stream_uid): Path<String>,
+    headers: HeaderMap, Json(stream): Json<StreamSpec>,
+) -> Result<(HeaderMap, Json<StreamSpec>)> {
+    let stream_uid = Uid::parse(&stream_uid)?;
+
+    if stream.stream_uid != stream_uid {
+        return Err(ValidationError::LayoutError(
+            "UID mismatch in request path and stream_uid field".into(),
+        )
+        .into());
+    }
+
+    let if_match = parse_if_match(&headers)?;
+    let stream = controller.stream_dispatcher.update_stream(stream, if_match.as_ref()).await?;
+
+    Ok(etag_response(stream.revision, stream))
+}
+
+#[utoipa::path(
+    patch,
+    path = "/streams/{stream_uid}",
+    params(IfMatchHeader),
+    request_body(
+        content = Object,
+        content_type = "application/merge-patch+json",
+        description = "JSON Merge Patch (RFC 7396) of StreamSpec",
+    ),
+    responses(
+        (status = 200, description = "Success", body = StreamSpec,
+            headers(("etag" = String, description = "Revision of stream"))),
+    )
+)]
+async fn patch_stream(
+    Extension(controller): Extension<Arc<ApiController>>, Path(stream_uid): Path<String>,
+    headers: HeaderMap, body: String,
+) -> Result<(HeaderMap, Json<StreamSpec>)> {
+    let stream_uid = Uid::parse(&stream_uid)?;
+
+    let patch = parse_merge_patch(&headers, &body)?;
+    let if_match = parse_if_match(&headers)?;
+    let stream = controller
+        .stream_dispatcher
+        .patch_stream(&stream_uid, &patch, if_match.as_ref())
+        .await?;
+
+    Ok(etag_response(stream.revision, stream))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/streams/{stream_uid}",
+    params(IfMatchHeader),
+    responses(
+        (status = 204, description = "Deleted"),
+    )
+)]
+async fn delete_stream(
+    Extension(controller): Extension<Arc<ApiController>>, Path(stream_uid): Path<String>,
+    headers: HeaderMap,
+) -> Result<StatusCode> {
+    let stream_uid = Uid::parse(&stream_uid)?;
+
+    let if_match = parse_if_match(&headers)?;
+    controller.stream_dispatcher.delete_stream(&stream_uid, if_match.as_ref()).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    post,
+    path = "/streams/{stream_uid}/renew",
+    responses(
+        (status = 200, description = "Success", body = StreamSpec),
+    )
+)]
+async fn renew_stream(
+    Extension(controller): Extension<Arc<ApiController>>, Path(stream_uid): Path<String>,
 ) -> Result<Json<StreamSpec>> {
     let stream_uid = Uid::parse(&stream_uid)?;
 
-    Ok(Json(controller.stream_dispatcher.get_stream(&stream_uid).await))
+    Ok(Json(controller.stream_dispatcher.renew_stream(&stream_uid).await?))
+}
+
+#[utoipa::path(
+    get,
+    path = "/streams/{stream_uid}/sdp",
+    responses(
+        (status = 200, description = "Success", body = String, content_type = "application/sdp"),
+    )
+)]
+async fn export_stream_sdp(
+    Extension(controller): Extension<Arc<ApiController>>, Path(stream_uid): Path<String>,
+) -> Result<Response> {
+    let stream_uid = Uid::parse(&stream_uid)?;
+
+    let sdp = controller.stream_dispatcher.export_sdp(&stream_uid).await?;
+
+    Ok(Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, SDP_CONTENT_TYPE)
+        .body(Body::from(sdp))
+        .unwrap())
+}
+
+#[utoipa::path(
+    get,
+    path = "/streams/{stream_uid}/levels",
+    responses(
+        (status = 200, description = "Success", body = [LevelsSpec]),
+    )
+)]
+async fn read_stream_levels(
+    Extension(controller): Extension<Arc<ApiController>>, Path(stream_uid): Path<String>,
+) -> Result<Json<Vec<LevelsSpec>>> {
+    let stream_uid = Uid::parse(&stream_uid)?;
+
+    Ok(Json(controller.stream_dispatcher.get_levels(&stream_uid).await?))
+}
+
+#[derive(Deserialize, utoipa::IntoParams)]
+struct MeasureLatencyParams {
+    /// Stream which source should receive marker sent by this stream.
+    /// Defaults to the same stream, e.g. when its destination is a
+    /// speaker and its source is a microphone.
+    #[param(nullable = false)]
+    detector_stream_uid: Option<String>,
+    /// How long to listen for marker, in seconds (default 2, max 10).
+    #[param(nullable = false)]
+    duration: Option<f32>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/streams/{stream_uid}/measure-latency",
+    params(MeasureLatencyParams),
+    responses(
+        (status = 200, description = "Success", body = LatencyReportSpec),
+    )
+)]
+async fn measure_stream_latency(
+    Extension(controller): Extension<Arc<ApiController>>, Path(stream_uid): Path<String>,
+    Query(params): Query<MeasureLatencyParams>,
+) -> Result<Json<LatencyReportSpec>> {
+    let stream_uid = Uid::parse(&stream_uid)?;
+    let detector_uid = match &params.detector_stream_uid {
+        Some(detector_uid) => Uid::parse(detector_uid)?,
+        None => stream_uid,
+    };
+
+    let duration = params.duration.unwrap_or(2.0);
+    if !(0.5..=10.0).contains(&duration) {
+        return Err(ValidationError::LayoutError(
+            "duration must be in range [0.5; 10]".into(),
+        )
+        .into());
+    }
+
+    Ok(Json(
+        controller
+            .stream_dispatcher
+            .measure_latency(&stream_uid, &detector_uid, Duration::from_secs_f32(duration))
+            .await?,
+    ))
+}
+
+// recordings
+
+#[utoipa::path(
+    get,
+    path = "/streams/{stream_uid}/recordings",
+    responses(
+        (status = 200, description = "Success", body = [RecordingInfo]),
+    )
+)]
+async fn list_recordings(
+    Extension(controller): Extension<Arc<ApiController>>, Path(stream_uid): Path<String>,
+) -> Result<Json<Vec<RecordingInfo>>> {
+    let stream_uid = Uid::parse(&stream_uid)?;
+
+    Ok(Json(controller.stream_dispatcher.list_recordings(&stream_uid).await?))
+}
+
+#[utoipa::path(
+    get,
+    path = "/streams/{stream_uid}/recordings/{recording_id}",
+    responses(
+        (status = 200, description = "Success", content(
+            (Vec<u8> = "audio/wav"),
+            (Vec<u8> = "audio/flac"),
+        )),
+    )
+)]
+async fn download_recording(
+    Extension(controller): Extension<Arc<ApiController>>,
+    Path((stream_uid, recording_id)): Path<(String, String)>,
+) -> Result<Response> {
+    let stream_uid = Uid::parse(&stream_uid)?;
+
+    let (recording, file) =
+        controller.stream_dispatcher.open_recording(&stream_uid, &recording_id).await?;
+
+    let mut response = Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, recording.format.content_type())
+        .header(
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{}\"", recording.recording_id),
+        );
+    // size of file being written may change while it's sent
+    if !recording.in_progress {
+        response = response.header(header::CONTENT_LENGTH, recording.size);
+    }
+
+    Ok(response.body(Body::from_stream(ReaderStream::new(file))).unwrap())
+}
+
+#[utoipa::path(
+    delete,
+    path = "/streams/{stream_uid}/recordings/{recording_id}",
+    responses(
+        (status = 204, description = "Deleted"),
+    )
+)]
+async fn delete_recording(
+    Extension(controller): Extension<Arc<ApiController>>,
+    Path((stream_uid, recording_id)): Path<(String, String)>,
+) -> Result<StatusCode> {
+    let stream_uid = Uid::parse(&stream_uid)?;
+
+    controller.stream_dispatcher.delete_recording(&stream_uid, &recording_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// groups
+
+#[utoipa::path(
+    get,
+    path = "/groups",
+    responses(
+        (status = 200, description = "Success", body = [GroupSpec]),
+    )
+)]
+async fn list_groups(
+    Extension(controller): Extension<Arc<ApiController>>,
+) -> Result<Json<Vec<GroupSpec>>> {
+    Ok(Json(controller.stream_dispatcher.get_all_groups().await?))
+}
+
+#[utoipa::path(
+    get,
+    path = "/groups/{group_uid}",
+    responses(
+        (status = 200, description = "Success", body = GroupSpec),
+    )
+)]
+async fn read_group(
+    Extension(controller): Extension<Arc<ApiController>>, Path(group_uid): Path<String>,
+) -> Result<Json<GroupSpec>> {
+    let group_uid = Uid::parse(&group_uid)?;
+
+    Ok(Json(controller.stream_dispatcher.get_group(&group_uid).await?))
+}
+
+#[utoipa::path(
+    put,
+    path = "/groups/{group_uid}",
+    request_body = GroupSpec,
+    responses(
+        (status = 200, description = "Success", body = GroupSpec),
+    )
+)]
+async fn update_group(
+    Extension(controller): Extension<Arc<ApiController>>, Path(group_uid): Path<String>,
+    Json(group): Json<GroupSpec>,
+) -> Result<Json<GroupSpec>> {
+    let group_uid = Uid::parse(&group_uid)?;
+
+    if group.group_uid != group_uid {
+        return Err(ValidationError::LayoutError(
+            "UID mismatch in request path and group_uid field".into(),
+        )
+        .into());
+    }
+
+    Ok(Json(controller.stream_dispatcher.update_group(group).await?))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/groups/{group_uid}",
+    responses(
+        (status = 204, description = "Deleted"),
+    )
+)]
+async fn delete_group(
+    Extension(controller): Extension<Arc<ApiController>>, Path(group_uid): Path<String>,
+) -> Result<StatusCode> {
+    let group_uid = Uid::parse(&group_uid)?;
+
+    controller.stream_dispatcher.delete_group(&group_uid).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    get,
+    path = "/groups/{group_uid}/status",
+    responses(
+        (status = 200, description = "Success", body = GroupStatusSpec),
+    )
+)]
+async fn read_group_status(
+    Extension(controller): Extension<Arc<ApiController>>, Path(group_uid): Path<String>,
+) -> Result<Json<GroupStatusSpec>> {
+    let group_uid = Uid::parse(&group_uid)?;
+
+    Ok(Json(controller.stream_dispatcher.get_group_status(&group_uid).await?))
+}
+
+// events
+
+#[derive(Deserialize, utoipa::IntoParams)]
+struct EventParams {
+    /// If true, levels_updated events are delivered too.
+    /// Level metering is enabled while there are such subscribers.
+    #[serde(default)]
+    levels: bool,
+}
+
+#[utoipa::path(
+    get,
+    path = "/events",
+    params(EventParams),
+    responses(
+        (status = 200, description = "Server-sent events, one per EventSpec",
+            body = EventSpec, content_type = "text/event-stream"),
+    )
+)]
+async fn subscribe_events(
+    Extension(controller): Extension<Arc<ApiController>>, Query(params): Query<EventParams>,
+) -> Sse<impl Stream<Item = result::Result<Event, Infallible>>> {
+    // watcher is owned by stream and dropped when client disconnects
+    let level_watcher = params.levels.then(|| controller.events.watch_levels());
+
+    let events = BroadcastStream::new(controller.events.subscribe()).filter_map(
+        move |event| match event {
+            Ok(event)
+                if event.event_type == EventType::LevelsUpdated && level_watcher.is_none() =>
+            {
+                None
+            },
+            Ok(event) => Some(Ok(Event::default()
+                .event(event.event_type.to_string())
+                .data(serde_json::to_string(&event).unwrap()))),
+            Err(BroadcastStreamRecvError::Lagged(count)) => {
+                tracing::warn!("event subscriber is too slow, dropped {count} events");
+                None
+            },
+        },
+    );
+
+    Sse::new(events).keep_alive(KeepAlive::default())
+}
+
+// system
+
+#[utoipa::path(
+    get,
+    path = "/health",
+    responses(
+        (status = 200, description = "Server is alive", body = HealthSpec),
+        (status = 503, description = "Server is broken and should be restarted",
+            body = HealthSpec),
+    )
+)]
+async fn read_health(
+    Extension(controller): Extension<Arc<ApiController>>,
+) -> (StatusCode, Json<HealthSpec>) {
+    let health = HealthSpec { alive: controller.stream_dispatcher.is_running() };
+
+    let status_code =
+        if health.alive { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE };
+
+    (status_code, Json(health))
+}
+
+#[utoipa::path(
+    get,
+    path = "/ready",
+    responses(
+        (status = 200, description = "Server is ready", body = ReadinessSpec),
+        (status = 503, description = "Server is starting", body = ReadinessSpec),
+    )
+)]
+async fn read_readiness(
+    Extension(controller): Extension<Arc<ApiController>>,
+) -> (StatusCode, Json<ReadinessSpec>) {
+    let driver_up = controller.endpoint_dispatcher.is_driver_up();
+    let vault_loaded = controller.stream_dispatcher.is_vault_loaded();
+    let streams_reconciled = controller.stream_dispatcher.is_reconciled();
+
+    let readiness = ReadinessSpec {
+        ready: driver_up && vault_loaded && streams_reconciled,
+        driver_up,
+        vault_loaded,
+        streams_reconciled,
+    };
+
+    let status_code =
+        if readiness.ready { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE };
+
+    (status_code, Json(readiness))
+}
+
+/// Cargo features enabled at build time.
+const ENABLED_FEATURES: &[&str] = &[
+    #[cfg(feature = "driver-tests")]
+    "driver-tests",
+    #[cfg(feature = "pipewire")]
+    "pipewire",
+    #[cfg(feature = "roc")]
+    "roc",
+];
+
+#[utoipa::path(
+    get,
+    path = "/version",
+    responses(
+        (status = 200, description = "Success", body = VersionSpec),
+    )
+)]
+async fn read_version(
+    Extension(controller): Extension<Arc<ApiController>>,
+) -> Json<VersionSpec> {
+    Json(VersionSpec {
+        version: env!("CARGO_PKG_VERSION").into(),
+        features: ENABLED_FEATURES.iter().map(|feature| feature.to_string()).collect(),
+        supported_drivers: DriverRegistry::new().supported_drivers(),
+        active_driver: controller.endpoint_dispatcher.driver_id(),
+    })
+}
+
+// helpers
+
+const SDP_CONTENT_TYPE: &str = "application/sdp";
+const MERGE_PATCH_CONTENT_TYPE: &str = "application/merge-patch+json";
+
+/// Documents If-Match header of requests that modify or delete entity.
+#[derive(utoipa::IntoParams)]
+#[into_params(parameter_in = Header)]
+#[allow(dead_code)]
+struct IfMatchHeader {
+    /// ETag of entity, as returned by GET; if entity was modified since
+    /// then, request fails with 412.
+    #[param(rename = "if-match", nullable = false)]
+    if_match: Option<String>,
+}
+
+/// Build response with entity revision in ETag header.
+fn etag_response<T>(revision: u64, body: T) -> (HeaderMap, Json<T>) {
+    let mut headers = HeaderMap::new();
+
+    headers.insert(header::ETAG, format!("\"{revision}\"").parse().unwrap());
+
+    (headers, Json(body))
+}
+
+/// Parse If-Match header, if present.
+/// ETags are quoted revisions; weak ETags never match, since
+/// If-Match requires strong comparison.
+fn parse_if_match(headers: &HeaderMap) -> Result<Option<RevisionMatch>> {
+    let Some(value) = headers.get(header::IF_MATCH) else {
+        return Ok(None);
+    };
+
+    let invalid = || ValidationError::LayoutError("invalid If-Match header".into());
+
+    let value = value.to_str().map_err(|_| invalid())?.trim();
+    if value == "*" {
+        return Ok(Some(RevisionMatch::Any));
+    }
+
+    let mut revisions = Vec::new();
+    for etag in value.split(',').map(str::trim) {
+        if etag.starts_with("W/") {
+            continue;
+        }
+        let revision = etag
+            .strip_prefix('"')
+            .and_then(|etag| etag.strip_suffix('"'))
+            .and_then(|etag| etag.parse().ok())
+            .ok_or_else(invalid)?;
+        revisions.push(revision);
+    }
+
+    Ok(Some(RevisionMatch::OneOf(revisions)))
+}
+
+/// Parse body of PATCH request.
+fn parse_merge_patch(headers: &HeaderMap, body: &str) -> Result<serde_json::Value> {
+    check_content_type(headers, MERGE_PATCH_CONTENT_TYPE)?;
+
+    Ok(serde_json::from_str(body)
+        .map_err(|err| ValidationError::LayoutError(format!("invalid merge patch: {err}")))?)
+}
+
+/// Ensure that request has expected Content-Type (parameters like charset are ignored).
+fn check_content_type(headers: &HeaderMap, expected_type: &str) -> Result<()> {
+    let content_type = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+
+    let mime_type = content_type.split(';').next().unwrap_or_default().trim();
+
+    if !mime_type.eq_ignore_ascii_case(expected_type) {
+        return Err(HandlerError::ContentTypeError(content_type.into()));
+    }
+
+    Ok(())
 }
@@ -0,0 +1,121 @@
+// Copyright (c) Roc Streaming authors
+// Licensed under MPL-2.0
+use axum::Router;
+use axum::extract::{Request, State};
+use axum::http::{HeaderValue, header};
+use axum::middleware::{self, Next};
+use axum::response::Response;
+
+/// Version of REST API.
+///
+/// Each version is mounted under its own prefix, e.g. /api/v1.
+/// Breaking changes of routes or DTOs go to a new version, so that
+/// clients of previous versions keep working.
+///
+/// Deprecation policy:
+///  - deprecated versions are still served, but their responses have
+///    "Deprecation" header; a version is removed not earlier than
+///    one release after it was deprecated
+///  - unprefixed paths (e.g. /streams) are deprecated aliases of v1,
+///    kept for clients that predate versioning; their responses also
+///    have "Link" header pointing to the prefixed path
+#[derive(
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    Debug,
+    strum::Display,
+    strum::EnumIter,
+    strum::EnumString,
+    clap::ValueEnum,
+)]
+#[strum(serialize_all = "lowercase")]
+pub enum ApiVersion {
+    V1,
+}
+
+impl ApiVersion {
+    /// Version used when not specified explicitly.
+    pub const LATEST: ApiVersion = ApiVersion::V1;
+
+    /// Version served at unprefixed paths.
+    pub const UNPREFIXED: ApiVersion = ApiVersion::V1;
+
+    /// Path prefix of version routes, e.g. "/api/v1".
+    pub fn prefix(&self) -> String {
+        format!("/api/{self}")
+    }
+
+    /// Whether version is deprecated and will be removed.
+    pub fn is_deprecated(&self) -> bool {
+        match self {
+            ApiVersion::V1 => false,
+        }
+    }
+
+    /// Remove version prefix from request path, if any.
+    /// E.g. "/api/v1/streams" becomes "/streams".
+    pub fn strip_prefix(path: &str) -> &str {
+        let Some(rest) = path.strip_prefix("/api/") else {
+            return path;
+        };
+        let (version, rest) = rest.split_at(rest.find('/').unwrap_or(rest.len()));
+
+        match version.parse::<ApiVersion>() {
+            Ok(_) if rest.is_empty() => "/",
+            Ok(_) => rest,
+            Err(_) => path,
+        }
+    }
+}
+
+/// Wrap router into layer that marks responses as deprecated.
+/// If successor is set, it's the version which paths should be used
+/// instead of paths of this router, i.e. router is unprefixed alias.
+pub(crate) fn with_deprecation_layer(router: Router, successor: Option<ApiVersion>) -> Router {
+    router.layer(middleware::from_fn_with_state(successor, add_deprecation))
+}
+
+async fn add_deprecation(
+    State(successor): State<Option<ApiVersion>>, request: Request, next: Next,
+) -> Response {
+    let successor_link = successor.map(|version| {
+        format!("<{}{}>; rel=\"successor-version\"", version.prefix(), request.uri().path())
+    });
+
+    let mut response = next.run(request).await;
+
+    let headers = response.headers_mut();
+    headers.insert("deprecation", HeaderValue::from_static("true"));
+    if let Some(link) = successor_link.and_then(|link| HeaderValue::from_str(&link).ok()) {
+        headers.insert(header::LINK, link);
+    }
+
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prefix() {
+        assert_eq!(ApiVersion::V1.prefix(), "/api/v1");
+    }
+
+    #[test]
+    fn test_strip_prefix() {
+        for (path, stripped) in [
+            ("/api/v1/streams", "/streams"),
+            ("/api/v1/streams/777777-888888-999999", "/streams/777777-888888-999999"),
+            ("/api/v1", "/"),
+            ("/streams", "/streams"),
+            ("/api/v0/streams", "/api/v0/streams"),
+            ("/api/streams", "/api/streams"),
+            ("/openapi/v1", "/openapi/v1"),
+        ] {
+            assert_eq!(ApiVersion::strip_prefix(path), stripped, "{path}");
+        }
+    }
+}
@@ -0,0 +1,111 @@
+// Copyright (c) Roc Streaming authors
+// Licensed under MPL-2.0
+use crate::auth::{AuthError, TokenStore};
+use crate::dto::TokenScope;
+use crate::rest_api::api_version::ApiVersion;
+use crate::rest_api::error::HandlerError;
+
+use axum::Router;
+use axum::extract::{Request, State};
+use axum::http::{HeaderMap, HeaderValue, Method, StatusCode, header};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use std::sync::Arc;
+
+/// Authentication settings of server.
+#[derive(Clone, Debug)]
+pub struct AuthConfig {
+    /// Tokens accepted by server.
+    pub tokens: Arc<TokenStore>,
+
+    /// Allow reading OpenAPI docs without token.
+    pub public_docs: bool,
+
+    /// Allow health and readiness checks without token.
+    pub public_health: bool,
+}
+
+impl AuthConfig {
+    /// Get scope required for request, or none if request is public.
+    fn required_scope(&self, method: &Method, path: &str) -> Option<TokenScope> {
+        // all API versions and unprefixed aliases share the same rules
+        let path = ApiVersion::strip_prefix(path);
+
+        if self.public_docs && (path == "/openapi" || path.starts_with("/openapi/")) {
+            return None;
+        }
+        if self.public_health && (path == "/health" || path == "/ready") {
+            return None;
+        }
+        // UI page has no data, it sends API requests with token itself
+        if matches!(*method, Method::GET | Method::HEAD) && (path == "/" || path == "/ui") {
+            return None;
+        }
+
+        if matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS) {
+            return Some(TokenScope::ReadOnly);
+        }
+
+        // streams, their recordings, and groups
+        if ["/streams", "/groups"]
+            .iter()
+            .any(|prefix| path == *prefix || path.starts_with(&format!("{prefix}/")))
+        {
+            return Some(TokenScope::Control);
+        }
+
+        // peers and endpoints
+        Some(TokenScope::Admin)
+    }
+}
+
+/// Wrap router into layer that rejects requests without bearer token
+/// of sufficient scope.
+pub(crate) fn with_auth_layer(router: Router, config: &AuthConfig) -> Router {
+    router.layer(middleware::from_fn_with_state(Arc::new(config.clone()), authenticate))
+}
+
+async fn authenticate(
+    State(config): State<Arc<AuthConfig>>, request: Request, next: Next,
+) -> Response {
+    let Some(required_scope) = config.required_scope(request.method(), request.uri().path())
+    else {
+        return next.run(request).await;
+    };
+
+    if let Err(err) = check_token(&config, request.headers(), required_scope).await {
+        tracing::debug!("rejecting {} {}: {err}", request.method(), request.uri().path());
+
+        let mut response = HandlerError::from(err).into_response();
+        if response.status() == StatusCode::UNAUTHORIZED {
+            response
+                .headers_mut()
+                .insert(header::WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"));
+        }
+        return response;
+    }
+
+    next.run(request).await
+}
+
+async fn check_token(
+    config: &AuthConfig, headers: &HeaderMap, required_scope: TokenScope,
+) -> Result<(), AuthError> {
+    let header = headers.get(header::AUTHORIZATION).ok_or(AuthError::MissingTokenError)?;
+
+    let bearer = header
+        .to_str()
+        .ok()
+        .and_then(|value| value.split_once(' '))
+        .filter(|(scheme, _)| scheme.eq_ignore_ascii_case("bearer"))
+        .map(|(_, bearer)| bearer.trim())
+        .ok_or(AuthError::InvalidTokenError)?;
+
+    let token = config.tokens.authenticate(bearer).await?;
+
+    if token.scope < required_scope {
+        return Err(AuthError::ScopeError(token.scope, required_scope));
+    }
+
+    Ok(())
+}
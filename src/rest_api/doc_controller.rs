@@ -1,8 +1,10 @@
 // Copyright (c) Roc Streaming authors
 // Licensed under MPL-2.0
+use crate::rest_api::api_version::ApiVersion;
+
 use axum::Router;
 use axum::body::Body;
-use axum::extract::State;
+use axum::extract::{Path, State};
 use axum::http::StatusCode;
 use axum::response::Response;
 use axum::routing::get;
@@ -10,36 +12,77 @@ use std::sync::Arc;
 use utoipa::openapi::OpenApi;
 use utoipa_redoc::{Redoc, Servable};
 
+/// Serves OpenAPI spec and Redoc page of each API version at
+/// /openapi/{version}, and of latest version at /openapi.
 pub struct DocController {
-    spec: OpenApi,
+    specs: Vec<(ApiVersion, OpenApi)>,
 }
 
 impl DocController {
-    pub fn new(spec: OpenApi) -> Self {
-        DocController { spec }
+    pub fn new(specs: Vec<(ApiVersion, OpenApi)>) -> Self {
+        DocController { specs }
     }
 
     pub fn router(self: &Arc<Self>) -> Router {
-        Router::new()
-            .route("/openapi/openapi.json", get(openapi_json))
-            .route("/openapi/openapi.yaml", get(openapi_yaml))
-            .merge(Redoc::with_url("/openapi", self.spec.clone()))
-            .with_state(Arc::clone(self))
+        let mut router = Router::new()
+            .route("/openapi/openapi.json", get(latest_openapi_json))
+            .route("/openapi/openapi.yaml", get(latest_openapi_yaml))
+            .route("/openapi/{version}/openapi.json", get(openapi_json))
+            .route("/openapi/{version}/openapi.yaml", get(openapi_yaml));
+
+        for (version, spec) in &self.specs {
+            router =
+                router.merge(Redoc::with_url(format!("/openapi/{version}"), spec.clone()));
+
+            if *version == ApiVersion::LATEST {
+                router = router.merge(Redoc::with_url("/openapi", spec.clone()));
+            }
+        }
+
+        router.with_state(Arc::clone(self))
     }
+
+    fn find_spec(&self, version: &str) -> Option<&OpenApi> {
+        self.specs.iter().find(|(v, _)| v.to_string() == version).map(|(_, spec)| spec)
+    }
+}
+
+async fn latest_openapi_json(State(controller): State<Arc<DocController>>) -> Response {
+    openapi_json(State(controller), Path(ApiVersion::LATEST.to_string())).await
+}
+
+async fn latest_openapi_yaml(State(controller): State<Arc<DocController>>) -> Response {
+    openapi_yaml(State(controller), Path(ApiVersion::LATEST.to_string())).await
 }
 
-async fn openapi_json(State(controller): State<Arc<DocController>>) -> Response {
+async fn openapi_json(
+    State(controller): State<Arc<DocController>>, Path(version): Path<String>,
+) -> Response {
+    let Some(spec) = controller.find_spec(&version) else {
+        return not_found();
+    };
+
     Response::builder()
         .status(StatusCode::OK)
         .header("Content-Type", "application/json")
-        .body(Body::from(controller.spec.to_json().unwrap()))
+        .body(Body::from(spec.to_json().unwrap()))
         .unwrap()
 }
 
-async fn openapi_yaml(State(controller): State<Arc<DocController>>) -> Response {
+async fn openapi_yaml(
+    State(controller): State<Arc<DocController>>, Path(version): Path<String>,
+) -> Response {
+    let Some(spec) = controller.find_spec(&version) else {
+        return not_found();
+    };
+
     Response::builder()
         .status(StatusCode::OK)
         .header("Content-Type", "application/yaml")
-        .body(Body::from(controller.spec.to_yaml().unwrap()))
+        .body(Body::from(spec.to_yaml().unwrap()))
         .unwrap()
 }
+
+fn not_found() -> Response {
+    Response::builder().status(StatusCode::NOT_FOUND).body(Body::empty()).unwrap()
+}
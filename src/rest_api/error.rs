@@ -1,6 +1,11 @@
 // Copyright (c) Roc Streaming authors
 // Licensed under MPL-2.0
+use crate::auth::AuthError;
 use crate::dto::*;
+use crate::io_endpoints::EndpointError;
+use crate::io_streams::StreamError;
+use crate::p2p::PeerError;
+use crate::recordings::RecordingError;
 
 use axum::extract::Json;
 use axum::http::StatusCode;
@@ -17,6 +22,9 @@ pub enum ServerError {
     #[error("bind failed: {0}")]
     BindError(#[source] io::Error),
 
+    #[error("tls error: {0}")]
+    TlsError(String),
+
     #[error("serve failed: {0}")]
     ServeError(#[source] io::Error),
 
@@ -30,6 +38,21 @@ pub enum ServerError {
 pub enum HandlerError {
     #[error("{0}")]
     ValidationError(#[from] ValidationError),
+
+    #[error("{0}")]
+    PeerError(#[from] PeerError),
+
+    #[error("{0}")]
+    EndpointError(#[from] EndpointError),
+
+    #[error("{0}")]
+    StreamError(#[from] StreamError),
+
+    #[error("unsupported content type '{0}'")]
+    ContentTypeError(String),
+
+    #[error("{0}")]
+    AuthError(#[from] AuthError),
 }
 
 impl IntoResponse for HandlerError {
@@ -38,6 +61,96 @@ impl IntoResponse for HandlerError {
 
         let (status_code, error_code) = match &self {
             Self::ValidationError(_) => (StatusCode::BAD_REQUEST, ErrorCode::InvalidArgs),
+            Self::ContentTypeError(_) => {
+                (StatusCode::UNSUPPORTED_MEDIA_TYPE, ErrorCode::InvalidArgs)
+            },
+            Self::AuthError(err) => match err {
+                AuthError::MissingTokenError | AuthError::InvalidTokenError => {
+                    (StatusCode::UNAUTHORIZED, ErrorCode::Unauthorized)
+                },
+                AuthError::ScopeError(..) => (StatusCode::FORBIDDEN, ErrorCode::Forbidden),
+                AuthError::NotFoundError(_) => (StatusCode::NOT_FOUND, ErrorCode::NotFound),
+                AuthError::ValidationError(_) => {
+                    (StatusCode::BAD_REQUEST, ErrorCode::InvalidArgs)
+                },
+                AuthError::VaultError(_) => {
+                    (StatusCode::INTERNAL_SERVER_ERROR, ErrorCode::InternalError)
+                },
+            },
+            Self::PeerError(err) => match err {
+                PeerError::NotFoundError(_) => (StatusCode::NOT_FOUND, ErrorCode::NotFound),
+                PeerError::RevisionError(_) => {
+                    (StatusCode::PRECONDITION_FAILED, ErrorCode::PreconditionFailed)
+                },
+                PeerError::ValidationError(_) => {
+                    (StatusCode::BAD_REQUEST, ErrorCode::InvalidArgs)
+                },
+                PeerError::VaultError(_) => {
+                    (StatusCode::INTERNAL_SERVER_ERROR, ErrorCode::InternalError)
+                },
+            },
+            Self::EndpointError(err) => match err {
+                EndpointError::NotFoundError(_) => {
+                    (StatusCode::NOT_FOUND, ErrorCode::NotFound)
+                },
+                EndpointError::ReadOnlyError(_) => (StatusCode::CONFLICT, ErrorCode::Conflict),
+                EndpointError::RevisionError(_) => {
+                    (StatusCode::PRECONDITION_FAILED, ErrorCode::PreconditionFailed)
+                },
+                EndpointError::ValidationError(_) | EndpointError::MediaError(_) => {
+                    (StatusCode::BAD_REQUEST, ErrorCode::InvalidArgs)
+                },
+                EndpointError::SignalError(..)
+                | EndpointError::DriverError(_)
+                | EndpointError::VaultError(_) => {
+                    (StatusCode::INTERNAL_SERVER_ERROR, ErrorCode::InternalError)
+                },
+            },
+            Self::StreamError(err) => match err {
+                StreamError::NotFoundError(_) => (StatusCode::NOT_FOUND, ErrorCode::NotFound),
+                StreamError::ExistsError(_) => {
+                    (StatusCode::CONFLICT, ErrorCode::AlreadyExists)
+                },
+                StreamError::RevisionError(_) => {
+                    (StatusCode::PRECONDITION_FAILED, ErrorCode::PreconditionFailed)
+                },
+                StreamError::GroupNotFoundError(_) => {
+                    (StatusCode::NOT_FOUND, ErrorCode::NotFound)
+                },
+                StreamError::GroupMemberError(_) => {
+                    (StatusCode::BAD_REQUEST, ErrorCode::InvalidArgs)
+                },
+                StreamError::GroupConflictError(..) => {
+                    (StatusCode::CONFLICT, ErrorCode::Conflict)
+                },
+                StreamError::NoLeaseError(_) => (StatusCode::CONFLICT, ErrorCode::Conflict),
+                StreamError::NotRunningError(_) | StreamError::MeasurementError(_) => {
+                    (StatusCode::CONFLICT, ErrorCode::Conflict)
+                },
+                StreamError::PortConflictError(..) | StreamError::PortsExhaustedError(_) => {
+                    (StatusCode::CONFLICT, ErrorCode::Conflict)
+                },
+                StreamError::ValidationError(_) => {
+                    (StatusCode::BAD_REQUEST, ErrorCode::InvalidArgs)
+                },
+                StreamError::RecordingError(err) => match err {
+                    RecordingError::NotFoundError(_) => {
+                        (StatusCode::NOT_FOUND, ErrorCode::NotFound)
+                    },
+                    RecordingError::InProgressError(_) => {
+                        (StatusCode::CONFLICT, ErrorCode::Conflict)
+                    },
+                    RecordingError::IdFormatError(_) => {
+                        (StatusCode::BAD_REQUEST, ErrorCode::InvalidArgs)
+                    },
+                    RecordingError::FormatError(_) | RecordingError::IoError(_) => {
+                        (StatusCode::INTERNAL_SERVER_ERROR, ErrorCode::InternalError)
+                    },
+                },
+                StreamError::VaultError(_) => {
+                    (StatusCode::INTERNAL_SERVER_ERROR, ErrorCode::InternalError)
+                },
+            },
         };
 
         let response_body = ErrorSpec { error_code, error_text };
@@ -0,0 +1,81 @@
+// Copyright (c) Roc Streaming authors
+// Licensed under MPL-2.0
+use std::fmt;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+/// Address on which server accepts connections.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum ListenAddr {
+    /// TCP address.
+    Tcp(SocketAddr),
+
+    /// Path to Unix domain socket.
+    Unix(PathBuf),
+}
+
+/// ListenAddr from "HOST:PORT", "tcp:HOST:PORT", or "unix:PATH" string.
+impl FromStr for ListenAddr {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        if let Some(path) = text.strip_prefix("unix:") {
+            if path.is_empty() {
+                return Err(format!("missing socket path in '{text}'"));
+            }
+            return Ok(ListenAddr::Unix(path.into()));
+        }
+
+        let addr = text.strip_prefix("tcp:").unwrap_or(text);
+
+        SocketAddr::from_str(addr)
+            .map(ListenAddr::Tcp)
+            .map_err(|_| format!("expected HOST:PORT or unix:PATH, got '{text}'"))
+    }
+}
+
+impl fmt::Display for ListenAddr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ListenAddr::Tcp(addr) => write!(f, "{addr}"),
+            ListenAddr::Unix(path) => write!(f, "unix:{}", path.display()),
+        }
+    }
+}
+
+impl From<SocketAddr> for ListenAddr {
+    fn from(addr: SocketAddr) -> Self {
+        ListenAddr::Tcp(addr)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assertables::*;
+
+    #[test]
+    fn test_parse() {
+        for (text, addr) in [
+            ("127.0.0.1:4040", ListenAddr::Tcp("127.0.0.1:4040".parse().unwrap())),
+            ("tcp:0.0.0.0:80", ListenAddr::Tcp("0.0.0.0:80".parse().unwrap())),
+            ("[::1]:4040", ListenAddr::Tcp("[::1]:4040".parse().unwrap())),
+            ("unix:/run/rocd.sock", ListenAddr::Unix("/run/rocd.sock".into())),
+            ("unix:rocd.sock", ListenAddr::Unix("rocd.sock".into())),
+        ] {
+            assert_eq!(text.parse::<ListenAddr>().unwrap(), addr, "{text}");
+        }
+
+        for text in ["", "127.0.0.1", "localhost:4040", "tcp:", "unix:", "udp:127.0.0.1:1"] {
+            assert_err!(text.parse::<ListenAddr>(), "{text}");
+        }
+    }
+
+    #[test]
+    fn test_display() {
+        for text in ["127.0.0.1:4040", "[::1]:4040", "unix:/run/rocd.sock"] {
+            assert_eq!(text.parse::<ListenAddr>().unwrap().to_string(), text);
+        }
+    }
+}
@@ -0,0 +1,350 @@
+// Copyright (c) Roc Streaming authors
+// Licensed under MPL-2.0
+use crate::audio::LinkMetrics;
+use crate::dto::*;
+use crate::io_endpoints::EndpointDispatcher;
+use crate::io_streams::StreamDispatcher;
+use crate::vault::Vault;
+
+use axum::Router;
+use axum::body::Body;
+use axum::extract::{MatchedPath, Request, State};
+use axum::http::StatusCode;
+use axum::middleware::{self, Next};
+use axum::response::Response;
+use axum::routing::get;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use strum::IntoEnumIterator;
+
+const METRICS_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Upper bounds of buckets of request latency histogram, in seconds.
+const LATENCY_BUCKETS: &[f64] = &[0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1.0, 5.0];
+
+/// Serves metrics in Prometheus text format and collects
+/// metrics of HTTP requests.
+pub struct MetricsController {
+    endpoint_dispatcher: Arc<EndpointDispatcher>,
+    stream_dispatcher: Arc<StreamDispatcher>,
+    vault: Arc<Vault>,
+    http_metrics: Mutex<BTreeMap<RouteKey, RouteMetrics>>,
+}
+
+/// Requests are grouped by method and route template, e.g.
+/// "/streams/{stream_uid}", to keep number of series bounded.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct RouteKey {
+    method: String,
+    route: String,
+}
+
+#[derive(Default)]
+struct RouteMetrics {
+    // request count by status code
+    requests: BTreeMap<u16, u64>,
+    // non-cumulative count by bucket, last one is +Inf
+    latency_buckets: [u64; LATENCY_BUCKETS.len() + 1],
+    latency_sum: Duration,
+}
+
+impl MetricsController {
+    pub fn new(
+        endpoint_dispatcher: &Arc<EndpointDispatcher>,
+        stream_dispatcher: &Arc<StreamDispatcher>, vault: &Arc<Vault>,
+    ) -> Self {
+        MetricsController {
+            endpoint_dispatcher: Arc::clone(endpoint_dispatcher),
+            stream_dispatcher: Arc::clone(stream_dispatcher),
+            vault: Arc::clone(vault),
+            http_metrics: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    pub fn router(self: &Arc<Self>) -> Router {
+        Router::new().route("/metrics", get(read_metrics)).with_state(Arc::clone(self))
+    }
+
+    /// Wrap router into layer that counts requests and measures
+    /// their latency. Should be applied after all routes are merged.
+    pub fn with_tracking_layer(self: &Arc<Self>, router: Router) -> Router {
+        router.layer(middleware::from_fn_with_state(Arc::clone(self), track_request))
+    }
+
+    fn record_request(&self, key: RouteKey, status: StatusCode, latency: Duration) {
+        let mut http_metrics = self.http_metrics.lock().unwrap();
+        let route_metrics = http_metrics.entry(key).or_default();
+
+        *route_metrics.requests.entry(status.as_u16()).or_default() += 1;
+
+        let bucket = LATENCY_BUCKETS
+            .iter()
+            .position(|bound| latency.as_secs_f64() <= *bound)
+            .unwrap_or(LATENCY_BUCKETS.len());
+        route_metrics.latency_buckets[bucket] += 1;
+        route_metrics.latency_sum += latency;
+    }
+
+    async fn render(&self) -> String {
+        let mut writer = MetricsWriter::default();
+
+        self.render_driver(&mut writer);
+        self.render_vault(&mut writer).await;
+        self.render_http(&mut writer);
+        self.render_streams(&mut writer).await;
+
+        writer.text
+    }
+
+    fn render_driver(&self, writer: &mut MetricsWriter) {
+        let driver_id = self.endpoint_dispatcher.driver_id().to_string();
+
+        writer.family("rocd_driver_up", "gauge", "Whether audio driver is operational.");
+        writer.sample(
+            "rocd_driver_up",
+            &[("driver", &driver_id)],
+            self.endpoint_dispatcher.is_driver_up() as u8,
+        );
+
+        writer.family("rocd_ready", "gauge", "Whether server is ready to handle requests.");
+        writer.sample(
+            "rocd_ready",
+            &[],
+            (self.endpoint_dispatcher.is_driver_up()
+                && self.stream_dispatcher.is_vault_loaded()
+                && self.stream_dispatcher.is_reconciled()) as u8,
+        );
+    }
+
+    async fn render_vault(&self, writer: &mut MetricsWriter) {
+        let metrics = self.vault.metrics().await;
+
+        writer.family("rocd_vault_cache_size", "gauge", "Entries cached in memory.");
+        writer.sample("rocd_vault_cache_size", &[], metrics.cache_size);
+
+        for (name, help, value) in [
+            ("rocd_vault_cache_hits_total", "Vault cache hits.", metrics.cache_hits),
+            ("rocd_vault_cache_misses_total", "Vault cache misses.", metrics.cache_misses),
+            (
+                "rocd_vault_cache_drops_total",
+                "Vault cache drops caused by conflicts.",
+                metrics.cache_drops,
+            ),
+            ("rocd_vault_cache_cows_total", "Vault cache copy-on-writes.", metrics.cache_cows),
+            ("rocd_db_reads_total", "DB read transactions.", metrics.db_reads),
+            ("rocd_db_writes_total", "DB write transactions.", metrics.db_writes),
+        ] {
+            writer.family(name, "counter", help);
+            writer.sample(name, &[], value);
+        }
+    }
+
+    fn render_http(&self, writer: &mut MetricsWriter) {
+        let http_metrics = self.http_metrics.lock().unwrap();
+
+        writer.family("rocd_http_requests_total", "counter", "HTTP requests by route.");
+        for (key, route_metrics) in http_metrics.iter() {
+            for (status, count) in &route_metrics.requests {
+                writer.sample(
+                    "rocd_http_requests_total",
+                    &[
+                        ("method", &key.method),
+                        ("route", &key.route),
+                        ("status", &status.to_string()),
+                    ],
+                    count,
+                );
+            }
+        }
+
+        writer.family(
+            "rocd_http_request_duration_seconds",
+            "histogram",
+            "HTTP request latency by route.",
+        );
+        for (key, route_metrics) in http_metrics.iter() {
+            let labels = [("method", key.method.as_str()), ("route", key.route.as_str())];
+
+            let mut count = 0;
+            for (n, bucket_count) in route_metrics.latency_buckets.iter().enumerate() {
+                count += bucket_count;
+                let bound = match LATENCY_BUCKETS.get(n) {
+                    Some(bound) => bound.to_string(),
+                    None => "+Inf".into(),
+                };
+                writer.sample(
+                    "rocd_http_request_duration_seconds_bucket",
+                    &[labels[0], labels[1], ("le", &bound)],
+                    count,
+                );
+            }
+            writer.sample(
+                "rocd_http_request_duration_seconds_sum",
+                &labels,
+                route_metrics.latency_sum.as_secs_f64(),
+            );
+            writer.sample("rocd_http_request_duration_seconds_count", &labels, count);
+        }
+    }
+
+    async fn render_streams(&self, writer: &mut MetricsWriter) {
+        let streams = match self.stream_dispatcher.get_all().await {
+            Ok(streams) => streams,
+            Err(err) => {
+                tracing::warn!("can't read streams for metrics: {err}");
+                Vec::new()
+            },
+        };
+
+        writer.family("rocd_streams", "gauge", "Streams by state.");
+        for state in StreamState::iter() {
+            let count = streams.iter().filter(|stream| stream.state == state).count();
+            writer.sample("rocd_streams", &[("state", &state.to_string())], count);
+        }
+
+        // collect status of running pipelines
+        let mut links: Vec<(String, &str, LinkMetrics)> = Vec::new();
+        let mut frames: Vec<(String, u64)> = Vec::new();
+
+        for stream in &streams {
+            let Ok(Some(status)) = self.stream_dispatcher.get_status(&stream.stream_uid).await
+            else {
+                continue;
+            };
+            let stream_uid = stream.stream_uid.to_string();
+
+            frames.push((stream_uid.clone(), status.frame_count));
+
+            if let Some(metrics) = status.source_metrics {
+                links.push((stream_uid.clone(), "source", metrics));
+            }
+            if let Some(metrics) = status.sink_metrics {
+                links.push((stream_uid, "destination", metrics));
+            }
+        }
+
+        writer.family("rocd_stream_frames_total", "counter", "Frames passed through stream.");
+        for (stream_uid, frame_count) in &frames {
+            writer.sample(
+                "rocd_stream_frames_total",
+                &[("stream_uid", stream_uid)],
+                frame_count,
+            );
+        }
+
+        type LinkField = fn(&LinkMetrics) -> f64;
+
+        for (name, kind, help, field) in [
+            (
+                "rocd_stream_connections",
+                "gauge",
+                "Remote peers connected to stream transport.",
+                (|m| m.connection_count as f64) as LinkField,
+            ),
+            (
+                "rocd_stream_e2e_latency_seconds",
+                "gauge",
+                "Estimated end-to-end latency of stream transport.",
+                |m| m.e2e_latency.as_secs_f64(),
+            ),
+            (
+                "rocd_stream_target_latency_seconds",
+                "gauge",
+                "Latency which stream receiver tries to maintain.",
+                |m| m.target_latency.as_secs_f64(),
+            ),
+            (
+                "rocd_stream_jitter_seconds",
+                "gauge",
+                "Estimated mean packet jitter of stream transport.",
+                |m| m.mean_jitter.as_secs_f64(),
+            ),
+            (
+                "rocd_stream_expected_packets_total",
+                "counter",
+                "Packets expected to be received by stream transport.",
+                |m| m.expected_packets as f64,
+            ),
+            (
+                "rocd_stream_lost_packets_total",
+                "counter",
+                "Packets not received in time by stream transport.",
+                |m| m.lost_packets as f64,
+            ),
+        ] {
+            writer.family(name, kind, help);
+            for (stream_uid, side, metrics) in &links {
+                writer.sample(
+                    name,
+                    &[("stream_uid", stream_uid), ("side", side)],
+                    field(metrics),
+                );
+            }
+        }
+    }
+}
+
+async fn read_metrics(State(controller): State<Arc<MetricsController>>) -> Response {
+    Response::builder()
+        .status(StatusCode::OK)
+        .header("Content-Type", METRICS_CONTENT_TYPE)
+        .body(Body::from(controller.render().await))
+        .unwrap()
+}
+
+async fn track_request(
+    State(controller): State<Arc<MetricsController>>, request: Request, next: Next,
+) -> Response {
+    let key = RouteKey {
+        method: request.method().to_string(),
+        // requests that didn't match any route are grouped together
+        route: request
+            .extensions()
+            .get::<MatchedPath>()
+            .map_or("unmatched", |path| path.as_str())
+            .to_string(),
+    };
+
+    let start_time = Instant::now();
+    let response = next.run(request).await;
+
+    controller.record_request(key, response.status(), start_time.elapsed());
+
+    response
+}
+
+/// Builds Prometheus text exposition format.
+#[derive(Default)]
+struct MetricsWriter {
+    text: String,
+}
+
+impl MetricsWriter {
+    fn family(&mut self, name: &str, kind: &str, help: &str) {
+        _ = writeln!(self.text, "# HELP {name} {help}");
+        _ = writeln!(self.text, "# TYPE {name} {kind}");
+    }
+
+    fn sample(&mut self, name: &str, labels: &[(&str, &str)], value: impl ToString) {
+        self.text.push_str(name);
+
+        if !labels.is_empty() {
+            self.text.push('{');
+            for (n, (label, label_value)) in labels.iter().enumerate() {
+                if n != 0 {
+                    self.text.push(',');
+                }
+                let label_value = label_value
+                    .replace('\\', "\\\\")
+                    .replace('"', "\\\"")
+                    .replace('\n', "\\n");
+                _ = write!(self.text, "{label}=\"{label_value}\"");
+            }
+            self.text.push('}');
+        }
+
+        _ = writeln!(self.text, " {}", value.to_string());
+    }
+}
@@ -1,11 +1,25 @@
 // Copyright (c) Roc Streaming authors
 // Licensed under MPL-2.0
 mod api_controller;
+mod api_version;
+mod auth_layer;
 mod doc_controller;
 mod error;
+mod listen_addr;
+mod metrics_controller;
+mod pagination;
 mod server;
+mod tls;
+mod ui_controller;
 
 pub use self::api_controller::*;
+pub use self::api_version::*;
+pub use self::auth_layer::*;
 pub use self::doc_controller::*;
 pub use self::error::*;
+pub use self::listen_addr::*;
+pub use self::metrics_controller::*;
+pub use self::pagination::*;
 pub use self::server::*;
+pub use self::tls::*;
+pub use self::ui_controller::*;
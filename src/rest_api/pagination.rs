@@ -0,0 +1,262 @@
+// Copyright (c) Roc Streaming authors
+// Licensed under MPL-2.0
+use crate::dto::*;
+
+use serde::Deserialize;
+use std::cmp::Ordering;
+use utoipa::ToSchema;
+
+/// Response header with cursor of next page.
+pub const NEXT_CURSOR_HEADER: &str = "x-next-cursor";
+
+/// Maximum value of limit query parameter.
+pub const MAX_PAGE_SIZE: u32 = 1000;
+
+#[derive(Copy, Clone, PartialEq, Debug, Default, strum::Display, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum SortOrder {
+    #[default]
+    Asc,
+    Desc,
+}
+
+#[derive(Copy, Clone, PartialEq, Debug, Default, strum::Display, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum PeerSortField {
+    #[default]
+    PeerUid,
+}
+
+#[derive(Copy, Clone, PartialEq, Debug, Default, strum::Display, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum EndpointSortField {
+    #[default]
+    EndpointUid,
+    DisplayName,
+    SystemName,
+    EndpointType,
+    Driver,
+}
+
+#[derive(Copy, Clone, PartialEq, Debug, Default, strum::Display, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum StreamSortField {
+    #[default]
+    StreamUid,
+    State,
+    Owner,
+    LeaseExpiration,
+}
+
+/// Key by which list items are sorted: value of sort field, then UID.
+/// UID makes keys unique, so that cursor position is unambiguous.
+type SortKey = (String, String);
+
+/// Part of sorted list, and cursor pointing after its last item
+/// if there are more items.
+#[derive(Debug)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub next_cursor: Option<String>,
+}
+
+/// Sort items and return page that starts after cursor.
+/// Sort is name of sort field; cursor is valid only for the same
+/// sort and order as the page that returned it.
+/// If limit is none, all remaining items are returned.
+pub fn paginate<T>(
+    items: Vec<T>, key_fn: impl Fn(&T) -> SortKey, sort: &str, order: SortOrder,
+    cursor: Option<&str>, limit: Option<u32>,
+) -> Result<Page<T>, ValidationError> {
+    if limit.is_some_and(|limit| !(1..=MAX_PAGE_SIZE).contains(&limit)) {
+        return Err(ValidationError::LayoutError(format!(
+            "limit must be in range [1; {MAX_PAGE_SIZE}]"
+        )));
+    }
+
+    let compare = |a: &SortKey, b: &SortKey| match order {
+        SortOrder::Asc => a.cmp(b),
+        SortOrder::Desc => b.cmp(a),
+    };
+
+    let mut keyed: Vec<(SortKey, T)> =
+        items.into_iter().map(|item| (key_fn(&item), item)).collect();
+    keyed.sort_by(|a, b| compare(&a.0, &b.0));
+
+    if let Some(cursor) = cursor {
+        let (cursor_sort, cursor_order, cursor_key) = decode_cursor(cursor)?;
+        if cursor_sort != sort || cursor_order != order.to_string() {
+            return Err(ValidationError::LayoutError(format!(
+                "cursor was returned for sort={cursor_sort} and order={cursor_order}"
+            )));
+        }
+        keyed.retain(|(key, _)| compare(key, &cursor_key) == Ordering::Greater);
+    }
+
+    let limit = limit.map_or(keyed.len(), |limit| limit as usize);
+    let next_cursor = if keyed.len() > limit {
+        Some(encode_cursor(sort, order, &keyed[limit - 1].0))
+    } else {
+        None
+    };
+
+    Ok(Page {
+        items: keyed.into_iter().take(limit).map(|(_, item)| item).collect(),
+        next_cursor,
+    })
+}
+
+/// Cursor is opaque to clients: it's hex-encoded sort field, order, and sort key.
+fn encode_cursor(sort: &str, order: SortOrder, key: &SortKey) -> String {
+    format!("{sort}\0{order}\0{}\0{}", key.0, key.1)
+        .bytes()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+fn decode_cursor(cursor: &str) -> Result<(String, String, SortKey), ValidationError> {
+    let invalid = || ValidationError::LayoutError(format!("invalid cursor '{cursor}'"));
+
+    if !cursor.len().is_multiple_of(2) || !cursor.is_ascii() {
+        return Err(invalid());
+    }
+
+    let bytes = (0..cursor.len())
+        .step_by(2)
+        .map(|pos| u8::from_str_radix(&cursor[pos..pos + 2], 16).map_err(|_| invalid()))
+        .collect::<Result<Vec<u8>, _>>()?;
+
+    let text = String::from_utf8(bytes).map_err(|_| invalid())?;
+    let [sort, order, value, uid] =
+        text.splitn(4, '\0').collect::<Vec<_>>().try_into().map_err(|_| invalid())?;
+
+    Ok((sort.into(), order.into(), (value.into(), uid.into())))
+}
+
+pub fn peer_sort_key(peer: &PeerSpec, _field: PeerSortField) -> SortKey {
+    (String::new(), peer.peer_uid.to_string())
+}
+
+pub fn endpoint_sort_key(endpoint: &EndpointSpec, field: EndpointSortField) -> SortKey {
+    let value = match field {
+        EndpointSortField::EndpointUid => String::new(),
+        EndpointSortField::DisplayName => endpoint.display_name.clone(),
+        EndpointSortField::SystemName => endpoint.system_name.clone(),
+        EndpointSortField::EndpointType => endpoint.endpoint_type.to_string(),
+        EndpointSortField::Driver => endpoint.driver.to_string(),
+    };
+
+    (value, endpoint.endpoint_uid.to_string())
+}
+
+pub fn stream_sort_key(stream: &StreamSpec, field: StreamSortField) -> SortKey {
+    let value = match field {
+        StreamSortField::StreamUid => String::new(),
+        StreamSortField::State => stream.state.to_string(),
+        StreamSortField::Owner => stream.owner.clone().unwrap_or_default(),
+        // zero-padded, so that lexicographic order is numeric order;
+        // streams without lease are ordered after streams with lease
+        StreamSortField::LeaseExpiration => match stream.lease_expiration {
+            Some(time) => format!("{:024}", time.unix_timestamp_nanos()),
+            None => "~".into(),
+        },
+    };
+
+    (value, stream.stream_uid.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assertables::*;
+
+    fn key(item: &(&str, &str)) -> SortKey {
+        (item.0.into(), item.1.into())
+    }
+
+    #[test]
+    fn test_paginate() {
+        let items = vec![("b", "1"), ("a", "3"), ("b", "0"), ("c", "2")];
+
+        let page = paginate(items.clone(), key, "key", SortOrder::Asc, None, None).unwrap();
+        assert_eq!(page.items, vec![("a", "3"), ("b", "0"), ("b", "1"), ("c", "2")]);
+        assert_none!(page.next_cursor);
+
+        let page = paginate(items.clone(), key, "key", SortOrder::Desc, None, None).unwrap();
+        assert_eq!(page.items, vec![("c", "2"), ("b", "1"), ("b", "0"), ("a", "3")]);
+
+        // walk pages
+        for order in [SortOrder::Asc, SortOrder::Desc] {
+            let all = paginate(items.clone(), key, "key", order, None, None).unwrap().items;
+
+            let mut walked = Vec::new();
+            let mut cursor = None;
+            loop {
+                let page =
+                    paginate(items.clone(), key, "key", order, cursor.as_deref(), Some(3))
+                        .unwrap();
+                assert_le!(page.items.len(), 3);
+                walked.extend(page.items);
+                cursor = page.next_cursor;
+                if cursor.is_none() {
+                    break;
+                }
+            }
+
+            assert_eq!(walked, all);
+        }
+
+        // cursor stays valid when item it points to is removed
+        let page = paginate(items.clone(), key, "key", SortOrder::Asc, None, Some(2)).unwrap();
+        let cursor = page.next_cursor.unwrap();
+        let rest = items.iter().copied().filter(|item| *item != ("b", "0")).collect();
+        let page = paginate(rest, key, "key", SortOrder::Asc, Some(&cursor), None).unwrap();
+        assert_eq!(page.items, vec![("b", "1"), ("c", "2")]);
+    }
+
+    #[test]
+    fn test_paginate_invalid() {
+        let items = vec![("a", "0")];
+
+        for limit in [0, MAX_PAGE_SIZE + 1] {
+            assert_err!(paginate(
+                items.clone(),
+                key,
+                "key",
+                SortOrder::Asc,
+                None,
+                Some(limit)
+            ));
+        }
+        for cursor in ["", "0", "zz", "6162"] {
+            assert_err!(paginate(
+                items.clone(),
+                key,
+                "key",
+                SortOrder::Asc,
+                Some(cursor),
+                None
+            ));
+        }
+
+        // cursor is bound to sort and order
+        let items = vec![("a", "0"), ("b", "1")];
+        let page = paginate(items.clone(), key, "key", SortOrder::Asc, None, Some(1)).unwrap();
+        let cursor = page.next_cursor.unwrap();
+
+        assert_ok!(paginate(items.clone(), key, "key", SortOrder::Asc, Some(&cursor), None));
+        assert_err!(paginate(
+            items.clone(),
+            key,
+            "other",
+            SortOrder::Asc,
+            Some(&cursor),
+            None
+        ));
+        assert_err!(paginate(items.clone(), key, "key", SortOrder::Desc, Some(&cursor), None));
+    }
+}
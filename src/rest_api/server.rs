@@ -1,131 +1,293 @@
 // Copyright (c) Roc Streaming authors
 // Licensed under MPL-2.0
+use crate::events::EventBus;
 use crate::io_endpoints::EndpointDispatcher;
 use crate::io_streams::StreamDispatcher;
 use crate::p2p::PeerDispatcher;
 use crate::rest_api::api_controller::ApiController;
+use crate::rest_api::auth_layer::*;
 use crate::rest_api::doc_controller::DocController;
 use crate::rest_api::error::ServerError;
+use crate::rest_api::listen_addr::ListenAddr;
+use crate::rest_api::metrics_controller::MetricsController;
+use crate::rest_api::tls::*;
+use crate::rest_api::ui_controller::UiController;
+use crate::vault::Vault;
 
 use axum::extract::Request;
 use axum::{Router, ServiceExt};
 use axum_server::Server;
+use axum_server::tls_rustls::{RustlsAcceptor, RustlsConfig};
+use derive_builder::Builder;
+use hyper_util::rt::{TokioExecutor, TokioIo};
+use hyper_util::server::conn::auto;
+use hyper_util::service::TowerToHyperService;
+use std::fs::{self, DirBuilder, Permissions};
+use std::future::Future;
 use std::io;
-use std::net::SocketAddr;
+use std::os::unix::fs::{DirBuilderExt, FileTypeExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
 use std::result;
 use std::sync::Arc;
-use tokio::net::TcpListener;
+use std::time::Duration;
+use tokio::net::{TcpListener, UnixListener, UnixStream};
 use tokio::sync::Mutex;
+use tokio_util::sync::CancellationToken;
 use tower::Layer;
-use tower_http::normalize_path::NormalizePathLayer;
+use tower_http::normalize_path::{NormalizePath, NormalizePathLayer};
 use tower_http::trace::TraceLayer;
 
 type Result<T> = result::Result<T, ServerError>;
 
 type ServerHandle = axum_server::Handle;
 type TaskHandle = tokio::task::JoinHandle<io::Result<()>>;
+type HttpService = NormalizePath<Router>;
 
-/// Runs HTTP server with REST API and OpenAPI docs.
+/// HTTP server config.
+#[derive(Builder, Default, Debug)]
+#[builder(setter(into))]
+pub struct ServerConfig {
+    /// If set, requests must have bearer token.
+    /// Otherwise, all requests are allowed.
+    #[builder(default)]
+    pub auth: Option<AuthConfig>,
+
+    /// Permissions of Unix socket files.
+    #[builder(default = 0o600)]
+    pub socket_mode: u32,
+
+    /// If set, TCP listeners use HTTPS.
+    /// Unix sockets always use plain HTTP.
+    #[builder(default)]
+    pub tls: Option<TlsConfig>,
+
+    /// If set, Prometheus metrics are served at /metrics.
+    #[builder(default = true)]
+    pub metrics: bool,
+
+    /// If set, web admin UI is served at /ui.
+    #[builder(default = true)]
+    pub ui: bool,
+}
+
+/// Runs HTTP server with REST API, OpenAPI docs, metrics, and web UI.
+///
+/// Server can listen on multiple TCP addresses and Unix sockets.
 pub struct RestServer {
+    socket_mode: u32,
+    tls: Option<TlsConfig>,
     state: Mutex<ServerState>,
 }
 
 struct ServerState {
     router: Option<Router>,
-    server_handle: Option<ServerHandle>,
-    task_handle: Option<TaskHandle>,
+    // cancelled to stop all listeners
+    stop_token: Option<CancellationToken>,
+    task_handles: Vec<TaskHandle>,
+}
+
+enum Listener {
+    Tcp(std::net::TcpListener),
+    Unix(UnixSocket),
+}
+
+/// Listener of Unix socket, which removes socket file when dropped.
+struct UnixSocket {
+    listener: UnixListener,
+    path: PathBuf,
+}
+
+impl Drop for UnixSocket {
+    fn drop(&mut self) {
+        _ = fs::remove_file(&self.path);
+    }
 }
 
 impl RestServer {
     /// Create unstarted server.
     pub fn new(
         peer_dispatcher: &Arc<PeerDispatcher>, endpoint_dispatcher: &Arc<EndpointDispatcher>,
-        stream_dispatcher: &Arc<StreamDispatcher>,
+        stream_dispatcher: &Arc<StreamDispatcher>, events: &Arc<EventBus>, vault: &Arc<Vault>,
+        config: &ServerConfig,
     ) -> Self {
         let mut router = Router::new();
-        let spec;
+        let specs;
 
         {
             let api_controller = Arc::new(ApiController::new(
                 peer_dispatcher,
                 endpoint_dispatcher,
                 stream_dispatcher,
+                events,
             ));
 
-            let (api_router, api_spec) = api_controller.router_with_spec();
+            let (api_router, api_specs) = api_controller.router_with_specs();
 
             router = router.merge(api_router);
-            spec = api_spec;
+            specs = api_specs;
         }
 
         {
-            let doc_controller = Arc::new(DocController::new(spec));
+            let doc_controller = Arc::new(DocController::new(specs));
             let doc_router = doc_controller.router();
 
             router = router.merge(doc_router);
         }
 
-        RestServer {
-            state: Mutex::new(ServerState {
-                router: Some(router),
-                server_handle: None,
-                task_handle: None,
-            }),
+        if config.ui {
+            let ui_controller = Arc::new(UiController::new());
+            let ui_router = ui_controller.router();
+
+            router = router.merge(ui_router);
         }
-    }
 
-    /// Runs http server with given router.
-    async fn serve_with_router(server: Server, router: Router) -> io::Result<()> {
-        let service = router;
+        let metrics_controller = config.metrics.then(|| {
+            Arc::new(MetricsController::new(endpoint_dispatcher, stream_dispatcher, vault))
+        });
+
+        if let Some(metrics_controller) = &metrics_controller {
+            let metrics_router = metrics_controller.router();
 
-        // add TraceLayer layer
-        let service = service.layer(TraceLayer::new_for_http());
+            router = router.merge(metrics_router);
+        }
 
-        // add NormalizePathLayer layer
-        let service = ServiceExt::<Request>::into_make_service(
-            NormalizePathLayer::trim_trailing_slash().layer(service),
-        );
+        if let Some(auth) = &config.auth {
+            router = with_auth_layer(router, auth);
+        }
 
-        server.serve(service).await
+        // applied last to also count requests rejected by auth
+        if let Some(metrics_controller) = &metrics_controller {
+            router = metrics_controller.with_tracking_layer(router);
+        }
+
+        RestServer {
+            socket_mode: config.socket_mode,
+            tls: config.tls.clone(),
+            state: Mutex::new(ServerState {
+                router: Some(router),
+                stop_token: None,
+                task_handles: Vec::new(),
+            }),
+        }
     }
 
-    /// Bind to address and run server in background.
+    /// Bind to addresses and run server in background.
     ///
     /// Must be called once after new().
     ///
     /// start().await doesn't wait for server to finish, it just waits
-    /// when bind is complete and server is started.
+    /// when bind is complete and server is started. If any bind fails,
+    /// server isn't started on any address.
     ///
     /// Call wait() to wait server to finish.
     ///
-    /// Returned address is the same as passed to argument, except that
-    /// if the port was zero, the actual port is returned.
-    pub async fn start(self: &Arc<Self>, addr: SocketAddr) -> Result<SocketAddr> {
+    /// Returned addresses are the same as passed to argument, except that
+    /// if a TCP port was zero, the actual port is returned.
+    pub async fn start(self: &Arc<Self>, addrs: &[ListenAddr]) -> Result<Vec<ListenAddr>> {
         let mut locked_state = self.state.lock().await;
 
         let router = locked_state.router.take().ok_or(ServerError::StateError)?;
 
-        let tcp_listener = TcpListener::bind(addr)
-            .await
-            .map_err(|err| ServerError::BindError(err))?
-            .into_std()
-            .map_err(|err| ServerError::BindError(err))?;
+        if addrs.is_empty() {
+            return Err(ServerError::BindError(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "no listen addresses",
+            )));
+        }
+
+        // load certificate before binding, to not start anything if it's broken
+        let tls_reloader = match &self.tls {
+            Some(tls) if addrs.iter().any(|addr| matches!(addr, ListenAddr::Tcp(_))) => {
+                Some(TlsReloader::load(tls).await?)
+            },
+            _ => None,
+        };
+        let rustls_config = tls_reloader.as_ref().map(|reloader| reloader.rustls_config());
 
-        let resolved_addr =
-            tcp_listener.local_addr().map_err(|err| ServerError::BindError(err))?;
+        let mut listeners = Vec::new();
+        let mut resolved_addrs = Vec::new();
 
-        tracing::info!("starting server at http://{}", resolved_addr);
+        for addr in addrs {
+            let (listener, resolved_addr) = match addr {
+                ListenAddr::Tcp(addr) => {
+                    let tcp_listener = TcpListener::bind(addr)
+                        .await
+                        .map_err(|err| ServerError::BindError(err))?
+                        .into_std()
+                        .map_err(|err| ServerError::BindError(err))?;
 
-        let server_handle = ServerHandle::new();
-        let server = Server::from_tcp(tcp_listener).handle(server_handle.clone());
+                    let resolved_addr = tcp_listener
+                        .local_addr()
+                        .map_err(|err| ServerError::BindError(err))?;
+
+                    (Listener::Tcp(tcp_listener), ListenAddr::Tcp(resolved_addr))
+                },
+                ListenAddr::Unix(path) => {
+                    let unix_socket = self.bind_unix(path).await?;
+
+                    (Listener::Unix(unix_socket), addr.clone())
+                },
+            };
+
+            listeners.push(listener);
+            resolved_addrs.push(resolved_addr);
+        }
+
+        // NormalizePathLayer should wrap router, otherwise it's applied after routing
+        let service = NormalizePathLayer::trim_trailing_slash()
+            .layer(router.layer(TraceLayer::new_for_http()));
+
+        let stop_token = CancellationToken::new();
 
-        let task_handle =
-            tokio::spawn(async move { RestServer::serve_with_router(server, router).await });
+        for (listener, addr) in listeners.into_iter().zip(&resolved_addrs) {
+            let scheme = match (&listener, &rustls_config) {
+                (Listener::Tcp(_), Some(_)) => "https",
+                _ => "http",
+            };
+            tracing::info!("starting {scheme} server at {addr}");
 
-        locked_state.server_handle = Some(server_handle);
-        locked_state.task_handle = Some(task_handle);
+            let service = service.clone();
+            let rustls_config = rustls_config.clone();
+            let stop_token = stop_token.clone();
 
-        Ok(resolved_addr)
+            locked_state.task_handles.push(tokio::spawn(async move {
+                let result = match listener {
+                    Listener::Tcp(tcp_listener) => {
+                        RestServer::serve_tcp(
+                            tcp_listener,
+                            rustls_config,
+                            service,
+                            &stop_token,
+                        )
+                        .await
+                    },
+                    Listener::Unix(unix_socket) => {
+                        RestServer::serve_unix(unix_socket, service, &stop_token).await
+                    },
+                };
+
+                // if one listener fails, stop others too
+                if result.is_err() {
+                    stop_token.cancel();
+                }
+
+                result
+            }));
+        }
+
+        if let Some(tls_reloader) = tls_reloader {
+            let stop_token = stop_token.clone();
+
+            locked_state.task_handles.push(tokio::spawn(async move {
+                tls_reloader.run(stop_token).await;
+                Ok(())
+            }));
+        }
+
+        locked_state.stop_token = Some(stop_token);
+
+        Ok(resolved_addrs)
     }
 
     /// Tell server to stop.
@@ -137,14 +299,14 @@ impl RestServer {
     ///
     /// Call wait() to wait server to finish.
     pub async fn stop(self: &Arc<Self>) {
-        let server_handle = {
+        let stop_token = {
             let mut locked_state = self.state.lock().await;
-            locked_state.server_handle.take()
+            locked_state.stop_token.take()
         };
 
-        if let Some(handle) = server_handle {
+        if let Some(token) = stop_token {
             tracing::debug!("stopping server");
-            handle.shutdown();
+            token.cancel();
         }
     }
 
@@ -154,18 +316,150 @@ impl RestServer {
     ///
     /// Won't return until server fails or stop() is called.
     pub async fn wait(self: &Arc<Self>) -> Result<()> {
-        let task_handle = {
+        let task_handles = {
             let mut locked_state = self.state.lock().await;
-            locked_state.task_handle.take()
+            std::mem::take(&mut locked_state.task_handles)
+        };
+
+        if task_handles.is_empty() {
+            return Err(ServerError::StateError);
         }
-        .ok_or(ServerError::StateError)?;
 
         tracing::debug!("waiting server");
-        task_handle
-            .await
-            .map_err(|err| ServerError::TokioError(err))?
-            .map_err(|err| ServerError::ServeError(err))?;
 
-        Ok(())
+        let mut result = Ok(());
+        for task_handle in task_handles {
+            let task_result =
+                task_handle.await.map_err(|err| ServerError::TokioError(err)).and_then(
+                    |serve_result| serve_result.map_err(|err| ServerError::ServeError(err)),
+                );
+
+            if result.is_ok() {
+                result = task_result;
+            }
+        }
+
+        result
+    }
+
+    /// Create Unix socket and set its permissions.
+    /// Socket file left after unclean shutdown is replaced, unless
+    /// another process still listens on it.
+    async fn bind_unix(&self, path: &Path) -> Result<UnixSocket> {
+        let bind_error = |err: io::Error| {
+            ServerError::BindError(io::Error::new(
+                err.kind(),
+                format!("{}: {err}", path.display()),
+            ))
+        };
+
+        if let Ok(metadata) = fs::symlink_metadata(path) {
+            if !metadata.file_type().is_socket() {
+                return Err(bind_error(io::Error::new(
+                    io::ErrorKind::AlreadyExists,
+                    "file exists and is not a socket",
+                )));
+            }
+            if UnixStream::connect(path).await.is_ok() {
+                return Err(bind_error(io::ErrorKind::AddrInUse.into()));
+            }
+            fs::remove_file(path).map_err(bind_error)?;
+        }
+
+        // socket is created with permissions derived from process umask,
+        // so it's bound inside private directory, gets socket_mode there,
+        // and only then is moved to its path; this way it's never
+        // accessible with looser permissions
+        let private_dir = path.with_file_name(format!(
+            ".{}.{}.tmp",
+            path.file_name().unwrap_or_default().to_string_lossy(),
+            std::process::id()
+        ));
+        DirBuilder::new().mode(0o700).create(&private_dir).map_err(bind_error)?;
+
+        let private_path = private_dir.join("socket");
+        let bind_result = Self::bind_and_move(&private_path, path, self.socket_mode);
+
+        _ = fs::remove_file(&private_path);
+        _ = fs::remove_dir(&private_dir);
+
+        Ok(UnixSocket { listener: bind_result.map_err(bind_error)?, path: path.into() })
+    }
+
+    /// Bind socket at temporary path, set its mode, and move it to final path.
+    fn bind_and_move(temp_path: &Path, path: &Path, mode: u32) -> io::Result<UnixListener> {
+        let listener = UnixListener::bind(temp_path)?;
+        fs::set_permissions(temp_path, Permissions::from_mode(mode))?;
+        fs::rename(temp_path, path)?;
+
+        Ok(listener)
+    }
+
+    /// Serve TCP connections until stop token is cancelled.
+    /// If rustls config is given, connections use TLS.
+    async fn serve_tcp(
+        tcp_listener: std::net::TcpListener, rustls_config: Option<RustlsConfig>,
+        service: HttpService, stop_token: &CancellationToken,
+    ) -> io::Result<()> {
+        let server_handle = ServerHandle::new();
+        let server = Server::from_tcp(tcp_listener).handle(server_handle.clone());
+        let make_service = ServiceExt::<Request>::into_make_service(service);
+
+        let mut serve_future: Pin<Box<dyn Future<Output = io::Result<()>> + Send>> =
+            match rustls_config {
+                Some(rustls_config) => Box::pin(
+                    server.acceptor(RustlsAcceptor::new(rustls_config)).serve(make_service),
+                ),
+                None => Box::pin(server.serve(make_service)),
+            };
+
+        tokio::select! {
+            result = &mut serve_future => result,
+            _ = stop_token.cancelled() => {
+                server_handle.shutdown();
+                serve_future.await
+            },
+        }
+    }
+
+    /// Serve Unix socket connections until stop token is cancelled.
+    /// Open connections are closed when token is cancelled.
+    async fn serve_unix(
+        unix_socket: UnixSocket, service: HttpService, stop_token: &CancellationToken,
+    ) -> io::Result<()> {
+        let builder = auto::Builder::new(TokioExecutor::new());
+
+        loop {
+            let stream = tokio::select! {
+                result = unix_socket.listener.accept() => match result {
+                    Ok((stream, _)) => stream,
+                    Err(err) => {
+                        // e.g. too many open files, retry after a pause
+                        tracing::warn!("can't accept connection: {err}");
+                        tokio::time::sleep(Duration::from_millis(100)).await;
+                        continue;
+                    },
+                },
+                _ = stop_token.cancelled() => return Ok(()),
+            };
+
+            let builder = builder.clone();
+            let service = TowerToHyperService::new(service.clone());
+            let stop_token = stop_token.clone();
+
+            tokio::spawn(async move {
+                let connection =
+                    builder.serve_connection_with_upgrades(TokioIo::new(stream), service);
+
+                tokio::select! {
+                    result = connection => {
+                        if let Err(err) = result {
+                            tracing::debug!("connection failed: {err}");
+                        }
+                    },
+                    _ = stop_token.cancelled() => (),
+                }
+            });
+        }
     }
 }
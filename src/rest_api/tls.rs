@@ -0,0 +1,280 @@
+// Copyright (c) Roc Streaming authors
+// Licensed under MPL-2.0
+use crate::rest_api::error::ServerError;
+
+use axum_server::tls_rustls::RustlsConfig;
+use rcgen::{CertificateParams, DistinguishedName, DnType, KeyPair};
+use rustls::ServerConfig as RustlsServerConfig;
+use rustls::crypto::ring;
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use sha2::{Digest, Sha256};
+use std::fmt::Write as _;
+use std::fs::{self, OpenOptions};
+use std::io::Write as _;
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+use std::result;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use time::OffsetDateTime;
+use tokio_util::sync::CancellationToken;
+
+type Result<T> = result::Result<T, ServerError>;
+
+/// TLS settings of server.
+/// Certificate and key are PEM files, re-read when they're modified.
+#[derive(Clone, Debug)]
+pub struct TlsConfig {
+    /// Certificate chain, server certificate first.
+    pub cert_path: PathBuf,
+
+    /// Private key of server certificate.
+    pub key_path: PathBuf,
+
+    /// How often files are checked for modifications.
+    pub reload_interval: Duration,
+}
+
+impl TlsConfig {
+    /// File names of self-signed certificate and key in directory.
+    const SELF_SIGNED_CERT: &str = "cert.pem";
+    const SELF_SIGNED_KEY: &str = "key.pem";
+
+    pub fn new(cert_path: &Path, key_path: &Path) -> Self {
+        TlsConfig {
+            cert_path: cert_path.into(),
+            key_path: key_path.into(),
+            reload_interval: Duration::from_secs(10),
+        }
+    }
+
+    /// Use self-signed certificate from directory, generating it if it
+    /// doesn't exist yet. Certificate is valid for localhost and given
+    /// host names or IP addresses.
+    pub fn self_signed(dir: &Path, host_names: &[String]) -> Result<Self> {
+        let config = TlsConfig::new(
+            &dir.join(Self::SELF_SIGNED_CERT),
+            &dir.join(Self::SELF_SIGNED_KEY),
+        );
+
+        if config.cert_path.exists() && config.key_path.exists() {
+            return Ok(config);
+        }
+
+        let tls_error = |err: &dyn std::fmt::Display| {
+            ServerError::TlsError(format!("{}: {err}", dir.display()))
+        };
+
+        let mut alt_names: Vec<String> =
+            ["localhost", "127.0.0.1", "::1"].iter().map(|name| name.to_string()).collect();
+        for host_name in host_names {
+            if !alt_names.contains(host_name) {
+                alt_names.push(host_name.clone());
+            }
+        }
+
+        let mut params = CertificateParams::new(alt_names).map_err(|err| tls_error(&err))?;
+        params.distinguished_name = DistinguishedName::new();
+        params.distinguished_name.push(DnType::CommonName, "rocd self-signed");
+        params.not_before = OffsetDateTime::now_utc() - time::Duration::days(1);
+        params.not_after = OffsetDateTime::now_utc() + time::Duration::days(3650);
+
+        let key_pair = KeyPair::generate().map_err(|err| tls_error(&err))?;
+        let cert = params.self_signed(&key_pair).map_err(|err| tls_error(&err))?;
+
+        fs::create_dir_all(dir).map_err(|err| tls_error(&err))?;
+
+        // key is written first and only readable by owner
+        OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .mode(0o600)
+            .open(&config.key_path)
+            .and_then(|mut file| file.write_all(key_pair.serialize_pem().as_bytes()))
+            .map_err(|err| tls_error(&err))?;
+        fs::write(&config.cert_path, cert.pem()).map_err(|err| tls_error(&err))?;
+
+        tracing::info!("generated self-signed certificate {}", config.cert_path.display());
+
+        Ok(config)
+    }
+
+    /// Get SHA-256 fingerprint of server certificate.
+    pub fn fingerprint(&self) -> Result<String> {
+        let data = fs::read(&self.cert_path).map_err(|err| self.cert_error(&err))?;
+        let cert = CertificateDer::pem_slice_iter(&data)
+            .next()
+            .ok_or_else(|| self.cert_error(&"no certificates found"))?
+            .map_err(|err| self.cert_error(&err))?;
+
+        Ok(cert_fingerprint(&cert))
+    }
+
+    /// Read certificate and key and build rustls config.
+    async fn load(&self) -> Result<Arc<RustlsServerConfig>> {
+        let cert_data =
+            tokio::fs::read(&self.cert_path).await.map_err(|err| self.cert_error(&err))?;
+        let key_data =
+            tokio::fs::read(&self.key_path).await.map_err(|err| self.key_error(&err))?;
+
+        let certs = CertificateDer::pem_slice_iter(&cert_data)
+            .collect::<result::Result<Vec<_>, _>>()
+            .map_err(|err| self.cert_error(&err))?;
+        if certs.is_empty() {
+            return Err(self.cert_error(&"no certificates found"));
+        }
+
+        let key =
+            PrivateKeyDer::from_pem_slice(&key_data).map_err(|err| self.key_error(&err))?;
+
+        let mut config =
+            RustlsServerConfig::builder_with_provider(Arc::new(ring::default_provider()))
+                .with_safe_default_protocol_versions()
+                .map_err(|err| self.cert_error(&err))?
+                .with_no_client_auth()
+                .with_single_cert(certs, key)
+                .map_err(|err| self.cert_error(&err))?;
+        config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+
+        Ok(Arc::new(config))
+    }
+
+    /// Get modification times of certificate and key.
+    fn modified(&self) -> Option<(SystemTime, SystemTime)> {
+        let modified = |path: &Path| fs::metadata(path).and_then(|meta| meta.modified()).ok();
+
+        Some((modified(&self.cert_path)?, modified(&self.key_path)?))
+    }
+
+    fn cert_error(&self, err: &dyn std::fmt::Display) -> ServerError {
+        ServerError::TlsError(format!("{}: {err}", self.cert_path.display()))
+    }
+
+    fn key_error(&self, err: &dyn std::fmt::Display) -> ServerError {
+        ServerError::TlsError(format!("{}: {err}", self.key_path.display()))
+    }
+}
+
+/// Format SHA-256 fingerprint of DER certificate as colon-separated
+/// hex bytes, e.g. for pinning certificate on clients.
+pub fn cert_fingerprint(cert_der: &[u8]) -> String {
+    Sha256::digest(cert_der).iter().enumerate().fold(String::new(), |mut text, (n, byte)| {
+        if n != 0 {
+            text.push(':');
+        }
+        _ = write!(text, "{byte:02X}");
+        text
+    })
+}
+
+/// Certificate shared by TLS listeners, reloaded when files change.
+pub(crate) struct TlsReloader {
+    config: TlsConfig,
+    rustls_config: RustlsConfig,
+}
+
+impl TlsReloader {
+    /// Load certificate, fails if it can't be loaded.
+    pub(crate) async fn load(config: &TlsConfig) -> Result<Self> {
+        let rustls_config = RustlsConfig::from_config(config.load().await?);
+
+        Ok(TlsReloader { config: config.clone(), rustls_config })
+    }
+
+    pub(crate) fn rustls_config(&self) -> RustlsConfig {
+        self.rustls_config.clone()
+    }
+
+    /// Periodically check files and reload certificate when they change,
+    /// until token is cancelled. If new files are broken, previous
+    /// certificate remains in use.
+    pub(crate) async fn run(self, stop_token: CancellationToken) {
+        let mut last_modified = self.config.modified();
+
+        loop {
+            tokio::select! {
+                _ = tokio::time::sleep(self.config.reload_interval) => (),
+                _ = stop_token.cancelled() => return,
+            }
+
+            let modified = self.config.modified();
+            if modified.is_none() || modified == last_modified {
+                continue;
+            }
+
+            match self.config.load().await {
+                Ok(rustls_config) => {
+                    self.rustls_config.reload_from_config(rustls_config);
+                    last_modified = modified;
+
+                    tracing::info!("reloaded certificate {}", self.config.cert_path.display());
+                },
+                Err(err) => {
+                    // files may be in the middle of update, retry next time
+                    tracing::warn!("can't reload certificate: {err}");
+                },
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assertables::*;
+    use std::os::unix::fs::PermissionsExt;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_self_signed() {
+        let dir = TempDir::new().unwrap();
+        let tls_dir = dir.path().join("tls");
+
+        let config = TlsConfig::self_signed(&tls_dir, &["rocd.local".into()]).unwrap();
+        assert_ok!(config.load().await);
+
+        let mode = fs::metadata(&config.key_path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+
+        // fingerprint is 32 hex bytes
+        let fingerprint = config.fingerprint().unwrap();
+        assert_eq!(fingerprint.len(), 32 * 3 - 1);
+
+        // existing certificate is reused
+        let config = TlsConfig::self_signed(&tls_dir, &[]).unwrap();
+        assert_eq!(config.fingerprint().unwrap(), fingerprint);
+
+        // new certificate is generated in other directory
+        let other_config = TlsConfig::self_signed(&dir.path().join("other"), &[]).unwrap();
+        assert_ne!(other_config.fingerprint().unwrap(), fingerprint);
+    }
+
+    #[tokio::test]
+    async fn test_broken_files() {
+        let dir = TempDir::new().unwrap();
+        let config = TlsConfig::self_signed(dir.path(), &[]).unwrap();
+
+        // missing key
+        let bad_config = TlsConfig::new(&config.cert_path, &dir.path().join("missing.pem"));
+        assert_matches!(bad_config.load().await, Err(ServerError::TlsError(_)));
+
+        // key instead of certificate
+        let bad_config = TlsConfig::new(&config.key_path, &config.key_path);
+        assert_matches!(bad_config.load().await, Err(ServerError::TlsError(_)));
+
+        // certificate instead of key
+        let bad_config = TlsConfig::new(&config.cert_path, &config.cert_path);
+        assert_matches!(bad_config.load().await, Err(ServerError::TlsError(_)));
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        assert_eq!(
+            cert_fingerprint(b""),
+            "E3:B0:C4:42:98:FC:1C:14:9A:FB:F4:C8:99:6F:B9:24:\
+             27:AE:41:E4:64:9B:93:4C:A4:95:99:1B:78:52:B8:55"
+        );
+    }
+}
@@ -0,0 +1,39 @@
+// Copyright (c) Roc Streaming authors
+// Licensed under MPL-2.0
+use axum::Router;
+use axum::body::Body;
+use axum::http::StatusCode;
+use axum::response::{Redirect, Response};
+use axum::routing::get;
+use std::sync::Arc;
+
+/// Single-page admin UI, embedded into binary.
+/// Page has no external assets, so it works without internet access.
+const INDEX_HTML: &str = include_str!("ui/index.html");
+
+/// Serves web admin UI at /ui.
+///
+/// Page itself contains no data and is always public. It uses REST API
+/// from browser, with bearer token entered by user if auth is enabled.
+pub struct UiController;
+
+impl UiController {
+    pub fn new() -> Self {
+        UiController
+    }
+
+    pub fn router(self: &Arc<Self>) -> Router {
+        Router::new()
+            .route("/", get(|| async { Redirect::to("/ui") }))
+            .route("/ui", get(index_html))
+    }
+}
+
+async fn index_html() -> Response {
+    Response::builder()
+        .status(StatusCode::OK)
+        .header("Content-Type", "text/html; charset=utf-8")
+        .header("Cache-Control", "no-cache")
+        .body(Body::from(INDEX_HTML))
+        .unwrap()
+}
@@ -108,8 +108,9 @@ impl Db {
                     Err(err) => return Err(VaultError::from(err)),
                 };
 
-                let iter =
-                    table.range::<&str>(..).map_err(|err| VaultError::ReadError(err.into()))?;
+                let iter = table
+                    .range::<&str>(..)
+                    .map_err(|err| VaultError::ReadError(err.into()))?;
 
                 let uids = iter
                     .map(|elem| -> Result<Uid> {
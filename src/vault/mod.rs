@@ -160,6 +160,60 @@ impl Vault {
             .remove_entity(Backend::STREAM_TABLE, &self.backend.stream_cache, uid)
             .await
     }
+
+    /// List all group UIDs.
+    pub async fn list_groups(&self) -> Result<Arc<HashSet<Uid>>> {
+        self.backend.list_entities(Backend::GROUP_TABLE, &self.backend.group_cache).await
+    }
+
+    /// Read group by UID.
+    pub async fn read_group(&self, uid: &Uid) -> Result<Arc<GroupSpec>> {
+        self.backend.read_entity(Backend::GROUP_TABLE, &self.backend.group_cache, uid).await
+    }
+
+    /// Write group.
+    pub async fn write_group(&self, group: &Arc<GroupSpec>) -> Result<()> {
+        self.backend
+            .write_entity(
+                Backend::GROUP_TABLE,
+                &self.backend.group_cache,
+                &group.group_uid,
+                group,
+            )
+            .await
+    }
+
+    /// Remove group.
+    pub async fn remove_group(&self, uid: &Uid) -> Result<()> {
+        self.backend.remove_entity(Backend::GROUP_TABLE, &self.backend.group_cache, uid).await
+    }
+
+    /// List all token UIDs.
+    pub async fn list_tokens(&self) -> Result<Arc<HashSet<Uid>>> {
+        self.backend.list_entities(Backend::TOKEN_TABLE, &self.backend.token_cache).await
+    }
+
+    /// Read token by UID.
+    pub async fn read_token(&self, uid: &Uid) -> Result<Arc<TokenSpec>> {
+        self.backend.read_entity(Backend::TOKEN_TABLE, &self.backend.token_cache, uid).await
+    }
+
+    /// Write token.
+    pub async fn write_token(&self, token: &Arc<TokenSpec>) -> Result<()> {
+        self.backend
+            .write_entity(
+                Backend::TOKEN_TABLE,
+                &self.backend.token_cache,
+                &token.token_uid,
+                token,
+            )
+            .await
+    }
+
+    /// Remove token.
+    pub async fn remove_token(&self, uid: &Uid) -> Result<()> {
+        self.backend.remove_entity(Backend::TOKEN_TABLE, &self.backend.token_cache, uid).await
+    }
 }
 
 #[derive(Debug)]
@@ -193,6 +247,8 @@ struct Backend {
     peer_cache: RwLock<Cache<PeerSpec>>,
     endpoint_cache: RwLock<Cache<EndpointSpec>>,
     stream_cache: RwLock<Cache<StreamSpec>>,
+    group_cache: RwLock<Cache<GroupSpec>>,
+    token_cache: RwLock<Cache<TokenSpec>>,
 
     // metrics
     cache_hits: AtomicUsize,
@@ -205,6 +261,8 @@ impl Backend {
     const PEER_TABLE: Table = Table::new("peers");
     const ENDPOINT_TABLE: Table = Table::new("endpoints");
     const STREAM_TABLE: Table = Table::new("streams");
+    const GROUP_TABLE: Table = Table::new("groups");
+    const TOKEN_TABLE: Table = Table::new("tokens");
 
     /// Constructor.
     fn new(config: &VaultConfig, db: Arc<Db>) -> Self {
@@ -214,6 +272,8 @@ impl Backend {
             peer_cache: RwLock::new(Cache::new(config)),
             endpoint_cache: RwLock::new(Cache::new(config)),
             stream_cache: RwLock::new(Cache::new(config)),
+            group_cache: RwLock::new(Cache::new(config)),
+            token_cache: RwLock::new(Cache::new(config)),
             cache_hits: AtomicUsize::new(0),
             cache_misses: AtomicUsize::new(0),
             cache_drops: AtomicUsize::new(0),
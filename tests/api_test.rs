@@ -3,16 +3,25 @@
 mod test_client;
 mod test_driver;
 mod test_server;
+mod test_transport;
 
 use crate::test_client::Client;
 use crate::test_client::types::*;
 use crate::test_driver::MockDriver;
 use crate::test_server::Server;
-use rocd::drivers::Driver;
+use rocd::drivers::{Driver, DriverRegistry};
 
+use assertables::*;
 use reqwest::StatusCode;
+use serde_json::json;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
 use test_context::{AsyncTestContext, test_context};
+use time::OffsetDateTime;
+use time::format_description::well_known::Rfc3339;
 use tracing_test::traced_test;
 
 struct ApiTestContext {
@@ -41,7 +50,7 @@ impl AsyncTestContext for ApiTestContext {
 #[traced_test]
 async fn test_list_peers(ctx: &mut ApiTestContext) {
     // GET /peers
-    let resp = ctx.client.list_peers().await.unwrap();
+    let resp = ctx.client.list_peers(None, None, None, None).await.unwrap();
 
     assert_eq!(resp.status(), StatusCode::OK);
     assert_eq!(
@@ -49,6 +58,7 @@ async fn test_list_peers(ctx: &mut ApiTestContext) {
         vec![PeerSpec {
             peer_uri: "/peers/777777-888888-999999".into(),
             peer_uid: "777777-888888-999999".into(),
+            revision: Some(0),
         }],
     );
 }
@@ -67,18 +77,291 @@ async fn test_read_peer(ctx: &mut ApiTestContext) {
             PeerSpec {
                 peer_uri: "/peers/777777-888888-999999".into(),
                 peer_uid: "777777-888888-999999".into(),
+                revision: Some(0),
             },
         );
     }
 }
 
+#[test_context(ApiTestContext)]
+#[tokio::test]
+#[traced_test]
+async fn test_unix_socket(ctx: &mut ApiTestContext) {
+    let socket_path = ctx.server.socket_path();
+
+    // only owner can connect
+    let mode = fs::metadata(&socket_path).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o600);
+
+    // private directory used for binding is removed
+    let leftovers: Vec<_> = fs::read_dir(socket_path.parent().unwrap())
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .filter(|name| name.ends_with(".tmp"))
+        .collect();
+    assert_eq!(leftovers, Vec::<String>::new());
+
+    // host is ignored when connecting via socket
+    let client = Client::new_with_client(
+        "http://localhost",
+        reqwest::Client::builder().unix_socket(socket_path).build().unwrap(),
+    );
+
+    // GET /peers/self
+    let resp = client.read_peer("self").await.unwrap();
+
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.into_inner().peer_uid, "777777-888888-999999");
+}
+
+#[test_context(ApiTestContext)]
+#[tokio::test]
+#[traced_test]
+async fn test_deprecated_alias(ctx: &mut ApiTestContext) {
+    // GET /api/v1/peers/self
+    let resp = reqwest::get(format!("{}/peers/self", ctx.server.api_url())).await.unwrap();
+
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert!(resp.headers().get("deprecation").is_none());
+    assert!(resp.headers().get(reqwest::header::LINK).is_none());
+
+    // GET /peers/self
+    let resp = reqwest::get(format!("{}/peers/self", ctx.server.url())).await.unwrap();
+
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.headers()["deprecation"], "true");
+    assert_eq!(
+        resp.headers()[reqwest::header::LINK],
+        "</api/v1/peers/self>; rel=\"successor-version\"",
+    );
+    assert_eq!(resp.json::<PeerSpec>().await.unwrap().peer_uid, "777777-888888-999999");
+
+    // docs of unknown version
+    let resp =
+        reqwest::get(format!("{}/openapi/v0/openapi.json", ctx.server.url())).await.unwrap();
+
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+}
+
+#[test_context(ApiTestContext)]
+#[tokio::test]
+#[traced_test]
+async fn test_ui(ctx: &mut ApiTestContext) {
+    // GET / redirects to /ui
+    for path in ["/", "/ui"] {
+        let resp = reqwest::get(format!("{}{path}", ctx.server.url())).await.unwrap();
+
+        assert_eq!(resp.status(), StatusCode::OK, "{path}");
+        assert_eq!(resp.url().path(), "/ui", "{path}");
+        assert_starts_with!(
+            resp.headers()[reqwest::header::CONTENT_TYPE].to_str().unwrap(),
+            "text/html"
+        );
+
+        let html = resp.text().await.unwrap();
+
+        assert_contains!(html, "<title>rocd admin</title>");
+        // no external assets
+        assert_not_contains!(html, "src=\"http");
+        assert_not_contains!(html, "href=\"http");
+        assert_not_contains!(html, "@import");
+    }
+}
+
+#[test_context(ApiTestContext)]
+#[tokio::test]
+#[traced_test]
+async fn test_health(ctx: &mut ApiTestContext) {
+    // GET /health
+    let resp = ctx.client.read_health().await.unwrap();
+
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.into_inner(), HealthSpec { alive: true });
+}
+
+#[test_context(ApiTestContext)]
+#[tokio::test]
+#[traced_test]
+async fn test_readiness(ctx: &mut ApiTestContext) {
+    // streams are reconciled on first tick of background task
+    let mut readiness = None;
+
+    for _ in 0..100 {
+        // GET /ready
+        match ctx.client.read_readiness().await {
+            Ok(resp) => {
+                assert_eq!(resp.status(), StatusCode::OK);
+                readiness = Some(resp.into_inner());
+                break;
+            },
+            Err(err) => {
+                assert_eq!(err.status(), Some(StatusCode::SERVICE_UNAVAILABLE));
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            },
+        }
+    }
+
+    assert_eq!(
+        readiness,
+        Some(ReadinessSpec {
+            ready: true,
+            driver_up: true,
+            vault_loaded: true,
+            streams_reconciled: true,
+        }),
+    );
+}
+
+#[tokio::test]
+#[traced_test]
+async fn test_readiness_driver_down() {
+    let driver = Arc::new(MockDriver { is_up: AtomicBool::new(false) });
+    let server = Server::start(&(Arc::clone(&driver) as Arc<dyn Driver>)).await;
+
+    async fn read_readiness(server: &Server) -> (StatusCode, ReadinessSpec) {
+        // GET /ready (client doesn't return body of 503)
+        let resp = reqwest::get(format!("{}/ready", server.api_url())).await.unwrap();
+
+        (resp.status(), resp.json().await.unwrap())
+    }
+
+    // vault is loaded on first tick of background task,
+    // but streams aren't reconciled while driver is down
+    let mut readiness = read_readiness(&server).await;
+    for _ in 0..100 {
+        if readiness.1.vault_loaded {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        readiness = read_readiness(&server).await;
+    }
+
+    assert_eq!(
+        readiness,
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            ReadinessSpec {
+                ready: false,
+                driver_up: false,
+                vault_loaded: true,
+                streams_reconciled: false,
+            }
+        ),
+    );
+
+    // streams are reconciled on next tick after driver is up
+    driver.is_up.store(true, Ordering::SeqCst);
+
+    for _ in 0..100 {
+        readiness = read_readiness(&server).await;
+        if readiness.0 == StatusCode::OK {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    assert_eq!(
+        readiness,
+        (
+            StatusCode::OK,
+            ReadinessSpec {
+                ready: true,
+                driver_up: true,
+                vault_loaded: true,
+                streams_reconciled: true,
+            }
+        ),
+    );
+
+    server.shutdown().await;
+}
+
+#[test_context(ApiTestContext)]
+#[tokio::test]
+#[traced_test]
+async fn test_version(ctx: &mut ApiTestContext) {
+    // GET /version
+    let resp = ctx.client.read_version().await.unwrap();
+
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let version = resp.into_inner();
+
+    assert_eq!(version.version, env!("CARGO_PKG_VERSION"));
+    assert_eq!(version.active_driver, DriverId::Unspecified);
+    assert_eq!(
+        version.supported_drivers.len(),
+        DriverRegistry::new().supported_drivers().len()
+    );
+}
+
+#[test_context(ApiTestContext)]
+#[tokio::test]
+#[traced_test]
+async fn test_metrics(ctx: &mut ApiTestContext) {
+    let stream_uid = "777777-888888-999999";
+
+    // PUT /streams/{stream_uid}
+    let resp =
+        ctx.client.update_stream(stream_uid, None, &make_stream(stream_uid)).await.unwrap();
+
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    // GET /metrics
+    let resp = reqwest::get(format!("{}/metrics", ctx.server.url())).await.unwrap();
+
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_starts_with!(
+        resp.headers()[reqwest::header::CONTENT_TYPE].to_str().unwrap(),
+        "text/plain; version=0.0.4"
+    );
+
+    let text = resp.text().await.unwrap();
+
+    for line in [
+        "rocd_driver_up{driver=\"unspecified\"} 1",
+        "rocd_db_writes_total 1",
+        "rocd_http_requests_total{method=\"PUT\",route=\"/api/v1/streams/{stream_uid}\",status=\"200\"} 1",
+        "rocd_http_request_duration_seconds_count{method=\"PUT\",route=\"/api/v1/streams/{stream_uid}\"} 1",
+        "rocd_streams{state=\"running\"} 1",
+        "rocd_streams{state=\"stopped\"} 0",
+        "rocd_stream_frames_total{stream_uid=\"777777-888888-999999\"}",
+    ] {
+        assert_contains!(text, line);
+    }
+}
+
+#[tokio::test]
+#[traced_test]
+async fn test_metrics_driver_down() {
+    let driver = Arc::new(MockDriver { is_up: AtomicBool::new(false) });
+    let server = Server::start(&(Arc::clone(&driver) as Arc<dyn Driver>)).await;
+
+    // GET /metrics
+    let resp = reqwest::get(format!("{}/metrics", server.url())).await.unwrap();
+
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let text = resp.text().await.unwrap();
+
+    for line in ["rocd_driver_up{driver=\"unspecified\"} 0", "rocd_ready 0"] {
+        assert_contains!(text, line);
+    }
+
+    server.shutdown().await;
+}
+
 #[test_context(ApiTestContext)]
 #[tokio::test]
 #[traced_test]
 async fn test_list_endpoints(ctx: &mut ApiTestContext) {
     for peer in ["111111-222222-333333", "self"] {
         // GET /peers/{peer_uid}/endpoints
-        let resp = ctx.client.list_endpoints(peer).await.unwrap();
+        let resp = ctx
+            .client
+            .list_endpoints(peer, None, None, None, None, None, None, None)
+            .await
+            .unwrap();
 
         assert_eq!(resp.status(), StatusCode::OK);
         assert_eq!(
@@ -92,6 +375,8 @@ async fn test_list_endpoints(ctx: &mut ApiTestContext) {
                 driver: DriverId::Pipewire,
                 display_name: "Display Name".into(),
                 system_name: "system_name".into(),
+                virtual_source: None,
+                revision: Some(0),
             }],
         );
     }
@@ -117,63 +402,2210 @@ async fn test_read_endpoint(ctx: &mut ApiTestContext) {
                 driver: DriverId::Pipewire,
                 display_name: "Display Name".into(),
                 system_name: "system_name".into(),
+                virtual_source: None,
+                revision: Some(0),
             },
         );
     }
 }
 
+fn make_virtual_endpoint(signal: SignalType) -> EndpointSpec {
+    EndpointSpec {
+        endpoint_uri: "/peers/777777-888888-999999/endpoints/123456-123456-123456".into(),
+        endpoint_uid: "123456-123456-123456".into(),
+        endpoint_type: EndpointType::VirtualSource,
+        stream_direction: EndpointDir::Input,
+        driver: DriverId::Virtual,
+        display_name: "Test Tone".into(),
+        system_name: "test_tone".into(),
+        virtual_source: Some(VirtualSourceSpec {
+            signal,
+            frequency: None,
+            end_frequency: None,
+            sweep_duration: None,
+            level: None,
+            channel: None,
+            file_path: None,
+        }),
+        revision: Some(1),
+    }
+}
+
+#[test_context(ApiTestContext)]
+#[tokio::test]
+#[traced_test]
+async fn test_virtual_endpoint(ctx: &mut ApiTestContext) {
+    let endpoint_uid = "123456-123456-123456";
+    let mut endpoint = make_virtual_endpoint(SignalType::Sine);
+
+    // PUT /peers/{peer_uid}/endpoints/{endpoint_uid}
+    let resp =
+        ctx.client.update_endpoint("self", endpoint_uid, None, &endpoint).await.unwrap();
+
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.into_inner(), endpoint);
+
+    // GET /peers/{peer_uid}/endpoints/{endpoint_uid}
+    let resp = ctx.client.read_endpoint("self", endpoint_uid).await.unwrap();
+
+    assert_eq!(resp.into_inner(), endpoint);
+
+    // GET /peers/{peer_uid}/endpoints?endpoint_type=virtual_source
+    let resp = ctx
+        .client
+        .list_endpoints(
+            "self",
+            None,
+            None,
+            Some(EndpointType::VirtualSource),
+            None,
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(resp.into_inner(), vec![endpoint.clone()]);
+
+    // use endpoint as stream source and record stream
+    let mut stream = make_stream("777777-888888-999999");
+    stream.source = ConnectionSpec::EndpointConnection {
+        connection_type: ConnectionType::Endpoint,
+        endpoint_uri: endpoint.endpoint_uri.clone(),
+    };
+    stream.recording = Some(RecordingSpec {
+        format: RecordingFormat::Wav,
+        rotation_interval: None,
+        max_files: None,
+        max_age: None,
+        metadata: Default::default(),
+    });
+
+    // PUT /streams/{stream_uid}
+    let resp = ctx.client.update_stream("777777-888888-999999", None, &stream).await.unwrap();
+
+    assert_eq!(resp.into_inner(), stream);
+
+    // change signal while stream is running
+    endpoint.virtual_source.as_mut().unwrap().level = Some(-6.0);
+    endpoint.virtual_source.as_mut().unwrap().channel = Some(1);
+    endpoint.revision = Some(2);
+
+    // PUT /peers/{peer_uid}/endpoints/{endpoint_uid}
+    let resp =
+        ctx.client.update_endpoint("self", endpoint_uid, None, &endpoint).await.unwrap();
+
+    assert_eq!(resp.into_inner(), endpoint);
+
+    tokio::time::sleep(Duration::from_millis(1500)).await;
+
+    // stream wasn't restarted
+    let resp = ctx.client.read_stream("777777-888888-999999").await.unwrap();
+
+    assert_eq!(resp.into_inner(), stream);
+
+    // stop recording
+    ctx.client
+        .update_stream("777777-888888-999999", None, &make_stream("777777-888888-999999"))
+        .await
+        .unwrap();
+
+    // GET /streams/{stream_uid}/recordings/{recording_id}
+    let recordings = ctx.client.list_recordings("777777-888888-999999").await.unwrap();
+    let recording_id = recordings.into_inner().pop().unwrap().recording_id;
+
+    let data = reqwest::get(format!(
+        "{}/streams/777777-888888-999999/recordings/{recording_id}",
+        ctx.server.api_url()
+    ))
+    .await
+    .unwrap()
+    .bytes()
+    .await
+    .unwrap();
+
+    // recording contains tone, last second is only in right channel
+    let data_offset = data.windows(4).position(|w| w == b"data").unwrap() + 8;
+    let samples: Vec<i16> = data[data_offset..]
+        .chunks_exact(2)
+        .map(|b| i16::from_le_bytes([b[0], b[1]]))
+        .collect();
+    let last_second = &samples[samples.len() - 44100 * 2..];
+
+    assert!(samples.iter().any(|s| *s != 0));
+    assert!(last_second.iter().step_by(2).all(|s| *s == 0));
+    assert!(last_second.iter().skip(1).step_by(2).any(|s| *s > 16000));
+
+    // DELETE /peers/{peer_uid}/endpoints/{endpoint_uid}
+    let resp = ctx.client.delete_endpoint("self", endpoint_uid, None).await.unwrap();
+
+    assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+
+    let err = ctx.client.delete_endpoint("self", endpoint_uid, None).await.unwrap_err();
+
+    assert_eq!(err.status(), Some(StatusCode::NOT_FOUND));
+}
+
+#[test_context(ApiTestContext)]
+#[tokio::test]
+#[traced_test]
+async fn test_virtual_endpoint_file(ctx: &mut ApiTestContext) {
+    let endpoint_uid = "123456-123456-123456";
+    let mut endpoint = make_virtual_endpoint(SignalType::WavFile);
+    endpoint.virtual_source.as_mut().unwrap().file_path = Some("tone.wav".into());
+
+    // file doesn't exist
+    let err =
+        ctx.client.update_endpoint("self", endpoint_uid, None, &endpoint).await.unwrap_err();
+
+    assert_eq!(err.status(), Some(StatusCode::BAD_REQUEST));
+
+    // 8-bit PCM, mono
+    let mut wav = Vec::new();
+    wav.extend_from_slice(b"RIFF\x2c\0\0\0WAVEfmt \x10\0\0\0\x01\0\x01\0");
+    wav.extend_from_slice(&8000u32.to_le_bytes());
+    wav.extend_from_slice(&8000u32.to_le_bytes());
+    wav.extend_from_slice(b"\x01\0\x08\0data\x08\0\0\0");
+    wav.extend_from_slice(&[128, 160, 192, 160, 128, 96, 64, 96]);
+    std::fs::create_dir_all(ctx.server.media_dir()).unwrap();
+    std::fs::write(ctx.server.media_dir().join("tone.wav"), &wav).unwrap();
+
+    // PUT /peers/{peer_uid}/endpoints/{endpoint_uid}
+    let resp =
+        ctx.client.update_endpoint("self", endpoint_uid, None, &endpoint).await.unwrap();
+
+    assert_eq!(resp.into_inner(), endpoint);
+
+    // path outside of media directory
+    endpoint.virtual_source.as_mut().unwrap().file_path = Some("../rocd.db".into());
+
+    let err =
+        ctx.client.update_endpoint("self", endpoint_uid, None, &endpoint).await.unwrap_err();
+
+    assert_eq!(err.status(), Some(StatusCode::BAD_REQUEST));
+
+    // device endpoints can't be modified
+    let mut device =
+        ctx.client.read_endpoint("self", "444444-555555-666666").await.unwrap().into_inner();
+    device.display_name = "Renamed".into();
+
+    let err = ctx
+        .client
+        .update_endpoint("self", "444444-555555-666666", None, &device)
+        .await
+        .unwrap_err();
+
+    assert_eq!(err.status(), Some(StatusCode::CONFLICT));
+}
+
+fn make_stream(stream_uid: &str) -> StreamSpec {
+    StreamSpec {
+        stream_uri: format!("/streams/{stream_uid}"),
+        stream_uid: stream_uid.into(),
+        source: ConnectionSpec::EndpointConnection {
+            connection_type: ConnectionType::Endpoint,
+            endpoint_uri: "/peers/111111-222222-333333/endpoints/444444-555555-666666".into(),
+        },
+        destination: ConnectionSpec::ExternalConnection {
+            connection_type: ConnectionType::External,
+            media_uri: "rtp+rs8m://192.168.0.101:10000".into(),
+            repair_uri: Some("rs8m://192.168.0.101:10001".into()),
+            control_uri: "rtcp://192.168.0.101:10002".into(),
+            multicast_interface: None,
+            multicast_ttl: None,
+        },
+        encoding: Some(EncodingSpec {
+            payload_type: 10,
+            sample_format: SampleFormat::S16,
+            sample_rate: 44100,
+            channels: 2,
+        }),
+        owner: None,
+        lease_ttl: None,
+        lease_expiration: None,
+        schedule: None,
+        state: Some(StreamState::Running),
+        next_transition: None,
+        error: None,
+        recording: None,
+        processing: None,
+        silence_detection: None,
+        failover: None,
+        active_source: None,
+        revision: Some(1),
+    }
+}
+
+/// Stream receiving on given ports and writing to mock endpoint.
+fn make_receiving_stream(stream_uid: &str, first_port: u16) -> StreamSpec {
+    let mut stream = make_stream(stream_uid);
+
+    stream.destination = stream.source.clone();
+    stream.source = ConnectionSpec::ExternalConnection {
+        connection_type: ConnectionType::External,
+        media_uri: format!("rtp+rs8m://0.0.0.0:{first_port}"),
+        repair_uri: Some(format!("rs8m://0.0.0.0:{}", first_port + 1)),
+        control_uri: format!("rtcp://0.0.0.0:{}", first_port + 2),
+        multicast_interface: None,
+        multicast_ttl: None,
+    };
+
+    stream
+}
+
 #[test_context(ApiTestContext)]
 #[tokio::test]
 #[traced_test]
 async fn test_list_streams(ctx: &mut ApiTestContext) {
     // GET /streams
-    let resp = ctx.client.list_streams().await.unwrap();
+    let resp = ctx
+        .client
+        .list_streams(None, None, None, None, None, None, None, None, None)
+        .await
+        .unwrap();
+
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.into_inner(), vec![]);
+
+    for stream_uid in ["777777-888888-999999", "111111-222222-333333"] {
+        // PUT /streams/{stream_uid}
+        let resp = ctx
+            .client
+            .update_stream(stream_uid, None, &make_stream(stream_uid))
+            .await
+            .unwrap();
+
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
+    // GET /streams
+    let resp = ctx
+        .client
+        .list_streams(None, None, None, None, None, None, None, None, None)
+        .await
+        .unwrap();
 
     assert_eq!(resp.status(), StatusCode::OK);
     assert_eq!(
         resp.into_inner(),
-        vec![StreamSpec {
-            stream_uri: "/streams/777777-888888-999999".into(),
-            stream_uid: "777777-888888-999999".into(),
-            source: ConnectionSpec::EndpointConnection {
-                connection_type: ConnectionType::Endpoint,
-                endpoint_uri: "/peers/111111-222222-333333/endpoints/444444-555555-666666"
-                    .into(),
-            },
-            destination: ConnectionSpec::ExternalConnection {
-                connection_type: ConnectionType::External,
-                media_uri: "rtp+rs8m://192.168.0.101:10000".into(),
-                repair_uri: "rs8m://192.168.0.101:10001".into(),
-                control_uri: "rtcp://192.168.0.101:10002".into(),
-            },
-        }],
+        vec![make_stream("111111-222222-333333"), make_stream("777777-888888-999999")],
+    );
+}
+
+#[test_context(ApiTestContext)]
+#[tokio::test]
+#[traced_test]
+async fn test_list_streams_query(ctx: &mut ApiTestContext) {
+    let mut streams = vec![
+        make_stream("111111-111111-111111"),
+        make_stream("222222-222222-222222"),
+        make_receiving_stream("333333-333333-333333", 10000),
+        make_receiving_stream("444444-444444-444444", 10010),
+        make_receiving_stream("555555-555555-555555", 10020),
+    ];
+    streams[0].owner = Some("b".into());
+    streams[1].owner = Some("c".into());
+    streams[2].owner = Some("a".into());
+
+    // mock transport doesn't support ldpc
+    streams[4].source = ConnectionSpec::ExternalConnection {
+        connection_type: ConnectionType::External,
+        media_uri: "rtp+ldpc://0.0.0.0:10020".into(),
+        repair_uri: Some("ldpc://0.0.0.0:10021".into()),
+        control_uri: "rtcp://0.0.0.0:10022".into(),
+        multicast_interface: None,
+        multicast_ttl: None,
+    };
+
+    for stream in &mut streams {
+        // PUT /streams/{stream_uid}
+        *stream = ctx
+            .client
+            .update_stream(&stream.stream_uid, None, stream)
+            .await
+            .unwrap()
+            .into_inner();
+    }
+
+    let uids = |streams: Vec<StreamSpec>| -> Vec<String> {
+        streams.into_iter().map(|stream| stream.stream_uid).collect()
+    };
+
+    // GET /streams?state=failed
+    let resp = ctx
+        .client
+        .list_streams(
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            Some(StreamState::Failed),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(uids(resp.into_inner()), vec!["555555-555555-555555"]);
+
+    // GET /streams?source_uri=...
+    let resp = ctx
+        .client
+        .list_streams(
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            Some("rtcp://0.0.0.0:10012"),
+            None,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(uids(resp.into_inner()), vec!["444444-444444-444444"]);
+
+    // GET /streams?destination_uri=...
+    let resp = ctx
+        .client
+        .list_streams(
+            None,
+            None,
+            Some("/peers/111111-222222-333333/endpoints/444444-555555-666666"),
+            None,
+            None,
+            None,
+            None,
+            None,
+            Some(StreamState::Running),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(uids(resp.into_inner()), vec!["333333-333333-333333", "444444-444444-444444"]);
+
+    // GET /streams?source_type=external
+    let resp = ctx
+        .client
+        .list_streams(
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            Some(ConnectionType::External),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        uids(resp.into_inner()),
+        vec!["333333-333333-333333", "444444-444444-444444", "555555-555555-555555"]
+    );
+
+    // GET /streams?destination_type=external
+    let resp = ctx
+        .client
+        .list_streams(
+            None,
+            Some(ConnectionType::External),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(uids(resp.into_inner()), vec!["111111-111111-111111", "222222-222222-222222"]);
+
+    // GET /streams?sort=owner&order=desc
+    let resp = ctx
+        .client
+        .list_streams(
+            None,
+            None,
+            None,
+            None,
+            Some(ListStreamsOrder::Desc),
+            Some(ListStreamsSort::Owner),
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        uids(resp.into_inner()),
+        vec![
+            "222222-222222-222222",
+            "111111-111111-111111",
+            "333333-333333-333333",
+            "555555-555555-555555",
+            "444444-444444-444444",
+        ]
     );
+
+    // GET /streams?limit=2&cursor=...
+    let mut pages = Vec::new();
+    let mut cursor: Option<String> = None;
+
+    loop {
+        let resp = ctx
+            .client
+            .list_streams(cursor.as_deref(), None, None, Some(2), None, None, None, None, None)
+            .await
+            .unwrap();
+
+        cursor = resp
+            .headers()
+            .get("x-next-cursor")
+            .map(|value| value.to_str().unwrap().to_string());
+        pages.push(uids(resp.into_inner()));
+
+        if cursor.is_none() {
+            break;
+        }
+    }
+
+    assert_eq!(
+        pages,
+        vec![
+            vec!["111111-111111-111111", "222222-222222-222222"],
+            vec!["333333-333333-333333", "444444-444444-444444"],
+            vec!["555555-555555-555555"],
+        ]
+    );
+
+    // invalid parameters
+    for (cursor, limit, source_uri) in
+        [(None, Some(0), None), (Some("xyz"), None, None), (None, None, Some("foo"))]
+    {
+        let err = ctx
+            .client
+            .list_streams(cursor, None, None, limit, None, None, None, source_uri, None)
+            .await
+            .unwrap_err();
+
+        assert_eq!(err.status(), Some(StatusCode::BAD_REQUEST));
+    }
+
+    // cursor can't be reused with different sort or order
+    let resp = ctx
+        .client
+        .list_streams(None, None, None, Some(2), None, None, None, None, None)
+        .await
+        .unwrap();
+    let cursor = resp.headers()["x-next-cursor"].to_str().unwrap().to_string();
+
+    for (order, sort) in
+        [(Some(ListStreamsOrder::Desc), None), (None, Some(ListStreamsSort::Owner))]
+    {
+        let err = ctx
+            .client
+            .list_streams(Some(&cursor), None, None, Some(2), order, sort, None, None, None)
+            .await
+            .unwrap_err();
+
+        assert_eq!(err.status(), Some(StatusCode::BAD_REQUEST));
+    }
+}
+
+#[test_context(ApiTestContext)]
+#[tokio::test]
+#[traced_test]
+async fn test_list_endpoints_query(ctx: &mut ApiTestContext) {
+    let endpoint = make_virtual_endpoint(SignalType::Sine);
+
+    ctx.client.update_endpoint("self", "123456-123456-123456", None, &endpoint).await.unwrap();
+
+    let uids = |endpoints: Vec<EndpointSpec>| -> Vec<String> {
+        endpoints.into_iter().map(|endpoint| endpoint.endpoint_uid).collect()
+    };
+
+    // GET /peers/{peer_uid}/endpoints?stream_direction=input
+    let resp = ctx
+        .client
+        .list_endpoints("self", None, None, None, None, None, None, Some(EndpointDir::Input))
+        .await
+        .unwrap();
+
+    assert_eq!(uids(resp.into_inner()), vec!["123456-123456-123456"]);
+
+    // GET /peers/{peer_uid}/endpoints?driver=pipewire
+    let resp = ctx
+        .client
+        .list_endpoints("self", None, Some(DriverId::Pipewire), None, None, None, None, None)
+        .await
+        .unwrap();
+
+    assert_eq!(uids(resp.into_inner()), vec!["444444-555555-666666"]);
+
+    // GET /peers/{peer_uid}/endpoints?sort=display_name&order=desc&limit=1
+    let resp = ctx
+        .client
+        .list_endpoints(
+            "self",
+            None,
+            None,
+            None,
+            Some(1),
+            Some(ListEndpointsOrder::Desc),
+            Some(ListEndpointsSort::DisplayName),
+            None,
+        )
+        .await
+        .unwrap();
+
+    let cursor = resp.headers()["x-next-cursor"].to_str().unwrap().to_string();
+
+    assert_eq!(uids(resp.into_inner()), vec!["123456-123456-123456"]);
+
+    // GET /peers/{peer_uid}/endpoints?sort=display_name&order=desc&cursor=...
+    let resp = ctx
+        .client
+        .list_endpoints(
+            "self",
+            Some(&cursor),
+            None,
+            None,
+            Some(1),
+            Some(ListEndpointsOrder::Desc),
+            Some(ListEndpointsSort::DisplayName),
+            None,
+        )
+        .await
+        .unwrap();
+
+    assert!(!resp.headers().contains_key("x-next-cursor"));
+    assert_eq!(uids(resp.into_inner()), vec!["444444-555555-666666"]);
 }
 
 #[test_context(ApiTestContext)]
 #[tokio::test]
 #[traced_test]
 async fn test_read_stream(ctx: &mut ApiTestContext) {
+    // GET /streams/{stream_uid}
+    let err = ctx.client.read_stream("777777-888888-999999").await.unwrap_err();
+
+    assert_eq!(err.status(), Some(StatusCode::NOT_FOUND));
+
+    // PUT /streams/{stream_uid}
+    let resp = ctx
+        .client
+        .update_stream("777777-888888-999999", None, &make_stream("777777-888888-999999"))
+        .await
+        .unwrap();
+
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.into_inner(), make_stream("777777-888888-999999"));
+
     // GET /streams/{stream_uid}
     let resp = ctx.client.read_stream("777777-888888-999999").await.unwrap();
 
     assert_eq!(resp.status(), StatusCode::OK);
-    assert_eq!(
-        resp.into_inner(),
-        StreamSpec {
-            stream_uri: "/streams/777777-888888-999999".into(),
-            stream_uid: "777777-888888-999999".into(),
-            source: ConnectionSpec::EndpointConnection {
-                connection_type: ConnectionType::Endpoint,
-                endpoint_uri: "/peers/111111-222222-333333/endpoints/444444-555555-666666"
-                    .into(),
-            },
-            destination: ConnectionSpec::ExternalConnection {
-                connection_type: ConnectionType::External,
-                media_uri: "rtp+rs8m://192.168.0.101:10000".into(),
-                repair_uri: "rs8m://192.168.0.101:10001".into(),
-                control_uri: "rtcp://192.168.0.101:10002".into(),
-            },
-        },
-    );
+    assert_eq!(resp.into_inner(), make_stream("777777-888888-999999"));
+}
+
+#[test_context(ApiTestContext)]
+#[tokio::test]
+#[traced_test]
+async fn test_update_stream_invalid(ctx: &mut ApiTestContext) {
+    // uid mismatch
+    let err = ctx
+        .client
+        .update_stream("777777-888888-999999", None, &make_stream("111111-222222-333333"))
+        .await
+        .unwrap_err();
+
+    assert_eq!(err.status(), Some(StatusCode::BAD_REQUEST));
+
+    // repair_uri missing for FEC scheme
+    let mut stream = make_stream("777777-888888-999999");
+    if let ConnectionSpec::ExternalConnection { repair_uri, .. } = &mut stream.destination {
+        *repair_uri = None;
+    }
+
+    let err =
+        ctx.client.update_stream("777777-888888-999999", None, &stream).await.unwrap_err();
+
+    assert_eq!(err.status(), Some(StatusCode::BAD_REQUEST));
+}
+
+#[test_context(ApiTestContext)]
+#[tokio::test]
+#[traced_test]
+async fn test_update_stream_ports(ctx: &mut ApiTestContext) {
+    let make_receiving_stream = |stream_uid: &str, port: u16| {
+        let mut stream = make_stream(stream_uid);
+        std::mem::swap(&mut stream.source, &mut stream.destination);
+        stream.source = ConnectionSpec::ExternalConnection {
+            connection_type: ConnectionType::External,
+            media_uri: format!("rtp+rs8m://0.0.0.0:{port}"),
+            repair_uri: Some("rs8m://0.0.0.0:0".into()),
+            control_uri: "rtcp://0.0.0.0:0".into(),
+            multicast_interface: None,
+            multicast_ttl: None,
+        };
+        stream
+    };
+
+    // PUT /streams/{stream_uid}
+    let resp = ctx
+        .client
+        .update_stream(
+            "777777-888888-999999",
+            None,
+            &make_receiving_stream("777777-888888-999999", 0),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let stream = resp.into_inner();
+    let ConnectionSpec::ExternalConnection { media_uri, repair_uri, control_uri, .. } =
+        &stream.source
+    else {
+        panic!("unexpected source: {:?}", stream.source);
+    };
+
+    let mut ports = Vec::new();
+    for uri in [media_uri, repair_uri.as_ref().unwrap(), control_uri] {
+        let port: u16 = uri.rsplit_once(':').unwrap().1.parse().unwrap();
+        assert!((10000..=10999).contains(&port), "{uri}");
+        assert!(!ports.contains(&port), "{uri}");
+        ports.push(port);
+    }
+
+    // GET /streams/{stream_uid}
+    let resp = ctx.client.read_stream("777777-888888-999999").await.unwrap();
+
+    assert_eq!(resp.into_inner(), stream);
+
+    // PUT /streams/{stream_uid} with port of another stream
+    let err = ctx
+        .client
+        .update_stream(
+            "111111-222222-333333",
+            None,
+            &make_receiving_stream("111111-222222-333333", ports[0]),
+        )
+        .await
+        .unwrap_err();
+
+    assert_eq!(err.status(), Some(StatusCode::CONFLICT));
+}
+
+#[test_context(ApiTestContext)]
+#[tokio::test]
+#[traced_test]
+async fn test_delete_stream(ctx: &mut ApiTestContext) {
+    // PUT /streams/{stream_uid}
+    ctx.client
+        .update_stream("777777-888888-999999", None, &make_stream("777777-888888-999999"))
+        .await
+        .unwrap();
+
+    // DELETE /streams/{stream_uid}
+    let resp = ctx.client.delete_stream("777777-888888-999999", None).await.unwrap();
+
+    assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+
+    // GET /streams/{stream_uid}
+    let err = ctx.client.read_stream("777777-888888-999999").await.unwrap_err();
+
+    assert_eq!(err.status(), Some(StatusCode::NOT_FOUND));
+
+    // DELETE /streams/{stream_uid}
+    let err = ctx.client.delete_stream("777777-888888-999999", None).await.unwrap_err();
+
+    assert_eq!(err.status(), Some(StatusCode::NOT_FOUND));
+}
+
+#[test_context(ApiTestContext)]
+#[tokio::test]
+#[traced_test]
+async fn test_stream_revision(ctx: &mut ApiTestContext) {
+    let stream_uid = "777777-888888-999999";
+    let mut stream = make_stream(stream_uid);
+
+    // PUT /streams/{stream_uid} with If-Match, but stream doesn't exist
+    let err = ctx.client.update_stream(stream_uid, Some("*"), &stream).await.unwrap_err();
+
+    assert_eq!(err.status(), Some(StatusCode::PRECONDITION_FAILED));
+
+    // PUT /streams/{stream_uid}
+    let resp = ctx.client.update_stream(stream_uid, None, &stream).await.unwrap();
+
+    assert_eq!(resp.headers()["etag"], "\"1\"");
+    assert_eq!(resp.into_inner().revision, Some(1));
+
+    // GET /streams/{stream_uid}
+    let resp = ctx.client.read_stream(stream_uid).await.unwrap();
+
+    assert_eq!(resp.headers()["etag"], "\"1\"");
+    let etag = resp.headers()["etag"].to_str().unwrap().to_string();
+
+    // PUT /streams/{stream_uid} with matching If-Match
+    stream.owner = Some("first".into());
+    let resp = ctx.client.update_stream(stream_uid, Some(&etag), &stream).await.unwrap();
+
+    assert_eq!(resp.headers()["etag"], "\"2\"");
+    assert_eq!(resp.into_inner().revision, Some(2));
+
+    // PUT /streams/{stream_uid} with stale If-Match
+    stream.owner = Some("second".into());
+    let err = ctx.client.update_stream(stream_uid, Some(&etag), &stream).await.unwrap_err();
+
+    assert_eq!(err.status(), Some(StatusCode::PRECONDITION_FAILED));
+
+    // weak etags never match, invalid etags are rejected
+    for (if_match, status) in [
+        ("W/\"2\"", StatusCode::PRECONDITION_FAILED),
+        ("2", StatusCode::BAD_REQUEST),
+        ("\"two\"", StatusCode::BAD_REQUEST),
+    ] {
+        let err =
+            ctx.client.update_stream(stream_uid, Some(if_match), &stream).await.unwrap_err();
+
+        assert_eq!(err.status(), Some(status));
+    }
+
+    // GET /streams
+    let resp = ctx
+        .client
+        .list_streams(None, None, None, None, None, None, None, None, None)
+        .await
+        .unwrap();
+
+    assert_eq!(resp.into_inner()[0].owner.as_deref(), Some("first"));
+
+    // DELETE /streams/{stream_uid} with stale If-Match
+    let err = ctx.client.delete_stream(stream_uid, Some(&etag)).await.unwrap_err();
+
+    assert_eq!(err.status(), Some(StatusCode::PRECONDITION_FAILED));
+
+    // DELETE /streams/{stream_uid} with one of matching etags
+    let resp = ctx.client.delete_stream(stream_uid, Some("\"1\", \"2\"")).await.unwrap();
+
+    assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+}
+
+#[test_context(ApiTestContext)]
+#[tokio::test]
+#[traced_test]
+async fn test_endpoint_revision(ctx: &mut ApiTestContext) {
+    let endpoint_uid = "123456-123456-123456";
+    let mut endpoint = make_virtual_endpoint(SignalType::Sine);
+
+    // PUT /peers/{peer_uid}/endpoints/{endpoint_uid}
+    ctx.client.update_endpoint("self", endpoint_uid, None, &endpoint).await.unwrap();
+
+    // GET /peers/{peer_uid}/endpoints/{endpoint_uid}
+    let resp = ctx.client.read_endpoint("self", endpoint_uid).await.unwrap();
+
+    assert_eq!(resp.headers()["etag"], "\"1\"");
+
+    // PUT /peers/{peer_uid}/endpoints/{endpoint_uid} with matching If-Match
+    endpoint.display_name = "First".into();
+    let resp = ctx
+        .client
+        .update_endpoint("self", endpoint_uid, Some("\"1\""), &endpoint)
+        .await
+        .unwrap();
+
+    assert_eq!(resp.headers()["etag"], "\"2\"");
+
+    // PUT /peers/{peer_uid}/endpoints/{endpoint_uid} with stale If-Match
+    endpoint.display_name = "Second".into();
+    let err = ctx
+        .client
+        .update_endpoint("self", endpoint_uid, Some("\"1\""), &endpoint)
+        .await
+        .unwrap_err();
+
+    assert_eq!(err.status(), Some(StatusCode::PRECONDITION_FAILED));
+
+    // DELETE /peers/{peer_uid}/endpoints/{endpoint_uid} with stale If-Match
+    let err =
+        ctx.client.delete_endpoint("self", endpoint_uid, Some("\"1\"")).await.unwrap_err();
+
+    assert_eq!(err.status(), Some(StatusCode::PRECONDITION_FAILED));
+
+    // DELETE /peers/{peer_uid}/endpoints/{endpoint_uid} with any revision
+    let resp = ctx.client.delete_endpoint("self", endpoint_uid, Some("*")).await.unwrap();
+
+    assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+}
+
+#[test_context(ApiTestContext)]
+#[tokio::test]
+#[traced_test]
+async fn test_peer_revision(ctx: &mut ApiTestContext) {
+    let peer = PeerSpec {
+        peer_uri: "/peers/777777-888888-999999".into(),
+        peer_uid: "777777-888888-999999".into(),
+        revision: None,
+    };
+
+    // GET /peers/{peer_uid}
+    let resp = ctx.client.read_peer("self").await.unwrap();
+
+    assert_eq!(resp.headers()["etag"], "\"0\"");
+
+    // PUT /peers/{peer_uid}
+    let resp = ctx.client.update_peer("self", Some("\"0\""), &peer).await.unwrap();
+
+    assert_eq!(resp.headers()["etag"], "\"1\"");
+    assert_eq!(resp.into_inner().revision, Some(1));
+
+    // PUT /peers/{peer_uid} with stale If-Match
+    let err = ctx.client.update_peer("self", Some("\"0\""), &peer).await.unwrap_err();
+
+    assert_eq!(err.status(), Some(StatusCode::PRECONDITION_FAILED));
+
+    // PUT /peers/{peer_uid} without If-Match
+    let resp = ctx.client.update_peer("self", None, &peer).await.unwrap();
+
+    assert_eq!(resp.headers()["etag"], "\"2\"");
+
+    // GET /peers
+    let resp = ctx.client.list_peers(None, None, None, None).await.unwrap();
+
+    assert_eq!(resp.into_inner(), vec![PeerSpec { revision: Some(2), ..peer.clone() }]);
+
+    // PUT /peers/{peer_uid} with UID mismatch
+    let err = ctx.client.update_peer("111111-222222-333333", None, &peer).await.unwrap_err();
+
+    assert_eq!(err.status(), Some(StatusCode::BAD_REQUEST));
+
+    // GET /peers/{peer_uid} for unknown peer
+    let err = ctx.client.read_peer("111111-222222-333333").await.unwrap_err();
+
+    assert_eq!(err.status(), Some(StatusCode::NOT_FOUND));
+}
+
+/// Send PATCH request with JSON Merge Patch.
+async fn merge_patch(
+    ctx: &ApiTestContext, path: &str, if_match: Option<&str>, patch: serde_json::Value,
+) -> reqwest::Response {
+    let mut request = reqwest::Client::new()
+        .patch(format!("{}{path}", ctx.server.api_url()))
+        .header("content-type", "application/merge-patch+json")
+        .body(patch.to_string());
+    if let Some(if_match) = if_match {
+        request = request.header("if-match", if_match);
+    }
+
+    request.send().await.unwrap()
+}
+
+#[test_context(ApiTestContext)]
+#[tokio::test]
+#[traced_test]
+async fn test_patch_stream(ctx: &mut ApiTestContext) {
+    let path = "/streams/777777-888888-999999";
+
+    // PATCH /streams/{stream_uid}
+    let resp = merge_patch(ctx, path, None, json!({"owner": "alice"})).await;
+
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+    // PUT /streams/{stream_uid}
+    ctx.client
+        .update_stream("777777-888888-999999", None, &make_stream("777777-888888-999999"))
+        .await
+        .unwrap();
+
+    // PATCH /streams/{stream_uid}
+    let resp = merge_patch(
+        ctx,
+        path,
+        Some("\"1\""),
+        json!({"owner": "alice", "lease_ttl": 30, "processing": {"gain": -3.0}}),
+    )
+    .await;
+
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.headers()["etag"], "\"2\"");
+
+    let stream: StreamSpec = resp.json().await.unwrap();
+
+    assert_eq!(stream.owner.as_deref(), Some("alice"));
+    assert_eq!(stream.lease_ttl, Some(30));
+    assert!(stream.lease_expiration.is_some());
+    assert_eq!(stream.processing.unwrap().gain, Some(-3.0));
+    assert_eq!(stream.destination, make_stream("777777-888888-999999").destination);
+
+    // PATCH /streams/{stream_uid}
+    let resp =
+        merge_patch(ctx, path, None, json!({"lease_ttl": null, "processing": null})).await;
+
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let stream: StreamSpec = resp.json().await.unwrap();
+
+    assert_eq!(
+        stream,
+        StreamSpec {
+            owner: Some("alice".into()),
+            revision: Some(3),
+            ..make_stream("777777-888888-999999")
+        }
+    );
+
+    // merged stream is invalid, or patch is malformed
+    for patch in [
+        json!({"lease_ttl": 0}),
+        json!({"source": null}),
+        json!({"destination": {"media_uri": "rtp+rs8m://192.168.0.101:0"}}),
+        json!({"stream_uid": "111111-222222-333333"}),
+        json!({"stream_uid": "111111-222222-333333",
+               "stream_uri": "/streams/111111-222222-333333"}),
+    ] {
+        let resp = merge_patch(ctx, path, None, patch).await;
+
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    }
+
+    let resp = reqwest::Client::new()
+        .patch(format!("{}{path}", ctx.server.api_url()))
+        .header("content-type", "application/merge-patch+json")
+        .body("{")
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    let resp = reqwest::Client::new()
+        .patch(format!("{}{path}", ctx.server.api_url()))
+        .header("content-type", "application/json")
+        .body("{}")
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(resp.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+
+    // PATCH /streams/{stream_uid} with stale If-Match
+    let resp = merge_patch(ctx, path, Some("\"2\""), json!({"owner": "bob"})).await;
+
+    assert_eq!(resp.status(), StatusCode::PRECONDITION_FAILED);
+
+    // failed requests didn't change stream
+    let resp = ctx.client.read_stream("777777-888888-999999").await.unwrap();
+
+    assert_eq!(resp.headers()["etag"], "\"3\"");
+    assert_eq!(resp.into_inner().owner.as_deref(), Some("alice"));
+}
+
+#[test_context(ApiTestContext)]
+#[tokio::test]
+#[traced_test]
+async fn test_stream_multicast(ctx: &mut ApiTestContext) {
+    let loopback_iface = rocd::io_streams::list_interfaces()
+        .unwrap()
+        .into_iter()
+        .find(|iface| iface.is_loopback && iface.ipv4_addr.is_some())
+        .expect("no loopback interface");
+
+    let multicast_connection =
+        |first_port: u16, multicast_ttl: Option<i32>| ConnectionSpec::ExternalConnection {
+            connection_type: ConnectionType::External,
+            media_uri: format!("rtp://239.255.42.11:{first_port}"),
+            repair_uri: None,
+            control_uri: format!("rtcp://239.255.42.11:{}", first_port + 1),
+            multicast_interface: Some(loopback_iface.name.clone()),
+            multicast_ttl,
+        };
+
+    // sending stream
+    let mut sender = make_stream("777777-888888-999999");
+    sender.destination = multicast_connection(10000, Some(4));
+
+    // receiving stream
+    let mut receiver = make_stream("111111-222222-333333");
+    receiver.destination = receiver.source.clone();
+    receiver.source = multicast_connection(10010, None);
+
+    for stream in [&sender, &receiver] {
+        // PUT /streams/{stream_uid}
+        let resp = ctx.client.update_stream(&stream.stream_uid, None, stream).await.unwrap();
+
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.into_inner().state, Some(StreamState::Running));
+    }
+
+    // interface and TTL are passed to transport as is
+    let to_dto = |connection: rocd::dto::ConnectionSpec| -> ConnectionSpec {
+        serde_json::from_value(serde_json::to_value(connection).unwrap()).unwrap()
+    };
+    let opened: Vec<ConnectionSpec> =
+        ctx.server.transport().opened_connections().into_iter().map(to_dto).collect();
+
+    assert_eq!(opened, vec![sender.destination, receiver.source]);
+}
+
+#[test_context(ApiTestContext)]
+#[tokio::test]
+#[traced_test]
+async fn test_stream_multicast_ttl(ctx: &mut ApiTestContext) {
+    let path = "/streams/777777-888888-999999";
+
+    let loopback_iface = rocd::io_streams::list_interfaces()
+        .unwrap()
+        .into_iter()
+        .find(|iface| iface.is_loopback && iface.ipv4_addr.is_some())
+        .expect("no loopback interface");
+
+    let mut stream = make_stream("777777-888888-999999");
+    stream.destination = ConnectionSpec::ExternalConnection {
+        connection_type: ConnectionType::External,
+        media_uri: "rtp+rs8m://239.255.42.10:10000".into(),
+        repair_uri: Some("rs8m://239.255.42.10:10001".into()),
+        control_uri: "rtcp://239.255.42.10:10002".into(),
+        multicast_interface: Some(loopback_iface.name.clone()),
+        multicast_ttl: None,
+    };
+
+    // PUT /streams/{stream_uid}
+    let resp = ctx.client.update_stream("777777-888888-999999", None, &stream).await.unwrap();
+
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    // PATCH /streams/{stream_uid}
+    // transport used for FEC can't apply multicast_ttl
+    let resp =
+        merge_patch(ctx, path, None, json!({"destination": {"multicast_ttl": 8}})).await;
+
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    // PUT /streams/{stream_uid}
+    if let ConnectionSpec::ExternalConnection { multicast_ttl, .. } = &mut stream.destination {
+        *multicast_ttl = Some(8);
+    }
+    let err =
+        ctx.client.update_stream("777777-888888-999999", None, &stream).await.unwrap_err();
+
+    assert_eq!(err.status(), Some(StatusCode::BAD_REQUEST));
+
+    // PUT /streams/{stream_uid}
+    // plain RTP transport applies multicast_ttl
+    stream.destination = ConnectionSpec::ExternalConnection {
+        connection_type: ConnectionType::External,
+        media_uri: "rtp://239.255.42.10:10000".into(),
+        repair_uri: None,
+        control_uri: "rtcp://239.255.42.10:10002".into(),
+        multicast_interface: Some(loopback_iface.name),
+        multicast_ttl: Some(8),
+    };
+    let resp = ctx.client.update_stream("777777-888888-999999", None, &stream).await.unwrap();
+
+    assert_eq!(resp.status(), StatusCode::OK);
+}
+
+#[test_context(ApiTestContext)]
+#[tokio::test]
+#[traced_test]
+async fn test_patch_endpoint(ctx: &mut ApiTestContext) {
+    let path = "/peers/self/endpoints/123456-123456-123456";
+
+    // PUT /peers/{peer_uid}/endpoints/{endpoint_uid}
+    ctx.client
+        .update_endpoint(
+            "self",
+            "123456-123456-123456",
+            None,
+            &make_virtual_endpoint(SignalType::Sine),
+        )
+        .await
+        .unwrap();
+
+    // PATCH /peers/{peer_uid}/endpoints/{endpoint_uid}
+    let resp = merge_patch(
+        ctx,
+        path,
+        Some("\"1\""),
+        json!({"display_name": "Quiet Tone", "virtual_source": {"level": -12.0}}),
+    )
+    .await;
+
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.headers()["etag"], "\"2\"");
+
+    let endpoint: EndpointSpec = resp.json().await.unwrap();
+
+    assert_eq!(endpoint.display_name, "Quiet Tone");
+    assert_eq!(endpoint.virtual_source.as_ref().unwrap().signal, SignalType::Sine);
+    assert_eq!(endpoint.virtual_source.as_ref().unwrap().level, Some(-12.0));
+
+    // GET /peers/{peer_uid}/endpoints/{endpoint_uid}
+    let resp = ctx.client.read_endpoint("self", "123456-123456-123456").await.unwrap();
+
+    assert_eq!(resp.into_inner(), endpoint);
+
+    // merged endpoint is invalid
+    for patch in [
+        json!({"endpoint_type": "system_device"}),
+        json!({"virtual_source": {"signal": "square"}}),
+        json!({"endpoint_uid": "111111-111111-111111"}),
+    ] {
+        let resp = merge_patch(ctx, path, None, patch).await;
+
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    }
+
+    // device endpoints can't be modified
+    let resp = merge_patch(
+        ctx,
+        "/peers/self/endpoints/444444-555555-666666",
+        None,
+        json!({"display_name": "Speakers"}),
+    )
+    .await;
+
+    assert_eq!(resp.status(), StatusCode::CONFLICT);
+
+    // PATCH /peers/{peer_uid}
+    let resp = merge_patch(ctx, "/peers/self", Some("\"0\""), json!({})).await;
+
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.headers()["etag"], "\"1\"");
+
+    // patched peer is stored
+    let resp = ctx.client.read_peer("self").await.unwrap();
+
+    assert_eq!(resp.headers()["etag"], "\"1\"");
+
+    // PATCH /peers/{peer_uid} with stale If-Match
+    let resp = merge_patch(ctx, "/peers/self", Some("\"0\""), json!({})).await;
+
+    assert_eq!(resp.status(), StatusCode::PRECONDITION_FAILED);
+
+    let resp =
+        merge_patch(ctx, "/peers/self", None, json!({"peer_uid": "111111-111111-111111"}))
+            .await;
+
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+}
+
+#[test_context(ApiTestContext)]
+#[tokio::test]
+#[traced_test]
+async fn test_stream_failed(ctx: &mut ApiTestContext) {
+    // mock transport doesn't support ldpc
+    let mut stream = make_stream("777777-888888-999999");
+    stream.destination = ConnectionSpec::ExternalConnection {
+        connection_type: ConnectionType::External,
+        media_uri: "rtp+ldpc://192.168.0.101:10000".into(),
+        repair_uri: Some("ldpc://192.168.0.101:10001".into()),
+        control_uri: "rtcp://192.168.0.101:10002".into(),
+        multicast_interface: None,
+        multicast_ttl: None,
+    };
+
+    // PUT /streams/{stream_uid}
+    let resp = ctx.client.update_stream("777777-888888-999999", None, &stream).await.unwrap();
+
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let failed_stream = resp.into_inner();
+    assert_eq!(failed_stream.state, Some(StreamState::Failed));
+    assert_eq!(
+        failed_stream.error.as_deref(),
+        Some("no transport supports rtp+ldpc://192.168.0.101:10000")
+    );
+
+    // GET /streams/{stream_uid}
+    let resp = ctx.client.read_stream("777777-888888-999999").await.unwrap();
+
+    assert_eq!(resp.into_inner(), failed_stream);
+
+    // PUT /streams/{stream_uid}
+    let resp = ctx
+        .client
+        .update_stream("777777-888888-999999", None, &make_stream("777777-888888-999999"))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        resp.into_inner(),
+        StreamSpec { revision: Some(2), ..make_stream("777777-888888-999999") }
+    );
+}
+
+#[test_context(ApiTestContext)]
+#[tokio::test]
+#[traced_test]
+async fn test_stream_lease(ctx: &mut ApiTestContext) {
+    let mut leased_stream = make_stream("777777-888888-999999");
+    leased_stream.owner = Some("test_owner".into());
+    leased_stream.lease_ttl = Some(2);
+
+    // PUT /streams/{stream_uid}
+    let resp =
+        ctx.client.update_stream("777777-888888-999999", None, &leased_stream).await.unwrap();
+
+    let expiration = resp.into_inner().lease_expiration.unwrap();
+
+    // PUT /streams/{stream_uid}
+    ctx.client
+        .update_stream("111111-222222-333333", None, &make_stream("111111-222222-333333"))
+        .await
+        .unwrap();
+
+    // POST /streams/{stream_uid}/renew
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let resp = ctx.client.renew_stream("777777-888888-999999").await.unwrap();
+
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let stream = resp.into_inner();
+
+    assert_eq!(stream.owner, Some("test_owner".into()));
+    assert_ne!(stream.lease_expiration.unwrap(), expiration);
+
+    // POST /streams/{stream_uid}/renew for stream without lease
+    let err = ctx.client.renew_stream("111111-222222-333333").await.unwrap_err();
+
+    assert_eq!(err.status(), Some(StatusCode::CONFLICT));
+
+    // wait until leased stream is reaped
+    for _ in 0..100 {
+        if ctx.client.read_stream("777777-888888-999999").await.is_err() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+
+    let err = ctx.client.read_stream("777777-888888-999999").await.unwrap_err();
+
+    assert_eq!(err.status(), Some(StatusCode::NOT_FOUND));
+
+    // stream without lease is kept
+    let resp = ctx
+        .client
+        .list_streams(None, None, None, None, None, None, None, None, None)
+        .await
+        .unwrap();
+
+    assert_eq!(resp.into_inner(), vec![make_stream("111111-222222-333333")]);
+}
+
+#[test_context(ApiTestContext)]
+#[tokio::test]
+#[traced_test]
+async fn test_stream_schedule(ctx: &mut ApiTestContext) {
+    let now = OffsetDateTime::now_utc();
+    let start_time = (now + Duration::from_secs(1)).format(&Rfc3339).unwrap();
+    let stop_time = (now + Duration::from_secs(2)).format(&Rfc3339).unwrap();
+
+    let mut scheduled_stream = make_stream("777777-888888-999999");
+    scheduled_stream.schedule = Some(ScheduleSpec {
+        start_time: Some(start_time.clone()),
+        stop_time: Some(stop_time.clone()),
+        duration: None,
+        cron: None,
+    });
+
+    // PUT /streams/{stream_uid}
+    let resp = ctx
+        .client
+        .update_stream("777777-888888-999999", None, &scheduled_stream)
+        .await
+        .unwrap();
+
+    let stream = resp.into_inner();
+
+    assert_eq!(stream.state, Some(StreamState::Stopped));
+    assert_eq!(
+        stream.next_transition,
+        Some(TransitionSpec { state: StreamState::Running, time: start_time })
+    );
+
+    // wait until started
+    let mut stream = stream;
+    for _ in 0..50 {
+        stream = ctx.client.read_stream("777777-888888-999999").await.unwrap().into_inner();
+        if stream.state == Some(StreamState::Running) {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+
+    assert_eq!(stream.state, Some(StreamState::Running));
+    assert_eq!(
+        stream.next_transition,
+        Some(TransitionSpec { state: StreamState::Stopped, time: stop_time })
+    );
+
+    // wait until stopped
+    for _ in 0..50 {
+        stream = ctx.client.read_stream("777777-888888-999999").await.unwrap().into_inner();
+        if stream.state == Some(StreamState::Stopped) {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+
+    assert_eq!(stream.state, Some(StreamState::Stopped));
+    assert_eq!(stream.next_transition, None);
+
+    // invalid schedule
+    scheduled_stream.schedule = Some(ScheduleSpec {
+        start_time: None,
+        stop_time: None,
+        duration: None,
+        cron: Some("0 9 * * *".into()),
+    });
+
+    let err = ctx
+        .client
+        .update_stream("777777-888888-999999", None, &scheduled_stream)
+        .await
+        .unwrap_err();
+
+    assert_eq!(err.status(), Some(StatusCode::BAD_REQUEST));
+}
+
+#[test_context(ApiTestContext)]
+#[tokio::test]
+#[traced_test]
+async fn test_stream_processing(ctx: &mut ApiTestContext) {
+    let endpoint = make_virtual_endpoint(SignalType::Sine);
+
+    ctx.client.update_endpoint("self", "123456-123456-123456", None, &endpoint).await.unwrap();
+
+    let mut stream = make_stream("777777-888888-999999");
+    stream.source = ConnectionSpec::EndpointConnection {
+        connection_type: ConnectionType::Endpoint,
+        endpoint_uri: endpoint.endpoint_uri.clone(),
+    };
+    stream.recording = Some(RecordingSpec {
+        format: RecordingFormat::Wav,
+        rotation_interval: None,
+        max_files: None,
+        max_age: None,
+        metadata: Default::default(),
+    });
+    stream.processing = Some(ProcessingSpec {
+        gain: Some(6.0),
+        mute: Some(false),
+        limiter_threshold: None,
+        loudness_target: None,
+    });
+
+    // PUT /streams/{stream_uid}
+    let resp = ctx.client.update_stream("777777-888888-999999", None, &stream).await.unwrap();
+
+    assert_eq!(resp.into_inner(), stream);
+
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    // mute running stream
+    stream.processing.as_mut().unwrap().mute = Some(true);
+    stream.revision = Some(2);
+
+    // PUT /streams/{stream_uid}
+    let resp = ctx.client.update_stream("777777-888888-999999", None, &stream).await.unwrap();
+
+    assert_eq!(resp.into_inner(), stream);
+
+    // invalid gain
+    let mut bad_stream = stream.clone();
+    bad_stream.processing.as_mut().unwrap().gain = Some(100.0);
+
+    let err =
+        ctx.client.update_stream("777777-888888-999999", None, &bad_stream).await.unwrap_err();
+
+    assert_eq!(err.status(), Some(StatusCode::BAD_REQUEST));
+
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    // stop recording
+    ctx.client
+        .update_stream("777777-888888-999999", None, &make_stream("777777-888888-999999"))
+        .await
+        .unwrap();
+
+    // pipeline wasn't restarted, so there is only one recording
+    let recordings = ctx.client.list_recordings("777777-888888-999999").await.unwrap();
+    let mut recordings = recordings.into_inner();
+
+    assert_eq!(recordings.len(), 1);
+
+    let recording_id = recordings.pop().unwrap().recording_id;
+    let data = reqwest::get(format!(
+        "{}/streams/777777-888888-999999/recordings/{recording_id}",
+        ctx.server.api_url()
+    ))
+    .await
+    .unwrap()
+    .bytes()
+    .await
+    .unwrap();
+
+    // tone is amplified by 6dB, then muted
+    let data_offset = data.windows(4).position(|w| w == b"data").unwrap() + 8;
+    let samples: Vec<i16> = data[data_offset..]
+        .chunks_exact(2)
+        .map(|b| i16::from_le_bytes([b[0], b[1]]))
+        .collect();
+    let peak = samples[..samples.len() / 4].iter().map(|s| s.unsigned_abs()).max().unwrap();
+
+    assert!((6400..6600).contains(&peak), "peak={peak}");
+    assert!(samples[samples.len() * 3 / 4..].iter().all(|s| *s == 0));
+}
+
+/// Read server-sent events until event of given type arrives.
+async fn wait_event(
+    events: &mut reqwest::Response, buf: &mut String, event_type: &str,
+) -> EventSpec {
+    let line = format!("event: {event_type}\n");
+
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while !buf.contains(&line) {
+            let chunk = events.chunk().await.unwrap().unwrap();
+            buf.push_str(std::str::from_utf8(&chunk).unwrap());
+        }
+    })
+    .await
+    .unwrap();
+
+    // consume everything up to and including found event
+    let pos = buf.find(&line).unwrap() + line.len();
+    let end = pos + buf[pos..].find("\n\n").unwrap() + 2;
+    let data = buf[pos..end].trim().strip_prefix("data: ").unwrap().to_string();
+    buf.drain(..end);
+
+    serde_json::from_str(&data).unwrap()
+}
+
+#[test_context(ApiTestContext)]
+#[tokio::test]
+#[traced_test]
+async fn test_stream_silence(ctx: &mut ApiTestContext) {
+    let endpoint = make_virtual_endpoint(SignalType::Sine);
+
+    ctx.client.update_endpoint("self", "123456-123456-123456", None, &endpoint).await.unwrap();
+
+    // GET /events
+    let mut events = reqwest::get(format!("{}/events", ctx.server.api_url())).await.unwrap();
+
+    assert_eq!(events.status(), StatusCode::OK);
+    assert_eq!(events.headers()["content-type"], "text/event-stream");
+
+    let mut buf = String::new();
+
+    let mut stream = make_stream("777777-888888-999999");
+    stream.source = ConnectionSpec::EndpointConnection {
+        connection_type: ConnectionType::Endpoint,
+        endpoint_uri: endpoint.endpoint_uri.clone(),
+    };
+    stream.silence_detection =
+        Some(SilenceDetectionSpec { threshold: Some(-40.0), hold_time: Some(0.2) });
+
+    // PUT /streams/{stream_uid}
+    let resp = ctx.client.update_stream("777777-888888-999999", None, &stream).await.unwrap();
+
+    assert_eq!(resp.into_inner(), stream);
+
+    // mute source, stream should pause after hold time
+    stream.processing = Some(ProcessingSpec {
+        gain: None,
+        mute: Some(true),
+        limiter_threshold: None,
+        loudness_target: None,
+    });
+
+    ctx.client.update_stream("777777-888888-999999", None, &stream).await.unwrap();
+
+    let event = wait_event(&mut events, &mut buf, "stream_paused").await;
+
+    assert_eq!(event.object_uri, stream.stream_uri);
+
+    // GET /streams/{stream_uid}
+    let resp = ctx.client.read_stream("777777-888888-999999").await.unwrap();
+
+    assert_eq!(resp.into_inner().state, Some(StreamState::Paused));
+
+    // unmute source, stream should resume
+    stream.processing.as_mut().unwrap().mute = Some(false);
+
+    ctx.client.update_stream("777777-888888-999999", None, &stream).await.unwrap();
+
+    let event = wait_event(&mut events, &mut buf, "stream_resumed").await;
+
+    assert_eq!(event.object_uri, stream.stream_uri);
+
+    // GET /streams/{stream_uid}
+    let resp = ctx.client.read_stream("777777-888888-999999").await.unwrap();
+
+    assert_eq!(resp.into_inner().state, Some(StreamState::Running));
+
+    // silence detection in receiving stream
+    let mut bad_stream = stream.clone();
+    std::mem::swap(&mut bad_stream.source, &mut bad_stream.destination);
+
+    let err =
+        ctx.client.update_stream("777777-888888-999999", None, &bad_stream).await.unwrap_err();
+
+    assert_eq!(err.status(), Some(StatusCode::BAD_REQUEST));
+}
+
+#[test_context(ApiTestContext)]
+#[tokio::test]
+#[traced_test]
+async fn test_stream_failover(ctx: &mut ApiTestContext) {
+    let endpoint = make_virtual_endpoint(SignalType::Sine);
+
+    ctx.client.update_endpoint("self", "123456-123456-123456", None, &endpoint).await.unwrap();
+
+    // GET /events
+    let mut events = reqwest::get(format!("{}/events", ctx.server.api_url())).await.unwrap();
+    let mut buf = String::new();
+
+    // nobody sends to receiver yet, so it plays tone from failover source
+    let mut receiver = make_receiving_stream("111111-222222-333333", 10000);
+    receiver.failover = Some(FailoverSpec {
+        sources: vec![ConnectionSpec::EndpointConnection {
+            connection_type: ConnectionType::Endpoint,
+            endpoint_uri: endpoint.endpoint_uri.clone(),
+        }],
+        silence_threshold: Some(-40.0),
+        silence_timeout: Some(0.2),
+        recovery_time: Some(0.3),
+    });
+
+    // PUT /streams/{stream_uid}
+    let resp =
+        ctx.client.update_stream("111111-222222-333333", None, &receiver).await.unwrap();
+
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.into_inner().failover, receiver.failover);
+
+    let event = wait_event(&mut events, &mut buf, "stream_source_switched").await;
+
+    assert_eq!(event.object_uri, receiver.stream_uri);
+    assert_eq!(
+        event.source_switch,
+        Some(SourceSwitchSpec {
+            previous_source: 0,
+            active_source: 1,
+            reason: SwitchReason::Silent,
+        })
+    );
+
+    // GET /streams/{stream_uid}
+    let resp = ctx.client.read_stream("111111-222222-333333").await.unwrap();
+
+    assert_eq!(resp.into_inner().active_source, Some(1));
+
+    // sender is looped back to receiver by mock transport,
+    // receiver switches back after recovery time
+    let mut sender = make_stream("777777-888888-999999");
+    sender.source = ConnectionSpec::EndpointConnection {
+        connection_type: ConnectionType::Endpoint,
+        endpoint_uri: endpoint.endpoint_uri.clone(),
+    };
+
+    ctx.client.update_stream("777777-888888-999999", None, &sender).await.unwrap();
+
+    let event = wait_event(&mut events, &mut buf, "stream_source_switched").await;
+
+    assert_eq!(
+        event.source_switch,
+        Some(SourceSwitchSpec {
+            previous_source: 1,
+            active_source: 0,
+            reason: SwitchReason::Recovered,
+        })
+    );
+
+    // GET /streams/{stream_uid}
+    let resp = ctx.client.read_stream("111111-222222-333333").await.unwrap();
+
+    assert_eq!(resp.into_inner().active_source, Some(0));
+
+    // failover source same as source
+    let mut bad_stream = receiver.clone();
+    bad_stream.failover.as_mut().unwrap().sources = vec![receiver.source.clone()];
+
+    let err =
+        ctx.client.update_stream("111111-222222-333333", None, &bad_stream).await.unwrap_err();
+
+    assert_eq!(err.status(), Some(StatusCode::BAD_REQUEST));
+
+    // failover source uses port of other stream
+    let mut bad_stream = make_receiving_stream("444444-555555-666666", 10010);
+    bad_stream.failover = receiver.failover.clone();
+    bad_stream.failover.as_mut().unwrap().sources = vec![receiver.source.clone()];
+
+    let err =
+        ctx.client.update_stream("444444-555555-666666", None, &bad_stream).await.unwrap_err();
+
+    assert_eq!(err.status(), Some(StatusCode::CONFLICT));
+}
+
+#[test_context(ApiTestContext)]
+#[tokio::test]
+#[traced_test]
+async fn test_stream_levels(ctx: &mut ApiTestContext) {
+    let endpoint = make_virtual_endpoint(SignalType::Sine);
+
+    ctx.client.update_endpoint("self", "123456-123456-123456", None, &endpoint).await.unwrap();
+
+    let mut stream = make_stream("777777-888888-999999");
+    stream.source = ConnectionSpec::EndpointConnection {
+        connection_type: ConnectionType::Endpoint,
+        endpoint_uri: endpoint.endpoint_uri.clone(),
+    };
+    stream.processing = Some(ProcessingSpec {
+        gain: Some(-6.0),
+        mute: None,
+        limiter_threshold: None,
+        loudness_target: None,
+    });
+
+    // PUT /streams/{stream_uid}
+    ctx.client.update_stream("777777-888888-999999", None, &stream).await.unwrap();
+
+    // GET /streams/{stream_uid}/levels
+    let resp = ctx.client.read_stream_levels("777777-888888-999999").await.unwrap();
+    let levels = resp.into_inner();
+
+    assert_eq!(levels.len(), 2);
+
+    // stream levels are measured after processing, endpoint levels before
+    for (level, object_uri, gain) in [
+        (&levels[0], stream.stream_uri.as_str(), -6.0),
+        (&levels[1], endpoint.endpoint_uri.as_str(), 0.0),
+    ] {
+        assert_eq!(level.object_uri, object_uri);
+        assert_eq!(level.channels.len(), 2);
+
+        // -20dBFS sine, RMS is 3dB lower than peak
+        for channel in &level.channels {
+            assert!((channel.peak - (gain - 20.0)).abs() < 0.5, "{level:?}");
+            assert!((channel.rms - (gain - 23.0)).abs() < 0.5, "{level:?}");
+        }
+    }
+
+    // GET /events?levels=true
+    let mut events =
+        reqwest::get(format!("{}/events?levels=true", ctx.server.api_url())).await.unwrap();
+    let mut buf = String::new();
+
+    let event = wait_event(&mut events, &mut buf, "levels_updated").await;
+    let levels = event.levels.unwrap();
+
+    assert_eq!(levels.object_uri, event.object_uri);
+    assert_eq!(levels.channels.len(), 2);
+
+    // GET /streams/{stream_uid}/levels
+    let err = ctx.client.read_stream_levels("111111-222222-333333").await.unwrap_err();
+
+    assert_eq!(err.status(), Some(StatusCode::NOT_FOUND));
+}
+
+#[test_context(ApiTestContext)]
+#[tokio::test]
+#[traced_test]
+async fn test_stream_latency(ctx: &mut ApiTestContext) {
+    let endpoint = make_virtual_endpoint(SignalType::Sine);
+
+    ctx.client.update_endpoint("self", "123456-123456-123456", None, &endpoint).await.unwrap();
+
+    // receiver is created first, so that sent audio isn't queued before it starts
+    let mut receiver = make_receiving_stream("111111-222222-333333", 10000);
+
+    // PUT /streams/{stream_uid}
+    ctx.client.update_stream("111111-222222-333333", None, &receiver).await.unwrap();
+
+    // sender is looped back to receiver by mock transport
+    let mut sender = make_stream("777777-888888-999999");
+    sender.source = ConnectionSpec::EndpointConnection {
+        connection_type: ConnectionType::Endpoint,
+        endpoint_uri: endpoint.endpoint_uri.clone(),
+    };
+
+    // PUT /streams/{stream_uid}
+    ctx.client.update_stream("777777-888888-999999", None, &sender).await.unwrap();
+
+    // POST /streams/{stream_uid}/measure-latency
+    let resp = ctx
+        .client
+        .measure_stream_latency(
+            "777777-888888-999999",
+            Some("111111-222222-333333"),
+            Some(1.0),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let report = resp.into_inner();
+
+    assert_eq!(report.stream_uri, sender.stream_uri);
+    assert_eq!(report.detector_stream_uri, receiver.stream_uri);
+    assert!(report.measured_latency >= 0.0, "{report:?}");
+    assert!(report.measured_latency < 0.1, "{report:?}");
+    assert_eq!(report.target_latency, Some(0.01));
+    assert!(report.estimated_latency.is_some(), "{report:?}");
+
+    // sender source doesn't receive its own marker
+    let err = ctx
+        .client
+        .measure_stream_latency("777777-888888-999999", None, Some(0.5))
+        .await
+        .unwrap_err();
+
+    assert_eq!(err.status(), Some(StatusCode::CONFLICT));
+
+    // invalid duration
+    let err = ctx
+        .client
+        .measure_stream_latency("777777-888888-999999", None, Some(60.0))
+        .await
+        .unwrap_err();
+
+    assert_eq!(err.status(), Some(StatusCode::BAD_REQUEST));
+
+    // detector stream is not running, mock transport doesn't support ldpc
+    receiver.source = ConnectionSpec::ExternalConnection {
+        connection_type: ConnectionType::External,
+        media_uri: "rtp+ldpc://0.0.0.0:10000".into(),
+        repair_uri: Some("ldpc://0.0.0.0:10001".into()),
+        control_uri: "rtcp://0.0.0.0:10002".into(),
+        multicast_interface: None,
+        multicast_ttl: None,
+    };
+
+    ctx.client.update_stream("111111-222222-333333", None, &receiver).await.unwrap();
+
+    let err = ctx
+        .client
+        .measure_stream_latency("777777-888888-999999", Some("111111-222222-333333"), None)
+        .await
+        .unwrap_err();
+
+    assert_eq!(err.status(), Some(StatusCode::CONFLICT));
+
+    // detector stream doesn't exist
+    let err = ctx
+        .client
+        .measure_stream_latency("777777-888888-999999", Some("123456-123456-123456"), None)
+        .await
+        .unwrap_err();
+
+    assert_eq!(err.status(), Some(StatusCode::NOT_FOUND));
+}
+
+/// Wait until group members have given alignment delays, in seconds.
+async fn wait_group_delays(ctx: &ApiTestContext, delays: &[f32]) -> GroupStatusSpec {
+    tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            // GET /groups/{group_uid}/status
+            let resp = ctx.client.read_group_status("777777-888888-999999").await.unwrap();
+            let status = resp.into_inner();
+
+            if status.members.len() == delays.len()
+                && status.members.iter().zip(delays).all(|(member, delay)| {
+                    member
+                        .delay
+                        .is_some_and(|member_delay| (member_delay - delay).abs() < 0.002)
+                })
+            {
+                break status;
+            }
+
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+    })
+    .await
+    .unwrap()
+}
+
+#[test_context(ApiTestContext)]
+#[tokio::test]
+#[traced_test]
+async fn test_groups(ctx: &mut ApiTestContext) {
+    // GET /groups
+    let resp = ctx.client.list_groups().await.unwrap();
+
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.into_inner(), vec![]);
+
+    // receivers report different latencies; they differ by more than
+    // aligner applies gradually, so that delays are changed at once
+    ctx.server.transport().set_latency(10000, Duration::from_millis(100));
+    ctx.server.transport().set_latency(10010, Duration::from_millis(10));
+
+    for (stream_uid, port) in
+        [("111111-222222-333333", 10000), ("444444-555555-666666", 10010)]
+    {
+        let stream = make_receiving_stream(stream_uid, port);
+
+        // PUT /streams/{stream_uid}
+        ctx.client.update_stream(stream_uid, None, &stream).await.unwrap();
+    }
+
+    let mut group = GroupSpec {
+        group_uri: "/groups/777777-888888-999999".into(),
+        group_uid: "777777-888888-999999".into(),
+        members: vec![
+            "/streams/111111-222222-333333".into(),
+            "/streams/444444-555555-666666".into(),
+        ],
+        target_latency: Some(0.2),
+    };
+
+    // PUT /groups/{group_uid}
+    let resp = ctx.client.update_group("777777-888888-999999", &group).await.unwrap();
+
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.into_inner(), group);
+
+    // GET /groups
+    let resp = ctx.client.list_groups().await.unwrap();
+
+    assert_eq!(resp.into_inner(), vec![group.clone()]);
+
+    // each member is delayed by difference between target and its latency
+    let status = wait_group_delays(ctx, &[0.1, 0.19]).await;
+
+    assert_eq!(status.group_uri, group.group_uri);
+    assert_eq!(status.members.len(), 2);
+
+    for (member, stream_uri) in status.members.iter().zip(&group.members) {
+        assert_eq!(&member.stream_uri, stream_uri);
+        assert!((member.latency.unwrap() - 0.2).abs() < 0.002, "{member:?}");
+    }
+
+    // without target, faster member is delayed to latency of slower one
+    group.target_latency = None;
+
+    // PUT /groups/{group_uid}
+    ctx.client.update_group("777777-888888-999999", &group).await.unwrap();
+
+    let status = wait_group_delays(ctx, &[0.0, 0.09]).await;
+
+    for member in &status.members {
+        assert!((member.latency.unwrap() - 0.1).abs() < 0.002, "{member:?}");
+    }
+
+    // sending stream can't be member
+    ctx.client
+        .update_stream("123456-123456-123456", None, &make_stream("123456-123456-123456"))
+        .await
+        .unwrap();
+
+    let mut bad_group = group.clone();
+    bad_group.members.push("/streams/123456-123456-123456".into());
+
+    let err = ctx.client.update_group("777777-888888-999999", &bad_group).await.unwrap_err();
+
+    assert_eq!(err.status(), Some(StatusCode::BAD_REQUEST));
+
+    // unknown stream can't be member
+    let mut bad_group = group.clone();
+    bad_group.members.push("/streams/aaaaaa-bbbbbb-cccccc".into());
+
+    let err = ctx.client.update_group("777777-888888-999999", &bad_group).await.unwrap_err();
+
+    assert_eq!(err.status(), Some(StatusCode::BAD_REQUEST));
+
+    // stream can't be member of two groups
+    let mut other_group = group.clone();
+    other_group.group_uri = "/groups/123456-123456-123456".into();
+    other_group.group_uid = "123456-123456-123456".into();
+
+    let err = ctx.client.update_group("123456-123456-123456", &other_group).await.unwrap_err();
+
+    assert_eq!(err.status(), Some(StatusCode::CONFLICT));
+
+    // DELETE /streams/{stream_uid}
+    ctx.client.delete_stream("444444-555555-666666", None).await.unwrap();
+
+    // GET /groups/{group_uid}
+    let resp = ctx.client.read_group("777777-888888-999999").await.unwrap();
+
+    assert_eq!(resp.into_inner().members, vec!["/streams/111111-222222-333333".to_string()]);
+
+    // DELETE /groups/{group_uid}
+    let resp = ctx.client.delete_group("777777-888888-999999").await.unwrap();
+
+    assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+
+    // GET /groups/{group_uid}
+    let err = ctx.client.read_group("777777-888888-999999").await.unwrap_err();
+
+    assert_eq!(err.status(), Some(StatusCode::NOT_FOUND));
+
+    // GET /groups/{group_uid}/status
+    let err = ctx.client.read_group_status("777777-888888-999999").await.unwrap_err();
+
+    assert_eq!(err.status(), Some(StatusCode::NOT_FOUND));
+}
+
+#[test_context(ApiTestContext)]
+#[tokio::test]
+#[traced_test]
+async fn test_stream_recordings(ctx: &mut ApiTestContext) {
+    // GET /streams/{stream_uid}/recordings
+    let resp = ctx.client.list_recordings("777777-888888-999999").await.unwrap();
+
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.into_inner(), vec![]);
+
+    let mut stream = make_stream("777777-888888-999999");
+    stream.recording = Some(RecordingSpec {
+        format: RecordingFormat::Wav,
+        rotation_interval: None,
+        max_files: None,
+        max_age: None,
+        metadata: [("title".into(), "Studio A".into())].into(),
+    });
+
+    // PUT /streams/{stream_uid}
+    let resp = ctx.client.update_stream("777777-888888-999999", None, &stream).await.unwrap();
+
+    assert_eq!(resp.into_inner(), stream);
+
+    // GET /streams/{stream_uid}/recordings
+    let recordings = ctx.client.list_recordings("777777-888888-999999").await.unwrap();
+    let recordings = recordings.into_inner();
+
+    assert_eq!(recordings.len(), 1);
+    assert_eq!(recordings[0].stream_uid, "777777-888888-999999");
+    assert_eq!(recordings[0].format, RecordingFormat::Wav);
+    assert!(recordings[0].in_progress);
+
+    let recording_id = recordings[0].recording_id.clone();
+
+    // DELETE /streams/{stream_uid}/recordings/{recording_id}
+    let err =
+        ctx.client.delete_recording("777777-888888-999999", &recording_id).await.unwrap_err();
+
+    assert_eq!(err.status(), Some(StatusCode::CONFLICT));
+
+    // PUT /streams/{stream_uid}
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    ctx.client
+        .update_stream("777777-888888-999999", None, &make_stream("777777-888888-999999"))
+        .await
+        .unwrap();
+
+    // GET /streams/{stream_uid}/recordings
+    let recordings = ctx.client.list_recordings("777777-888888-999999").await.unwrap();
+    let recording = recordings.into_inner().pop().unwrap();
+
+    assert_eq!(recording.recording_id, recording_id);
+    assert!(!recording.in_progress);
+
+    // GET /streams/{stream_uid}/recordings/{recording_id}
+    let resp = reqwest::get(format!(
+        "{}/streams/777777-888888-999999/recordings/{recording_id}",
+        ctx.server.api_url()
+    ))
+    .await
+    .unwrap();
+
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.headers()["content-type"], "audio/wav");
+
+    let data = resp.bytes().await.unwrap();
+
+    assert_eq!(data.len() as i64, recording.size);
+    assert_eq!(&data[0..4], b"RIFF");
+    assert!(data.windows(8).any(|w| w == b"Studio A"));
+
+    // GET /streams/{stream_uid}/recordings/{recording_id}
+    let result = ctx.client.download_recording("777777-888888-999999", "../../rocd.db").await;
+
+    assert_eq!(result.err().and_then(|err| err.status()), Some(StatusCode::BAD_REQUEST));
+
+    // DELETE /streams/{stream_uid}/recordings/{recording_id}
+    let resp =
+        ctx.client.delete_recording("777777-888888-999999", &recording_id).await.unwrap();
+
+    assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+
+    let result = ctx.client.download_recording("777777-888888-999999", &recording_id).await;
+
+    assert_eq!(result.err().and_then(|err| err.status()), Some(StatusCode::NOT_FOUND));
+
+    // GET /streams/{stream_uid}/recordings
+    let resp = ctx.client.list_recordings("777777-888888-999999").await.unwrap();
+
+    assert_eq!(resp.into_inner(), vec![]);
+}
+
+#[test_context(ApiTestContext)]
+#[tokio::test]
+#[traced_test]
+async fn test_export_stream_sdp(ctx: &mut ApiTestContext) {
+    // PUT /streams/{stream_uid}
+    ctx.client
+        .update_stream("777777-888888-999999", None, &make_stream("777777-888888-999999"))
+        .await
+        .unwrap();
+
+    // GET /streams/{stream_uid}/sdp
+    let resp =
+        reqwest::get(format!("{}/streams/777777-888888-999999/sdp", ctx.server.api_url()))
+            .await
+            .unwrap();
+
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.headers()["content-type"], "application/sdp");
+
+    let sdp = resp.text().await.unwrap();
+
+    assert!(sdp.contains("c=IN IP4 192.168.0.101\r\n"));
+    assert!(sdp.contains("m=audio 10000 RTP/AVP 10\r\n"));
+    assert!(sdp.contains("a=rtcp:10002\r\n"));
+    assert!(sdp.contains("m=application 10001 UDP/FEC\r\n"));
+
+    // GET /streams/{stream_uid}/sdp
+    let result = ctx.client.export_stream_sdp("111111-222222-333333").await;
+
+    assert_eq!(result.err().and_then(|err| err.status()), Some(StatusCode::NOT_FOUND));
+}
+
+#[test_context(ApiTestContext)]
+#[tokio::test]
+#[traced_test]
+async fn test_export_stream_sdp_hostname(ctx: &mut ApiTestContext) {
+    let mut stream = make_stream("777777-888888-999999");
+    stream.destination = ConnectionSpec::ExternalConnection {
+        connection_type: ConnectionType::External,
+        media_uri: "rtp://myhost.local:10000".into(),
+        repair_uri: None,
+        control_uri: "rtcp://myhost.local:10001".into(),
+        multicast_interface: None,
+        multicast_ttl: None,
+    };
+
+    // PUT /streams/{stream_uid}
+    let resp = ctx.client.update_stream("777777-888888-999999", None, &stream).await.unwrap();
+
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    // GET /streams/{stream_uid}/sdp
+    let result = ctx.client.export_stream_sdp("777777-888888-999999").await;
+
+    assert_eq!(result.err().and_then(|err| err.status()), Some(StatusCode::BAD_REQUEST));
+}
+
+#[test_context(ApiTestContext)]
+#[tokio::test]
+#[traced_test]
+async fn test_import_stream_sdp(ctx: &mut ApiTestContext) {
+    let sdp = "v=0\r\n\
+               o=- 0 0 IN IP4 192.168.0.102\r\n\
+               s=Test\r\n\
+               c=IN IP4 192.168.0.102\r\n\
+               t=0 0\r\n\
+               m=audio 20000 RTP/AVP 96\r\n\
+               a=rtpmap:96 L16/48000/2\r\n";
+
+    let http_client = reqwest::Client::new();
+
+    // POST /streams
+    let resp = http_client
+        .post(format!("{}/streams", ctx.server.api_url()))
+        .query(&[(
+            "endpoint_uri",
+            "/peers/111111-222222-333333/endpoints/444444-555555-666666",
+        )])
+        .header("content-type", "application/sdp")
+        .body(sdp)
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(resp.status(), StatusCode::CREATED);
+
+    let stream: StreamSpec = resp.json().await.unwrap();
+
+    assert_eq!(
+        stream.source,
+        ConnectionSpec::ExternalConnection {
+            connection_type: ConnectionType::External,
+            media_uri: "rtp://192.168.0.102:20000".into(),
+            repair_uri: None,
+            control_uri: "rtcp://192.168.0.102:20001".into(),
+            multicast_interface: None,
+            multicast_ttl: None,
+        },
+    );
+    assert_eq!(
+        stream.destination,
+        ConnectionSpec::EndpointConnection {
+            connection_type: ConnectionType::Endpoint,
+            endpoint_uri: "/peers/111111-222222-333333/endpoints/444444-555555-666666".into(),
+        },
+    );
+    assert_eq!(
+        stream.encoding,
+        Some(EncodingSpec {
+            payload_type: 96,
+            sample_format: SampleFormat::S16,
+            sample_rate: 48000,
+            channels: 2,
+        }),
+    );
+
+    // GET /streams/{stream_uid}
+    let resp = ctx.client.read_stream(&stream.stream_uid).await.unwrap();
+
+    assert_eq!(resp.into_inner(), stream);
+
+    // POST /streams with wrong content type
+    let resp = http_client
+        .post(format!("{}/streams", ctx.server.api_url()))
+        .query(&[(
+            "endpoint_uri",
+            "/peers/111111-222222-333333/endpoints/444444-555555-666666",
+        )])
+        .header("content-type", "text/plain")
+        .body(sdp)
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(resp.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+
+    // POST /streams with malformed SDP
+    let resp = http_client
+        .post(format!("{}/streams", ctx.server.api_url()))
+        .query(&[(
+            "endpoint_uri",
+            "/peers/111111-222222-333333/endpoints/444444-555555-666666",
+        )])
+        .header("content-type", "application/sdp")
+        .body("v=0\r\n")
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
 }
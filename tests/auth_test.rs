@@ -0,0 +1,244 @@
+// Copyright (c) Roc Streaming authors
+// Licensed under MPL-2.0
+mod test_client;
+mod test_driver;
+mod test_server;
+mod test_transport;
+
+use crate::test_client::Client;
+use crate::test_driver::MockDriver;
+use crate::test_server::Server;
+use rocd::drivers::Driver;
+use rocd::dto::{ErrorCode, ErrorSpec, TokenScope};
+
+use reqwest::header::{AUTHORIZATION, HeaderMap, HeaderValue, WWW_AUTHENTICATE};
+use reqwest::{Method, StatusCode};
+use std::sync::Arc;
+use test_context::{AsyncTestContext, test_context};
+use tracing_test::traced_test;
+
+struct AuthTestContext {
+    driver: Arc<dyn Driver>,
+    server: Server,
+}
+
+impl AsyncTestContext for AuthTestContext {
+    async fn setup() -> AuthTestContext {
+        let driver = MockDriver::open().await.unwrap();
+        // docs are public, health check is not
+        let server = Server::start_with_auth(&driver, true, false).await;
+
+        AuthTestContext { driver, server }
+    }
+
+    async fn teardown(self) {
+        self.server.shutdown().await;
+        self.driver.close().await;
+    }
+}
+
+impl AuthTestContext {
+    async fn create_token(&self, scope: TokenScope) -> String {
+        self.server.tokens().create_token("test", scope).await.unwrap().1
+    }
+
+    /// Send request with empty body and given authorization header.
+    async fn send(
+        &self, method: Method, path: &str, authorization: Option<&str>,
+    ) -> reqwest::Response {
+        let mut request =
+            reqwest::Client::new().request(method, format!("{}{path}", self.server.url()));
+        if let Some(authorization) = authorization {
+            request = request.header(AUTHORIZATION, authorization);
+        }
+
+        request.send().await.unwrap()
+    }
+}
+
+async fn read_error(resp: reqwest::Response) -> ErrorSpec {
+    resp.json().await.unwrap()
+}
+
+#[test_context(AuthTestContext)]
+#[tokio::test]
+#[traced_test]
+async fn test_missing_token(ctx: &mut AuthTestContext) {
+    // deprecated unprefixed alias is protected too
+    for path in ["/api/v1/streams", "/streams"] {
+        let resp = ctx.send(Method::GET, path, None).await;
+
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED, "{path}");
+        assert_eq!(resp.headers().get(WWW_AUTHENTICATE).unwrap(), "Bearer");
+        assert_eq!(read_error(resp).await.error_code, ErrorCode::Unauthorized);
+    }
+}
+
+#[test_context(AuthTestContext)]
+#[tokio::test]
+#[traced_test]
+async fn test_invalid_token(ctx: &mut AuthTestContext) {
+    let bearer = ctx.create_token(TokenScope::Admin).await;
+    let (token_uid, secret) = bearer.split_once('.').unwrap();
+
+    for authorization in [
+        format!("Basic {bearer}"),
+        format!("Bearer {token_uid}"),
+        format!("Bearer {token_uid}.{secret}0"),
+        format!("Bearer 777777-888888-999999.{secret}"),
+        "Bearer".to_string(),
+    ] {
+        let resp = ctx.send(Method::GET, "/api/v1/streams", Some(&authorization)).await;
+
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED, "{authorization}");
+        assert_eq!(read_error(resp).await.error_code, ErrorCode::Unauthorized);
+    }
+
+    // scheme is case-insensitive
+    let resp =
+        ctx.send(Method::GET, "/api/v1/streams", Some(&format!("bearer {bearer}"))).await;
+
+    assert_eq!(resp.status(), StatusCode::OK);
+}
+
+#[test_context(AuthTestContext)]
+#[tokio::test]
+#[traced_test]
+async fn test_removed_token(ctx: &mut AuthTestContext) {
+    let (token, bearer) =
+        ctx.server.tokens().create_token("test", TokenScope::ReadOnly).await.unwrap();
+    let authorization = format!("Bearer {bearer}");
+
+    let resp = ctx.send(Method::GET, "/api/v1/streams", Some(&authorization)).await;
+
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    ctx.server.tokens().remove_token(&token.token_uid).await.unwrap();
+
+    let resp = ctx.send(Method::GET, "/api/v1/streams", Some(&authorization)).await;
+
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[test_context(AuthTestContext)]
+#[tokio::test]
+#[traced_test]
+async fn test_scopes(ctx: &mut AuthTestContext) {
+    let read_only = format!("Bearer {}", ctx.create_token(TokenScope::ReadOnly).await);
+    let control = format!("Bearer {}", ctx.create_token(TokenScope::Control).await);
+    let admin = format!("Bearer {}", ctx.create_token(TokenScope::Admin).await);
+
+    // requests to missing entities, so that allowed requests
+    // pass auth and fail with 404
+    let stream_path = "/api/v1/streams/777777-888888-999999";
+    let recording_path =
+        "/api/v1/streams/777777-888888-999999/recordings/20260310T100001.500Z.wav";
+    let group_path = "/api/v1/groups/777777-888888-999999";
+    let endpoint_path = "/api/v1/peers/self/endpoints/777777-888888-999999";
+
+    let cases = [
+        // read requests need read_only scope
+        (Method::GET, stream_path, TokenScope::ReadOnly),
+        (Method::GET, group_path, TokenScope::ReadOnly),
+        (Method::GET, "/api/v1/peers", TokenScope::ReadOnly),
+        // changing streams, recordings and groups needs control scope
+        (Method::DELETE, stream_path, TokenScope::Control),
+        (Method::DELETE, recording_path, TokenScope::Control),
+        (Method::DELETE, group_path, TokenScope::Control),
+        // changing peers and endpoints needs admin scope
+        (Method::DELETE, endpoint_path, TokenScope::Admin),
+    ];
+
+    for (method, path, required_scope) in cases {
+        for (scope, authorization) in [
+            (TokenScope::ReadOnly, &read_only),
+            (TokenScope::Control, &control),
+            (TokenScope::Admin, &admin),
+        ] {
+            let resp = ctx.send(method.clone(), path, Some(authorization)).await;
+
+            if scope >= required_scope {
+                assert_ne!(resp.status(), StatusCode::FORBIDDEN, "{method} {path} {scope}");
+                assert_ne!(resp.status(), StatusCode::UNAUTHORIZED, "{method} {path} {scope}");
+            } else {
+                assert_eq!(resp.status(), StatusCode::FORBIDDEN, "{method} {path} {scope}");
+                assert_eq!(read_error(resp).await.error_code, ErrorCode::Forbidden);
+            }
+        }
+    }
+}
+
+#[test_context(AuthTestContext)]
+#[tokio::test]
+#[traced_test]
+async fn test_client_with_token(ctx: &mut AuthTestContext) {
+    let bearer = ctx.create_token(TokenScope::ReadOnly).await;
+
+    let mut headers = HeaderMap::new();
+    headers.insert(AUTHORIZATION, HeaderValue::from_str(&format!("Bearer {bearer}")).unwrap());
+    let client = Client::new_with_client(
+        ctx.server.url(),
+        reqwest::Client::builder().default_headers(headers).build().unwrap(),
+    );
+
+    // GET /streams
+    let resp = client
+        .list_streams(None, None, None, None, None, None, None, None, None)
+        .await
+        .unwrap();
+
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.into_inner(), vec![]);
+
+    // without token
+    let client = Client::new(ctx.server.url());
+
+    let err = client
+        .list_streams(None, None, None, None, None, None, None, None, None)
+        .await
+        .unwrap_err();
+
+    assert_eq!(err.status(), Some(StatusCode::UNAUTHORIZED));
+}
+
+#[test_context(AuthTestContext)]
+#[tokio::test]
+#[traced_test]
+async fn test_public_paths(ctx: &mut AuthTestContext) {
+    // docs are public
+    for path in [
+        "/openapi",
+        "/openapi/openapi.json",
+        "/openapi/openapi.yaml",
+        "/openapi/v1",
+        "/openapi/v1/openapi.json",
+        "/openapi/v1/openapi.yaml",
+    ] {
+        let resp = ctx.send(Method::GET, path, None).await;
+
+        assert_eq!(resp.status(), StatusCode::OK, "{path}");
+    }
+
+    // ui page is always public, it has no data
+    for path in ["/", "/ui"] {
+        let resp = ctx.send(Method::GET, path, None).await;
+
+        assert_eq!(resp.status(), StatusCode::OK, "{path}");
+    }
+
+    // but only for reading, writes to root and its API aliases need token
+    for method in [Method::POST, Method::PUT, Method::DELETE] {
+        for path in ["/", "/ui", "/api/v1", "/api/v1/"] {
+            let resp = ctx.send(method.clone(), path, None).await;
+
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED, "{method} {path}");
+        }
+    }
+
+    // health and readiness checks aren't
+    for path in ["/api/v1/health", "/api/v1/ready"] {
+        let resp = ctx.send(Method::GET, path, None).await;
+
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED, "{path}");
+    }
+}
@@ -1,7 +1,8 @@
 // Copyright (c) Roc Streaming authors
 // Licensed under MPL-2.0
+use rocd::audio::AudioFormat;
 use rocd::drivers::*;
-use rocd::dto::DriverId;
+use rocd::dto::{DriverId, Uid, Uri};
 
 use test_context::{AsyncTestContext, test_context};
 use tracing_test::traced_test;
@@ -64,3 +65,36 @@ async fn test_open_driver(ctx: &mut DriverTestContext) {
     })
     .await
 }
+
+#[cfg_attr(not(feature = "driver-tests"), ignore = "driver-tests")]
+#[test_context(DriverTestContext)]
+#[tokio::test]
+#[traced_test]
+async fn test_open_device_endpoint(ctx: &mut DriverTestContext) {
+    let peer_uid = Uid::parse("111111-222222-333333").unwrap();
+    let default_uri = Uri::from_endpoint(&peer_uid, &Uid::parse(DEFAULT_DEVICE_UID).unwrap());
+    let other_uri =
+        Uri::from_endpoint(&peer_uid, &Uid::parse("aaaaaa-bbbbbb-cccccc").unwrap());
+    let format = AudioFormat { sample_rate: 44100, channels: 2 };
+
+    ctx.each_driver(async |driver_id| {
+        let driver = ctx.driver_registry.open_driver(driver_id).await.unwrap();
+
+        let source = driver.open_source(&default_uri, format).await.unwrap();
+        assert_eq!(source.format(), format);
+
+        let sink = driver.open_sink(&default_uri, format).await.unwrap();
+        assert_eq!(sink.format(), format);
+
+        // streams are closed before driver
+        drop(source);
+        drop(sink);
+
+        // only default device is supported
+        assert!(driver.open_source(&other_uri, format).await.is_err());
+        assert!(driver.open_sink(&other_uri, format).await.is_err());
+
+        driver.close().await;
+    })
+    .await
+}
@@ -0,0 +1,142 @@
+// Copyright (c) Roc Streaming authors
+// Licensed under MPL-2.0
+mod test_audio;
+
+use crate::test_audio::{PeakSink, SineSource};
+use async_trait::async_trait;
+use rocd::audio::*;
+use rocd::drivers::*;
+use rocd::dto::*;
+use rocd::io_endpoints::EndpointDispatcher;
+use rocd::io_streams::{RtpTransport, Transport};
+use rocd::vault::{Vault, VaultConfigBuilder};
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tempfile::TempDir;
+use tracing_test::traced_test;
+
+/// Driver which device endpoints capture sine wave and measure
+/// peak level of played audio.
+struct ToneDriver {
+    peak: Arc<Mutex<f32>>,
+}
+
+#[async_trait]
+impl Driver for ToneDriver {
+    async fn open() -> DriverResult<Arc<dyn Driver>> {
+        Ok(Arc::new(ToneDriver { peak: Arc::new(Mutex::new(0.0)) }))
+    }
+
+    async fn close(self: Arc<Self>) {}
+
+    fn id(&self) -> DriverId {
+        DriverId::Unspecified
+    }
+
+    async fn open_source(
+        &self, _endpoint_uri: &Uri, format: AudioFormat,
+    ) -> DriverResult<Box<dyn AudioSource>> {
+        Ok(Box::new(SineSource { format, phase: 0.0 }))
+    }
+
+    async fn open_sink(
+        &self, _endpoint_uri: &Uri, format: AudioFormat,
+    ) -> DriverResult<Box<dyn AudioSink>> {
+        Ok(Box::new(PeakSink { format, peak: Arc::clone(&self.peak) }))
+    }
+}
+
+async fn make_dispatcher(temp_dir: &TempDir, driver: &Arc<dyn Driver>) -> EndpointDispatcher {
+    let vault = Vault::open(
+        &VaultConfigBuilder::default()
+            .db_path(temp_dir.path().join("test.db").to_str().unwrap())
+            .build()
+            .unwrap(),
+    )
+    .await
+    .unwrap();
+
+    EndpointDispatcher::new(driver, &Arc::new(vault), temp_dir.path())
+}
+
+// Device endpoint is captured via driver, sent over loopback using
+// built-in RTP transport, and played to another device endpoint.
+#[tokio::test]
+#[traced_test]
+async fn test_device_endpoint_loopback() {
+    let temp_dir = TempDir::with_prefix("rocd_test").unwrap();
+    let peak = Arc::new(Mutex::new(0.0f32));
+    let driver: Arc<dyn Driver> = Arc::new(ToneDriver { peak: Arc::clone(&peak) });
+    let dispatcher = make_dispatcher(&temp_dir, &driver).await;
+
+    let endpoint_uri = Uri::from_endpoint(
+        &Uid::parse("111111-222222-333333").unwrap(),
+        &Uid::parse("444444-555555-666666").unwrap(),
+    );
+    let encoding = EncodingSpec::from_static_payload(10).unwrap();
+    let format = AudioFormat::from_encoding(&encoding);
+    let connection = ConnectionSpec::External {
+        connection_type: ConnectionType::External,
+        media_uri: Uri::parse("rtp://127.0.0.1:42300").unwrap(),
+        repair_uri: None,
+        control_uri: Uri::parse("rtcp://127.0.0.1:42301").unwrap(),
+        multicast_interface: None,
+        multicast_ttl: None,
+    };
+    let transport = RtpTransport::new();
+
+    let recv_pipeline = AudioPipeline::start(
+        "recv",
+        transport.open_receiver(&connection, &encoding, false).unwrap(),
+        dispatcher.open_sink(&endpoint_uri, format).await.unwrap(),
+    )
+    .unwrap();
+
+    let send_pipeline = AudioPipeline::start(
+        "send",
+        dispatcher.open_source(&endpoint_uri, format).await.unwrap(),
+        transport.open_sender(&connection, &encoding, false).unwrap(),
+    )
+    .unwrap();
+
+    let start_time = Instant::now();
+    while *peak.lock().unwrap() < 0.45 {
+        assert!(start_time.elapsed() < Duration::from_secs(10), "no audio received");
+        assert!(recv_pipeline.is_running() && send_pipeline.is_running());
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+}
+
+// Driver errors are reported by dispatcher.
+#[tokio::test]
+#[traced_test]
+async fn test_device_endpoint_unsupported() {
+    struct NoAudioDriver;
+
+    #[async_trait]
+    impl Driver for NoAudioDriver {
+        async fn open() -> DriverResult<Arc<dyn Driver>> {
+            Ok(Arc::new(NoAudioDriver))
+        }
+
+        async fn close(self: Arc<Self>) {}
+
+        fn id(&self) -> DriverId {
+            DriverId::Unspecified
+        }
+    }
+
+    let temp_dir = TempDir::with_prefix("rocd_test").unwrap();
+    let driver: Arc<dyn Driver> = Arc::new(NoAudioDriver);
+    let dispatcher = make_dispatcher(&temp_dir, &driver).await;
+
+    let endpoint_uri = Uri::from_endpoint(
+        &Uid::parse("111111-222222-333333").unwrap(),
+        &Uid::parse("444444-555555-666666").unwrap(),
+    );
+    let format = AudioFormat { sample_rate: 44100, channels: 2 };
+
+    assert!(dispatcher.open_source(&endpoint_uri, format).await.is_err());
+    assert!(dispatcher.open_sink(&endpoint_uri, format).await.is_err());
+}
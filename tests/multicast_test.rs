@@ -0,0 +1,72 @@
+// Copyright (c) Roc Streaming authors
+// Licensed under MPL-2.0
+use rocd::io_streams::*;
+
+use std::net::SocketAddr;
+use std::str::FromStr;
+use std::time::Duration;
+use tokio::time::timeout;
+use tracing_test::traced_test;
+
+fn loopback_iface() -> NetInterface {
+    list_interfaces()
+        .unwrap()
+        .into_iter()
+        .find(|iface| iface.is_loopback && iface.ipv4_addr.is_some())
+        .expect("no loopback interface")
+}
+
+// Sender and receiver bound to loopback interface exchange a few packets
+// via multicast group.
+#[tokio::test]
+#[traced_test]
+async fn test_loopback_send_receive() {
+    let iface = loopback_iface();
+    let group_addr = SocketAddr::from_str("239.255.42.1:0").unwrap();
+
+    // bind receiver to random port, then send to the same port
+    let receiver = open_multicast_receiver(&group_addr, Some(&iface)).unwrap();
+    let group_addr = SocketAddr::new(group_addr.ip(), receiver.local_addr().unwrap().port());
+
+    let sender = open_multicast_sender(&group_addr, Some(&iface), Some(1)).unwrap();
+
+    for n in 0..3u8 {
+        let packet = [n; 16];
+        sender.send_to(&packet, group_addr).await.unwrap();
+
+        let mut buf = [0u8; 64];
+        let (size, _) = timeout(Duration::from_secs(5), receiver.recv_from(&mut buf))
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(&buf[..size], &packet);
+    }
+}
+
+// Two receivers on the same host can join the same group and port.
+#[tokio::test]
+#[traced_test]
+async fn test_loopback_two_receivers() {
+    let iface = loopback_iface();
+    let group_addr = SocketAddr::from_str("239.255.42.2:0").unwrap();
+
+    let receiver1 = open_multicast_receiver(&group_addr, Some(&iface)).unwrap();
+    let group_addr = SocketAddr::new(group_addr.ip(), receiver1.local_addr().unwrap().port());
+    let receiver2 = open_multicast_receiver(&group_addr, Some(&iface)).unwrap();
+
+    let sender = open_multicast_sender(&group_addr, Some(&iface), Some(1)).unwrap();
+
+    let packet = [7u8; 16];
+    sender.send_to(&packet, group_addr).await.unwrap();
+
+    for receiver in [&receiver1, &receiver2] {
+        let mut buf = [0u8; 64];
+        let (size, _) = timeout(Duration::from_secs(5), receiver.recv_from(&mut buf))
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(&buf[..size], &packet);
+    }
+}
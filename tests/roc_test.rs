@@ -0,0 +1,99 @@
+// Copyright (c) Roc Streaming authors
+// Licensed under MPL-2.0
+#![cfg(feature = "roc")]
+
+mod test_audio;
+
+use crate::test_audio::{PeakSink, SineSource};
+use rocd::audio::*;
+use rocd::dto::*;
+use rocd::io_streams::{RocTransport, Transport};
+
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tracing_test::traced_test;
+
+const ENCODING_PT: u8 = 10;
+
+fn make_connection(base_port: u16) -> ConnectionSpec {
+    ConnectionSpec::External {
+        connection_type: ConnectionType::External,
+        media_uri: Uri::parse(&format!("rtp+rs8m://127.0.0.1:{base_port}")).unwrap(),
+        repair_uri: Some(Uri::parse(&format!("rs8m://127.0.0.1:{}", base_port + 1)).unwrap()),
+        control_uri: Uri::parse(&format!("rtcp://127.0.0.1:{}", base_port + 2)).unwrap(),
+        multicast_interface: None,
+        multicast_ttl: None,
+    }
+}
+
+// Two in-process streams over loopback: sine generator writes to
+// roc sender, roc receiver reads and measures peak level.
+#[test]
+#[traced_test]
+fn test_loopback_stream() {
+    let transport = RocTransport::open().unwrap();
+    let encoding = EncodingSpec::from_static_payload(ENCODING_PT).unwrap();
+    let format = AudioFormat::from_encoding(&encoding);
+    let connection = make_connection(41000);
+
+    assert!(transport.supports(&connection, &encoding));
+
+    let peak = Arc::new(Mutex::new(0.0f32));
+
+    let receiver = transport.open_receiver(&connection, &encoding, false).unwrap();
+    let recv_pipeline = AudioPipeline::start(
+        "recv",
+        receiver,
+        Box::new(PeakSink { format, peak: Arc::clone(&peak) }),
+    )
+    .unwrap();
+
+    let sender = transport.open_sender(&connection, &encoding, false).unwrap();
+    let send_pipeline =
+        AudioPipeline::start("send", Box::new(SineSource { format, phase: 0.0 }), sender)
+            .unwrap();
+
+    let start_time = Instant::now();
+    while *peak.lock().unwrap() < 0.1 {
+        assert!(start_time.elapsed() < Duration::from_secs(10), "no audio received");
+        assert!(recv_pipeline.is_running() && send_pipeline.is_running());
+        thread::sleep(Duration::from_millis(50));
+    }
+
+    let metrics = recv_pipeline.status().source_metrics.unwrap();
+    assert_eq!(metrics.connection_count, 1);
+}
+
+// S24 is not supported by libroc.
+#[test]
+#[traced_test]
+fn test_unsupported_encoding() {
+    let transport = RocTransport::open().unwrap();
+
+    let encoding = EncodingSpec {
+        payload_type: 100,
+        sample_format: SampleFormat::S24,
+        sample_rate: 48000,
+        channels: 2,
+    };
+
+    assert!(!transport.supports(&make_connection(41010), &encoding));
+}
+
+// libroc 0.4 can't set multicast TTL, so such connections are left
+// to other transports.
+#[test]
+#[traced_test]
+fn test_unsupported_multicast_ttl() {
+    let transport = RocTransport::open().unwrap();
+    let encoding = EncodingSpec::from_static_payload(ENCODING_PT).unwrap();
+
+    let mut connection = make_connection(41020);
+    assert!(transport.supports(&connection, &encoding));
+
+    if let ConnectionSpec::External { multicast_ttl, .. } = &mut connection {
+        *multicast_ttl = Some(8);
+    }
+    assert!(!transport.supports(&connection, &encoding));
+}
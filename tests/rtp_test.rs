@@ -0,0 +1,134 @@
+// Copyright (c) Roc Streaming authors
+// Licensed under MPL-2.0
+mod test_audio;
+
+use crate::test_audio::{PeakSink, SineSource};
+use rocd::audio::*;
+use rocd::dto::*;
+use rocd::io_streams::{RtpTransport, Transport, TransportRegistry};
+
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tracing_test::traced_test;
+
+fn make_connection(media_port: u16, control_port: u16) -> ConnectionSpec {
+    ConnectionSpec::External {
+        connection_type: ConnectionType::External,
+        media_uri: Uri::parse(&format!("rtp://127.0.0.1:{media_port}")).unwrap(),
+        repair_uri: None,
+        control_uri: Uri::parse(&format!("rtcp://127.0.0.1:{control_port}")).unwrap(),
+        multicast_interface: None,
+        multicast_ttl: None,
+    }
+}
+
+/// Run sender and receiver streams over loopback, wait until receiver
+/// gets sine wave, and return receiver and sender metrics.
+fn run_loopback(encoding: &EncodingSpec, base_port: u16) -> (LinkMetrics, LinkMetrics) {
+    let transport = RtpTransport::new();
+    let format = AudioFormat::from_encoding(encoding);
+    let connection = make_connection(base_port, base_port + 1);
+
+    assert!(transport.supports(&connection, encoding));
+
+    let peak = Arc::new(Mutex::new(0.0f32));
+
+    let receiver = transport.open_receiver(&connection, encoding, false).unwrap();
+    let recv_pipeline = AudioPipeline::start(
+        "recv",
+        receiver,
+        Box::new(PeakSink { format, peak: Arc::clone(&peak) }),
+    )
+    .unwrap();
+
+    let sender = transport.open_sender(&connection, encoding, false).unwrap();
+    let send_pipeline =
+        AudioPipeline::start("send", Box::new(SineSource { format, phase: 0.0 }), sender)
+            .unwrap();
+
+    let start_time = Instant::now();
+    while *peak.lock().unwrap() < 0.45 {
+        assert!(start_time.elapsed() < Duration::from_secs(10), "no audio received");
+        assert!(recv_pipeline.is_running() && send_pipeline.is_running());
+        thread::sleep(Duration::from_millis(50));
+    }
+
+    // wait until sender gets receiver report
+    while send_pipeline.status().sink_metrics.unwrap_or_default().connection_count == 0 {
+        assert!(start_time.elapsed() < Duration::from_secs(10), "no receiver report");
+        thread::sleep(Duration::from_millis(50));
+    }
+
+    // peak of decoded sine doesn't exceed amplitude
+    assert!(*peak.lock().unwrap() < 0.51);
+
+    (
+        recv_pipeline.status().source_metrics.unwrap(),
+        send_pipeline.status().sink_metrics.unwrap(),
+    )
+}
+
+// Two in-process streams over loopback using L16.
+#[test]
+#[traced_test]
+fn test_loopback_l16() {
+    let encoding = EncodingSpec::from_static_payload(10).unwrap();
+
+    let (recv_metrics, send_metrics) = run_loopback(&encoding, 42200);
+
+    assert_eq!(recv_metrics.connection_count, 1);
+    assert!(recv_metrics.expected_packets > 0);
+    assert_eq!(recv_metrics.lost_packets, 0);
+
+    assert_eq!(send_metrics.connection_count, 1);
+    assert_eq!(send_metrics.lost_packets, 0);
+}
+
+// Two in-process streams over loopback using L24 with dynamic payload type.
+#[test]
+#[traced_test]
+fn test_loopback_l24() {
+    let encoding = EncodingSpec {
+        payload_type: 96,
+        sample_format: SampleFormat::S24,
+        sample_rate: 48000,
+        channels: 1,
+    };
+
+    let (recv_metrics, _) = run_loopback(&encoding, 42210);
+
+    assert_eq!(recv_metrics.connection_count, 1);
+}
+
+// Built-in transport is selected for rtp:// but not for FEC schemes.
+#[test]
+fn test_select_transport() {
+    let registry = TransportRegistry::new();
+
+    let l24_encoding = EncodingSpec {
+        payload_type: 96,
+        sample_format: SampleFormat::S24,
+        sample_rate: 48000,
+        channels: 2,
+    };
+
+    assert_eq!(
+        registry.select(&make_connection(42220, 42221), &l24_encoding).unwrap().name(),
+        "rtp"
+    );
+
+    #[cfg(not(feature = "roc"))]
+    {
+        let fec_connection = ConnectionSpec::External {
+            connection_type: ConnectionType::External,
+            media_uri: Uri::parse("rtp+rs8m://127.0.0.1:42222").unwrap(),
+            repair_uri: Some(Uri::parse("rs8m://127.0.0.1:42223").unwrap()),
+            control_uri: Uri::parse("rtcp://127.0.0.1:42224").unwrap(),
+            multicast_interface: None,
+            multicast_ttl: None,
+        };
+
+        assert!(registry.select(&fec_connection, &EncodingSpec::default()).is_err());
+    }
+}
@@ -0,0 +1,53 @@
+// Copyright (c) Roc Streaming authors
+// Licensed under MPL-2.0
+use rocd::audio::*;
+
+use std::f32::consts::PI;
+use std::sync::{Arc, Mutex};
+
+/// Source producing 440Hz sine wave with amplitude 0.5.
+pub struct SineSource {
+    pub format: AudioFormat,
+    pub phase: f32,
+}
+
+impl AudioSource for SineSource {
+    fn format(&self) -> AudioFormat {
+        self.format
+    }
+
+    fn is_clocked(&self) -> bool {
+        false
+    }
+
+    fn read(&mut self, samples: &mut [f32]) -> Result<(), AudioError> {
+        for frame in samples.chunks_mut(self.format.channels as usize) {
+            frame.fill((self.phase * 2.0 * PI).sin() * 0.5);
+            self.phase = (self.phase + 440.0 / self.format.sample_rate as f32) % 1.0;
+        }
+        Ok(())
+    }
+}
+
+/// Sink measuring peak level of all written samples.
+pub struct PeakSink {
+    pub format: AudioFormat,
+    pub peak: Arc<Mutex<f32>>,
+}
+
+impl AudioSink for PeakSink {
+    fn format(&self) -> AudioFormat {
+        self.format
+    }
+
+    fn is_clocked(&self) -> bool {
+        false
+    }
+
+    fn write(&mut self, samples: &[f32]) -> Result<(), AudioError> {
+        let frame_peak = samples.iter().fold(0.0f32, |peak, sample| peak.max(sample.abs()));
+        let mut peak = self.peak.lock().unwrap();
+        *peak = peak.max(frame_peak);
+        Ok(())
+    }
+}
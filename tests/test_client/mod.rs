@@ -1,7 +1,7 @@
 #[allow(unused_imports)]
 pub use progenitor_client::{ByteStream, ClientInfo, Error, ResponseValue};
 #[allow(unused_imports)]
-use progenitor_client::{encode_path, ClientHooks, OperationInfo, RequestBuilderExt};
+use progenitor_client::{ClientHooks, OperationInfo, RequestBuilderExt, encode_path};
 /// Types used as operation parameters and responses.
 #[allow(clippy::all)]
 pub mod types {
@@ -11,18 +11,12 @@ pub mod types {
         pub struct ConversionError(::std::borrow::Cow<'static, str>);
         impl ::std::error::Error for ConversionError {}
         impl ::std::fmt::Display for ConversionError {
-            fn fmt(
-                &self,
-                f: &mut ::std::fmt::Formatter<'_>,
-            ) -> Result<(), ::std::fmt::Error> {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> Result<(), ::std::fmt::Error> {
                 ::std::fmt::Display::fmt(&self.0, f)
             }
         }
         impl ::std::fmt::Debug for ConversionError {
-            fn fmt(
-                &self,
-                f: &mut ::std::fmt::Formatter<'_>,
-            ) -> Result<(), ::std::fmt::Error> {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> Result<(), ::std::fmt::Error> {
                 ::std::fmt::Debug::fmt(&self.0, f)
             }
         }
@@ -37,6 +31,41 @@ pub mod types {
             }
         }
     }
+    ///Levels of one channel.
+    ///
+    /// <details><summary>JSON schema</summary>
+    ///
+    /// ```json
+    ///{
+    ///  "description": "Levels of one channel.",
+    ///  "type": "object",
+    ///  "required": [
+    ///    "peak",
+    ///    "rms"
+    ///  ],
+    ///  "properties": {
+    ///    "peak": {
+    ///      "type": "number",
+    ///      "format": "float"
+    ///    },
+    ///    "rms": {
+    ///      "type": "number",
+    ///      "format": "float"
+    ///    }
+    ///  }
+    ///}
+    /// ```
+    /// </details>
+    #[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, PartialEq)]
+    pub struct ChannelLevelSpec {
+        pub peak: f32,
+        pub rms: f32,
+    }
+    impl ::std::convert::From<&ChannelLevelSpec> for ChannelLevelSpec {
+        fn from(value: &ChannelLevelSpec) -> Self {
+            value.clone()
+        }
+    }
     ///`ConnectionSpec`
     ///
     /// <details><summary>JSON schema</summary>
@@ -66,8 +95,7 @@ pub mod types {
     ///      "required": [
     ///        "connection_type",
     ///        "control_uri",
-    ///        "media_uri",
-    ///        "repair_uri"
+    ///        "media_uri"
     ///      ],
     ///      "properties": {
     ///        "connection_type": {
@@ -79,7 +107,16 @@ pub mod types {
     ///        "media_uri": {
     ///          "type": "string"
     ///        },
+    ///        "multicast_interface": {
+    ///          "type": "string"
+    ///        },
+    ///        "multicast_ttl": {
+    ///          "type": "integer",
+    ///          "format": "int32",
+    ///          "minimum": 0.0
+    ///        },
     ///        "repair_uri": {
+    ///          "description": "Required when media_uri has FEC scheme (e.g. rtp+rs8m),\nnot allowed otherwise (e.g. rtp).",
     ///          "type": "string"
     ///        }
     ///      }
@@ -99,7 +136,14 @@ pub mod types {
             connection_type: ConnectionType,
             control_uri: ::std::string::String,
             media_uri: ::std::string::String,
-            repair_uri: ::std::string::String,
+            #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
+            multicast_interface: ::std::option::Option<::std::string::String>,
+            #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
+            multicast_ttl: ::std::option::Option<i32>,
+            /**Required when media_uri has FEC scheme (e.g. rtp+rs8m),
+            not allowed otherwise (e.g. rtp).*/
+            #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
+            repair_uri: ::std::option::Option<::std::string::String>,
         },
     }
     impl ::std::convert::From<&Self> for ConnectionSpec {
@@ -131,7 +175,7 @@ pub mod types {
         Hash,
         Ord,
         PartialEq,
-        PartialOrd
+        PartialOrd,
     )]
     pub enum ConnectionType {
         #[serde(rename = "endpoint")]
@@ -154,9 +198,7 @@ pub mod types {
     }
     impl ::std::str::FromStr for ConnectionType {
         type Err = self::error::ConversionError;
-        fn from_str(
-            value: &str,
-        ) -> ::std::result::Result<Self, self::error::ConversionError> {
+        fn from_str(value: &str) -> ::std::result::Result<Self, self::error::ConversionError> {
             match value {
                 "endpoint" => Ok(Self::Endpoint),
                 "external" => Ok(Self::External),
@@ -166,9 +208,7 @@ pub mod types {
     }
     impl ::std::convert::TryFrom<&str> for ConnectionType {
         type Error = self::error::ConversionError;
-        fn try_from(
-            value: &str,
-        ) -> ::std::result::Result<Self, self::error::ConversionError> {
+        fn try_from(value: &str) -> ::std::result::Result<Self, self::error::ConversionError> {
             value.parse()
         }
     }
@@ -197,6 +237,7 @@ pub mod types {
     ///  "type": "string",
     ///  "enum": [
     ///    "unspecified",
+    ///    "virtual",
     ///    "pipewire"
     ///  ]
     ///}
@@ -212,11 +253,13 @@ pub mod types {
         Hash,
         Ord,
         PartialEq,
-        PartialOrd
+        PartialOrd,
     )]
     pub enum DriverId {
         #[serde(rename = "unspecified")]
         Unspecified,
+        #[serde(rename = "virtual")]
+        Virtual,
         #[serde(rename = "pipewire")]
         Pipewire,
     }
@@ -229,17 +272,17 @@ pub mod types {
         fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
             match *self {
                 Self::Unspecified => f.write_str("unspecified"),
+                Self::Virtual => f.write_str("virtual"),
                 Self::Pipewire => f.write_str("pipewire"),
             }
         }
     }
     impl ::std::str::FromStr for DriverId {
         type Err = self::error::ConversionError;
-        fn from_str(
-            value: &str,
-        ) -> ::std::result::Result<Self, self::error::ConversionError> {
+        fn from_str(value: &str) -> ::std::result::Result<Self, self::error::ConversionError> {
             match value {
                 "unspecified" => Ok(Self::Unspecified),
+                "virtual" => Ok(Self::Virtual),
                 "pipewire" => Ok(Self::Pipewire),
                 _ => Err("invalid value".into()),
             }
@@ -247,9 +290,7 @@ pub mod types {
     }
     impl ::std::convert::TryFrom<&str> for DriverId {
         type Error = self::error::ConversionError;
-        fn try_from(
-            value: &str,
-        ) -> ::std::result::Result<Self, self::error::ConversionError> {
+        fn try_from(value: &str) -> ::std::result::Result<Self, self::error::ConversionError> {
             value.parse()
         }
     }
@@ -269,6 +310,59 @@ pub mod types {
             value.parse()
         }
     }
+    ///Audio encoding of media packets.
+    ///
+    /// <details><summary>JSON schema</summary>
+    ///
+    /// ```json
+    ///{
+    ///  "description": "Audio encoding of media packets.",
+    ///  "type": "object",
+    ///  "required": [
+    ///    "channels",
+    ///    "payload_type",
+    ///    "sample_format",
+    ///    "sample_rate"
+    ///  ],
+    ///  "properties": {
+    ///    "channels": {
+    ///      "type": "integer",
+    ///      "format": "int32",
+    ///      "minimum": 0.0
+    ///    },
+    ///    "payload_type": {
+    ///      "description": "RTP payload type.\nStatic payload types 10 and 11 are defined by RFC 3551,\ndynamic payload types (96-127) may be used for other encodings.",
+    ///      "type": "integer",
+    ///      "format": "int32",
+    ///      "minimum": 0.0
+    ///    },
+    ///    "sample_format": {
+    ///      "$ref": "#/components/schemas/SampleFormat"
+    ///    },
+    ///    "sample_rate": {
+    ///      "type": "integer",
+    ///      "format": "int32",
+    ///      "minimum": 0.0
+    ///    }
+    ///  }
+    ///}
+    /// ```
+    /// </details>
+    #[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, PartialEq)]
+    pub struct EncodingSpec {
+        pub channels: i32,
+        /**RTP payload type.
+        Static payload types 10 and 11 are defined by RFC 3551,
+        dynamic payload types (96-127) may be used for other encodings.*/
+        pub payload_type: i32,
+        pub sample_format: SampleFormat,
+        pub sample_rate: i32,
+    }
+    impl ::std::convert::From<&EncodingSpec> for EncodingSpec {
+        fn from(value: &EncodingSpec) -> Self {
+            value.clone()
+        }
+    }
     ///`EndpointDir`
     ///
     /// <details><summary>JSON schema</summary>
@@ -294,7 +388,7 @@ pub mod types {
         Hash,
         Ord,
         PartialEq,
-        PartialOrd
+        PartialOrd,
     )]
     pub enum EndpointDir {
         #[serde(rename = "input")]
@@ -320,9 +414,7 @@ pub mod types {
     }
     impl ::std::str::FromStr for EndpointDir {
         type Err = self::error::ConversionError;
-        fn from_str(
-            value: &str,
-        ) -> ::std::result::Result<Self, self::error::ConversionError> {
+        fn from_str(value: &str) -> ::std::result::Result<Self, self::error::ConversionError> {
             match value {
                 "input" => Ok(Self::Input),
                 "output" => Ok(Self::Output),
@@ -333,9 +425,7 @@ pub mod types {
     }
     impl ::std::convert::TryFrom<&str> for EndpointDir {
         type Error = self::error::ConversionError;
-        fn try_from(
-            value: &str,
-        ) -> ::std::result::Result<Self, self::error::ConversionError> {
+        fn try_from(value: &str) -> ::std::result::Result<Self, self::error::ConversionError> {
             value.parse()
         }
     }
@@ -387,11 +477,20 @@ pub mod types {
     ///    "endpoint_uri": {
     ///      "type": "string"
     ///    },
+    ///    "revision": {
+    ///      "readOnly": true,
+    ///      "type": "integer",
+    ///      "format": "int64",
+    ///      "minimum": 0.0
+    ///    },
     ///    "stream_direction": {
     ///      "$ref": "#/components/schemas/EndpointDir"
     ///    },
     ///    "system_name": {
     ///      "type": "string"
+    ///    },
+    ///    "virtual_source": {
+    ///      "$ref": "#/components/schemas/VirtualSourceSpec"
     ///    }
     ///  }
     ///}
@@ -404,8 +503,12 @@ pub mod types {
         pub endpoint_type: EndpointType,
         pub endpoint_uid: ::std::string::String,
         pub endpoint_uri: ::std::string::String,
+        #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
+        pub revision: ::std::option::Option<i64>,
         pub stream_direction: EndpointDir,
         pub system_name: ::std::string::String,
+        #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
+        pub virtual_source: ::std::option::Option<VirtualSourceSpec>,
     }
     impl ::std::convert::From<&EndpointSpec> for EndpointSpec {
         fn from(value: &EndpointSpec) -> Self {
@@ -421,7 +524,8 @@ pub mod types {
     ///  "type": "string",
     ///  "enum": [
     ///    "system_device",
-    ///    "streaming_device"
+    ///    "streaming_device",
+    ///    "virtual_source"
     ///  ]
     ///}
     /// ```
@@ -436,13 +540,15 @@ pub mod types {
         Hash,
         Ord,
         PartialEq,
-        PartialOrd
+        PartialOrd,
     )]
     pub enum EndpointType {
         #[serde(rename = "system_device")]
         SystemDevice,
         #[serde(rename = "streaming_device")]
         StreamingDevice,
+        #[serde(rename = "virtual_source")]
+        VirtualSource,
     }
     impl ::std::convert::From<&Self> for EndpointType {
         fn from(value: &EndpointType) -> Self {
@@ -454,26 +560,24 @@ pub mod types {
             match *self {
                 Self::SystemDevice => f.write_str("system_device"),
                 Self::StreamingDevice => f.write_str("streaming_device"),
+                Self::VirtualSource => f.write_str("virtual_source"),
             }
         }
     }
     impl ::std::str::FromStr for EndpointType {
         type Err = self::error::ConversionError;
-        fn from_str(
-            value: &str,
-        ) -> ::std::result::Result<Self, self::error::ConversionError> {
+        fn from_str(value: &str) -> ::std::result::Result<Self, self::error::ConversionError> {
             match value {
                 "system_device" => Ok(Self::SystemDevice),
                 "streaming_device" => Ok(Self::StreamingDevice),
+                "virtual_source" => Ok(Self::VirtualSource),
                 _ => Err("invalid value".into()),
             }
         }
     }
     impl ::std::convert::TryFrom<&str> for EndpointType {
         type Error = self::error::ConversionError;
-        fn try_from(
-            value: &str,
-        ) -> ::std::result::Result<Self, self::error::ConversionError> {
+        fn try_from(value: &str) -> ::std::result::Result<Self, self::error::ConversionError> {
             value.parse()
         }
     }
@@ -493,22 +597,33 @@ pub mod types {
             value.parse()
         }
     }
-    ///`PeerSpec`
+    ///Notification about change on server, delivered via GET /events.
     ///
     /// <details><summary>JSON schema</summary>
     ///
     /// ```json
     ///{
+    ///  "description": "Notification about change on server, delivered via GET /events.",
     ///  "type": "object",
     ///  "required": [
-    ///    "peer_uid",
-    ///    "peer_uri"
+    ///    "event_type",
+    ///    "object_uri",
+    ///    "time"
     ///  ],
     ///  "properties": {
-    ///    "peer_uid": {
+    ///    "event_type": {
+    ///      "$ref": "#/components/schemas/EventType"
+    ///    },
+    ///    "levels": {
+    ///      "$ref": "#/components/schemas/LevelsSpec"
+    ///    },
+    ///    "object_uri": {
     ///      "type": "string"
     ///    },
-    ///    "peer_uri": {
+    ///    "source_switch": {
+    ///      "$ref": "#/components/schemas/SourceSwitchSpec"
+    ///    },
+    ///    "time": {
     ///      "type": "string"
     ///    }
     ///  }
@@ -516,37 +631,180 @@ pub mod types {
     /// ```
     /// </details>
     #[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, PartialEq)]
-    pub struct PeerSpec {
-        pub peer_uid: ::std::string::String,
-        pub peer_uri: ::std::string::String,
+    pub struct EventSpec {
+        pub event_type: EventType,
+        #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
+        pub levels: ::std::option::Option<LevelsSpec>,
+        pub object_uri: ::std::string::String,
+        #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
+        pub source_switch: ::std::option::Option<SourceSwitchSpec>,
+        pub time: ::std::string::String,
     }
-    impl ::std::convert::From<&PeerSpec> for PeerSpec {
-        fn from(value: &PeerSpec) -> Self {
+    impl ::std::convert::From<&EventSpec> for EventSpec {
+        fn from(value: &EventSpec) -> Self {
             value.clone()
         }
     }
-    ///`StreamSpec`
+    ///`EventType`
+    ///
+    /// <details><summary>JSON schema</summary>
+    ///
+    /// ```json
+    ///{
+    ///  "type": "string",
+    ///  "enum": [
+    ///    "stream_paused",
+    ///    "stream_resumed",
+    ///    "levels_updated",
+    ///    "stream_source_switched"
+    ///  ]
+    ///}
+    /// ```
+    /// </details>
+    #[derive(
+        ::serde::Deserialize,
+        ::serde::Serialize,
+        Clone,
+        Copy,
+        Debug,
+        Eq,
+        Hash,
+        Ord,
+        PartialEq,
+        PartialOrd,
+    )]
+    pub enum EventType {
+        #[serde(rename = "stream_paused")]
+        StreamPaused,
+        #[serde(rename = "stream_resumed")]
+        StreamResumed,
+        #[serde(rename = "levels_updated")]
+        LevelsUpdated,
+        #[serde(rename = "stream_source_switched")]
+        StreamSourceSwitched,
+    }
+    impl ::std::convert::From<&Self> for EventType {
+        fn from(value: &EventType) -> Self {
+            value.clone()
+        }
+    }
+    impl ::std::fmt::Display for EventType {
+        fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+            match *self {
+                Self::StreamPaused => f.write_str("stream_paused"),
+                Self::StreamResumed => f.write_str("stream_resumed"),
+                Self::LevelsUpdated => f.write_str("levels_updated"),
+                Self::StreamSourceSwitched => f.write_str("stream_source_switched"),
+            }
+        }
+    }
+    impl ::std::str::FromStr for EventType {
+        type Err = self::error::ConversionError;
+        fn from_str(value: &str) -> ::std::result::Result<Self, self::error::ConversionError> {
+            match value {
+                "stream_paused" => Ok(Self::StreamPaused),
+                "stream_resumed" => Ok(Self::StreamResumed),
+                "levels_updated" => Ok(Self::LevelsUpdated),
+                "stream_source_switched" => Ok(Self::StreamSourceSwitched),
+                _ => Err("invalid value".into()),
+            }
+        }
+    }
+    impl ::std::convert::TryFrom<&str> for EventType {
+        type Error = self::error::ConversionError;
+        fn try_from(value: &str) -> ::std::result::Result<Self, self::error::ConversionError> {
+            value.parse()
+        }
+    }
+    impl ::std::convert::TryFrom<&::std::string::String> for EventType {
+        type Error = self::error::ConversionError;
+        fn try_from(
+            value: &::std::string::String,
+        ) -> ::std::result::Result<Self, self::error::ConversionError> {
+            value.parse()
+        }
+    }
+    impl ::std::convert::TryFrom<::std::string::String> for EventType {
+        type Error = self::error::ConversionError;
+        fn try_from(
+            value: ::std::string::String,
+        ) -> ::std::result::Result<Self, self::error::ConversionError> {
+            value.parse()
+        }
+    }
+    /**Fallback sources of stream.
+
+    Primary source and all fallback sources are opened together. Stream
+    plays first source that doesn't fail and isn't silent, and switches
+    back to preferred source when it stays healthy for recovery time.*/
     ///
     /// <details><summary>JSON schema</summary>
     ///
     /// ```json
     ///{
+    ///  "description": "Fallback sources of stream.\n\nPrimary source and all fallback sources are opened together. Stream\nplays first source that doesn't fail and isn't silent, and switches\nback to preferred source when it stays healthy for recovery time.",
     ///  "type": "object",
     ///  "required": [
-    ///    "destination",
-    ///    "source",
-    ///    "stream_uid",
-    ///    "stream_uri"
+    ///    "sources"
     ///  ],
     ///  "properties": {
-    ///    "destination": {
-    ///      "$ref": "#/components/schemas/ConnectionSpec"
+    ///    "recovery_time": {
+    ///      "type": "number",
+    ///      "format": "float"
     ///    },
-    ///    "source": {
-    ///      "$ref": "#/components/schemas/ConnectionSpec"
+    ///    "silence_threshold": {
+    ///      "type": "number",
+    ///      "format": "float"
     ///    },
-    ///    "stream_uid": {
-    ///      "type": "string"
+    ///    "silence_timeout": {
+    ///      "type": "number",
+    ///      "format": "float"
+    ///    },
+    ///    "sources": {
+    ///      "type": "array",
+    ///      "items": {
+    ///        "$ref": "#/components/schemas/ConnectionSpec"
+    ///      }
+    ///    }
+    ///  }
+    ///}
+    /// ```
+    /// </details>
+    #[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, PartialEq)]
+    pub struct FailoverSpec {
+        #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
+        pub recovery_time: ::std::option::Option<f32>,
+        #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
+        pub silence_threshold: ::std::option::Option<f32>,
+        #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
+        pub silence_timeout: ::std::option::Option<f32>,
+        pub sources: ::std::vec::Vec<ConnectionSpec>,
+    }
+    impl ::std::convert::From<&FailoverSpec> for FailoverSpec {
+        fn from(value: &FailoverSpec) -> Self {
+            value.clone()
+        }
+    }
+    /**Alignment of one group member.
+    Latencies are absent if member isn't running or its latency is unknown yet.*/
+    ///
+    /// <details><summary>JSON schema</summary>
+    ///
+    /// ```json
+    ///{
+    ///  "description": "Alignment of one group member.\nLatencies are absent if member isn't running or its latency is unknown yet.",
+    ///  "type": "object",
+    ///  "required": [
+    ///    "stream_uri"
+    ///  ],
+    ///  "properties": {
+    ///    "delay": {
+    ///      "type": "number",
+    ///      "format": "float"
+    ///    },
+    ///    "latency": {
+    ///      "type": "number",
+    ///      "format": "float"
     ///    },
     ///    "stream_uri": {
     ///      "type": "string"
@@ -556,87 +814,2120 @@ pub mod types {
     /// ```
     /// </details>
     #[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, PartialEq)]
-    pub struct StreamSpec {
-        pub destination: ConnectionSpec,
-        pub source: ConnectionSpec,
-        pub stream_uid: ::std::string::String,
+    pub struct GroupMemberStatusSpec {
+        #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
+        pub delay: ::std::option::Option<f32>,
+        #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
+        pub latency: ::std::option::Option<f32>,
         pub stream_uri: ::std::string::String,
     }
-    impl ::std::convert::From<&StreamSpec> for StreamSpec {
-        fn from(value: &StreamSpec) -> Self {
+    impl ::std::convert::From<&GroupMemberStatusSpec> for GroupMemberStatusSpec {
+        fn from(value: &GroupMemberStatusSpec) -> Self {
             value.clone()
         }
     }
-}
-#[derive(Clone, Debug)]
-/**Client for rocd REST API
+    /**Group of receiving streams which latencies are equalised,
+    e.g. speakers in different rooms playing same source.
 
-Real-time audio streaming daemon.
+    Each member reports end-to-end latency estimated by its transport.
+    Members that have lower latency are delayed, so that all members
+    play with same latency: target_latency, or latency of slowest member
+    if it's higher.
 
-Version: 0.1.0*/
-pub struct Client {
-    pub(crate) baseurl: String,
-    pub(crate) client: reqwest::Client,
-}
-impl Client {
-    /// Create a new client.
+    Members don't share a clock reference: clock drift between receivers
+    is not corrected, only compensated indirectly when it shows up in
+    estimated latency. Alignment is therefore as good as latency
+    estimates of transports, and can't be better than their jitter.*/
     ///
-    /// `baseurl` is the base URL provided to the internal
-    /// `reqwest::Client`, and should include a scheme and hostname,
-    /// as well as port and a path stem if applicable.
-    pub fn new(baseurl: &str) -> Self {
-        #[cfg(not(target_arch = "wasm32"))]
-        let client = {
-            let dur = ::std::time::Duration::from_secs(15u64);
-            reqwest::ClientBuilder::new().connect_timeout(dur).timeout(dur)
-        };
-        #[cfg(target_arch = "wasm32")]
-        let client = reqwest::ClientBuilder::new();
-        Self::new_with_client(baseurl, client.build().unwrap())
-    }
-    /// Construct a new client with an existing `reqwest::Client`,
-    /// allowing more control over its configuration.
+    /// <details><summary>JSON schema</summary>
     ///
-    /// `baseurl` is the base URL provided to the internal
-    /// `reqwest::Client`, and should include a scheme and hostname,
-    /// as well as port and a path stem if applicable.
-    pub fn new_with_client(baseurl: &str, client: reqwest::Client) -> Self {
-        Self {
-            baseurl: baseurl.to_string(),
-            client,
-        }
-    }
-}
-impl ClientInfo<()> for Client {
-    fn api_version() -> &'static str {
-        "0.1.0"
+    /// ```json
+    ///{
+    ///  "description": "Group of receiving streams which latencies are equalised,\ne.g. speakers in different rooms playing same source.\n\nEach member reports end-to-end latency estimated by its transport.\nMembers that have lower latency are delayed, so that all members\nplay with same latency: target_latency, or latency of slowest member\nif it's higher.\n\nMembers don't share a clock reference: clock drift between receivers\nis not corrected, only compensated indirectly when it shows up in\nestimated latency. Alignment is therefore as good as latency\nestimates of transports, and can't be better than their jitter.",
+    ///  "type": "object",
+    ///  "required": [
+    ///    "group_uid",
+    ///    "group_uri",
+    ///    "members"
+    ///  ],
+    ///  "properties": {
+    ///    "group_uid": {
+    ///      "type": "string"
+    ///    },
+    ///    "group_uri": {
+    ///      "type": "string"
+    ///    },
+    ///    "members": {
+    ///      "type": "array",
+    ///      "items": {
+    ///        "type": "string"
+    ///      }
+    ///    },
+    ///    "target_latency": {
+    ///      "type": "number",
+    ///      "format": "float"
+    ///    }
+    ///  }
+    ///}
+    /// ```
+    /// </details>
+    #[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, PartialEq)]
+    pub struct GroupSpec {
+        pub group_uid: ::std::string::String,
+        pub group_uri: ::std::string::String,
+        pub members: ::std::vec::Vec<::std::string::String>,
+        #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
+        pub target_latency: ::std::option::Option<f32>,
     }
-    fn baseurl(&self) -> &str {
-        self.baseurl.as_str()
+    impl ::std::convert::From<&GroupSpec> for GroupSpec {
+        fn from(value: &GroupSpec) -> Self {
+            value.clone()
+        }
     }
-    fn client(&self) -> &reqwest::Client {
-        &self.client
+    ///Current alignment of group members (GET /groups/{uid}/status).
+    ///
+    /// <details><summary>JSON schema</summary>
+    ///
+    /// ```json
+    ///{
+    ///  "description": "Current alignment of group members (GET /groups/{uid}/status).",
+    ///  "type": "object",
+    ///  "required": [
+    ///    "group_uri",
+    ///    "members",
+    ///    "time"
+    ///  ],
+    ///  "properties": {
+    ///    "group_uri": {
+    ///      "type": "string"
+    ///    },
+    ///    "members": {
+    ///      "type": "array",
+    ///      "items": {
+    ///        "$ref": "#/components/schemas/GroupMemberStatusSpec"
+    ///      }
+    ///    },
+    ///    "time": {
+    ///      "type": "string"
+    ///    }
+    ///  }
+    ///}
+    /// ```
+    /// </details>
+    #[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, PartialEq)]
+    pub struct GroupStatusSpec {
+        pub group_uri: ::std::string::String,
+        pub members: ::std::vec::Vec<GroupMemberStatusSpec>,
+        pub time: ::std::string::String,
     }
-    fn inner(&self) -> &() {
-        &()
+    impl ::std::convert::From<&GroupStatusSpec> for GroupStatusSpec {
+        fn from(value: &GroupStatusSpec) -> Self {
+            value.clone()
+        }
     }
-}
-impl ClientHooks<()> for &Client {}
-#[allow(clippy::all)]
+    /**Liveness of server.
+
+    Server is alive while its background tasks are running.*/
+    ///
+    /// <details><summary>JSON schema</summary>
+    ///
+    /// ```json
+    ///{
+    ///  "description": "Liveness of server.\n\nServer is alive while its background tasks are running.",
+    ///  "type": "object",
+    ///  "required": [
+    ///    "alive"
+    ///  ],
+    ///  "properties": {
+    ///    "alive": {
+    ///      "type": "boolean"
+    ///    }
+    ///  }
+    ///}
+    /// ```
+    /// </details>
+    #[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, PartialEq)]
+    pub struct HealthSpec {
+        pub alive: bool,
+    }
+    impl ::std::convert::From<&HealthSpec> for HealthSpec {
+        fn from(value: &HealthSpec) -> Self {
+            value.clone()
+        }
+    }
+    /**Result of latency measurement (POST /streams/{uid}/measure-latency).
+
+    Measured latency is the time between sending a marker signal from
+    stream destination and detecting it at detector stream source.*/
+    ///
+    /// <details><summary>JSON schema</summary>
+    ///
+    /// ```json
+    ///{
+    ///  "description": "Result of latency measurement (POST /streams/{uid}/measure-latency).\n\nMeasured latency is the time between sending a marker signal from\nstream destination and detecting it at detector stream source.",
+    ///  "type": "object",
+    ///  "required": [
+    ///    "detector_stream_uri",
+    ///    "measured_latency",
+    ///    "stream_uri",
+    ///    "time"
+    ///  ],
+    ///  "properties": {
+    ///    "detector_stream_uri": {
+    ///      "type": "string"
+    ///    },
+    ///    "estimated_latency": {
+    ///      "type": "number",
+    ///      "format": "float"
+    ///    },
+    ///    "measured_latency": {
+    ///      "type": "number",
+    ///      "format": "float"
+    ///    },
+    ///    "stream_uri": {
+    ///      "type": "string"
+    ///    },
+    ///    "target_latency": {
+    ///      "type": "number",
+    ///      "format": "float"
+    ///    },
+    ///    "time": {
+    ///      "type": "string"
+    ///    }
+    ///  }
+    ///}
+    /// ```
+    /// </details>
+    #[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, PartialEq)]
+    pub struct LatencyReportSpec {
+        pub detector_stream_uri: ::std::string::String,
+        #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
+        pub estimated_latency: ::std::option::Option<f32>,
+        pub measured_latency: f32,
+        pub stream_uri: ::std::string::String,
+        #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
+        pub target_latency: ::std::option::Option<f32>,
+        pub time: ::std::string::String,
+    }
+    impl ::std::convert::From<&LatencyReportSpec> for LatencyReportSpec {
+        fn from(value: &LatencyReportSpec) -> Self {
+            value.clone()
+        }
+    }
+    /**Audio levels of stream or endpoint, measured during one metering interval.
+
+    Stream levels are measured at destination, after processing.
+    Source endpoint levels are measured before processing.*/
+    ///
+    /// <details><summary>JSON schema</summary>
+    ///
+    /// ```json
+    ///{
+    ///  "description": "Audio levels of stream or endpoint, measured during one metering interval.\n\nStream levels are measured at destination, after processing.\nSource endpoint levels are measured before processing.",
+    ///  "type": "object",
+    ///  "required": [
+    ///    "channels",
+    ///    "object_uri",
+    ///    "time"
+    ///  ],
+    ///  "properties": {
+    ///    "channels": {
+    ///      "type": "array",
+    ///      "items": {
+    ///        "$ref": "#/components/schemas/ChannelLevelSpec"
+    ///      }
+    ///    },
+    ///    "object_uri": {
+    ///      "type": "string"
+    ///    },
+    ///    "time": {
+    ///      "type": "string"
+    ///    }
+    ///  }
+    ///}
+    /// ```
+    /// </details>
+    #[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, PartialEq)]
+    pub struct LevelsSpec {
+        pub channels: ::std::vec::Vec<ChannelLevelSpec>,
+        pub object_uri: ::std::string::String,
+        pub time: ::std::string::String,
+    }
+    impl ::std::convert::From<&LevelsSpec> for LevelsSpec {
+        fn from(value: &LevelsSpec) -> Self {
+            value.clone()
+        }
+    }
+    ///`ListEndpointsOrder`
+    ///
+    /// <details><summary>JSON schema</summary>
+    ///
+    /// ```json
+    ///{
+    ///  "type": "string",
+    ///  "enum": [
+    ///    "asc",
+    ///    "desc"
+    ///  ]
+    ///}
+    /// ```
+    /// </details>
+    #[derive(
+        ::serde::Deserialize,
+        ::serde::Serialize,
+        Clone,
+        Copy,
+        Debug,
+        Eq,
+        Hash,
+        Ord,
+        PartialEq,
+        PartialOrd,
+    )]
+    pub enum ListEndpointsOrder {
+        #[serde(rename = "asc")]
+        Asc,
+        #[serde(rename = "desc")]
+        Desc,
+    }
+    impl ::std::convert::From<&Self> for ListEndpointsOrder {
+        fn from(value: &ListEndpointsOrder) -> Self {
+            value.clone()
+        }
+    }
+    impl ::std::fmt::Display for ListEndpointsOrder {
+        fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+            match *self {
+                Self::Asc => f.write_str("asc"),
+                Self::Desc => f.write_str("desc"),
+            }
+        }
+    }
+    impl ::std::str::FromStr for ListEndpointsOrder {
+        type Err = self::error::ConversionError;
+        fn from_str(value: &str) -> ::std::result::Result<Self, self::error::ConversionError> {
+            match value {
+                "asc" => Ok(Self::Asc),
+                "desc" => Ok(Self::Desc),
+                _ => Err("invalid value".into()),
+            }
+        }
+    }
+    impl ::std::convert::TryFrom<&str> for ListEndpointsOrder {
+        type Error = self::error::ConversionError;
+        fn try_from(value: &str) -> ::std::result::Result<Self, self::error::ConversionError> {
+            value.parse()
+        }
+    }
+    impl ::std::convert::TryFrom<&::std::string::String> for ListEndpointsOrder {
+        type Error = self::error::ConversionError;
+        fn try_from(
+            value: &::std::string::String,
+        ) -> ::std::result::Result<Self, self::error::ConversionError> {
+            value.parse()
+        }
+    }
+    impl ::std::convert::TryFrom<::std::string::String> for ListEndpointsOrder {
+        type Error = self::error::ConversionError;
+        fn try_from(
+            value: ::std::string::String,
+        ) -> ::std::result::Result<Self, self::error::ConversionError> {
+            value.parse()
+        }
+    }
+    ///`ListEndpointsSort`
+    ///
+    /// <details><summary>JSON schema</summary>
+    ///
+    /// ```json
+    ///{
+    ///  "type": "string",
+    ///  "enum": [
+    ///    "endpoint_uid",
+    ///    "display_name",
+    ///    "system_name",
+    ///    "endpoint_type",
+    ///    "driver"
+    ///  ]
+    ///}
+    /// ```
+    /// </details>
+    #[derive(
+        ::serde::Deserialize,
+        ::serde::Serialize,
+        Clone,
+        Copy,
+        Debug,
+        Eq,
+        Hash,
+        Ord,
+        PartialEq,
+        PartialOrd,
+    )]
+    pub enum ListEndpointsSort {
+        #[serde(rename = "endpoint_uid")]
+        EndpointUid,
+        #[serde(rename = "display_name")]
+        DisplayName,
+        #[serde(rename = "system_name")]
+        SystemName,
+        #[serde(rename = "endpoint_type")]
+        EndpointType,
+        #[serde(rename = "driver")]
+        Driver,
+    }
+    impl ::std::convert::From<&Self> for ListEndpointsSort {
+        fn from(value: &ListEndpointsSort) -> Self {
+            value.clone()
+        }
+    }
+    impl ::std::fmt::Display for ListEndpointsSort {
+        fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+            match *self {
+                Self::EndpointUid => f.write_str("endpoint_uid"),
+                Self::DisplayName => f.write_str("display_name"),
+                Self::SystemName => f.write_str("system_name"),
+                Self::EndpointType => f.write_str("endpoint_type"),
+                Self::Driver => f.write_str("driver"),
+            }
+        }
+    }
+    impl ::std::str::FromStr for ListEndpointsSort {
+        type Err = self::error::ConversionError;
+        fn from_str(value: &str) -> ::std::result::Result<Self, self::error::ConversionError> {
+            match value {
+                "endpoint_uid" => Ok(Self::EndpointUid),
+                "display_name" => Ok(Self::DisplayName),
+                "system_name" => Ok(Self::SystemName),
+                "endpoint_type" => Ok(Self::EndpointType),
+                "driver" => Ok(Self::Driver),
+                _ => Err("invalid value".into()),
+            }
+        }
+    }
+    impl ::std::convert::TryFrom<&str> for ListEndpointsSort {
+        type Error = self::error::ConversionError;
+        fn try_from(value: &str) -> ::std::result::Result<Self, self::error::ConversionError> {
+            value.parse()
+        }
+    }
+    impl ::std::convert::TryFrom<&::std::string::String> for ListEndpointsSort {
+        type Error = self::error::ConversionError;
+        fn try_from(
+            value: &::std::string::String,
+        ) -> ::std::result::Result<Self, self::error::ConversionError> {
+            value.parse()
+        }
+    }
+    impl ::std::convert::TryFrom<::std::string::String> for ListEndpointsSort {
+        type Error = self::error::ConversionError;
+        fn try_from(
+            value: ::std::string::String,
+        ) -> ::std::result::Result<Self, self::error::ConversionError> {
+            value.parse()
+        }
+    }
+    ///`ListPeersOrder`
+    ///
+    /// <details><summary>JSON schema</summary>
+    ///
+    /// ```json
+    ///{
+    ///  "type": "string",
+    ///  "enum": [
+    ///    "asc",
+    ///    "desc"
+    ///  ]
+    ///}
+    /// ```
+    /// </details>
+    #[derive(
+        ::serde::Deserialize,
+        ::serde::Serialize,
+        Clone,
+        Copy,
+        Debug,
+        Eq,
+        Hash,
+        Ord,
+        PartialEq,
+        PartialOrd,
+    )]
+    pub enum ListPeersOrder {
+        #[serde(rename = "asc")]
+        Asc,
+        #[serde(rename = "desc")]
+        Desc,
+    }
+    impl ::std::convert::From<&Self> for ListPeersOrder {
+        fn from(value: &ListPeersOrder) -> Self {
+            value.clone()
+        }
+    }
+    impl ::std::fmt::Display for ListPeersOrder {
+        fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+            match *self {
+                Self::Asc => f.write_str("asc"),
+                Self::Desc => f.write_str("desc"),
+            }
+        }
+    }
+    impl ::std::str::FromStr for ListPeersOrder {
+        type Err = self::error::ConversionError;
+        fn from_str(value: &str) -> ::std::result::Result<Self, self::error::ConversionError> {
+            match value {
+                "asc" => Ok(Self::Asc),
+                "desc" => Ok(Self::Desc),
+                _ => Err("invalid value".into()),
+            }
+        }
+    }
+    impl ::std::convert::TryFrom<&str> for ListPeersOrder {
+        type Error = self::error::ConversionError;
+        fn try_from(value: &str) -> ::std::result::Result<Self, self::error::ConversionError> {
+            value.parse()
+        }
+    }
+    impl ::std::convert::TryFrom<&::std::string::String> for ListPeersOrder {
+        type Error = self::error::ConversionError;
+        fn try_from(
+            value: &::std::string::String,
+        ) -> ::std::result::Result<Self, self::error::ConversionError> {
+            value.parse()
+        }
+    }
+    impl ::std::convert::TryFrom<::std::string::String> for ListPeersOrder {
+        type Error = self::error::ConversionError;
+        fn try_from(
+            value: ::std::string::String,
+        ) -> ::std::result::Result<Self, self::error::ConversionError> {
+            value.parse()
+        }
+    }
+    ///`ListPeersSort`
+    ///
+    /// <details><summary>JSON schema</summary>
+    ///
+    /// ```json
+    ///{
+    ///  "type": "string",
+    ///  "enum": [
+    ///    "peer_uid"
+    ///  ]
+    ///}
+    /// ```
+    /// </details>
+    #[derive(
+        ::serde::Deserialize,
+        ::serde::Serialize,
+        Clone,
+        Copy,
+        Debug,
+        Eq,
+        Hash,
+        Ord,
+        PartialEq,
+        PartialOrd,
+    )]
+    pub enum ListPeersSort {
+        #[serde(rename = "peer_uid")]
+        PeerUid,
+    }
+    impl ::std::convert::From<&Self> for ListPeersSort {
+        fn from(value: &ListPeersSort) -> Self {
+            value.clone()
+        }
+    }
+    impl ::std::fmt::Display for ListPeersSort {
+        fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+            match *self {
+                Self::PeerUid => f.write_str("peer_uid"),
+            }
+        }
+    }
+    impl ::std::str::FromStr for ListPeersSort {
+        type Err = self::error::ConversionError;
+        fn from_str(value: &str) -> ::std::result::Result<Self, self::error::ConversionError> {
+            match value {
+                "peer_uid" => Ok(Self::PeerUid),
+                _ => Err("invalid value".into()),
+            }
+        }
+    }
+    impl ::std::convert::TryFrom<&str> for ListPeersSort {
+        type Error = self::error::ConversionError;
+        fn try_from(value: &str) -> ::std::result::Result<Self, self::error::ConversionError> {
+            value.parse()
+        }
+    }
+    impl ::std::convert::TryFrom<&::std::string::String> for ListPeersSort {
+        type Error = self::error::ConversionError;
+        fn try_from(
+            value: &::std::string::String,
+        ) -> ::std::result::Result<Self, self::error::ConversionError> {
+            value.parse()
+        }
+    }
+    impl ::std::convert::TryFrom<::std::string::String> for ListPeersSort {
+        type Error = self::error::ConversionError;
+        fn try_from(
+            value: ::std::string::String,
+        ) -> ::std::result::Result<Self, self::error::ConversionError> {
+            value.parse()
+        }
+    }
+    ///`ListStreamsOrder`
+    ///
+    /// <details><summary>JSON schema</summary>
+    ///
+    /// ```json
+    ///{
+    ///  "type": "string",
+    ///  "enum": [
+    ///    "asc",
+    ///    "desc"
+    ///  ]
+    ///}
+    /// ```
+    /// </details>
+    #[derive(
+        ::serde::Deserialize,
+        ::serde::Serialize,
+        Clone,
+        Copy,
+        Debug,
+        Eq,
+        Hash,
+        Ord,
+        PartialEq,
+        PartialOrd,
+    )]
+    pub enum ListStreamsOrder {
+        #[serde(rename = "asc")]
+        Asc,
+        #[serde(rename = "desc")]
+        Desc,
+    }
+    impl ::std::convert::From<&Self> for ListStreamsOrder {
+        fn from(value: &ListStreamsOrder) -> Self {
+            value.clone()
+        }
+    }
+    impl ::std::fmt::Display for ListStreamsOrder {
+        fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+            match *self {
+                Self::Asc => f.write_str("asc"),
+                Self::Desc => f.write_str("desc"),
+            }
+        }
+    }
+    impl ::std::str::FromStr for ListStreamsOrder {
+        type Err = self::error::ConversionError;
+        fn from_str(value: &str) -> ::std::result::Result<Self, self::error::ConversionError> {
+            match value {
+                "asc" => Ok(Self::Asc),
+                "desc" => Ok(Self::Desc),
+                _ => Err("invalid value".into()),
+            }
+        }
+    }
+    impl ::std::convert::TryFrom<&str> for ListStreamsOrder {
+        type Error = self::error::ConversionError;
+        fn try_from(value: &str) -> ::std::result::Result<Self, self::error::ConversionError> {
+            value.parse()
+        }
+    }
+    impl ::std::convert::TryFrom<&::std::string::String> for ListStreamsOrder {
+        type Error = self::error::ConversionError;
+        fn try_from(
+            value: &::std::string::String,
+        ) -> ::std::result::Result<Self, self::error::ConversionError> {
+            value.parse()
+        }
+    }
+    impl ::std::convert::TryFrom<::std::string::String> for ListStreamsOrder {
+        type Error = self::error::ConversionError;
+        fn try_from(
+            value: ::std::string::String,
+        ) -> ::std::result::Result<Self, self::error::ConversionError> {
+            value.parse()
+        }
+    }
+    ///`ListStreamsSort`
+    ///
+    /// <details><summary>JSON schema</summary>
+    ///
+    /// ```json
+    ///{
+    ///  "type": "string",
+    ///  "enum": [
+    ///    "stream_uid",
+    ///    "state",
+    ///    "owner",
+    ///    "lease_expiration"
+    ///  ]
+    ///}
+    /// ```
+    /// </details>
+    #[derive(
+        ::serde::Deserialize,
+        ::serde::Serialize,
+        Clone,
+        Copy,
+        Debug,
+        Eq,
+        Hash,
+        Ord,
+        PartialEq,
+        PartialOrd,
+    )]
+    pub enum ListStreamsSort {
+        #[serde(rename = "stream_uid")]
+        StreamUid,
+        #[serde(rename = "state")]
+        State,
+        #[serde(rename = "owner")]
+        Owner,
+        #[serde(rename = "lease_expiration")]
+        LeaseExpiration,
+    }
+    impl ::std::convert::From<&Self> for ListStreamsSort {
+        fn from(value: &ListStreamsSort) -> Self {
+            value.clone()
+        }
+    }
+    impl ::std::fmt::Display for ListStreamsSort {
+        fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+            match *self {
+                Self::StreamUid => f.write_str("stream_uid"),
+                Self::State => f.write_str("state"),
+                Self::Owner => f.write_str("owner"),
+                Self::LeaseExpiration => f.write_str("lease_expiration"),
+            }
+        }
+    }
+    impl ::std::str::FromStr for ListStreamsSort {
+        type Err = self::error::ConversionError;
+        fn from_str(value: &str) -> ::std::result::Result<Self, self::error::ConversionError> {
+            match value {
+                "stream_uid" => Ok(Self::StreamUid),
+                "state" => Ok(Self::State),
+                "owner" => Ok(Self::Owner),
+                "lease_expiration" => Ok(Self::LeaseExpiration),
+                _ => Err("invalid value".into()),
+            }
+        }
+    }
+    impl ::std::convert::TryFrom<&str> for ListStreamsSort {
+        type Error = self::error::ConversionError;
+        fn try_from(value: &str) -> ::std::result::Result<Self, self::error::ConversionError> {
+            value.parse()
+        }
+    }
+    impl ::std::convert::TryFrom<&::std::string::String> for ListStreamsSort {
+        type Error = self::error::ConversionError;
+        fn try_from(
+            value: &::std::string::String,
+        ) -> ::std::result::Result<Self, self::error::ConversionError> {
+            value.parse()
+        }
+    }
+    impl ::std::convert::TryFrom<::std::string::String> for ListStreamsSort {
+        type Error = self::error::ConversionError;
+        fn try_from(
+            value: ::std::string::String,
+        ) -> ::std::result::Result<Self, self::error::ConversionError> {
+            value.parse()
+        }
+    }
+    ///`PeerSpec`
+    ///
+    /// <details><summary>JSON schema</summary>
+    ///
+    /// ```json
+    ///{
+    ///  "type": "object",
+    ///  "required": [
+    ///    "peer_uid",
+    ///    "peer_uri"
+    ///  ],
+    ///  "properties": {
+    ///    "peer_uid": {
+    ///      "type": "string"
+    ///    },
+    ///    "peer_uri": {
+    ///      "type": "string"
+    ///    },
+    ///    "revision": {
+    ///      "readOnly": true,
+    ///      "type": "integer",
+    ///      "format": "int64",
+    ///      "minimum": 0.0
+    ///    }
+    ///  }
+    ///}
+    /// ```
+    /// </details>
+    #[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, PartialEq)]
+    pub struct PeerSpec {
+        pub peer_uid: ::std::string::String,
+        pub peer_uri: ::std::string::String,
+        #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
+        pub revision: ::std::option::Option<i64>,
+    }
+    impl ::std::convert::From<&PeerSpec> for PeerSpec {
+        fn from(value: &PeerSpec) -> Self {
+            value.clone()
+        }
+    }
+    /**Audio processing applied to stream between source and destination.
+
+    Processing is applied in order: loudness normalization, gain, mute,
+    limiter. Changes are applied to running stream without restart.*/
+    ///
+    /// <details><summary>JSON schema</summary>
+    ///
+    /// ```json
+    ///{
+    ///  "description": "Audio processing applied to stream between source and destination.\n\nProcessing is applied in order: loudness normalization, gain, mute,\nlimiter. Changes are applied to running stream without restart.",
+    ///  "type": "object",
+    ///  "properties": {
+    ///    "gain": {
+    ///      "type": "number",
+    ///      "format": "float"
+    ///    },
+    ///    "limiter_threshold": {
+    ///      "type": "number",
+    ///      "format": "float"
+    ///    },
+    ///    "loudness_target": {
+    ///      "type": "number",
+    ///      "format": "float"
+    ///    },
+    ///    "mute": {
+    ///      "type": "boolean"
+    ///    }
+    ///  }
+    ///}
+    /// ```
+    /// </details>
+    #[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, PartialEq)]
+    pub struct ProcessingSpec {
+        #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
+        pub gain: ::std::option::Option<f32>,
+        #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
+        pub limiter_threshold: ::std::option::Option<f32>,
+        #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
+        pub loudness_target: ::std::option::Option<f32>,
+        #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
+        pub mute: ::std::option::Option<bool>,
+    }
+    impl ::std::convert::From<&ProcessingSpec> for ProcessingSpec {
+        fn from(value: &ProcessingSpec) -> Self {
+            value.clone()
+        }
+    }
+    impl ::std::default::Default for ProcessingSpec {
+        fn default() -> Self {
+            Self {
+                gain: Default::default(),
+                limiter_threshold: Default::default(),
+                loudness_target: Default::default(),
+                mute: Default::default(),
+            }
+        }
+    }
+    /**Readiness of server to handle requests.
+
+    Audio driver is opened before server starts accepting requests.
+    After that, server becomes ready when streams are loaded from
+    persistent storage, and their audio pipelines are started.
+    Server stops being ready if audio driver goes down.*/
+    ///
+    /// <details><summary>JSON schema</summary>
+    ///
+    /// ```json
+    ///{
+    ///  "description": "Readiness of server to handle requests.\n\nAudio driver is opened before server starts accepting requests.\nAfter that, server becomes ready when streams are loaded from\npersistent storage, and their audio pipelines are started.\nServer stops being ready if audio driver goes down.",
+    ///  "type": "object",
+    ///  "required": [
+    ///    "driver_up",
+    ///    "ready",
+    ///    "streams_reconciled",
+    ///    "vault_loaded"
+    ///  ],
+    ///  "properties": {
+    ///    "driver_up": {
+    ///      "type": "boolean"
+    ///    },
+    ///    "ready": {
+    ///      "type": "boolean"
+    ///    },
+    ///    "streams_reconciled": {
+    ///      "type": "boolean"
+    ///    },
+    ///    "vault_loaded": {
+    ///      "type": "boolean"
+    ///    }
+    ///  }
+    ///}
+    /// ```
+    /// </details>
+    #[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, PartialEq)]
+    pub struct ReadinessSpec {
+        pub driver_up: bool,
+        pub ready: bool,
+        pub streams_reconciled: bool,
+        pub vault_loaded: bool,
+    }
+    impl ::std::convert::From<&ReadinessSpec> for ReadinessSpec {
+        fn from(value: &ReadinessSpec) -> Self {
+            value.clone()
+        }
+    }
+    ///`RecordingFormat`
+    ///
+    /// <details><summary>JSON schema</summary>
+    ///
+    /// ```json
+    ///{
+    ///  "type": "string",
+    ///  "enum": [
+    ///    "wav",
+    ///    "flac"
+    ///  ]
+    ///}
+    /// ```
+    /// </details>
+    #[derive(
+        ::serde::Deserialize,
+        ::serde::Serialize,
+        Clone,
+        Copy,
+        Debug,
+        Eq,
+        Hash,
+        Ord,
+        PartialEq,
+        PartialOrd,
+    )]
+    pub enum RecordingFormat {
+        #[serde(rename = "wav")]
+        Wav,
+        #[serde(rename = "flac")]
+        Flac,
+    }
+    impl ::std::convert::From<&Self> for RecordingFormat {
+        fn from(value: &RecordingFormat) -> Self {
+            value.clone()
+        }
+    }
+    impl ::std::fmt::Display for RecordingFormat {
+        fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+            match *self {
+                Self::Wav => f.write_str("wav"),
+                Self::Flac => f.write_str("flac"),
+            }
+        }
+    }
+    impl ::std::str::FromStr for RecordingFormat {
+        type Err = self::error::ConversionError;
+        fn from_str(value: &str) -> ::std::result::Result<Self, self::error::ConversionError> {
+            match value {
+                "wav" => Ok(Self::Wav),
+                "flac" => Ok(Self::Flac),
+                _ => Err("invalid value".into()),
+            }
+        }
+    }
+    impl ::std::convert::TryFrom<&str> for RecordingFormat {
+        type Error = self::error::ConversionError;
+        fn try_from(value: &str) -> ::std::result::Result<Self, self::error::ConversionError> {
+            value.parse()
+        }
+    }
+    impl ::std::convert::TryFrom<&::std::string::String> for RecordingFormat {
+        type Error = self::error::ConversionError;
+        fn try_from(
+            value: &::std::string::String,
+        ) -> ::std::result::Result<Self, self::error::ConversionError> {
+            value.parse()
+        }
+    }
+    impl ::std::convert::TryFrom<::std::string::String> for RecordingFormat {
+        type Error = self::error::ConversionError;
+        fn try_from(
+            value: ::std::string::String,
+        ) -> ::std::result::Result<Self, self::error::ConversionError> {
+            value.parse()
+        }
+    }
+    ///Recorded file.
+    ///
+    /// <details><summary>JSON schema</summary>
+    ///
+    /// ```json
+    ///{
+    ///  "description": "Recorded file.",
+    ///  "type": "object",
+    ///  "required": [
+    ///    "format",
+    ///    "in_progress",
+    ///    "recording_id",
+    ///    "size",
+    ///    "start_time",
+    ///    "stream_uid"
+    ///  ],
+    ///  "properties": {
+    ///    "format": {
+    ///      "$ref": "#/components/schemas/RecordingFormat"
+    ///    },
+    ///    "in_progress": {
+    ///      "type": "boolean"
+    ///    },
+    ///    "recording_id": {
+    ///      "type": "string"
+    ///    },
+    ///    "size": {
+    ///      "type": "integer",
+    ///      "format": "int64",
+    ///      "minimum": 0.0
+    ///    },
+    ///    "start_time": {
+    ///      "type": "string"
+    ///    },
+    ///    "stream_uid": {
+    ///      "type": "string"
+    ///    }
+    ///  }
+    ///}
+    /// ```
+    /// </details>
+    #[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, PartialEq)]
+    pub struct RecordingInfo {
+        pub format: RecordingFormat,
+        pub in_progress: bool,
+        pub recording_id: ::std::string::String,
+        pub size: i64,
+        pub start_time: ::std::string::String,
+        pub stream_uid: ::std::string::String,
+    }
+    impl ::std::convert::From<&RecordingInfo> for RecordingInfo {
+        fn from(value: &RecordingInfo) -> Self {
+            value.clone()
+        }
+    }
+    /**Recording of stream audio to files on server.
+
+    Audio written to destination is also written to files in server state
+    directory. A new file is started every rotation_interval seconds of audio.
+    After a new file is started, oldest files are deleted if there are more
+    than max_files recordings, or if recordings are older than max_age.*/
+    ///
+    /// <details><summary>JSON schema</summary>
+    ///
+    /// ```json
+    ///{
+    ///  "description": "Recording of stream audio to files on server.\n\nAudio written to destination is also written to files in server state\ndirectory. A new file is started every rotation_interval seconds of audio.\nAfter a new file is started, oldest files are deleted if there are more\nthan max_files recordings, or if recordings are older than max_age.",
+    ///  "type": "object",
+    ///  "required": [
+    ///    "format"
+    ///  ],
+    ///  "properties": {
+    ///    "format": {
+    ///      "$ref": "#/components/schemas/RecordingFormat"
+    ///    },
+    ///    "max_age": {
+    ///      "type": "integer",
+    ///      "format": "int32",
+    ///      "minimum": 0.0
+    ///    },
+    ///    "max_files": {
+    ///      "type": "integer",
+    ///      "format": "int32",
+    ///      "minimum": 0.0
+    ///    },
+    ///    "metadata": {
+    ///      "type": "object",
+    ///      "additionalProperties": {
+    ///        "type": "string"
+    ///      }
+    ///    },
+    ///    "rotation_interval": {
+    ///      "type": "integer",
+    ///      "format": "int32",
+    ///      "minimum": 0.0
+    ///    }
+    ///  }
+    ///}
+    /// ```
+    /// </details>
+    #[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, PartialEq)]
+    pub struct RecordingSpec {
+        pub format: RecordingFormat,
+        #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
+        pub max_age: ::std::option::Option<i32>,
+        #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
+        pub max_files: ::std::option::Option<i32>,
+        #[serde(default, skip_serializing_if = ":: std :: collections :: HashMap::is_empty")]
+        pub metadata:
+            ::std::collections::HashMap<::std::string::String, ::std::string::String>,
+        #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
+        pub rotation_interval: ::std::option::Option<i32>,
+    }
+    impl ::std::convert::From<&RecordingSpec> for RecordingSpec {
+        fn from(value: &RecordingSpec) -> Self {
+            value.clone()
+        }
+    }
+    ///`SampleFormat`
+    ///
+    /// <details><summary>JSON schema</summary>
+    ///
+    /// ```json
+    ///{
+    ///  "type": "string",
+    ///  "enum": [
+    ///    "s16",
+    ///    "s24"
+    ///  ]
+    ///}
+    /// ```
+    /// </details>
+    #[derive(
+        ::serde::Deserialize,
+        ::serde::Serialize,
+        Clone,
+        Copy,
+        Debug,
+        Eq,
+        Hash,
+        Ord,
+        PartialEq,
+        PartialOrd,
+    )]
+    pub enum SampleFormat {
+        #[serde(rename = "s16")]
+        S16,
+        #[serde(rename = "s24")]
+        S24,
+    }
+    impl ::std::convert::From<&Self> for SampleFormat {
+        fn from(value: &SampleFormat) -> Self {
+            value.clone()
+        }
+    }
+    impl ::std::fmt::Display for SampleFormat {
+        fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+            match *self {
+                Self::S16 => f.write_str("s16"),
+                Self::S24 => f.write_str("s24"),
+            }
+        }
+    }
+    impl ::std::str::FromStr for SampleFormat {
+        type Err = self::error::ConversionError;
+        fn from_str(value: &str) -> ::std::result::Result<Self, self::error::ConversionError> {
+            match value {
+                "s16" => Ok(Self::S16),
+                "s24" => Ok(Self::S24),
+                _ => Err("invalid value".into()),
+            }
+        }
+    }
+    impl ::std::convert::TryFrom<&str> for SampleFormat {
+        type Error = self::error::ConversionError;
+        fn try_from(value: &str) -> ::std::result::Result<Self, self::error::ConversionError> {
+            value.parse()
+        }
+    }
+    impl ::std::convert::TryFrom<&::std::string::String> for SampleFormat {
+        type Error = self::error::ConversionError;
+        fn try_from(
+            value: &::std::string::String,
+        ) -> ::std::result::Result<Self, self::error::ConversionError> {
+            value.parse()
+        }
+    }
+    impl ::std::convert::TryFrom<::std::string::String> for SampleFormat {
+        type Error = self::error::ConversionError;
+        fn try_from(
+            value: ::std::string::String,
+        ) -> ::std::result::Result<Self, self::error::ConversionError> {
+            value.parse()
+        }
+    }
+    /**Schedule of stream start and stop.
+
+    One-shot schedule uses start_time and either stop_time or duration.
+    Both start_time and stop_time may be omitted, in which case the stream
+    is started immediately or is never stopped.
+
+    Recurring schedule uses cron and duration: stream is started at every
+    time matching cron expression and stopped after duration.
+
+    All times are in UTC.*/
+    ///
+    /// <details><summary>JSON schema</summary>
+    ///
+    /// ```json
+    ///{
+    ///  "description": "Schedule of stream start and stop.\n\nOne-shot schedule uses start_time and either stop_time or duration.\nBoth start_time and stop_time may be omitted, in which case the stream\nis started immediately or is never stopped.\n\nRecurring schedule uses cron and duration: stream is started at every\ntime matching cron expression and stopped after duration.\n\nAll times are in UTC.",
+    ///  "type": "object",
+    ///  "properties": {
+    ///    "cron": {
+    ///      "type": "string"
+    ///    },
+    ///    "duration": {
+    ///      "type": "integer",
+    ///      "format": "int32",
+    ///      "minimum": 0.0
+    ///    },
+    ///    "start_time": {
+    ///      "type": "string"
+    ///    },
+    ///    "stop_time": {
+    ///      "type": "string"
+    ///    }
+    ///  }
+    ///}
+    /// ```
+    /// </details>
+    #[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, PartialEq)]
+    pub struct ScheduleSpec {
+        #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
+        pub cron: ::std::option::Option<::std::string::String>,
+        #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
+        pub duration: ::std::option::Option<i32>,
+        #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
+        pub start_time: ::std::option::Option<::std::string::String>,
+        #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
+        pub stop_time: ::std::option::Option<::std::string::String>,
+    }
+    impl ::std::convert::From<&ScheduleSpec> for ScheduleSpec {
+        fn from(value: &ScheduleSpec) -> Self {
+            value.clone()
+        }
+    }
+    impl ::std::default::Default for ScheduleSpec {
+        fn default() -> Self {
+            Self {
+                cron: Default::default(),
+                duration: Default::default(),
+                start_time: Default::default(),
+                stop_time: Default::default(),
+            }
+        }
+    }
+    ///`SignalType`
+    ///
+    /// <details><summary>JSON schema</summary>
+    ///
+    /// ```json
+    ///{
+    ///  "type": "string",
+    ///  "enum": [
+    ///    "sine",
+    ///    "pink_noise",
+    ///    "sweep",
+    ///    "wav_file"
+    ///  ]
+    ///}
+    /// ```
+    /// </details>
+    #[derive(
+        ::serde::Deserialize,
+        ::serde::Serialize,
+        Clone,
+        Copy,
+        Debug,
+        Eq,
+        Hash,
+        Ord,
+        PartialEq,
+        PartialOrd,
+    )]
+    pub enum SignalType {
+        #[serde(rename = "sine")]
+        Sine,
+        #[serde(rename = "pink_noise")]
+        PinkNoise,
+        #[serde(rename = "sweep")]
+        Sweep,
+        #[serde(rename = "wav_file")]
+        WavFile,
+    }
+    impl ::std::convert::From<&Self> for SignalType {
+        fn from(value: &SignalType) -> Self {
+            value.clone()
+        }
+    }
+    impl ::std::fmt::Display for SignalType {
+        fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+            match *self {
+                Self::Sine => f.write_str("sine"),
+                Self::PinkNoise => f.write_str("pink_noise"),
+                Self::Sweep => f.write_str("sweep"),
+                Self::WavFile => f.write_str("wav_file"),
+            }
+        }
+    }
+    impl ::std::str::FromStr for SignalType {
+        type Err = self::error::ConversionError;
+        fn from_str(value: &str) -> ::std::result::Result<Self, self::error::ConversionError> {
+            match value {
+                "sine" => Ok(Self::Sine),
+                "pink_noise" => Ok(Self::PinkNoise),
+                "sweep" => Ok(Self::Sweep),
+                "wav_file" => Ok(Self::WavFile),
+                _ => Err("invalid value".into()),
+            }
+        }
+    }
+    impl ::std::convert::TryFrom<&str> for SignalType {
+        type Error = self::error::ConversionError;
+        fn try_from(value: &str) -> ::std::result::Result<Self, self::error::ConversionError> {
+            value.parse()
+        }
+    }
+    impl ::std::convert::TryFrom<&::std::string::String> for SignalType {
+        type Error = self::error::ConversionError;
+        fn try_from(
+            value: &::std::string::String,
+        ) -> ::std::result::Result<Self, self::error::ConversionError> {
+            value.parse()
+        }
+    }
+    impl ::std::convert::TryFrom<::std::string::String> for SignalType {
+        type Error = self::error::ConversionError;
+        fn try_from(
+            value: ::std::string::String,
+        ) -> ::std::result::Result<Self, self::error::ConversionError> {
+            value.parse()
+        }
+    }
+    /**Silence detection of sending stream.
+
+    When signal stays below threshold for hold time, stream stops sending
+    packets and enters paused state, and resumes as soon as signal returns.
+    Receivers keep playing silence while sender is paused.*/
+    ///
+    /// <details><summary>JSON schema</summary>
+    ///
+    /// ```json
+    ///{
+    ///  "description": "Silence detection of sending stream.\n\nWhen signal stays below threshold for hold time, stream stops sending\npackets and enters paused state, and resumes as soon as signal returns.\nReceivers keep playing silence while sender is paused.",
+    ///  "type": "object",
+    ///  "properties": {
+    ///    "hold_time": {
+    ///      "type": "number",
+    ///      "format": "float"
+    ///    },
+    ///    "threshold": {
+    ///      "type": "number",
+    ///      "format": "float"
+    ///    }
+    ///  }
+    ///}
+    /// ```
+    /// </details>
+    #[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, PartialEq)]
+    pub struct SilenceDetectionSpec {
+        #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
+        pub hold_time: ::std::option::Option<f32>,
+        #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
+        pub threshold: ::std::option::Option<f32>,
+    }
+    impl ::std::convert::From<&SilenceDetectionSpec> for SilenceDetectionSpec {
+        fn from(value: &SilenceDetectionSpec) -> Self {
+            value.clone()
+        }
+    }
+    impl ::std::default::Default for SilenceDetectionSpec {
+        fn default() -> Self {
+            Self { hold_time: Default::default(), threshold: Default::default() }
+        }
+    }
+    ///Change of active source of stream with failover.
+    ///
+    /// <details><summary>JSON schema</summary>
+    ///
+    /// ```json
+    ///{
+    ///  "description": "Change of active source of stream with failover.",
+    ///  "type": "object",
+    ///  "required": [
+    ///    "active_source",
+    ///    "previous_source",
+    ///    "reason"
+    ///  ],
+    ///  "properties": {
+    ///    "active_source": {
+    ///      "type": "integer",
+    ///      "format": "int32",
+    ///      "minimum": 0.0
+    ///    },
+    ///    "previous_source": {
+    ///      "type": "integer",
+    ///      "format": "int32",
+    ///      "minimum": 0.0
+    ///    },
+    ///    "reason": {
+    ///      "$ref": "#/components/schemas/SwitchReason"
+    ///    }
+    ///  }
+    ///}
+    /// ```
+    /// </details>
+    #[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, PartialEq)]
+    pub struct SourceSwitchSpec {
+        pub active_source: i32,
+        pub previous_source: i32,
+        pub reason: SwitchReason,
+    }
+    impl ::std::convert::From<&SourceSwitchSpec> for SourceSwitchSpec {
+        fn from(value: &SourceSwitchSpec) -> Self {
+            value.clone()
+        }
+    }
+    ///`StreamSpec`
+    ///
+    /// <details><summary>JSON schema</summary>
+    ///
+    /// ```json
+    ///{
+    ///  "type": "object",
+    ///  "required": [
+    ///    "destination",
+    ///    "source",
+    ///    "stream_uid",
+    ///    "stream_uri"
+    ///  ],
+    ///  "properties": {
+    ///    "active_source": {
+    ///      "readOnly": true,
+    ///      "type": "integer",
+    ///      "format": "int32",
+    ///      "minimum": 0.0
+    ///    },
+    ///    "destination": {
+    ///      "$ref": "#/components/schemas/ConnectionSpec"
+    ///    },
+    ///    "encoding": {
+    ///      "$ref": "#/components/schemas/EncodingSpec"
+    ///    },
+    ///    "error": {
+    ///      "readOnly": true,
+    ///      "type": "string"
+    ///    },
+    ///    "failover": {
+    ///      "$ref": "#/components/schemas/FailoverSpec"
+    ///    },
+    ///    "lease_expiration": {
+    ///      "readOnly": true,
+    ///      "type": "string"
+    ///    },
+    ///    "lease_ttl": {
+    ///      "type": "integer",
+    ///      "format": "int32",
+    ///      "minimum": 0.0
+    ///    },
+    ///    "next_transition": {
+    ///      "$ref": "#/components/schemas/TransitionSpec"
+    ///    },
+    ///    "owner": {
+    ///      "type": "string"
+    ///    },
+    ///    "processing": {
+    ///      "$ref": "#/components/schemas/ProcessingSpec"
+    ///    },
+    ///    "recording": {
+    ///      "$ref": "#/components/schemas/RecordingSpec"
+    ///    },
+    ///    "revision": {
+    ///      "readOnly": true,
+    ///      "type": "integer",
+    ///      "format": "int64",
+    ///      "minimum": 0.0
+    ///    },
+    ///    "schedule": {
+    ///      "$ref": "#/components/schemas/ScheduleSpec"
+    ///    },
+    ///    "silence_detection": {
+    ///      "$ref": "#/components/schemas/SilenceDetectionSpec"
+    ///    },
+    ///    "source": {
+    ///      "$ref": "#/components/schemas/ConnectionSpec"
+    ///    },
+    ///    "state": {
+    ///      "$ref": "#/components/schemas/StreamState"
+    ///    },
+    ///    "stream_uid": {
+    ///      "type": "string"
+    ///    },
+    ///    "stream_uri": {
+    ///      "type": "string"
+    ///    }
+    ///  }
+    ///}
+    /// ```
+    /// </details>
+    #[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, PartialEq)]
+    pub struct StreamSpec {
+        #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
+        pub active_source: ::std::option::Option<i32>,
+        pub destination: ConnectionSpec,
+        #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
+        pub encoding: ::std::option::Option<EncodingSpec>,
+        #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
+        pub error: ::std::option::Option<::std::string::String>,
+        #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
+        pub failover: ::std::option::Option<FailoverSpec>,
+        #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
+        pub lease_expiration: ::std::option::Option<::std::string::String>,
+        #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
+        pub lease_ttl: ::std::option::Option<i32>,
+        #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
+        pub next_transition: ::std::option::Option<TransitionSpec>,
+        #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
+        pub owner: ::std::option::Option<::std::string::String>,
+        #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
+        pub processing: ::std::option::Option<ProcessingSpec>,
+        #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
+        pub recording: ::std::option::Option<RecordingSpec>,
+        #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
+        pub revision: ::std::option::Option<i64>,
+        #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
+        pub schedule: ::std::option::Option<ScheduleSpec>,
+        #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
+        pub silence_detection: ::std::option::Option<SilenceDetectionSpec>,
+        pub source: ConnectionSpec,
+        #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
+        pub state: ::std::option::Option<StreamState>,
+        pub stream_uid: ::std::string::String,
+        pub stream_uri: ::std::string::String,
+    }
+    impl ::std::convert::From<&StreamSpec> for StreamSpec {
+        fn from(value: &StreamSpec) -> Self {
+            value.clone()
+        }
+    }
+    ///`StreamState`
+    ///
+    /// <details><summary>JSON schema</summary>
+    ///
+    /// ```json
+    ///{
+    ///  "type": "string",
+    ///  "enum": [
+    ///    "running",
+    ///    "stopped",
+    ///    "failed",
+    ///    "paused"
+    ///  ]
+    ///}
+    /// ```
+    /// </details>
+    #[derive(
+        ::serde::Deserialize,
+        ::serde::Serialize,
+        Clone,
+        Copy,
+        Debug,
+        Eq,
+        Hash,
+        Ord,
+        PartialEq,
+        PartialOrd,
+    )]
+    pub enum StreamState {
+        #[serde(rename = "running")]
+        Running,
+        #[serde(rename = "stopped")]
+        Stopped,
+        #[serde(rename = "failed")]
+        Failed,
+        #[serde(rename = "paused")]
+        Paused,
+    }
+    impl ::std::convert::From<&Self> for StreamState {
+        fn from(value: &StreamState) -> Self {
+            value.clone()
+        }
+    }
+    impl ::std::fmt::Display for StreamState {
+        fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+            match *self {
+                Self::Running => f.write_str("running"),
+                Self::Stopped => f.write_str("stopped"),
+                Self::Failed => f.write_str("failed"),
+                Self::Paused => f.write_str("paused"),
+            }
+        }
+    }
+    impl ::std::str::FromStr for StreamState {
+        type Err = self::error::ConversionError;
+        fn from_str(value: &str) -> ::std::result::Result<Self, self::error::ConversionError> {
+            match value {
+                "running" => Ok(Self::Running),
+                "stopped" => Ok(Self::Stopped),
+                "failed" => Ok(Self::Failed),
+                "paused" => Ok(Self::Paused),
+                _ => Err("invalid value".into()),
+            }
+        }
+    }
+    impl ::std::convert::TryFrom<&str> for StreamState {
+        type Error = self::error::ConversionError;
+        fn try_from(value: &str) -> ::std::result::Result<Self, self::error::ConversionError> {
+            value.parse()
+        }
+    }
+    impl ::std::convert::TryFrom<&::std::string::String> for StreamState {
+        type Error = self::error::ConversionError;
+        fn try_from(
+            value: &::std::string::String,
+        ) -> ::std::result::Result<Self, self::error::ConversionError> {
+            value.parse()
+        }
+    }
+    impl ::std::convert::TryFrom<::std::string::String> for StreamState {
+        type Error = self::error::ConversionError;
+        fn try_from(
+            value: ::std::string::String,
+        ) -> ::std::result::Result<Self, self::error::ConversionError> {
+            value.parse()
+        }
+    }
+    ///`SwitchReason`
+    ///
+    /// <details><summary>JSON schema</summary>
+    ///
+    /// ```json
+    ///{
+    ///  "type": "string",
+    ///  "enum": [
+    ///    "failed",
+    ///    "silent",
+    ///    "recovered"
+    ///  ]
+    ///}
+    /// ```
+    /// </details>
+    #[derive(
+        ::serde::Deserialize,
+        ::serde::Serialize,
+        Clone,
+        Copy,
+        Debug,
+        Eq,
+        Hash,
+        Ord,
+        PartialEq,
+        PartialOrd,
+    )]
+    pub enum SwitchReason {
+        #[serde(rename = "failed")]
+        Failed,
+        #[serde(rename = "silent")]
+        Silent,
+        #[serde(rename = "recovered")]
+        Recovered,
+    }
+    impl ::std::convert::From<&Self> for SwitchReason {
+        fn from(value: &SwitchReason) -> Self {
+            value.clone()
+        }
+    }
+    impl ::std::fmt::Display for SwitchReason {
+        fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+            match *self {
+                Self::Failed => f.write_str("failed"),
+                Self::Silent => f.write_str("silent"),
+                Self::Recovered => f.write_str("recovered"),
+            }
+        }
+    }
+    impl ::std::str::FromStr for SwitchReason {
+        type Err = self::error::ConversionError;
+        fn from_str(value: &str) -> ::std::result::Result<Self, self::error::ConversionError> {
+            match value {
+                "failed" => Ok(Self::Failed),
+                "silent" => Ok(Self::Silent),
+                "recovered" => Ok(Self::Recovered),
+                _ => Err("invalid value".into()),
+            }
+        }
+    }
+    impl ::std::convert::TryFrom<&str> for SwitchReason {
+        type Error = self::error::ConversionError;
+        fn try_from(value: &str) -> ::std::result::Result<Self, self::error::ConversionError> {
+            value.parse()
+        }
+    }
+    impl ::std::convert::TryFrom<&::std::string::String> for SwitchReason {
+        type Error = self::error::ConversionError;
+        fn try_from(
+            value: &::std::string::String,
+        ) -> ::std::result::Result<Self, self::error::ConversionError> {
+            value.parse()
+        }
+    }
+    impl ::std::convert::TryFrom<::std::string::String> for SwitchReason {
+        type Error = self::error::ConversionError;
+        fn try_from(
+            value: ::std::string::String,
+        ) -> ::std::result::Result<Self, self::error::ConversionError> {
+            value.parse()
+        }
+    }
+    ///Planned change of stream state.
+    ///
+    /// <details><summary>JSON schema</summary>
+    ///
+    /// ```json
+    ///{
+    ///  "description": "Planned change of stream state.",
+    ///  "type": "object",
+    ///  "required": [
+    ///    "state",
+    ///    "time"
+    ///  ],
+    ///  "properties": {
+    ///    "state": {
+    ///      "$ref": "#/components/schemas/StreamState"
+    ///    },
+    ///    "time": {
+    ///      "type": "string"
+    ///    }
+    ///  }
+    ///}
+    /// ```
+    /// </details>
+    #[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, PartialEq)]
+    pub struct TransitionSpec {
+        pub state: StreamState,
+        pub time: ::std::string::String,
+    }
+    impl ::std::convert::From<&TransitionSpec> for TransitionSpec {
+        fn from(value: &TransitionSpec) -> Self {
+            value.clone()
+        }
+    }
+    ///Version and build configuration of server.
+    ///
+    /// <details><summary>JSON schema</summary>
+    ///
+    /// ```json
+    ///{
+    ///  "description": "Version and build configuration of server.",
+    ///  "type": "object",
+    ///  "required": [
+    ///    "active_driver",
+    ///    "features",
+    ///    "supported_drivers",
+    ///    "version"
+    ///  ],
+    ///  "properties": {
+    ///    "active_driver": {
+    ///      "$ref": "#/components/schemas/DriverId"
+    ///    },
+    ///    "features": {
+    ///      "type": "array",
+    ///      "items": {
+    ///        "type": "string"
+    ///      }
+    ///    },
+    ///    "supported_drivers": {
+    ///      "type": "array",
+    ///      "items": {
+    ///        "$ref": "#/components/schemas/DriverId"
+    ///      }
+    ///    },
+    ///    "version": {
+    ///      "type": "string"
+    ///    }
+    ///  }
+    ///}
+    /// ```
+    /// </details>
+    #[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, PartialEq)]
+    pub struct VersionSpec {
+        pub active_driver: DriverId,
+        pub features: ::std::vec::Vec<::std::string::String>,
+        pub supported_drivers: ::std::vec::Vec<DriverId>,
+        pub version: ::std::string::String,
+    }
+    impl ::std::convert::From<&VersionSpec> for VersionSpec {
+        fn from(value: &VersionSpec) -> Self {
+            value.clone()
+        }
+    }
+    /**Built-in signal generator used as endpoint.
+
+    Virtual source endpoint can be used as source of any stream, e.g. to
+    check a speaker chain without external player. Parameters can be changed
+    while streams are running, and are applied immediately.*/
+    ///
+    /// <details><summary>JSON schema</summary>
+    ///
+    /// ```json
+    ///{
+    ///  "description": "Built-in signal generator used as endpoint.\n\nVirtual source endpoint can be used as source of any stream, e.g. to\ncheck a speaker chain without external player. Parameters can be changed\nwhile streams are running, and are applied immediately.",
+    ///  "type": "object",
+    ///  "required": [
+    ///    "signal"
+    ///  ],
+    ///  "properties": {
+    ///    "channel": {
+    ///      "type": "integer",
+    ///      "format": "int32",
+    ///      "minimum": 0.0
+    ///    },
+    ///    "end_frequency": {
+    ///      "type": "number",
+    ///      "format": "float"
+    ///    },
+    ///    "file_path": {
+    ///      "type": "string"
+    ///    },
+    ///    "frequency": {
+    ///      "type": "number",
+    ///      "format": "float"
+    ///    },
+    ///    "level": {
+    ///      "type": "number",
+    ///      "format": "float"
+    ///    },
+    ///    "signal": {
+    ///      "$ref": "#/components/schemas/SignalType"
+    ///    },
+    ///    "sweep_duration": {
+    ///      "type": "number",
+    ///      "format": "float"
+    ///    }
+    ///  }
+    ///}
+    /// ```
+    /// </details>
+    #[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, PartialEq)]
+    pub struct VirtualSourceSpec {
+        #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
+        pub channel: ::std::option::Option<i32>,
+        #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
+        pub end_frequency: ::std::option::Option<f32>,
+        #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
+        pub file_path: ::std::option::Option<::std::string::String>,
+        #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
+        pub frequency: ::std::option::Option<f32>,
+        #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
+        pub level: ::std::option::Option<f32>,
+        pub signal: SignalType,
+        #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
+        pub sweep_duration: ::std::option::Option<f32>,
+    }
+    impl ::std::convert::From<&VirtualSourceSpec> for VirtualSourceSpec {
+        fn from(value: &VirtualSourceSpec) -> Self {
+            value.clone()
+        }
+    }
+}
+#[derive(Clone, Debug)]
+/**Client for rocd REST API
+
+Real-time audio streaming daemon.
+
+Version: 0.1.0*/
+pub struct Client {
+    pub(crate) baseurl: String,
+    pub(crate) client: reqwest::Client,
+}
+impl Client {
+    /// Create a new client.
+    ///
+    /// `baseurl` is the base URL provided to the internal
+    /// `reqwest::Client`, and should include a scheme and hostname,
+    /// as well as port and a path stem if applicable.
+    pub fn new(baseurl: &str) -> Self {
+        #[cfg(not(target_arch = "wasm32"))]
+        let client = {
+            let dur = ::std::time::Duration::from_secs(15u64);
+            reqwest::ClientBuilder::new().connect_timeout(dur).timeout(dur)
+        };
+        #[cfg(target_arch = "wasm32")]
+        let client = reqwest::ClientBuilder::new();
+        Self::new_with_client(baseurl, client.build().unwrap())
+    }
+    /// Construct a new client with an existing `reqwest::Client`,
+    /// allowing more control over its configuration.
+    ///
+    /// `baseurl` is the base URL provided to the internal
+    /// `reqwest::Client`, and should include a scheme and hostname,
+    /// as well as port and a path stem if applicable.
+    pub fn new_with_client(baseurl: &str, client: reqwest::Client) -> Self {
+        Self { baseurl: baseurl.to_string(), client }
+    }
+}
+impl ClientInfo<()> for Client {
+    fn api_version() -> &'static str {
+        "0.1.0"
+    }
+    fn baseurl(&self) -> &str {
+        self.baseurl.as_str()
+    }
+    fn client(&self) -> &reqwest::Client {
+        &self.client
+    }
+    fn inner(&self) -> &() {
+        &()
+    }
+}
+impl ClientHooks<()> for &Client {}
+#[allow(clippy::all)]
 impl Client {
-    /**Sends a `GET` request to `/peers`
+    /**Sends a `GET` request to `/api/v1/events`
+
+    Arguments:
+    - `levels`: If true, levels_updated events are delivered too.
+    Level metering is enabled while there are such subscribers.
+    */
+    pub async fn subscribe_events<'a>(
+        &'a self, levels: Option<bool>,
+    ) -> Result<ResponseValue<ByteStream>, Error<()>> {
+        let url = format!("{}/api/v1/events", self.baseurl,);
+        let mut header_map = ::reqwest::header::HeaderMap::with_capacity(1usize);
+        header_map.append(
+            ::reqwest::header::HeaderName::from_static("api-version"),
+            ::reqwest::header::HeaderValue::from_static(Self::api_version()),
+        );
+        #[allow(unused_mut)]
+        let mut request = self
+            .client
+            .get(url)
+            .query(&progenitor_client::QueryParam::new("levels", &levels))
+            .headers(header_map)
+            .build()?;
+        let info = OperationInfo { operation_id: "subscribe_events" };
+        self.pre(&mut request, &info).await?;
+        let result = self.exec(request, &info).await;
+        self.post(&result, &info).await?;
+        let response = result?;
+        match response.status().as_u16() {
+            200u16 => Ok(ResponseValue::stream(response)),
+            _ => Err(Error::UnexpectedResponse(response)),
+        }
+    }
+    /**Sends a `GET` request to `/api/v1/groups`
+
+    */
+    pub async fn list_groups<'a>(
+        &'a self,
+    ) -> Result<ResponseValue<::std::vec::Vec<types::GroupSpec>>, Error<()>> {
+        let url = format!("{}/api/v1/groups", self.baseurl,);
+        let mut header_map = ::reqwest::header::HeaderMap::with_capacity(1usize);
+        header_map.append(
+            ::reqwest::header::HeaderName::from_static("api-version"),
+            ::reqwest::header::HeaderValue::from_static(Self::api_version()),
+        );
+        #[allow(unused_mut)]
+        let mut request = self
+            .client
+            .get(url)
+            .header(
+                ::reqwest::header::ACCEPT,
+                ::reqwest::header::HeaderValue::from_static("application/json"),
+            )
+            .headers(header_map)
+            .build()?;
+        let info = OperationInfo { operation_id: "list_groups" };
+        self.pre(&mut request, &info).await?;
+        let result = self.exec(request, &info).await;
+        self.post(&result, &info).await?;
+        let response = result?;
+        match response.status().as_u16() {
+            200u16 => ResponseValue::from_response(response).await,
+            _ => Err(Error::UnexpectedResponse(response)),
+        }
+    }
+    /**Sends a `GET` request to `/api/v1/groups/{group_uid}`
+
+    */
+    pub async fn read_group<'a>(
+        &'a self, group_uid: &'a str,
+    ) -> Result<ResponseValue<types::GroupSpec>, Error<()>> {
+        let url =
+            format!("{}/api/v1/groups/{}", self.baseurl, encode_path(&group_uid.to_string()),);
+        let mut header_map = ::reqwest::header::HeaderMap::with_capacity(1usize);
+        header_map.append(
+            ::reqwest::header::HeaderName::from_static("api-version"),
+            ::reqwest::header::HeaderValue::from_static(Self::api_version()),
+        );
+        #[allow(unused_mut)]
+        let mut request = self
+            .client
+            .get(url)
+            .header(
+                ::reqwest::header::ACCEPT,
+                ::reqwest::header::HeaderValue::from_static("application/json"),
+            )
+            .headers(header_map)
+            .build()?;
+        let info = OperationInfo { operation_id: "read_group" };
+        self.pre(&mut request, &info).await?;
+        let result = self.exec(request, &info).await;
+        self.post(&result, &info).await?;
+        let response = result?;
+        match response.status().as_u16() {
+            200u16 => ResponseValue::from_response(response).await,
+            _ => Err(Error::UnexpectedResponse(response)),
+        }
+    }
+    /**Sends a `PUT` request to `/api/v1/groups/{group_uid}`
+
+    */
+    pub async fn update_group<'a>(
+        &'a self, group_uid: &'a str, body: &'a types::GroupSpec,
+    ) -> Result<ResponseValue<types::GroupSpec>, Error<()>> {
+        let url =
+            format!("{}/api/v1/groups/{}", self.baseurl, encode_path(&group_uid.to_string()),);
+        let mut header_map = ::reqwest::header::HeaderMap::with_capacity(1usize);
+        header_map.append(
+            ::reqwest::header::HeaderName::from_static("api-version"),
+            ::reqwest::header::HeaderValue::from_static(Self::api_version()),
+        );
+        #[allow(unused_mut)]
+        let mut request = self
+            .client
+            .put(url)
+            .header(
+                ::reqwest::header::ACCEPT,
+                ::reqwest::header::HeaderValue::from_static("application/json"),
+            )
+            .json(&body)
+            .headers(header_map)
+            .build()?;
+        let info = OperationInfo { operation_id: "update_group" };
+        self.pre(&mut request, &info).await?;
+        let result = self.exec(request, &info).await;
+        self.post(&result, &info).await?;
+        let response = result?;
+        match response.status().as_u16() {
+            200u16 => ResponseValue::from_response(response).await,
+            _ => Err(Error::UnexpectedResponse(response)),
+        }
+    }
+    /**Sends a `DELETE` request to `/api/v1/groups/{group_uid}`
+
+    */
+    pub async fn delete_group<'a>(
+        &'a self, group_uid: &'a str,
+    ) -> Result<ResponseValue<()>, Error<()>> {
+        let url =
+            format!("{}/api/v1/groups/{}", self.baseurl, encode_path(&group_uid.to_string()),);
+        let mut header_map = ::reqwest::header::HeaderMap::with_capacity(1usize);
+        header_map.append(
+            ::reqwest::header::HeaderName::from_static("api-version"),
+            ::reqwest::header::HeaderValue::from_static(Self::api_version()),
+        );
+        #[allow(unused_mut)]
+        let mut request = self.client.delete(url).headers(header_map).build()?;
+        let info = OperationInfo { operation_id: "delete_group" };
+        self.pre(&mut request, &info).await?;
+        let result = self.exec(request, &info).await;
+        self.post(&result, &info).await?;
+        let response = result?;
+        match response.status().as_u16() {
+            204u16 => Ok(ResponseValue::empty(response)),
+            _ => Err(Error::UnexpectedResponse(response)),
+        }
+    }
+    /**Sends a `GET` request to `/api/v1/groups/{group_uid}/status`
+
+    */
+    pub async fn read_group_status<'a>(
+        &'a self, group_uid: &'a str,
+    ) -> Result<ResponseValue<types::GroupStatusSpec>, Error<()>> {
+        let url = format!(
+            "{}/api/v1/groups/{}/status",
+            self.baseurl,
+            encode_path(&group_uid.to_string()),
+        );
+        let mut header_map = ::reqwest::header::HeaderMap::with_capacity(1usize);
+        header_map.append(
+            ::reqwest::header::HeaderName::from_static("api-version"),
+            ::reqwest::header::HeaderValue::from_static(Self::api_version()),
+        );
+        #[allow(unused_mut)]
+        let mut request = self
+            .client
+            .get(url)
+            .header(
+                ::reqwest::header::ACCEPT,
+                ::reqwest::header::HeaderValue::from_static("application/json"),
+            )
+            .headers(header_map)
+            .build()?;
+        let info = OperationInfo { operation_id: "read_group_status" };
+        self.pre(&mut request, &info).await?;
+        let result = self.exec(request, &info).await;
+        self.post(&result, &info).await?;
+        let response = result?;
+        match response.status().as_u16() {
+            200u16 => ResponseValue::from_response(response).await,
+            _ => Err(Error::UnexpectedResponse(response)),
+        }
+    }
+    /**Sends a `GET` request to `/api/v1/health`
+
+    */
+    pub async fn read_health<'a>(
+        &'a self,
+    ) -> Result<ResponseValue<types::HealthSpec>, Error<types::HealthSpec>> {
+        let url = format!("{}/api/v1/health", self.baseurl,);
+        let mut header_map = ::reqwest::header::HeaderMap::with_capacity(1usize);
+        header_map.append(
+            ::reqwest::header::HeaderName::from_static("api-version"),
+            ::reqwest::header::HeaderValue::from_static(Self::api_version()),
+        );
+        #[allow(unused_mut)]
+        let mut request = self
+            .client
+            .get(url)
+            .header(
+                ::reqwest::header::ACCEPT,
+                ::reqwest::header::HeaderValue::from_static("application/json"),
+            )
+            .headers(header_map)
+            .build()?;
+        let info = OperationInfo { operation_id: "read_health" };
+        self.pre(&mut request, &info).await?;
+        let result = self.exec(request, &info).await;
+        self.post(&result, &info).await?;
+        let response = result?;
+        match response.status().as_u16() {
+            200u16 => ResponseValue::from_response(response).await,
+            503u16 => Err(Error::ErrorResponse(ResponseValue::from_response(response).await?)),
+            _ => Err(Error::UnexpectedResponse(response)),
+        }
+    }
+    /**Sends a `GET` request to `/api/v1/peers`
 
-*/
+    Arguments:
+    - `cursor`: Value of x-next-cursor header of previous page.
+    - `limit`: Maximum number of returned peers; all by default.
+    - `order`: Sort direction (default asc).
+    - `sort`: Field by which peers are sorted (default peer_uid).
+    */
     pub async fn list_peers<'a>(
-        &'a self,
+        &'a self, cursor: Option<&'a str>, limit: Option<i32>,
+        order: Option<types::ListPeersOrder>, sort: Option<types::ListPeersSort>,
     ) -> Result<ResponseValue<::std::vec::Vec<types::PeerSpec>>, Error<()>> {
-        let url = format!("{}/peers", self.baseurl,);
+        let url = format!("{}/api/v1/peers", self.baseurl,);
         let mut header_map = ::reqwest::header::HeaderMap::with_capacity(1usize);
-        header_map
-            .append(
-                ::reqwest::header::HeaderName::from_static("api-version"),
-                ::reqwest::header::HeaderValue::from_static(Self::api_version()),
-            );
+        header_map.append(
+            ::reqwest::header::HeaderName::from_static("api-version"),
+            ::reqwest::header::HeaderValue::from_static(Self::api_version()),
+        );
         #[allow(unused_mut)]
         let mut request = self
             .client
@@ -645,11 +2936,13 @@ impl Client {
                 ::reqwest::header::ACCEPT,
                 ::reqwest::header::HeaderValue::from_static("application/json"),
             )
+            .query(&progenitor_client::QueryParam::new("cursor", &cursor))
+            .query(&progenitor_client::QueryParam::new("limit", &limit))
+            .query(&progenitor_client::QueryParam::new("order", &order))
+            .query(&progenitor_client::QueryParam::new("sort", &sort))
             .headers(header_map)
             .build()?;
-        let info = OperationInfo {
-            operation_id: "list_peers",
-        };
+        let info = OperationInfo { operation_id: "list_peers" };
         self.pre(&mut request, &info).await?;
         let result = self.exec(request, &info).await;
         self.post(&result, &info).await?;
@@ -659,22 +2952,19 @@ impl Client {
             _ => Err(Error::UnexpectedResponse(response)),
         }
     }
-    /**Sends a `GET` request to `/peers/{peer_uid}`
+    /**Sends a `GET` request to `/api/v1/peers/{peer_uid}`
 
-*/
+    */
     pub async fn read_peer<'a>(
-        &'a self,
-        peer_uid: &'a str,
+        &'a self, peer_uid: &'a str,
     ) -> Result<ResponseValue<types::PeerSpec>, Error<()>> {
-        let url = format!(
-            "{}/peers/{}", self.baseurl, encode_path(& peer_uid.to_string()),
-        );
+        let url =
+            format!("{}/api/v1/peers/{}", self.baseurl, encode_path(&peer_uid.to_string()),);
         let mut header_map = ::reqwest::header::HeaderMap::with_capacity(1usize);
-        header_map
-            .append(
-                ::reqwest::header::HeaderName::from_static("api-version"),
-                ::reqwest::header::HeaderValue::from_static(Self::api_version()),
-            );
+        header_map.append(
+            ::reqwest::header::HeaderName::from_static("api-version"),
+            ::reqwest::header::HeaderValue::from_static(Self::api_version()),
+        );
         #[allow(unused_mut)]
         let mut request = self
             .client
@@ -685,9 +2975,7 @@ impl Client {
             )
             .headers(header_map)
             .build()?;
-        let info = OperationInfo {
-            operation_id: "read_peer",
-        };
+        let info = OperationInfo { operation_id: "read_peer" };
         self.pre(&mut request, &info).await?;
         let result = self.exec(request, &info).await;
         self.post(&result, &info).await?;
@@ -697,22 +2985,27 @@ impl Client {
             _ => Err(Error::UnexpectedResponse(response)),
         }
     }
-    /**Sends a `PUT` request to `/peers/{peer_uid}`
+    /**Sends a `PUT` request to `/api/v1/peers/{peer_uid}`
 
-*/
+    Arguments:
+    - `peer_uid`
+    - `if_match`: ETag of entity, as returned by GET; if entity was modified since
+    then, request fails with 412.
+    - `body`
+    */
     pub async fn update_peer<'a>(
-        &'a self,
-        peer_uid: &'a str,
+        &'a self, peer_uid: &'a str, if_match: Option<&'a str>, body: &'a types::PeerSpec,
     ) -> Result<ResponseValue<types::PeerSpec>, Error<()>> {
-        let url = format!(
-            "{}/peers/{}", self.baseurl, encode_path(& peer_uid.to_string()),
+        let url =
+            format!("{}/api/v1/peers/{}", self.baseurl, encode_path(&peer_uid.to_string()),);
+        let mut header_map = ::reqwest::header::HeaderMap::with_capacity(2usize);
+        header_map.append(
+            ::reqwest::header::HeaderName::from_static("api-version"),
+            ::reqwest::header::HeaderValue::from_static(Self::api_version()),
         );
-        let mut header_map = ::reqwest::header::HeaderMap::with_capacity(1usize);
-        header_map
-            .append(
-                ::reqwest::header::HeaderName::from_static("api-version"),
-                ::reqwest::header::HeaderValue::from_static(Self::api_version()),
-            );
+        if let Some(value) = if_match {
+            header_map.append("if-match", value.to_string().try_into()?);
+        }
         #[allow(unused_mut)]
         let mut request = self
             .client
@@ -721,11 +3014,10 @@ impl Client {
                 ::reqwest::header::ACCEPT,
                 ::reqwest::header::HeaderValue::from_static("application/json"),
             )
+            .json(&body)
             .headers(header_map)
             .build()?;
-        let info = OperationInfo {
-            operation_id: "update_peer",
-        };
+        let info = OperationInfo { operation_id: "update_peer" };
         self.pre(&mut request, &info).await?;
         let result = self.exec(request, &info).await;
         self.post(&result, &info).await?;
@@ -735,22 +3027,34 @@ impl Client {
             _ => Err(Error::UnexpectedResponse(response)),
         }
     }
-    /**Sends a `GET` request to `/peers/{peer_uid}/endpoints`
+    /**Sends a `GET` request to `/api/v1/peers/{peer_uid}/endpoints`
 
-*/
+    Arguments:
+    - `peer_uid`
+    - `cursor`: Value of x-next-cursor header of previous page.
+    - `driver`: Return only endpoints of this driver.
+    - `endpoint_type`: Return only endpoints of this type.
+    - `limit`: Maximum number of returned endpoints; all by default.
+    - `order`: Sort direction (default asc).
+    - `sort`: Field by which endpoints are sorted (default endpoint_uid).
+    - `stream_direction`: Return only endpoints with this direction.
+    */
     pub async fn list_endpoints<'a>(
-        &'a self,
-        peer_uid: &'a str,
+        &'a self, peer_uid: &'a str, cursor: Option<&'a str>, driver: Option<types::DriverId>,
+        endpoint_type: Option<types::EndpointType>, limit: Option<i32>,
+        order: Option<types::ListEndpointsOrder>, sort: Option<types::ListEndpointsSort>,
+        stream_direction: Option<types::EndpointDir>,
     ) -> Result<ResponseValue<::std::vec::Vec<types::EndpointSpec>>, Error<()>> {
         let url = format!(
-            "{}/peers/{}/endpoints", self.baseurl, encode_path(& peer_uid.to_string()),
+            "{}/api/v1/peers/{}/endpoints",
+            self.baseurl,
+            encode_path(&peer_uid.to_string()),
         );
         let mut header_map = ::reqwest::header::HeaderMap::with_capacity(1usize);
-        header_map
-            .append(
-                ::reqwest::header::HeaderName::from_static("api-version"),
-                ::reqwest::header::HeaderValue::from_static(Self::api_version()),
-            );
+        header_map.append(
+            ::reqwest::header::HeaderName::from_static("api-version"),
+            ::reqwest::header::HeaderValue::from_static(Self::api_version()),
+        );
         #[allow(unused_mut)]
         let mut request = self
             .client
@@ -759,11 +3063,16 @@ impl Client {
                 ::reqwest::header::ACCEPT,
                 ::reqwest::header::HeaderValue::from_static("application/json"),
             )
+            .query(&progenitor_client::QueryParam::new("cursor", &cursor))
+            .query(&progenitor_client::QueryParam::new("driver", &driver))
+            .query(&progenitor_client::QueryParam::new("endpoint_type", &endpoint_type))
+            .query(&progenitor_client::QueryParam::new("limit", &limit))
+            .query(&progenitor_client::QueryParam::new("order", &order))
+            .query(&progenitor_client::QueryParam::new("sort", &sort))
+            .query(&progenitor_client::QueryParam::new("stream_direction", &stream_direction))
             .headers(header_map)
             .build()?;
-        let info = OperationInfo {
-            operation_id: "list_endpoints",
-        };
+        let info = OperationInfo { operation_id: "list_endpoints" };
         self.pre(&mut request, &info).await?;
         let result = self.exec(request, &info).await;
         self.post(&result, &info).await?;
@@ -773,24 +3082,23 @@ impl Client {
             _ => Err(Error::UnexpectedResponse(response)),
         }
     }
-    /**Sends a `GET` request to `/peers/{peer_uid}/endpoints/{endpoint_uid}`
+    /**Sends a `GET` request to `/api/v1/peers/{peer_uid}/endpoints/{endpoint_uid}`
 
-*/
+    */
     pub async fn read_endpoint<'a>(
-        &'a self,
-        peer_uid: &'a str,
-        endpoint_uid: &'a str,
+        &'a self, peer_uid: &'a str, endpoint_uid: &'a str,
     ) -> Result<ResponseValue<types::EndpointSpec>, Error<()>> {
         let url = format!(
-            "{}/peers/{}/endpoints/{}", self.baseurl, encode_path(& peer_uid
-            .to_string()), encode_path(& endpoint_uid.to_string()),
+            "{}/api/v1/peers/{}/endpoints/{}",
+            self.baseurl,
+            encode_path(&peer_uid.to_string()),
+            encode_path(&endpoint_uid.to_string()),
         );
         let mut header_map = ::reqwest::header::HeaderMap::with_capacity(1usize);
-        header_map
-            .append(
-                ::reqwest::header::HeaderName::from_static("api-version"),
-                ::reqwest::header::HeaderValue::from_static(Self::api_version()),
-            );
+        header_map.append(
+            ::reqwest::header::HeaderName::from_static("api-version"),
+            ::reqwest::header::HeaderValue::from_static(Self::api_version()),
+        );
         #[allow(unused_mut)]
         let mut request = self
             .client
@@ -801,9 +3109,7 @@ impl Client {
             )
             .headers(header_map)
             .build()?;
-        let info = OperationInfo {
-            operation_id: "read_endpoint",
-        };
+        let info = OperationInfo { operation_id: "read_endpoint" };
         self.pre(&mut request, &info).await?;
         let result = self.exec(request, &info).await;
         self.post(&result, &info).await?;
@@ -813,24 +3119,33 @@ impl Client {
             _ => Err(Error::UnexpectedResponse(response)),
         }
     }
-    /**Sends a `PUT` request to `/peers/{peer_uid}/endpoints/{endpoint_uid}`
+    /**Sends a `PUT` request to `/api/v1/peers/{peer_uid}/endpoints/{endpoint_uid}`
 
-*/
+    Arguments:
+    - `peer_uid`
+    - `endpoint_uid`
+    - `if_match`: ETag of entity, as returned by GET; if entity was modified since
+    then, request fails with 412.
+    - `body`
+    */
     pub async fn update_endpoint<'a>(
-        &'a self,
-        peer_uid: &'a str,
-        endpoint_uid: &'a str,
+        &'a self, peer_uid: &'a str, endpoint_uid: &'a str, if_match: Option<&'a str>,
+        body: &'a types::EndpointSpec,
     ) -> Result<ResponseValue<types::EndpointSpec>, Error<()>> {
         let url = format!(
-            "{}/peers/{}/endpoints/{}", self.baseurl, encode_path(& peer_uid
-            .to_string()), encode_path(& endpoint_uid.to_string()),
+            "{}/api/v1/peers/{}/endpoints/{}",
+            self.baseurl,
+            encode_path(&peer_uid.to_string()),
+            encode_path(&endpoint_uid.to_string()),
         );
-        let mut header_map = ::reqwest::header::HeaderMap::with_capacity(1usize);
-        header_map
-            .append(
-                ::reqwest::header::HeaderName::from_static("api-version"),
-                ::reqwest::header::HeaderValue::from_static(Self::api_version()),
-            );
+        let mut header_map = ::reqwest::header::HeaderMap::with_capacity(2usize);
+        header_map.append(
+            ::reqwest::header::HeaderName::from_static("api-version"),
+            ::reqwest::header::HeaderValue::from_static(Self::api_version()),
+        );
+        if let Some(value) = if_match {
+            header_map.append("if-match", value.to_string().try_into()?);
+        }
         #[allow(unused_mut)]
         let mut request = self
             .client
@@ -839,11 +3154,10 @@ impl Client {
                 ::reqwest::header::ACCEPT,
                 ::reqwest::header::HeaderValue::from_static("application/json"),
             )
+            .json(&body)
             .headers(header_map)
             .build()?;
-        let info = OperationInfo {
-            operation_id: "update_endpoint",
-        };
+        let info = OperationInfo { operation_id: "update_endpoint" };
         self.pre(&mut request, &info).await?;
         let result = self.exec(request, &info).await;
         self.post(&result, &info).await?;
@@ -853,19 +3167,106 @@ impl Client {
             _ => Err(Error::UnexpectedResponse(response)),
         }
     }
-    /**Sends a `GET` request to `/streams`
+    /**Sends a `DELETE` request to `/api/v1/peers/{peer_uid}/endpoints/{endpoint_uid}`
 
-*/
-    pub async fn list_streams<'a>(
+    Arguments:
+    - `peer_uid`
+    - `endpoint_uid`
+    - `if_match`: ETag of entity, as returned by GET; if entity was modified since
+    then, request fails with 412.
+    */
+    pub async fn delete_endpoint<'a>(
+        &'a self, peer_uid: &'a str, endpoint_uid: &'a str, if_match: Option<&'a str>,
+    ) -> Result<ResponseValue<()>, Error<()>> {
+        let url = format!(
+            "{}/api/v1/peers/{}/endpoints/{}",
+            self.baseurl,
+            encode_path(&peer_uid.to_string()),
+            encode_path(&endpoint_uid.to_string()),
+        );
+        let mut header_map = ::reqwest::header::HeaderMap::with_capacity(2usize);
+        header_map.append(
+            ::reqwest::header::HeaderName::from_static("api-version"),
+            ::reqwest::header::HeaderValue::from_static(Self::api_version()),
+        );
+        if let Some(value) = if_match {
+            header_map.append("if-match", value.to_string().try_into()?);
+        }
+        #[allow(unused_mut)]
+        let mut request = self.client.delete(url).headers(header_map).build()?;
+        let info = OperationInfo { operation_id: "delete_endpoint" };
+        self.pre(&mut request, &info).await?;
+        let result = self.exec(request, &info).await;
+        self.post(&result, &info).await?;
+        let response = result?;
+        match response.status().as_u16() {
+            204u16 => Ok(ResponseValue::empty(response)),
+            _ => Err(Error::UnexpectedResponse(response)),
+        }
+    }
+    /**Sends a `GET` request to `/api/v1/ready`
+
+    */
+    pub async fn read_readiness<'a>(
         &'a self,
+    ) -> Result<ResponseValue<types::ReadinessSpec>, Error<types::ReadinessSpec>> {
+        let url = format!("{}/api/v1/ready", self.baseurl,);
+        let mut header_map = ::reqwest::header::HeaderMap::with_capacity(1usize);
+        header_map.append(
+            ::reqwest::header::HeaderName::from_static("api-version"),
+            ::reqwest::header::HeaderValue::from_static(Self::api_version()),
+        );
+        #[allow(unused_mut)]
+        let mut request = self
+            .client
+            .get(url)
+            .header(
+                ::reqwest::header::ACCEPT,
+                ::reqwest::header::HeaderValue::from_static("application/json"),
+            )
+            .headers(header_map)
+            .build()?;
+        let info = OperationInfo { operation_id: "read_readiness" };
+        self.pre(&mut request, &info).await?;
+        let result = self.exec(request, &info).await;
+        self.post(&result, &info).await?;
+        let response = result?;
+        match response.status().as_u16() {
+            200u16 => ResponseValue::from_response(response).await,
+            503u16 => Err(Error::ErrorResponse(ResponseValue::from_response(response).await?)),
+            _ => Err(Error::UnexpectedResponse(response)),
+        }
+    }
+    /**Sends a `GET` request to `/api/v1/streams`
+
+    Arguments:
+    - `cursor`: Value of x-next-cursor header of previous page.
+    - `destination_type`: Return only streams which destination has this connection type,
+    e.g. external for streams sending to network.
+    - `destination_uri`: Return only streams which destination has this endpoint URI,
+    or this media, repair, or control URI.
+    - `limit`: Maximum number of returned streams; all by default.
+    - `order`: Sort direction (default asc).
+    - `sort`: Field by which streams are sorted (default stream_uid).
+    - `source_type`: Return only streams which source has this connection type,
+    e.g. external for streams receiving from network.
+    - `source_uri`: Return only streams which source has this endpoint URI,
+    or this media, repair, or control URI.
+    - `state`: Return only streams in this state.
+    */
+    pub async fn list_streams<'a>(
+        &'a self, cursor: Option<&'a str>, destination_type: Option<types::ConnectionType>,
+        destination_uri: Option<&'a str>, limit: Option<i32>,
+        order: Option<types::ListStreamsOrder>, sort: Option<types::ListStreamsSort>,
+        source_type: Option<types::ConnectionType>, source_uri: Option<&'a str>,
+        state: Option<types::StreamState>,
     ) -> Result<ResponseValue<::std::vec::Vec<types::StreamSpec>>, Error<()>> {
-        let url = format!("{}/streams", self.baseurl,);
+        let url = format!("{}/api/v1/streams", self.baseurl,);
         let mut header_map = ::reqwest::header::HeaderMap::with_capacity(1usize);
-        header_map
-            .append(
-                ::reqwest::header::HeaderName::from_static("api-version"),
-                ::reqwest::header::HeaderValue::from_static(Self::api_version()),
-            );
+        header_map.append(
+            ::reqwest::header::HeaderName::from_static("api-version"),
+            ::reqwest::header::HeaderValue::from_static(Self::api_version()),
+        );
         #[allow(unused_mut)]
         let mut request = self
             .client
@@ -874,11 +3275,18 @@ impl Client {
                 ::reqwest::header::ACCEPT,
                 ::reqwest::header::HeaderValue::from_static("application/json"),
             )
+            .query(&progenitor_client::QueryParam::new("cursor", &cursor))
+            .query(&progenitor_client::QueryParam::new("destination_type", &destination_type))
+            .query(&progenitor_client::QueryParam::new("destination_uri", &destination_uri))
+            .query(&progenitor_client::QueryParam::new("limit", &limit))
+            .query(&progenitor_client::QueryParam::new("order", &order))
+            .query(&progenitor_client::QueryParam::new("sort", &sort))
+            .query(&progenitor_client::QueryParam::new("source_type", &source_type))
+            .query(&progenitor_client::QueryParam::new("source_uri", &source_uri))
+            .query(&progenitor_client::QueryParam::new("state", &state))
             .headers(header_map)
             .build()?;
-        let info = OperationInfo {
-            operation_id: "list_streams",
-        };
+        let info = OperationInfo { operation_id: "list_streams" };
         self.pre(&mut request, &info).await?;
         let result = self.exec(request, &info).await;
         self.post(&result, &info).await?;
@@ -888,22 +3296,22 @@ impl Client {
             _ => Err(Error::UnexpectedResponse(response)),
         }
     }
-    /**Sends a `GET` request to `/streams/{stream_uid}`
+    /**Sends a `GET` request to `/api/v1/streams/{stream_uid}`
 
-*/
+    */
     pub async fn read_stream<'a>(
-        &'a self,
-        stream_uid: &'a str,
+        &'a self, stream_uid: &'a str,
     ) -> Result<ResponseValue<types::StreamSpec>, Error<()>> {
         let url = format!(
-            "{}/streams/{}", self.baseurl, encode_path(& stream_uid.to_string()),
+            "{}/api/v1/streams/{}",
+            self.baseurl,
+            encode_path(&stream_uid.to_string()),
         );
         let mut header_map = ::reqwest::header::HeaderMap::with_capacity(1usize);
-        header_map
-            .append(
-                ::reqwest::header::HeaderName::from_static("api-version"),
-                ::reqwest::header::HeaderValue::from_static(Self::api_version()),
-            );
+        header_map.append(
+            ::reqwest::header::HeaderName::from_static("api-version"),
+            ::reqwest::header::HeaderValue::from_static(Self::api_version()),
+        );
         #[allow(unused_mut)]
         let mut request = self
             .client
@@ -914,9 +3322,7 @@ impl Client {
             )
             .headers(header_map)
             .build()?;
-        let info = OperationInfo {
-            operation_id: "read_stream",
-        };
+        let info = OperationInfo { operation_id: "read_stream" };
         self.pre(&mut request, &info).await?;
         let result = self.exec(request, &info).await;
         self.post(&result, &info).await?;
@@ -926,22 +3332,34 @@ impl Client {
             _ => Err(Error::UnexpectedResponse(response)),
         }
     }
-    /**Sends a `PUT` request to `/streams/{stream_uid}`
+    /**Create stream or replace existing one.
+
+    If stream with given UID doesn't exist, it's created (upsert).
 
-*/
+    Sends a `PUT` request to `/api/v1/streams/{stream_uid}`
+
+    Arguments:
+    - `stream_uid`
+    - `if_match`: ETag of entity, as returned by GET; if entity was modified since
+    then, request fails with 412.
+    - `body`
+    */
     pub async fn update_stream<'a>(
-        &'a self,
-        stream_uid: &'a str,
+        &'a self, stream_uid: &'a str, if_match: Option<&'a str>, body: &'a types::StreamSpec,
     ) -> Result<ResponseValue<types::StreamSpec>, Error<()>> {
         let url = format!(
-            "{}/streams/{}", self.baseurl, encode_path(& stream_uid.to_string()),
+            "{}/api/v1/streams/{}",
+            self.baseurl,
+            encode_path(&stream_uid.to_string()),
         );
-        let mut header_map = ::reqwest::header::HeaderMap::with_capacity(1usize);
-        header_map
-            .append(
-                ::reqwest::header::HeaderName::from_static("api-version"),
-                ::reqwest::header::HeaderValue::from_static(Self::api_version()),
-            );
+        let mut header_map = ::reqwest::header::HeaderMap::with_capacity(2usize);
+        header_map.append(
+            ::reqwest::header::HeaderName::from_static("api-version"),
+            ::reqwest::header::HeaderValue::from_static(Self::api_version()),
+        );
+        if let Some(value) = if_match {
+            header_map.append("if-match", value.to_string().try_into()?);
+        }
         #[allow(unused_mut)]
         let mut request = self
             .client
@@ -950,11 +3368,319 @@ impl Client {
                 ::reqwest::header::ACCEPT,
                 ::reqwest::header::HeaderValue::from_static("application/json"),
             )
+            .json(&body)
             .headers(header_map)
             .build()?;
-        let info = OperationInfo {
-            operation_id: "update_stream",
-        };
+        let info = OperationInfo { operation_id: "update_stream" };
+        self.pre(&mut request, &info).await?;
+        let result = self.exec(request, &info).await;
+        self.post(&result, &info).await?;
+        let response = result?;
+        match response.status().as_u16() {
+            200u16 => ResponseValue::from_response(response).await,
+            _ => Err(Error::UnexpectedResponse(response)),
+        }
+    }
+    /**Sends a `DELETE` request to `/api/v1/streams/{stream_uid}`
+
+    Arguments:
+    - `stream_uid`
+    - `if_match`: ETag of entity, as returned by GET; if entity was modified since
+    then, request fails with 412.
+    */
+    pub async fn delete_stream<'a>(
+        &'a self, stream_uid: &'a str, if_match: Option<&'a str>,
+    ) -> Result<ResponseValue<()>, Error<()>> {
+        let url = format!(
+            "{}/api/v1/streams/{}",
+            self.baseurl,
+            encode_path(&stream_uid.to_string()),
+        );
+        let mut header_map = ::reqwest::header::HeaderMap::with_capacity(2usize);
+        header_map.append(
+            ::reqwest::header::HeaderName::from_static("api-version"),
+            ::reqwest::header::HeaderValue::from_static(Self::api_version()),
+        );
+        if let Some(value) = if_match {
+            header_map.append("if-match", value.to_string().try_into()?);
+        }
+        #[allow(unused_mut)]
+        let mut request = self.client.delete(url).headers(header_map).build()?;
+        let info = OperationInfo { operation_id: "delete_stream" };
+        self.pre(&mut request, &info).await?;
+        let result = self.exec(request, &info).await;
+        self.post(&result, &info).await?;
+        let response = result?;
+        match response.status().as_u16() {
+            204u16 => Ok(ResponseValue::empty(response)),
+            _ => Err(Error::UnexpectedResponse(response)),
+        }
+    }
+    /**Sends a `GET` request to `/api/v1/streams/{stream_uid}/levels`
+
+    */
+    pub async fn read_stream_levels<'a>(
+        &'a self, stream_uid: &'a str,
+    ) -> Result<ResponseValue<::std::vec::Vec<types::LevelsSpec>>, Error<()>> {
+        let url = format!(
+            "{}/api/v1/streams/{}/levels",
+            self.baseurl,
+            encode_path(&stream_uid.to_string()),
+        );
+        let mut header_map = ::reqwest::header::HeaderMap::with_capacity(1usize);
+        header_map.append(
+            ::reqwest::header::HeaderName::from_static("api-version"),
+            ::reqwest::header::HeaderValue::from_static(Self::api_version()),
+        );
+        #[allow(unused_mut)]
+        let mut request = self
+            .client
+            .get(url)
+            .header(
+                ::reqwest::header::ACCEPT,
+                ::reqwest::header::HeaderValue::from_static("application/json"),
+            )
+            .headers(header_map)
+            .build()?;
+        let info = OperationInfo { operation_id: "read_stream_levels" };
+        self.pre(&mut request, &info).await?;
+        let result = self.exec(request, &info).await;
+        self.post(&result, &info).await?;
+        let response = result?;
+        match response.status().as_u16() {
+            200u16 => ResponseValue::from_response(response).await,
+            _ => Err(Error::UnexpectedResponse(response)),
+        }
+    }
+    /**Sends a `POST` request to `/api/v1/streams/{stream_uid}/measure-latency`
+
+    Arguments:
+    - `stream_uid`
+    - `detector_stream_uid`: Stream which source should receive marker sent by this stream.
+    Defaults to the same stream, e.g. when its destination is a
+    speaker and its source is a microphone.
+    - `duration`: How long to listen for marker, in seconds (default 2, max 10).
+    */
+    pub async fn measure_stream_latency<'a>(
+        &'a self, stream_uid: &'a str, detector_stream_uid: Option<&'a str>,
+        duration: Option<f32>,
+    ) -> Result<ResponseValue<types::LatencyReportSpec>, Error<()>> {
+        let url = format!(
+            "{}/api/v1/streams/{}/measure-latency",
+            self.baseurl,
+            encode_path(&stream_uid.to_string()),
+        );
+        let mut header_map = ::reqwest::header::HeaderMap::with_capacity(1usize);
+        header_map.append(
+            ::reqwest::header::HeaderName::from_static("api-version"),
+            ::reqwest::header::HeaderValue::from_static(Self::api_version()),
+        );
+        #[allow(unused_mut)]
+        let mut request = self
+            .client
+            .post(url)
+            .header(
+                ::reqwest::header::ACCEPT,
+                ::reqwest::header::HeaderValue::from_static("application/json"),
+            )
+            .query(&progenitor_client::QueryParam::new(
+                "detector_stream_uid",
+                &detector_stream_uid,
+            ))
+            .query(&progenitor_client::QueryParam::new("duration", &duration))
+            .headers(header_map)
+            .build()?;
+        let info = OperationInfo { operation_id: "measure_stream_latency" };
+        self.pre(&mut request, &info).await?;
+        let result = self.exec(request, &info).await;
+        self.post(&result, &info).await?;
+        let response = result?;
+        match response.status().as_u16() {
+            200u16 => ResponseValue::from_response(response).await,
+            _ => Err(Error::UnexpectedResponse(response)),
+        }
+    }
+    /**Sends a `GET` request to `/api/v1/streams/{stream_uid}/recordings`
+
+    */
+    pub async fn list_recordings<'a>(
+        &'a self, stream_uid: &'a str,
+    ) -> Result<ResponseValue<::std::vec::Vec<types::RecordingInfo>>, Error<()>> {
+        let url = format!(
+            "{}/api/v1/streams/{}/recordings",
+            self.baseurl,
+            encode_path(&stream_uid.to_string()),
+        );
+        let mut header_map = ::reqwest::header::HeaderMap::with_capacity(1usize);
+        header_map.append(
+            ::reqwest::header::HeaderName::from_static("api-version"),
+            ::reqwest::header::HeaderValue::from_static(Self::api_version()),
+        );
+        #[allow(unused_mut)]
+        let mut request = self
+            .client
+            .get(url)
+            .header(
+                ::reqwest::header::ACCEPT,
+                ::reqwest::header::HeaderValue::from_static("application/json"),
+            )
+            .headers(header_map)
+            .build()?;
+        let info = OperationInfo { operation_id: "list_recordings" };
+        self.pre(&mut request, &info).await?;
+        let result = self.exec(request, &info).await;
+        self.post(&result, &info).await?;
+        let response = result?;
+        match response.status().as_u16() {
+            200u16 => ResponseValue::from_response(response).await,
+            _ => Err(Error::UnexpectedResponse(response)),
+        }
+    }
+    /**Sends a `GET` request to `/api/v1/streams/{stream_uid}/recordings/{recording_id}`
+
+    */
+    pub async fn download_recording<'a>(
+        &'a self, stream_uid: &'a str, recording_id: &'a str,
+    ) -> Result<ResponseValue<ByteStream>, Error<()>> {
+        let url = format!(
+            "{}/api/v1/streams/{}/recordings/{}",
+            self.baseurl,
+            encode_path(&stream_uid.to_string()),
+            encode_path(&recording_id.to_string()),
+        );
+        let mut header_map = ::reqwest::header::HeaderMap::with_capacity(1usize);
+        header_map.append(
+            ::reqwest::header::HeaderName::from_static("api-version"),
+            ::reqwest::header::HeaderValue::from_static(Self::api_version()),
+        );
+        #[allow(unused_mut)]
+        let mut request = self.client.get(url).headers(header_map).build()?;
+        let info = OperationInfo { operation_id: "download_recording" };
+        self.pre(&mut request, &info).await?;
+        let result = self.exec(request, &info).await;
+        self.post(&result, &info).await?;
+        let response = result?;
+        match response.status().as_u16() {
+            200u16 => Ok(ResponseValue::stream(response)),
+            _ => Err(Error::UnexpectedResponse(response)),
+        }
+    }
+    /**Sends a `DELETE` request to `/api/v1/streams/{stream_uid}/recordings/{recording_id}`
+
+    */
+    pub async fn delete_recording<'a>(
+        &'a self, stream_uid: &'a str, recording_id: &'a str,
+    ) -> Result<ResponseValue<()>, Error<()>> {
+        let url = format!(
+            "{}/api/v1/streams/{}/recordings/{}",
+            self.baseurl,
+            encode_path(&stream_uid.to_string()),
+            encode_path(&recording_id.to_string()),
+        );
+        let mut header_map = ::reqwest::header::HeaderMap::with_capacity(1usize);
+        header_map.append(
+            ::reqwest::header::HeaderName::from_static("api-version"),
+            ::reqwest::header::HeaderValue::from_static(Self::api_version()),
+        );
+        #[allow(unused_mut)]
+        let mut request = self.client.delete(url).headers(header_map).build()?;
+        let info = OperationInfo { operation_id: "delete_recording" };
+        self.pre(&mut request, &info).await?;
+        let result = self.exec(request, &info).await;
+        self.post(&result, &info).await?;
+        let response = result?;
+        match response.status().as_u16() {
+            204u16 => Ok(ResponseValue::empty(response)),
+            _ => Err(Error::UnexpectedResponse(response)),
+        }
+    }
+    /**Sends a `POST` request to `/api/v1/streams/{stream_uid}/renew`
+
+    */
+    pub async fn renew_stream<'a>(
+        &'a self, stream_uid: &'a str,
+    ) -> Result<ResponseValue<types::StreamSpec>, Error<()>> {
+        let url = format!(
+            "{}/api/v1/streams/{}/renew",
+            self.baseurl,
+            encode_path(&stream_uid.to_string()),
+        );
+        let mut header_map = ::reqwest::header::HeaderMap::with_capacity(1usize);
+        header_map.append(
+            ::reqwest::header::HeaderName::from_static("api-version"),
+            ::reqwest::header::HeaderValue::from_static(Self::api_version()),
+        );
+        #[allow(unused_mut)]
+        let mut request = self
+            .client
+            .post(url)
+            .header(
+                ::reqwest::header::ACCEPT,
+                ::reqwest::header::HeaderValue::from_static("application/json"),
+            )
+            .headers(header_map)
+            .build()?;
+        let info = OperationInfo { operation_id: "renew_stream" };
+        self.pre(&mut request, &info).await?;
+        let result = self.exec(request, &info).await;
+        self.post(&result, &info).await?;
+        let response = result?;
+        match response.status().as_u16() {
+            200u16 => ResponseValue::from_response(response).await,
+            _ => Err(Error::UnexpectedResponse(response)),
+        }
+    }
+    /**Sends a `GET` request to `/api/v1/streams/{stream_uid}/sdp`
+
+    */
+    pub async fn export_stream_sdp<'a>(
+        &'a self, stream_uid: &'a str,
+    ) -> Result<ResponseValue<ByteStream>, Error<()>> {
+        let url = format!(
+            "{}/api/v1/streams/{}/sdp",
+            self.baseurl,
+            encode_path(&stream_uid.to_string()),
+        );
+        let mut header_map = ::reqwest::header::HeaderMap::with_capacity(1usize);
+        header_map.append(
+            ::reqwest::header::HeaderName::from_static("api-version"),
+            ::reqwest::header::HeaderValue::from_static(Self::api_version()),
+        );
+        #[allow(unused_mut)]
+        let mut request = self.client.get(url).headers(header_map).build()?;
+        let info = OperationInfo { operation_id: "export_stream_sdp" };
+        self.pre(&mut request, &info).await?;
+        let result = self.exec(request, &info).await;
+        self.post(&result, &info).await?;
+        let response = result?;
+        match response.status().as_u16() {
+            200u16 => Ok(ResponseValue::stream(response)),
+            _ => Err(Error::UnexpectedResponse(response)),
+        }
+    }
+    /**Sends a `GET` request to `/api/v1/version`
+
+    */
+    pub async fn read_version<'a>(
+        &'a self,
+    ) -> Result<ResponseValue<types::VersionSpec>, Error<()>> {
+        let url = format!("{}/api/v1/version", self.baseurl,);
+        let mut header_map = ::reqwest::header::HeaderMap::with_capacity(1usize);
+        header_map.append(
+            ::reqwest::header::HeaderName::from_static("api-version"),
+            ::reqwest::header::HeaderValue::from_static(Self::api_version()),
+        );
+        #[allow(unused_mut)]
+        let mut request = self
+            .client
+            .get(url)
+            .header(
+                ::reqwest::header::ACCEPT,
+                ::reqwest::header::HeaderValue::from_static("application/json"),
+            )
+            .headers(header_map)
+            .build()?;
+        let info = OperationInfo { operation_id: "read_version" };
         self.pre(&mut request, &info).await?;
         let result = self.exec(request, &info).await;
         self.post(&result, &info).await?;
@@ -1,19 +1,24 @@
 // Copyright (c) Roc Streaming authors
 // Licensed under MPL-2.0
+use rocd::audio::*;
 use rocd::drivers::*;
-use rocd::dto::DriverId;
+use rocd::dto::{DriverId, Uri};
 
 use async_trait::async_trait;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 
-pub struct MockDriver {}
+pub struct MockDriver {
+    // tests clear it to emulate failed driver
+    pub is_up: AtomicBool,
+}
 
 #[async_trait]
 impl Driver for MockDriver {
     async fn open() -> DriverResult<Arc<dyn Driver>> {
         tracing::debug!("opening mock driver");
 
-        Ok(Arc::new(MockDriver {}))
+        Ok(Arc::new(MockDriver { is_up: AtomicBool::new(true) }))
     }
 
     async fn close(self: Arc<Self>) {
@@ -23,4 +28,54 @@ impl Driver for MockDriver {
     fn id(&self) -> DriverId {
         DriverId::Unspecified
     }
+
+    fn is_up(&self) -> bool {
+        self.is_up.load(Ordering::SeqCst)
+    }
+
+    async fn open_source(
+        &self, _endpoint_uri: &Uri, format: AudioFormat,
+    ) -> DriverResult<Box<dyn AudioSource>> {
+        Ok(Box::new(NullEndpoint { format }))
+    }
+
+    async fn open_sink(
+        &self, _endpoint_uri: &Uri, format: AudioFormat,
+    ) -> DriverResult<Box<dyn AudioSink>> {
+        Ok(Box::new(NullEndpoint { format }))
+    }
+}
+
+/// Endpoint producing silence and discarding played audio.
+struct NullEndpoint {
+    format: AudioFormat,
+}
+
+impl AudioSource for NullEndpoint {
+    fn format(&self) -> AudioFormat {
+        self.format
+    }
+
+    fn is_clocked(&self) -> bool {
+        false
+    }
+
+    fn read(&mut self, samples: &mut [f32]) -> Result<(), AudioError> {
+        samples.fill(0.0);
+        Ok(())
+    }
+}
+
+impl AudioSink for NullEndpoint {
+    fn format(&self) -> AudioFormat {
+        self.format
+    }
+
+    fn is_clocked(&self) -> bool {
+        false
+    }
+
+    fn write(&mut self, _samples: &[f32]) -> Result<(), AudioError> {
+        Ok(())
+    }
 }
@@ -1,36 +1,136 @@
 // Copyright (c) Roc Streaming authors
 // Licensed under MPL-2.0
+use crate::test_transport::MockTransport;
+use rocd::auth::TokenStore;
 use rocd::drivers::Driver;
+use rocd::events::EventBus;
 use rocd::io_endpoints::EndpointDispatcher;
-use rocd::io_streams::StreamDispatcher;
+use rocd::io_streams::{PortRange, StreamDispatcher, TransportRegistry};
 use rocd::p2p::PeerDispatcher;
-use rocd::rest_api::RestServer;
+use rocd::recordings::RecordingStore;
+use rocd::rest_api::{
+    ApiVersion, AuthConfig, ListenAddr, RestServer, ServerConfigBuilder, TlsConfig,
+};
+use rocd::vault::{Vault, VaultConfigBuilder};
 
 use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::sync::Arc;
+use std::time::Duration;
+use tempfile::TempDir;
 
 pub struct Server {
     server: Arc<RestServer>,
     address: SocketAddr,
     url: String,
+    api_url: String,
+    tokens: Arc<TokenStore>,
+    transport: Arc<MockTransport>,
+    // Dropped last, after server releases vault.
+    state_dir: TempDir,
 }
 
 impl Server {
     pub async fn start(driver: &Arc<dyn Driver>) -> Self {
-        let peer_dispatcher = Arc::new(PeerDispatcher::new());
-        let endpoint_dispatch = Arc::new(EndpointDispatcher::new(driver));
-        let stream_dispatch = Arc::new(StreamDispatcher::new(driver));
+        Server::launch(driver, |_, _, _| ()).await
+    }
+
+    /// Start server which requires bearer tokens.
+    /// Arguments tell whether docs and health check are public.
+    pub async fn start_with_auth(
+        driver: &Arc<dyn Driver>, public_docs: bool, public_health: bool,
+    ) -> Self {
+        Server::launch(driver, |_, tokens, config| {
+            config.auth(AuthConfig { tokens: Arc::clone(tokens), public_docs, public_health });
+        })
+        .await
+    }
+
+    /// Start HTTPS server with self-signed certificate in tls_dir(),
+    /// which is checked for changes every 100ms.
+    pub async fn start_with_tls(driver: &Arc<dyn Driver>) -> Self {
+        Server::launch(driver, |state_dir, _, config| {
+            let mut tls_config = TlsConfig::self_signed(&state_dir.join("tls"), &[]).unwrap();
+            tls_config.reload_interval = Duration::from_millis(100);
+            config.tls(tls_config);
+        })
+        .await
+    }
+
+    async fn launch(
+        driver: &Arc<dyn Driver>,
+        configure: impl FnOnce(&Path, &Arc<TokenStore>, &mut ServerConfigBuilder),
+    ) -> Self {
+        let state_dir = TempDir::with_prefix("rocd_test").unwrap();
+
+        let vault = Arc::new(
+            Vault::open(
+                &VaultConfigBuilder::default()
+                    .db_path(state_dir.path().join("rocd.db").to_str().unwrap())
+                    .build()
+                    .unwrap(),
+            )
+            .await
+            .unwrap(),
+        );
+
+        let recordings = Arc::new(RecordingStore::new(&state_dir.path().join("recordings")));
+
+        let transport = Arc::new(MockTransport::new());
+        let mut transports = TransportRegistry::empty();
+        transports.add(Arc::clone(&transport) as _);
+        let transports = Arc::new(transports);
+
+        let events = Arc::new(EventBus::new());
+
+        let peer_dispatcher = Arc::new(PeerDispatcher::new(&vault));
+        let endpoint_dispatch =
+            Arc::new(EndpointDispatcher::new(driver, &vault, &state_dir.path().join("media")));
+        let stream_dispatch = Arc::new(StreamDispatcher::new(
+            &endpoint_dispatch,
+            &vault,
+            &transports,
+            &recordings,
+            &events,
+            PortRange::default(),
+            Duration::from_millis(50),
+        ));
+
+        stream_dispatch.start();
+
+        let tokens = Arc::new(TokenStore::new(&vault));
+
+        let mut server_config = ServerConfigBuilder::default();
+        configure(state_dir.path(), &tokens, &mut server_config);
+        let server_config = server_config.build().unwrap();
 
-        let server =
-            Arc::new(RestServer::new(&peer_dispatcher, &endpoint_dispatch, &stream_dispatch));
+        let server = Arc::new(RestServer::new(
+            &peer_dispatcher,
+            &endpoint_dispatch,
+            &stream_dispatch,
+            &events,
+            &vault,
+            &server_config,
+        ));
 
-        let address =
-            server.start(SocketAddr::from_str("127.0.0.1:0").unwrap()).await.unwrap();
+        let addrs = server
+            .start(&[
+                ListenAddr::Tcp(SocketAddr::from_str("127.0.0.1:0").unwrap()),
+                ListenAddr::Unix(state_dir.path().join("rocd.sock")),
+            ])
+            .await
+            .unwrap();
 
-        let url = format!("http://{}", address);
+        let ListenAddr::Tcp(address) = addrs[0] else {
+            panic!("unexpected address: {}", addrs[0]);
+        };
 
-        Server { server, address, url }
+        let scheme = if server_config.tls.is_some() { "https" } else { "http" };
+        let url = format!("{scheme}://{address}");
+        let api_url = format!("{url}{}", ApiVersion::LATEST.prefix());
+
+        Server { server, address, url, api_url, tokens, transport, state_dir }
     }
 
     pub async fn shutdown(&self) {
@@ -45,4 +145,29 @@ impl Server {
     pub fn url(&self) -> &str {
         &self.url
     }
+
+    /// URL with prefix of latest API version, e.g. http://host:port/api/v1.
+    pub fn api_url(&self) -> &str {
+        &self.api_url
+    }
+
+    pub fn tokens(&self) -> &TokenStore {
+        &self.tokens
+    }
+
+    pub fn transport(&self) -> &MockTransport {
+        &self.transport
+    }
+
+    pub fn socket_path(&self) -> PathBuf {
+        self.state_dir.path().join("rocd.sock")
+    }
+
+    pub fn tls_dir(&self) -> PathBuf {
+        self.state_dir.path().join("tls")
+    }
+
+    pub fn media_dir(&self) -> PathBuf {
+        self.state_dir.path().join("media")
+    }
 }
@@ -0,0 +1,163 @@
+// Copyright (c) Roc Streaming authors
+// Licensed under MPL-2.0
+use rocd::audio::*;
+use rocd::dto::*;
+use rocd::io_streams::{Transport, TransportResult};
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Transport that loops sent audio back to receivers on same port.
+/// Receivers get silence when nothing was sent to their port.
+/// Supports all connections except LDPC, and like libroc doesn't
+/// support multicast_ttl with FEC, so that tests can check how
+/// unsupported connections are handled.
+pub struct MockTransport {
+    ports: Arc<Mutex<HashMap<u16, VecDeque<f32>>>>,
+    latencies: Arc<Mutex<HashMap<u16, Duration>>>,
+    opened: Mutex<Vec<ConnectionSpec>>,
+}
+
+impl MockTransport {
+    pub fn new() -> Self {
+        MockTransport {
+            ports: Arc::new(Mutex::new(HashMap::new())),
+            latencies: Arc::new(Mutex::new(HashMap::new())),
+            opened: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Get connections for which senders and receivers were opened.
+    pub fn opened_connections(&self) -> Vec<ConnectionSpec> {
+        self.opened.lock().unwrap().clone()
+    }
+
+    /// Add fixed latency to latency reported by receivers on port.
+    pub fn set_latency(&self, port: u16, latency: Duration) {
+        self.latencies.lock().unwrap().insert(port, latency);
+    }
+}
+
+impl Transport for MockTransport {
+    fn name(&self) -> &'static str {
+        "mock"
+    }
+
+    fn supports(&self, connection: &ConnectionSpec, _encoding: &EncodingSpec) -> bool {
+        match connection.fec_encoding() {
+            Some(FecEncoding::Disable) => true,
+            Some(FecEncoding::Rs8m) => {
+                !matches!(connection, ConnectionSpec::External { multicast_ttl: Some(_), .. })
+            },
+            _ => false,
+        }
+    }
+
+    fn open_receiver(
+        &self, connection: &ConnectionSpec, encoding: &EncodingSpec, _external_clock: bool,
+    ) -> TransportResult<Box<dyn AudioSource>> {
+        self.opened.lock().unwrap().push(connection.clone());
+
+        Ok(Box::new(LoopbackIo {
+            format: AudioFormat::from_encoding(encoding),
+            port: media_port(connection),
+            ports: Arc::clone(&self.ports),
+            latencies: Arc::clone(&self.latencies),
+        }))
+    }
+
+    fn open_sender(
+        &self, connection: &ConnectionSpec, encoding: &EncodingSpec, _external_clock: bool,
+    ) -> TransportResult<Box<dyn AudioSink>> {
+        self.opened.lock().unwrap().push(connection.clone());
+
+        Ok(Box::new(LoopbackIo {
+            format: AudioFormat::from_encoding(encoding),
+            port: media_port(connection),
+            ports: Arc::clone(&self.ports),
+            latencies: Arc::clone(&self.latencies),
+        }))
+    }
+}
+
+fn media_port(connection: &ConnectionSpec) -> u16 {
+    connection.external_uris().first().and_then(|uri| uri.port()).unwrap_or_default()
+}
+
+/// Source reading audio sent to port and sink sending audio to port.
+pub struct LoopbackIo {
+    format: AudioFormat,
+    port: u16,
+    ports: Arc<Mutex<HashMap<u16, VecDeque<f32>>>>,
+    latencies: Arc<Mutex<HashMap<u16, Duration>>>,
+}
+
+impl LoopbackIo {
+    /// Sent audio is dropped if it's not read during this time.
+    const MAX_BUFFERED: Duration = Duration::from_secs(1);
+}
+
+impl AudioSource for LoopbackIo {
+    fn format(&self) -> AudioFormat {
+        self.format
+    }
+
+    fn is_clocked(&self) -> bool {
+        false
+    }
+
+    fn read(&mut self, samples: &mut [f32]) -> Result<(), AudioError> {
+        let mut ports = self.ports.lock().unwrap();
+
+        // read only whole frames, so that sent signal isn't broken into pieces
+        let len = samples.len();
+        match ports.get_mut(&self.port).filter(|queue| queue.len() >= len) {
+            Some(queue) => {
+                for (sample, queued) in samples.iter_mut().zip(queue.drain(..len)) {
+                    *sample = queued;
+                }
+            },
+            None => samples.fill(0.0),
+        }
+
+        Ok(())
+    }
+
+    fn metrics(&self) -> Option<LinkMetrics> {
+        let queued = self.ports.lock().unwrap().get(&self.port).map_or(0, |queue| queue.len());
+        let latency =
+            self.latencies.lock().unwrap().get(&self.port).copied().unwrap_or_default();
+
+        Some(LinkMetrics {
+            e2e_latency: self.format.duration_of_samples(queued) + latency,
+            // receiver waits until whole frame is queued
+            target_latency: FRAME_DURATION,
+            ..Default::default()
+        })
+    }
+}
+
+impl AudioSink for LoopbackIo {
+    fn format(&self) -> AudioFormat {
+        self.format
+    }
+
+    fn is_clocked(&self) -> bool {
+        false
+    }
+
+    fn write(&mut self, samples: &[f32]) -> Result<(), AudioError> {
+        let max_len = self.format.samples_per_duration(Self::MAX_BUFFERED);
+
+        let mut ports = self.ports.lock().unwrap();
+        let queue = ports.entry(self.port).or_default();
+
+        queue.extend(samples);
+        if queue.len() > max_len {
+            queue.drain(..queue.len() - max_len);
+        }
+
+        Ok(())
+    }
+}
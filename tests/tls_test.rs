@@ -0,0 +1,151 @@
+// Copyright (c) Roc Streaming authors
+// Licensed under MPL-2.0
+mod test_client;
+mod test_driver;
+mod test_server;
+mod test_transport;
+
+use crate::test_client::Client;
+use crate::test_driver::MockDriver;
+use crate::test_server::Server;
+use rocd::drivers::Driver;
+use rocd::rest_api::{TlsConfig, cert_fingerprint};
+
+use reqwest::StatusCode;
+use reqwest::tls::TlsInfo;
+use std::fs;
+use std::sync::Arc;
+use std::time::Duration;
+use tempfile::TempDir;
+use test_context::{AsyncTestContext, test_context};
+use tracing_test::traced_test;
+
+struct TlsTestContext {
+    driver: Arc<dyn Driver>,
+    server: Server,
+}
+
+impl AsyncTestContext for TlsTestContext {
+    async fn setup() -> TlsTestContext {
+        let driver = MockDriver::open().await.unwrap();
+        let server = Server::start_with_tls(&driver).await;
+
+        TlsTestContext { driver, server }
+    }
+
+    async fn teardown(self) {
+        self.server.shutdown().await;
+        self.driver.close().await;
+    }
+}
+
+impl TlsTestContext {
+    fn server_tls_config(&self) -> TlsConfig {
+        TlsConfig::self_signed(&self.server.tls_dir(), &[]).unwrap()
+    }
+
+    /// Connect to server and get fingerprint of its certificate.
+    /// Certificate isn't verified, like clients that pin fingerprint.
+    async fn fetch_fingerprint(&self) -> String {
+        let client = reqwest::Client::builder()
+            .danger_accept_invalid_certs(true)
+            .tls_info(true)
+            .build()
+            .unwrap();
+
+        let resp =
+            client.get(format!("{}/peers/self", self.server.api_url())).send().await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let tls_info = resp.extensions().get::<TlsInfo>().unwrap();
+        cert_fingerprint(tls_info.peer_certificate().unwrap())
+    }
+}
+
+#[test_context(TlsTestContext)]
+#[tokio::test]
+#[traced_test]
+async fn test_https(ctx: &mut TlsTestContext) {
+    assert!(ctx.server.url().starts_with("https://"));
+
+    // client trusts self-signed certificate
+    let cert_pem = fs::read(&ctx.server_tls_config().cert_path).unwrap();
+    let client = Client::new_with_client(
+        ctx.server.url(),
+        reqwest::Client::builder()
+            .add_root_certificate(reqwest::Certificate::from_pem(&cert_pem).unwrap())
+            .build()
+            .unwrap(),
+    );
+
+    // GET /peers/self
+    let resp = client.read_peer("self").await.unwrap();
+
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    // client doesn't trust certificate
+    let client = Client::new(ctx.server.url());
+
+    assert!(client.read_peer("self").await.is_err());
+
+    // server certificate has fingerprint reported by config
+    assert_eq!(ctx.fetch_fingerprint().await, ctx.server_tls_config().fingerprint().unwrap());
+}
+
+#[test_context(TlsTestContext)]
+#[tokio::test]
+#[traced_test]
+async fn test_plain_http(ctx: &mut TlsTestContext) {
+    // TCP listener doesn't accept plain HTTP
+    let http_url = ctx.server.url().replace("https://", "http://");
+
+    let resp = reqwest::get(format!("{http_url}/peers/self")).await;
+
+    assert!(resp.is_err() || resp.unwrap().status() != StatusCode::OK);
+
+    // Unix socket doesn't use TLS
+    let client = Client::new_with_client(
+        "http://localhost",
+        reqwest::Client::builder().unix_socket(ctx.server.socket_path()).build().unwrap(),
+    );
+
+    // GET /peers/self
+    let resp = client.read_peer("self").await.unwrap();
+
+    assert_eq!(resp.status(), StatusCode::OK);
+}
+
+#[test_context(TlsTestContext)]
+#[tokio::test]
+#[traced_test]
+async fn test_reload(ctx: &mut TlsTestContext) {
+    let old_fingerprint = ctx.fetch_fingerprint().await;
+
+    // broken files are ignored
+    let server_config = ctx.server_tls_config();
+    fs::write(&server_config.cert_path, "broken").unwrap();
+    tokio::time::sleep(Duration::from_millis(300)).await;
+
+    assert_eq!(ctx.fetch_fingerprint().await, old_fingerprint);
+
+    // replace certificate
+    let other_dir = TempDir::new().unwrap();
+    let new_config = TlsConfig::self_signed(other_dir.path(), &[]).unwrap();
+    let new_fingerprint = new_config.fingerprint().unwrap();
+    assert_ne!(new_fingerprint, old_fingerprint);
+
+    fs::copy(&new_config.key_path, &server_config.key_path).unwrap();
+    fs::copy(&new_config.cert_path, &server_config.cert_path).unwrap();
+
+    // new connections get new certificate
+    let mut fingerprint = String::new();
+    for _ in 0..50 {
+        fingerprint = ctx.fetch_fingerprint().await;
+        if fingerprint == new_fingerprint {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+
+    assert_eq!(fingerprint, new_fingerprint);
+}
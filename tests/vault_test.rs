@@ -9,6 +9,7 @@ use std::collections::HashSet;
 use std::path::Path;
 use std::sync::Arc;
 use tempfile::TempDir;
+use time::macros::datetime;
 use tracing_test::traced_test;
 
 #[ctor]
@@ -47,7 +48,7 @@ fn make_uid<S: ToString>(name: S) -> Uid {
 }
 
 fn make_peer_spec(peer_uid: &Uid) -> Arc<PeerSpec> {
-    Arc::new(PeerSpec { peer_uri: Uri::from_peer(peer_uid), peer_uid: *peer_uid })
+    Arc::new(PeerSpec { peer_uri: Uri::from_peer(peer_uid), peer_uid: *peer_uid, revision: 1 })
 }
 
 fn make_endpoint_spec<S: ToString>(endpoint_uid: &Uid, endpoint_name: S) -> Arc<EndpointSpec> {
@@ -62,6 +63,8 @@ fn make_endpoint_spec<S: ToString>(endpoint_uid: &Uid, endpoint_name: S) -> Arc<
         driver: DriverId::Pipewire,
         display_name: "Test Name".into(),
         system_name: endpoint_name,
+        virtual_source: None,
+        revision: 2,
     })
 }
 
@@ -79,9 +82,68 @@ fn make_stream_spec(stream_uid: &Uid) -> Arc<StreamSpec> {
         destination: ConnectionSpec::External {
             connection_type: ConnectionType::External,
             media_uri: Uri::parse("rtp+rs8m://192.168.0.101:10000").unwrap(),
-            repair_uri: Uri::parse("rs8m://192.168.0.101:10001").unwrap(),
+            repair_uri: Some(Uri::parse("rs8m://192.168.0.101:10001").unwrap()),
             control_uri: Uri::parse("rtcp://192.168.0.101:10002").unwrap(),
+            multicast_interface: None,
+            multicast_ttl: None,
         },
+        encoding: EncodingSpec::default(),
+        owner: Some("test_owner".into()),
+        lease_ttl: Some(30),
+        lease_expiration: Some(datetime!(2026-03-10 10:00:30.5 UTC)),
+        schedule: Some(ScheduleSpec {
+            start_time: Some(datetime!(2026-03-10 10:00 UTC)),
+            stop_time: None,
+            duration: Some(600),
+            cron: None,
+        }),
+        state: StreamState::Stopped,
+        next_transition: Some(TransitionSpec {
+            state: StreamState::Running,
+            time: datetime!(2026-03-10 10:00 UTC),
+        }),
+        error: Some("can't open endpoint".into()),
+        recording: Some(RecordingSpec {
+            format: RecordingFormat::Flac,
+            rotation_interval: Some(3600),
+            max_files: None,
+            max_age: Some(86400),
+            metadata: [("title".into(), "Studio A".into())].into(),
+        }),
+        processing: Some(ProcessingSpec {
+            gain: Some(-6.0),
+            mute: false,
+            limiter_threshold: Some(-1.0),
+            loudness_target: None,
+        }),
+        silence_detection: Some(SilenceDetectionSpec {
+            threshold: Some(-50.0),
+            hold_time: None,
+        }),
+        failover: Some(FailoverSpec {
+            sources: vec![ConnectionSpec::External {
+                connection_type: ConnectionType::External,
+                media_uri: Uri::parse("rtp://0.0.0.0:10010").unwrap(),
+                repair_uri: None,
+                control_uri: Uri::parse("rtcp://0.0.0.0:10011").unwrap(),
+                multicast_interface: None,
+                multicast_ttl: None,
+            }],
+            silence_threshold: None,
+            silence_timeout: Some(1.0),
+            recovery_time: None,
+        }),
+        active_source: Some(1),
+        revision: 3,
+    })
+}
+
+fn make_group_spec(group_uid: &Uid, stream_uid: &Uid) -> Arc<GroupSpec> {
+    Arc::new(GroupSpec {
+        group_uri: Uri::from_group(group_uid),
+        group_uid: *group_uid,
+        members: vec![Uri::from_stream(stream_uid)],
+        target_latency: Some(0.2),
     })
 }
 
@@ -577,28 +639,36 @@ async fn test_dto_types() {
     let stream_uid = make_uid("stream_uid");
     let stream_spec = make_stream_spec(&stream_uid);
 
+    let group_uid = make_uid("group_uid");
+    let group_spec = make_group_spec(&group_uid, &stream_uid);
+
     // write
     assert_ok!(vault.write_peer(&peer_spec).await);
     assert_ok!(vault.write_endpoint(&endpoint_spec).await);
     assert_ok!(vault.write_stream(&stream_spec).await);
+    assert_ok!(vault.write_group(&group_spec).await);
 
     // read
     assert_eq!(*vault.read_peer(&peer_uid).await.unwrap(), *peer_spec);
     assert_eq!(*vault.read_endpoint(&endpoint_uid).await.unwrap(), *endpoint_spec);
     assert_eq!(*vault.read_stream(&stream_uid).await.unwrap(), *stream_spec);
+    assert_eq!(*vault.read_group(&group_uid).await.unwrap(), *group_spec);
 
     // list
     assert_eq!(*vault.list_peers().await.unwrap(), HashSet::from([peer_uid]));
     assert_eq!(*vault.list_endpoints().await.unwrap(), HashSet::from([endpoint_uid]));
     assert_eq!(*vault.list_streams().await.unwrap(), HashSet::from([stream_uid]));
+    assert_eq!(*vault.list_groups().await.unwrap(), HashSet::from([group_uid]));
 
     // remove
     assert_ok!(vault.remove_peer(&peer_uid).await);
     assert_ok!(vault.remove_endpoint(&endpoint_uid).await);
     assert_ok!(vault.remove_stream(&stream_uid).await);
+    assert_ok!(vault.remove_group(&group_uid).await);
 
     // list
     assert_eq!(*vault.list_peers().await.unwrap(), HashSet::new());
     assert_eq!(*vault.list_endpoints().await.unwrap(), HashSet::new());
     assert_eq!(*vault.list_streams().await.unwrap(), HashSet::new());
+    assert_eq!(*vault.list_groups().await.unwrap(), HashSet::new());
 }
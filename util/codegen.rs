@@ -1,6 +1,6 @@
 // Copyright (c) Roc Streaming authors
 // Licensed under MPL-2.0
-use rocd::rest_api::ApiController;
+use rocd::rest_api::{ApiController, ApiVersion};
 
 use clap::{ArgGroup, Parser, ValueEnum};
 use std::fs;
@@ -23,6 +23,10 @@ struct CliArgs {
     #[arg(long, default_value_t = false)]
     progenitor: bool,
 
+    /// API version to generate spec or client for.
+    #[arg(long, value_enum, value_name = "VERSION", default_value_t = ApiVersion::LATEST)]
+    api_version: ApiVersion,
+
     /// Output file name.
     #[arg(short, long)]
     output: String,
@@ -34,24 +38,28 @@ enum OpenapiFormat {
     Yaml,
 }
 
-fn generate_json_spec() -> String {
-    let openapi = ApiController::spec();
+fn generate_json_spec(version: ApiVersion) -> String {
+    let openapi = ApiController::spec(version);
 
     openapi.to_pretty_json().unwrap() + "\n"
 }
 
-fn generate_yaml_spec() -> String {
-    let openapi = ApiController::spec();
+fn generate_yaml_spec(version: ApiVersion) -> String {
+    let openapi = ApiController::spec(version);
 
     openapi.to_yaml().unwrap()
 }
 
-fn generate_rust_client() -> String {
-    let mut json_spec = generate_json_spec();
+fn generate_rust_client(version: ApiVersion) -> String {
+    let mut json_spec = generate_json_spec(version);
     // TODO: remove this hack when progenitor will support openapi 3.1.0.
     json_spec = json_spec.replace("3.1.0", "3.0.0");
 
-    let spec = serde_json::from_slice(json_spec.as_bytes()).unwrap();
+    let mut spec = serde_json::from_slice(json_spec.as_bytes()).unwrap();
+    // TODO: remove this hack when progenitor will support arbitrary request bodies.
+    remove_unsupported_operations(&mut spec);
+
+    let spec = serde_json::from_value(spec).unwrap();
 
     let mut generator = progenitor::Generator::new(
         progenitor::GenerationSettings::default().with_derive("PartialEq"),
@@ -63,6 +71,36 @@ fn generate_rust_client() -> String {
     prettyplease::unparse(&ast)
 }
 
+/// Remove operations with request body content types not supported by progenitor
+/// (e.g. application/sdp). Such operations should be tested using raw HTTP requests.
+fn remove_unsupported_operations(spec: &mut serde_json::Value) {
+    const SUPPORTED_CONTENT_TYPES: &[&str] = &[
+        "application/json",
+        "application/octet-stream",
+        "application/x-www-form-urlencoded",
+        "text/plain",
+    ];
+
+    let Some(paths) = spec["paths"].as_object_mut() else {
+        return;
+    };
+
+    for path_item in paths.values_mut() {
+        let Some(operations) = path_item.as_object_mut() else {
+            continue;
+        };
+
+        operations.retain(|_, operation| {
+            match operation["requestBody"]["content"].as_object() {
+                Some(content) => content.keys().all(|content_type| {
+                    SUPPORTED_CONTENT_TYPES.contains(&content_type.as_str())
+                }),
+                None => true,
+            }
+        });
+    }
+}
+
 fn write_file(path: &str, content: &str) {
     let p = Path::new(path);
     if p.exists() && fs::read_to_string(p).unwrap_or_default() == content {
@@ -79,18 +117,18 @@ fn main() {
 
     match args.openapi {
         Some(OpenapiFormat::Json) => {
-            write_file(&args.output, &generate_json_spec());
+            write_file(&args.output, &generate_json_spec(args.api_version));
             process::exit(0);
         },
         Some(OpenapiFormat::Yaml) => {
-            write_file(&args.output, &generate_yaml_spec());
+            write_file(&args.output, &generate_yaml_spec(args.api_version));
             process::exit(0);
         },
         None => (),
     };
 
     if args.progenitor {
-        write_file(&args.output, &generate_rust_client());
+        write_file(&args.output, &generate_rust_client(args.api_version));
         process::exit(0);
     }
 